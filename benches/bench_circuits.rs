@@ -0,0 +1,34 @@
+#![feature(test)]
+
+extern crate test;
+use ark_bls12_381::Fr as ScalarField;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use test::Bencher;
+use gkr::examples_circuits::{self, ExampleCircuit};
+
+fn bench_witness(b: &mut Bencher, example: ExampleCircuit<ScalarField>) {
+	let mut rng = StdRng::seed_from_u64(0);
+	let inputs = example.random_inputs(&mut rng);
+	b.iter(|| example.witness(&inputs));
+}
+
+#[bench]
+fn mul_tree_witness(b: &mut Bencher) {
+	bench_witness(b, examples_circuits::mul_tree(12));
+}
+
+#[bench]
+fn inner_product_witness(b: &mut Bencher) {
+	bench_witness(b, examples_circuits::inner_product(11));
+}
+
+#[bench]
+fn fibonacci_witness(b: &mut Bencher) {
+	bench_witness(b, examples_circuits::fibonacci(256));
+}
+
+#[bench]
+fn hash_chain_witness(b: &mut Bencher) {
+	bench_witness(b, examples_circuits::hash_chain(64));
+}
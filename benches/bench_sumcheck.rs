@@ -52,7 +52,7 @@ fn build_gi_lookup() -> Vec<sumcheck::UniPoly> {
 }
 
 // Steps being benchmarked
-fn verifier_steps_only(gi_lookup: &Vec<sumcheck::UniPoly>, r: Option<ScalarField>) {
+fn verifier_steps_only(gi_lookup: &[sumcheck::UniPoly], r: Option<ScalarField>) {
 	// initial round
 	let p = sumcheck::Prover::new(&G_1);
 	let mut gi = gi_lookup[0].clone();
@@ -0,0 +1,102 @@
+// src/accumulator.rs
+
+use ark_ff::PrimeField;
+use std::collections::BTreeMap;
+
+use crate::error::Error;
+use crate::eq::eq_table;
+use crate::ml_extension::{DenseMLE, IndexOrder};
+use crate::sumcheck::protocol::Subclaim;
+use crate::transcript::Transcript;
+
+/// 後回しにした評価の主張 oracle(point) = value
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeferredClaim<F: PrimeField, K> {
+    pub oracle: K,
+    pub point: Vec<F>,
+    pub value: F,
+}
+
+/// 複数のサブプロトコルが残す評価の主張を溜め，最後にまとめて検査する
+///
+/// 主張はすべて transcript に吸収し，`finalize` でランダムな重み ρ_i を導出して
+///   Σ_b W(b)·Σ_i ρ_i·eq(p_i, b) = Σ_i ρ_i·v_i
+/// を oracle ごとに 1 回ずつ確かめる。同じ oracle への主張が何個あっても表の走査は 1 回で済む。
+pub struct Accumulator<F: PrimeField, K: Ord + Clone = &'static str> {
+    transcript: Transcript,
+    claims: Vec<DeferredClaim<F, K>>,
+}
+
+impl<F: PrimeField, K: Ord + Clone> Accumulator<F, K> {
+    pub fn new(transcript: Transcript) -> Self {
+        Accumulator { transcript, claims: Vec::new() }
+    }
+
+    /// 主張を追加する
+    pub fn add(&mut self, oracle: K, point: Vec<F>, value: F) {
+        self.transcript.append_fields(b"deferred-point", &point);
+        self.transcript.append_field(b"deferred-value", &value);
+        self.claims.push(DeferredClaim { oracle, point, value });
+    }
+
+    /// sum-check のサブクレーム（多項式 `oracle` の点 `point` での値）を追加する
+    pub fn add_subclaim(&mut self, oracle: K, subclaim: &Subclaim<F>) {
+        self.add(oracle, subclaim.point.clone(), subclaim.expected_value);
+    }
+
+    /// 別の accumulator の主張をすべて取り込む
+    pub fn merge(&mut self, other: Accumulator<F, K>) {
+        for claim in other.claims {
+            self.add(claim.oracle, claim.point, claim.value);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.claims.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.claims.is_empty()
+    }
+
+    pub fn claims(&self) -> &[DeferredClaim<F, K>] {
+        &self.claims
+    }
+
+    /// 溜めた主張をまとめて検査する。`oracle` は主張に現れる oracle の評価表を返す
+    pub fn finalize<'a>(mut self, oracle: impl Fn(&K) -> Option<&'a DenseMLE<F>>) -> Result<(), Error>
+    where
+        F: 'a,
+    {
+        let mut groups: BTreeMap<K, Vec<DeferredClaim<F, K>>> = BTreeMap::new();
+        for claim in std::mem::take(&mut self.claims) {
+            groups.entry(claim.oracle.clone()).or_default().push(claim);
+        }
+        for (key, claims) in groups {
+            let table = oracle(&key).ok_or(Error::MalformedProof("unknown oracle in deferred claims"))?;
+            // eq_table は先頭の変数を最上位ビットとする順序なので，表もその順序で読む
+            let table = table.to_order(IndexOrder::BigEndian);
+            let mut weights = vec![F::zero(); table.evaluations.len()];
+            let mut expected = F::zero();
+            for claim in claims.iter() {
+                if claim.point.len() != table.num_vars {
+                    return Err(Error::LengthMismatch {
+                        what: "deferred claim variables",
+                        expected: table.num_vars,
+                        found: claim.point.len(),
+                    });
+                }
+                let rho: F = self.transcript.challenge_field(b"deferred-rho");
+                for (w, e) in weights.iter_mut().zip(eq_table(&claim.point)) {
+                    *w += rho * e;
+                }
+                expected += rho * claim.value;
+            }
+            let actual: F = weights.iter().zip(table.evaluations.iter()).map(|(w, t)| *w * t).sum();
+            if actual != expected {
+                return Err(Error::EvaluationMismatch("batched deferred claim"));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,339 @@
+// src/basefold.rs
+//
+// ハッシュだけに基づく多重線形 PCS（BaseFold）。信頼できるセットアップも，FFT に向いた体も要らない。
+//
+// 評価表 m（2^k 個）を折り畳み可能な符号でエンコードする：
+// Enc_0(a) = (a, ..., a)（長さ 2^R），Enc_i(m_l || m_r) = (Enc_{i-1}(m_l) + t_i∘Enc_{i-1}(m_r)) || (Enc_{i-1}(m_l) - t_i∘Enc_{i-1}(m_r))。
+// t_i は公開の乱数から作る対角の係数。符号語 C の組 (C[j], C[j + L]) から Enc_{i-1}(m_l)[j], Enc_{i-1}(m_r)[j] が戻るので，
+// チャレンジ r で (1 - r)·Enc(m_l) + r·Enc(m_r) = Enc_{i-1}(m(r, ·)) に畳み込める（FRI の折り畳み）。
+//
+// 開示 m(z) = v は Σ_b m(b)·eq(z, b) = v の sum-check と符号語の折り畳みを同じチャレンジで進め，
+// 最後に残る定数 m(r) で sum-check を閉じる。折り畳みの整合性は Merkle 木の開示によるランダムな検査で確かめる。
+
+use ark_ff::{batch_inversion, PrimeField};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use sha3::{Digest, Sha3_256};
+use std::marker::PhantomData;
+
+use crate::eq::{eq_eval, eq_table};
+use crate::error::Error;
+use crate::ml_extension::{DenseMLE, IndexOrder};
+use crate::pcs::MultilinearPCS;
+use crate::serialization::{write_field, Endianness};
+use crate::statement::Digest32;
+use crate::sumcheck::protocol;
+use crate::transcript::Transcript;
+
+/// 開示の transcript を初期化するラベル
+pub const BASEFOLD_LABEL: &[u8] = b"gkr-basefold";
+/// 既定の符号化率の逆数の log2（符号語は評価表の 2^R 倍の長さ）
+pub const DEFAULT_LOG_BLOWUP: usize = 2;
+/// 既定の検査の回数
+pub const DEFAULT_NUM_QUERIES: usize = 40;
+
+/// BaseFold の公開パラメータ（コミットと検証で共通）
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BaseFoldParams<F: PrimeField> {
+    pub num_vars: usize,
+    /// R
+    pub log_blowup: usize,
+    pub num_queries: usize,
+    /// `twiddles[i - 1]` = t_i（長さ 2^{R + i - 1}，全て非零）
+    pub twiddles: Vec<Vec<F>>,
+}
+
+/// 1 つの検査で 1 段の符号語から開く組 (C[j], C[j + L]) とその Merkle パス
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BaseFoldQuery<F: PrimeField> {
+    pub left: F,
+    pub right: F,
+    /// 葉から根へ向かう兄弟の列
+    pub path: Vec<Digest32>,
+}
+
+/// BaseFold の開示の証明
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BaseFoldProof<F: PrimeField> {
+    /// Σ_b m(b)·eq(z, b) の sum-check のラウンドメッセージ（1, 2 での値）
+    pub round_msgs: Vec<Vec<F>>,
+    /// 畳み込んだ符号語 C_1, ..., C_k の Merkle 根
+    pub roots: Vec<Digest32>,
+    /// 全ての変数を畳み込んだ値 m(r)（C_k の全ての成分がこれに等しい）
+    pub final_value: F,
+    /// `queries[q][i]` は q 番目の検査の C_i での開示
+    pub queries: Vec<Vec<BaseFoldQuery<F>>>,
+}
+
+/// 任意の素体の上の BaseFold
+///
+/// `num_vars` 変数で作ったパラメータで，それ以下の変数数の多項式をコミットできる。
+pub struct BaseFold<F: PrimeField>(PhantomData<F>);
+
+impl<F: PrimeField> BaseFold<F> {
+    /// 既定の符号化率と検査の回数での透明なセットアップ
+    pub fn setup(num_vars: usize, label: &[u8]) -> BaseFoldParams<F> {
+        Self::setup_with(num_vars, DEFAULT_LOG_BLOWUP, DEFAULT_NUM_QUERIES, label)
+    }
+
+    /// 透明なセットアップ。t_i は `label` のハッシュを種にした乱数から作る
+    pub fn setup_with(num_vars: usize, log_blowup: usize, num_queries: usize, label: &[u8]) -> BaseFoldParams<F> {
+        assert!(log_blowup > 0, "the code needs a blowup of at least 2");
+        let mut rng = StdRng::from_seed(Sha3_256::digest(label).into());
+        let twiddles = (1..=num_vars)
+            .map(|i| {
+                (0..1usize << (log_blowup + i - 1))
+                    .map(|_| loop {
+                        let t = F::rand(&mut rng);
+                        if !t.is_zero() {
+                            break t;
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        BaseFoldParams { num_vars, log_blowup, num_queries, twiddles }
+    }
+
+    /// 評価表（先頭の変数が最上位ビット）を長さ 2^{k + R} の符号語にエンコードする
+    pub fn encode(params: &BaseFoldParams<F>, table: &[F]) -> Vec<F> {
+        let k = table.len().trailing_zeros() as usize;
+        assert!(k <= params.num_vars, "polynomial has more variables than the setup");
+        let mut blocks: Vec<Vec<F>> = table.iter().map(|a| vec![*a; 1 << params.log_blowup]).collect();
+        for twiddles in params.twiddles[..k].iter() {
+            blocks = blocks
+                .chunks(2)
+                .map(|pair| {
+                    let (l, r) = (&pair[0], &pair[1]);
+                    let sum = l.iter().zip(r).zip(twiddles).map(|((a, b), t)| *a + *t * b);
+                    let diff = l.iter().zip(r).zip(twiddles).map(|((a, b), t)| *a - *t * b);
+                    sum.chain(diff).collect()
+                })
+                .collect();
+        }
+        blocks.pop().unwrap()
+    }
+
+    /// 符号語 C_i（k - i 変数分）をチャレンジ r で畳み込む
+    fn fold(codeword: &[F], twiddles: &[F], r: F) -> Vec<F> {
+        let half = codeword.len() / 2;
+        let mut inverses = twiddles.to_vec();
+        batch_inversion(&mut inverses);
+        (0..half).map(|j| fold_pair(codeword[j], codeword[j + half], inverses[j], r)).collect()
+    }
+}
+
+/// 組 (a, b) = (x + t·y, x - t·y) から (1 - r)·x + r·y を求める（`t_inv` = t^{-1}）
+fn fold_pair<F: PrimeField>(a: F, b: F, t_inv: F, r: F) -> F {
+    let two_inv = F::from(2u64).inverse().unwrap();
+    let x = (a + b) * two_inv;
+    let y = (a - b) * two_inv * t_inv;
+    x + r * (y - x)
+}
+
+/// 組 (C[j], C[j + L]) を葉とする Merkle 木（`layers[0]` が葉，最後が根）
+struct MerkleTree {
+    layers: Vec<Vec<Digest32>>,
+}
+
+impl MerkleTree {
+    fn new<F: PrimeField>(codeword: &[F]) -> Self {
+        let half = codeword.len() / 2;
+        let mut layers = vec![(0..half).map(|j| leaf_hash(codeword[j], codeword[j + half])).collect::<Vec<_>>()];
+        while layers.last().unwrap().len() > 1 {
+            let next = layers.last().unwrap().chunks(2).map(|pair| node_hash(&pair[0], &pair[1])).collect();
+            layers.push(next);
+        }
+        MerkleTree { layers }
+    }
+
+    fn root(&self) -> Digest32 {
+        self.layers.last().unwrap()[0]
+    }
+
+    fn path(&self, mut index: usize) -> Vec<Digest32> {
+        let mut path = Vec::with_capacity(self.layers.len() - 1);
+        for layer in self.layers[..self.layers.len() - 1].iter() {
+            path.push(layer[index ^ 1]);
+            index >>= 1;
+        }
+        path
+    }
+}
+
+fn leaf_hash<F: PrimeField>(left: F, right: F) -> Digest32 {
+    let mut bytes = Vec::new();
+    write_field(&mut bytes, &left, Endianness::Little);
+    write_field(&mut bytes, &right, Endianness::Little);
+    let mut hasher = Sha3_256::new();
+    hasher.update(b"leaf");
+    hasher.update(&bytes);
+    hasher.finalize().into()
+}
+
+fn node_hash(left: &Digest32, right: &Digest32) -> Digest32 {
+    let mut hasher = Sha3_256::new();
+    hasher.update(b"node");
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// `index` 番目の葉 (left, right) から `path` を辿った根が `root` に一致するか
+fn verify_path<F: PrimeField>(root: &Digest32, mut index: usize, left: F, right: F, path: &[Digest32]) -> bool {
+    let mut hash = leaf_hash(left, right);
+    for sibling in path.iter() {
+        hash = if index & 1 == 0 { node_hash(&hash, sibling) } else { node_hash(sibling, &hash) };
+        index >>= 1;
+    }
+    hash == *root
+}
+
+/// 開示の transcript（コミットメント，点，値を吸収したもの）
+fn opening_transcript<F: PrimeField>(root: &Digest32, point: &[F], value: F) -> Transcript {
+    let mut transcript = Transcript::new(BASEFOLD_LABEL);
+    transcript.append_message(b"root", root);
+    transcript.append_fields(b"point", point);
+    transcript.append_field(b"value", &value);
+    transcript
+}
+
+/// 検査する位置（C_0 の葉の添字，`num_leaves` は 2 のべき）
+fn query_index(transcript: &mut Transcript, num_leaves: usize) -> usize {
+    let bytes = transcript.challenge_bytes(b"query");
+    (u64::from_le_bytes(bytes[..8].try_into().unwrap()) as usize) & (num_leaves - 1)
+}
+
+impl<F: PrimeField> MultilinearPCS<F> for BaseFold<F> {
+    type ProverParam = BaseFoldParams<F>;
+    type VerifierParam = BaseFoldParams<F>;
+    /// 符号語 C_0 の Merkle 根
+    type Commitment = Digest32;
+    type Proof = BaseFoldProof<F>;
+
+    fn commit(pp: &BaseFoldParams<F>, mle: &DenseMLE<F>) -> Digest32 {
+        let table = mle.to_order(IndexOrder::BigEndian).evaluations;
+        MerkleTree::new(&Self::encode(pp, &table)).root()
+    }
+
+    fn open(pp: &BaseFoldParams<F>, mle: &DenseMLE<F>, point: &[F]) -> (F, BaseFoldProof<F>) {
+        let k = mle.num_vars;
+        assert_eq!(point.len(), k);
+        let table = mle.to_order(IndexOrder::BigEndian);
+        let mut codewords = vec![Self::encode(pp, &table.evaluations)];
+        let mut trees = vec![MerkleTree::new(&codewords[0])];
+        let mut state = protocol::prover_init(vec![table, DenseMLE::from_evaluations_vec(k, eq_table(point))]);
+        let value = state.current_sum;
+        let mut transcript = opening_transcript(&trees[0].root(), point, value);
+
+        let mut round_msgs = Vec::with_capacity(k);
+        let mut roots = Vec::with_capacity(k);
+        for i in 0..k {
+            let msg = protocol::prove_round(&mut state);
+            transcript.append_fields(b"round_msg", &msg);
+            round_msgs.push(msg);
+            let r: F = transcript.challenge_field(b"challenge");
+            protocol::apply_challenge(&mut state, r);
+            let folded = Self::fold(&codewords[i], &pp.twiddles[k - i - 1], r);
+            let tree = MerkleTree::new(&folded);
+            transcript.append_message(b"fold_root", &tree.root());
+            roots.push(tree.root());
+            codewords.push(folded);
+            trees.push(tree);
+        }
+        let final_value = state.tables[0].evaluations[0];
+        transcript.append_field(b"final_value", &final_value);
+
+        let num_leaves = codewords[0].len() / 2;
+        let queries = (0..pp.num_queries)
+            .map(|_| {
+                let index = query_index(&mut transcript, num_leaves);
+                codewords
+                    .iter()
+                    .zip(trees.iter())
+                    .map(|(codeword, tree)| {
+                        let half = codeword.len() / 2;
+                        let j = index & (half - 1);
+                        BaseFoldQuery { left: codeword[j], right: codeword[j + half], path: tree.path(j) }
+                    })
+                    .collect()
+            })
+            .collect();
+        (value, BaseFoldProof { round_msgs, roots, final_value, queries })
+    }
+
+    fn verify(
+        vp: &BaseFoldParams<F>,
+        commitment: &Digest32,
+        point: &[F],
+        value: F,
+        proof: &BaseFoldProof<F>,
+    ) -> Result<(), Error> {
+        let k = point.len();
+        if k > vp.num_vars {
+            return Err(Error::LengthMismatch { what: "opening point coordinates", expected: vp.num_vars, found: k });
+        }
+        if proof.round_msgs.len() != k {
+            return Err(Error::LengthMismatch { what: "round messages", expected: k, found: proof.round_msgs.len() });
+        }
+        if proof.roots.len() != k {
+            return Err(Error::LengthMismatch { what: "folded roots", expected: k, found: proof.roots.len() });
+        }
+        if proof.queries.len() != vp.num_queries {
+            return Err(Error::LengthMismatch { what: "queries", expected: vp.num_queries, found: proof.queries.len() });
+        }
+        let mut transcript = opening_transcript(commitment, point, value);
+        let mut state = protocol::verifier_init(k, 2, value);
+        for (msg, root) in proof.round_msgs.iter().zip(proof.roots.iter()) {
+            protocol::verify_round(&mut state, msg)?;
+            transcript.append_fields(b"round_msg", msg);
+            let r: F = transcript.challenge_field(b"challenge");
+            protocol::apply_challenge_verifier(&mut state, r);
+            transcript.append_message(b"fold_root", root);
+        }
+        let subclaim = protocol::finalize(state)?;
+        if proof.final_value * eq_eval(point, &subclaim.point) != subclaim.expected_value {
+            return Err(Error::EvaluationMismatch("sum-check against the final folded value"));
+        }
+        transcript.append_field(b"final_value", &proof.final_value);
+
+        let roots: Vec<&Digest32> = std::iter::once(commitment).chain(proof.roots.iter()).collect();
+        let num_leaves = 1 << (k + vp.log_blowup - 1);
+        for query in proof.queries.iter() {
+            if query.len() != k + 1 {
+                return Err(Error::LengthMismatch { what: "query openings", expected: k + 1, found: query.len() });
+            }
+            let index = query_index(&mut transcript, num_leaves);
+            // 直前の段を畳み込んで得た，この段の C_i[index mod 2^{k - i + R}] の値
+            let mut expected: Option<F> = None;
+            for (i, (opening, root)) in query.iter().zip(roots.iter()).enumerate() {
+                let half = num_leaves >> i;
+                let j = index & (half - 1);
+                if opening.path.len() != (k - i + vp.log_blowup - 1)
+                    || !verify_path(root, j, opening.left, opening.right, &opening.path)
+                {
+                    return Err(Error::EvaluationMismatch("Merkle path of a query"));
+                }
+                if let Some(e) = expected {
+                    let opened = if index & ((half << 1) - 1) < half { opening.left } else { opening.right };
+                    if opened != e {
+                        return Err(Error::EvaluationMismatch("folding of a query"));
+                    }
+                }
+                if i == k {
+                    if opening.left != proof.final_value || opening.right != proof.final_value {
+                        return Err(Error::EvaluationMismatch("final folded codeword"));
+                    }
+                } else {
+                    let t_inv = vp.twiddles[k - i - 1][j].inverse().ok_or(Error::MalformedProof("zero twiddle"))?;
+                    expected = Some(fold_pair(opening.left, opening.right, t_inv, subclaim.point[i]));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn commitment_bytes(commitment: &Digest32) -> Vec<u8> {
+        commitment.to_vec()
+    }
+}
@@ -0,0 +1,14 @@
+// src/bin/gkr.rs
+//
+// 回路と入力のファイルから証明を作る・検証するコマンド（`cli` feature）。中身は `gkr::cli`。
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match gkr::cli::run(&args) {
+        Ok(output) => println!("{}", output),
+        Err(e) => {
+            eprintln!("gkr: {}", e);
+            std::process::exit(e.exit_code());
+        }
+    }
+}
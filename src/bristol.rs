@@ -0,0 +1,231 @@
+// src/bristol.rs
+//
+// Bristol Fashion 形式のブール回路（AES, SHA-256, 加算器など）を層状の回路に変換する。
+//
+//   <ゲート数> <配線数>
+//   <入力の数> <各入力のビット数...>
+//   <出力の数> <各出力のビット数...>
+//   <入力数> <出力数> <入力の配線...> <出力の配線...> <XOR | AND | INV | EQ | EQW | MAND>
+//
+// 入力は先頭の配線，出力は末尾の配線に並ぶ。ゲートは位相順に書かれている前提で，
+// 定義前の配線を読むものや同じ配線を 2 度定義するものは拒否する。
+// 層への割り当てと中継は `CircuitBuilder` に任せる。
+
+use ark_ff::Field;
+use std::fmt;
+
+use crate::builder::{BuiltCircuit, CircuitBuilder, Wire};
+use crate::error::Error;
+
+/// ブール演算を体の演算に写す方法
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum BristolMode {
+    /// 値を 0/1 に限った任意の体の演算（XOR = a + b − 2ab，INV = 1 − a）
+    #[default]
+    Arithmetic,
+    /// 標数 2 の体の演算（XOR = a + b，INV = a + 1）。それ以外の体では使えない
+    Binary,
+}
+
+/// Bristol 回路を読めなかった理由（`line` は 1 始まりの行番号）
+#[derive(Debug)]
+pub enum BristolError {
+    Syntax { line: usize, reason: &'static str },
+    UnknownGate { line: usize, name: String },
+    /// 範囲外，未定義，または定義済みの配線
+    Wire { line: usize, wire: usize, reason: &'static str },
+    /// `BristolMode::Binary` を標数 2 でない体で使った
+    UnsupportedField,
+    Circuit(Error),
+}
+
+impl fmt::Display for BristolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BristolError::Syntax { line, reason } => write!(f, "line {}: {}", line, reason),
+            BristolError::UnknownGate { line, name } => write!(f, "line {}: unknown gate {:?}", line, name),
+            BristolError::Wire { line, wire, reason } => write!(f, "line {}: wire {} {}", line, wire, reason),
+            BristolError::UnsupportedField => write!(f, "binary mode needs a field of characteristic 2"),
+            BristolError::Circuit(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for BristolError {}
+
+impl From<Error> for BristolError {
+    fn from(e: Error) -> Self {
+        BristolError::Circuit(e)
+    }
+}
+
+/// 変換した回路と，Bristol の入出力の区切り
+pub struct BristolCircuit<F: Field> {
+    pub built: BuiltCircuit<F>,
+    pub mode: BristolMode,
+    /// 各入力のビット数（回路の入力はこの順に並ぶ）
+    pub input_sizes: Vec<usize>,
+    /// 各出力のビット数（出力層はこの順に並ぶ）
+    pub output_sizes: Vec<usize>,
+}
+
+impl<F: Field> BristolCircuit<F> {
+    /// ビット列から回路の入力（定数を含む）を作る
+    pub fn assign_bits(&self, bits: &[bool]) -> Vec<F> {
+        let inputs: Vec<F> = bits.iter().map(|&b| if b { F::one() } else { F::zero() }).collect();
+        self.built.assign(&inputs)
+    }
+
+    /// ビット列に対する出力
+    pub fn evaluate_bits(&self, bits: &[bool]) -> Vec<bool> {
+        let inputs: Vec<F> = bits.iter().map(|&b| if b { F::one() } else { F::zero() }).collect();
+        self.built.evaluate(&inputs).iter().map(|x| !x.is_zero()).collect()
+    }
+}
+
+/// 入力数 2 のゲートだけの回路に変換する
+pub fn parse_bristol<F: Field>(input: &str, mode: BristolMode) -> Result<BristolCircuit<F>, BristolError> {
+    parse_bristol_with(input, mode, CircuitBuilder::new())
+}
+
+/// 空の `builder` に回路を組み立てる（`CircuitBuilder::with_max_fan_in` で XOR の層を減らせる）
+pub fn parse_bristol_with<F: Field>(
+    input: &str,
+    mode: BristolMode,
+    mut builder: CircuitBuilder<F>,
+) -> Result<BristolCircuit<F>, BristolError> {
+    if mode == BristolMode::Binary && F::characteristic() != [2] {
+        return Err(BristolError::UnsupportedField);
+    }
+    let mut lines = input
+        .lines()
+        .enumerate()
+        .map(|(i, text)| (i + 1, text.split_whitespace().collect::<Vec<_>>()))
+        .filter(|(_, tokens)| !tokens.is_empty());
+    let mut header = |reason: &'static str| -> Result<(usize, Vec<usize>), BristolError> {
+        let (line, tokens) = lines.next().ok_or(BristolError::Syntax { line: 0, reason })?;
+        let numbers = tokens.iter().map(|t| parse_number(t, line)).collect::<Result<_, _>>()?;
+        Ok((line, numbers))
+    };
+    let (line, counts) = header("missing gate and wire counts")?;
+    let [num_gates, num_wires] = counts[..] else {
+        return Err(BristolError::Syntax { line, reason: "expected gate and wire counts" });
+    };
+    let input_sizes = sizes(header("missing input sizes")?)?;
+    let output_sizes = sizes(header("missing output sizes")?)?;
+    let num_input_bits: usize = input_sizes.iter().sum();
+    let num_output_bits: usize = output_sizes.iter().sum();
+    if num_input_bits + num_output_bits > num_wires {
+        return Err(BristolError::Syntax { line, reason: "more input and output bits than wires" });
+    }
+
+    let mut wires: Vec<Option<Wire>> = vec![None; num_wires];
+    for w in wires.iter_mut().take(num_input_bits) {
+        *w = Some(builder.input());
+    }
+    let mut constants = Constants::default();
+    let mut gates = 0;
+    for (line, tokens) in lines {
+        let (name, numbers) = tokens.split_last().expect("empty lines are skipped");
+        let numbers = numbers.iter().map(|t| parse_number(t, line)).collect::<Result<Vec<_>, _>>()?;
+        let (arity, rest) = match numbers.as_slice() {
+            [n_in, n_out, rest @ ..] if rest.len() == n_in + n_out => ((*n_in, *n_out), rest),
+            _ => return Err(BristolError::Syntax { line, reason: "wire count does not match the gate arity" }),
+        };
+        let read = |w: usize, wires: &[Option<Wire>]| -> Result<Wire, BristolError> {
+            match wires.get(w) {
+                None => Err(BristolError::Wire { line, wire: w, reason: "is out of range" }),
+                Some(None) => Err(BristolError::Wire { line, wire: w, reason: "is read before it is defined" }),
+                Some(Some(wire)) => Ok(*wire),
+            }
+        };
+        let outputs: Vec<Wire> = match (*name, arity) {
+            ("XOR", (2, 1)) => {
+                let (a, b) = (read(rest[0], &wires)?, read(rest[1], &wires)?);
+                vec![xor(&mut builder, &mut constants, mode, a, b)]
+            }
+            ("AND", (2, 1)) => vec![builder.mul(read(rest[0], &wires)?, read(rest[1], &wires)?)],
+            ("INV", (1, 1)) => {
+                let a = read(rest[0], &wires)?;
+                vec![inv(&mut builder, &mut constants, mode, a)]
+            }
+            ("EQW", (1, 1)) => vec![read(rest[0], &wires)?],
+            // EQ の入力は配線でなく定数 0/1
+            ("EQ", (1, 1)) => match rest[0] {
+                0 | 1 => vec![builder.small_constant(rest[0] as u64)],
+                _ => return Err(BristolError::Syntax { line, reason: "EQ assigns a constant other than 0 or 1" }),
+            },
+            ("MAND", (n_in, n_out)) if n_in == 2 * n_out => (0..n_out)
+                .map(|k| Ok(builder.mul(read(rest[k], &wires)?, read(rest[n_out + k], &wires)?)))
+                .collect::<Result<_, BristolError>>()?,
+            ("XOR" | "AND" | "INV" | "EQW" | "EQ" | "MAND", _) => {
+                return Err(BristolError::Syntax { line, reason: "wrong number of wires for the gate" });
+            }
+            _ => return Err(BristolError::UnknownGate { line, name: name.to_string() }),
+        };
+        for (&w, wire) in rest[arity.0..].iter().zip(outputs) {
+            match wires.get_mut(w) {
+                None => return Err(BristolError::Wire { line, wire: w, reason: "is out of range" }),
+                Some(Some(_)) => return Err(BristolError::Wire { line, wire: w, reason: "is defined twice" }),
+                Some(slot) => *slot = Some(wire),
+            }
+        }
+        gates += 1;
+    }
+    if gates != num_gates {
+        return Err(BristolError::Syntax { line: 1, reason: "gate count does not match the header" });
+    }
+    let first_output = num_wires - num_output_bits;
+    for (w, wire) in wires.iter().enumerate().skip(first_output) {
+        let wire = wire.ok_or(BristolError::Wire { line: 0, wire: w, reason: "is an output but never defined" })?;
+        builder.output(wire);
+    }
+    Ok(BristolCircuit { built: builder.build()?, mode, input_sizes, output_sizes })
+}
+
+/// 回路の入力として置く定数（使うものだけを 1 度ずつ作る）
+#[derive(Default)]
+struct Constants {
+    one: Option<Wire>,
+    minus_one: Option<Wire>,
+    minus_two: Option<Wire>,
+}
+
+fn constant<F: Field>(builder: &mut CircuitBuilder<F>, slot: &mut Option<Wire>, c: F) -> Wire {
+    *slot.get_or_insert_with(|| builder.constant(c))
+}
+
+fn xor<F: Field>(builder: &mut CircuitBuilder<F>, constants: &mut Constants, mode: BristolMode, a: Wire, b: Wire) -> Wire {
+    match mode {
+        BristolMode::Binary => builder.add(a, b),
+        BristolMode::Arithmetic => {
+            let minus_two = constant(builder, &mut constants.minus_two, -F::from(2u64));
+            let ab = builder.product(&[a, b, minus_two]);
+            builder.sum(&[a, b, ab])
+        }
+    }
+}
+
+fn inv<F: Field>(builder: &mut CircuitBuilder<F>, constants: &mut Constants, mode: BristolMode, a: Wire) -> Wire {
+    let one = constant(builder, &mut constants.one, F::one());
+    match mode {
+        BristolMode::Binary => builder.add(a, one),
+        BristolMode::Arithmetic => {
+            let minus_one = constant(builder, &mut constants.minus_one, -F::one());
+            let minus_a = builder.mul(a, minus_one);
+            builder.add(one, minus_a)
+        }
+    }
+}
+
+fn parse_number(token: &str, line: usize) -> Result<usize, BristolError> {
+    token.parse().map_err(|_| BristolError::Syntax { line, reason: "expected a number" })
+}
+
+/// `<個数> <大きさ...>` の行
+fn sizes((line, numbers): (usize, Vec<usize>)) -> Result<Vec<usize>, BristolError> {
+    match numbers.split_first() {
+        Some((&count, sizes)) if sizes.len() == count => Ok(sizes.to_vec()),
+        _ => Err(BristolError::Syntax { line, reason: "size count does not match the sizes" }),
+    }
+}
@@ -0,0 +1,266 @@
+// src/builder.rs
+//
+// 配線を手で並べずに層状の回路を組み立てる。
+// ゲートを追加すると配線への参照（`Wire`）が返り，`build` で層への割り当て，
+// 層をまたぐ値の中継を自動で行う。層の幅は 2 のべきに揃えない（証明側が 0 で埋める）。
+
+use ark_ff::Field;
+use std::collections::{HashMap, HashSet};
+
+use crate::circuit::{Circuit, Gate, Layer};
+use crate::error::Error;
+use crate::wiring::{circuit_wiring, LayerWiring};
+
+/// `CircuitBuilder` の中の配線（入力，定数，ゲートの出力）への参照
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Wire(usize);
+
+#[derive(Clone, Debug)]
+enum Node<F: Field> {
+    Input,
+    Constant(F),
+    SmallConstant(u64),
+    Add(Wire, Wire),
+    Mul(Wire, Wire),
+    Sum(Vec<Wire>),
+    Product(Vec<Wire>),
+}
+
+impl<F: Field> Node<F> {
+    /// 読む配線（入力と定数は空）
+    fn reads(&self) -> Vec<Wire> {
+        match self {
+            Node::Add(a, b) | Node::Mul(a, b) => vec![*a, *b],
+            Node::Sum(ws) | Node::Product(ws) => ws.clone(),
+            Node::Input | Node::Constant(_) | Node::SmallConstant(_) => vec![],
+        }
+    }
+}
+
+/// 層状の回路の組み立て
+///
+/// `constant` の定数は回路の入力として扱い，利用者の入力の後ろに並べる（`BuiltCircuit::assign` が埋める）。
+/// `small_constant` の定数は最初の層の `Gate::Constant` になり，入力を増やさない。
+/// `sum`, `product` は 1 つのゲートの入力数を `max_fan_in` 以下に抑えた木に分ける。
+#[derive(Clone, Debug)]
+pub struct CircuitBuilder<F: Field> {
+    nodes: Vec<Node<F>>,
+    /// 入力から数えた層（入力と `constant` の定数は 0，`small_constant` の定数は 1）
+    depths: Vec<usize>,
+    outputs: Vec<Wire>,
+    max_fan_in: usize,
+}
+
+impl<F: Field> Default for CircuitBuilder<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Field> CircuitBuilder<F> {
+    /// 入力数 2 のゲートだけを使う builder
+    pub fn new() -> Self {
+        Self::with_max_fan_in(2)
+    }
+
+    /// `sum`, `product` の 1 ゲートあたりの入力数の上限を指定する
+    ///
+    /// 上限を上げると幅の広い和や積の木が浅くなる。積の因子数は層の sum-check のフェーズ数になる。
+    pub fn with_max_fan_in(max_fan_in: usize) -> Self {
+        assert!(max_fan_in >= 2, "fan-in must be at least 2");
+        CircuitBuilder { nodes: Vec::new(), depths: Vec::new(), outputs: Vec::new(), max_fan_in }
+    }
+
+    pub fn max_fan_in(&self) -> usize {
+        self.max_fan_in
+    }
+
+    fn push(&mut self, node: Node<F>, depth: usize) -> Wire {
+        self.nodes.push(node);
+        self.depths.push(depth);
+        Wire(self.nodes.len() - 1)
+    }
+
+    /// 入力から数えた w の層（入力と `constant` の定数は 0）
+    pub fn depth(&self, w: Wire) -> usize {
+        assert!(w.0 < self.nodes.len(), "wire does not belong to this builder");
+        self.depths[w.0]
+    }
+
+    /// 回路の入力を 1 つ追加する（追加した順に入力の添字が決まる）
+    pub fn input(&mut self) -> Wire {
+        self.push(Node::Input, 0)
+    }
+
+    pub fn constant(&mut self, c: F) -> Wire {
+        self.push(Node::Constant(c), 0)
+    }
+
+    /// 定数ゲート（`Gate::Constant`）として置く定数
+    pub fn small_constant(&mut self, c: u64) -> Wire {
+        self.push(Node::SmallConstant(c), 1)
+    }
+
+    pub fn add(&mut self, a: Wire, b: Wire) -> Wire {
+        let depth = self.depth(a).max(self.depth(b)) + 1;
+        self.push(Node::Add(a, b), depth)
+    }
+
+    pub fn mul(&mut self, a: Wire, b: Wire) -> Wire {
+        let depth = self.depth(a).max(self.depth(b)) + 1;
+        self.push(Node::Mul(a, b), depth)
+    }
+
+    /// ws の総和（入力数 `max_fan_in` 以下の加算の木）
+    pub fn sum(&mut self, ws: &[Wire]) -> Wire {
+        self.reduce(ws, Self::add, Node::Sum)
+    }
+
+    /// ws の総積（因子数 `max_fan_in` 以下の乗算の木）
+    pub fn product(&mut self, ws: &[Wire]) -> Wire {
+        self.reduce(ws, Self::mul, Node::Product)
+    }
+
+    /// ws を `max_fan_in` 個ずつのゲートでまとめ，1 つになるまで繰り返す
+    fn reduce(
+        &mut self,
+        ws: &[Wire],
+        binary: fn(&mut Self, Wire, Wire) -> Wire,
+        wide: fn(Vec<Wire>) -> Node<F>,
+    ) -> Wire {
+        assert!(!ws.is_empty(), "cannot reduce an empty list of wires");
+        let mut level = ws.to_vec();
+        while level.len() > 1 {
+            level = level
+                .chunks(self.max_fan_in)
+                .map(|chunk| match *chunk {
+                    [w] => w,
+                    [a, b] => binary(self, a, b),
+                    _ => {
+                        let depth = chunk.iter().map(|w| self.depth(*w)).max().unwrap() + 1;
+                        self.push(wide(chunk.to_vec()), depth)
+                    }
+                })
+                .collect();
+        }
+        level[0]
+    }
+
+    /// w を回路の出力にする（出力層では呼んだ順に並ぶ）
+    pub fn output(&mut self, w: Wire) {
+        self.depth(w);
+        self.outputs.push(w);
+    }
+
+    /// 層を割り当てて回路と各層の配線述語を作る
+    ///
+    /// ゲートは入力から数えた深さの層に置き，より上の層で使う値は `Gate::Input` で中継する。
+    /// 各層と回路の入力は使う配線の数だけの幅になり，2 のべきには切り上げない。
+    pub fn build(self) -> Result<BuiltCircuit<F>, Error> {
+        if self.outputs.is_empty() {
+            return Err(Error::InvalidCircuit("builder has no outputs"));
+        }
+        let depth = self.outputs.iter().map(|w| self.depths[w.0]).max().unwrap().max(1);
+
+        // 最下層：利用者の入力，定数の順
+        let inputs: Vec<Wire> =
+            (0..self.nodes.len()).filter(|&i| matches!(self.nodes[i], Node::Input)).map(Wire).collect();
+        let constant_wires: Vec<Wire> =
+            (0..self.nodes.len()).filter(|&i| matches!(self.nodes[i], Node::Constant(_))).map(Wire).collect();
+        let constants: Vec<F> = constant_wires
+            .iter()
+            .map(|w| match self.nodes[w.0] {
+                Node::Constant(c) => c,
+                _ => unreachable!(),
+            })
+            .collect();
+        let bottom: Vec<Wire> = inputs.iter().chain(constant_wires.iter()).copied().collect();
+        if bottom.is_empty() {
+            return Err(Error::InvalidCircuit("circuit has no inputs"));
+        }
+
+        // 出力層から下へ，各層に置く配線を決める
+        let mut levels = vec![self.outputs.clone()];
+        for d in (2..=depth).rev() {
+            let mut below = Vec::new();
+            let mut seen = HashSet::new();
+            for &w in levels.last().unwrap() {
+                let reads = if self.depths[w.0] == d { self.nodes[w.0].reads() } else { vec![w] };
+                for r in reads {
+                    if seen.insert(r) {
+                        below.push(r);
+                    }
+                }
+            }
+            levels.push(below);
+        }
+        levels.push(bottom);
+
+        // levels[i] の配線を levels[i + 1] の位置を読むゲートにする
+        let mut layers = Vec::with_capacity(depth);
+        let mut layer_widths = Vec::with_capacity(depth);
+        let mut num_relays = 0;
+        for i in 0..depth {
+            let d = depth - i;
+            let positions: HashMap<Wire, usize> = levels[i + 1].iter().enumerate().map(|(p, w)| (*w, p)).collect();
+            let gates: Vec<Gate> = levels[i]
+                .iter()
+                .map(|&w| match &self.nodes[w.0] {
+                    _ if self.depths[w.0] != d => Gate::Input(positions[&w]),
+                    Node::Add(a, b) => Gate::Add(positions[a], positions[b]),
+                    Node::Mul(a, b) => Gate::Mul(positions[a], positions[b]),
+                    Node::Sum(ws) => Gate::Sum(ws.iter().map(|w| positions[w]).collect()),
+                    Node::Product(ws) => Gate::Product(ws.iter().map(|w| positions[w]).collect()),
+                    Node::SmallConstant(c) => Gate::Constant(*c),
+                    Node::Input | Node::Constant(_) => unreachable!("inputs only appear in the bottom level"),
+                })
+                .collect();
+            layer_widths.push(gates.len());
+            num_relays += gates.iter().filter(|g| matches!(g, Gate::Input(_))).count();
+            layers.push(Layer { gates });
+        }
+
+        let circuit = Circuit::new(levels[depth].len(), layers)?;
+        let wiring = circuit_wiring(&circuit);
+        Ok(BuiltCircuit {
+            circuit,
+            wiring,
+            num_inputs: inputs.len(),
+            constants,
+            num_outputs: self.outputs.len(),
+            layer_widths,
+            num_relays,
+        })
+    }
+}
+
+/// `CircuitBuilder::build` の結果
+#[derive(Clone)]
+pub struct BuiltCircuit<F: Field> {
+    pub circuit: Circuit,
+    /// 各層の配線述語（`wiring::circuit_wiring(&circuit)`）
+    pub wiring: Vec<LayerWiring<F>>,
+    /// 利用者の入力の数
+    pub num_inputs: usize,
+    /// 利用者の入力の後ろに置く定数
+    pub constants: Vec<F>,
+    /// `output` で指定した出力の数（出力層の幅）
+    pub num_outputs: usize,
+    /// 各層の実際の幅（`circuit.layers` と同じく出力層が先頭。2 のべきとは限らない）
+    pub layer_widths: Vec<usize>,
+    /// 層をまたぐ値のために入れた中継ゲート（`Gate::Input`）の数
+    pub num_relays: usize,
+}
+
+impl<F: Field> BuiltCircuit<F> {
+    /// 利用者の入力から回路の入力（入力 || 定数）を作る
+    pub fn assign(&self, inputs: &[F]) -> Vec<F> {
+        assert_eq!(inputs.len(), self.num_inputs, "wrong number of inputs");
+        inputs.iter().chain(self.constants.iter()).copied().collect()
+    }
+
+    /// 利用者の入力に対する出力
+    pub fn evaluate(&self, inputs: &[F]) -> Vec<F> {
+        self.circuit.evaluate(&self.assign(inputs)).swap_remove(0)
+    }
+}
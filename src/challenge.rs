@@ -0,0 +1,120 @@
+// src/challenge.rs
+
+use ark_ff::{Field, PrimeField};
+use rand::{CryptoRng, Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+use crate::transcript::Transcript;
+
+/// シードから決まる乱数生成器（テストや不具合の再現で，チャレンジ列をビット単位で固定する）
+pub fn seeded_rng(seed: u64) -> ChaCha20Rng {
+    ChaCha20Rng::seed_from_u64(seed)
+}
+
+/// 検証者のチャレンジの出どころ
+///
+/// 対話版は暗号学的な乱数生成器（`RngCore + CryptoRng` を実装する型はそのまま使える）から，非対話版は Fiat–Shamir の
+/// `Transcript` から引く。独自の導出（外部の乱数ビーコン，別の証明系の transcript など）を使うときは
+/// この trait を実装して検証器に渡す。
+pub trait ChallengeGenerator<F> {
+    /// 検証者が受け取ったメッセージをラベル付きで渡す（乱数生成器は読み捨てる）
+    fn absorb(&mut self, label: &[u8], msg: &[F]);
+
+    /// それまでに渡したメッセージに続くチャレンジを 1 つ引く
+    fn challenge(&mut self, label: &[u8]) -> F;
+}
+
+// 予測できる乱数（`SmallRng` など）からチャレンジを引くと健全性が崩れるので `CryptoRng` に限る
+impl<F: Field, R: RngCore + CryptoRng> ChallengeGenerator<F> for R {
+    fn absorb(&mut self, _label: &[u8], _msg: &[F]) {}
+
+    fn challenge(&mut self, _label: &[u8]) -> F {
+        F::rand(self)
+    }
+}
+
+impl<F: PrimeField> ChallengeGenerator<F> for Transcript {
+    fn absorb(&mut self, label: &[u8], msg: &[F]) {
+        self.append(label, msg);
+    }
+
+    fn challenge(&mut self, label: &[u8]) -> F {
+        self.squeeze(label)
+    }
+}
+
+/// どのチャレンジを拒否して引き直すか
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct ChallengePolicy {
+    /// 0 と 1（ブール超立方体の評価点）を拒否する
+    pub reject_degenerate: bool,
+    /// 同じフェーズ内で既に使った値を拒否する
+    pub reject_repeated: bool,
+}
+
+impl ChallengePolicy {
+    /// すべて拒否する設定
+    pub fn strict() -> Self {
+        ChallengePolicy { reject_degenerate: true, reject_repeated: true }
+    }
+}
+
+/// ポリシーに従ってチャレンジを引く
+///
+/// 拒否した場合は試行カウンタを進めて引き直す。Fiat–Shamir では
+/// カウンタを吸収してからハッシュし直すことで決定的に引き直せる。
+#[derive(Clone, Debug)]
+pub struct ChallengeSampler<F: Field> {
+    pub policy: ChallengePolicy,
+    seen: Vec<F>,
+    attempts: u64,
+}
+
+impl<F: Field> ChallengeSampler<F> {
+    pub fn new(policy: ChallengePolicy) -> Self {
+        ChallengeSampler { policy, seen: Vec::new(), attempts: 0 }
+    }
+
+    /// 新しいフェーズを始める（重複検査の履歴を捨てる）
+    pub fn begin_phase(&mut self) {
+        self.seen.clear();
+    }
+
+    /// これまでに引いた回数（拒否した分を含む）
+    pub fn attempts(&self) -> u64 {
+        self.attempts
+    }
+
+    /// r がポリシー上受理できるか
+    pub fn accepts(&self, r: &F) -> bool {
+        if self.policy.reject_degenerate && (r.is_zero() || r.is_one()) {
+            return false;
+        }
+        !(self.policy.reject_repeated && self.seen.contains(r))
+    }
+
+    /// `draw(counter)` で候補を引き，受理されるまで繰り返す
+    pub fn sample(&mut self, mut draw: impl FnMut(u64) -> F) -> F {
+        loop {
+            let r = draw(self.attempts);
+            self.attempts += 1;
+            if self.accepts(&r) {
+                if self.policy.reject_repeated {
+                    self.seen.push(r);
+                }
+                return r;
+            }
+        }
+    }
+
+    /// 暗号学的な乱数生成器から一様に引く
+    pub fn sample_rng<R: Rng + CryptoRng>(&mut self, rng: &mut R) -> F {
+        self.sample(|_| F::rand(rng))
+    }
+}
+
+impl<F: Field> Default for ChallengeSampler<F> {
+    fn default() -> Self {
+        ChallengeSampler::new(ChallengePolicy::default())
+    }
+}
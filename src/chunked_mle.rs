@@ -0,0 +1,115 @@
+// src/chunked_mle.rs
+//
+// 2^28 を超えるような巨大な表のための，固定長のページに分けて持つ密な MLE。
+//
+// 表を 1 つの Vec に置くと，確保が 1 回の巨大な割り当てになり，畳み込んでも容量は解放されない。
+// `ChunkedMLE` は 2^page_vars 要素のページの列で持ち，先頭の変数（添字の最上位ビット）を固定するたびに
+// 上半分のページを下半分のページに畳み込んでから手放すので，生きている領域が毎ラウンド半分になる。
+// 表が 1 ページに収まってからは，そのページの中で畳み込んで切り詰める。
+
+use ark_ff::Field;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::ml_extension::{DenseMLE, IndexOrder};
+
+/// 既定のページの大きさ（2^20 要素）
+pub const DEFAULT_PAGE_VARS: usize = 20;
+
+/// ページに分けて持つ密な MLE（先頭の変数が添字の最上位ビット）
+#[derive(Clone, Debug)]
+pub struct ChunkedMLE<F: Field> {
+    num_vars: usize,
+    page_vars: usize,
+    /// どのページも長さ 2^page_vars（表が 1 ページより小さいときは 1 ページで長さ 2^num_vars）
+    pages: Vec<Vec<F>>,
+}
+
+impl<F: Field> ChunkedMLE<F> {
+    /// 添字 i ごとの値 f(i) から，2^page_vars 要素ずつのページに分けて作る
+    pub fn from_fn(num_vars: usize, page_vars: usize, f: impl Fn(usize) -> F) -> Self {
+        let page_len = 1usize << page_vars.min(num_vars);
+        let pages = (0..1usize << num_vars.saturating_sub(page_vars))
+            .map(|p| (p * page_len..(p + 1) * page_len).map(&f).collect())
+            .collect();
+        ChunkedMLE { num_vars, page_vars, pages }
+    }
+
+    /// 密な表をページに分ける（表の順序は `IndexOrder::BigEndian` に揃える）
+    pub fn from_dense(dense: &DenseMLE<F>, page_vars: usize) -> Self {
+        let dense = dense.to_order(IndexOrder::BigEndian);
+        let page_len = 1usize << page_vars.min(dense.num_vars);
+        let pages = dense.evaluations.chunks(page_len).map(<[F]>::to_vec).collect();
+        ChunkedMLE { num_vars: dense.num_vars, page_vars, pages }
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    /// 1 ページの要素数の log2
+    pub fn page_vars(&self) -> usize {
+        self.page_vars
+    }
+
+    /// 生きているページの数
+    pub fn num_pages(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// 添字 i の値
+    pub fn get(&self, i: usize) -> F {
+        let page_len = self.pages[0].len();
+        self.pages[i / page_len][i % page_len]
+    }
+
+    /// 添字の順に値を返す
+    pub fn iter(&self) -> impl Iterator<Item = &F> + '_ {
+        self.pages.iter().flatten()
+    }
+
+    /// 先頭の変数を r に固定する。上半分のページは畳み込んだ後に解放する
+    pub fn fix_variable_in_place(&mut self, r: F) {
+        assert!(self.num_vars > 0, "no variable left to fix");
+        let fold = |lo: &mut [F], hi: &[F]| {
+            for (a, b) in lo.iter_mut().zip(hi) {
+                *a += r * (*b - *a);
+            }
+        };
+        if self.pages.len() > 1 {
+            let hi = self.pages.split_off(self.pages.len() / 2);
+            #[cfg(feature = "parallel")]
+            self.pages.par_iter_mut().zip(hi.par_iter()).for_each(|(lo, hi)| fold(lo, hi));
+            #[cfg(not(feature = "parallel"))]
+            self.pages.iter_mut().zip(hi.iter()).for_each(|(lo, hi)| fold(lo, hi));
+        } else {
+            let page = &mut self.pages[0];
+            let half = page.len() / 2;
+            let (lo, hi) = page.split_at_mut(half);
+            fold(lo, hi);
+            page.truncate(half);
+        }
+        self.num_vars -= 1;
+    }
+
+    /// 先頭の変数から順に `prefix` に固定する
+    pub fn fix_variables_in_place(&mut self, prefix: &[F]) {
+        assert!(prefix.len() <= self.num_vars, "too many variables to fix");
+        for r in prefix {
+            self.fix_variable_in_place(*r);
+        }
+    }
+
+    /// 任意の点での多重線形拡張の値
+    pub fn evaluate(&self, point: &[F]) -> F {
+        assert_eq!(point.len(), self.num_vars);
+        let mut table = self.clone();
+        table.fix_variables_in_place(point);
+        table.pages[0][0]
+    }
+
+    /// 1 つの Vec に並べた密な表
+    pub fn to_dense(&self) -> DenseMLE<F> {
+        DenseMLE::from_evaluations_vec(self.num_vars, self.iter().copied().collect())
+    }
+}
@@ -0,0 +1,133 @@
+// src/circuit.rs
+
+use ark_ff::Field;
+
+use crate::error::Error;
+
+/// ゲート。添字は 1 つ入力側の層（最下層なら回路の入力）を指す
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Gate {
+    Add(usize, usize),
+    Mul(usize, usize),
+    /// 入力側の層の値をそのまま通す（回路の入力を上の層へ運ぶのに使う）
+    Input(usize),
+    /// 入力を読まずに定数 c を出す
+    Constant(u64),
+    /// 任意個の値の和
+    Sum(Vec<usize>),
+    /// 任意個の値の積
+    Product(Vec<usize>),
+}
+
+impl Gate {
+    /// 読む配線の組（`Input(a)` は (a, a)，何も読まない `Constant` は (0, 0)）
+    ///
+    /// `Sum`, `Product` は 2 つ組にならないので `reads` を使う。
+    pub fn inputs(&self) -> (usize, usize) {
+        match *self {
+            Gate::Add(a, b) | Gate::Mul(a, b) => (a, b),
+            Gate::Input(a) => (a, a),
+            Gate::Constant(_) => (0, 0),
+            Gate::Sum(_) | Gate::Product(_) => panic!("gate has no pair of inputs"),
+        }
+    }
+
+    /// 読む配線の列
+    pub fn reads(&self) -> Vec<usize> {
+        match self {
+            Gate::Add(a, b) | Gate::Mul(a, b) => vec![*a, *b],
+            Gate::Input(a) => vec![*a],
+            Gate::Constant(_) => vec![],
+            Gate::Sum(xs) | Gate::Product(xs) => xs.clone(),
+        }
+    }
+
+    /// 積の形に書いたときの因子の数（`Product` 以外は 2 以下）
+    pub fn fan_in(&self) -> usize {
+        match self {
+            Gate::Product(xs) => xs.len(),
+            Gate::Input(_) | Gate::Constant(_) | Gate::Sum(_) => 1,
+            Gate::Add(..) | Gate::Mul(..) => 2,
+        }
+    }
+
+    pub fn apply<F: Field>(&self, below: &[F]) -> F {
+        match self {
+            Gate::Add(a, b) => below[*a] + below[*b],
+            Gate::Mul(a, b) => below[*a] * below[*b],
+            Gate::Input(a) => below[*a],
+            Gate::Constant(c) => F::from(*c),
+            Gate::Sum(xs) => xs.iter().map(|x| below[*x]).sum(),
+            Gate::Product(xs) => xs.iter().map(|x| below[*x]).product(),
+        }
+    }
+}
+
+/// 回路の 1 層
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Layer {
+    pub gates: Vec<Gate>,
+}
+
+/// 層状の算術回路。`layers[0]` が出力層で，最後の層は回路の入力を読む
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Circuit {
+    pub num_inputs: usize,
+    pub layers: Vec<Layer>,
+}
+
+impl Circuit {
+    /// 配線を検査して回路を作る
+    pub fn new(num_inputs: usize, layers: Vec<Layer>) -> Result<Self, Error> {
+        let circuit = Circuit { num_inputs, layers };
+        circuit.validate()?;
+        Ok(circuit)
+    }
+
+    pub fn depth(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// i 番目の層が読む層（最下層なら回路の入力）の幅
+    pub fn input_width(&self, i: usize) -> usize {
+        self.layers.get(i + 1).map(|layer| layer.gates.len()).unwrap_or(self.num_inputs)
+    }
+
+    /// 層の積の形での最大の因子数（2 未満なら 2。`wiring::LayerWiring::fan_in` と同じ値）
+    pub fn fan_in(&self, i: usize) -> usize {
+        self.layers[i].gates.iter().map(Gate::fan_in).fold(2, usize::max)
+    }
+
+    /// 入力と層が空でなく，全てのゲートが入力側の層の範囲内を読んでいるか
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.num_inputs == 0 {
+            return Err(Error::InvalidCircuit("circuit has no inputs"));
+        }
+        if self.layers.is_empty() {
+            return Err(Error::InvalidCircuit("circuit has no layers"));
+        }
+        for (i, layer) in self.layers.iter().enumerate() {
+            if layer.gates.is_empty() {
+                return Err(Error::InvalidCircuit("circuit has an empty layer"));
+            }
+            let width = self.input_width(i);
+            if layer.gates.iter().any(|gate| gate.reads().iter().any(|&x| x >= width)) {
+                return Err(Error::InvalidCircuit("gate reads a wire outside the layer below"));
+            }
+        }
+        Ok(())
+    }
+
+    /// 入力から順に評価し，各層の値を返す（`values[0]` が出力層，最後が入力）
+    pub fn evaluate<F: Field>(&self, inputs: &[F]) -> Vec<Vec<F>> {
+        assert_eq!(inputs.len(), self.num_inputs);
+        let mut values = vec![inputs.to_vec()];
+        for layer in self.layers.iter().rev() {
+            let below = values.last().unwrap();
+            let current = layer.gates.iter().map(|gate| gate.apply(below)).collect();
+            values.push(current);
+        }
+        values.reverse();
+        values
+    }
+}
@@ -0,0 +1,249 @@
+// src/circuit_format.rs
+//
+// 回路の入出力形式。フロントエンドが生成した回路を，Rust を再コンパイルせずに
+// prover へ読み込ませるためのもの。
+//
+// バイナリ形式（整数は全て LEB128 の可変長）:
+//
+//   "GKRC" | version: u8 | num_inputs | depth | 層 × depth
+//   層     : ゲート数 | ゲート × ゲート数
+//   ゲート : tag: u8 | 引数
+//
+// tag は `statement::circuit_digest` と同じ（Add = 0, Mul = 1, Input = 2, Constant = 3,
+// Sum = 4, Product = 5）。Add/Mul は 2 つの添字，Input は 1 つ，Constant は値，
+// Sum/Product は個数に続けて添字を並べる。
+//
+// JSON 形式はデバッグ用で，`{"num_inputs": 3, "layers": [[{"mul": [0, 1]}], ...]}` の形。
+// どちらも読み込み時に `Circuit::new` で配線を検査する。
+
+use serde_json::{json, Value};
+
+use crate::circuit::{Circuit, Gate, Layer};
+use crate::serialization::SerializationError;
+
+/// 回路バイト列の先頭に置くマジック
+pub const CIRCUIT_MAGIC: [u8; 4] = *b"GKRC";
+/// 回路のバイナリ形式のバージョン
+pub const CIRCUIT_FORMAT_VERSION: u8 = 1;
+
+const TAG_ADD: u8 = 0;
+const TAG_MUL: u8 = 1;
+const TAG_INPUT: u8 = 2;
+const TAG_CONSTANT: u8 = 3;
+const TAG_SUM: u8 = 4;
+const TAG_PRODUCT: u8 = 5;
+
+impl Circuit {
+    /// バイナリ形式に書き出す
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut out = CIRCUIT_MAGIC.to_vec();
+        out.push(CIRCUIT_FORMAT_VERSION);
+        write_varint(&mut out, self.num_inputs as u64);
+        write_varint(&mut out, self.layers.len() as u64);
+        for layer in &self.layers {
+            write_varint(&mut out, layer.gates.len() as u64);
+            for gate in &layer.gates {
+                write_gate(&mut out, gate);
+            }
+        }
+        out
+    }
+
+    /// バイナリ形式から読み，配線を検査する
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, SerializationError> {
+        let mut input = bytes;
+        if take(&mut input, CIRCUIT_MAGIC.len())? != CIRCUIT_MAGIC {
+            return Err(SerializationError::BadMagic);
+        }
+        let version = take(&mut input, 1)?[0];
+        if version != CIRCUIT_FORMAT_VERSION {
+            return Err(SerializationError::UnsupportedVersion(version));
+        }
+        let num_inputs = read_usize(&mut input)?;
+        let depth = read_count(&mut input, 1)?;
+        let mut layers = Vec::with_capacity(depth);
+        for _ in 0..depth {
+            // ゲートは少なくとも tag と 1 バイトの引数を持つ
+            let width = read_count(&mut input, 2)?;
+            let gates = (0..width).map(|_| read_gate(&mut input)).collect::<Result<_, _>>()?;
+            layers.push(Layer { gates });
+        }
+        if !input.is_empty() {
+            return Err(SerializationError::TrailingBytes(input.len()));
+        }
+        Ok(Circuit::new(num_inputs, layers)?)
+    }
+
+    /// デバッグ用の JSON 形式に書き出す
+    pub fn to_json(&self) -> String {
+        let layers: Vec<Value> = self
+            .layers
+            .iter()
+            .map(|layer| Value::Array(layer.gates.iter().map(gate_to_json).collect()))
+            .collect();
+        let root = json!({ "num_inputs": self.num_inputs, "layers": layers });
+        serde_json::to_string_pretty(&root).expect("JSON values always serialize")
+    }
+
+    /// JSON 形式から読み，配線を検査する
+    pub fn from_json(input: &str) -> Result<Self, SerializationError> {
+        let root: Value = serde_json::from_str(input).map_err(|e| SerializationError::Json(e.to_string()))?;
+        let num_inputs = root.get("num_inputs").ok_or(malformed("missing \"num_inputs\""))?;
+        let num_inputs = json_usize(num_inputs)?;
+        let layers = root
+            .get("layers")
+            .and_then(Value::as_array)
+            .ok_or(malformed("missing \"layers\""))?
+            .iter()
+            .map(|layer| {
+                let gates = layer.as_array().ok_or(malformed("layer is not an array"))?;
+                let gates = gates.iter().map(gate_from_json).collect::<Result<_, _>>()?;
+                Ok(Layer { gates })
+            })
+            .collect::<Result<_, SerializationError>>()?;
+        Ok(Circuit::new(num_inputs, layers)?)
+    }
+}
+
+fn write_varint(out: &mut Vec<u8>, mut x: u64) {
+    while x >= 0x80 {
+        out.push((x as u8) | 0x80);
+        x >>= 7;
+    }
+    out.push(x as u8);
+}
+
+/// LEB128 を読む（冗長な符号化と 64 ビットを超える値は拒否する）
+fn read_varint(input: &mut &[u8]) -> Result<u64, SerializationError> {
+    let mut x = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = take(input, 1)?[0];
+        let bits = u64::from(byte & 0x7f);
+        if shift == 63 && bits > 1 {
+            return Err(malformed("varint overflows 64 bits"));
+        }
+        x |= bits << shift;
+        if byte & 0x80 == 0 {
+            if byte == 0 && shift > 0 {
+                return Err(malformed("varint is not minimally encoded"));
+            }
+            return Ok(x);
+        }
+    }
+    Err(malformed("varint overflows 64 bits"))
+}
+
+fn read_usize(input: &mut &[u8]) -> Result<usize, SerializationError> {
+    usize::try_from(read_varint(input)?).map_err(|_| malformed("value does not fit in usize"))
+}
+
+/// 要素数を読む。各要素が `min_len` バイト以上なので，残りのバイト数を超える数は拒否する
+fn read_count(input: &mut &[u8], min_len: usize) -> Result<usize, SerializationError> {
+    let count = read_usize(input)?;
+    if count > input.len() / min_len {
+        return Err(SerializationError::UnexpectedEnd);
+    }
+    Ok(count)
+}
+
+fn take<'a>(input: &mut &'a [u8], n: usize) -> Result<&'a [u8], SerializationError> {
+    if input.len() < n {
+        return Err(SerializationError::UnexpectedEnd);
+    }
+    let (head, tail) = input.split_at(n);
+    *input = tail;
+    Ok(head)
+}
+
+fn write_gate(out: &mut Vec<u8>, gate: &Gate) {
+    match gate {
+        Gate::Add(a, b) | Gate::Mul(a, b) => {
+            out.push(if matches!(gate, Gate::Add(..)) { TAG_ADD } else { TAG_MUL });
+            write_varint(out, *a as u64);
+            write_varint(out, *b as u64);
+        }
+        Gate::Input(a) => {
+            out.push(TAG_INPUT);
+            write_varint(out, *a as u64);
+        }
+        Gate::Constant(c) => {
+            out.push(TAG_CONSTANT);
+            write_varint(out, *c);
+        }
+        Gate::Sum(xs) | Gate::Product(xs) => {
+            out.push(if matches!(gate, Gate::Sum(_)) { TAG_SUM } else { TAG_PRODUCT });
+            write_varint(out, xs.len() as u64);
+            for x in xs {
+                write_varint(out, *x as u64);
+            }
+        }
+    }
+}
+
+fn read_gate(input: &mut &[u8]) -> Result<Gate, SerializationError> {
+    let tag = take(input, 1)?[0];
+    Ok(match tag {
+        TAG_ADD => Gate::Add(read_usize(input)?, read_usize(input)?),
+        TAG_MUL => Gate::Mul(read_usize(input)?, read_usize(input)?),
+        TAG_INPUT => Gate::Input(read_usize(input)?),
+        TAG_CONSTANT => Gate::Constant(read_varint(input)?),
+        TAG_SUM | TAG_PRODUCT => {
+            let len = read_count(input, 1)?;
+            let xs = (0..len).map(|_| read_usize(input)).collect::<Result<_, _>>()?;
+            if tag == TAG_SUM {
+                Gate::Sum(xs)
+            } else {
+                Gate::Product(xs)
+            }
+        }
+        other => return Err(SerializationError::UnknownGate(other)),
+    })
+}
+
+fn gate_to_json(gate: &Gate) -> Value {
+    match gate {
+        Gate::Add(a, b) => json!({ "add": [a, b] }),
+        Gate::Mul(a, b) => json!({ "mul": [a, b] }),
+        Gate::Input(a) => json!({ "input": a }),
+        Gate::Constant(c) => json!({ "constant": c }),
+        Gate::Sum(xs) => json!({ "sum": xs }),
+        Gate::Product(xs) => json!({ "product": xs }),
+    }
+}
+
+fn gate_from_json(value: &Value) -> Result<Gate, SerializationError> {
+    let (kind, args) = value
+        .as_object()
+        .filter(|map| map.len() == 1)
+        .and_then(|map| map.iter().next())
+        .ok_or(malformed("gate is not a single-key object"))?;
+    let list = || -> Result<Vec<usize>, SerializationError> {
+        args.as_array().ok_or(malformed("gate inputs are not an array"))?.iter().map(json_usize).collect()
+    };
+    let pair = || -> Result<(usize, usize), SerializationError> {
+        match list()?.as_slice() {
+            [a, b] => Ok((*a, *b)),
+            _ => Err(malformed("binary gate needs exactly two inputs")),
+        }
+    };
+    Ok(match kind.as_str() {
+        "add" => pair().map(|(a, b)| Gate::Add(a, b))?,
+        "mul" => pair().map(|(a, b)| Gate::Mul(a, b))?,
+        "input" => Gate::Input(json_usize(args)?),
+        "constant" => Gate::Constant(args.as_u64().ok_or(malformed("constant is not a u64"))?),
+        "sum" => Gate::Sum(list()?),
+        "product" => Gate::Product(list()?),
+        _ => return Err(malformed("unknown gate kind")),
+    })
+}
+
+fn json_usize(value: &Value) -> Result<usize, SerializationError> {
+    value
+        .as_u64()
+        .and_then(|x| usize::try_from(x).ok())
+        .ok_or(malformed("expected a non-negative integer"))
+}
+
+fn malformed(reason: &'static str) -> SerializationError {
+    SerializationError::MalformedCircuit(reason)
+}
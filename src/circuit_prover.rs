@@ -0,0 +1,892 @@
+// src/circuit_prover.rs
+//
+// 層状回路全体の GKR。出力層から入力層へ向かって 1 層ずつ LinearGKR で主張を還元する。
+//
+// 各層の値 V を 1 変数増やした表 W（W(0, x) = V(x)，W(1, 0) = 1，他は 0）に埋め込み，
+// 加算 V(a) + V(b) を W(a)·W(one) + W(one)·W(b) と積の形に書き直して，
+// 層全体を Σ_{x,y} f1(z, x, y)·W(x)·W(y) の 1 つの LinearGKR に載せる。
+// 1 層の還元で得られる V の 2 点 (u', v') での主張は，ランダムな係数 α, β による
+// 線形結合 α·V(u') + β·V(v') = Σ_z (α·eq(u', z) + β·eq(v', z))·V(z) として次の層へ渡す。
+// `ClaimReduction::LineRestriction` を選ぶと，元の GKR のように u', v' を通る直線上の 1 点に移る。
+//
+// 因子が 3 つ以上の積（`Gate::Product`）を含む層は，因子数 k に合わせて全ての項を W(one) で k 因子に揃え，
+// `fan_in` の k フェーズの sum-check で還元する。このとき V の主張は k 点になり，同じように 1 つにまとめる。
+//
+// `prove_batch` は同じ回路の N 個の入力を，各層の値にコピーの添字 b（log N 変数）を先頭に足した
+// V(b, x) として 1 本の GKR で証明する（データ並列 GKR）。配線は b についてブロック対角で，
+// 検証者は共通の配線を主張の点ごとに 1 回評価し，b の部分は eq の積で閉じた形に求める。
+//
+// `prove_repeated` は小さな体での健全性のため，同じ主張の証明を `soundness::SecurityConfig` の回数だけ繰り返す。
+//
+// `prove_committed` は入力層の MLE を `pcs` でコミットし，最後の主張を入力の代わりに開示で確かめさせる。
+// 配線述語の方をコミットして使い回す前処理モードは `preprocessing` に，
+// 中間層の値を隠すゼロ知識モードは `zk_gkr`（`zk` フィーチャ）にある。
+
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::PrimeField;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use crate::circuit::Circuit;
+use crate::error::Error;
+use crate::eq::{eq_eval_many, eq_prefix_sum, eq_table};
+use crate::fan_in::{FanInProof, FanInProver, FanInVerifier};
+use crate::ml_extension::{DenseMLE, SparseMLE};
+use crate::pcs::MultilinearPCS;
+use crate::prover::{LinearGKRProof, LinearGKRProver};
+use crate::self_check::direct_evaluation;
+use crate::soundness::SecurityConfig;
+use crate::statement::Statement;
+use crate::sumcheck::lagrange_weights;
+use crate::transcript::Transcript;
+use crate::verifier::LinearGKRVerifier;
+use crate::wiring::{self, LayerWiring};
+
+/// 回路の証明を初期化するラベル
+pub const CIRCUIT_LABEL: &[u8] = b"gkr-circuit";
+
+/// 同じ回路の複数の入力をまとめた証明を初期化するラベル
+pub const BATCH_LABEL: &[u8] = b"gkr-circuit-batch";
+
+/// 繰り返した回路の証明を初期化するラベル
+pub const REPEATED_LABEL: &[u8] = b"gkr-circuit-repeated";
+
+/// 回路全体の証明
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GKRProof<F: PrimeField = ScalarField> {
+    /// 主張する出力
+    pub outputs: Vec<F>,
+    /// 出力側から順に，因子数 2 の層ごとの LinearGKR の証明
+    pub layer_proofs: Vec<LinearGKRProof<F>>,
+    /// 出力側から順に，因子数 3 以上の層ごとの k フェーズの sum-check の証明
+    pub wide_layer_proofs: Vec<FanInProof<F>>,
+    /// `ClaimReduction::LineRestriction` のときの層ごとの直線への制限（0, 1, ..., (k - 1)·l での値）
+    pub line_restrictions: Vec<Vec<F>>,
+}
+
+/// 入力をコミットメントで隠した回路の証明（`GKRProver::prove_committed`）
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommittedGKRProof<F: PrimeField, O> {
+    pub proof: GKRProof<F>,
+    /// 入力層の最後の結合した主張の各点での入力の MLE の値
+    pub input_evals: Vec<F>,
+    /// `input_evals` の各値の開示の証明
+    pub openings: Vec<O>,
+}
+
+/// 同じ回路の N 個の入力に対する証明（`GKRProver::prove_batch`）
+///
+/// 層の間の主張の還元は `ClaimReduction::RandomCombination` に限る。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BatchGKRProof<F: PrimeField = ScalarField> {
+    /// コピーごとの主張する出力
+    pub outputs: Vec<Vec<F>>,
+    /// 出力側から順に，因子数 2 の層ごとの LinearGKR の証明
+    pub layer_proofs: Vec<LinearGKRProof<F>>,
+    /// 出力側から順に，因子数 3 以上の層ごとの k フェーズの sum-check の証明
+    pub wide_layer_proofs: Vec<FanInProof<F>>,
+}
+
+/// 同じ主張の証明を k 回繰り返したもの（`GKRProver::prove_repeated`）
+///
+/// 各回は 1 つの transcript を順に共有し，回ごとに添字を吸収してから還元する。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RepeatedGKRProof<F: PrimeField = ScalarField> {
+    pub repetitions: Vec<GKRProof<F>>,
+}
+
+impl<F: PrimeField> RepeatedGKRProof<F> {
+    /// 主張する出力（1 回目の証明のもの）
+    pub fn outputs(&self) -> &[F] {
+        self.repetitions.first().map(|proof| &proof.outputs[..]).unwrap_or(&[])
+    }
+}
+
+/// データ並列 GKR のコピーの数 N と，その添字の変数数 m = ⌈log2 N⌉
+///
+/// 添字 N 以上のコピーは W が全て 0（定数 1 の配線も 0）で，どの層の値も 0 になる。
+#[derive(Clone, Copy, Debug)]
+struct Copies {
+    vars: usize,
+    count: usize,
+}
+
+impl Copies {
+    const SINGLE: Copies = Copies { vars: 0, count: 1 };
+
+    fn new(count: usize) -> Self {
+        Copies { vars: count.next_power_of_two().trailing_zeros() as usize, count }
+    }
+}
+
+/// 1 層の還元で得た k 点（因子数 2 の層では 2 点）の主張を次の層の 1 つの主張にまとめる方法
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ClaimReduction {
+    /// ランダムな係数による線形結合 α·V(u') + β·V(v') + ...（`CombinedClaim`）
+    #[default]
+    RandomCombination,
+    /// V を k 点を通る k - 1 次の曲線（2 点なら直線）に制限した 1 変数多項式を送り，曲線上のランダムな 1 点に移る
+    LineRestriction,
+}
+
+impl ClaimReduction {
+    fn tag(self) -> u8 {
+        match self {
+            ClaimReduction::RandomCombination => 0,
+            ClaimReduction::LineRestriction => 1,
+        }
+    }
+
+    /// Prover 側の還元。直線への制限を使う場合はそれも返す
+    fn prove<F: PrimeField>(
+        self,
+        transcript: &mut Transcript,
+        values: &[F],
+        points: Vec<Vec<F>>,
+    ) -> (CombinedClaim<F>, Option<Vec<F>>) {
+        match self {
+            ClaimReduction::RandomCombination => (CombinedClaim::reduce_many(transcript, points), None),
+            ClaimReduction::LineRestriction => {
+                let degree = (points.len() - 1) * points[0].len();
+                let restriction: Vec<F> =
+                    (0..=degree).map(|t| padded_evaluation(values, &curve(&points, F::from(t as u64)))).collect();
+                transcript.append_fields(b"line_restriction", &restriction);
+                let r = transcript.challenge_field(b"line_point");
+                (CombinedClaim::at_point(curve(&points, r)), Some(restriction))
+            }
+        }
+    }
+
+    /// Verifier 側の還元。k 点での V の主張 (点, 値) から次の層の主張とその値を求める
+    fn verify<F: PrimeField>(
+        self,
+        transcript: &mut Transcript,
+        claims: Vec<(Vec<F>, F)>,
+        restriction: Option<&[F]>,
+    ) -> Result<(CombinedClaim<F>, F), Error> {
+        let (points, values): (Vec<Vec<F>>, Vec<F>) = claims.into_iter().unzip();
+        match (self, restriction) {
+            (ClaimReduction::RandomCombination, None) => {
+                let next = CombinedClaim::reduce_many(transcript, points);
+                let claim = next.combine(&values);
+                Ok((next, claim))
+            }
+            (ClaimReduction::LineRestriction, Some(restriction)) => {
+                let degree = (points.len() - 1) * points[0].len();
+                if restriction.len() != degree + 1 {
+                    return Err(Error::LengthMismatch {
+                        what: "line restriction evaluations",
+                        expected: degree + 1,
+                        found: restriction.len(),
+                    });
+                }
+                // 曲線上の t = 0, 1, ..., k - 1 が k 個の主張に一致すること
+                if values.iter().enumerate().any(|(t, v)| interpolate(restriction, F::from(t as u64)) != *v) {
+                    return Err(Error::EvaluationMismatch("line restriction at the layer claims"));
+                }
+                transcript.append_fields(b"line_restriction", restriction);
+                let r = transcript.challenge_field(b"line_point");
+                Ok((CombinedClaim::at_point(curve(&points, r)), interpolate(restriction, r)))
+            }
+            _ => Err(Error::MalformedProof("line restrictions do not match the claim reduction")),
+        }
+    }
+}
+
+/// t = 0, 1, ..., k - 1 で k 個の点を通る k - 1 次の曲線上の点（2 点 u, v なら (1 - t)·u + t·v）
+fn curve<F: PrimeField>(points: &[Vec<F>], t: F) -> Vec<F> {
+    let weights = lagrange_weights(points.len(), t);
+    (0..points[0].len()).map(|i| points.iter().zip(weights.iter()).map(|(p, w)| p[i] * w).sum()).collect()
+}
+
+/// 0, 1, ..., d での値から 1 変数多項式の点 t での値を求める
+fn interpolate<F: PrimeField>(evals: &[F], t: F) -> F {
+    lagrange_weights(evals.len(), t).iter().zip(evals.iter()).map(|(w, e)| *w * e).sum()
+}
+
+/// 層の配線述語を W 上の k 因子の積の形 f1(z, x_1, ..., x_k)（l + 1 変数ずつ，添字は `fan_in` の規約）に書き直す
+///
+/// 定数 1 の配線は W の添字 2^l で，因子が k に満たない項は残りを W(one) で埋める。
+/// add(z, x, y) は (x, one) と (one, y) に，relay(z, x) は (x, one) に，constant(z) は (one, one) に振り分ける。
+/// k = 2 のときの添字は prover の規約 `z << 2n | y << n | x` と同じ。
+fn product_form<F: PrimeField>(layer: &LayerWiring<F>, fan_in: usize) -> SparseMLE<F> {
+    let l = layer.num_vars;
+    let n = l + 1;
+    let mask = (1 << l) - 1;
+    let one = 1 << l;
+    let mut evaluations = HashMap::new();
+    let mut add = |z: usize, factors: &[usize], val: F| {
+        assert!(factors.len() <= fan_in);
+        let padding = std::iter::repeat_n(one, fan_in - factors.len());
+        let index = padding.chain(factors.iter().rev().copied()).fold(z, |acc, x| (acc << n) | x);
+        *evaluations.entry(index).or_insert_with(F::zero) += val;
+    };
+    for (&index, &val) in layer.mul.evaluations.iter() {
+        add(index >> (2 * l), &[index & mask, (index >> l) & mask], val);
+    }
+    for (&index, &val) in layer.add.evaluations.iter() {
+        let (z, x, y) = (index >> (2 * l), index & mask, (index >> l) & mask);
+        add(z, &[x, one], val);
+        add(z, &[one, y], val);
+    }
+    for (&index, &val) in layer.relay.evaluations.iter() {
+        add(index >> l, &[index & mask], val);
+    }
+    for (&z, &val) in layer.constant.evaluations.iter() {
+        add(z, &[], val);
+    }
+    for (&k, table) in layer.wide_mul.iter() {
+        for (&index, &val) in table.evaluations.iter() {
+            let factors: Vec<usize> = (0..k).map(|j| (index >> (j * l)) & mask).collect();
+            add(index >> (k * l), &factors, val);
+        }
+    }
+    SparseMLE::new((fan_in + 1) * n, evaluations)
+}
+
+/// 回路の層ごとの積の形の配線述語
+pub(crate) fn product_forms<F: PrimeField>(circuit: &Circuit) -> Vec<SparseMLE<F>> {
+    wiring::circuit_wiring::<F>(circuit).iter().map(|layer| product_form(layer, layer.fan_in())).collect()
+}
+
+/// 積の形の配線述語 f1 を，上の層の主張の点 q と W 側の k 点で評価する点 ((0, q), x_k, ..., x_1)
+pub(crate) fn wiring_point<F: PrimeField>(q: &[F], points: &[Vec<F>]) -> Vec<F> {
+    embed(q).into_iter().chain(points.iter().rev().flatten().copied()).collect()
+}
+
+/// 層の値を 2^l に 0 で埋め，定数 1 の配線を足した W の表
+pub(crate) fn extended_values<F: PrimeField>(values: &[F], l: usize) -> DenseMLE<F> {
+    let mut table = vec![F::zero(); 2 << l];
+    table[..values.len()].copy_from_slice(values);
+    table[1 << l] = F::one();
+    DenseMLE::from_evaluations_vec(l + 1, table)
+}
+
+/// l 変数の点 p を W 側（先頭が定数 1 の配線のビット）の点 (0, p) に埋め込む
+fn embed<F: PrimeField>(point: &[F]) -> Vec<F> {
+    std::iter::once(F::zero()).chain(point.iter().copied()).collect()
+}
+
+/// 2^l に 0 で埋めた値の多重線形拡張の点 p での値
+fn padded_evaluation<F: PrimeField>(values: &[F], point: &[F]) -> F {
+    let mut table = vec![F::zero(); 1 << point.len()];
+    table[..values.len()].copy_from_slice(values);
+    direct_evaluation(&table, point)
+}
+
+/// 出力の主張を transcript に吸収し，最初の点 r_0 を引く
+pub(crate) fn output_point<F: PrimeField>(transcript: &mut Transcript, outputs: &[F], l: usize) -> Vec<F> {
+    transcript.append_fields(b"outputs", outputs);
+    (0..l).map(|_| transcript.challenge_field(b"output_point")).collect()
+}
+
+/// 層の値 V についての複数の点 u_j' での主張を Σ_j c_j·V(u_j') の 1 つにまとめたもの
+///
+/// 次の層の sum-check は Σ_z (Σ_j c_j·eq(u_j', z))·V(z) を対象にするので，
+/// LinearGKR の g を固定する代わりに `weights` で出力側の変数を重み付けする。
+/// 2 点のときの係数は α, β と呼ぶ。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CombinedClaim<F: PrimeField = ScalarField> {
+    pub points: Vec<Vec<F>>,
+    pub coeffs: Vec<F>,
+}
+
+impl<F: PrimeField> CombinedClaim<F> {
+    /// 出力層の 1 点 r_0 での主張（係数 1）
+    pub fn at_point(point: Vec<F>) -> Self {
+        CombinedClaim { points: vec![point], coeffs: vec![F::one()] }
+    }
+
+    /// 2 点 u', v' の主張を結合する係数 α, β を transcript から引く
+    pub fn reduce(transcript: &mut Transcript, u: Vec<F>, v: Vec<F>) -> Self {
+        Self::reduce_many(transcript, vec![u, v])
+    }
+
+    /// k 点の主張を結合する係数を transcript から引く（先頭から α, β, 残りは γ のラベル）
+    pub fn reduce_many(transcript: &mut Transcript, points: Vec<Vec<F>>) -> Self {
+        let coeffs = (0..points.len())
+            .map(|j| {
+                let label: &[u8] = match j {
+                    0 => b"alpha",
+                    1 => b"beta",
+                    _ => b"gamma",
+                };
+                transcript.challenge_field(label)
+            })
+            .collect();
+        CombinedClaim { points, coeffs }
+    }
+
+    /// 各点での V の値から結合した主張の値を求める
+    pub fn combine(&self, values: &[F]) -> F {
+        assert_eq!(values.len(), self.points.len());
+        self.coeffs.iter().zip(values.iter()).map(|(c, v)| *c * v).sum()
+    }
+
+    /// 値の表（2^l に 0 で埋める）から結合した主張の値を直接求める
+    pub fn evaluate(&self, values: &[F]) -> F {
+        let at: Vec<F> = self.points.iter().map(|p| padded_evaluation(values, p)).collect();
+        self.combine(&at)
+    }
+
+    /// W 側の出力変数の重み Σ_j c_j·eq((0, u_j'), z)
+    pub fn weights(&self) -> Vec<F> {
+        self.batched_weights(Copies::SINGLE)
+    }
+
+    /// 先頭 m 変数がコピーの添字 b のときの W 側の重み Σ_j c_j·eq((b_j, 0, z_j), ·)
+    fn batched_weights(&self, copies: Copies) -> Vec<F> {
+        let mut weights = vec![F::zero(); 2 << self.points[0].len()];
+        for (p, c) in self.points.iter().zip(self.coeffs.iter()) {
+            let (b, z) = p.split_at(copies.vars);
+            let point: Vec<F> = b.iter().copied().chain(embed(z)).collect();
+            for (w, e) in weights.iter_mut().zip(eq_table(&point)) {
+                *w += *c * e;
+            }
+        }
+        weights
+    }
+}
+
+/// コミットする入力層の MLE（回路の変数数 l に合わせて 2^l に 0 で埋める）
+fn input_mle<F: PrimeField>(circuit: &Circuit, inputs: &[F]) -> DenseMLE<F> {
+    let l = wiring::circuit_num_vars(circuit);
+    let mut evaluations = inputs.to_vec();
+    evaluations.resize(1 << l, F::zero());
+    DenseMLE::from_evaluations_vec(l, evaluations)
+}
+
+/// W(u) = (1 - u_0)·V(u') + u_0·eq(u', 0) から V(u') を取り出す（u' = u[1..]）
+///
+/// コピーの添字 b を先頭に持つときは u = (b, u_0, x) で，W(u) = (1 - u_0)·V(b, x) + u_0·eq(x, 0)·Σ_{c<N} eq(b, c)。
+fn unembed<F: PrimeField>(u: &[F], w_at_u: F, copies: Copies) -> Result<(Vec<F>, F), Error> {
+    let (b, rest) = u.split_at(copies.vars);
+    let (u0, x) = (rest[0], &rest[1..]);
+    let at_zero: F = x.iter().map(|r| F::one() - r).product::<F>() * eq_prefix_sum(b, copies.count);
+    let inverse = (F::one() - u0).inverse().ok_or(Error::Transcript("degenerate challenge for the constant-one wire"))?;
+    let point = b.iter().chain(x.iter()).copied().collect();
+    Ok((point, (w_at_u - u0 * at_zero) * inverse))
+}
+
+/// コピーごとの値を 2^l に 0 で埋めて並べ，コピーの数も 2^m に 0 で埋めた V(b, x) の表
+fn batched_values<F: PrimeField>(copies: &[&[F]], l: usize, m: usize) -> Vec<F> {
+    let mut table = vec![F::zero(); 1 << (m + l)];
+    for (b, values) in copies.iter().enumerate() {
+        table[b << l..(b << l) + values.len()].copy_from_slice(values);
+    }
+    table
+}
+
+/// コピーごとの W（`extended_values`）を並べた表。N 以上の添字のコピーは 0
+fn batched_extended_values<F: PrimeField>(copies: &[&[F]], l: usize, m: usize) -> DenseMLE<F> {
+    let mut table = vec![F::zero(); 2 << (m + l)];
+    for (b, values) in copies.iter().enumerate() {
+        table[b << (l + 1)..(b << (l + 1)) + (2 << l)].copy_from_slice(&extended_values(values, l).evaluations);
+    }
+    DenseMLE::from_evaluations_vec(m + l + 1, table)
+}
+
+/// 積の形 f1（k 因子，1 つ分 n 変数）を 2^m 個のコピーに並べたブロック対角の述語
+///
+/// 出力と各因子の添字の先頭に同じコピーの添字 b を付ける（1 つ分 m + n 変数）。
+fn block_diagonal<F: PrimeField>(f1: &SparseMLE<F>, fan_in: usize, n: usize, m: usize) -> SparseMLE<F> {
+    let mask = (1 << n) - 1;
+    let mut evaluations = HashMap::with_capacity(f1.evaluations.len() << m);
+    for b in 0..1 << m {
+        for (&index, &val) in f1.evaluations.iter() {
+            let batched = (0..=fan_in).rev().fold(0, |acc, j| (acc << (m + n)) | (b << n) | ((index >> (j * n)) & mask));
+            evaluations.insert(batched, val);
+        }
+    }
+    SparseMLE::new((fan_in + 1) * (m + n), evaluations)
+}
+
+/// 層状回路全体の GKR Prover
+pub struct GKRProver<F: PrimeField = ScalarField>(PhantomData<F>);
+
+impl<F: PrimeField> GKRProver<F> {
+    /// 回路を入力で評価し，出力層から入力層まで順に還元する証明を作る
+    pub fn prove_circuit(circuit: &Circuit, inputs: &[F]) -> GKRProof<F> {
+        Self::prove_circuit_with(circuit, inputs, ClaimReduction::default())
+    }
+
+    /// 層の間の主張の還元方法を指定して証明を作る
+    pub fn prove_circuit_with(circuit: &Circuit, inputs: &[F], reduction: ClaimReduction) -> GKRProof<F> {
+        let values = circuit.evaluate(inputs);
+        let statement = Statement::new(circuit, inputs.to_vec(), values[0].clone());
+        let mut transcript = Transcript::for_statement(CIRCUIT_LABEL, &statement);
+        Self::prove_layers(circuit, &values, &product_forms(circuit), reduction, &mut transcript).0
+    }
+
+    /// 体が小さいときのために，`config` の回数だけ証明を繰り返して健全性誤差を下げる
+    pub fn prove_repeated(
+        circuit: &Circuit,
+        inputs: &[F],
+        config: &SecurityConfig,
+    ) -> Result<RepeatedGKRProof<F>, Error> {
+        let k = config.repetitions::<F>(circuit)?;
+        let values = circuit.evaluate(inputs);
+        let statement = Statement::new(circuit, inputs.to_vec(), values[0].clone());
+        let mut transcript = repeated_transcript(&statement, k);
+        let f1s = product_forms(circuit);
+        let repetitions = (0..k)
+            .map(|i| {
+                transcript.append_message(b"repetition", &(i as u64).to_le_bytes());
+                Self::prove_layers(circuit, &values, &f1s, ClaimReduction::default(), &mut transcript).0
+            })
+            .collect();
+        Ok(RepeatedGKRProof { repetitions })
+    }
+
+    /// 入力を多重線形多項式のコミットメントで隠して証明する
+    ///
+    /// 入力層の MLE（2^l に 0 で埋める）へのコミットメントを主張に含め，
+    /// 最後の結合した主張の各点で開示する。検証者は入力を受け取らない。
+    pub fn prove_committed<P: MultilinearPCS<F>>(
+        pp: &P::ProverParam,
+        circuit: &Circuit,
+        inputs: &[F],
+        reduction: ClaimReduction,
+    ) -> (P::Commitment, CommittedGKRProof<F, P::Proof>) {
+        let values = circuit.evaluate(inputs);
+        let input_mle = input_mle(circuit, inputs);
+        let commitment = P::commit(pp, &input_mle);
+        let statement =
+            Statement::new(circuit, Vec::new(), values[0].clone()).with_commitment(P::commitment_bytes(&commitment));
+        let mut transcript = Transcript::for_statement(CIRCUIT_LABEL, &statement);
+        let (proof, _, reduced) = Self::prove_layers(circuit, &values, &product_forms(circuit), reduction, &mut transcript);
+        let (input_evals, openings) = reduced.points.iter().map(|p| P::open(pp, &input_mle, p)).unzip();
+        (commitment, CommittedGKRProof { proof, input_evals, openings })
+    }
+
+    /// 出力層から入力層まで還元し，証明，層ごとに配線述語を評価する点，入力層についての最後の結合した主張を返す
+    ///
+    /// `f1s` は層ごとの積の形の配線述語（`product_forms`）。配線述語を評価する点は
+    /// 上の層の結合した主張の点ごとに 1 つずつ（`wiring_point`）。
+    pub(crate) fn prove_layers(
+        circuit: &Circuit,
+        values: &[Vec<F>],
+        f1s: &[SparseMLE<F>],
+        reduction: ClaimReduction,
+        transcript: &mut Transcript,
+    ) -> (GKRProof<F>, Vec<Vec<Vec<F>>>, CombinedClaim<F>) {
+        let l = wiring::circuit_num_vars(circuit);
+        transcript.append_message(b"claim_reduction", &[reduction.tag()]);
+
+        let mut reduced = CombinedClaim::at_point(output_point(transcript, &values[0], l));
+        let mut layer_proofs = Vec::with_capacity(circuit.depth());
+        let mut wide_layer_proofs = Vec::new();
+        let mut line_restrictions = Vec::new();
+        let mut wiring_points = Vec::with_capacity(circuit.depth());
+        for (i, f1) in f1s.iter().enumerate() {
+            transcript.begin_layer(i);
+            let w = extended_values(&values[i + 1], l);
+            let k = f1.num_vars / (l + 1) - 1;
+            let points = if k == 2 {
+                let pre = LinearGKRProver::precompute(f1, &w, &w);
+                let (proof, u, v) = LinearGKRProver::prove_weighted(&pre, &reduced.weights(), transcript);
+                layer_proofs.push(proof);
+                vec![u, v]
+            } else {
+                let (proof, points) = FanInProver::prove_weighted(f1, &vec![&w; k], &reduced.weights(), transcript);
+                wide_layer_proofs.push(proof);
+                points
+            };
+            wiring_points.push(reduced.points.iter().map(|q| wiring_point(q, &points)).collect());
+            let restriction;
+            let points = points.iter().map(|p| p[1..].to_vec()).collect();
+            (reduced, restriction) = reduction.prove(transcript, &values[i + 1], points);
+            line_restrictions.extend(restriction);
+        }
+        let proof = GKRProof { outputs: values[0].clone(), layer_proofs, wide_layer_proofs, line_restrictions };
+        (proof, wiring_points, reduced)
+    }
+
+    /// 同じ回路の N 個の入力をまとめて証明する（データ並列 GKR）
+    ///
+    /// 各層の値にコピーの添字（⌈log2 N⌉ 変数）を足した V(b, x) を 1 本の GKR で還元するので，
+    /// 層ごとの sum-check は N 個分で 1 回になり，配線述語は全てのコピーで共通に使う。
+    pub fn prove_batch(circuit: &Circuit, inputs: &[Vec<F>]) -> BatchGKRProof<F> {
+        assert!(!inputs.is_empty(), "a batch needs at least one instance");
+        let copies = Copies::new(inputs.len());
+        let m = copies.vars;
+        let values: Vec<Vec<Vec<F>>> = inputs.iter().map(|x| circuit.evaluate(x)).collect();
+        let outputs: Vec<Vec<F>> = values.iter().map(|v| v[0].clone()).collect();
+        let l = wiring::circuit_num_vars(circuit);
+        let mut transcript = batch_transcript(circuit, inputs, &outputs);
+
+        let mut reduced = CombinedClaim::at_point(output_point(&mut transcript, &outputs.concat(), m + l));
+        let mut layer_proofs = Vec::with_capacity(circuit.depth());
+        let mut wide_layer_proofs = Vec::new();
+        for (i, layer) in wiring::circuit_wiring::<F>(circuit).iter().enumerate() {
+            transcript.begin_layer(i);
+            let below: Vec<&[F]> = values.iter().map(|v| v[i + 1].as_slice()).collect();
+            let w = batched_extended_values(&below, l, m);
+            let k = layer.fan_in();
+            let f1 = block_diagonal(&product_form(layer, k), k, l + 1, m);
+            let weights = reduced.batched_weights(copies);
+            let points = if k == 2 {
+                let pre = LinearGKRProver::precompute(&f1, &w, &w);
+                let (proof, u, v) = LinearGKRProver::prove_weighted(&pre, &weights, &mut transcript);
+                layer_proofs.push(proof);
+                vec![u, v]
+            } else {
+                let (proof, points) = FanInProver::prove_weighted(&f1, &vec![&w; k], &weights, &mut transcript);
+                wide_layer_proofs.push(proof);
+                points
+            };
+            // W 側の点 (b, u_0, x) から V 側の点 (b, x) へ
+            let points = points.iter().map(|p| p[..m].iter().chain(p[m + 1..].iter()).copied().collect()).collect();
+            reduced = CombinedClaim::reduce_many(&mut transcript, points);
+        }
+        BatchGKRProof { outputs, layer_proofs, wide_layer_proofs }
+    }
+}
+
+/// 繰り返しの主張（回路，入力，出力，回数）を吸収した transcript
+fn repeated_transcript<F: PrimeField>(statement: &Statement<F>, repetitions: usize) -> Transcript {
+    let mut transcript = Transcript::for_statement(REPEATED_LABEL, statement);
+    transcript.append_message(b"repetitions", &(repetitions as u64).to_le_bytes());
+    transcript
+}
+
+/// バッチの主張（回路，全ての入力と出力，コピーの数）を吸収した transcript
+fn batch_transcript<F: PrimeField>(circuit: &Circuit, inputs: &[Vec<F>], outputs: &[Vec<F>]) -> Transcript {
+    let statement = Statement::new(circuit, inputs.concat(), outputs.concat());
+    let mut transcript = Transcript::for_statement(BATCH_LABEL, &statement);
+    transcript.append_message(b"num_copies", &(inputs.len() as u64).to_le_bytes());
+    transcript
+}
+
+/// 因子数 2 の層と 3 以上の層の証明の数が，層ごとの因子数 `fan_ins` に合っているか
+fn check_layer_proof_counts(fan_ins: &[usize], num_layer_proofs: usize, num_wide: usize) -> Result<(), Error> {
+    let expected_wide = fan_ins.iter().filter(|&&k| k > 2).count();
+    if num_layer_proofs != fan_ins.len() - expected_wide {
+        return Err(Error::LengthMismatch {
+            what: "layer proofs",
+            expected: fan_ins.len() - expected_wide,
+            found: num_layer_proofs,
+        });
+    }
+    if num_wide != expected_wide {
+        return Err(Error::LengthMismatch { what: "wide layer proofs", expected: expected_wide, found: num_wide });
+    }
+    Ok(())
+}
+
+/// 層状回路全体の GKR Verifier
+pub struct GKRVerifier<F: PrimeField = ScalarField>(PhantomData<F>);
+
+impl<F: PrimeField> GKRVerifier<F> {
+    /// 入力と回路から，`proof.outputs` が正しい出力であることを検証する
+    ///
+    /// 各層を `verify_layer` で還元し，最後に結合した主張を入力の多重線形拡張で直接評価する。
+    pub fn verify_circuit(circuit: &Circuit, inputs: &[F], proof: &GKRProof<F>) -> Result<(), Error> {
+        Self::verify_circuit_with(circuit, inputs, proof, ClaimReduction::default())
+    }
+
+    /// 証明を作ったときと同じ還元方法を指定して検証する
+    pub fn verify_circuit_with(
+        circuit: &Circuit,
+        inputs: &[F],
+        proof: &GKRProof<F>,
+        reduction: ClaimReduction,
+    ) -> Result<(), Error> {
+        circuit.validate()?;
+        if inputs.len() != circuit.num_inputs {
+            return Err(Error::LengthMismatch { what: "inputs", expected: circuit.num_inputs, found: inputs.len() });
+        }
+        let statement = Statement::new(circuit, inputs.to_vec(), proof.outputs.clone());
+        let mut transcript = Transcript::for_statement(CIRCUIT_LABEL, &statement);
+        let (reduced, claim) = Self::verify_circuit_layers(circuit, proof, reduction, &mut transcript)?;
+        // 入力層の主張は入力から直接確かめる
+        if reduced.evaluate(inputs) != claim {
+            return Err(Error::EvaluationMismatch("input layer at the final claim"));
+        }
+        Ok(())
+    }
+
+    /// `GKRProver::prove_repeated` の証明を検証する
+    ///
+    /// 回数は証明ではなく `config` から決め，全ての回が同じ出力を主張して受理されることを確かめる。
+    pub fn verify_repeated(
+        circuit: &Circuit,
+        inputs: &[F],
+        proof: &RepeatedGKRProof<F>,
+        config: &SecurityConfig,
+    ) -> Result<(), Error> {
+        circuit.validate()?;
+        if inputs.len() != circuit.num_inputs {
+            return Err(Error::LengthMismatch { what: "inputs", expected: circuit.num_inputs, found: inputs.len() });
+        }
+        let k = config.repetitions::<F>(circuit)?;
+        if proof.repetitions.len() != k {
+            return Err(Error::LengthMismatch { what: "repetitions", expected: k, found: proof.repetitions.len() });
+        }
+        let outputs = proof.outputs();
+        if proof.repetitions.iter().any(|p| p.outputs != outputs) {
+            return Err(Error::MalformedProof("repetitions claim different outputs"));
+        }
+        let statement = Statement::new(circuit, inputs.to_vec(), outputs.to_vec());
+        let mut transcript = repeated_transcript(&statement, k);
+        for (i, repetition) in proof.repetitions.iter().enumerate() {
+            transcript.append_message(b"repetition", &(i as u64).to_le_bytes());
+            let (reduced, claim) =
+                Self::verify_circuit_layers(circuit, repetition, ClaimReduction::default(), &mut transcript)?;
+            if reduced.evaluate(inputs) != claim {
+                return Err(Error::EvaluationMismatch("input layer at the final claim"));
+            }
+        }
+        Ok(())
+    }
+
+    /// `GKRProver::prove_committed` の証明を，入力の代わりにそのコミットメントから検証する
+    ///
+    /// 最後の結合した主張の各点での入力層の値を開示の検証で確かめるので，
+    /// 検証者の手間は回路の配線の評価と PCS の検証だけで，入力の長さに比例する処理は無い。
+    pub fn verify_committed<P: MultilinearPCS<F>>(
+        vp: &P::VerifierParam,
+        circuit: &Circuit,
+        commitment: &P::Commitment,
+        proof: &CommittedGKRProof<F, P::Proof>,
+        reduction: ClaimReduction,
+    ) -> Result<(), Error> {
+        circuit.validate()?;
+        let statement = Statement::new(circuit, Vec::new(), proof.proof.outputs.clone())
+            .with_commitment(P::commitment_bytes(commitment));
+        let mut transcript = Transcript::for_statement(CIRCUIT_LABEL, &statement);
+        let (reduced, claim) = Self::verify_circuit_layers(circuit, &proof.proof, reduction, &mut transcript)?;
+        let num_points = reduced.points.len();
+        if proof.input_evals.len() != num_points {
+            return Err(Error::LengthMismatch {
+                what: "input evaluations",
+                expected: num_points,
+                found: proof.input_evals.len(),
+            });
+        }
+        if proof.openings.len() != num_points {
+            return Err(Error::LengthMismatch { what: "openings", expected: num_points, found: proof.openings.len() });
+        }
+        if reduced.combine(&proof.input_evals) != claim {
+            return Err(Error::EvaluationMismatch("input layer at the final claim"));
+        }
+        for ((point, value), opening) in reduced.points.iter().zip(proof.input_evals.iter()).zip(proof.openings.iter()) {
+            P::verify(vp, commitment, point, *value, opening)?;
+        }
+        Ok(())
+    }
+
+    /// 回路の配線を直接評価しながら `verify_layers` で入力層まで還元する
+    pub(crate) fn verify_circuit_layers(
+        circuit: &Circuit,
+        proof: &GKRProof<F>,
+        reduction: ClaimReduction,
+        transcript: &mut Transcript,
+    ) -> Result<(CombinedClaim<F>, F), Error> {
+        let layers = wiring::circuit_wiring::<F>(circuit);
+        let l = wiring::circuit_num_vars(circuit);
+        let fan_ins: Vec<usize> = layers.iter().map(LayerWiring::fan_in).collect();
+        let num_outputs = circuit.layers[0].gates.len();
+        Self::verify_layers(l, num_outputs, &fan_ins, proof, reduction, transcript, |i, reduced, points, f1_claim| {
+            let f1_at_point = wiring_evaluation(&layers[i], fan_ins[i], reduced, points, Copies::SINGLE);
+            if f1_at_point != f1_claim {
+                return Err(Error::EvaluationMismatch("wiring evaluation"));
+            }
+            Ok(())
+        })
+    }
+
+    /// 証明の形を確かめて出力層から入力層まで還元し，入力層についての最後の結合した主張とその値を返す
+    ///
+    /// 層 i のサブクレームの f1 の値は `check_wiring(i, 上の層の結合した主張, W 側の点, f1 の値)` で確かめる。
+    pub(crate) fn verify_layers(
+        num_vars: usize,
+        num_outputs: usize,
+        fan_ins: &[usize],
+        proof: &GKRProof<F>,
+        reduction: ClaimReduction,
+        transcript: &mut Transcript,
+        mut check_wiring: impl FnMut(usize, &CombinedClaim<F>, &[Vec<F>], F) -> Result<(), Error>,
+    ) -> Result<(CombinedClaim<F>, F), Error> {
+        if proof.outputs.len() != num_outputs {
+            return Err(Error::LengthMismatch { what: "outputs", expected: num_outputs, found: proof.outputs.len() });
+        }
+        check_layer_proof_counts(fan_ins, proof.layer_proofs.len(), proof.wide_layer_proofs.len())?;
+        let num_restrictions = match reduction {
+            ClaimReduction::RandomCombination => 0,
+            ClaimReduction::LineRestriction => fan_ins.len(),
+        };
+        if proof.line_restrictions.len() != num_restrictions {
+            return Err(Error::LengthMismatch {
+                what: "line restrictions",
+                expected: num_restrictions,
+                found: proof.line_restrictions.len(),
+            });
+        }
+        let (l, n) = (num_vars, num_vars + 1);
+        transcript.append_message(b"claim_reduction", &[reduction.tag()]);
+
+        let mut reduced = CombinedClaim::at_point(output_point(transcript, &proof.outputs, l));
+        let mut claim = reduced.evaluate(&proof.outputs);
+        let (mut layer_proofs, mut wide_layer_proofs) = (proof.layer_proofs.iter(), proof.wide_layer_proofs.iter());
+        for (i, &k) in fan_ins.iter().enumerate() {
+            transcript.begin_layer(i);
+            let (points, evals, f1_claim) = if k == 2 {
+                let subclaim = LinearGKRVerifier::verify(n, claim, layer_proofs.next().unwrap(), transcript)?;
+                (vec![subclaim.u, subclaim.v], vec![subclaim.f2_at_u, subclaim.f3_at_v], subclaim.f1_at_guv)
+            } else {
+                let subclaim = FanInVerifier::verify(n, k, claim, wide_layer_proofs.next().unwrap(), transcript)?;
+                (subclaim.points, subclaim.evals, subclaim.f1_at_point)
+            };
+            check_wiring(i, &reduced, &points, f1_claim)?;
+            let claims = points.iter().zip(evals).map(|(p, e)| unembed(p, e, Copies::SINGLE)).collect::<Result<_, _>>()?;
+            let restriction = proof.line_restrictions.get(i).map(Vec::as_slice);
+            (reduced, claim) = reduction.verify(transcript, claims, restriction)?;
+        }
+        Ok((reduced, claim))
+    }
+
+    /// `GKRProver::prove_batch` の証明を検証する
+    ///
+    /// 配線は全てのコピーで共通なので，層ごとの配線の評価は N によらず主張の点ごとに 1 回で済む。
+    pub fn verify_batch(circuit: &Circuit, inputs: &[Vec<F>], proof: &BatchGKRProof<F>) -> Result<(), Error> {
+        circuit.validate()?;
+        if inputs.is_empty() {
+            return Err(Error::MalformedProof("batch has no instances"));
+        }
+        if let Some(x) = inputs.iter().find(|x| x.len() != circuit.num_inputs) {
+            return Err(Error::LengthMismatch { what: "inputs", expected: circuit.num_inputs, found: x.len() });
+        }
+        if proof.outputs.len() != inputs.len() {
+            return Err(Error::LengthMismatch { what: "batch outputs", expected: inputs.len(), found: proof.outputs.len() });
+        }
+        let num_outputs = circuit.layers[0].gates.len();
+        if let Some(y) = proof.outputs.iter().find(|y| y.len() != num_outputs) {
+            return Err(Error::LengthMismatch { what: "outputs", expected: num_outputs, found: y.len() });
+        }
+        let fan_ins: Vec<usize> = (0..circuit.depth()).map(|i| circuit.fan_in(i)).collect();
+        check_layer_proof_counts(&fan_ins, proof.layer_proofs.len(), proof.wide_layer_proofs.len())?;
+        let copies = Copies::new(inputs.len());
+        let m = copies.vars;
+        let l = wiring::circuit_num_vars(circuit);
+        let mut transcript = batch_transcript(circuit, inputs, &proof.outputs);
+
+        let mut reduced = CombinedClaim::at_point(output_point(&mut transcript, &proof.outputs.concat(), m + l));
+        let outputs: Vec<&[F]> = proof.outputs.iter().map(Vec::as_slice).collect();
+        let mut claim = reduced.evaluate(&batched_values(&outputs, l, m));
+        let (mut layer_proofs, mut wide_layer_proofs) = (proof.layer_proofs.iter(), proof.wide_layer_proofs.iter());
+        for (i, layer) in wiring::circuit_wiring::<F>(circuit).iter().enumerate() {
+            transcript.begin_layer(i);
+            let n = m + l + 1;
+            let claims = if layer.fan_in() == 2 {
+                let subclaim = LinearGKRVerifier::verify(n, claim, layer_proofs.next().unwrap(), &mut transcript)?;
+                let (points, evals) = (vec![subclaim.u, subclaim.v], vec![subclaim.f2_at_u, subclaim.f3_at_v]);
+                layer_claims(layer, 2, &reduced, subclaim.f1_at_guv, points, evals, copies)?
+            } else {
+                let k = layer.fan_in();
+                let subclaim = FanInVerifier::verify(n, k, claim, wide_layer_proofs.next().unwrap(), &mut transcript)?;
+                layer_claims(layer, k, &reduced, subclaim.f1_at_point, subclaim.points, subclaim.evals, copies)?
+            };
+            (reduced, claim) = ClaimReduction::RandomCombination.verify(&mut transcript, claims, None)?;
+        }
+        // 入力層の主張は全てのコピーの入力から直接確かめる
+        let inputs: Vec<&[F]> = inputs.iter().map(Vec::as_slice).collect();
+        if reduced.evaluate(&batched_values(&inputs, l, m)) != claim {
+            return Err(Error::EvaluationMismatch("input layer at the final claim"));
+        }
+        Ok(())
+    }
+
+    /// 1 層分の還元：上の層の結合した主張 `claim` を，この層の値 V についての次の結合した主張に変える
+    ///
+    /// LinearGKR のサブクレームの f1 の値は配線から直接計算して照合し，
+    /// W(u), W(v) の主張を V(u'), V(v') に直してから `reduction` で 1 つにまとめる。
+    /// `restriction` は `ClaimReduction::LineRestriction` のときの直線への制限。
+    /// 回路の証明と同じ transcript をたどるには，先に `transcript.begin_layer(i)` で層の添字を吸収しておくこと。
+    pub fn verify_layer(
+        layer: &LayerWiring<F>,
+        reduced: &CombinedClaim<F>,
+        claim: F,
+        proof: &LinearGKRProof<F>,
+        reduction: ClaimReduction,
+        restriction: Option<&[F]>,
+        transcript: &mut Transcript,
+    ) -> Result<(CombinedClaim<F>, F), Error> {
+        let n = layer.num_vars + 1;
+        let subclaim = LinearGKRVerifier::verify(n, claim, proof, transcript)?;
+        let evals = vec![subclaim.f2_at_u, subclaim.f3_at_v];
+        let points = vec![subclaim.u, subclaim.v];
+        let claims = layer_claims(layer, 2, reduced, subclaim.f1_at_guv, points, evals, Copies::SINGLE)?;
+        reduction.verify(transcript, claims, restriction)
+    }
+
+    /// 因子数 k が 3 以上の層の還元（`verify_layer` の k フェーズ版）
+    pub fn verify_wide_layer(
+        layer: &LayerWiring<F>,
+        reduced: &CombinedClaim<F>,
+        claim: F,
+        proof: &FanInProof<F>,
+        reduction: ClaimReduction,
+        restriction: Option<&[F]>,
+        transcript: &mut Transcript,
+    ) -> Result<(CombinedClaim<F>, F), Error> {
+        let (n, k) = (layer.num_vars + 1, layer.fan_in());
+        let subclaim = FanInVerifier::verify(n, k, claim, proof, transcript)?;
+        let claims =
+            layer_claims(layer, k, reduced, subclaim.f1_at_point, subclaim.points, subclaim.evals, Copies::SINGLE)?;
+        reduction.verify(transcript, claims, restriction)
+    }
+}
+
+/// サブクレームの f1 の値を配線から直接計算して照合し，W の k 点での主張を V の主張 (u_j', V(u_j')) に直す
+fn layer_claims<F: PrimeField>(
+    layer: &LayerWiring<F>,
+    fan_in: usize,
+    reduced: &CombinedClaim<F>,
+    f1_claim: F,
+    points: Vec<Vec<F>>,
+    w_evals: Vec<F>,
+    copies: Copies,
+) -> Result<Vec<(Vec<F>, F)>, Error> {
+    if wiring_evaluation(layer, fan_in, reduced, &points, copies) != f1_claim {
+        return Err(Error::EvaluationMismatch("wiring evaluation"));
+    }
+    points.iter().zip(w_evals).map(|(p, e)| unembed(p, e, copies)).collect()
+}
+
+/// 結合した主張の重みでの配線述語の値 Σ_j c_j·f1((b_j, 0, q_j), points...)
+///
+/// コピーの添字を持つときも共通の配線を主張の点ごとに 1 回走査するだけで，
+/// ブロック対角の b の部分は `eq_eval_many` で O(m) で求める。
+fn wiring_evaluation<F: PrimeField>(
+    layer: &LayerWiring<F>,
+    fan_in: usize,
+    reduced: &CombinedClaim<F>,
+    points: &[Vec<F>],
+    copies: Copies,
+) -> F {
+    let n = layer.num_vars + 1;
+    let mask = (1 << n) - 1;
+    let m = copies.vars;
+    let eqs: Vec<Vec<F>> = points.iter().map(|p| eq_table(&p[m..])).collect();
+    let f1 = product_form(layer, fan_in);
+    reduced
+        .points
+        .iter()
+        .zip(reduced.coeffs.iter())
+        .map(|(q, c)| {
+            let (qb, qz) = q.split_at(m);
+            let copy_parts: Vec<&[F]> = std::iter::once(qb).chain(points.iter().map(|p| &p[..m])).collect();
+            let eq_z = eq_table(&embed(qz));
+            let wiring: F = f1
+                .evaluations
+                .iter()
+                .map(|(&index, &val)| {
+                    let factors: F = eqs.iter().enumerate().map(|(j, eq)| eq[(index >> (j * n)) & mask]).product();
+                    val * eq_z[index >> (fan_in * n)] * factors
+                })
+                .sum();
+            *c * eq_eval_many(&copy_parts) * wiring
+        })
+        .sum()
+}
@@ -0,0 +1,152 @@
+// src/cli.rs
+//
+// `gkr` コマンド（src/bin/gkr.rs）の本体。体は BLS12-381 のスカラー体に固定する。
+//
+//   gkr prove  --circuit c.bin --witness w.json --out proof.bin
+//   gkr verify --circuit c.bin --witness w.json --proof proof.bin
+//
+// 回路は `Circuit::serialize` のバイナリ（拡張子 .json なら `Circuit::to_json` の JSON），
+// 入力は `witness::load_witness` が読める形式，証明は `GKRProof` の正準形式（圧縮）。
+// どちらのコマンドも出力層の値を 10 進文字列の JSON 配列として返す。
+
+use ark_bls12_381::Fr as ScalarField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use std::fmt;
+use std::path::Path;
+
+use crate::circuit::Circuit;
+use crate::circuit_prover::{GKRProof, GKRProver, GKRVerifier};
+use crate::error::Error;
+use crate::serialization::SerializationError;
+use crate::witness::{load_witness, EncodingRules, WitnessError};
+
+pub const USAGE: &str = "usage:
+  gkr prove  --circuit <c.bin|c.json> --witness <w.json|w.csv|w.bin> --out <proof.bin>
+  gkr verify --circuit <c.bin|c.json> --witness <w.json|w.csv|w.bin> --proof <proof.bin>";
+
+/// コマンドの失敗
+#[derive(Debug)]
+pub enum CliError {
+    Usage(String),
+    Io(std::io::Error),
+    Witness(WitnessError),
+    Serialization(SerializationError),
+    /// 入力の数が回路と合わない
+    InputCount { expected: usize, found: usize },
+    /// 証明を検証できなかった
+    Rejected(Error),
+}
+
+impl CliError {
+    /// プロセスの終了コード（使い方の誤りは 2，それ以外は 1）
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Usage(_) => 2,
+            _ => 1,
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Usage(reason) => write!(f, "{}\n{}", reason, USAGE),
+            CliError::Io(e) => write!(f, "io error: {}", e),
+            CliError::Witness(e) => write!(f, "cannot read witness: {}", e),
+            CliError::Serialization(e) => write!(f, "cannot decode: {}", e),
+            CliError::InputCount { expected, found } => {
+                write!(f, "circuit takes {} inputs but the witness has {}", expected, found)
+            }
+            CliError::Rejected(e) => write!(f, "proof rejected: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<std::io::Error> for CliError {
+    fn from(e: std::io::Error) -> Self {
+        CliError::Io(e)
+    }
+}
+
+impl From<WitnessError> for CliError {
+    fn from(e: WitnessError) -> Self {
+        CliError::Witness(e)
+    }
+}
+
+impl From<SerializationError> for CliError {
+    fn from(e: SerializationError) -> Self {
+        CliError::Serialization(e)
+    }
+}
+
+impl From<ark_serialize::SerializationError> for CliError {
+    fn from(e: ark_serialize::SerializationError) -> Self {
+        CliError::Serialization(e.into())
+    }
+}
+
+/// 引数（プログラム名を除く）を解釈して実行し，標準出力に書く内容を返す
+pub fn run(args: &[String]) -> Result<String, CliError> {
+    let (command, rest) = args.split_first().ok_or_else(|| CliError::Usage("missing command".to_string()))?;
+    match command.as_str() {
+        "prove" => {
+            let [circuit, witness, out] = options(rest, ["--circuit", "--witness", "--out"])?;
+            let (circuit, inputs) = load(&circuit, &witness)?;
+            let proof = GKRProver::prove_circuit(&circuit, &inputs);
+            let mut bytes = Vec::new();
+            proof.serialize_compressed(&mut bytes)?;
+            std::fs::write(out, bytes)?;
+            Ok(outputs_json(&proof))
+        }
+        "verify" => {
+            let [circuit, witness, proof] = options(rest, ["--circuit", "--witness", "--proof"])?;
+            let (circuit, inputs) = load(&circuit, &witness)?;
+            let proof = GKRProof::<ScalarField>::deserialize_compressed(&std::fs::read(proof)?[..])?;
+            GKRVerifier::verify_circuit(&circuit, &inputs, &proof).map_err(CliError::Rejected)?;
+            Ok(outputs_json(&proof))
+        }
+        "help" | "--help" | "-h" => Ok(USAGE.to_string()),
+        other => Err(CliError::Usage(format!("unknown command {:?}", other))),
+    }
+}
+
+/// `--name value` の組を `names` の順に取り出す（全て必須，重複と未知の名前は拒否する）
+fn options<const N: usize>(args: &[String], names: [&str; N]) -> Result<[String; N], CliError> {
+    let mut values: [Option<String>; N] = std::array::from_fn(|_| None);
+    let mut args = args.iter();
+    while let Some(name) = args.next() {
+        let i = names
+            .iter()
+            .position(|n| n == name)
+            .ok_or_else(|| CliError::Usage(format!("unknown option {:?}", name)))?;
+        let value = args.next().ok_or_else(|| CliError::Usage(format!("{} needs a value", name)))?;
+        if values[i].replace(value.clone()).is_some() {
+            return Err(CliError::Usage(format!("{} is given twice", name)));
+        }
+    }
+    if let Some((name, _)) = names.iter().zip(&values).find(|(_, v)| v.is_none()) {
+        return Err(CliError::Usage(format!("missing {}", name)));
+    }
+    Ok(values.map(Option::unwrap))
+}
+
+fn load(circuit: &str, witness: &str) -> Result<(Circuit, Vec<ScalarField>), CliError> {
+    let circuit = if Path::new(circuit).extension().is_some_and(|ext| ext == "json") {
+        Circuit::from_json(&std::fs::read_to_string(circuit)?)?
+    } else {
+        Circuit::deserialize(&std::fs::read(circuit)?)?
+    };
+    let inputs = load_witness::<ScalarField, _>(witness, &EncodingRules::default())?.values;
+    if inputs.len() != circuit.num_inputs {
+        return Err(CliError::InputCount { expected: circuit.num_inputs, found: inputs.len() });
+    }
+    Ok((circuit, inputs))
+}
+
+fn outputs_json(proof: &GKRProof<ScalarField>) -> String {
+    let outputs: Vec<String> = proof.outputs.iter().map(|x| x.to_string()).collect();
+    serde_json::to_string(&outputs).expect("strings always serialize")
+}
@@ -0,0 +1,110 @@
+// src/corrupt.rs
+//
+// 健全性の回帰テスト向けに，シリアライズ済みの証明を改ざんする補助関数群。
+// いずれも入力を復号し，改ざん後に元と同じ WireConfig で再エンコードする
+// （`flip_byte` のみ生のバイト列を直接書き換える）。
+
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::One;
+
+use crate::prover::LinearGKRProof;
+
+/// 改ざん対象のフェーズ
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    One,
+    Two,
+}
+
+fn messages_mut(proof: &mut LinearGKRProof, phase: Phase) -> &mut Vec<Vec<ScalarField>> {
+    match phase {
+        Phase::One => &mut proof.phase1_msgs,
+        Phase::Two => &mut proof.phase2_msgs,
+    }
+}
+
+fn rewrite(bytes: &[u8], f: impl FnOnce(&mut LinearGKRProof)) -> Vec<u8> {
+    let (mut proof, config) = LinearGKRProof::<ScalarField>::from_bytes(bytes).expect("input must be a valid proof");
+    f(&mut proof);
+    proof.to_bytes(&config)
+}
+
+/// 証明中の体の元の総数（phase1, phase2 の順に平坦化したときの長さ）
+pub fn num_elements(bytes: &[u8]) -> usize {
+    let (proof, _) = LinearGKRProof::<ScalarField>::from_bytes(bytes).expect("input must be a valid proof");
+    proof.phase1_msgs.iter().chain(proof.phase2_msgs.iter()).map(Vec::len).sum()
+}
+
+/// 平坦化した順で `offset` 番目の体の元に 1 を加える（正規形のまま値だけが変わる）
+pub fn flip_element(bytes: &[u8], offset: usize) -> Vec<u8> {
+    rewrite(bytes, |proof| {
+        let elem = proof
+            .phase1_msgs
+            .iter_mut()
+            .chain(proof.phase2_msgs.iter_mut())
+            .flat_map(|msg| msg.iter_mut())
+            .nth(offset)
+            .expect("offset out of range");
+        *elem += ScalarField::one();
+    })
+}
+
+/// 生のバイト列の `byte_offset` バイト目を反転する（ヘッダや正規形検査の回帰テスト用）
+pub fn flip_byte(bytes: &[u8], byte_offset: usize) -> Vec<u8> {
+    let mut out = bytes.to_vec();
+    out[byte_offset] ^= 0xff;
+    out
+}
+
+/// 指定フェーズのメッセージを先頭 `keep` 個に切り詰める
+pub fn truncate_messages(bytes: &[u8], phase: Phase, keep: usize) -> Vec<u8> {
+    rewrite(bytes, |proof| messages_mut(proof, phase).truncate(keep))
+}
+
+/// 指定フェーズの `index` 番目のメッセージから末尾の係数を取り除く
+pub fn truncate_message(bytes: &[u8], phase: Phase, index: usize) -> Vec<u8> {
+    rewrite(bytes, |proof| {
+        messages_mut(proof, phase)[index].pop();
+    })
+}
+
+/// phase1 と phase2 のメッセージ列を入れ替える
+pub fn swap_phases(bytes: &[u8]) -> Vec<u8> {
+    rewrite(bytes, |proof| std::mem::swap(&mut proof.phase1_msgs, &mut proof.phase2_msgs))
+}
+
+/// 主張された総和を改ざんする（現状の証明は総和を含まないため，検証器へ渡す値を変える）
+pub fn alter_claimed_sum(claimed_sum: ScalarField) -> ScalarField {
+    claimed_sum + ScalarField::one()
+}
+
+/// 改ざん済みのバイト列が拒否されるか。復号に失敗した場合も拒否とみなす
+pub fn is_rejected<T, E>(
+    bytes: &[u8],
+    verify: impl FnOnce(&LinearGKRProof) -> Result<T, E>,
+) -> bool {
+    match LinearGKRProof::<ScalarField>::from_bytes(bytes) {
+        Ok((proof, _)) => verify(&proof).is_err(),
+        Err(_) => true,
+    }
+}
+
+/// 改ざん済みの証明バイト列が拒否されることを表明する
+///
+/// ```ignore
+/// let bad = corrupt::truncate_messages(&bytes, Phase::One, 0);
+/// assert_rejects!(bad, |proof| LinearGKRVerifier::verify(l, claimed_sum, proof, &mut transcript.clone()));
+/// ```
+#[macro_export]
+macro_rules! assert_rejects {
+    ($bytes:expr, $verify:expr) => {
+        assert!(
+            $crate::corrupt::is_rejected(&$bytes, $verify),
+            "corrupted proof was accepted: {}",
+            stringify!($bytes)
+        )
+    };
+    ($bytes:expr, $verify:expr, $($arg:tt)+) => {
+        assert!($crate::corrupt::is_rejected(&$bytes, $verify), $($arg)+)
+    };
+}
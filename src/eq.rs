@@ -0,0 +1,75 @@
+// src/eq.rs
+//
+// eq(r, x) = Π_i (r_i x_i + (1 - r_i)(1 - x_i)) に関する補助関数。
+// 表の添字は先頭の変数を最上位ビットとする（`IndexOrder::BigEndian`）。
+
+use ark_ff::Field;
+
+/// eq(r, b) を全ての b ∈ {0,1}^n について並べた表（r[0] が添字の最上位ビット）
+///
+/// 変数を 1 つずつ `extend_eq_table` で足していくので，乗算は合計 2^n 回程度で済む。
+pub fn eq_table<F: Field>(r: &[F]) -> Vec<F> {
+    let mut table = Vec::with_capacity(1 << r.len());
+    table.push(F::one());
+    for ri in r {
+        extend_eq_table(&mut table, *ri);
+    }
+    table
+}
+
+/// eq(r, ·) の表に変数 r_new を末尾（添字の最下位ビット）として加え，eq((r, r_new), ·) の表にする
+///
+/// 各要素 e を (e·(1 - r_new), e·r_new) の組に置き換える。乗算は要素ごとに 1 回。
+pub fn extend_eq_table<F: Field>(table: &mut Vec<F>, r_new: F) {
+    let n = table.len();
+    table.resize(2 * n, F::zero());
+    // 後ろから埋めれば，まだ読んでいない要素を上書きしない
+    for i in (0..n).rev() {
+        let e = table[i];
+        let hi = e * r_new;
+        table[2 * i + 1] = hi;
+        table[2 * i] = e - hi;
+    }
+}
+
+/// eq(a, b) = Π (a_i b_i + (1 - a_i)(1 - b_i))
+pub fn eq_eval<F: Field>(a: &[F], b: &[F]) -> F {
+    assert_eq!(a.len(), b.len(), "eq of points of different lengths");
+    a.iter().zip(b.iter()).map(|(a, b)| *a * b + (F::one() - a) * (F::one() - b)).product()
+}
+
+/// 複数の点のビットが全て一致するときの多重線形な指示関数 Π_i (Π_j p_j[i] + Π_j (1 - p_j[i]))
+///
+/// 2 点なら `eq_eval` と同じ。Σ_b eq(p_1, b)···eq(p_k, b) に等しい。
+pub fn eq_eval_many<F: Field>(points: &[&[F]]) -> F {
+    let n = points.first().map_or(0, |p| p.len());
+    assert!(points.iter().all(|p| p.len() == n), "eq of points of different lengths");
+    (0..n)
+        .map(|i| {
+            let ones: F = points.iter().map(|p| p[i]).product();
+            let zeros: F = points.iter().map(|p| F::one() - p[i]).product();
+            ones + zeros
+        })
+        .product()
+}
+
+/// 添字が count 未満のブール点 b についての Σ_b eq(r, b)（r[0] が添字の最上位ビット，O(n)）
+pub fn eq_prefix_sum<F: Field>(r: &[F], count: usize) -> F {
+    let n = r.len();
+    assert!(count <= 1 << n, "count exceeds the hypercube");
+    if count == 1 << n {
+        return F::one();
+    }
+    // count の上位ビットから，そこまで一致して次のビットで下回る b の和を足していく
+    let mut sum = F::zero();
+    let mut prefix = F::one();
+    for (i, ri) in r.iter().enumerate() {
+        if (count >> (n - 1 - i)) & 1 == 1 {
+            sum += prefix * (F::one() - ri);
+            prefix *= ri;
+        } else {
+            prefix *= F::one() - ri;
+        }
+    }
+    sum
+}
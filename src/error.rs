@@ -0,0 +1,97 @@
+// src/error.rs
+//
+// クレート全体で共通の検証エラー。
+// 呼び出し側が失敗の原因（証明の形，要素数，sum-check のラウンド，評価値の不一致，transcript）を
+// 区別できるように，文字列ではなく列挙型で返す。
+
+use std::fmt;
+
+use crate::sumcheck::SumcheckError;
+
+/// sum-check の 1 ラウンドの失敗理由
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundError {
+    /// 変数の数より多くのメッセージが届いた
+    TooManyRounds,
+    /// メッセージの評価値の個数が 2 未満
+    InvalidLength(usize),
+    /// メッセージが表す多項式の次数が，多項式の構造から決まる上限を超える
+    DegreeBoundExceeded { degree: usize, bound: usize },
+    /// g_i(0) + g_i(1) が現在の主張と一致しない
+    SumMismatch,
+}
+
+impl fmt::Display for RoundError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RoundError::TooManyRounds => write!(f, "too many rounds"),
+            RoundError::InvalidLength(len) => write!(f, "message has an invalid length {}", len),
+            RoundError::DegreeBoundExceeded { degree, bound } => {
+                write!(f, "message has degree {} but the bound is {}", degree, bound)
+            }
+            RoundError::SumMismatch => write!(f, "g(0) + g(1) does not match the claim"),
+        }
+    }
+}
+
+/// 証明の生成・検証の失敗理由
+///
+/// 証明のバイト列の復号の失敗は `serialization::SerializationError` で別に返す。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// 証明の構造が不正（要素数以外の理由）
+    MalformedProof(&'static str),
+    /// 要素数の不一致（`what` は数えた対象）
+    LengthMismatch { what: &'static str, expected: usize, found: usize },
+    /// sum-check の `round` 番目（0 始まり）のラウンドの検査に失敗した
+    Round { round: usize, kind: RoundError },
+    /// 最後の主張が，実際の評価値（オラクル，配線，公開された値）と一致しない
+    EvaluationMismatch(&'static str),
+    /// transcript から引いたチャレンジでは続行できない
+    Transcript(&'static str),
+    /// 回路の構造が不正
+    InvalidCircuit(&'static str),
+    /// 1 回の証明の健全性（ビット）では，繰り返しても目標に届かない
+    InsufficientSoundness { bits: usize, target: usize },
+    /// lookup の witness の `index` 番目の値が表にない
+    NotInTable { index: usize },
+    /// 範囲検査の `index` 番目の値が [0, 2^bits) にない
+    OutOfRange { index: usize, bits: usize },
+    Sumcheck(SumcheckError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::MalformedProof(reason) => write!(f, "malformed proof: {}", reason),
+            Error::LengthMismatch { what, expected, found } => {
+                write!(f, "expected {} {} but found {}", expected, what, found)
+            }
+            Error::Round { round, kind } => write!(f, "sum-check round {}: {}", round, kind),
+            Error::EvaluationMismatch(what) => write!(f, "{} does not match", what),
+            Error::Transcript(reason) => write!(f, "transcript error: {}", reason),
+            Error::InvalidCircuit(reason) => write!(f, "invalid circuit: {}", reason),
+            Error::InsufficientSoundness { bits, target } => {
+                write!(f, "{} bits of soundness per run cannot reach the target of {} bits", bits, target)
+            }
+            Error::NotInTable { index } => write!(f, "witness value {} is not in the lookup table", index),
+            Error::OutOfRange { index, bits } => write!(f, "value {} is not in [0, 2^{})", index, bits),
+            Error::Sumcheck(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Sumcheck(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<SumcheckError> for Error {
+    fn from(e: SumcheckError) -> Self {
+        Error::Sumcheck(e)
+    }
+}
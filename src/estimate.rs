@@ -0,0 +1,245 @@
+// src/estimate.rs
+//
+// 証明を始める前に，時間・ピークメモリ・証明サイズを見積もるためのコストモデル。
+// 係数は `CostModel::calibrate` でその計算機上の実測値に合わせられる。
+// 各層の表の作り方は `ProverBackend` ごとにモデル化し，既定は `ProverBackend::Libra`（`GKRProver` と同じ）。
+
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::{Field, UniformRand, Zero};
+use ark_serialize::CanonicalSerialize;
+use rand::Rng;
+use std::mem::size_of;
+use std::time::{Duration, Instant};
+
+use crate::circuit::Circuit;
+use crate::circuit_prover::{product_forms, GKRProof};
+use crate::fan_in::FanInProof;
+use crate::prover::{LinearGKRProof, ProverBackend};
+use crate::serialization::WireConfig;
+use crate::wiring;
+
+/// 前計算で並べ替えた配線の 1 要素 (z, x, y, 値) のメモリ
+const WIRING_ENTRY_BYTES: usize = 3 * size_of::<usize>() + size_of::<ScalarField>();
+
+/// 見積もり対象の単層関係の形状
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RelationShape {
+    /// f2, f3 の変数数 l
+    pub num_vars: usize,
+    /// 配線述語 f1 の非零要素数
+    pub wiring_nnz: usize,
+}
+
+/// 1 演算あたりのコスト
+///
+/// 係数はバックエンドによらない。バックエンドの違いは `prover_cost_with` での演算の回数に現れる。
+#[derive(Clone, Copy, Debug)]
+pub struct CostModel {
+    pub field_mul: Duration,
+    pub field_add: Duration,
+    /// HashMap の 1 要素の挿入・参照（f1 の疎表現を読んで並べ替える前計算で，要素ごとに 2 回とみなす）
+    pub hash_op: Duration,
+    /// HashMap の 1 要素あたりのメモリ（キー・値・制御バイト込み）
+    pub hash_entry_bytes: usize,
+}
+
+impl Default for CostModel {
+    /// 一般的な x86-64 上での BLS12-381 Fr の目安
+    fn default() -> Self {
+        CostModel {
+            field_mul: Duration::from_nanos(30),
+            field_add: Duration::from_nanos(5),
+            hash_op: Duration::from_nanos(40),
+            hash_entry_bytes: size_of::<usize>() + size_of::<ScalarField>() + 1,
+        }
+    }
+}
+
+impl CostModel {
+    /// この計算機で体演算を `iterations` 回ずつ計測して係数を合わせる
+    pub fn calibrate<R: Rng>(iterations: usize, rng: &mut R) -> Self {
+        let iterations = iterations.max(1);
+        let mut acc = ScalarField::rand(rng);
+        let x = ScalarField::rand(rng);
+
+        let start = Instant::now();
+        for _ in 0..iterations {
+            acc *= x;
+        }
+        let field_mul = per_iteration(start.elapsed(), iterations);
+
+        let start = Instant::now();
+        for _ in 0..iterations {
+            acc += x;
+        }
+        let field_add = per_iteration(start.elapsed(), iterations);
+
+        let mut map = std::collections::HashMap::with_capacity(iterations);
+        let start = Instant::now();
+        for i in 0..iterations {
+            map.insert(i, acc);
+        }
+        let hash_op = per_iteration(start.elapsed(), iterations);
+        // 最適化で計測対象が消えないようにする
+        std::hint::black_box((acc.square(), map.len()));
+
+        CostModel { field_mul, field_add, hash_op, ..CostModel::default() }
+    }
+}
+
+/// 見積もり結果
+#[derive(Clone, Copy, Debug)]
+pub struct CostEstimate {
+    pub time: Duration,
+    pub peak_memory: usize,
+    pub proof_bytes: usize,
+}
+
+impl CostEstimate {
+    /// 与えられた上限に収まるか
+    pub fn fits(&self, max_time: Duration, max_memory: usize) -> bool {
+        self.time <= max_time && self.peak_memory <= max_memory
+    }
+}
+
+/// 回路全体の見積もりの設定
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EstimateConfig {
+    pub model: CostModel,
+    /// 因子数 2 の層の表の作り方。`GKRProver` は主張を重み付きの和で扱うので既定の `Libra` と同じになり，
+    /// `BooleanSlice` は各層をブール点から個別に証明する場合の目安になる
+    pub backend: ProverBackend,
+}
+
+/// `LinearGKRProver::prove` のコストを既定のバックエンド（`ProverBackend::Libra`）で見積もる
+pub fn prover_cost(shape: &RelationShape, model: &CostModel) -> CostEstimate {
+    prover_cost_with(shape, model, ProverBackend::default())
+}
+
+/// `prover_cost` と同じだが，Phase 1, 2 の表の作り方を選ぶ
+///
+/// - 前計算: f1 の疎表現を読んで並べ替える（nnz 要素）
+/// - Phase 1, 2: `Libra` は eq の表（2^l）で重み付けしながら配線全体を走査し，
+///   `BooleanSlice` は z = g の部分（平均 nnz / 2^l 要素）だけを走査する。走査は要素ごとに 2 回の積和
+/// - 各フェーズの sum-check: 総和に 2^l 回，ラウンドの評価と畳み込みに合わせて約 4·2^l 回の積和
+///
+/// ピークメモリは f1 の疎表現と並べ替えた配線，2^l 要素の密表（証拠と Phase 2 の表，`Libra` では eq(g, ·) も）とする。
+pub fn prover_cost_with(shape: &RelationShape, model: &CostModel, backend: ProverBackend) -> CostEstimate {
+    let l = shape.num_vars;
+    let table = 1usize << l;
+    let nnz = shape.wiring_nnz;
+    let mul_add = model.field_mul + model.field_add;
+
+    let precompute = times(model.hash_op, 2 * nnz);
+    let (scanned, eq_tables, dense_tables) = match backend {
+        ProverBackend::Libra => (nnz, 2 * table, 7),
+        ProverBackend::BooleanSlice => (nnz.div_ceil(table), table, 6),
+    };
+    let phases = times(mul_add, 4 * scanned + eq_tables + 2 * table);
+    let rounds = times(mul_add, 2 * 5 * table);
+
+    let elem = size_of::<ScalarField>();
+    let peak_memory = nnz * (model.hash_entry_bytes + WIRING_ENTRY_BYTES) + dense_tables * table * elem;
+
+    CostEstimate { time: precompute + phases + rounds, peak_memory, proof_bytes: proof_bytes(l) }
+}
+
+/// `GKRProver::prove_circuit` のコストを層ごとの見積もりの和で見積もる
+///
+/// 全ての層は共通の l + 1 変数（l = `circuit_num_vars`）の W の上で証明する。因子数 2 の層は
+/// `prover_cost_with` で `config.backend` を，因子数 k > 2 の層は k フェーズの sum-check を見積もる。
+/// 回路の評価はゲートごとに 1 回の積和，層の間の主張の還元は eq の表 2 つ分とする。
+///
+/// ピークメモリは全ての層の値と配線述語に，1 層の証明の作業領域の最大を足したもの。
+/// 証明サイズは同じ形の `GKRProof` を正準形式（CLI が書き出す形式）でエンコードして数える。
+pub fn circuit_cost(circuit: &Circuit, config: &EstimateConfig) -> CostEstimate {
+    let model = &config.model;
+    let n = wiring::circuit_num_vars(circuit) + 1;
+    let table = 1usize << n;
+    let mul_add = model.field_mul + model.field_add;
+    let elem = size_of::<ScalarField>();
+
+    let gates: usize = circuit.layers.iter().map(|layer| layer.gates.len()).sum();
+    let mut time = times(mul_add, gates);
+    let mut resident = (gates + circuit.num_inputs) * elem;
+    let mut work = 0;
+    let outputs = circuit.layers.first().map_or(0, |layer| layer.gates.len());
+    let mut proof = GKRProof {
+        outputs: vec![ScalarField::zero(); outputs],
+        layer_proofs: Vec::new(),
+        wide_layer_proofs: Vec::new(),
+        line_restrictions: Vec::new(),
+    };
+    for f1 in product_forms::<ScalarField>(circuit) {
+        let k = f1.num_vars / n - 1;
+        let nnz = f1.evaluations.len();
+        let layer = if k == 2 {
+            proof.layer_proofs.push(layer_proof(n));
+            prover_cost_with(&RelationShape { num_vars: n, wiring_nnz: nnz }, model, config.backend)
+        } else {
+            proof.wide_layer_proofs.push(fan_in_proof(n, k));
+            fan_in_cost(n, k, nnz, model)
+        };
+        time += layer.time + times(mul_add, 2 * table);
+        let wiring_memory = nnz * model.hash_entry_bytes;
+        resident += wiring_memory;
+        work = work.max(layer.peak_memory.saturating_sub(wiring_memory));
+    }
+
+    CostEstimate { time, peak_memory: resident + work, proof_bytes: proof.compressed_size() }
+}
+
+/// 因子数 k の層の k フェーズの sum-check（`FanInProver::prove_weighted`）
+///
+/// 各フェーズで配線全体を走査して表を作り（要素ごとに k 回の積和），2 因子の sum-check を行う。
+fn fan_in_cost(num_vars: usize, k: usize, nnz: usize, model: &CostModel) -> CostEstimate {
+    let table = 1usize << num_vars;
+    let mul_add = model.field_mul + model.field_add;
+    let precompute = times(model.hash_op, nnz);
+    let phases = times(mul_add, k * (k * nnz + 6 * table));
+
+    let elem = size_of::<ScalarField>();
+    let entry = k * size_of::<usize>() + size_of::<ScalarField>();
+    let peak_memory = nnz * (model.hash_entry_bytes + entry) + 2 * k * table * elem;
+
+    CostEstimate { time: precompute + phases, peak_memory, proof_bytes: fan_in_proof(num_vars, k).compressed_size() }
+}
+
+/// 現在のワイヤフォーマットでの証明サイズ（同じ形の証明を実際にエンコードして数える）
+pub fn proof_bytes(num_vars: usize) -> usize {
+    layer_proof(num_vars).to_bytes(&WireConfig::default()).len()
+}
+
+/// 各ラウンドが 2 次の多項式の 1, 2 での値（g(0) は省く）からなる，l 変数の層の証明の形
+fn layer_proof(num_vars: usize) -> LinearGKRProof {
+    let msg = vec![ScalarField::zero(); 2];
+    LinearGKRProof {
+        claimed_sum: ScalarField::zero(),
+        phase1_msgs: vec![msg.clone(); num_vars],
+        phase2_msgs: vec![msg; num_vars],
+        f1_at_guv: ScalarField::zero(),
+        f2_at_u: ScalarField::zero(),
+        f3_at_v: ScalarField::zero(),
+    }
+}
+
+/// 因子数 k の層の証明の形
+fn fan_in_proof(num_vars: usize, k: usize) -> FanInProof {
+    FanInProof {
+        claimed_sum: ScalarField::zero(),
+        phase_msgs: vec![vec![vec![ScalarField::zero(); 2]; num_vars]; k],
+        f1_at_point: ScalarField::zero(),
+        evals: vec![ScalarField::zero(); k],
+    }
+}
+
+/// 計測した時間を 1 回あたりに割る（回数は u32 に収まらなくてよい）
+fn per_iteration(elapsed: Duration, iterations: usize) -> Duration {
+    let nanos = elapsed.as_nanos() / iterations as u128;
+    Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX))
+}
+
+fn times(d: Duration, n: usize) -> Duration {
+    let nanos = d.as_nanos().saturating_mul(n as u128);
+    Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX))
+}
@@ -0,0 +1,117 @@
+// src/examples_circuits.rs
+//
+// テストとベンチマークで共通に使う標準的な回路。
+// どの層も幅が 2 のべきになるよう組んである。
+
+use ark_ff::Field;
+use rand::RngCore;
+
+use crate::circuit::{Circuit, Gate, Layer};
+use crate::wiring::{self, TreeOp};
+
+type InputGenerator<F> = Box<dyn Fn(&mut dyn RngCore) -> Vec<F>>;
+type ReferenceOutput<F> = Box<dyn Fn(&[F]) -> Vec<F>>;
+
+/// 回路と，その入力（witness）の生成器
+pub struct ExampleCircuit<F: Field> {
+    pub name: String,
+    pub circuit: Circuit,
+    generate: InputGenerator<F>,
+    reference: ReferenceOutput<F>,
+}
+
+impl<F: Field> ExampleCircuit<F> {
+    /// 回路の入力をランダムに生成する（定数 1 の配線などは固定値で埋める）
+    pub fn random_inputs(&self, rng: &mut dyn RngCore) -> Vec<F> {
+        (self.generate)(rng)
+    }
+
+    /// 入力から全層の値を計算する（`values[0]` が出力層）
+    pub fn witness(&self, inputs: &[F]) -> Vec<Vec<F>> {
+        self.circuit.evaluate(inputs)
+    }
+
+    /// 回路を使わずに直接計算した出力（検算用）
+    pub fn expected_output(&self, inputs: &[F]) -> Vec<F> {
+        (self.reference)(inputs)
+    }
+}
+
+fn random_vec<F: Field>(len: usize, rng: &mut dyn RngCore) -> Vec<F> {
+    (0..len).map(|_| F::rand(rng)).collect()
+}
+
+/// 2^n 個の入力の総積を求める二分木（深さ n）
+pub fn mul_tree<F: Field>(n: usize) -> ExampleCircuit<F> {
+    ExampleCircuit {
+        name: format!("mul_tree({})", n),
+        circuit: wiring::binary_tree_circuit(n, TreeOp::Mul),
+        generate: Box::new(move |rng| random_vec(1 << n, rng)),
+        reference: Box::new(|inputs| vec![inputs.iter().product()]),
+    }
+}
+
+/// 長さ 2^n のベクトル a, b の内積（入力は a || b，深さ n + 1）
+pub fn inner_product<F: Field>(n: usize) -> ExampleCircuit<F> {
+    ExampleCircuit {
+        name: format!("inner_product({})", n),
+        circuit: wiring::inner_product_circuit(n),
+        generate: Box::new(move |rng| random_vec(2 << n, rng)),
+        reference: Box::new(|inputs| {
+            let (a, b) = inputs.split_at(inputs.len() / 2);
+            vec![a.iter().zip(b.iter()).map(|(x, y)| *x * y).sum()]
+        }),
+    }
+}
+
+/// 入力 [a, b, 1, 1] から (a, b) ← (b, a + b) を k 回適用する（深さ k）
+///
+/// 各層は [b·1, a + b, 1·1, 1·1] で，出力は [F_k, F_{k+1}, 1, 1] の形になる。
+pub fn fibonacci<F: Field>(k: usize) -> ExampleCircuit<F> {
+    let layer = Layer { gates: vec![Gate::Mul(1, 2), Gate::Add(0, 1), Gate::Mul(2, 3), Gate::Mul(2, 3)] };
+    ExampleCircuit {
+        name: format!("fibonacci({})", k),
+        circuit: Circuit { num_inputs: 4, layers: vec![layer; k] },
+        generate: Box::new(|rng| vec![F::rand(rng), F::rand(rng), F::one(), F::one()]),
+        reference: Box::new(move |inputs| {
+            let (mut a, mut b) = (inputs[0], inputs[1]);
+            for _ in 0..k {
+                (a, b) = (b, a + b);
+            }
+            vec![a, b, F::one(), F::one()]
+        }),
+    }
+}
+
+/// 入力 [x, 1, 1, 1] に x ↦ x^5 + x を k 回適用する（深さ 4k）
+///
+/// 1 ラウンドは x², x⁴, x⁵ を掛け算の層で作り，最後の加算の層で x を足す。
+/// x 自身と定数 1 は x·1, 1·1 として次の層へ運ぶ。
+pub fn hash_chain<F: Field>(k: usize) -> ExampleCircuit<F> {
+    let carry = |first: Gate| Layer { gates: vec![first, Gate::Mul(1, 2), Gate::Mul(2, 3), Gate::Mul(2, 3)] };
+    // 出力側から並べるので，1 ラウンドの層も逆順になる
+    let round = [
+        Layer { gates: vec![Gate::Add(0, 1), Gate::Mul(2, 3), Gate::Mul(2, 3), Gate::Mul(2, 3)] },
+        carry(Gate::Mul(0, 1)),
+        carry(Gate::Mul(0, 0)),
+        Layer { gates: vec![Gate::Mul(0, 0), Gate::Mul(0, 1), Gate::Mul(1, 2), Gate::Mul(1, 2)] },
+    ];
+    let layers = (0..k).flat_map(|_| round.iter().cloned()).collect();
+    ExampleCircuit {
+        name: format!("hash_chain({})", k),
+        circuit: Circuit { num_inputs: 4, layers },
+        generate: Box::new(|rng| vec![F::rand(rng), F::one(), F::one(), F::one()]),
+        reference: Box::new(move |inputs| {
+            let mut x = inputs[0];
+            for _ in 0..k {
+                x = x.square().square() * x + x;
+            }
+            vec![x, F::one(), F::one(), F::one()]
+        }),
+    }
+}
+
+/// テストとベンチマークで回す標準的な回路一式
+pub fn standard_suite<F: Field>() -> Vec<ExampleCircuit<F>> {
+    vec![mul_tree(4), inner_product(3), fibonacci(8), hash_chain(3)]
+}
@@ -0,0 +1,271 @@
+// src/expr.rs
+//
+// 算術式（`x^3 + x + 5` など）を層状の回路にコンパイルする。
+//
+// - 共通部分式：構造が同じ部分式は 1 度だけ計算し，その配線を使い回す
+// - 深さの均衡：連なった和や積は項をまとめて，浅い項から順に（Huffman 符号と同じ要領で）組み合わせる
+// - 定数畳み込み：変数を含まない部分式はコンパイル時に計算し，定数として回路の入力に置く
+//
+// 層の割り当てと中継は `CircuitBuilder` に任せる。
+
+use ark_ff::Field;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::ops;
+
+use crate::builder::{BuiltCircuit, CircuitBuilder, Wire};
+use crate::error::Error;
+
+/// 算術式。`Var(i)` は i 番目の入力
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Expr<F: Field> {
+    Var(usize),
+    Const(F),
+    Add(Box<Expr<F>>, Box<Expr<F>>),
+    Mul(Box<Expr<F>>, Box<Expr<F>>),
+    Neg(Box<Expr<F>>),
+    Pow(Box<Expr<F>>, u64),
+}
+
+impl<F: Field> Expr<F> {
+    pub fn var(i: usize) -> Self {
+        Expr::Var(i)
+    }
+
+    pub fn constant(c: impl Into<F>) -> Self {
+        Expr::Const(c.into())
+    }
+
+    pub fn pow(self, k: u64) -> Self {
+        Expr::Pow(Box::new(self), k)
+    }
+
+    /// 入力に対する値
+    pub fn evaluate(&self, vars: &[F]) -> F {
+        match self {
+            Expr::Var(i) => vars[*i],
+            Expr::Const(c) => *c,
+            Expr::Add(a, b) => a.evaluate(vars) + b.evaluate(vars),
+            Expr::Mul(a, b) => a.evaluate(vars) * b.evaluate(vars),
+            Expr::Neg(a) => -a.evaluate(vars),
+            Expr::Pow(a, k) => a.evaluate(vars).pow([*k]),
+        }
+    }
+
+    /// 読む変数の添字の最大値 + 1（変数を含まなければ 0）
+    pub fn num_vars(&self) -> usize {
+        match self {
+            Expr::Var(i) => i + 1,
+            Expr::Const(_) => 0,
+            Expr::Add(a, b) | Expr::Mul(a, b) => a.num_vars().max(b.num_vars()),
+            Expr::Neg(a) | Expr::Pow(a, _) => a.num_vars(),
+        }
+    }
+
+    fn children(&self) -> Vec<&Expr<F>> {
+        match self {
+            Expr::Var(_) | Expr::Const(_) => vec![],
+            Expr::Add(a, b) | Expr::Mul(a, b) => vec![a, b],
+            Expr::Neg(a) | Expr::Pow(a, _) => vec![a],
+        }
+    }
+}
+
+impl<F: Field> ops::Add for Expr<F> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Expr::Add(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl<F: Field> ops::Sub for Expr<F> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl<F: Field> ops::Mul for Expr<F> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Expr::Mul(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl<F: Field> ops::Neg for Expr<F> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Expr::Neg(Box::new(self))
+    }
+}
+
+/// 式の列を入力数 2 のゲートだけの回路にする（出力層は `outputs` の順，入力は `num_vars` 個）
+pub fn compile<F: Field>(outputs: &[Expr<F>], num_vars: usize) -> Result<BuiltCircuit<F>, Error> {
+    compile_with(outputs, num_vars, CircuitBuilder::new())
+}
+
+/// 空の `builder` に回路を組み立てる（`CircuitBuilder::with_max_fan_in` で和や積の木が浅くなる）
+pub fn compile_with<F: Field>(
+    outputs: &[Expr<F>],
+    num_vars: usize,
+    builder: CircuitBuilder<F>,
+) -> Result<BuiltCircuit<F>, Error> {
+    if outputs.iter().any(|e| e.num_vars() > num_vars) {
+        return Err(Error::InvalidCircuit("expression reads an unknown variable"));
+    }
+    let mut compiler = Compiler {
+        uses: HashMap::new(),
+        wires: HashMap::new(),
+        constants: HashMap::new(),
+        squares: HashMap::new(),
+        vars: vec![],
+        builder,
+    };
+    compiler.vars = (0..num_vars).map(|_| compiler.builder.input()).collect();
+    for e in outputs {
+        compiler.count_uses(e);
+    }
+    for e in outputs {
+        let w = compiler.wire(e);
+        compiler.builder.output(w);
+    }
+    compiler.builder.build()
+}
+
+struct Compiler<'a, F: Field> {
+    /// 構造が同じ部分式の出現回数
+    uses: HashMap<&'a Expr<F>, usize>,
+    wires: HashMap<&'a Expr<F>, Wire>,
+    constants: HashMap<F, Wire>,
+    /// 冪で作った平方（`Pow` をまたいで使い回す）
+    squares: HashMap<Wire, Wire>,
+    vars: Vec<Wire>,
+    builder: CircuitBuilder<F>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Add,
+    Mul,
+}
+
+impl<'a, F: Field> Compiler<'a, F> {
+    fn count_uses(&mut self, e: &'a Expr<F>) {
+        let count = self.uses.entry(e).or_insert(0);
+        *count += 1;
+        // 2 回目以降は同じ配線を使うので中は数えない
+        if *count == 1 {
+            for c in e.children() {
+                self.count_uses(c);
+            }
+        }
+    }
+
+    fn wire(&mut self, e: &'a Expr<F>) -> Wire {
+        if let Some(w) = self.wires.get(e) {
+            return *w;
+        }
+        let w = match (e, constant_value(e)) {
+            (_, Some(c)) => self.constant(c),
+            (Expr::Var(i), None) => self.vars[*i],
+            (Expr::Add(..), None) => self.reduce(e, Op::Add),
+            (Expr::Mul(..) | Expr::Pow(..), None) => self.reduce(e, Op::Mul),
+            (Expr::Neg(a), None) => {
+                let minus_one = self.constant(-F::one());
+                let a = self.wire(a);
+                self.builder.mul(minus_one, a)
+            }
+            (Expr::Const(_), None) => unreachable!("constants always fold"),
+        };
+        self.wires.insert(e, w);
+        w
+    }
+
+    fn constant(&mut self, c: F) -> Wire {
+        let builder = &mut self.builder;
+        *self.constants.entry(c).or_insert_with(|| builder.constant(c))
+    }
+
+    /// 連なった和（積）の項を集め，浅いものから `max_fan_in` 個ずつまとめる
+    fn reduce(&mut self, e: &'a Expr<F>, op: Op) -> Wire {
+        let mut operands = Vec::new();
+        self.collect(e, op, true, &mut operands);
+        // ヒープの中では配線を `wires` での位置で識別する
+        let mut heap: BinaryHeap<Reverse<(usize, usize)>> =
+            operands.iter().enumerate().map(|(i, w)| Reverse((self.builder.depth(*w), i))).collect();
+        let mut wires = operands;
+        while heap.len() > 1 {
+            let take = heap.len().min(self.builder.max_fan_in());
+            let chunk: Vec<Wire> = (0..take).map(|_| wires[heap.pop().unwrap().0 .1]).collect();
+            let w = match op {
+                Op::Add => self.builder.sum(&chunk),
+                Op::Mul => self.builder.product(&chunk),
+            };
+            wires.push(w);
+            heap.push(Reverse((self.builder.depth(w), wires.len() - 1)));
+        }
+        wires[heap.pop().unwrap().0 .1]
+    }
+
+    /// e の中の同じ演算の連なりを辿り，項の配線を集める（共有される部分式と定数はそこで止める）
+    fn collect(&mut self, e: &'a Expr<F>, op: Op, root: bool, out: &mut Vec<Wire>) {
+        let shared = !root && (self.uses.get(e).copied().unwrap_or(0) > 1 || constant_value(e).is_some());
+        match e {
+            _ if shared => out.push(self.wire(e)),
+            Expr::Add(a, b) if op == Op::Add => {
+                self.collect(a, op, false, out);
+                self.collect(b, op, false, out);
+            }
+            Expr::Mul(a, b) if op == Op::Mul => {
+                self.collect(a, op, false, out);
+                self.collect(b, op, false, out);
+            }
+            Expr::Pow(a, k) if op == Op::Mul => {
+                // a^k を a^(2^i) の積に分ける（a^(2^i) は平方を重ねて作る）
+                if *k == 0 {
+                    out.push(self.constant(F::one()));
+                    return;
+                }
+                let mut square = self.wire(a);
+                for i in 0..(64 - k.leading_zeros()) {
+                    if i > 0 {
+                        square = self.square(square);
+                    }
+                    if (k >> i) & 1 == 1 {
+                        out.push(square);
+                    }
+                }
+            }
+            Expr::Neg(a) if op == Op::Mul => {
+                out.push(self.constant(-F::one()));
+                self.collect(a, op, false, out);
+            }
+            _ => out.push(self.wire(e)),
+        }
+    }
+
+    fn square(&mut self, w: Wire) -> Wire {
+        if let Some(s) = self.squares.get(&w) {
+            return *s;
+        }
+        let s = self.builder.mul(w, w);
+        self.squares.insert(w, s);
+        s
+    }
+}
+
+/// 変数を含まない式の値
+fn constant_value<F: Field>(e: &Expr<F>) -> Option<F> {
+    match e {
+        Expr::Var(_) => None,
+        Expr::Const(c) => Some(*c),
+        Expr::Add(a, b) => Some(constant_value(a)? + constant_value(b)?),
+        Expr::Mul(a, b) => Some(constant_value(a)? * constant_value(b)?),
+        Expr::Neg(a) => Some(-constant_value(a)?),
+        Expr::Pow(a, k) => Some(constant_value(a)?.pow([*k])),
+    }
+}
@@ -0,0 +1,206 @@
+// src/fan_in.rs
+//
+// 入力数（fan-in）k のゲートのための k フェーズの sum-check。
+// Σ_{x_1..x_k} f1(g, x_1, ..., x_k)·f_1(x_1)···f_k(x_k) を，LinearGKR の Phase 1, 2 と同じ要領で
+// x_1 から順に 1 フェーズずつ還元する。k = 2 のとき証明は `LinearGKRProver` のものと一致する。
+//
+// f1 は (k + 1)·l 変数で，添字は `z << kl | x_k << (k-1)l | ... | x_1`（k = 2 で prover の規約と同じ）。
+
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::PrimeField;
+use std::marker::PhantomData;
+
+use crate::eq::eq_table;
+use crate::error::Error;
+use crate::ml_extension::{DenseMLE, IndexOrder, SparseMLE};
+use crate::self_check::direct_evaluation;
+use crate::sumcheck::protocol;
+use crate::transcript::{labels, Transcript};
+
+/// k フェーズの sum-check の証明
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FanInProof<F: PrimeField = ScalarField> {
+    /// Prover が主張する総和
+    pub claimed_sum: F,
+    /// フェーズごとのラウンドメッセージ列（`phase_msgs[j]` が x_{j+1} のフェーズ）
+    pub phase_msgs: Vec<Vec<Vec<F>>>,
+    /// 最終点での値の主張 f1(g, r_1, ..., r_k)
+    pub f1_at_point: F,
+    /// 最終点での値の主張 f_j(r_j)
+    pub evals: Vec<F>,
+}
+
+impl<F: PrimeField> FanInProof<F> {
+    pub fn fan_in(&self) -> usize {
+        self.phase_msgs.len()
+    }
+}
+
+/// k フェーズの sum-check のサブクレーム（`LinearGKRSubclaim` の k 入力版）
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FanInSubclaim<F: PrimeField = ScalarField> {
+    /// フェーズごとのチャレンジ列 r_1, ..., r_k
+    pub points: Vec<Vec<F>>,
+    pub expected_value: F,
+    pub f1_at_point: F,
+    pub evals: Vec<F>,
+}
+
+impl<F: PrimeField> FanInSubclaim<F> {
+    /// 平文の f1, f_j を評価して最終検査を行う（f1 の変数は z, x_k, ..., x_1 の順）
+    pub fn verify_against(&self, f1: &SparseMLE<F>, fs: &[&DenseMLE<F>], g: &[F]) -> Result<(), Error> {
+        let point: Vec<F> = g.iter().chain(self.points.iter().rev().flatten()).copied().collect();
+        if f1.evaluate(&point) != self.f1_at_point {
+            return Err(Error::EvaluationMismatch("f1 at (g, r_1, ..., r_k)"));
+        }
+        if fs.iter().zip(self.points.iter()).zip(self.evals.iter()).any(|((f, r), e)| f.evaluate(r) != *e) {
+            return Err(Error::EvaluationMismatch("f_j at r_j"));
+        }
+        if self.f1_at_point * self.evals.iter().product::<F>() != self.expected_value {
+            return Err(Error::EvaluationMismatch("product of the final evaluations"));
+        }
+        Ok(())
+    }
+}
+
+/// k フェーズの sum-check の Prover
+pub struct FanInProver<F: PrimeField = ScalarField>(PhantomData<F>);
+
+impl<F: PrimeField> FanInProver<F> {
+    /// f1: (k + 1)·l 変数の疎な MLE，fs: l 変数の密な MLE k 個，g: 出力側の点（長さ l）
+    pub fn prove(f1: &SparseMLE<F>, fs: &[&DenseMLE<F>], g: &[F], transcript: &mut Transcript) -> FanInProof<F> {
+        Self::prove_weighted(f1, fs, &eq_table(g), transcript).0
+    }
+
+    /// 出力側の変数を重み付きの和 Σ_z weights[z]·f1(z, ·) で消去して証明する
+    ///
+    /// 証明とともに，各フェーズのチャレンジ列 r_1, ..., r_k を返す。
+    pub fn prove_weighted(
+        f1: &SparseMLE<F>,
+        fs: &[&DenseMLE<F>],
+        weights: &[F],
+        transcript: &mut Transcript,
+    ) -> (FanInProof<F>, Vec<Vec<F>>) {
+        let k = fs.len();
+        assert!(k > 0, "fan-in must be positive");
+        let l = fs[0].num_vars;
+        assert!(fs.iter().all(|f| f.num_vars == l));
+        assert_eq!(f1.num_vars, (k + 1) * l);
+        assert_eq!(weights.len(), 1 << l);
+        let mask = (1 << l) - 1;
+        let fs: Vec<DenseMLE<F>> = fs.iter().map(|f| f.to_order(IndexOrder::BigEndian)).collect();
+        // f1 の非零要素を (x_1, ..., x_k) と重み付きの値に分解する
+        let wiring: Vec<(Vec<usize>, F)> = f1
+            .big_endian_entries()
+            .filter_map(|(index, val)| {
+                let w = weights[index >> (k * l)] * val;
+                (!w.is_zero()).then(|| ((0..k).map(|j| (index >> (j * l)) & mask).collect(), w))
+            })
+            .collect();
+
+        let mut claimed_sum = F::zero();
+        let mut phase_msgs = Vec::with_capacity(k);
+        let mut points: Vec<Vec<F>> = Vec::with_capacity(k);
+        let mut eqs: Vec<Vec<F>> = Vec::with_capacity(k);
+        let mut evals = Vec::with_capacity(k);
+        let mut f1_at_point = F::zero();
+        // Π_{i<j} f_i(r_i)
+        let mut scale = F::one();
+        for j in 0..k {
+            // A_j(x_j) = Σ f1(·, r_1, ..., r_{j-1}, x_j, x_{j+1}, ...)·Π_{i>j} f_i(x_i)
+            let mut table = vec![F::zero(); 1 << l];
+            for (xs, w) in wiring.iter() {
+                let fixed: F = eqs.iter().zip(xs.iter()).map(|(eq, x)| eq[*x]).product();
+                let rest: F = fs[j + 1..].iter().zip(xs[j + 1..].iter()).map(|(f, x)| f.evaluations[*x]).product();
+                table[xs[j]] += *w * fixed * rest;
+            }
+            let mut scaled = DenseMLE::from_evaluations_vec(l, table.clone());
+            scaled.scale(scale);
+            let mut state = protocol::prover_init(vec![scaled, fs[j].clone()]);
+            if j == 0 {
+                claimed_sum = state.current_sum;
+                transcript.append_field(labels::CLAIMED_SUM, &claimed_sum);
+            }
+            let (round_label, challenge_label) = (labels::phase_round(j), labels::phase_challenge(j));
+            let mut msgs = Vec::with_capacity(l);
+            let mut r = Vec::with_capacity(l);
+            for _ in 0..l {
+                let msg = protocol::prove_round(&mut state);
+                transcript.append(&round_label, &msg);
+                msgs.push(msg);
+                let r_i: F = transcript.squeeze(&challenge_label);
+                r.push(r_i);
+                protocol::apply_challenge(&mut state, r_i);
+            }
+            let eval = state.tables[1].evaluations[0];
+            scale *= eval;
+            evals.push(eval);
+            if j + 1 == k {
+                // 最後のフェーズの表は f1(g, r_1, ..., r_{k-1}, ·) そのもの
+                f1_at_point = direct_evaluation(&table, &r);
+            }
+            phase_msgs.push(msgs);
+            eqs.push(eq_table(&r));
+            points.push(r);
+        }
+        let final_evals: Vec<F> = std::iter::once(f1_at_point).chain(evals.iter().copied()).collect();
+        transcript.append(labels::FAN_IN_FINAL_EVALS, &final_evals);
+        (FanInProof { claimed_sum, phase_msgs, f1_at_point, evals }, points)
+    }
+}
+
+/// k フェーズの sum-check の Verifier
+pub struct FanInVerifier<F: PrimeField = ScalarField>(PhantomData<F>);
+
+impl<F: PrimeField> FanInVerifier<F> {
+    /// num_vars: 各 f_j の変数数（l），fan_in: k
+    pub fn verify(
+        num_vars: usize,
+        fan_in: usize,
+        claimed_sum: F,
+        proof: &FanInProof<F>,
+        transcript: &mut Transcript,
+    ) -> Result<FanInSubclaim<F>, Error> {
+        let l = num_vars;
+        if proof.claimed_sum != claimed_sum {
+            return Err(Error::EvaluationMismatch("claimed sum"));
+        }
+        if proof.phase_msgs.len() != fan_in {
+            return Err(Error::LengthMismatch { what: "phases", expected: fan_in, found: proof.phase_msgs.len() });
+        }
+        if proof.evals.len() != fan_in {
+            return Err(Error::LengthMismatch { what: "final evaluations", expected: fan_in, found: proof.evals.len() });
+        }
+        for msgs in proof.phase_msgs.iter() {
+            if msgs.len() != l {
+                return Err(Error::LengthMismatch { what: "round messages", expected: l, found: msgs.len() });
+            }
+        }
+
+        transcript.append_field(labels::CLAIMED_SUM, &claimed_sum);
+        let mut expected_value = claimed_sum;
+        let mut points = Vec::with_capacity(fan_in);
+        for (j, msgs) in proof.phase_msgs.iter().enumerate() {
+            // 各フェーズのラウンド多項式は 2 つの MLE の積なので 2 次
+            let mut state = protocol::verifier_init(l, 2, expected_value);
+            let (round_label, challenge_label) = (labels::phase_round(j), labels::phase_challenge(j));
+            for msg in msgs.iter() {
+                protocol::verify_round(&mut state, msg)?;
+                transcript.append(&round_label, msg);
+                let r_i: F = transcript.squeeze(&challenge_label);
+                protocol::apply_challenge_verifier(&mut state, r_i);
+            }
+            let subclaim = protocol::finalize(state)?;
+            points.push(subclaim.point);
+            expected_value = subclaim.expected_value;
+        }
+
+        if proof.f1_at_point * proof.evals.iter().product::<F>() != expected_value {
+            return Err(Error::EvaluationMismatch("product of the final evaluations"));
+        }
+        let final_evals: Vec<F> = std::iter::once(proof.f1_at_point).chain(proof.evals.iter().copied()).collect();
+        transcript.append(labels::FAN_IN_FINAL_EVALS, &final_evals);
+
+        Ok(FanInSubclaim { points, expected_value, f1_at_point: proof.f1_at_point, evals: proof.evals.clone() })
+    }
+}
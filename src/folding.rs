@@ -0,0 +1,281 @@
+// src/folding.rs
+//
+// IVC の各ステップが残す「コミット済みの MLE の 1 点評価」の主張を，走行中のインスタンスへ
+// 折り畳む（Nova 風）。折り畳みの検証は O(num_vars) の体演算とハッシュで済み，
+// 重い評価の検査（decide）は最後に一度だけ行えばよい。
+//
+// 2 つの主張 W1(r1) = v1, W2(r2) = v2 は
+//   v1 + ρ·v2 = Σ_b [eq(r1, b)·W1(b) + ρ·eq(r2, b)·W2(b)]
+// に対する sum-check で共通の点 s での W1(s), W2(s) に帰着させ，
+// さらにランダムな β で (C1 + β·C2, s, W1(s) + β·W2(s)) に畳み込む。
+
+use ark_ff::PrimeField;
+
+use crate::eq::{eq_eval, eq_table};
+use crate::error::Error;
+use crate::ml_extension::{fix_first_variable_batch, DenseMLE, IndexOrder};
+use crate::self_check::RoundChecker;
+use crate::sparse_sumcheck::verify_round;
+use crate::sumcheck::MessageForm;
+use crate::transcript::Transcript;
+
+/// 線形に折り畳めるコミットメント
+pub trait FoldableCommitment<F: PrimeField>: Clone {
+    /// self + β·other
+    fn fold(&self, other: &Self, beta: F) -> Self;
+    /// transcript に吸収する
+    fn append_to(&self, transcript: &mut Transcript);
+    /// `mle` のコミットメントか（decide で使う）
+    fn opens_to(&self, mle: &DenseMLE<F>) -> bool;
+}
+
+/// 評価表そのもの（先頭の変数を最上位ビットとする順序）を値とする（秘匿性のない）コミットメント。テストと参照実装用
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlainCommitment<F: PrimeField>(pub Vec<F>);
+
+impl<F: PrimeField> PlainCommitment<F> {
+    pub fn commit(mle: &DenseMLE<F>) -> Self {
+        PlainCommitment(mle.to_order(IndexOrder::BigEndian).evaluations)
+    }
+}
+
+impl<F: PrimeField> FoldableCommitment<F> for PlainCommitment<F> {
+    fn fold(&self, other: &Self, beta: F) -> Self {
+        PlainCommitment(self.0.iter().zip(other.0.iter()).map(|(a, b)| *a + beta * b).collect())
+    }
+
+    fn append_to(&self, transcript: &mut Transcript) {
+        transcript.append_fields(b"plain-commitment", &self.0);
+    }
+
+    fn opens_to(&self, mle: &DenseMLE<F>) -> bool {
+        self.0 == mle.to_order(IndexOrder::BigEndian).evaluations
+    }
+}
+
+/// コミット済みの MLE W について W(point) = value という主張
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EvaluationClaim<F: PrimeField, C> {
+    pub commitment: C,
+    pub point: Vec<F>,
+    pub value: F,
+}
+
+impl<F: PrimeField, C: FoldableCommitment<F>> EvaluationClaim<F, C> {
+    fn append_to(&self, transcript: &mut Transcript) {
+        self.commitment.append_to(transcript);
+        transcript.append_fields(b"claim-point", &self.point);
+        transcript.append_field(b"claim-value", &self.value);
+    }
+}
+
+/// 折り畳み 1 回分の証明
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FoldingProof<F: PrimeField> {
+    /// sum-check の各ラウンドのメッセージ（`MessageForm::default()` でエンコード）
+    pub round_msgs: Vec<Vec<F>>,
+    /// 共通点 s での W1(s), W2(s)
+    pub running_eval: F,
+    pub incoming_eval: F,
+}
+
+/// MLE を任意の点で評価する（先頭の変数から畳み込む）
+pub fn evaluate_mle<F: PrimeField>(mle: &DenseMLE<F>, point: &[F]) -> F {
+    mle.evaluate(point)
+}
+
+fn begin<F: PrimeField, C: FoldableCommitment<F>>(
+    transcript: &mut Transcript,
+    running: &EvaluationClaim<F, C>,
+    incoming: &EvaluationClaim<F, C>,
+) -> F {
+    transcript.append_message(b"fold", &(running.point.len() as u64).to_le_bytes());
+    running.append_to(transcript);
+    incoming.append_to(transcript);
+    transcript.challenge_field(b"rho")
+}
+
+fn finish<F: PrimeField, C: FoldableCommitment<F>>(
+    transcript: &mut Transcript,
+    running: &EvaluationClaim<F, C>,
+    incoming: &EvaluationClaim<F, C>,
+    point: Vec<F>,
+    proof: &FoldingProof<F>,
+) -> (EvaluationClaim<F, C>, F) {
+    transcript.append_field(b"running-eval", &proof.running_eval);
+    transcript.append_field(b"incoming-eval", &proof.incoming_eval);
+    let beta: F = transcript.challenge_field(b"beta");
+    let folded = EvaluationClaim {
+        commitment: running.commitment.fold(&incoming.commitment, beta),
+        point,
+        value: proof.running_eval + beta * proof.incoming_eval,
+    };
+    (folded, beta)
+}
+
+/// 走行中の主張 `running`（証拠 `running_mle`）に新しい主張を折り畳む（prover 側）
+///
+/// 折り畳んだ主張と，その証拠（W1 + β·W2，先頭の変数を最上位ビットとする順序）を返す。
+pub fn fold_prove<F: PrimeField, C: FoldableCommitment<F>>(
+    transcript: &mut Transcript,
+    running: &EvaluationClaim<F, C>,
+    running_mle: &DenseMLE<F>,
+    incoming: &EvaluationClaim<F, C>,
+    incoming_mle: &DenseMLE<F>,
+) -> (FoldingProof<F>, EvaluationClaim<F, C>, DenseMLE<F>) {
+    let n = running.point.len();
+    assert!(incoming.point.len() == n && running_mle.num_vars == n && incoming_mle.num_vars == n);
+    let rho = begin(transcript, running, incoming);
+    let form = MessageForm::default();
+    // eq_table と同じ順序に揃える（揃えないと表を一緒に畳み込めない）
+    let running_mle = &running_mle.to_order(IndexOrder::BigEndian);
+    let incoming_mle = &incoming_mle.to_order(IndexOrder::BigEndian);
+
+    // [eq(r1, ·), W1, eq(r2, ·), W2]
+    let mut tables = vec![
+        DenseMLE::from_evaluations_vec(n, eq_table(&running.point)),
+        running_mle.clone(),
+        DenseMLE::from_evaluations_vec(n, eq_table(&incoming.point)),
+        incoming_mle.clone(),
+    ];
+    let mut checker = RoundChecker::new_if_enabled("fold_prove", running.value + rho * incoming.value);
+    let mut round_msgs = Vec::with_capacity(n);
+    let mut point = Vec::with_capacity(n);
+    for _ in 0..n {
+        let half = tables[0].evaluations.len() / 2;
+        let mut evals = vec![F::zero(); 3];
+        for i in 0..half {
+            let at = |k: usize, t: usize| {
+                let (lo, hi) = (tables[k].evaluations[i], tables[k].evaluations[i + half]);
+                lo + F::from(t as u64) * (hi - lo)
+            };
+            for (t, e) in evals.iter_mut().enumerate() {
+                *e += at(0, t) * at(1, t) + rho * at(2, t) * at(3, t);
+            }
+        }
+        let msg = form.encode(evals.clone());
+        transcript.append_fields(b"fold-round", &msg);
+        round_msgs.push(msg);
+        let r: F = transcript.challenge_field(b"fold-challenge");
+        fix_first_variable_batch(&mut tables, r);
+        point.push(r);
+        if let Some(checker) = checker.as_mut() {
+            checker.advance(&evals, r);
+            checker.spot_check("W1", &running_mle.evaluations, 0, &tables[1]);
+            checker.spot_check("W2", &incoming_mle.evaluations, 0, &tables[3]);
+        }
+    }
+    let proof = FoldingProof {
+        round_msgs,
+        running_eval: tables[1].evaluations[0],
+        incoming_eval: tables[3].evaluations[0],
+    };
+    let (folded, beta) = finish(transcript, running, incoming, point, &proof);
+    let folded_mle = DenseMLE::linear_combination(&[(F::one(), running_mle), (beta, incoming_mle)]);
+    (proof, folded, folded_mle)
+}
+
+/// 折り畳みを検証し，折り畳んだ主張を返す（verifier 側，O(num_vars)）
+pub fn fold_verify<F: PrimeField, C: FoldableCommitment<F>>(
+    transcript: &mut Transcript,
+    running: &EvaluationClaim<F, C>,
+    incoming: &EvaluationClaim<F, C>,
+    proof: &FoldingProof<F>,
+) -> Result<EvaluationClaim<F, C>, Error> {
+    let n = running.point.len();
+    if incoming.point.len() != n {
+        return Err(Error::LengthMismatch { what: "claim variables", expected: n, found: incoming.point.len() });
+    }
+    if proof.round_msgs.len() != n {
+        return Err(Error::LengthMismatch { what: "folding round messages", expected: n, found: proof.round_msgs.len() });
+    }
+    let rho = begin(transcript, running, incoming);
+    let form = MessageForm::default();
+    let mut claim = running.value + rho * incoming.value;
+    let mut point = Vec::with_capacity(n);
+    for (round, msg) in proof.round_msgs.iter().enumerate() {
+        transcript.append_fields(b"fold-round", msg);
+        let r: F = transcript.challenge_field(b"fold-challenge");
+        claim = verify_round(claim, msg, 2, r, form).map_err(|kind| Error::Round { round, kind })?;
+        point.push(r);
+    }
+    let expected = eq_eval(&running.point, &point) * proof.running_eval
+        + rho * eq_eval(&incoming.point, &point) * proof.incoming_eval;
+    if claim != expected {
+        return Err(Error::EvaluationMismatch("folded evaluation claim"));
+    }
+    Ok(finish(transcript, running, incoming, point, proof).0)
+}
+
+/// 最後に一度だけ行う重い検査：コミットメントが証拠に一致し，証拠がその点で主張の値を取るか
+pub fn decide<F: PrimeField, C: FoldableCommitment<F>>(claim: &EvaluationClaim<F, C>, witness: &DenseMLE<F>) -> bool {
+    witness.num_vars == claim.point.len()
+        && claim.commitment.opens_to(witness)
+        && evaluate_mle(witness, &claim.point) == claim.value
+}
+
+/// IVC の prover 側：ステップごとの主張を受け取り，走行中のインスタンスに折り畳む
+pub struct FoldingProver<F: PrimeField, C: FoldableCommitment<F>> {
+    transcript: Transcript,
+    running: Option<(EvaluationClaim<F, C>, DenseMLE<F>)>,
+}
+
+impl<F: PrimeField, C: FoldableCommitment<F>> FoldingProver<F, C> {
+    pub fn new(transcript: Transcript) -> Self {
+        FoldingProver { transcript, running: None }
+    }
+
+    /// ステップの主張を取り込む。最初のステップでは証明は不要（`None`）
+    pub fn push(&mut self, claim: EvaluationClaim<F, C>, witness: DenseMLE<F>) -> Option<FoldingProof<F>> {
+        match self.running.take() {
+            None => {
+                self.running = Some((claim, witness));
+                None
+            }
+            Some((running, running_mle)) => {
+                let (proof, folded, folded_mle) =
+                    fold_prove(&mut self.transcript, &running, &running_mle, &claim, &witness);
+                self.running = Some((folded, folded_mle));
+                Some(proof)
+            }
+        }
+    }
+
+    /// 走行中の主張とその証拠
+    pub fn running(&self) -> Option<&(EvaluationClaim<F, C>, DenseMLE<F>)> {
+        self.running.as_ref()
+    }
+}
+
+/// IVC の verifier 側：各ステップの折り畳みだけを安く検証し，decide は最後に回す
+pub struct FoldingVerifier<F: PrimeField, C: FoldableCommitment<F>> {
+    transcript: Transcript,
+    running: Option<EvaluationClaim<F, C>>,
+}
+
+impl<F: PrimeField, C: FoldableCommitment<F>> FoldingVerifier<F, C> {
+    pub fn new(transcript: Transcript) -> Self {
+        FoldingVerifier { transcript, running: None }
+    }
+
+    /// ステップの主張と折り畳みの証明を取り込む
+    pub fn push(&mut self, claim: EvaluationClaim<F, C>, proof: Option<&FoldingProof<F>>) -> Result<(), Error> {
+        let folded = match (self.running.take(), proof) {
+            (None, None) => claim,
+            (Some(running), Some(proof)) => fold_verify(&mut self.transcript, &running, &claim, proof)?,
+            (None, Some(_)) => return Err(Error::MalformedProof("unexpected folding proof for the first step")),
+            (Some(_), None) => return Err(Error::MalformedProof("missing folding proof")),
+        };
+        self.running = Some(folded);
+        Ok(())
+    }
+
+    pub fn running(&self) -> Option<&EvaluationClaim<F, C>> {
+        self.running.as_ref()
+    }
+
+    /// 走行中の主張を最終的な証拠で検査する
+    pub fn decide(&self, witness: &DenseMLE<F>) -> bool {
+        self.running.as_ref().is_some_and(|claim| decide(claim, witness))
+    }
+}
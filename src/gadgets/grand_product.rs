@@ -0,0 +1,117 @@
+// src/gadgets/grand_product.rs
+//
+// GKR による総積の証明（grand product argument）。
+//
+// 2^n 個の値 v の総積 Π_i v_i を，隣り合う 2 つを掛ける深さ n の二分木回路
+// （`wiring::binary_tree_circuit(n, TreeOp::Mul)`）で求め，`circuit_prover` の層ごとの還元で証明する。
+// 層の間の主張は `ClaimReduction::LineRestriction` で 1 点にまとめるので，
+// 検証者に残るのは入力の MLE ṽ の 1 点 r での値 ṽ(r) = e という主張（`ProductClaim`）だけになる。
+// この主張は平文の v（`verify_against`）か，コミットメントの開示などのオラクルで確かめる。
+
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::PrimeField;
+
+use crate::circuit::Circuit;
+use crate::circuit_prover::{product_forms, ClaimReduction, CombinedClaim, GKRProof, GKRProver, GKRVerifier};
+use crate::error::Error;
+use crate::ml_extension::{DenseMLE, IndexOrder};
+use crate::statement::Statement;
+use crate::transcript::Transcript;
+use crate::wiring::{self, TreeOp};
+
+/// 総積の証明を初期化するラベル
+pub const GRAND_PRODUCT_LABEL: &[u8] = b"gkr-grand-product";
+
+/// 層の間の主張の還元方法（入力側に 1 点の主張だけを残す）
+const REDUCTION: ClaimReduction = ClaimReduction::LineRestriction;
+
+/// 総積の証明。`proof.outputs` は主張する総積 1 つ
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProductProof<F: PrimeField = ScalarField> {
+    pub proof: GKRProof<F>,
+}
+
+impl<F: PrimeField> ProductProof<F> {
+    /// 証明が主張する総積
+    pub fn product(&self) -> F {
+        self.proof.outputs[0]
+    }
+}
+
+/// 検証の後に残る，入力の MLE ṽ についての主張 ṽ(point) = value
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProductClaim<F: PrimeField = ScalarField> {
+    pub point: Vec<F>,
+    pub value: F,
+}
+
+impl<F: PrimeField> ProductClaim<F> {
+    /// 平文の v で主張を確かめる
+    pub fn verify_against(&self, values: &DenseMLE<F>) -> Result<(), Error> {
+        self.verify_with_oracle(|point| values.evaluate(point))
+    }
+
+    /// `verify_against` のオラクル版。クロージャは ṽ(point) を返す
+    pub fn verify_with_oracle(&self, v: impl FnOnce(&[F]) -> F) -> Result<(), Error> {
+        if v(&self.point) != self.value {
+            return Err(Error::EvaluationMismatch("input values at the final claim"));
+        }
+        Ok(())
+    }
+}
+
+/// n 変数の値（2^n 個）の総積を求める二分木回路
+pub fn product_circuit(num_vars: usize) -> Circuit {
+    wiring::binary_tree_circuit(num_vars, TreeOp::Mul)
+}
+
+/// v の総積を証明する
+pub fn prove_product<F: PrimeField>(values: &DenseMLE<F>) -> ProductProof<F> {
+    let mut transcript = product_transcript::<F>(values.num_vars);
+    prove_product_with(values, &mut transcript)
+}
+
+/// 呼び出し側の transcript の上で v の総積を証明する（他の証明に組み込むとき用）
+pub fn prove_product_with<F: PrimeField>(values: &DenseMLE<F>, transcript: &mut Transcript) -> ProductProof<F> {
+    assert!(values.num_vars > 0, "a grand product needs at least two values");
+    let circuit = product_circuit(values.num_vars);
+    let inputs = values.to_order(IndexOrder::BigEndian).evaluations;
+    let layer_values = circuit.evaluate(&inputs);
+    let (proof, _, _) = GKRProver::prove_layers(&circuit, &layer_values, &product_forms(&circuit), REDUCTION, transcript);
+    ProductProof { proof }
+}
+
+/// n 変数の値の総積が `claimed_product` であることを検証し，入力の MLE についての主張を返す
+pub fn verify_product<F: PrimeField>(
+    num_vars: usize,
+    claimed_product: F,
+    proof: &ProductProof<F>,
+) -> Result<ProductClaim<F>, Error> {
+    let mut transcript = product_transcript::<F>(num_vars);
+    verify_product_with(num_vars, claimed_product, proof, &mut transcript)
+}
+
+/// `prove_product_with` の証明を同じ状態の transcript で検証する
+pub fn verify_product_with<F: PrimeField>(
+    num_vars: usize,
+    claimed_product: F,
+    proof: &ProductProof<F>,
+    transcript: &mut Transcript,
+) -> Result<ProductClaim<F>, Error> {
+    let circuit = product_circuit(num_vars);
+    circuit.validate()?;
+    if proof.proof.outputs.first() != Some(&claimed_product) {
+        return Err(Error::EvaluationMismatch("claimed product"));
+    }
+    let (reduced, value) = GKRVerifier::verify_circuit_layers(&circuit, &proof.proof, REDUCTION, transcript)?;
+    let CombinedClaim { mut points, .. } = reduced;
+    Ok(ProductClaim { point: points.remove(0), value })
+}
+
+/// 回路（変数の数）を吸収した transcript
+///
+/// 入力は吸収しないので，入力を縛るには `prove_product_with` を使い，呼び出し側でコミットメントなどを吸収しておく。
+fn product_transcript<F: PrimeField>(num_vars: usize) -> Transcript {
+    let statement = Statement::<F>::new(&product_circuit(num_vars), Vec::new(), Vec::new());
+    Transcript::for_statement(GRAND_PRODUCT_LABEL, &statement)
+}
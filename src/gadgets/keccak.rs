@@ -0,0 +1,254 @@
+// src/gadgets/keccak.rs
+//
+// Keccak-f[1600] 置換の層状回路。
+//
+// 状態の 1600 ビットを 1 ビットずつ体の元 0 / 1 として配線に載せ，XOR と AND を体の演算で書く。
+//   a ⊕ b = a + b - 2ab，¬a = 1 - a，¬a ∧ b = b - ab
+// θ の列のパリティは ±1 の符号 1 - 2a の積で取る（符号の積はビットの XOR の符号）ので，
+// 5 ビットや 11 ビットの XOR も 1 回の積の木で済む。ρ と π は配線の並べ替えだけでゲートを使わない。
+// 値は全て 0 / 1 なので，Goldilocks のような小さい体の上でもそのまま組める（ブール向けの体の使い方）。
+//
+// 状態の添字は lane = x + 5y の 64 ビットのレーンを並べ，ビット i = 64·lane + z（z はレーンの下位から）。
+// `keccak_p(state, n)` はラウンド定数の最後の n 個を使う Keccak-p[1600, n] で，n = 24 が Keccak-f。
+//
+// 1 ブロックに多数の置換を証明する用途のために，`prove_batch` は同じ回路の置換を
+// `GKRProver::prove_batch` のデータ並列 GKR でまとめて証明する。
+
+use ark_ff::PrimeField;
+use std::array;
+
+use crate::builder::{BuiltCircuit, CircuitBuilder, Wire};
+use crate::circuit_prover::{BatchGKRProof, GKRProver, GKRVerifier};
+use crate::error::Error;
+
+/// Keccak-f[1600] のラウンド数
+pub const ROUNDS: usize = 24;
+/// 状態のビット数
+pub const STATE_BITS: usize = 1600;
+
+/// ι のラウンド定数
+pub const ROUND_CONSTANTS: [u64; ROUNDS] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// ρ の回転量（π の順に辿ったレーンごと）
+const RHO: [u32; 24] = [1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44];
+/// π で辿るレーンの順
+const PI: [usize; 24] = [10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1];
+
+/// ρ と π（レーン 1 から π の順に，1 つ前のレーンを回転して置く）
+fn rho_pi<T: Clone>(lanes: &mut [T], rotate: impl Fn(&T, u32) -> T) {
+    let mut last = lanes[1].clone();
+    for (&j, &r) in PI.iter().zip(&RHO) {
+        last = std::mem::replace(&mut lanes[j], rotate(&last, r));
+    }
+}
+
+/// Keccak-p[1600, rounds]（最後の `rounds` 個のラウンド定数を使う）
+pub fn keccak_p(state: &mut [u64; 25], rounds: usize) {
+    assert!(rounds <= ROUNDS, "Keccak has at most 24 rounds");
+    for rc in &ROUND_CONSTANTS[ROUNDS - rounds..] {
+        let c: [u64; 5] = array::from_fn(|x| (0..5).fold(0, |acc, y| acc ^ state[x + 5 * y]));
+        for (i, lane) in state.iter_mut().enumerate() {
+            let x = i % 5;
+            *lane ^= c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+        }
+        rho_pi(state, |lane, r| lane.rotate_left(r));
+        for row in state.chunks_mut(5) {
+            let b: [u64; 5] = array::from_fn(|x| row[x]);
+            for (x, lane) in row.iter_mut().enumerate() {
+                *lane = b[x] ^ (!b[(x + 1) % 5] & b[(x + 2) % 5]);
+            }
+        }
+        state[0] ^= rc;
+    }
+}
+
+/// Keccak-f[1600]
+pub fn keccak_f(state: &mut [u64; 25]) {
+    keccak_p(state, ROUNDS);
+}
+
+/// 状態を 1600 個のビット（体の元 0 / 1）にする
+pub fn state_bits<F: PrimeField>(state: &[u64; 25]) -> Vec<F> {
+    state.iter().flat_map(|lane| (0..64).map(move |z| F::from((lane >> z) & 1))).collect()
+}
+
+/// 1600 個のビットから状態に戻す（0 / 1 でない値があればエラー）
+pub fn state_from_bits<F: PrimeField>(bits: &[F]) -> Result<[u64; 25], Error> {
+    if bits.len() != STATE_BITS {
+        return Err(Error::LengthMismatch { what: "state bits", expected: STATE_BITS, found: bits.len() });
+    }
+    let mut state = [0u64; 25];
+    for (i, bit) in bits.iter().enumerate() {
+        if bit.is_one() {
+            state[i / 64] |= 1 << (i % 64);
+        } else if !bit.is_zero() {
+            return Err(Error::MalformedProof("state value is not a bit"));
+        }
+    }
+    Ok(state)
+}
+
+/// 組み立て中の回路に，1600 ビットの状態 `state` の Keccak-p[1600, rounds] を足して出力側の配線を返す
+///
+/// 入力の配線が 0 / 1 であることは確かめない（呼び出し側が保証する）。
+pub fn permute_wires<F: PrimeField>(builder: &mut CircuitBuilder<F>, state: &[Wire], rounds: usize) -> Vec<Wire> {
+    assert_eq!(state.len(), STATE_BITS, "state must have 1600 bits");
+    assert!(rounds <= ROUNDS, "Keccak has at most 24 rounds");
+    let mut gadget = BitGadget::new(builder);
+    let mut state = state.to_vec();
+    for &rc in &ROUND_CONSTANTS[ROUNDS - rounds..] {
+        state = gadget.round(&state, rc);
+    }
+    state
+}
+
+/// Keccak-p[1600, rounds] の 1 回だけの回路（入力 1600 ビット，出力 1600 ビット）
+pub fn circuit<F: PrimeField>(rounds: usize) -> Result<BuiltCircuit<F>, Error> {
+    if rounds == 0 || rounds > ROUNDS {
+        return Err(Error::InvalidCircuit("Keccak-p needs 1 to 24 rounds"));
+    }
+    let mut builder = CircuitBuilder::new();
+    let state: Vec<Wire> = (0..STATE_BITS).map(|_| builder.input()).collect();
+    for w in permute_wires(&mut builder, &state, rounds) {
+        builder.output(w);
+    }
+    builder.build()
+}
+
+/// `circuit` の回路で，状態 `states` の置換をデータ並列 GKR でまとめて証明する
+pub fn prove_batch<F: PrimeField>(built: &BuiltCircuit<F>, states: &[[u64; 25]]) -> BatchGKRProof<F> {
+    let inputs: Vec<Vec<F>> = states.iter().map(|s| built.assign(&state_bits(s))).collect();
+    GKRProver::<F>::prove_batch(&built.circuit, &inputs)
+}
+
+/// `prove_batch` の証明を検証し，置換後の状態を返す
+pub fn verify_batch<F: PrimeField>(
+    built: &BuiltCircuit<F>,
+    states: &[[u64; 25]],
+    proof: &BatchGKRProof<F>,
+) -> Result<Vec<[u64; 25]>, Error> {
+    let inputs: Vec<Vec<F>> = states.iter().map(|s| built.assign(&state_bits(s))).collect();
+    GKRVerifier::<F>::verify_batch(&built.circuit, &inputs, proof)?;
+    proof.outputs.iter().map(|bits| state_from_bits(bits)).collect()
+}
+
+/// 配線の上のビット演算。符号との変換に使う定数は置換ごとに 1 回だけ置く
+struct BitGadget<'a, F: PrimeField> {
+    builder: &'a mut CircuitBuilder<F>,
+    one: Wire,
+    minus_one: Wire,
+    minus_two: Wire,
+    half: Wire,
+    minus_half: Wire,
+}
+
+impl<'a, F: PrimeField> BitGadget<'a, F> {
+    fn new(builder: &'a mut CircuitBuilder<F>) -> Self {
+        let half = F::from(2u64).inverse().expect("field characteristic is not 2");
+        let one = builder.small_constant(1);
+        let minus_one = builder.constant(-F::one());
+        let minus_two = builder.constant(-F::from(2u64));
+        let (half, minus_half) = (builder.constant(half), builder.constant(-half));
+        BitGadget { builder, one, minus_one, minus_two, half, minus_half }
+    }
+
+    /// ビット a の符号 1 - 2a
+    fn sign(&mut self, a: Wire) -> Wire {
+        let scaled = self.builder.mul(self.minus_two, a);
+        self.builder.add(self.one, scaled)
+    }
+
+    /// 符号 s のビット (1 - s) / 2
+    fn bit(&mut self, s: Wire) -> Wire {
+        let scaled = self.builder.mul(self.minus_half, s);
+        self.builder.add(self.half, scaled)
+    }
+
+    /// a ⊕ b = a + b - 2ab
+    fn xor(&mut self, a: Wire, b: Wire) -> Wire {
+        let ab = self.builder.mul(a, b);
+        let scaled = self.builder.mul(self.minus_two, ab);
+        self.builder.sum(&[a, b, scaled])
+    }
+
+    /// ¬a = 1 - a
+    fn not(&mut self, a: Wire) -> Wire {
+        let scaled = self.builder.mul(self.minus_one, a);
+        self.builder.add(self.one, scaled)
+    }
+
+    /// ¬a ∧ b = b - ab
+    fn and_not(&mut self, a: Wire, b: Wire) -> Wire {
+        let ab = self.builder.mul(a, b);
+        let scaled = self.builder.mul(self.minus_one, ab);
+        self.builder.add(b, scaled)
+    }
+
+    fn round(&mut self, state: &[Wire], rc: u64) -> Vec<Wire> {
+        let mut lanes: Vec<Vec<Wire>> = self.theta(state).chunks(64).map(<[Wire]>::to_vec).collect();
+        rho_pi(&mut lanes, |lane, r| (0..64).map(|z| lane[(z + 64 - r as usize) % 64]).collect());
+        let mut state = Vec::with_capacity(STATE_BITS);
+        for row in lanes.chunks(5) {
+            for (x, lane) in row.iter().enumerate() {
+                for (z, &a) in lane.iter().enumerate() {
+                    let t = self.and_not(row[(x + 1) % 5][z], row[(x + 2) % 5][z]);
+                    state.push(self.xor(a, t));
+                }
+            }
+        }
+        // ι：ラウンド定数のビットが 1 のところだけ反転する
+        for z in (0..64).filter(|z| (rc >> z) & 1 == 1) {
+            state[z] = self.not(state[z]);
+        }
+        state
+    }
+
+    /// θ：各ビットに隣の 2 列のパリティを足す。パリティは符号の積で取る
+    fn theta(&mut self, state: &[Wire]) -> Vec<Wire> {
+        let signs: Vec<Wire> = state.iter().map(|&a| self.sign(a)).collect();
+        let index = |lane: usize, z: usize| 64 * lane + z;
+        let columns: Vec<Vec<Wire>> = (0..5)
+            .map(|x| {
+                (0..64)
+                    .map(|z| {
+                        let column: Vec<Wire> = (0..5).map(|y| signs[index(x + 5 * y, z)]).collect();
+                        self.builder.product(&column)
+                    })
+                    .collect()
+            })
+            .collect();
+        (0..STATE_BITS)
+            .map(|i| {
+                let (x, z) = (i / 64 % 5, i % 64);
+                let factors = [signs[i], columns[(x + 4) % 5][z], columns[(x + 1) % 5][(z + 63) % 64]];
+                let s = self.builder.product(&factors);
+                self.bit(s)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,231 @@
+// src/gadgets/lookup.rs
+//
+// 対数微分（LogUp）による lookup の証明。
+//
+// witness の列 w（2^n 個）の全ての値が表 t に含まれることを，transcript から引いた x での有理関数の等式
+//   Σ_i 1 / (x + w_i) = Σ_j m_j / (x + t_j)    （m_j は t_j が w に現れる回数）
+// に帰着させる。両辺の分数の和は，隣り合う 2 つの分数 p_1/q_1 + p_2/q_2 = (p_1·q_2 + p_2·q_1) / (q_1·q_2)
+// を 1 段とする二分木回路（`fractional_sum_circuit`）で求め，`circuit_prover` の層ごとの還元で証明する。
+// 1 段は積の層 (p_1·q_2, p_2·q_1, q_1·q_2) と和の層 (p_1·q_2 + p_2·q_1, q_1·q_2) の 2 層になる。
+//
+// 回路の入力は分子の列と分母の列を並べた p || q なので，入力層の 1 点 (b, r) での主張は
+// (1 - b)·p̃(r) + b·q̃(r) の形になる。witness 側は p = 1, q = x + w なので w̃(r) の主張（`LookupClaim`）に，
+// 表の側は公開された t と証明に含めた m から検証者が直接確かめる。
+// 表の長さは先頭の値を繰り返して 2 のべきに埋め，埋めた位置の回数は 0 とする。
+//
+// x は witness を決めた後に引かなければならない（x を見てから表にない w を等式に合わせられる）。
+// 既定の `prove_lookup` / `verify_lookup` は witness の値そのものを transcript に吸収し，
+// `_with` の版は呼び出し側が witness へのコミットメントを先に吸収しておく。
+
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::PrimeField;
+use std::collections::HashMap;
+
+use crate::circuit::{Circuit, Gate, Layer};
+use crate::circuit_prover::{product_forms, ClaimReduction, GKRProof, GKRProver, GKRVerifier};
+use crate::error::Error;
+use crate::ml_extension::{DenseMLE, IndexOrder};
+use crate::transcript::Transcript;
+
+/// lookup の証明を初期化するラベル
+pub const LOOKUP_LABEL: &[u8] = b"gkr-logup";
+
+/// 層の間の主張の還元方法（入力側に 1 点の主張だけを残す）
+const REDUCTION: ClaimReduction = ClaimReduction::LineRestriction;
+
+/// lookup の証明
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LookupProof<F: PrimeField = ScalarField> {
+    /// 2 のべきに埋めた表の各値が witness に現れる回数
+    pub multiplicities: Vec<F>,
+    /// Σ_i 1 / (x + w_i) の回路の証明（出力は分子と分母）
+    pub witness_proof: GKRProof<F>,
+    /// Σ_j m_j / (x + t_j) の回路の証明（出力は分子と分母）
+    pub table_proof: GKRProof<F>,
+}
+
+/// 検証の後に残る，witness の MLE w̃ についての主張 w̃(point) = value
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LookupClaim<F: PrimeField = ScalarField> {
+    pub point: Vec<F>,
+    pub value: F,
+}
+
+impl<F: PrimeField> LookupClaim<F> {
+    /// 平文の witness で主張を確かめる
+    pub fn verify_against(&self, witness: &DenseMLE<F>) -> Result<(), Error> {
+        self.verify_with_oracle(|point| witness.evaluate(point))
+    }
+
+    /// `verify_against` のオラクル版。クロージャは w̃(point) を返す
+    pub fn verify_with_oracle(&self, w: impl FnOnce(&[F]) -> F) -> Result<(), Error> {
+        if w(&self.point) != self.value {
+            return Err(Error::EvaluationMismatch("witness at the final claim"));
+        }
+        Ok(())
+    }
+}
+
+/// 2^n 個の分数 p_i / q_i の和を求める回路（入力は p || q，出力は [分子, 分母]，深さ 2n）
+pub fn fractional_sum_circuit(num_vars: usize) -> Circuit {
+    let mut layers = Vec::with_capacity(2 * num_vars);
+    for k in 0..num_vars {
+        // 2^(k+1) 個の分数を 2^k 個にする段。和の層は [p_1·q_2, p_2·q_1, q_1·q_2] を読む
+        let (h, m) = (1 << k, 2 << k);
+        let numerators = (0..h).map(|i| Gate::Add(i, h + i));
+        let denominators = (0..h).map(|i| Gate::Input(2 * h + i));
+        layers.push(Layer { gates: numerators.chain(denominators).collect() });
+        let cross = |i: usize, j: usize| Gate::Mul(i, m + j);
+        let gates = (0..h)
+            .map(|i| cross(2 * i, 2 * i + 1))
+            .chain((0..h).map(|i| cross(2 * i + 1, 2 * i)))
+            .chain((0..h).map(|i| Gate::Mul(m + 2 * i, m + 2 * i + 1)))
+            .collect();
+        layers.push(Layer { gates });
+    }
+    Circuit { num_inputs: 2 << num_vars, layers }
+}
+
+/// witness の全ての値が `table` に含まれることを証明する（witness の値を transcript に吸収する）
+pub fn prove_lookup<F: PrimeField>(witness: &DenseMLE<F>, table: &[F]) -> Result<LookupProof<F>, Error> {
+    let mut transcript = Transcript::new(LOOKUP_LABEL);
+    absorb_witness(&mut transcript, witness);
+    prove_lookup_with(witness, table, &mut transcript)
+}
+
+/// 呼び出し側の transcript の上で lookup を証明する（witness へのコミットメントを先に吸収しておく）
+///
+/// 表にない値があれば，その添字（先頭の変数を最上位ビットとする）を `Error::NotInTable` で返す。
+pub fn prove_lookup_with<F: PrimeField>(
+    witness: &DenseMLE<F>,
+    table: &[F],
+    transcript: &mut Transcript,
+) -> Result<LookupProof<F>, Error> {
+    assert!(witness.num_vars > 0, "a lookup needs at least two witness values");
+    let witness = witness.to_order(IndexOrder::BigEndian).evaluations;
+    let table = padded_table(table)?;
+    let multiplicities = multiplicities(&witness, &table)?;
+    let x = lookup_challenge(transcript, witness.len(), &table, &multiplicities);
+
+    let numerators = vec![F::one(); witness.len()];
+    let witness_proof = prove_fractional_sum(&numerators, &witness, x, transcript);
+    let table_proof = prove_fractional_sum(&multiplicities, &table, x, transcript);
+    Ok(LookupProof { multiplicities, witness_proof, table_proof })
+}
+
+/// `prove_lookup` の証明を平文の witness で検証する（最後の主張も witness で確かめる）
+pub fn verify_lookup<F: PrimeField>(
+    witness: &DenseMLE<F>,
+    table: &[F],
+    proof: &LookupProof<F>,
+) -> Result<(), Error> {
+    let mut transcript = Transcript::new(LOOKUP_LABEL);
+    absorb_witness(&mut transcript, witness);
+    verify_lookup_with(witness.num_vars, table, proof, &mut transcript)?.verify_against(witness)
+}
+
+/// `prove_lookup_with` の証明を同じ状態の transcript で検証し，witness の MLE についての主張を返す
+///
+/// transcript が witness へのコミットメントを含まなければ，主張は witness に縛られない。
+pub fn verify_lookup_with<F: PrimeField>(
+    num_vars: usize,
+    table: &[F],
+    proof: &LookupProof<F>,
+    transcript: &mut Transcript,
+) -> Result<LookupClaim<F>, Error> {
+    let table = padded_table(table)?;
+    if proof.multiplicities.len() != table.len() {
+        return Err(Error::LengthMismatch {
+            what: "multiplicities",
+            expected: table.len(),
+            found: proof.multiplicities.len(),
+        });
+    }
+    let x = lookup_challenge(transcript, 1 << num_vars, &table, &proof.multiplicities);
+
+    let witness_circuit = fractional_sum_circuit(num_vars);
+    witness_circuit.validate()?;
+    let (reduced, witness_claim) =
+        GKRVerifier::verify_circuit_layers(&witness_circuit, &proof.witness_proof, REDUCTION, transcript)?;
+    let table_circuit = fractional_sum_circuit(table.len().trailing_zeros() as usize);
+    let (table_reduced, table_claim) =
+        GKRVerifier::verify_circuit_layers(&table_circuit, &proof.table_proof, REDUCTION, transcript)?;
+    // 表の側の入力 m || (x + t) は公開されているので直接確かめる
+    if table_reduced.evaluate(&fractional_inputs(&proof.multiplicities, &table, x)) != table_claim {
+        return Err(Error::EvaluationMismatch("table at the final claim"));
+    }
+
+    let (p_w, q_w) = (proof.witness_proof.outputs[0], proof.witness_proof.outputs[1]);
+    let (p_t, q_t) = (proof.table_proof.outputs[0], proof.table_proof.outputs[1]);
+    if q_w.is_zero() || q_t.is_zero() {
+        return Err(Error::Transcript("lookup challenge hits a denominator"));
+    }
+    if p_w * q_t != p_t * q_w {
+        return Err(Error::EvaluationMismatch("sums of the logarithmic derivatives"));
+    }
+
+    // 入力層の主張 (1 - b)·1 + b·(x + w̃(r)) から w̃(r) を取り出す
+    let (b, r) = reduced.points[0].split_first().unwrap();
+    let inverse = b.inverse().ok_or(Error::Transcript("degenerate challenge for the denominator half"))?;
+    let value = (witness_claim - F::one() + b) * inverse - x;
+    Ok(LookupClaim { point: r.to_vec(), value })
+}
+
+/// 表を先頭の値で 2 のべき（2 以上）の長さに埋める
+///
+/// 0 で埋めると表にない 0 を引けてしまうので，表にある値を繰り返す。
+fn padded_table<F: PrimeField>(table: &[F]) -> Result<Vec<F>, Error> {
+    let first = *table.first().ok_or(Error::InvalidCircuit("lookup table is empty"))?;
+    let mut padded = table.to_vec();
+    padded.resize(table.len().next_power_of_two().max(2), first);
+    Ok(padded)
+}
+
+/// 表の各値が witness に現れる回数（同じ値が表に複数あれば先頭の位置に数える）
+fn multiplicities<F: PrimeField>(witness: &[F], table: &[F]) -> Result<Vec<F>, Error> {
+    let mut position = HashMap::with_capacity(table.len());
+    for (j, t) in table.iter().enumerate() {
+        position.entry(*t).or_insert(j);
+    }
+    let mut counts = vec![0u64; table.len()];
+    for (index, w) in witness.iter().enumerate() {
+        let j = position.get(w).ok_or(Error::NotInTable { index })?;
+        counts[*j] += 1;
+    }
+    Ok(counts.into_iter().map(F::from).collect())
+}
+
+/// 既定の入口で witness の値を吸収する（コミットメントの代わり）
+fn absorb_witness<F: PrimeField>(transcript: &mut Transcript, witness: &DenseMLE<F>) {
+    transcript.append_fields(b"lookup_witness", &witness.to_order(IndexOrder::BigEndian).evaluations);
+}
+
+/// 表，回数，witness の長さを吸収して x を引く
+fn lookup_challenge<F: PrimeField>(
+    transcript: &mut Transcript,
+    witness_len: usize,
+    table: &[F],
+    multiplicities: &[F],
+) -> F {
+    transcript.append_message(b"lookup_witness_len", &(witness_len as u64).to_le_bytes());
+    transcript.append_fields(b"lookup_table", table);
+    transcript.append_fields(b"lookup_multiplicities", multiplicities);
+    transcript.challenge_field(b"lookup_challenge")
+}
+
+/// 分数の和の回路の入力 p || (x + values)
+fn fractional_inputs<F: PrimeField>(numerators: &[F], values: &[F], x: F) -> Vec<F> {
+    numerators.iter().copied().chain(values.iter().map(|v| x + v)).collect()
+}
+
+/// Σ_i p_i / (x + values_i) を `fractional_sum_circuit` で求め，transcript の上で証明する
+pub fn prove_fractional_sum<F: PrimeField>(
+    numerators: &[F],
+    values: &[F],
+    x: F,
+    transcript: &mut Transcript,
+) -> GKRProof<F> {
+    let circuit = fractional_sum_circuit(values.len().trailing_zeros() as usize);
+    let layer_values = circuit.evaluate(&fractional_inputs(numerators, values, x));
+    GKRProver::prove_layers(&circuit, &layer_values, &product_forms(&circuit), REDUCTION, transcript).0
+}
@@ -0,0 +1,163 @@
+// src/gadgets/matmul.rs
+//
+// 行列積 C = A·B の専用の sum-check（Thaler'13）。
+//
+// 行列は行の添字を先頭（上位）の変数，列の添字を末尾の変数とする MLE で表す。
+// transcript から引いた行の点 r_1 と列の点 r_2 で
+//   C̃(r_1, r_2) = Σ_k Ã(r_1, k)·B̃(k, r_2)
+// が成り立つので，Ã の行の変数を r_1 に，B̃ の列の変数を r_2 に固定した 2 つの表（どちらも O(n^2) で作れる）の
+// 積の和を `VirtualPolynomial` の sum-check で証明する。sum-check 自体は内側の次元 n の表の上で O(n) なので，
+// 一般の回路に展開したとき（O(n^3) 個の乗算ゲート）と違い，Prover の手間は行列を読む O(n^2) で済む。
+//
+// 検証者に残るのは Ã(r_1, s), B̃(s, r_2), C̃(r_1, r_2) の 3 つの主張（`MatMulClaims`）で，
+// 行列へのコミットメントの開示か平文の行列で確かめる。
+
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::{Field, PrimeField};
+
+use crate::error::Error;
+use crate::ml_extension::{DenseMLE, IndexOrder};
+use crate::transcript::Transcript;
+use crate::virtual_poly::VirtualPolynomial;
+
+/// 行列積の証明を初期化するラベル
+pub const MATMUL_LABEL: &[u8] = b"gkr-matmul";
+
+/// (2^rows × 2^inner) と (2^inner × 2^cols) の行列の積の形（各次元の変数の数）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MatMulShape {
+    pub row_vars: usize,
+    pub inner_vars: usize,
+    pub col_vars: usize,
+}
+
+impl MatMulShape {
+    /// 2^log_n 次の正方行列どうしの積
+    pub fn square(log_n: usize) -> Self {
+        MatMulShape { row_vars: log_n, inner_vars: log_n, col_vars: log_n }
+    }
+}
+
+/// 行列積の証明
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MatMulProof<F: PrimeField = ScalarField> {
+    /// 主張する C̃(r_1, r_2)
+    pub claimed_value: F,
+    /// 内側の変数についての sum-check のメッセージ
+    pub msgs: Vec<Vec<F>>,
+    /// Ã(r_1, s)
+    pub a_value: F,
+    /// B̃(s, r_2)
+    pub b_value: F,
+}
+
+/// 検証の後に残る，3 つの行列の MLE についての主張
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MatMulClaims<F: PrimeField = ScalarField> {
+    pub row_point: Vec<F>,
+    pub col_point: Vec<F>,
+    /// sum-check のチャレンジ s（内側の添字の点）
+    pub inner_point: Vec<F>,
+    /// Ã(r_1, s)
+    pub a_value: F,
+    /// B̃(s, r_2)
+    pub b_value: F,
+    /// C̃(r_1, r_2)
+    pub c_value: F,
+}
+
+impl<F: PrimeField> MatMulClaims<F> {
+    /// 平文の A, B, C で主張を確かめる
+    pub fn verify_against(&self, a: &DenseMLE<F>, b: &DenseMLE<F>, c: &DenseMLE<F>) -> Result<(), Error> {
+        self.verify_with_oracles(|p| a.evaluate(p), |p| b.evaluate(p), |p| c.evaluate(p))
+    }
+
+    /// `verify_against` のオラクル版。各クロージャは Ã, B̃, C̃ の与えられた点での値を返す
+    pub fn verify_with_oracles(
+        &self,
+        a: impl FnOnce(&[F]) -> F,
+        b: impl FnOnce(&[F]) -> F,
+        c: impl FnOnce(&[F]) -> F,
+    ) -> Result<(), Error> {
+        if a(&[self.row_point.as_slice(), &self.inner_point].concat()) != self.a_value {
+            return Err(Error::EvaluationMismatch("A at (r_1, s)"));
+        }
+        if b(&[self.inner_point.as_slice(), &self.col_point].concat()) != self.b_value {
+            return Err(Error::EvaluationMismatch("B at (s, r_2)"));
+        }
+        if c(&[self.row_point.as_slice(), &self.col_point].concat()) != self.c_value {
+            return Err(Error::EvaluationMismatch("C at (r_1, r_2)"));
+        }
+        Ok(())
+    }
+}
+
+/// 行ごとの値の列から行列の MLE を作る（行と列の数は 2 のべき）
+pub fn matrix_mle<F: Field>(rows: &[Vec<F>]) -> DenseMLE<F> {
+    assert!(rows.len().is_power_of_two() && rows[0].len().is_power_of_two(), "dimensions must be powers of two");
+    assert!(rows.iter().all(|row| row.len() == rows[0].len()), "rows must have the same length");
+    let num_vars = (rows.len() * rows[0].len()).trailing_zeros() as usize;
+    DenseMLE::from_evaluations_vec(num_vars, rows.concat())
+}
+
+/// C = A·B の MLE を平文で求める（証明には使わない。テストや witness の生成用）
+pub fn multiply<F: Field>(a: &DenseMLE<F>, b: &DenseMLE<F>, shape: MatMulShape) -> DenseMLE<F> {
+    let (k, p) = (1 << shape.inner_vars, 1 << shape.col_vars);
+    let (a, b) = (a.to_order(IndexOrder::BigEndian), b.to_order(IndexOrder::BigEndian));
+    DenseMLE::from_fn(shape.row_vars + shape.col_vars, |index| {
+        let (i, j) = (index / p, index % p);
+        (0..k).map(|l| a.evaluations[i * k + l] * b.evaluations[l * p + j]).sum()
+    })
+}
+
+/// C = A·B を証明する（呼び出し側の transcript には C へのコミットメントを先に吸収しておく）
+pub fn prove_matmul<F: PrimeField>(
+    a: &DenseMLE<F>,
+    b: &DenseMLE<F>,
+    shape: MatMulShape,
+    transcript: &mut Transcript,
+) -> MatMulProof<F> {
+    assert_eq!(a.num_vars, shape.row_vars + shape.inner_vars, "A does not match the shape");
+    assert_eq!(b.num_vars, shape.inner_vars + shape.col_vars, "B does not match the shape");
+    let (row_point, col_point) = outer_points(transcript, shape);
+    // Ã(r_1, ·) と B̃(·, r_2) はどちらも行列を 1 度なめるだけで作れる
+    let a_row = a.fix_variables(&row_point);
+    let b_col = b.fix_last_variables(&col_point);
+    let mut poly = VirtualPolynomial::new(shape.inner_vars);
+    poly.add_product(F::one(), [a_row.clone(), b_col.clone()]);
+    let (claimed_value, msgs, inner_point) = poly.prove(transcript);
+    let (a_value, b_value) = (a_row.evaluate(&inner_point), b_col.evaluate(&inner_point));
+    transcript.append_fields(b"matmul_final_evals", &[a_value, b_value]);
+    MatMulProof { claimed_value, msgs, a_value, b_value }
+}
+
+/// `prove_matmul` の証明を同じ状態の transcript で検証し，3 つの行列についての主張を返す
+pub fn verify_matmul<F: PrimeField>(
+    shape: MatMulShape,
+    proof: &MatMulProof<F>,
+    transcript: &mut Transcript,
+) -> Result<MatMulClaims<F>, Error> {
+    let (row_point, col_point) = outer_points(transcript, shape);
+    let subclaim = VirtualPolynomial::verify(shape.inner_vars, 2, proof.claimed_value, &proof.msgs, transcript)?;
+    if proof.a_value * proof.b_value != subclaim.expected_value {
+        return Err(Error::EvaluationMismatch("product of the final evaluations"));
+    }
+    transcript.append_fields(b"matmul_final_evals", &[proof.a_value, proof.b_value]);
+    Ok(MatMulClaims {
+        row_point,
+        col_point,
+        inner_point: subclaim.point,
+        a_value: proof.a_value,
+        b_value: proof.b_value,
+        c_value: proof.claimed_value,
+    })
+}
+
+/// 形を吸収し，C の行の点 r_1 と列の点 r_2 を引く
+fn outer_points<F: PrimeField>(transcript: &mut Transcript, shape: MatMulShape) -> (Vec<F>, Vec<F>) {
+    let dims = [shape.row_vars, shape.inner_vars, shape.col_vars].map(|v| v as u64);
+    transcript.append_message(b"matmul_shape", &dims.iter().flat_map(|d| d.to_le_bytes()).collect::<Vec<u8>>());
+    let row_point = (0..shape.row_vars).map(|_| transcript.challenge_field(b"matmul_row_point")).collect();
+    let col_point = (0..shape.col_vars).map(|_| transcript.challenge_field(b"matmul_col_point")).collect();
+    (row_point, col_point)
+}
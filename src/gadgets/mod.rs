@@ -0,0 +1,17 @@
+// src/gadgets/mod.rs
+//
+// GKR の上に組んだ証明のガジェット。各モジュールは決まった形の回路や sum-check を組み立てて，
+// 「Prover の関数」と「入力の MLE についての主張を返す Verifier の関数」の組を提供する。
+
+pub mod grand_product;
+pub mod keccak;
+pub mod lookup;
+pub mod matmul;
+pub mod nn;
+pub mod permutation;
+pub mod poseidon2;
+pub mod range_check;
+pub mod sha256;
+pub mod uint;
+
+pub use range_check::range_check;
@@ -0,0 +1,312 @@
+// src/gadgets/nn.rs
+//
+// 固定小数点のニューラルネットの推論の証明（全結合層，ReLU，再スケール）。
+//
+// 活性化は (特徴 × バッチ) の行列の MLE（特徴の添字が先頭の変数）で表し，入力から順に並べた列をトレースと呼ぶ。
+// 層ごとに，隣り合う 2 つの活性化 X（入力側）と Y（出力側）の関係を専用のガジェットで証明する。
+//   - 全結合層 Y = W·X + b·1ᵀ は `matmul` の sum-check で，Ỹ(r_1, r_2) = (W·X)~(r_1, r_2) + b̃(r_1) を使う。
+//   - ReLU は組 (x, y) を β で x + β·y に符号化し，表 {q + β·max(q, 0)} への `lookup` で証明する。
+//   - 再スケール y = ⌊x / 2^s⌋ は余り x - 2^s·y が [0, 2^s) にあることを `lookup` で証明する。
+// どの層の検証も活性化の MLE の 1 点での値（の線形結合）の主張（`TraceClaim`）を残すので，
+// 検証者はそれをトレースへのコミットメントの開示か平文のトレースで確かめる。
+// 重みとバイアスはネットワークの一部として検証者が持ち，直接評価する。
+//
+// 値は [-2^(bits-1), 2^(bits-1)) の符号付き整数を体に埋め込んだもので，小数部は `frac_bits` ビット。
+// 全結合層の出力の小数部は 2·frac_bits になるので，ReLU の前に `rescale` で戻す（バイアスも 2·frac_bits で与える）。
+
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::PrimeField;
+
+use crate::error::Error;
+use crate::gadgets::lookup::{prove_lookup_with, verify_lookup_with, LookupProof};
+use crate::gadgets::matmul::{multiply, prove_matmul, verify_matmul, MatMulProof, MatMulShape};
+use crate::ml_extension::{DenseMLE, IndexOrder};
+use crate::transcript::Transcript;
+
+/// 推論の証明を初期化するラベル
+pub const INFERENCE_LABEL: &[u8] = b"gkr-inference";
+
+/// 固定小数点の表現（値のビット数と小数部のビット数）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedPoint {
+    pub bits: usize,
+    pub frac_bits: usize,
+}
+
+impl FixedPoint {
+    /// 実数を最も近い固定小数点の値にする
+    pub fn quantize<F: PrimeField>(&self, x: f64) -> F {
+        from_signed((x * (1u64 << self.frac_bits) as f64).round() as i64)
+    }
+
+    /// 小数部 `frac_bits` の値を実数に戻す
+    pub fn dequantize<F: PrimeField>(&self, v: F) -> f64 {
+        to_signed(v) as f64 / (1u64 << self.frac_bits) as f64
+    }
+
+    /// ReLU の表の入力側の値 -2^(bits-1), ..., 2^(bits-1) - 1
+    fn range(&self) -> impl Iterator<Item = i64> {
+        let half = 1i64 << (self.bits - 1);
+        -half..half
+    }
+}
+
+/// 符号付き整数を体に埋め込む（負の値は p - |x|）
+pub fn from_signed<F: PrimeField>(x: i64) -> F {
+    if x < 0 {
+        -F::from(x.unsigned_abs())
+    } else {
+        F::from(x as u64)
+    }
+}
+
+/// `from_signed` の逆。64 ビットに収まらない値は panic
+pub fn to_signed<F: PrimeField>(v: F) -> i64 {
+    let small = |x: F| {
+        let bigint = x.into_bigint();
+        let limbs = bigint.as_ref();
+        limbs[1..].iter().all(|l| *l == 0).then_some(limbs[0])
+    };
+    small(v)
+        .map(|u| u as i64)
+        .or_else(|| small(-v).map(|u| -(u as i64)))
+        .expect("value is outside the signed 64-bit range")
+}
+
+/// ネットワークの 1 層
+#[derive(Clone)]
+pub enum NnLayer<F: PrimeField = ScalarField> {
+    /// Y = W·X + b·1ᵀ（W は 2^out × 2^in の行列，b は長さ 2^out）
+    Dense { weights: DenseMLE<F>, bias: DenseMLE<F> },
+    /// y = max(x, 0)
+    Relu,
+    /// y = ⌊x / 2^frac_bits⌋
+    Rescale,
+}
+
+/// 層を順に積んだネットワーク
+#[derive(Clone)]
+pub struct Network<F: PrimeField = ScalarField> {
+    pub quantization: FixedPoint,
+    /// 入力の特徴の変数の数
+    pub input_vars: usize,
+    /// バッチの変数の数（2^batch_vars 個の入力をまとめて推論する）
+    pub batch_vars: usize,
+    pub layers: Vec<NnLayer<F>>,
+}
+
+impl<F: PrimeField> Network<F> {
+    pub fn new(quantization: FixedPoint, input_vars: usize, batch_vars: usize) -> Self {
+        Network { quantization, input_vars, batch_vars, layers: Vec::new() }
+    }
+
+    /// 全結合層を積む。出力の特徴の変数の数は `bias` から決まる
+    pub fn dense(mut self, weights: DenseMLE<F>, bias: DenseMLE<F>) -> Self {
+        let in_vars = self.output_vars();
+        assert_eq!(weights.num_vars, bias.num_vars + in_vars, "weights do not match the previous layer");
+        self.layers.push(NnLayer::Dense { weights, bias });
+        self
+    }
+
+    pub fn relu(mut self) -> Self {
+        self.layers.push(NnLayer::Relu);
+        self
+    }
+
+    pub fn rescale(mut self) -> Self {
+        self.layers.push(NnLayer::Rescale);
+        self
+    }
+
+    /// 各活性化の特徴の変数の数（先頭が入力，最後が出力）
+    pub fn feature_vars(&self) -> Vec<usize> {
+        let mut vars = vec![self.input_vars];
+        for layer in &self.layers {
+            let last = *vars.last().unwrap();
+            vars.push(match layer {
+                NnLayer::Dense { bias, .. } => bias.num_vars,
+                NnLayer::Relu | NnLayer::Rescale => last,
+            });
+        }
+        vars
+    }
+
+    fn output_vars(&self) -> usize {
+        *self.feature_vars().last().unwrap()
+    }
+
+    /// 入力から全ての活性化（トレース）を求める
+    pub fn forward(&self, input: &DenseMLE<F>) -> Vec<DenseMLE<F>> {
+        assert_eq!(input.num_vars, self.input_vars + self.batch_vars, "input does not match the network");
+        let mut trace = vec![input.to_order(IndexOrder::BigEndian)];
+        for (layer, vars) in self.layers.iter().zip(self.feature_vars()) {
+            let x = trace.last().unwrap();
+            let y = match layer {
+                NnLayer::Dense { weights, bias } => {
+                    let shape = self.dense_shape(bias, vars);
+                    let product = multiply(weights, x, shape);
+                    let bias = bias.to_order(IndexOrder::BigEndian);
+                    let batch_vars = self.batch_vars;
+                    DenseMLE::from_fn(product.num_vars, |i| product.evaluations[i] + bias.evaluations[i >> batch_vars])
+                }
+                NnLayer::Relu => map_values(x, |v| v.max(0)),
+                NnLayer::Rescale => map_values(x, |v| v.div_euclid(1 << self.quantization.frac_bits)),
+            };
+            trace.push(y);
+        }
+        trace
+    }
+
+    fn dense_shape(&self, bias: &DenseMLE<F>, in_vars: usize) -> MatMulShape {
+        MatMulShape { row_vars: bias.num_vars, inner_vars: in_vars, col_vars: self.batch_vars }
+    }
+
+    /// ReLU の表 {q + β·max(q, 0)}
+    fn relu_table(&self, beta: F) -> Vec<F> {
+        self.quantization.range().map(|q| from_signed::<F>(q) + beta * from_signed::<F>(q.max(0))).collect()
+    }
+
+    /// 再スケールの余りの表 0, ..., 2^frac_bits - 1
+    fn remainder_table(&self) -> Vec<F> {
+        (0..1u64 << self.quantization.frac_bits).map(F::from).collect()
+    }
+
+    fn scale(&self) -> F {
+        F::from(1u64 << self.quantization.frac_bits)
+    }
+}
+
+/// 表の値を符号付き整数として写す
+fn map_values<F: PrimeField>(x: &DenseMLE<F>, f: impl Fn(i64) -> i64) -> DenseMLE<F> {
+    DenseMLE::from_fn(x.num_vars, |i| from_signed(f(to_signed(x.evaluations[i]))))
+}
+
+/// 層ごとの証明
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LayerProof<F: PrimeField = ScalarField> {
+    Dense(MatMulProof<F>),
+    Relu(LookupProof<F>),
+    Rescale(LookupProof<F>),
+}
+
+/// 推論の証明（入力側の層から順）
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InferenceProof<F: PrimeField = ScalarField> {
+    pub layer_proofs: Vec<LayerProof<F>>,
+}
+
+/// トレースについての主張 Σ_(i, c) c·Ã_i(point) = value（A_i は i 番目の活性化）
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceClaim<F: PrimeField = ScalarField> {
+    pub terms: Vec<(usize, F)>,
+    pub point: Vec<F>,
+    pub value: F,
+}
+
+/// 検証の後に残るトレースについての主張の列
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InferenceClaims<F: PrimeField = ScalarField> {
+    pub claims: Vec<TraceClaim<F>>,
+}
+
+impl<F: PrimeField> InferenceClaims<F> {
+    /// 平文のトレースで主張を確かめる
+    pub fn verify_against(&self, trace: &[DenseMLE<F>]) -> Result<(), Error> {
+        self.verify_with_oracle(|i, point| trace[i].evaluate(point))
+    }
+
+    /// `verify_against` のオラクル版。クロージャは i 番目の活性化の Ã_i(point) を返す
+    pub fn verify_with_oracle(&self, mut activation: impl FnMut(usize, &[F]) -> F) -> Result<(), Error> {
+        for claim in &self.claims {
+            let combined: F = claim.terms.iter().map(|(i, c)| *c * activation(*i, &claim.point)).sum();
+            if combined != claim.value {
+                return Err(Error::EvaluationMismatch("activation at the final claim"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 入力から推論し，トレースと証明を返す
+///
+/// 呼び出し側の transcript にはトレースへのコミットメントを先に吸収しておく。
+/// 値が固定小数点の範囲を外れて ReLU の表に無いときは `Error::NotInTable` を返す。
+pub fn prove_inference<F: PrimeField>(
+    network: &Network<F>,
+    input: &DenseMLE<F>,
+    transcript: &mut Transcript,
+) -> Result<(Vec<DenseMLE<F>>, InferenceProof<F>), Error> {
+    let trace = network.forward(input);
+    let mut layer_proofs = Vec::with_capacity(network.layers.len());
+    for (i, (layer, vars)) in network.layers.iter().zip(network.feature_vars()).enumerate() {
+        transcript.append_message(b"inference_layer", &(i as u64).to_le_bytes());
+        let (x, y) = (&trace[i], &trace[i + 1]);
+        layer_proofs.push(match layer {
+            NnLayer::Dense { weights, bias } => {
+                LayerProof::Dense(prove_matmul(weights, x, network.dense_shape(bias, vars), transcript))
+            }
+            NnLayer::Relu => {
+                let beta: F = transcript.challenge_field(b"relu_beta");
+                let pairs = DenseMLE::from_fn(x.num_vars, |j| x.evaluations[j] + beta * y.evaluations[j]);
+                LayerProof::Relu(prove_lookup_with(&pairs, &network.relu_table(beta), transcript)?)
+            }
+            NnLayer::Rescale => {
+                let scale = network.scale();
+                let remainders = DenseMLE::from_fn(x.num_vars, |j| x.evaluations[j] - scale * y.evaluations[j]);
+                LayerProof::Rescale(prove_lookup_with(&remainders, &network.remainder_table(), transcript)?)
+            }
+        });
+    }
+    Ok((trace, InferenceProof { layer_proofs }))
+}
+
+/// `prove_inference` の証明を同じ状態の transcript で検証し，トレースについての主張を返す
+pub fn verify_inference<F: PrimeField>(
+    network: &Network<F>,
+    proof: &InferenceProof<F>,
+    transcript: &mut Transcript,
+) -> Result<InferenceClaims<F>, Error> {
+    if proof.layer_proofs.len() != network.layers.len() {
+        return Err(Error::LengthMismatch {
+            what: "layer proofs",
+            expected: network.layers.len(),
+            found: proof.layer_proofs.len(),
+        });
+    }
+    let mut claims = Vec::new();
+    let layers = network.layers.iter().zip(network.feature_vars()).zip(proof.layer_proofs.iter());
+    for (i, ((layer, vars), layer_proof)) in layers.enumerate() {
+        transcript.append_message(b"inference_layer", &(i as u64).to_le_bytes());
+        let num_vars = vars + network.batch_vars;
+        match (layer, layer_proof) {
+            (NnLayer::Dense { weights, bias }, LayerProof::Dense(p)) => {
+                let c = verify_matmul(network.dense_shape(bias, vars), p, transcript)?;
+                if weights.evaluate(&[c.row_point.as_slice(), &c.inner_point].concat()) != c.a_value {
+                    return Err(Error::EvaluationMismatch("weights at the final claim"));
+                }
+                claims.push(TraceClaim {
+                    terms: vec![(i, F::one())],
+                    point: [c.inner_point, c.col_point.clone()].concat(),
+                    value: c.b_value,
+                });
+                claims.push(TraceClaim {
+                    terms: vec![(i + 1, F::one())],
+                    point: [c.row_point.as_slice(), &c.col_point].concat(),
+                    value: c.c_value + bias.evaluate(&c.row_point),
+                });
+            }
+            (NnLayer::Relu, LayerProof::Relu(p)) => {
+                let beta: F = transcript.challenge_field(b"relu_beta");
+                let c = verify_lookup_with(num_vars, &network.relu_table(beta), p, transcript)?;
+                claims.push(TraceClaim { terms: vec![(i, F::one()), (i + 1, beta)], point: c.point, value: c.value });
+            }
+            (NnLayer::Rescale, LayerProof::Rescale(p)) => {
+                let c = verify_lookup_with(num_vars, &network.remainder_table(), p, transcript)?;
+                let terms = vec![(i, F::one()), (i + 1, -network.scale())];
+                claims.push(TraceClaim { terms, point: c.point, value: c.value });
+            }
+            _ => return Err(Error::MalformedProof("layer proof does not match the layer")),
+        }
+    }
+    Ok(InferenceClaims { claims })
+}
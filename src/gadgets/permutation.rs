@@ -0,0 +1,167 @@
+// src/gadgets/permutation.rs
+//
+// 総積（`grand_product`）による置換・多重集合の等しさの証明。
+//
+// 2 つの列 a, b（どちらも 2^n 個）が多重集合として等しいことを，transcript から引いた γ での
+//   Π_i (γ - a_i) = Π_i (γ - b_i)
+// に帰着させ，両辺を `grand_product` で証明する。
+// k 個の列を並べた組 (a^(1)_i, ..., a^(k)_i) を比べるときは，位置ごとのチャレンジ β_j で
+// a_i = Σ_j β_j·a^(j)_i と 1 つの値に符号化する（β_1 = 1）。Plonkish の copy constraint なら
+// (値, 位置の番号) と (値, 置換した位置の番号) の 2 列ずつを比べればよい。
+//
+// γ - a の MLE は γ - Σ_j β_j·ã^(j) なので，総積の検証で残る主張はそのまま
+// 各列の MLE の 1 点での値の線形結合についての主張（`TupleClaim`）になり，コミットメントの開示で確かめられる。
+//
+// β, γ は列を決めた後に引かなければならない（γ を見てから等式を満たす置換でない列を選べる）。
+// 既定の `prove_permutation` / `verify_permutation` は両辺の列そのものを transcript に吸収し，
+// `_multiset_equality` は呼び出し側が列へのコミットメントを先に吸収しておく。
+
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::PrimeField;
+
+use crate::error::Error;
+use crate::gadgets::grand_product::{prove_product_with, verify_product_with, ProductProof};
+use crate::ml_extension::{DenseMLE, IndexOrder};
+use crate::transcript::Transcript;
+
+/// 置換の証明を初期化するラベル
+pub const PERMUTATION_LABEL: &[u8] = b"gkr-permutation";
+
+/// 置換の証明。両辺の Π_i (γ - a_i) の総積の証明
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PermutationProof<F: PrimeField = ScalarField> {
+    pub lhs: ProductProof<F>,
+    pub rhs: ProductProof<F>,
+}
+
+/// 検証の後に残る，列の組の MLE についての主張 Σ_j coeffs_j·ã^(j)(point) = value
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TupleClaim<F: PrimeField = ScalarField> {
+    pub point: Vec<F>,
+    /// 列ごとの係数 β_j
+    pub coeffs: Vec<F>,
+    pub value: F,
+}
+
+impl<F: PrimeField> TupleClaim<F> {
+    /// 平文の列で主張を確かめる
+    pub fn verify_against(&self, columns: &[DenseMLE<F>]) -> Result<(), Error> {
+        if columns.len() != self.coeffs.len() {
+            return Err(Error::LengthMismatch { what: "columns", expected: self.coeffs.len(), found: columns.len() });
+        }
+        self.verify_with_oracle(|j, point| columns[j].evaluate(point))
+    }
+
+    /// `verify_against` のオラクル版。クロージャは j 番目の列の ã^(j)(point) を返す
+    pub fn verify_with_oracle(&self, mut column: impl FnMut(usize, &[F]) -> F) -> Result<(), Error> {
+        let combined: F = self.coeffs.iter().enumerate().map(|(j, c)| *c * column(j, &self.point)).sum();
+        if combined != self.value {
+            return Err(Error::EvaluationMismatch("columns at the final claim"));
+        }
+        Ok(())
+    }
+}
+
+/// 両辺の列の組についての主張
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PermutationClaims<F: PrimeField = ScalarField> {
+    pub lhs: TupleClaim<F>,
+    pub rhs: TupleClaim<F>,
+}
+
+impl<F: PrimeField> PermutationClaims<F> {
+    /// 平文の両辺の列で主張を確かめる
+    pub fn verify_against(&self, lhs: &[DenseMLE<F>], rhs: &[DenseMLE<F>]) -> Result<(), Error> {
+        self.lhs.verify_against(lhs)?;
+        self.rhs.verify_against(rhs)
+    }
+}
+
+/// 列 a と b が多重集合として等しいことを証明する（両辺の列を transcript に吸収する）
+pub fn prove_permutation<F: PrimeField>(lhs: &DenseMLE<F>, rhs: &DenseMLE<F>) -> PermutationProof<F> {
+    let mut transcript = Transcript::new(PERMUTATION_LABEL);
+    absorb_columns(&mut transcript, lhs, rhs);
+    prove_multiset_equality(std::slice::from_ref(lhs), std::slice::from_ref(rhs), &mut transcript)
+}
+
+/// `prove_permutation` の証明を平文の列で検証する（最後の主張も両辺の列で確かめる）
+pub fn verify_permutation<F: PrimeField>(
+    lhs: &DenseMLE<F>,
+    rhs: &DenseMLE<F>,
+    proof: &PermutationProof<F>,
+) -> Result<(), Error> {
+    if lhs.num_vars != rhs.num_vars {
+        return Err(Error::LengthMismatch { what: "column variables", expected: lhs.num_vars, found: rhs.num_vars });
+    }
+    let mut transcript = Transcript::new(PERMUTATION_LABEL);
+    absorb_columns(&mut transcript, lhs, rhs);
+    verify_multiset_equality(lhs.num_vars, 1, proof, &mut transcript)?
+        .verify_against(std::slice::from_ref(lhs), std::slice::from_ref(rhs))
+}
+
+/// 列の組 (lhs[0]_i, ..., lhs[k-1]_i) と (rhs[0]_i, ..., rhs[k-1]_i) が多重集合として等しいことを証明する
+///
+/// 呼び出し側の transcript には列へのコミットメントを先に吸収しておく。
+pub fn prove_multiset_equality<F: PrimeField>(
+    lhs: &[DenseMLE<F>],
+    rhs: &[DenseMLE<F>],
+    transcript: &mut Transcript,
+) -> PermutationProof<F> {
+    assert!(!lhs.is_empty() && lhs.len() == rhs.len(), "both sides need the same positive number of columns");
+    let num_vars = lhs[0].num_vars;
+    assert!(lhs.iter().chain(rhs.iter()).all(|c| c.num_vars == num_vars), "columns need the same number of variables");
+    let (coeffs, gamma) = tuple_challenges(transcript, num_vars, lhs.len());
+    let lhs = prove_product_with(&shifted_tuples(lhs, &coeffs, gamma), transcript);
+    let rhs = prove_product_with(&shifted_tuples(rhs, &coeffs, gamma), transcript);
+    PermutationProof { lhs, rhs }
+}
+
+/// `prove_multiset_equality` の証明を同じ状態の transcript で検証する（各辺 k 列）
+///
+/// transcript が列へのコミットメントを含まなければ，主張は列に縛られない。
+pub fn verify_multiset_equality<F: PrimeField>(
+    num_vars: usize,
+    num_columns: usize,
+    proof: &PermutationProof<F>,
+    transcript: &mut Transcript,
+) -> Result<PermutationClaims<F>, Error> {
+    if num_columns == 0 {
+        return Err(Error::LengthMismatch { what: "columns", expected: 1, found: 0 });
+    }
+    let (coeffs, gamma) = tuple_challenges(transcript, num_vars, num_columns);
+    let product = proof.lhs.product();
+    if proof.rhs.product() != product {
+        return Err(Error::EvaluationMismatch("products of both sides"));
+    }
+    // γ - Σ_j β_j·ã^(j)(r) = e から Σ_j β_j·ã^(j)(r) = γ - e
+    let mut side = |proof: &ProductProof<F>| -> Result<TupleClaim<F>, Error> {
+        let claim = verify_product_with(num_vars, product, proof, transcript)?;
+        Ok(TupleClaim { point: claim.point, coeffs: coeffs.clone(), value: gamma - claim.value })
+    };
+    let lhs = side(&proof.lhs)?;
+    let rhs = side(&proof.rhs)?;
+    Ok(PermutationClaims { lhs, rhs })
+}
+
+/// 既定の入口で両辺の列を吸収する（コミットメントの代わり）
+fn absorb_columns<F: PrimeField>(transcript: &mut Transcript, lhs: &DenseMLE<F>, rhs: &DenseMLE<F>) {
+    transcript.append_fields(b"permutation_lhs", &lhs.to_order(IndexOrder::BigEndian).evaluations);
+    transcript.append_fields(b"permutation_rhs", &rhs.to_order(IndexOrder::BigEndian).evaluations);
+}
+
+/// 変数と列の数を吸収し，列の係数 β（先頭は 1）と γ を引く
+fn tuple_challenges<F: PrimeField>(transcript: &mut Transcript, num_vars: usize, num_columns: usize) -> (Vec<F>, F) {
+    transcript.append_message(b"permutation_num_vars", &(num_vars as u64).to_le_bytes());
+    transcript.append_message(b"permutation_num_columns", &(num_columns as u64).to_le_bytes());
+    let coeffs = std::iter::once(F::one())
+        .chain((1..num_columns).map(|_| transcript.challenge_field(b"permutation_beta")))
+        .collect();
+    (coeffs, transcript.challenge_field(b"permutation_gamma"))
+}
+
+/// γ - Σ_j β_j·columns[j] の表
+fn shifted_tuples<F: PrimeField>(columns: &[DenseMLE<F>], coeffs: &[F], gamma: F) -> DenseMLE<F> {
+    let columns: Vec<Vec<F>> = columns.iter().map(|c| c.to_order(IndexOrder::BigEndian).evaluations).collect();
+    let num_vars = columns[0].len().trailing_zeros() as usize;
+    DenseMLE::from_fn(num_vars, |i| gamma - columns.iter().zip(coeffs.iter()).map(|(c, beta)| *beta * c[i]).sum::<F>())
+}
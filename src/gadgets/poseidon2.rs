@@ -0,0 +1,257 @@
+// src/gadgets/poseidon2.rs
+//
+// Poseidon2 置換の層状回路。
+//
+// 状態の幅 t は 2, 3 と 4 の倍数（24 まで）。置換は外側の線形層 M_E を掛けてから，
+// 外側のラウンド R_F / 2 回（全ての要素に定数を足し，全ての要素に S-box x^α，M_E），
+// 内側のラウンド R_P 回（先頭の要素だけに定数と S-box，M_I），外側のラウンド R_F / 2 回の順に行う。
+// M_E は t = 2, 3 で circ(2, 1), circ(2, 1, 1)，t = 4k で対角に 2·M4，それ以外に M4 を並べたブロック行列。
+// M_I は全ての要素が 1 で対角が μ_i の行列で，(M_I·s)_i = Σ_j s_j + (μ_i - 1)·s_i と計算する。
+//
+// 回路は `CircuitBuilder` で組むので，ラウンド定数と μ_i - 1 は回路の入力の後ろに置く定数になり，
+// `BuiltCircuit::assign` が埋める。配線述語は `BuiltCircuit::wiring` にある。
+// `permute_wires` は組み立て中の回路に置換を足すので，Merkle パスのように置換を繋げた回路も組める。
+//
+// `Poseidon2Params::generated` の定数は `poseidon` と同じく SHA3-256 から導いた値で，参照実装とは互換でない。
+// 参照実装の定数を使うときは `Poseidon2Params::new` に渡す。
+
+use ark_ff::PrimeField;
+use sha3::{Digest, Sha3_256};
+use std::collections::HashMap;
+
+use crate::builder::{BuiltCircuit, CircuitBuilder, Wire};
+use crate::error::Error;
+use crate::poseidon::sbox_exponent;
+
+/// 外側のラウンドの既定の回数（前後に半分ずつ）
+pub const EXTERNAL_ROUNDS: usize = 8;
+/// 内側のラウンドの既定の回数
+pub const INTERNAL_ROUNDS: usize = 56;
+
+/// t = 4k の M_E を組む 4 × 4 の行列
+const M4: [[u64; 4]; 4] = [[5, 7, 1, 3], [4, 6, 1, 1], [1, 3, 5, 7], [1, 1, 4, 6]];
+
+/// 置換のパラメータ
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Poseidon2Params<F: PrimeField> {
+    pub width: usize,
+    pub alpha: u64,
+    /// 外側のラウンドごとの加算定数（R_F 行，各 t 個）
+    pub external_constants: Vec<Vec<F>>,
+    /// 内側のラウンドごとの先頭の要素への加算定数（R_P 個）
+    pub internal_constants: Vec<F>,
+    /// M_I の対角 μ_i（t 個）
+    pub internal_diagonal: Vec<F>,
+}
+
+impl<F: PrimeField> Poseidon2Params<F> {
+    /// 与えた定数でパラメータを作る（α は体から決める）
+    pub fn new(
+        width: usize,
+        external_constants: Vec<Vec<F>>,
+        internal_constants: Vec<F>,
+        internal_diagonal: Vec<F>,
+    ) -> Result<Self, Error> {
+        if !(width == 2 || width == 3 || (width.is_multiple_of(4) && width <= 24)) {
+            return Err(Error::InvalidCircuit("Poseidon2 width must be 2, 3 or a multiple of 4 up to 24"));
+        }
+        if !external_constants.len().is_multiple_of(2) {
+            return Err(Error::InvalidCircuit("Poseidon2 needs an even number of external rounds"));
+        }
+        if let Some(row) = external_constants.iter().find(|row| row.len() != width) {
+            return Err(Error::LengthMismatch { what: "external round constants", expected: width, found: row.len() });
+        }
+        if internal_diagonal.len() != width {
+            return Err(Error::LengthMismatch {
+                what: "internal diagonal",
+                expected: width,
+                found: internal_diagonal.len(),
+            });
+        }
+        Ok(Poseidon2Params {
+            width,
+            alpha: sbox_exponent::<F>(),
+            external_constants,
+            internal_constants,
+            internal_diagonal,
+        })
+    }
+
+    /// SHA3-256 から導いた定数でパラメータを作る
+    ///
+    /// t = 2, 3 の μ は参照実装と同じ (2, 3), (2, 2, 3)，t = 4k の μ は導いた値。
+    pub fn generated(width: usize, external_rounds: usize, internal_rounds: usize) -> Result<Self, Error> {
+        let mut index = 0u64;
+        let mut next = || {
+            index += 1;
+            constant::<F>(width, index)
+        };
+        let external_constants = (0..external_rounds).map(|_| (0..width).map(|_| next()).collect()).collect();
+        let internal_constants = (0..internal_rounds).map(|_| next()).collect();
+        let internal_diagonal = match width {
+            2 => vec![F::from(2u64), F::from(3u64)],
+            3 => vec![F::from(2u64), F::from(2u64), F::from(3u64)],
+            _ => (0..width).map(|_| next()).collect(),
+        };
+        Self::new(width, external_constants, internal_constants, internal_diagonal)
+    }
+
+    /// 既定のラウンド数（R_F = 8，R_P = 56）で `generated` を呼ぶ
+    pub fn with_default_rounds(width: usize) -> Result<Self, Error> {
+        Self::generated(width, EXTERNAL_ROUNDS, INTERNAL_ROUNDS)
+    }
+
+    /// 状態を置換する
+    pub fn permute(&self, state: &mut [F]) {
+        assert_eq!(state.len(), self.width, "state does not match the width");
+        let (first, last) = self.external_constants.split_at(self.external_constants.len() / 2);
+        self.external_layer(state);
+        for constants in first {
+            self.external_round(state, constants);
+        }
+        for c in &self.internal_constants {
+            state[0] = (state[0] + c).pow([self.alpha]);
+            let total: F = state.iter().sum();
+            for (s, mu) in state.iter_mut().zip(&self.internal_diagonal) {
+                *s = total + (*mu - F::one()) * *s;
+            }
+        }
+        for constants in last {
+            self.external_round(state, constants);
+        }
+    }
+
+    fn external_round(&self, state: &mut [F], constants: &[F]) {
+        for (s, c) in state.iter_mut().zip(constants) {
+            *s = (*s + c).pow([self.alpha]);
+        }
+        self.external_layer(state);
+    }
+
+    fn external_layer(&self, state: &mut [F]) {
+        let prev = state.to_vec();
+        for (i, s) in state.iter_mut().enumerate() {
+            *s = (0..self.width).map(|j| F::from(external_entry(self.width, i, j)) * prev[j]).sum();
+        }
+    }
+}
+
+/// M_E の (i, j) 成分
+fn external_entry(width: usize, i: usize, j: usize) -> u64 {
+    match width {
+        2 | 3 => 1 + (i == j) as u64,
+        _ => M4[i % 4][j % 4] * (1 + (i / 4 == j / 4) as u64),
+    }
+}
+
+/// 幅 `width` の添字 i の定数
+fn constant<F: PrimeField>(width: usize, i: u64) -> F {
+    let mut hasher = Sha3_256::new();
+    hasher.update(b"gkr-poseidon2");
+    hasher.update((width as u64).to_le_bytes());
+    hasher.update(i.to_le_bytes());
+    F::from_le_bytes_mod_order(&hasher.finalize())
+}
+
+/// 組み立て中の回路に，状態 `state` の配線の置換を足して出力側の配線を返す
+pub fn permute_wires<F: PrimeField>(
+    builder: &mut CircuitBuilder<F>,
+    params: &Poseidon2Params<F>,
+    state: &[Wire],
+) -> Vec<Wire> {
+    assert_eq!(state.len(), params.width, "state does not match the width");
+    let mut gadget = WireGadget { builder, params, small: HashMap::new() };
+    let (first, last) = params.external_constants.split_at(params.external_constants.len() / 2);
+    let mut state = gadget.external_layer(state);
+    for constants in first {
+        state = gadget.external_round(&state, constants);
+    }
+    for c in &params.internal_constants {
+        let c = gadget.builder.constant(*c);
+        state[0] = gadget.builder.add(state[0], c);
+        state[0] = gadget.sbox(state[0]);
+        state = gadget.internal_layer(&state);
+    }
+    for constants in last {
+        state = gadget.external_round(&state, constants);
+    }
+    state
+}
+
+/// 1 回の置換だけの回路（入力 t 個，出力 t 個）
+pub fn circuit<F: PrimeField>(params: &Poseidon2Params<F>) -> Result<BuiltCircuit<F>, Error> {
+    let mut builder = CircuitBuilder::new();
+    let state: Vec<Wire> = (0..params.width).map(|_| builder.input()).collect();
+    for w in permute_wires(&mut builder, params, &state) {
+        builder.output(w);
+    }
+    builder.build()
+}
+
+/// 配線の上の線形層と S-box。小さい整数の定数ゲートは使い回す
+struct WireGadget<'a, F: PrimeField> {
+    builder: &'a mut CircuitBuilder<F>,
+    params: &'a Poseidon2Params<F>,
+    small: HashMap<u64, Wire>,
+}
+
+impl<F: PrimeField> WireGadget<'_, F> {
+    fn sbox(&mut self, x: Wire) -> Wire {
+        self.builder.product(&vec![x; self.params.alpha as usize])
+    }
+
+    /// m·x（m は小さい整数）
+    fn scale(&mut self, m: u64, x: Wire) -> Wire {
+        if m == 1 {
+            return x;
+        }
+        let builder = &mut *self.builder;
+        let m = *self.small.entry(m).or_insert_with(|| builder.small_constant(m));
+        self.builder.mul(m, x)
+    }
+
+    fn external_layer(&mut self, state: &[Wire]) -> Vec<Wire> {
+        let width = self.params.width;
+        (0..width)
+            .map(|i| {
+                let terms: Vec<Wire> = (0..width).map(|j| self.scale(external_entry(width, i, j), state[j])).collect();
+                self.builder.sum(&terms)
+            })
+            .collect()
+    }
+
+    fn external_round(&mut self, state: &[Wire], constants: &[F]) -> Vec<Wire> {
+        let state: Vec<Wire> = state
+            .iter()
+            .zip(constants)
+            .map(|(s, c)| {
+                let c = self.builder.constant(*c);
+                let s = self.builder.add(*s, c);
+                self.sbox(s)
+            })
+            .collect();
+        self.external_layer(&state)
+    }
+
+    fn internal_layer(&mut self, state: &[Wire]) -> Vec<Wire> {
+        let total = self.builder.sum(state);
+        let params = self.params;
+        state
+            .iter()
+            .zip(&params.internal_diagonal)
+            .map(|(s, mu)| {
+                let shift = *mu - F::one();
+                if shift.is_zero() {
+                    return total;
+                }
+                let shifted = if shift.is_one() {
+                    *s
+                } else {
+                    let shift = self.builder.constant(shift);
+                    self.builder.mul(shift, *s)
+                };
+                self.builder.add(total, shifted)
+            })
+            .collect()
+    }
+}
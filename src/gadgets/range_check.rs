@@ -0,0 +1,255 @@
+// src/gadgets/range_check.rs
+//
+// コミットした MLE ṽ の全ての値が [0, 2^bits) にあることの証明。
+//
+// 方法は値の数 n = 2^num_vars と bits から `RangeCheckMethod::select` が決める。
+//   - lookup：表 0..2^bits に対する `lookup` の LogUp。手間は n + 2^bits なので，表が n·bits 以下のとき
+//   - ビット分解：値をビットの列 B_0, …, B_{bits-1} に分け，transcript から引いた r, ρ で
+//       Σ_x eq(r, x)·Σ_i ρ^i·(B_i(x)^2 - B_i(x)) = 0
+//     を sum-check で示す（全てのビットが 0 / 1）。最後の点 s で ṽ(s) = Σ_i 2^i·B̃_i(s) が成り立つので，
+//     ṽ についての主張は B̃_i(s) から決まる。ビットの列は ṽ と一緒にコミットする補助の witness で，
+//     主張（`RangeClaim`）は B̃_i(s) の値も含む。
+//
+// どちらの方法でも検証者に残るのは 1 点での主張で，`RangeClaim::verify_against` か
+// `verify_with_oracle`（コミットメントの開示）で確かめる。
+// challenge は値とビットの列を決めた後に引く。既定の `range_check` / `verify_range_check` は値そのものを
+// transcript に吸収し（ビットの列は値から決まる），`_with` の版は呼び出し側がコミットメントを先に吸収しておく。
+
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::PrimeField;
+
+use crate::eq::{eq_eval, eq_table};
+use crate::error::Error;
+use crate::gadgets::lookup::{prove_lookup_with, verify_lookup_with, LookupProof};
+use crate::ml_extension::{DenseMLE, IndexOrder};
+use crate::transcript::Transcript;
+use crate::virtual_poly::VirtualPolynomial;
+
+/// 範囲検査の証明を初期化するラベル
+pub const RANGE_CHECK_LABEL: &[u8] = b"gkr-range-check";
+/// lookup を選ぶ表の大きさの上限（ビット数）
+pub const MAX_LOOKUP_BITS: usize = 20;
+
+/// 範囲検査の方法
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RangeCheckMethod {
+    BitDecomposition,
+    Lookup,
+}
+
+impl RangeCheckMethod {
+    /// 表 2^bits が値の数とビット数の積 n·bits 以下なら lookup，そうでなければビット分解
+    pub fn select(num_vars: usize, bits: usize) -> Self {
+        if num_vars > 0 && bits <= MAX_LOOKUP_BITS && 1usize << bits <= bits << num_vars {
+            RangeCheckMethod::Lookup
+        } else {
+            RangeCheckMethod::BitDecomposition
+        }
+    }
+}
+
+/// ビット分解による範囲検査の証明
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BitDecompositionProof<F: PrimeField = ScalarField> {
+    /// ゼロ検査の sum-check のメッセージ
+    pub msgs: Vec<Vec<F>>,
+    /// 最後の点 s での B̃_i(s)
+    pub bit_values: Vec<F>,
+}
+
+/// 範囲検査の証明（`RangeCheckMethod::select` の方法）
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RangeProof<F: PrimeField = ScalarField> {
+    BitDecomposition(BitDecompositionProof<F>),
+    Lookup(LookupProof<F>),
+}
+
+/// 検証の後に残る主張 ṽ(point) = value（ビット分解では B̃_i(point) = bit_values[i] も）
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RangeClaim<F: PrimeField = ScalarField> {
+    pub point: Vec<F>,
+    pub value: F,
+    /// ビットの列の値（lookup では空）
+    pub bit_values: Vec<F>,
+}
+
+impl<F: PrimeField> RangeClaim<F> {
+    /// 平文の値とビットの列（`decompose` の出力。lookup では空でよい）で主張を確かめる
+    pub fn verify_against(&self, values: &DenseMLE<F>, bit_columns: &[DenseMLE<F>]) -> Result<(), Error> {
+        if bit_columns.len() != self.bit_values.len() {
+            return Err(Error::LengthMismatch {
+                what: "bit columns",
+                expected: self.bit_values.len(),
+                found: bit_columns.len(),
+            });
+        }
+        self.verify_with_oracle(|point| values.evaluate(point), |i, point| bit_columns[i].evaluate(point))
+    }
+
+    /// `verify_against` のオラクル版。クロージャは ṽ(point) と i 番目のビットの列の B̃_i(point) を返す
+    pub fn verify_with_oracle(
+        &self,
+        values: impl FnOnce(&[F]) -> F,
+        mut bit: impl FnMut(usize, &[F]) -> F,
+    ) -> Result<(), Error> {
+        if values(&self.point) != self.value {
+            return Err(Error::EvaluationMismatch("values at the final claim"));
+        }
+        for (i, b) in self.bit_values.iter().enumerate() {
+            if bit(i, &self.point) != *b {
+                return Err(Error::EvaluationMismatch("bit column at the final claim"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 値を bits 本のビットの列（下位のビットから）に分ける
+///
+/// 範囲にない値があれば，その添字（先頭の変数を最上位ビットとする）を `Error::OutOfRange` で返す。
+pub fn decompose<F: PrimeField>(values: &DenseMLE<F>, bits: usize) -> Result<Vec<DenseMLE<F>>, Error> {
+    check_bits::<F>(bits)?;
+    let values = values.to_order(IndexOrder::BigEndian);
+    let mut columns = vec![Vec::with_capacity(values.evaluations.len()); bits];
+    for (index, v) in values.evaluations.iter().enumerate() {
+        let bigint = v.into_bigint();
+        let limbs = bigint.as_ref();
+        let bit = |i: usize| (limbs[i / 64] >> (i % 64)) & 1;
+        if (bits..64 * limbs.len()).any(|i| bit(i) == 1) {
+            return Err(Error::OutOfRange { index, bits });
+        }
+        for (i, column) in columns.iter_mut().enumerate() {
+            column.push(F::from(bit(i)));
+        }
+    }
+    Ok(columns.into_iter().map(|column| DenseMLE::from_evaluations_vec(values.num_vars, column)).collect())
+}
+
+/// 全ての値が [0, 2^bits) にあることを証明する（値を transcript に吸収する）
+pub fn range_check<F: PrimeField>(values: &DenseMLE<F>, bits: usize) -> Result<RangeProof<F>, Error> {
+    let mut transcript = Transcript::new(RANGE_CHECK_LABEL);
+    absorb_values(&mut transcript, values);
+    range_check_with(values, bits, &mut transcript)
+}
+
+/// 呼び出し側の transcript の上で範囲検査を証明する
+///
+/// 値へのコミットメント（ビット分解では `decompose` のビットの列へのものも）を先に吸収しておく。
+pub fn range_check_with<F: PrimeField>(
+    values: &DenseMLE<F>,
+    bits: usize,
+    transcript: &mut Transcript,
+) -> Result<RangeProof<F>, Error> {
+    check_bits::<F>(bits)?;
+    let method = RangeCheckMethod::select(values.num_vars, bits);
+    absorb_statement(transcript, values.num_vars, bits);
+    match method {
+        RangeCheckMethod::Lookup => match prove_lookup_with(values, &range_table(bits), transcript) {
+            Ok(proof) => Ok(RangeProof::Lookup(proof)),
+            Err(Error::NotInTable { index }) => Err(Error::OutOfRange { index, bits }),
+            Err(e) => Err(e),
+        },
+        RangeCheckMethod::BitDecomposition => {
+            let columns = decompose(values, bits)?;
+            let (r, rho) = zero_check_challenges::<F>(transcript, values.num_vars);
+            let eq = DenseMLE::from_evaluations_vec(values.num_vars, eq_table(&r));
+            let mut poly = VirtualPolynomial::new(values.num_vars);
+            for (column, coeff) in columns.iter().zip(powers(rho)) {
+                poly.add_product(coeff, [column.clone(), column.clone(), eq.clone()]);
+                poly.add_product(-coeff, [column.clone(), eq.clone()]);
+            }
+            let (_, msgs, point) = poly.prove(transcript);
+            let bit_values: Vec<F> = columns.iter().map(|column| column.evaluate(&point)).collect();
+            transcript.append_fields(b"range_bit_evals", &bit_values);
+            Ok(RangeProof::BitDecomposition(BitDecompositionProof { msgs, bit_values }))
+        }
+    }
+}
+
+/// `range_check` の証明を平文の値で検証する（最後の主張も値とそのビットの列で確かめる）
+pub fn verify_range_check<F: PrimeField>(
+    values: &DenseMLE<F>,
+    bits: usize,
+    proof: &RangeProof<F>,
+) -> Result<(), Error> {
+    let mut transcript = Transcript::new(RANGE_CHECK_LABEL);
+    absorb_values(&mut transcript, values);
+    let claim = verify_range_check_with(values.num_vars, bits, proof, &mut transcript)?;
+    let bit_columns = match proof {
+        RangeProof::Lookup(_) => Vec::new(),
+        RangeProof::BitDecomposition(_) => decompose(values, bits)?,
+    };
+    claim.verify_against(values, &bit_columns)
+}
+
+/// `range_check_with` の証明を同じ状態の transcript で検証し，値の MLE についての主張を返す
+///
+/// transcript が値とビットの列へのコミットメントを含まなければ，主張はそれらに縛られない。
+pub fn verify_range_check_with<F: PrimeField>(
+    num_vars: usize,
+    bits: usize,
+    proof: &RangeProof<F>,
+    transcript: &mut Transcript,
+) -> Result<RangeClaim<F>, Error> {
+    check_bits::<F>(bits)?;
+    absorb_statement(transcript, num_vars, bits);
+    match (RangeCheckMethod::select(num_vars, bits), proof) {
+        (RangeCheckMethod::Lookup, RangeProof::Lookup(proof)) => {
+            let claim = verify_lookup_with(num_vars, &range_table(bits), proof, transcript)?;
+            Ok(RangeClaim { point: claim.point, value: claim.value, bit_values: Vec::new() })
+        }
+        (RangeCheckMethod::BitDecomposition, RangeProof::BitDecomposition(proof)) => {
+            if proof.bit_values.len() != bits {
+                return Err(Error::LengthMismatch {
+                    what: "bit evaluations",
+                    expected: bits,
+                    found: proof.bit_values.len(),
+                });
+            }
+            let (r, rho) = zero_check_challenges::<F>(transcript, num_vars);
+            let subclaim = VirtualPolynomial::verify(num_vars, 3, F::zero(), &proof.msgs, transcript)?;
+            let booleanity: F = proof.bit_values.iter().zip(powers(rho)).map(|(b, c)| c * (b.square() - b)).sum();
+            if eq_eval(&r, &subclaim.point) * booleanity != subclaim.expected_value {
+                return Err(Error::EvaluationMismatch("booleanity at the final point"));
+            }
+            transcript.append_fields(b"range_bit_evals", &proof.bit_values);
+            let value = proof.bit_values.iter().zip(powers(F::from(2u64))).map(|(b, c)| c * b).sum();
+            Ok(RangeClaim { point: subclaim.point, value, bit_values: proof.bit_values.clone() })
+        }
+        _ => Err(Error::MalformedProof("range proof does not match the selected method")),
+    }
+}
+
+/// 範囲のビット数は 1 以上で，体の元に収まる
+fn check_bits<F: PrimeField>(bits: usize) -> Result<(), Error> {
+    if bits == 0 || bits >= F::MODULUS_BIT_SIZE as usize {
+        return Err(Error::InvalidCircuit("range width must be between 1 and the field size"));
+    }
+    Ok(())
+}
+
+fn range_table<F: PrimeField>(bits: usize) -> Vec<F> {
+    (0..1u64 << bits).map(F::from).collect()
+}
+
+/// 既定の入口で値を吸収する（コミットメントの代わり）
+fn absorb_values<F: PrimeField>(transcript: &mut Transcript, values: &DenseMLE<F>) {
+    transcript.append_fields(b"range_values", &values.to_order(IndexOrder::BigEndian).evaluations);
+}
+
+/// 値の数とビット数を吸収する（方法はここから決まる）
+fn absorb_statement(transcript: &mut Transcript, num_vars: usize, bits: usize) {
+    let statement = [num_vars as u64, bits as u64];
+    transcript.append_message(b"range_statement", &statement.iter().flat_map(|d| d.to_le_bytes()).collect::<Vec<u8>>());
+}
+
+/// ゼロ検査の点 r とビットの列を束ねる ρ
+fn zero_check_challenges<F: PrimeField>(transcript: &mut Transcript, num_vars: usize) -> (Vec<F>, F) {
+    let r = (0..num_vars).map(|_| transcript.challenge_field(b"range_zero_check_point")).collect();
+    (r, transcript.challenge_field(b"range_booleanity"))
+}
+
+/// 1, x, x^2, …
+fn powers<F: PrimeField>(x: F) -> impl Iterator<Item = F> {
+    std::iter::successors(Some(F::one()), move |p| Some(*p * x))
+}
@@ -0,0 +1,352 @@
+// src/gadgets/sha256.rs
+//
+// SHA-256 の圧縮関数の層状回路。
+//
+// 32 ビットの語はビットごとの配線 32 本（下位から）で持つ。回転とシフトは配線の並べ替えだけで，
+// Σ, σ の 3 つの XOR は `keccak` と同じく ±1 の符号の積で取る。Ch と Maj はビットの多項式
+//   Ch(e, f, g) = ef + g - eg，Maj(a, b, c) = ab + ac + bc - 2abc
+// で書く。
+//
+// mod 2^32 の加算は繰り上がりの連鎖を回路で追わず，和のビットと繰り上がり（3 ビット）を
+// 表引きの値のように補助の入力として受け取り，回路はその値が正しいことだけを確かめる。
+//   Σ_i 2^i s_i + 2^32·Σ_j 2^j c_j - Σ (項の値) = 0，補助のビット b ごとに b·(b - 1) = 0
+// 項は 7 個以下なので繰り上がりは 3 ビットに収まり，体の上の等式がそのまま整数の等式になる。
+// 新しい語は補助の入力から読むので，回路の深さはラウンド数によらず一定になる。
+//
+// 回路の入力は状態 8 語，ブロック 16 語，補助の値の順（`Sha256Circuit::witness` が並べる）。
+// 出力は圧縮後の状態 8 語（体の元としての値）と，その後ろに全ての制約の値（正しい入力なら 0）。
+
+use ark_ff::PrimeField;
+use std::collections::HashMap;
+
+use crate::builder::{BuiltCircuit, CircuitBuilder, Wire};
+use crate::error::Error;
+
+/// 初期ハッシュ値
+pub const IV: [u32; 8] =
+    [0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19];
+
+/// ラウンド定数
+pub const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5, 0xd807aa98,
+    0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786,
+    0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d, 0xb00327c8,
+    0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13,
+    0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819,
+    0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a,
+    0x5b9cca4f, 0x682e6ff3, 0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7,
+    0xc67178f2,
+];
+
+/// 補助の加算ごとの繰り上がりのビット数
+pub const CARRY_BITS: usize = 3;
+
+fn big_sigma0(x: u32) -> u32 {
+    x.rotate_right(2) ^ x.rotate_right(13) ^ x.rotate_right(22)
+}
+
+fn big_sigma1(x: u32) -> u32 {
+    x.rotate_right(6) ^ x.rotate_right(11) ^ x.rotate_right(25)
+}
+
+fn small_sigma0(x: u32) -> u32 {
+    x.rotate_right(7) ^ x.rotate_right(18) ^ (x >> 3)
+}
+
+fn small_sigma1(x: u32) -> u32 {
+    x.rotate_right(17) ^ x.rotate_right(19) ^ (x >> 10)
+}
+
+fn ch(e: u32, f: u32, g: u32) -> u32 {
+    (e & f) ^ (!e & g)
+}
+
+fn maj(a: u32, b: u32, c: u32) -> u32 {
+    (a & b) ^ (a & c) ^ (b & c)
+}
+
+/// 圧縮関数（回路と比べる参照実装）
+pub fn compress(state: &mut [u32; 8], block: &[u32; 16]) {
+    let mut w = block.to_vec();
+    for t in 16..64 {
+        let x =
+            small_sigma1(w[t - 2]).wrapping_add(w[t - 7]).wrapping_add(small_sigma0(w[t - 15])).wrapping_add(w[t - 16]);
+        w.push(x);
+    }
+    let mut v = *state;
+    for (k, wt) in K.iter().zip(&w) {
+        let [a, b, c, d, e, f, g, h] = v;
+        let t1 = h.wrapping_add(big_sigma1(e)).wrapping_add(ch(e, f, g)).wrapping_add(*k).wrapping_add(*wt);
+        let t2 = big_sigma0(a).wrapping_add(maj(a, b, c));
+        v = [t1.wrapping_add(t2), a, b, c, d.wrapping_add(t1), e, f, g];
+    }
+    for (s, x) in state.iter_mut().zip(v) {
+        *s = s.wrapping_add(x);
+    }
+}
+
+/// 回路の入力の値を回路と同じ順に並べる
+struct Witness<F: PrimeField> {
+    values: Vec<F>,
+}
+
+impl<F: PrimeField> Witness<F> {
+    fn push_bits(&mut self, x: u64, bits: usize) {
+        self.values.extend((0..bits).map(|i| F::from((x >> i) & 1)));
+    }
+
+    /// terms の和の下位 32 ビットと繰り上がりを補助の値として置き，和を返す
+    fn sum(&mut self, terms: &[u32]) -> u32 {
+        let total: u64 = terms.iter().map(|&t| t as u64).sum();
+        self.push_bits(total, 32 + CARRY_BITS);
+        total as u32
+    }
+}
+
+/// 圧縮関数の回路
+pub struct Sha256Circuit<F: PrimeField> {
+    pub built: BuiltCircuit<F>,
+    /// 状態の 8 語の後ろに並ぶ制約の数
+    pub num_constraints: usize,
+}
+
+impl<F: PrimeField> Sha256Circuit<F> {
+    /// 状態とブロックから回路の入力（補助の値を含む）を作る
+    pub fn witness(&self, state: &[u32; 8], block: &[u32; 16]) -> Vec<F> {
+        let mut witness = Witness { values: Vec::new() };
+        for &x in state.iter().chain(block) {
+            witness.push_bits(x as u64, 32);
+        }
+        let mut w = block.to_vec();
+        for t in 16..64 {
+            let x = witness.sum(&[small_sigma1(w[t - 2]), w[t - 7], small_sigma0(w[t - 15]), w[t - 16]]);
+            w.push(x);
+        }
+        let mut v = *state;
+        for (&k, &wt) in K.iter().zip(&w) {
+            let [a, b, c, d, e, f, g, h] = v;
+            let (s1, choice) = (big_sigma1(e), ch(e, f, g));
+            let e = witness.sum(&[d, h, s1, choice, k, wt]);
+            let a = witness.sum(&[h, s1, choice, k, wt, big_sigma0(a), maj(a, b, c)]);
+            v = [a, v[0], b, c, e, v[4], f, g];
+        }
+        for (s, x) in state.iter().zip(v) {
+            witness.sum(&[*s, x]);
+        }
+        witness.values
+    }
+
+    /// 回路の出力から圧縮後の状態を読む（制約が 0 でなければエラー）
+    pub fn digest(&self, outputs: &[F]) -> Result<[u32; 8], Error> {
+        if outputs.len() != 8 + self.num_constraints {
+            return Err(Error::LengthMismatch {
+                what: "outputs",
+                expected: 8 + self.num_constraints,
+                found: outputs.len(),
+            });
+        }
+        if outputs[8..].iter().any(|c| !c.is_zero()) {
+            return Err(Error::EvaluationMismatch("SHA-256 constraint"));
+        }
+        let mut state = [0u32; 8];
+        for (s, x) in state.iter_mut().zip(outputs) {
+            let bigint = x.into_bigint();
+            let limbs = bigint.as_ref();
+            *s = match u32::try_from(limbs[0]) {
+                Ok(word) if limbs[1..].iter().all(|l| *l == 0) => word,
+                _ => return Err(Error::MalformedProof("output word is not 32 bits")),
+            };
+        }
+        Ok(state)
+    }
+}
+
+/// 圧縮関数 1 回の回路
+pub fn circuit<F: PrimeField>() -> Result<Sha256Circuit<F>, Error> {
+    let mut builder = CircuitBuilder::new();
+    let mut gadget = WordGadget::new(&mut builder);
+    let state: Vec<Word> = (0..8).map(|_| gadget.input_word()).collect();
+    let block: Vec<Word> = (0..16).map(|_| gadget.input_word()).collect();
+    let (digest, constraints) = gadget.compress(&state, &block);
+    for w in digest.iter().chain(&constraints) {
+        builder.output(*w);
+    }
+    Ok(Sha256Circuit { built: builder.build()?, num_constraints: constraints.len() })
+}
+
+/// 32 ビットの語（下位のビットから）
+type Word = Vec<Wire>;
+
+/// 語の上の演算。定数とビットの符号は使い回し，補助の値の制約を集める
+struct WordGadget<'a, F: PrimeField> {
+    builder: &'a mut CircuitBuilder<F>,
+    minus_one: Wire,
+    minus_two: Wire,
+    half: Wire,
+    minus_half: Wire,
+    /// 2^i（i < 32 + CARRY_BITS）
+    powers: Vec<Wire>,
+    signs: HashMap<Wire, Wire>,
+    constraints: Vec<Wire>,
+}
+
+impl<'a, F: PrimeField> WordGadget<'a, F> {
+    fn new(builder: &'a mut CircuitBuilder<F>) -> Self {
+        let half = F::from(2u64).inverse().expect("field characteristic is not 2");
+        let minus_one = builder.constant(-F::one());
+        let minus_two = builder.constant(-F::from(2u64));
+        let (half, minus_half) = (builder.constant(half), builder.constant(-half));
+        let powers = (0..32 + CARRY_BITS).map(|i| builder.small_constant(1 << i)).collect();
+        WordGadget {
+            builder,
+            minus_one,
+            minus_two,
+            half,
+            minus_half,
+            powers,
+            signs: HashMap::new(),
+            constraints: Vec::new(),
+        }
+    }
+
+    fn input_word(&mut self) -> Word {
+        (0..32).map(|_| self.builder.input()).collect()
+    }
+
+    /// ビットの列の値 Σ_i 2^i b_i
+    fn value(&mut self, bits: &[Wire]) -> Wire {
+        let terms: Vec<Wire> = bits
+            .iter()
+            .enumerate()
+            .map(|(i, &b)| if i == 0 { b } else { self.builder.mul(self.powers[i], b) })
+            .collect();
+        self.builder.sum(&terms)
+    }
+
+    /// terms の和の下位 32 ビットを補助の入力として受け取り，制約を足して返す
+    fn sum(&mut self, terms: &[Wire]) -> Word {
+        let mut bits: Vec<Wire> = (0..32 + CARRY_BITS).map(|_| self.builder.input()).collect();
+        for &b in &bits {
+            let shifted = self.builder.add(b, self.minus_one);
+            let boolean = self.builder.mul(b, shifted);
+            self.constraints.push(boolean);
+        }
+        let value = self.value(&bits);
+        let total = self.builder.sum(terms);
+        let negated = self.builder.mul(self.minus_one, total);
+        let constraint = self.builder.add(value, negated);
+        self.constraints.push(constraint);
+        bits.truncate(32);
+        bits
+    }
+
+    /// ビット a の符号 1 - 2a
+    fn sign(&mut self, a: Wire) -> Wire {
+        if let Some(&s) = self.signs.get(&a) {
+            return s;
+        }
+        let scaled = self.builder.mul(self.minus_two, a);
+        let one = self.powers[0];
+        let s = self.builder.add(one, scaled);
+        self.signs.insert(a, s);
+        s
+    }
+
+    /// ビット列ごとの XOR。`None` のビット（シフトで入る 0）は除く
+    fn xor(&mut self, parts: &[Vec<Option<Wire>>]) -> Word {
+        (0..32)
+            .map(|i| {
+                let signs: Vec<Wire> = parts.iter().filter_map(|p| p[i]).map(|b| self.sign(b)).collect();
+                let s = self.builder.product(&signs);
+                let scaled = self.builder.mul(self.minus_half, s);
+                self.builder.add(self.half, scaled)
+            })
+            .collect()
+    }
+
+    fn big_sigma(&mut self, x: &[Wire], r: [usize; 3]) -> Word {
+        let parts: Vec<Vec<Option<Wire>>> = r.iter().map(|&n| rotr(x, n)).collect();
+        self.xor(&parts)
+    }
+
+    fn small_sigma(&mut self, x: &[Wire], r: [usize; 2], s: usize) -> Word {
+        let parts = vec![rotr(x, r[0]), rotr(x, r[1]), shr(x, s)];
+        self.xor(&parts)
+    }
+
+    /// Ch(e, f, g) = ef + g - eg
+    fn ch(&mut self, e: &[Wire], f: &[Wire], g: &[Wire]) -> Word {
+        (0..32)
+            .map(|i| {
+                let ef = self.builder.mul(e[i], f[i]);
+                let eg = self.builder.mul(e[i], g[i]);
+                let negated = self.builder.mul(self.minus_one, eg);
+                self.builder.sum(&[ef, g[i], negated])
+            })
+            .collect()
+    }
+
+    /// Maj(a, b, c) = ab + ac + bc - 2abc
+    fn maj(&mut self, a: &[Wire], b: &[Wire], c: &[Wire]) -> Word {
+        (0..32)
+            .map(|i| {
+                let ab = self.builder.mul(a[i], b[i]);
+                let ac = self.builder.mul(a[i], c[i]);
+                let bc = self.builder.mul(b[i], c[i]);
+                let abc = self.builder.mul(ab, c[i]);
+                let scaled = self.builder.mul(self.minus_two, abc);
+                self.builder.sum(&[ab, ac, bc, scaled])
+            })
+            .collect()
+    }
+
+    /// 圧縮後の状態の 8 語の値と，集めた制約を返す（補助の入力は `Sha256Circuit::witness` と同じ順に置く）
+    fn compress(mut self, state: &[Word], block: &[Word]) -> (Vec<Wire>, Vec<Wire>) {
+        let mut w = block.to_vec();
+        for t in 16..64 {
+            let s1 = self.small_sigma(&w[t - 2], [17, 19], 10);
+            let s0 = self.small_sigma(&w[t - 15], [7, 18], 3);
+            let terms = [self.value(&s1), self.value(&w[t - 7]), self.value(&s0), self.value(&w[t - 16])];
+            let x = self.sum(&terms);
+            w.push(x);
+        }
+        let mut v = state.to_vec();
+        for (&k, wt) in K.iter().zip(&w) {
+            let s1 = self.big_sigma(&v[4], [6, 11, 25]);
+            let ch = self.ch(&v[4], &v[5], &v[6]);
+            let s0 = self.big_sigma(&v[0], [2, 13, 22]);
+            let maj = self.maj(&v[0], &v[1], &v[2]);
+            let k = self.builder.small_constant(k as u64);
+            let shared = [self.value(&v[7]), self.value(&s1), self.value(&ch), k, self.value(wt)];
+            let d = self.value(&v[3]);
+            let e_terms: Vec<Wire> = std::iter::once(d).chain(shared).collect();
+            let e = self.sum(&e_terms);
+            let (s0, maj) = (self.value(&s0), self.value(&maj));
+            let a_terms: Vec<Wire> = shared.into_iter().chain([s0, maj]).collect();
+            let a = self.sum(&a_terms);
+            v.rotate_right(1);
+            v[0] = a;
+            v[4] = e;
+        }
+        let digest = state
+            .iter()
+            .zip(&v)
+            .map(|(s, x)| {
+                let terms = [self.value(s), self.value(x)];
+                let sum = self.sum(&terms);
+                self.value(&sum)
+            })
+            .collect();
+        (digest, self.constraints)
+    }
+}
+
+/// ROTR^n(x) のビット
+fn rotr(x: &[Wire], n: usize) -> Vec<Option<Wire>> {
+    (0..32).map(|i| Some(x[(i + n) % 32])).collect()
+}
+
+/// SHR^n(x) のビット（上位の n ビットは 0）
+fn shr(x: &[Wire], n: usize) -> Vec<Option<Wire>> {
+    (0..32).map(|i| x.get(i + n).copied()).collect()
+}
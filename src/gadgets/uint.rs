@@ -0,0 +1,410 @@
+// src/gadgets/uint.rs
+//
+// 32 / 64 ビットの符号なし整数の回路。
+//
+// 整数はビットごとの配線（下位から）で持ち，ビットは全て補助の入力として置いて
+// b·(b - 1) = 0 の制約で範囲を確かめる（ビット分解による範囲検査）。
+// 加算，減算，乗算は結果と繰り上がりを補助の入力で受け取り，16 ビットのリムごとの等式
+//   加算：a_k + b_k + c_{k-1} = s_k + 2^16·c_k
+//   乗算：Σ_{i+j=k} a_i·b_j + c_{k-1} = r_k + 2^16·c_k
+// を制約にする。等式の両辺は 2^36 未満なので，64 ビットの Goldilocks の上でも体で回り込まない。
+// 比較は減算の借り，シフトと回転は配線の並べ替えで作る。
+//
+// `UintBuilder` は値を持ったまま回路を組み，補助の値を含む回路の入力を組み立てと同時に並べる。
+// 回路の形は値によらない。出力は利用者の出力（整数は 32 ビットずつ，ビットは 1 つ）と，
+// その後ろに全ての制約の値（正しい入力なら 0）の順に並ぶ。
+
+use ark_ff::PrimeField;
+use std::collections::HashMap;
+
+use crate::builder::{BuiltCircuit, CircuitBuilder, Wire};
+use crate::error::Error;
+
+/// 加算と乗算の等式を分けるリムのビット数
+pub const LIMB_BITS: usize = 16;
+/// リムの積の等式を扱える体のビット数の下限
+pub const MIN_FIELD_BITS: u32 = 40;
+
+/// 回路の中の整数
+#[derive(Clone, Debug)]
+pub struct UInt {
+    bits: Vec<Wire>,
+    value: u64,
+}
+
+impl UInt {
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    /// ビット数（32 か 64）
+    pub fn width(&self) -> usize {
+        self.bits.len()
+    }
+
+    /// 下位からのビットの配線
+    pub fn bits(&self) -> &[Wire] {
+        &self.bits
+    }
+}
+
+/// 回路の中の 1 ビット（比較の結果など）
+#[derive(Clone, Copy, Debug)]
+pub struct Bit {
+    wire: Wire,
+    value: bool,
+}
+
+impl Bit {
+    pub fn value(&self) -> bool {
+        self.value
+    }
+
+    pub fn wire(&self) -> Wire {
+        self.wire
+    }
+}
+
+/// `UintBuilder::build` の回路
+pub struct UintCircuit<F: PrimeField> {
+    pub built: BuiltCircuit<F>,
+    /// 利用者の出力ごとのビット数（ビットは 1）
+    pub output_widths: Vec<usize>,
+    /// 利用者の出力の後ろに並ぶ制約の数
+    pub num_constraints: usize,
+}
+
+impl<F: PrimeField> UintCircuit<F> {
+    /// 回路の出力の制約が全て 0 であることを確かめ，利用者の出力の値を返す
+    pub fn check_outputs(&self, outputs: &[F]) -> Result<Vec<u64>, Error> {
+        let num_words: usize = self.output_widths.iter().map(|&w| words(w)).sum();
+        if outputs.len() != num_words + self.num_constraints {
+            return Err(Error::LengthMismatch {
+                what: "outputs",
+                expected: num_words + self.num_constraints,
+                found: outputs.len(),
+            });
+        }
+        let (values, constraints) = outputs.split_at(num_words);
+        if constraints.iter().any(|c| !c.is_zero()) {
+            return Err(Error::EvaluationMismatch("integer constraint"));
+        }
+        let mut values = values.iter();
+        self.output_widths
+            .iter()
+            .map(|&width| {
+                (0..words(width)).try_fold(0u64, |acc, i| {
+                    let word = to_word(values.next().unwrap())?;
+                    Ok(acc | ((word as u64) << (32 * i)))
+                })
+            })
+            .collect()
+    }
+}
+
+/// 出力で使う 32 ビットの語の数
+fn words(width: usize) -> usize {
+    width.div_ceil(32)
+}
+
+fn to_word<F: PrimeField>(x: &F) -> Result<u32, Error> {
+    let bigint = x.into_bigint();
+    let limbs = bigint.as_ref();
+    match u32::try_from(limbs[0]) {
+        Ok(word) if limbs[1..].iter().all(|l| *l == 0) => Ok(word),
+        _ => Err(Error::MalformedProof("output word is not 32 bits")),
+    }
+}
+
+/// 値を持ったまま整数の回路を組み立てる
+pub struct UintBuilder<F: PrimeField> {
+    builder: CircuitBuilder<F>,
+    /// 回路の入力の値（`builder.input` を呼んだ順）
+    witness: Vec<F>,
+    constraints: Vec<Wire>,
+    outputs: Vec<Wire>,
+    output_widths: Vec<usize>,
+    minus_one: Wire,
+    /// 2^i の定数ゲート
+    powers: HashMap<usize, Wire>,
+    zero: Option<Wire>,
+}
+
+impl<F: PrimeField> UintBuilder<F> {
+    pub fn new() -> Result<Self, Error> {
+        if F::MODULUS_BIT_SIZE < MIN_FIELD_BITS {
+            return Err(Error::InvalidCircuit("field is too small for 16-bit limb products"));
+        }
+        let mut builder = CircuitBuilder::new();
+        let minus_one = builder.constant(-F::one());
+        Ok(UintBuilder {
+            builder,
+            witness: Vec::new(),
+            constraints: Vec::new(),
+            outputs: Vec::new(),
+            output_widths: Vec::new(),
+            minus_one,
+            powers: HashMap::new(),
+            zero: None,
+        })
+    }
+
+    pub fn input_u32(&mut self, value: u32) -> UInt {
+        UInt { bits: self.range_check(value as u64, 32), value: value as u64 }
+    }
+
+    pub fn input_u64(&mut self, value: u64) -> UInt {
+        UInt { bits: self.range_check(value, 64), value }
+    }
+
+    /// 整数を出力にする（32 ビットずつ下位から）
+    pub fn output(&mut self, a: &UInt) {
+        for word in a.bits.chunks(32) {
+            let w = self.pack(word);
+            self.outputs.push(w);
+        }
+        self.output_widths.push(a.width());
+    }
+
+    pub fn output_bit(&mut self, b: Bit) {
+        self.outputs.push(b.wire);
+        self.output_widths.push(1);
+    }
+
+    /// 回路と，補助の値を含む回路の入力（`BuiltCircuit::assign` に渡す値）を返す
+    pub fn build(mut self) -> Result<(UintCircuit<F>, Vec<F>), Error> {
+        for &w in self.outputs.iter().chain(&self.constraints) {
+            self.builder.output(w);
+        }
+        let circuit = UintCircuit {
+            built: self.builder.build()?,
+            output_widths: self.output_widths,
+            num_constraints: self.constraints.len(),
+        };
+        Ok((circuit, self.witness))
+    }
+
+    /// value の下位 bits ビットを補助の入力に置き，各ビットが 0 / 1 である制約を足す
+    fn range_check(&mut self, value: u64, bits: usize) -> Vec<Wire> {
+        (0..bits)
+            .map(|i| {
+                let b = self.builder.input();
+                self.witness.push(F::from((value >> i) & 1));
+                let shifted = self.builder.add(b, self.minus_one);
+                let boolean = self.builder.mul(b, shifted);
+                self.constraints.push(boolean);
+                b
+            })
+            .collect()
+    }
+
+    fn power(&mut self, i: usize) -> Wire {
+        let builder = &mut self.builder;
+        *self.powers.entry(i).or_insert_with(|| builder.small_constant(1 << i))
+    }
+
+    /// 2^i·w
+    fn shifted(&mut self, i: usize, w: Wire) -> Wire {
+        if i == 0 {
+            return w;
+        }
+        let p = self.power(i);
+        self.builder.mul(p, w)
+    }
+
+    /// ビット列の値 Σ_i 2^i b_i
+    fn pack(&mut self, bits: &[Wire]) -> Wire {
+        let terms: Vec<Wire> = bits.iter().enumerate().map(|(i, &b)| self.shifted(i, b)).collect();
+        self.builder.sum(&terms)
+    }
+
+    /// 16 ビットのリムの値
+    fn limbs(&mut self, bits: &[Wire]) -> Vec<Wire> {
+        bits.chunks(LIMB_BITS).map(|limb| self.pack(limb)).collect()
+    }
+
+    /// Σ lhs = Σ rhs の制約を足す
+    fn equal(&mut self, lhs: &[Wire], rhs: &[Wire]) {
+        let lhs = self.builder.sum(lhs);
+        let rhs = self.builder.sum(rhs);
+        let negated = self.builder.mul(self.minus_one, rhs);
+        let constraint = self.builder.add(lhs, negated);
+        self.constraints.push(constraint);
+    }
+
+    fn not(&mut self, b: Bit) -> Bit {
+        let one = self.power(0);
+        let negated = self.builder.mul(self.minus_one, b.wire);
+        Bit { wire: self.builder.add(one, negated), value: !b.value }
+    }
+
+    fn zero(&mut self) -> Wire {
+        let builder = &mut self.builder;
+        *self.zero.get_or_insert_with(|| builder.small_constant(0))
+    }
+
+    /// a + b mod 2^n と繰り上がり
+    pub fn overflowing_add(&mut self, a: &UInt, b: &UInt) -> (UInt, Bit) {
+        let n = same_width(a, b);
+        let total = a.value as u128 + b.value as u128;
+        let sum = UInt { bits: self.range_check(total as u64 & mask(n), n), value: total as u64 & mask(n) };
+        let (a_limbs, b_limbs, s_limbs) = (self.limbs(&a.bits), self.limbs(&b.bits), self.limbs(&sum.bits));
+        let mut carry: Option<(Wire, u64)> = None;
+        for (k, ((&a_k, &b_k), &s_k)) in a_limbs.iter().zip(&b_limbs).zip(&s_limbs).enumerate() {
+            let carry_in = carry.map_or(0, |(_, c)| c);
+            let c = (limb(a.value, k) + limb(b.value, k) + carry_in) >> LIMB_BITS;
+            let c_wire = self.range_check(c, 1)[0];
+            let mut lhs = vec![a_k, b_k];
+            lhs.extend(carry.map(|(w, _)| w));
+            let high = self.shifted(LIMB_BITS, c_wire);
+            self.equal(&lhs, &[s_k, high]);
+            carry = Some((c_wire, c));
+        }
+        let (wire, c) = carry.unwrap();
+        (sum, Bit { wire, value: c == 1 })
+    }
+
+    pub fn wrapping_add(&mut self, a: &UInt, b: &UInt) -> UInt {
+        self.overflowing_add(a, b).0
+    }
+
+    /// a - b mod 2^n と借り（a < b のとき 1）
+    pub fn overflowing_sub(&mut self, a: &UInt, b: &UInt) -> (UInt, Bit) {
+        let n = same_width(a, b);
+        let value = a.value.wrapping_sub(b.value) & mask(n);
+        let diff = UInt { bits: self.range_check(value, n), value };
+        let (a_limbs, b_limbs, d_limbs) = (self.limbs(&a.bits), self.limbs(&b.bits), self.limbs(&diff.bits));
+        let mut borrow: Option<(Wire, u64)> = None;
+        for (k, ((&a_k, &b_k), &d_k)) in a_limbs.iter().zip(&b_limbs).zip(&d_limbs).enumerate() {
+            let borrow_in = borrow.map_or(0, |(_, c)| c);
+            let br = (limb(a.value, k) < limb(b.value, k) + borrow_in) as u64;
+            let br_wire = self.range_check(br, 1)[0];
+            // a_k + 2^16·br_k = b_k + br_{k-1} + d_k
+            let high = self.shifted(LIMB_BITS, br_wire);
+            let mut rhs = vec![b_k, d_k];
+            rhs.extend(borrow.map(|(w, _)| w));
+            self.equal(&[a_k, high], &rhs);
+            borrow = Some((br_wire, br));
+        }
+        let (wire, br) = borrow.unwrap();
+        (diff, Bit { wire, value: br == 1 })
+    }
+
+    pub fn wrapping_sub(&mut self, a: &UInt, b: &UInt) -> UInt {
+        self.overflowing_sub(a, b).0
+    }
+
+    /// a·b mod 2^n
+    pub fn wrapping_mul(&mut self, a: &UInt, b: &UInt) -> UInt {
+        let n = same_width(a, b);
+        let bits = self.multiply(a, b, false);
+        UInt { bits, value: (a.value as u128 * b.value as u128) as u64 & mask(n) }
+    }
+
+    /// a·b の下位 n ビットと上位 n ビット
+    pub fn widening_mul(&mut self, a: &UInt, b: &UInt) -> (UInt, UInt) {
+        let n = same_width(a, b);
+        let product = a.value as u128 * b.value as u128;
+        let mut bits = self.multiply(a, b, true);
+        let high = bits.split_off(n);
+        (UInt { bits, value: product as u64 & mask(n) }, UInt { bits: high, value: (product >> n) as u64 })
+    }
+
+    /// 列ごとの等式で積を確かめ，結果のビットを返す（`wide` なら 2n ビット）
+    fn multiply(&mut self, a: &UInt, b: &UInt, wide: bool) -> Vec<Wire> {
+        let n = a.width();
+        let m = n / LIMB_BITS;
+        let product = a.value as u128 * b.value as u128;
+        let mut bits = self.range_check(product as u64, n);
+        if wide {
+            bits.extend(self.range_check((product >> n) as u64, n));
+        }
+        let r_limbs = self.limbs(&bits);
+        let (a_limbs, b_limbs) = (self.limbs(&a.bits), self.limbs(&b.bits));
+        // 列の和 < m·2^32 なので，繰り上がりは 16 + ⌈log2(m + 1)⌉ ビットに収まる
+        let carry_bits = LIMB_BITS + (usize::BITS - m.leading_zeros()) as usize;
+        let columns = if wide { 2 * m - 1 } else { m };
+        let mut carry: Option<(Wire, u128)> = None;
+        for (k, &r_k) in r_limbs.iter().enumerate().take(columns) {
+            let pairs: Vec<(usize, usize)> = (0..m).filter(|&i| k >= i && k - i < m).map(|i| (i, k - i)).collect();
+            let column: u128 = pairs.iter().map(|&(i, j)| (limb(a.value, i) * limb(b.value, j)) as u128).sum();
+            let carry_in = carry.map_or(0, |(_, c)| c);
+            let c = (column + carry_in) >> LIMB_BITS;
+            let mut lhs: Vec<Wire> = pairs.iter().map(|&(i, j)| self.builder.mul(a_limbs[i], b_limbs[j])).collect();
+            lhs.extend(carry.map(|(w, _)| w));
+            // 幅の広い積の最後の列の繰り上がりは，結果の最上位のリムそのもの
+            let c_wire = if wide && k == columns - 1 {
+                r_limbs[k + 1]
+            } else {
+                let c_bits = self.range_check(c as u64, carry_bits);
+                self.pack(&c_bits)
+            };
+            let high = self.shifted(LIMB_BITS, c_wire);
+            self.equal(&lhs, &[r_k, high]);
+            carry = Some((c_wire, c));
+        }
+        bits
+    }
+
+    /// a < b
+    pub fn lt(&mut self, a: &UInt, b: &UInt) -> Bit {
+        self.overflowing_sub(a, b).1
+    }
+
+    /// a ≤ b
+    pub fn le(&mut self, a: &UInt, b: &UInt) -> Bit {
+        let gt = self.lt(b, a);
+        self.not(gt)
+    }
+
+    /// a = b（差の全てのビットが 0）
+    pub fn is_equal(&mut self, a: &UInt, b: &UInt) -> Bit {
+        let diff = self.wrapping_sub(a, b);
+        let zeros: Vec<Wire> = diff.bits.iter().map(|&d| self.not(Bit { wire: d, value: false }).wire).collect();
+        Bit { wire: self.builder.product(&zeros), value: a.value == b.value }
+    }
+
+    /// a << k（k < n）
+    pub fn shl(&mut self, a: &UInt, k: usize) -> UInt {
+        let n = a.width();
+        assert!(k < n, "shift amount must be less than the width");
+        let zero = self.zero();
+        let bits = (0..n).map(|i| if i >= k { a.bits[i - k] } else { zero }).collect();
+        UInt { bits, value: (a.value << k) & mask(n) }
+    }
+
+    /// a >> k（k < n）
+    pub fn shr(&mut self, a: &UInt, k: usize) -> UInt {
+        let n = a.width();
+        assert!(k < n, "shift amount must be less than the width");
+        let zero = self.zero();
+        let bits = (0..n).map(|i| a.bits.get(i + k).copied().unwrap_or(zero)).collect();
+        UInt { bits, value: a.value >> k }
+    }
+
+    pub fn rotate_left(&mut self, a: &UInt, k: usize) -> UInt {
+        let n = a.width();
+        let bits = (0..n).map(|i| a.bits[(i + n - k % n) % n]).collect();
+        let value = if n == 64 { a.value.rotate_left(k as u32) } else { (a.value as u32).rotate_left(k as u32) as u64 };
+        UInt { bits, value }
+    }
+
+    pub fn rotate_right(&mut self, a: &UInt, k: usize) -> UInt {
+        let n = a.width();
+        self.rotate_left(a, n - k % n)
+    }
+}
+
+fn same_width(a: &UInt, b: &UInt) -> usize {
+    assert_eq!(a.width(), b.width(), "operands have different widths");
+    a.width()
+}
+
+fn mask(n: usize) -> u64 {
+    u64::MAX >> (64 - n)
+}
+
+/// value の k 番目の 16 ビットのリム
+fn limb(value: u64, k: usize) -> u64 {
+    (value >> (LIMB_BITS * k)) & 0xffff
+}
@@ -0,0 +1,75 @@
+// src/gray_code.rs
+
+/// i 番目の Gray 符号 i ^ (i >> 1)
+pub fn gray_code(i: usize) -> usize {
+    i ^ (i >> 1)
+}
+
+/// `gray_code` の逆：Gray 符号 g が何番目か
+pub fn gray_rank(mut g: usize) -> usize {
+    let mut i = 0;
+    while g != 0 {
+        i ^= g;
+        g >>= 1;
+    }
+    i
+}
+
+/// Gray 符号順に列挙した超立方体の点
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GrayPoint {
+    /// 点の添字（最上位ビットが先頭の変数）
+    pub index: usize,
+    /// 直前の点から反転した変数の位置（先頭の変数が 0）。最初の点では `None`
+    pub flipped: Option<usize>,
+}
+
+impl GrayPoint {
+    /// 反転した変数の新しい値（0 → 1 なら true）
+    pub fn flipped_to_one(&self, num_vars: usize) -> Option<bool> {
+        self.flipped.map(|v| (self.index >> (num_vars - 1 - v)) & 1 == 1)
+    }
+}
+
+/// {0,1}^num_vars を Gray 符号順に 2^num_vars 個列挙するイテレータ
+///
+/// 隣り合う点は 1 座標だけ異なるので，評価値を点ごとに計算し直す代わりに
+/// `flipped` の座標に関わる因子だけを更新すればよい。
+#[derive(Clone, Debug)]
+pub struct GrayCodeIter {
+    num_vars: usize,
+    step: usize,
+}
+
+impl GrayCodeIter {
+    pub fn new(num_vars: usize) -> Self {
+        GrayCodeIter { num_vars, step: 0 }
+    }
+}
+
+impl Iterator for GrayCodeIter {
+    type Item = GrayPoint;
+
+    fn next(&mut self) -> Option<GrayPoint> {
+        if self.step >> self.num_vars != 0 {
+            return None;
+        }
+        let index = gray_code(self.step);
+        // step 番目では step の最下位の 1 のビットが反転する
+        let flipped = (self.step != 0).then(|| self.num_vars - 1 - self.step.trailing_zeros() as usize);
+        self.step += 1;
+        Some(GrayPoint { index, flipped })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (1usize << self.num_vars) - self.step;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for GrayCodeIter {}
+
+/// {0,1}^num_vars の Gray 符号順の列挙
+pub fn gray_code_points(num_vars: usize) -> GrayCodeIter {
+    GrayCodeIter::new(num_vars)
+}
@@ -0,0 +1,81 @@
+// src/hypercube.rs
+
+use ark_ff::Field;
+
+use crate::gray_code::{gray_code_points, GrayCodeIter};
+
+/// 添字 index を {0,1}^num_vars の点として point に書き込む（先頭の変数が最上位ビット）
+pub fn fill_point<F: Field>(index: usize, point: &mut [F]) {
+    let n = point.len();
+    for (i, x) in point.iter_mut().enumerate() {
+        *x = if (index >> (n - 1 - i)) & 1 == 1 { F::one() } else { F::zero() };
+    }
+}
+
+/// {0,1}^num_vars の全点を走査する
+///
+/// 点は内部のベクトルを書き換えて貸し出すので，点ごとのメモリ確保は無い。
+/// `new` は添字の昇順（2 進カウンタ），`gray` は Gray 符号順に列挙し，
+/// どちらも 1 点あたり償却 O(1) 個の座標だけを書き換える。
+pub struct BooleanHypercube<F: Field> {
+    num_vars: usize,
+    /// 次に返す点の番号（列挙順での位置）
+    step: usize,
+    point: Vec<F>,
+    /// Gray 符号順のときの添字の列
+    gray: Option<GrayCodeIter>,
+}
+
+impl<F: Field> BooleanHypercube<F> {
+    /// 添字の昇順に列挙する
+    pub fn new(num_vars: usize) -> Self {
+        BooleanHypercube { num_vars, step: 0, point: vec![F::zero(); num_vars], gray: None }
+    }
+
+    /// Gray 符号順に列挙する（隣り合う点は 1 座標だけ異なる）
+    pub fn gray(num_vars: usize) -> Self {
+        BooleanHypercube { gray: Some(gray_code_points(num_vars)), ..Self::new(num_vars) }
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    /// 次の点の (添字, 座標)。全点を返し終えたら `None`
+    pub fn next_point(&mut self) -> Option<(usize, &Vec<F>)> {
+        if self.step >> self.num_vars != 0 {
+            return None;
+        }
+        let index = match self.gray.as_mut() {
+            Some(gray) => {
+                let p = gray.next()?;
+                if let Some(k) = p.flipped {
+                    self.point[k] = if self.point[k].is_zero() { F::one() } else { F::zero() };
+                }
+                p.index
+            }
+            None => {
+                if self.step != 0 {
+                    // 末尾の 1 を 0 に戻し，その手前の 0 を 1 にする
+                    let k = self.num_vars - 1 - self.step.trailing_zeros() as usize;
+                    for x in self.point[k + 1..].iter_mut() {
+                        *x = F::zero();
+                    }
+                    self.point[k] = F::one();
+                }
+                self.step
+            }
+        };
+        self.step += 1;
+        Some((index, &self.point))
+    }
+
+    /// 全点での f の値の和
+    pub fn sum(mut self, mut f: impl FnMut(&Vec<F>) -> F) -> F {
+        let mut sum = F::zero();
+        while let Some((_, point)) = self.next_point() {
+            sum += f(point);
+        }
+        sum
+    }
+}
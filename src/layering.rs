@@ -0,0 +1,120 @@
+// src/layering.rs
+//
+// 層に分かれていないゲートの DAG を GKR で扱える層状の回路に変換する。
+// ゲートを位相順に並べ直してから `CircuitBuilder` に渡し，層の割り当て・中継・詰め物はそちらに任せる。
+
+use ark_ff::Field;
+
+use crate::builder::{BuiltCircuit, CircuitBuilder, Wire};
+use crate::error::Error;
+
+/// DAG のゲート。添字は `GateGraph::gates` の位置で，並び順は位相順でなくてよい
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DagGate<F: Field> {
+    Input,
+    Constant(F),
+    Add(usize, usize),
+    Mul(usize, usize),
+}
+
+/// ゲートの DAG と出力
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GateGraph<F: Field> {
+    pub gates: Vec<DagGate<F>>,
+    pub outputs: Vec<usize>,
+}
+
+/// 層状にした回路と，その形の報告
+pub struct Layering<F: Field> {
+    pub built: BuiltCircuit<F>,
+    /// ゲートの位相順（各ゲートは読むゲートより後ろに来る）
+    pub topological_order: Vec<usize>,
+}
+
+impl<F: Field> Layering<F> {
+    pub fn depth(&self) -> usize {
+        self.built.circuit.depth()
+    }
+
+    /// 各層の詰め物をする前の幅（出力層が先頭）
+    pub fn widths(&self) -> &[usize] {
+        &self.built.layer_widths
+    }
+
+    /// 層をまたぐために入れた中継ゲートの数
+    pub fn num_relays(&self) -> usize {
+        self.built.num_relays
+    }
+}
+
+impl<F: Field> GateGraph<F> {
+    /// 位相順を求める。範囲外の参照や閉路があればエラー
+    pub fn topological_order(&self) -> Result<Vec<usize>, Error> {
+        let n = self.gates.len();
+        let reads = |i: usize| match self.gates[i] {
+            DagGate::Add(a, b) | DagGate::Mul(a, b) => vec![a, b],
+            DagGate::Input | DagGate::Constant(_) => vec![],
+        };
+        if (0..n).any(|i| reads(i).iter().any(|&r| r >= n)) || self.outputs.iter().any(|&o| o >= n) {
+            return Err(Error::InvalidCircuit("gate reads an unknown gate"));
+        }
+        // 0: 未訪問, 1: 探索中, 2: 完了（深さ優先探索を明示的なスタックで行う）
+        let mut state = vec![0u8; n];
+        let mut order = Vec::with_capacity(n);
+        for root in 0..n {
+            if state[root] != 0 {
+                continue;
+            }
+            let mut stack = vec![(root, false)];
+            while let Some((i, expanded)) = stack.pop() {
+                if expanded {
+                    state[i] = 2;
+                    order.push(i);
+                    continue;
+                }
+                match state[i] {
+                    2 => continue,
+                    1 => return Err(Error::InvalidCircuit("gate graph has a cycle")),
+                    _ => {}
+                }
+                state[i] = 1;
+                stack.push((i, true));
+                for r in reads(i) {
+                    match state[r] {
+                        0 => stack.push((r, false)),
+                        1 => return Err(Error::InvalidCircuit("gate graph has a cycle")),
+                        _ => {}
+                    }
+                }
+            }
+        }
+        Ok(order)
+    }
+
+    /// 位相順に並べ直して層状の回路を作る
+    ///
+    /// 回路の入力は `DagGate::Input` の添字の昇順に並ぶ（`BuiltCircuit::assign` にはその順で渡す）。
+    pub fn layer(&self) -> Result<Layering<F>, Error> {
+        let topological_order = self.topological_order()?;
+        let mut builder = CircuitBuilder::new();
+        let mut wires: Vec<Option<Wire>> = vec![None; self.gates.len()];
+        for (i, gate) in self.gates.iter().enumerate() {
+            if *gate == DagGate::Input {
+                wires[i] = Some(builder.input());
+            }
+        }
+        for &i in topological_order.iter() {
+            let wire = |j: usize| wires[j].expect("operands precede their readers");
+            wires[i] = match self.gates[i] {
+                DagGate::Input => wires[i],
+                DagGate::Constant(c) => Some(builder.constant(c)),
+                DagGate::Add(a, b) => Some(builder.add(wire(a), wire(b))),
+                DagGate::Mul(a, b) => Some(builder.mul(wire(a), wire(b))),
+            };
+        }
+        for &o in self.outputs.iter() {
+            builder.output(wires[o].unwrap());
+        }
+        Ok(Layering { built: builder.build()?, topological_order })
+    }
+}
@@ -1,4 +1,55 @@
 pub mod sumcheck;
 pub mod ml_extension;
+pub mod chunked_mle;
+#[cfg(feature = "mmap")]
+pub mod mmap_mle;
 pub mod prover;
 pub mod verifier;
+pub mod session;
+#[cfg(feature = "net")]
+pub mod net;
+pub mod fan_in;
+pub mod pcs;
+pub mod basefold;
+pub mod witness;
+pub mod serialization;
+pub mod simulate;
+pub mod corrupt;
+pub mod estimate;
+pub mod soundness;
+pub mod sparse_sumcheck;
+pub mod small_field;
+pub mod challenge;
+pub mod circuit;
+pub mod circuit_format;
+#[cfg(feature = "cli")]
+pub mod cli;
+pub mod wiring;
+pub mod builder;
+pub mod layering;
+pub mod bristol;
+pub mod expr;
+#[cfg(feature = "r1cs")]
+pub mod r1cs;
+pub mod examples_circuits;
+pub mod gadgets;
+pub mod gray_code;
+pub mod hypercube;
+pub mod eq;
+pub mod transcript;
+pub mod poseidon;
+pub mod statement;
+pub mod streaming;
+pub mod folding;
+pub mod accumulator;
+pub mod self_check;
+pub mod error;
+pub mod circuit_prover;
+pub mod preprocessing;
+#[cfg(feature = "zk")]
+pub mod zk_gkr;
+pub mod virtual_poly;
+#[cfg(feature = "parallel")]
+pub mod parallel;
+#[cfg(all(feature = "numa", target_os = "linux"))]
+pub mod numa;
@@ -1,7 +1,53 @@
 // src/ml_extension.rs
 
 use ark_ff::Field;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use rand::seq::index;
+use rand::Rng;
 use std::collections::HashMap;
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+
+/// バッチ畳み込みで 1 スレッドが受け持つ要素数
+const FOLD_CHUNK: usize = 1 << 12;
+
+/// 変数と表の添字のビットの対応
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum IndexOrder {
+    /// 先頭の変数が添字の最上位ビット（prover / verifier の内部の規約）
+    #[default]
+    BigEndian,
+    /// 先頭の変数が添字の最下位ビット
+    LittleEndian,
+}
+
+impl IndexOrder {
+    /// num_vars 変数の i 番目（0 始まり）の変数に対応する添字のビット位置
+    pub fn bit(self, i: usize, num_vars: usize) -> usize {
+        match self {
+            IndexOrder::BigEndian => num_vars - 1 - i,
+            IndexOrder::LittleEndian => i,
+        }
+    }
+
+    /// この順序の添字を `to` の順序の添字に変換する
+    pub fn convert_index(self, index: usize, num_vars: usize, to: IndexOrder) -> usize {
+        if self == to {
+            index
+        } else {
+            reverse_bits(index, num_vars)
+        }
+    }
+}
+
+/// 添字の下位 num_vars ビットを反転する
+pub fn reverse_bits(index: usize, num_vars: usize) -> usize {
+    if num_vars == 0 {
+        0
+    } else {
+        index.reverse_bits() >> (usize::BITS as usize - num_vars)
+    }
+}
 
 /// 密な multilinear extension
 #[derive(Clone)]
@@ -9,32 +55,257 @@ pub struct DenseMLE<F: Field> {
     pub num_vars: usize,
     /// {0,1}^num_vars 上の評価結果（長さは 2^num_vars）
     pub evaluations: Vec<F>,
+    /// 変数と添字のビットの対応
+    pub order: IndexOrder,
 }
 
 impl<F: Field> DenseMLE<F> {
+    /// 先頭の変数を添字の最上位ビットとする表から作る
     pub fn from_evaluations_vec(num_vars: usize, evaluations: Vec<F>) -> Self {
+        Self::from_evaluations_with_order(num_vars, evaluations, IndexOrder::BigEndian)
+    }
+
+    /// 長さが 2 のべきでない表（先頭の変数が最上位ビット）を 0 で埋めて作る
+    ///
+    /// 変数数は ⌈log2(長さ)⌉（空の表は 0 変数）。埋めた位置の値は 0 なので，
+    /// 実際の長さより後ろの添字を読む配線が無ければ多重線形拡張の値は変わらない。
+    pub fn from_padded_evaluations(mut evaluations: Vec<F>) -> Self {
+        let num_vars = evaluations.len().max(1).next_power_of_two().trailing_zeros() as usize;
+        evaluations.resize(1 << num_vars, F::zero());
+        Self::from_evaluations_vec(num_vars, evaluations)
+    }
+
+    pub fn from_evaluations_with_order(num_vars: usize, evaluations: Vec<F>, order: IndexOrder) -> Self {
         assert_eq!(evaluations.len(), 1 << num_vars);
-        DenseMLE { num_vars, evaluations }
+        DenseMLE { num_vars, evaluations, order }
+    }
+
+    /// ブール超立方体の添字 i（先頭の変数が最上位ビット）ごとの値 f(i) から作る
+    pub fn from_fn(num_vars: usize, f: impl Fn(usize) -> F) -> Self {
+        Self::from_evaluations_vec(num_vars, (0..1 << num_vars).map(f).collect())
+    }
+
+    /// 全ての値を一様に引いた MLE
+    pub fn rand<R: Rng>(num_vars: usize, rng: &mut R) -> Self {
+        Self::from_evaluations_vec(num_vars, (0..1 << num_vars).map(|_| F::rand(rng)).collect())
+    }
+
+    /// 同じ多項式を `order` の順序の表で表す
+    pub fn to_order(&self, order: IndexOrder) -> Self {
+        if self.order == order {
+            return self.clone();
+        }
+        let evaluations =
+            (0..self.evaluations.len()).map(|i| self.evaluations[reverse_bits(i, self.num_vars)]).collect();
+        DenseMLE { num_vars: self.num_vars, evaluations, order }
     }
     
-    /// 固定された {0,1}^n 上の評価とみなし，点 evaluation を返す（単純な実装）
+    /// 任意の点での多重線形拡張の値（先頭の変数から表を半分ずつ畳み込む，O(2^n)）
     pub fn evaluate(&self, point: &[F]) -> F {
         assert_eq!(point.len(), self.num_vars);
-        let mut index = 0;
-        for (i, bit) in point.iter().enumerate() {
-            if !bit.is_zero() {
-                index |= 1 << (self.num_vars - 1 - i);
+        let mut table = self.clone();
+        table.fix_variables_in_place(point);
+        table.evaluations[0]
+    }
+    
+    /// 先頭の変数を r に固定し，その場で表を半分にする
+    pub fn fix_first_variable_in_place(&mut self, r: F) {
+        fix_first_variable_batch(std::slice::from_mut(self), r);
+    }
+
+    /// 末尾の変数を r に固定し，その場で表を半分にする
+    pub fn fix_last_variable_in_place(&mut self, r: F) {
+        match self.order {
+            IndexOrder::BigEndian => self.fix_lowest_bit(r),
+            IndexOrder::LittleEndian => self.fix_highest_bit(r),
+        }
+    }
+
+    /// 添字の最上位ビットの変数を r に固定する（表の上下の半分を畳む）
+    fn fix_highest_bit(&mut self, r: F) {
+        assert!(self.num_vars > 0, "no variable left to fix");
+        let half = self.evaluations.len() / 2;
+        let (lo, hi) = self.evaluations.split_at_mut(half);
+        #[cfg(feature = "parallel")]
+        lo.par_iter_mut().zip(hi.par_iter()).for_each(|(a, b)| *a += r * (*b - *a));
+        #[cfg(not(feature = "parallel"))]
+        for (a, b) in lo.iter_mut().zip(hi.iter()) {
+            *a += r * (*b - *a);
+        }
+        self.evaluations.truncate(half);
+        self.num_vars -= 1;
+    }
+
+    /// 添字の最下位ビットの変数を r に固定する（隣り合う要素の組を畳む）
+    fn fix_lowest_bit(&mut self, r: F) {
+        assert!(self.num_vars > 0, "no variable left to fix");
+        #[cfg(feature = "parallel")]
+        {
+            // 書き込み先と読み出し元が重なるので，新しい表に畳んでから差し替える
+            self.evaluations = self.evaluations.par_chunks(2).map(|pair| pair[0] + r * (pair[1] - pair[0])).collect();
+        }
+        #[cfg(not(feature = "parallel"))]
+        {
+            let half = self.evaluations.len() / 2;
+            for i in 0..half {
+                let (lo, hi) = (self.evaluations[2 * i], self.evaluations[2 * i + 1]);
+                self.evaluations[i] = lo + r * (hi - lo);
             }
+            self.evaluations.truncate(half);
         }
-        self.evaluations[index]
+        self.num_vars -= 1;
     }
-    
+
+    /// 先頭の変数を `prefix` に固定した (num_vars - |prefix|) 変数の MLE
+    pub fn fix_variables(&self, prefix: &[F]) -> Self {
+        let mut fixed = self.clone();
+        fixed.fix_variables_in_place(prefix);
+        fixed
+    }
+
+    /// `fix_variables` のその場版（表を切り詰めるだけで新たに確保しない）
+    pub fn fix_variables_in_place(&mut self, prefix: &[F]) {
+        assert!(prefix.len() <= self.num_vars, "too many variables to fix");
+        for r in prefix {
+            self.fix_first_variable_in_place(*r);
+        }
+    }
+
+    /// 末尾の変数を `suffix` に固定した MLE（`suffix` の最後の要素が最後の変数）
+    pub fn fix_last_variables(&self, suffix: &[F]) -> Self {
+        let mut fixed = self.clone();
+        fixed.fix_last_variables_in_place(suffix);
+        fixed
+    }
+
+    /// `fix_last_variables` のその場版
+    pub fn fix_last_variables_in_place(&mut self, suffix: &[F]) {
+        assert!(suffix.len() <= self.num_vars, "too many variables to fix");
+        for r in suffix.iter().rev() {
+            self.fix_last_variable_in_place(*r);
+        }
+    }
+
     /// 全評価に対してスカラー倍を実施
     pub fn scale(&mut self, scalar: F) {
         for e in self.evaluations.iter_mut() {
             *e *= scalar;
         }
     }
+
+    /// 点ごとの積 P(b)·Q(b) の表（多重線形拡張は P·Q そのものではなく，ブール点で一致する多重線形多項式）
+    pub fn hadamard(&self, other: &DenseMLE<F>) -> Self {
+        let mut product = self.clone();
+        product.zip_in_place(other, |a, b| *a *= b);
+        product
+    }
+
+    /// Σ_k c_k·P_k（表の順序は先頭の項に揃える）
+    pub fn linear_combination(terms: &[(F, &DenseMLE<F>)]) -> Self {
+        let ((c, first), rest) = terms.split_first().expect("linear combination needs at least one term");
+        let mut sum = (*first).clone();
+        sum.scale(*c);
+        for (c, mle) in rest {
+            sum.zip_in_place(mle, |a, b| *a += *c * b);
+        }
+        sum
+    }
+
+    /// 変数数を確かめ，`other` を自分の順序に揃えて要素ごとに `f` を適用する
+    fn zip_in_place(&mut self, other: &DenseMLE<F>, f: impl Fn(&mut F, &F) + Sync + Send) {
+        assert_eq!(self.num_vars, other.num_vars, "num_vars mismatch in element-wise operation");
+        let aligned;
+        let other = if other.order == self.order {
+            other
+        } else {
+            aligned = other.to_order(self.order);
+            &aligned
+        };
+        #[cfg(feature = "parallel")]
+        self.evaluations.par_iter_mut().zip(other.evaluations.par_iter()).for_each(|(a, b)| f(a, b));
+        #[cfg(not(feature = "parallel"))]
+        self.evaluations.iter_mut().zip(other.evaluations.iter()).for_each(|(a, b)| f(a, b));
+    }
+}
+
+impl<F: Field> AddAssign<&DenseMLE<F>> for DenseMLE<F> {
+    fn add_assign(&mut self, other: &DenseMLE<F>) {
+        self.zip_in_place(other, |a, b| *a += b);
+    }
+}
+
+impl<F: Field> SubAssign<&DenseMLE<F>> for DenseMLE<F> {
+    fn sub_assign(&mut self, other: &DenseMLE<F>) {
+        self.zip_in_place(other, |a, b| *a -= b);
+    }
+}
+
+impl<F: Field> Add for &DenseMLE<F> {
+    type Output = DenseMLE<F>;
+
+    fn add(self, other: &DenseMLE<F>) -> DenseMLE<F> {
+        let mut sum = self.clone();
+        sum += other;
+        sum
+    }
+}
+
+impl<F: Field> Sub for &DenseMLE<F> {
+    type Output = DenseMLE<F>;
+
+    fn sub(self, other: &DenseMLE<F>) -> DenseMLE<F> {
+        let mut difference = self.clone();
+        difference -= other;
+        difference
+    }
+}
+
+/// 同じチャレンジ r で複数の表の先頭変数を一度に固定する
+///
+/// 各表で e'[i] = e[i] + r·(e[i + half] - e[i]) を計算する。添字 i ごとに全ての表を
+/// 更新するので，表ごとに畳み込むよりも走査と（`parallel` 有効時の）同期が 1 回で済む。
+/// 全ての表は同じ変数数・同じ `IndexOrder` でなければならない。
+pub fn fix_first_variable_batch<F: Field>(mles: &mut [DenseMLE<F>], r: F) {
+    let Some((num_vars, order)) = mles.first().map(|m| (m.num_vars, m.order)) else {
+        return;
+    };
+    assert!(num_vars > 0, "no variable left to fix");
+    assert!(mles.iter().all(|m| m.num_vars == num_vars), "num_vars mismatch in batch fold");
+    assert!(mles.iter().all(|m| m.order == order), "index order mismatch in batch fold");
+    if order == IndexOrder::LittleEndian {
+        // 先頭の変数は最下位ビットなので，隣り合う要素の組を畳む
+        mles.iter_mut().for_each(|mle| mle.fix_lowest_bit(r));
+        return;
+    }
+    let half = 1 << (num_vars - 1);
+
+    // チャンク番号ごとに，各表の (下半分, 上半分) の断片をまとめる
+    let mut groups: Vec<Vec<(&mut [F], &[F])>> = Vec::new();
+    for mle in mles.iter_mut() {
+        let (lo, hi) = mle.evaluations.split_at_mut(half);
+        for (c, (lo, hi)) in lo.chunks_mut(FOLD_CHUNK).zip(hi.chunks(FOLD_CHUNK)).enumerate() {
+            if groups.len() <= c {
+                groups.push(Vec::new());
+            }
+            groups[c].push((lo, hi));
+        }
+    }
+    let fold_group = |group: Vec<(&mut [F], &[F])>| {
+        for (lo, hi) in group {
+            for (a, b) in lo.iter_mut().zip(hi.iter()) {
+                *a += r * (*b - *a);
+            }
+        }
+    };
+    #[cfg(feature = "parallel")]
+    groups.into_par_iter().for_each(fold_group);
+    #[cfg(not(feature = "parallel"))]
+    groups.into_iter().for_each(fold_group);
+
+    for mle in mles.iter_mut() {
+        mle.evaluations.truncate(half);
+        mle.num_vars -= 1;
+    }
 }
 
 /// 疎な multilinear extension（インデックス→値のマップで表現）
@@ -42,48 +313,159 @@ impl<F: Field> DenseMLE<F> {
 pub struct SparseMLE<F: Field> {
     pub num_vars: usize,
     pub evaluations: HashMap<usize, F>,
+    /// 変数と添字のビットの対応
+    pub order: IndexOrder,
 }
 
 impl<F: Field> SparseMLE<F> {
-    /// 固定する変数は先頭から固定すると仮定
+    /// 先頭の変数を添字の最上位ビットとする要素から作る
+    pub fn new(num_vars: usize, evaluations: HashMap<usize, F>) -> Self {
+        SparseMLE { num_vars, evaluations, order: IndexOrder::BigEndian }
+    }
+
+    pub fn with_order(num_vars: usize, evaluations: HashMap<usize, F>, order: IndexOrder) -> Self {
+        SparseMLE { num_vars, evaluations, order }
+    }
+
+    /// (添字, 値) の列から作る（先頭の変数が最上位ビット）
+    ///
+    /// 添字が 2^num_vars 以上なら panic する。同じ添字の値は足し合わせ，0 になった要素は持たない。
+    pub fn from_entries(num_vars: usize, entries: Vec<(usize, F)>) -> Self {
+        let size = 1usize << num_vars;
+        let mut evaluations = HashMap::with_capacity(entries.len());
+        for (index, val) in entries {
+            assert!(index < size, "index {} is outside the hypercube of {} variables", index, num_vars);
+            *evaluations.entry(index).or_insert_with(F::zero) += val;
+        }
+        evaluations.retain(|_, val| !val.is_zero());
+        Self::new(num_vars, evaluations)
+    }
+
+    /// 密な表の値の割合が `max_density` 以下なら，非零の値だけを持つ疎表現に変換する
+    pub fn from_dense_if_sparse(dense: &DenseMLE<F>, max_density: f64) -> Option<Self> {
+        let nnz = dense.evaluations.iter().filter(|val| !val.is_zero()).count();
+        (nnz as f64 <= max_density * dense.evaluations.len() as f64).then(|| Self::from(dense))
+    }
+
+    /// 保持している要素の数
+    pub fn nnz(&self) -> usize {
+        self.evaluations.len()
+    }
+
+    /// (添字, 値) を添字の昇順に並べた列（添字は `order` の規約のまま）
+    pub fn sorted_entries(&self) -> impl Iterator<Item = (usize, F)> {
+        let mut entries: Vec<(usize, F)> = self.evaluations.iter().map(|(&index, &val)| (index, val)).collect();
+        entries.sort_unstable_by_key(|&(index, _)| index);
+        entries.into_iter()
+    }
+
+    /// 異なる `nnz` 個の添字を一様に選び，それぞれに一様な値を置いた MLE
+    pub fn rand<R: Rng>(num_vars: usize, nnz: usize, rng: &mut R) -> Self {
+        let size = 1usize << num_vars;
+        assert!(nnz <= size, "more non-zero entries than points on the hypercube");
+        let evaluations = index::sample(rng, size, nnz).into_iter().map(|i| (i, F::rand(rng))).collect();
+        Self::new(num_vars, evaluations)
+    }
+
+    /// 同じ多項式を `order` の順序の添字で表す
+    pub fn to_order(&self, order: IndexOrder) -> Self {
+        let evaluations = self
+            .evaluations
+            .iter()
+            .map(|(&index, &val)| (self.order.convert_index(index, self.num_vars, order), val))
+            .collect();
+        SparseMLE { num_vars: self.num_vars, evaluations, order }
+    }
+
+    /// 先頭の変数を添字の最上位ビットとしたときの (添字, 値) の列
+    pub fn big_endian_entries(&self) -> impl Iterator<Item = (usize, F)> + '_ {
+        self.evaluations
+            .iter()
+            .map(|(&index, &val)| (self.order.convert_index(index, self.num_vars, IndexOrder::BigEndian), val))
+    }
+
+    /// 先頭の変数を `fixed` に固定した MLE（任意の体の元で固定できる）
+    ///
+    /// 各要素を Π (b_i·r_i + (1 - b_i)(1 - r_i)) で重み付けし，残りの変数の添字が同じ要素を足し合わせる。
+    /// ブール点で固定した場合は，先頭のビットが一致する要素だけが残る。
     pub fn fix_variables(&self, fixed: &[F]) -> Self {
         let fixed_count = fixed.len();
         assert!(fixed_count <= self.num_vars);
         let new_num_vars = self.num_vars - fixed_count;
+        let mask = (1 << new_num_vars) - 1;
         let mut new_evals = HashMap::new();
-        // 各評価について，先頭 fixed_count 個が fixed と一致する場合のみ残す
         for (&index, &val) in self.evaluations.iter() {
-            let mut valid = true;
-            for i in 0..fixed_count {
-                let bit = (index >> (self.num_vars - 1 - i)) & 1;
-                let fixed_bit = if fixed[i].is_zero() { 0 } else { 1 };
-                if bit != fixed_bit {
-                    valid = false;
-                    break;
-                }
-            }
-            if valid {
-                let mut new_index = 0;
-                for i in fixed_count..self.num_vars {
-                    let bit = (index >> (self.num_vars - 1 - i)) & 1;
-                    new_index = (new_index << 1) | bit;
-                }
-                new_evals.insert(new_index, val);
+            let weight = self.lagrange_weight(index, fixed);
+            if !weight.is_zero() {
+                let rest = match self.order {
+                    IndexOrder::BigEndian => index & mask,
+                    IndexOrder::LittleEndian => index >> fixed_count,
+                };
+                *new_evals.entry(rest).or_insert_with(F::zero) += weight * val;
             }
         }
-        SparseMLE { num_vars: new_num_vars, evaluations: new_evals }
+        SparseMLE { num_vars: new_num_vars, evaluations: new_evals, order: self.order }
     }
     
+    /// 任意の点での値。非零要素ごとに重み Π (b_i·r_i + (1 - b_i)(1 - r_i)) を掛けて足す（O(nnz·num_vars)）
+    pub fn evaluate(&self, point: &[F]) -> F {
+        assert_eq!(point.len(), self.num_vars);
+        self.evaluations.iter().map(|(&index, &val)| self.lagrange_weight(index, point) * val).sum()
+    }
+
+    /// 添字 index の先頭 |prefix| 変数のビット b と prefix の eq(b, prefix)
+    fn lagrange_weight(&self, index: usize, prefix: &[F]) -> F {
+        prefix
+            .iter()
+            .enumerate()
+            .map(|(i, r)| if (index >> self.order.bit(i, self.num_vars)) & 1 == 1 { *r } else { F::one() - r })
+            .product()
+    }
+
     /// 疎表現を密な multilinear extension に変換
     pub fn to_dense_multilinear_extension(&self) -> DenseMLE<F> {
         let size = 1 << self.num_vars;
         let mut evaluations = vec![F::zero(); size];
-        for i in 0..size {
+        for (i, e) in evaluations.iter_mut().enumerate() {
             if let Some(val) = self.evaluations.get(&i) {
-                evaluations[i] = *val;
+                *e = *val;
+            }
+        }
+        DenseMLE { num_vars: self.num_vars, evaluations, order: self.order }
+    }
+}
+
+impl<F: Field> From<&DenseMLE<F>> for SparseMLE<F> {
+    /// 非零の値だけを持つ疎表現（順序は元の表のまま）
+    fn from(dense: &DenseMLE<F>) -> Self {
+        let evaluations =
+            dense.evaluations.iter().enumerate().filter(|(_, val)| !val.is_zero()).map(|(i, &val)| (i, val)).collect();
+        SparseMLE::with_order(dense.num_vars, evaluations, dense.order)
+    }
+}
+
+impl<F: Field> AddAssign<&SparseMLE<F>> for SparseMLE<F> {
+    /// 要素を足し合わせる（`other` の添字は自分の順序に揃える。0 になった要素は持たない）
+    fn add_assign(&mut self, other: &SparseMLE<F>) {
+        assert_eq!(self.num_vars, other.num_vars, "num_vars mismatch in sparse addition");
+        for (&index, &val) in other.evaluations.iter() {
+            let index = other.order.convert_index(index, self.num_vars, self.order);
+            let entry = self.evaluations.entry(index).or_insert_with(F::zero);
+            *entry += val;
+            if entry.is_zero() {
+                self.evaluations.remove(&index);
             }
         }
-        DenseMLE { num_vars: self.num_vars, evaluations }
+    }
+}
+
+impl<F: Field> Add for &SparseMLE<F> {
+    type Output = SparseMLE<F>;
+
+    fn add(self, other: &SparseMLE<F>) -> SparseMLE<F> {
+        let mut sum = self.clone();
+        sum += other;
+        sum
     }
 }
 
@@ -0,0 +1,175 @@
+// src/mmap_mle.rs
+//
+// メモリに載らない表のための，メモリマップしたファイルに置く密な MLE（`mmap` フィーチャ）。
+//
+// ファイルは添字の順（先頭の変数が最上位ビット）に並べた値の列で，各値は `field_byte_len` バイトの
+// リトルエンディアン（`serialization::write_field`）。ヘッダは持たないので，開くときに変数の数を渡す。
+// 先頭の変数を固定するときは，下半分と上半分を working_set 要素ずつ読んで畳み込み，下半分に書き戻してから
+// ファイルを半分に切り詰める。一度にメモリに置くのは working_set 要素の 2 倍までになる。
+
+use ark_ff::PrimeField;
+use memmap2::MmapMut;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::path::Path;
+
+use crate::ml_extension::{DenseMLE, IndexOrder};
+use crate::serialization::{field_byte_len, read_field, write_field, Endianness};
+
+/// 既定の 1 回に読み書きする要素数（2^16）
+pub const DEFAULT_WORKING_SET: usize = 1 << 16;
+
+/// メモリマップしたファイルに値を置く密な MLE
+#[derive(Debug)]
+pub struct MmapMLE<F: PrimeField> {
+    file: File,
+    map: MmapMut,
+    num_vars: usize,
+    working_set: usize,
+    _field: PhantomData<F>,
+}
+
+impl<F: PrimeField> MmapMLE<F> {
+    /// `path` にファイルを作り（既にあれば切り詰め），添字 i ごとの値 f(i) を書き込む
+    pub fn create(path: impl AsRef<Path>, num_vars: usize, f: impl Fn(usize) -> F) -> io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).create(true).truncate(true).open(path)?;
+        file.set_len(((1usize << num_vars) * field_byte_len::<F>()) as u64)?;
+        let mut table = Self::map(file, num_vars)?;
+        let len = 1usize << num_vars;
+        for start in (0..len).step_by(table.working_set) {
+            let end = (start + table.working_set).min(len);
+            let values: Vec<F> = (start..end).map(&f).collect();
+            table.write_range(start, &values);
+        }
+        Ok(table)
+    }
+
+    /// 密な表をファイルに書き出す（表の順序は `IndexOrder::BigEndian` に揃える）
+    pub fn from_dense(path: impl AsRef<Path>, dense: &DenseMLE<F>) -> io::Result<Self> {
+        let dense = dense.to_order(IndexOrder::BigEndian);
+        Self::create(path, dense.num_vars, |i| dense.evaluations[i])
+    }
+
+    /// 既存のファイルを 2^num_vars 要素の表として開く。長さが合わなければ `InvalidData`
+    pub fn open(path: impl AsRef<Path>, num_vars: usize) -> io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        let expected = ((1usize << num_vars) * field_byte_len::<F>()) as u64;
+        let found = file.metadata()?.len();
+        if found != expected {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("expected {} bytes for {} variables, found {}", expected, num_vars, found),
+            ));
+        }
+        Self::map(file, num_vars)
+    }
+
+    /// 1 回に読み書きする要素数を変える（0 は 1 とみなす）
+    pub fn with_working_set(mut self, elements: usize) -> Self {
+        self.working_set = elements.max(1);
+        self
+    }
+
+    fn map(file: File, num_vars: usize) -> io::Result<Self> {
+        // SAFETY: ファイルはこの値が排他的に持ち，マップしている間に他から長さを変えない
+        let map = unsafe { MmapMut::map_mut(&file)? };
+        Ok(MmapMLE { file, map, num_vars, working_set: DEFAULT_WORKING_SET, _field: PhantomData })
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    pub fn working_set(&self) -> usize {
+        self.working_set
+    }
+
+    /// 添字 i の値
+    pub fn get(&self, i: usize) -> io::Result<F> {
+        Ok(self.read_range(i..i + 1)?[0])
+    }
+
+    /// 添字の範囲の値を読む。正準形でないバイト列があれば `InvalidData`
+    pub fn read_range(&self, range: Range<usize>) -> io::Result<Vec<F>> {
+        assert!(range.end <= 1 << self.num_vars, "range outside the table");
+        let size = field_byte_len::<F>();
+        let mut bytes = &self.map[range.start * size..range.end * size];
+        range
+            .map(|_| read_field(&mut bytes, Endianness::Little).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)))
+            .collect()
+    }
+
+    fn write_range(&mut self, start: usize, values: &[F]) {
+        let size = field_byte_len::<F>();
+        let mut bytes = Vec::with_capacity(values.len() * size);
+        for x in values {
+            write_field(&mut bytes, x, Endianness::Little);
+        }
+        self.map[start * size..start * size + bytes.len()].copy_from_slice(&bytes);
+    }
+
+    /// 先頭の変数を r に固定する。畳み込んだ下半分を書き戻し，ファイルを半分に切り詰める
+    pub fn fix_variable_in_place(&mut self, r: F) -> io::Result<()> {
+        assert!(self.num_vars > 0, "no variable left to fix");
+        let half = 1usize << (self.num_vars - 1);
+        for start in (0..half).step_by(self.working_set) {
+            let end = (start + self.working_set).min(half);
+            let mut lo = self.read_range(start..end)?;
+            let hi = self.read_range(half + start..half + end)?;
+            for (a, b) in lo.iter_mut().zip(hi) {
+                *a += r * (b - *a);
+            }
+            self.write_range(start, &lo);
+        }
+        self.map.flush()?;
+        self.file.set_len((half * field_byte_len::<F>()) as u64)?;
+        // SAFETY: `map` と同じ。切り詰めた後の長さでマップし直す
+        self.map = unsafe { MmapMut::map_mut(&self.file)? };
+        self.num_vars -= 1;
+        Ok(())
+    }
+
+    /// 先頭の変数から順に `prefix` に固定する
+    pub fn fix_variables_in_place(&mut self, prefix: &[F]) -> io::Result<()> {
+        assert!(prefix.len() <= self.num_vars, "too many variables to fix");
+        for r in prefix {
+            self.fix_variable_in_place(*r)?;
+        }
+        Ok(())
+    }
+
+    /// 任意の点での多重線形拡張の値。ファイルは書き換えない
+    ///
+    /// 表を working_set 以下の 2 冪の塊に分け，塊ごとに後ろの変数をメモリ上で固定し，
+    /// 前の変数についての eq の重みを掛けて足し合わせる。
+    pub fn evaluate(&self, point: &[F]) -> io::Result<F> {
+        assert_eq!(point.len(), self.num_vars);
+        let chunk_vars = (usize::BITS - 1 - self.working_set.leading_zeros()) as usize;
+        let chunk_vars = chunk_vars.min(self.num_vars);
+        let (head, tail) = point.split_at(self.num_vars - chunk_vars);
+        let chunk = 1usize << chunk_vars;
+        let mut sum = F::zero();
+        for c in 0..1usize << head.len() {
+            let weight: F = head
+                .iter()
+                .enumerate()
+                .map(|(k, r)| if (c >> (head.len() - 1 - k)) & 1 == 1 { *r } else { F::one() - r })
+                .product();
+            let values = self.read_range(c * chunk..(c + 1) * chunk)?;
+            sum += weight * DenseMLE::from_evaluations_vec(chunk_vars, values).evaluate(tail);
+        }
+        Ok(sum)
+    }
+
+    /// 表全体をメモリに読み込む
+    pub fn to_dense(&self) -> io::Result<DenseMLE<F>> {
+        Ok(DenseMLE::from_evaluations_vec(self.num_vars, self.read_range(0..1 << self.num_vars)?))
+    }
+
+    /// 書き込んだ内容をファイルに反映する
+    pub fn flush(&self) -> io::Result<()> {
+        self.map.flush()
+    }
+}
@@ -0,0 +1,190 @@
+// src/net.rs
+//
+// `session` の状態機械を非同期ストリーム（TCP など）越しに回す。
+//
+// フレームは「長さ: u32 | 本体」で，整数と体の元はリトルエンディアン。
+//   プローバ → 検証側 : tag: u8 | 内容
+//     0 = 主張する総和（体の元 1 つ），1 = ラウンド（個数: u32 | 体の元），2 = 最終点での値（体の元 3 つ）
+//   検証側 → プローバ : チャレンジ（体の元 1 つ）
+// 検証側が拒否したときは何も送らずに戻る。呼び出し側がストリームを閉じれば，
+// プローバ側は途中の EOF をエラーとして返す。
+
+use ark_ff::PrimeField;
+use rand::{CryptoRng, Rng};
+use std::fmt;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::error::Error;
+use crate::prover::LinearGKRProof;
+use crate::serialization::{read_field, read_len, write_field, write_len, Endianness, SerializationError};
+use crate::session::{ProverMessage, ProverSession, Stage, VerifierSession};
+use crate::verifier::LinearGKRSubclaim;
+
+/// 受け付けるフレームの最大長
+pub const MAX_FRAME_LEN: usize = 1 << 20;
+
+const TAG_CLAIMED_SUM: u8 = 0;
+const TAG_ROUND: u8 = 1;
+const TAG_FINAL_EVALUATIONS: u8 = 2;
+const ENDIANNESS: Endianness = Endianness::Little;
+
+/// 通信しながらの対話に失敗した理由
+#[derive(Debug)]
+pub enum NetError {
+    Io(std::io::Error),
+    /// フレームの中身が読めない
+    Frame(SerializationError),
+    /// 長さが `MAX_FRAME_LEN` を超えるフレーム
+    FrameTooLarge(usize),
+    /// 未知のメッセージの tag
+    UnknownMessage(u8),
+    /// 相手のメッセージがプロトコルに反する（検証側で拒否した場合を含む）
+    Protocol(Error),
+}
+
+impl fmt::Display for NetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetError::Io(e) => write!(f, "io error: {}", e),
+            NetError::Frame(e) => write!(f, "malformed frame: {}", e),
+            NetError::FrameTooLarge(len) => write!(f, "frame of {} bytes exceeds the limit", len),
+            NetError::UnknownMessage(tag) => write!(f, "unknown message tag {}", tag),
+            NetError::Protocol(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for NetError {}
+
+impl From<std::io::Error> for NetError {
+    fn from(e: std::io::Error) -> Self {
+        NetError::Io(e)
+    }
+}
+
+impl From<SerializationError> for NetError {
+    fn from(e: SerializationError) -> Self {
+        NetError::Frame(e)
+    }
+}
+
+impl From<Error> for NetError {
+    fn from(e: Error) -> Self {
+        NetError::Protocol(e)
+    }
+}
+
+/// プローバ側を最後まで回し，送ったメッセージを証明の形で返す
+pub async fn run_prover<F: PrimeField, S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut S,
+    mut session: ProverSession<'_, F>,
+) -> Result<LinearGKRProof<F>, NetError> {
+    while let Some(msg) = session.next_message() {
+        write_frame(stream, &encode_message(&msg)).await?;
+        if matches!(msg, ProverMessage::Round(_)) {
+            let frame = read_frame(stream).await?;
+            session.receive_challenge(decode_challenge(&frame)?)?;
+        }
+    }
+    Ok(session.into_proof().expect("the session ran to the end"))
+}
+
+/// 検証側を最後まで回し，サブクレームを返す。拒否したときはストリームに何も書かずに戻る
+pub async fn run_verifier<F: PrimeField, S: AsyncRead + AsyncWrite + Unpin, R: Rng + CryptoRng>(
+    stream: &mut S,
+    mut session: VerifierSession<F>,
+    rng: &mut R,
+) -> Result<LinearGKRSubclaim<F>, NetError> {
+    loop {
+        let frame = read_frame(stream).await?;
+        session.receive_message(&decode_message(&frame)?)?;
+        if let Some(r) = session.next_challenge(rng) {
+            let mut out = Vec::new();
+            write_field(&mut out, &r, ENDIANNESS);
+            write_frame(stream, &out).await?;
+        } else if session.stage() == Stage::Done {
+            return Ok(session.finish()?);
+        }
+    }
+}
+
+/// プローバのメッセージをフレームの本体に符号化する
+pub fn encode_message<F: PrimeField>(msg: &ProverMessage<F>) -> Vec<u8> {
+    let mut out = Vec::new();
+    match msg {
+        ProverMessage::ClaimedSum(sum) => {
+            out.push(TAG_CLAIMED_SUM);
+            write_field(&mut out, sum, ENDIANNESS);
+        }
+        ProverMessage::Round(evals) => {
+            out.push(TAG_ROUND);
+            write_len(&mut out, evals.len(), ENDIANNESS);
+            for e in evals {
+                write_field(&mut out, e, ENDIANNESS);
+            }
+        }
+        ProverMessage::FinalEvaluations { f1_at_guv, f2_at_u, f3_at_v } => {
+            out.push(TAG_FINAL_EVALUATIONS);
+            for e in [f1_at_guv, f2_at_u, f3_at_v] {
+                write_field(&mut out, e, ENDIANNESS);
+            }
+        }
+    }
+    out
+}
+
+/// `encode_message` の逆。余分なバイトは拒否する
+pub fn decode_message<F: PrimeField>(frame: &[u8]) -> Result<ProverMessage<F>, NetError> {
+    let (&tag, mut input) = frame.split_first().ok_or(SerializationError::UnexpectedEnd)?;
+    let msg = match tag {
+        TAG_CLAIMED_SUM => ProverMessage::ClaimedSum(read_field(&mut input, ENDIANNESS)?),
+        TAG_ROUND => {
+            let len = read_len(&mut input, ENDIANNESS)?;
+            if len > input.len() {
+                return Err(SerializationError::UnexpectedEnd.into());
+            }
+            ProverMessage::Round((0..len).map(|_| read_field(&mut input, ENDIANNESS)).collect::<Result<_, _>>()?)
+        }
+        TAG_FINAL_EVALUATIONS => ProverMessage::FinalEvaluations {
+            f1_at_guv: read_field(&mut input, ENDIANNESS)?,
+            f2_at_u: read_field(&mut input, ENDIANNESS)?,
+            f3_at_v: read_field(&mut input, ENDIANNESS)?,
+        },
+        other => return Err(NetError::UnknownMessage(other)),
+    };
+    if !input.is_empty() {
+        return Err(SerializationError::TrailingBytes(input.len()).into());
+    }
+    Ok(msg)
+}
+
+fn decode_challenge<F: PrimeField>(frame: &[u8]) -> Result<F, SerializationError> {
+    let mut input = frame;
+    let r = read_field(&mut input, ENDIANNESS)?;
+    if !input.is_empty() {
+        return Err(SerializationError::TrailingBytes(input.len()));
+    }
+    Ok(r)
+}
+
+async fn write_frame<S: AsyncWrite + Unpin>(stream: &mut S, body: &[u8]) -> Result<(), NetError> {
+    let mut header = Vec::with_capacity(4);
+    write_len(&mut header, body.len(), ENDIANNESS);
+    stream.write_all(&header).await?;
+    stream.write_all(body).await?;
+    // 相手の返事を待つ前に，バッファされたストリームでも確実に送る
+    stream.flush().await?;
+    Ok(())
+}
+
+async fn read_frame<S: AsyncRead + Unpin>(stream: &mut S) -> Result<Vec<u8>, NetError> {
+    let mut header = [0u8; 4];
+    stream.read_exact(&mut header).await?;
+    let len = read_len(&mut &header[..], ENDIANNESS)?;
+    if len > MAX_FRAME_LEN {
+        return Err(NetError::FrameTooLarge(len));
+    }
+    let mut body = vec![0u8; len];
+    stream.read_exact(&mut body).await?;
+    Ok(body)
+}
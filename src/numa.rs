@@ -0,0 +1,155 @@
+// src/numa.rs
+//
+// 複数ソケットの prover 機で巨大な表がノード間を行き来しないよう，
+// 表のページ配置（インターリーブ / 分割ごとのノード固定）とワーカーのピン留めを行う。
+// Linux 専用で，`numa` feature が有効なときだけコンパイルされる。
+
+use ark_ff::Field;
+use std::io;
+use std::path::Path;
+
+use crate::ml_extension::DenseMLE;
+
+const MPOL_BIND: libc::c_int = 2;
+const MPOL_INTERLEAVE: libc::c_int = 3;
+const MPOL_MF_MOVE: libc::c_uint = 1 << 1;
+
+/// 表のページをどのノードに置くか
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NumaPolicy {
+    /// OS の既定（最初に触れたスレッドのノード）
+    Default,
+    /// 全ノードにページ単位で交互に配置する
+    Interleave,
+    /// 表をノード数に等分し，i 番目の分割をノード i に置く
+    /// （`pinned_thread_pool` のワーカー i が分割 i を処理する前提）
+    NodeLocal,
+}
+
+/// オンラインな NUMA ノードの番号一覧（情報が取れなければ [0]）
+pub fn online_nodes() -> Vec<usize> {
+    std::fs::read_to_string("/sys/devices/system/node/online")
+        .ok()
+        .and_then(|s| parse_cpu_list(s.trim()))
+        .filter(|nodes| !nodes.is_empty())
+        .unwrap_or_else(|| vec![0])
+}
+
+/// ノードに属する CPU の一覧
+pub fn node_cpus(node: usize) -> Vec<usize> {
+    let path = format!("/sys/devices/system/node/node{}/cpulist", node);
+    if !Path::new(&path).exists() {
+        return (0..num_cpus()).collect();
+    }
+    std::fs::read_to_string(path).ok().and_then(|s| parse_cpu_list(s.trim())).unwrap_or_default()
+}
+
+fn num_cpus() -> usize {
+    std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+}
+
+/// "0-3,8,10-11" 形式のリストを展開する
+pub fn parse_cpu_list(s: &str) -> Option<Vec<usize>> {
+    let mut out = Vec::new();
+    for part in s.split(',').filter(|p| !p.is_empty()) {
+        match part.split_once('-') {
+            Some((a, b)) => out.extend(a.parse::<usize>().ok()?..=b.parse::<usize>().ok()?),
+            None => out.push(part.parse().ok()?),
+        }
+    }
+    Some(out)
+}
+
+/// 呼び出したスレッドを指定 CPU 群に固定する
+pub fn pin_current_thread(cpus: &[usize]) -> io::Result<()> {
+    // SAFETY: cpu_set_t はゼロ初期化が有効な値で，CPU_SET は範囲内の添字のみ受け取る
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        for &cpu in cpus.iter().filter(|&&c| c < libc::CPU_SETSIZE as usize) {
+            libc::CPU_SET(cpu, &mut set);
+        }
+        if libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+/// ワーカー i をノード `nodes[i % nodes.len()]` の CPU に固定したスレッドプール
+///
+/// ピン留めに失敗した場合（コンテナ内など）は固定せずに動作を続ける。
+pub fn pinned_thread_pool(num_threads: usize) -> Result<rayon::ThreadPool, rayon::ThreadPoolBuildError> {
+    let nodes = online_nodes();
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .start_handler(move |i| {
+            let _ = pin_current_thread(&node_cpus(nodes[i % nodes.len()]));
+        })
+        .build()
+}
+
+/// 指定ポリシーで `len` 要素の 0 埋めされた表を確保する
+///
+/// 確保直後（ページに触れる前）にメモリポリシーを設定してから 0 埋めするので，
+/// 最初のアクセスでページが目的のノードに割り当てられる。ポリシーの設定は
+/// ベストエフォートで，カーネルが拒否した場合は既定の配置になる。
+pub fn allocate_table<F: Field>(len: usize, policy: NumaPolicy) -> Vec<F> {
+    let mut table: Vec<F> = Vec::with_capacity(len);
+    let bytes = len * std::mem::size_of::<F>();
+    let base = table.as_mut_ptr() as usize;
+    let nodes = online_nodes();
+    match policy {
+        NumaPolicy::Default => {}
+        NumaPolicy::Interleave => {
+            let _ = bind_range(base, bytes, MPOL_INTERLEAVE, &nodes);
+        }
+        NumaPolicy::NodeLocal => {
+            let shard = bytes.div_ceil(nodes.len());
+            for (i, node) in nodes.iter().enumerate() {
+                let start = (i * shard).min(bytes);
+                let end = ((i + 1) * shard).min(bytes);
+                let _ = bind_range(base + start, end - start, MPOL_BIND, &[*node]);
+            }
+        }
+    }
+    table.resize(len, F::zero());
+    table
+}
+
+/// `allocate_table` で確保した 0 の MLE
+pub fn zeroed_mle<F: Field>(num_vars: usize, policy: NumaPolicy) -> DenseMLE<F> {
+    DenseMLE::from_evaluations_vec(num_vars, allocate_table(1 << num_vars, policy))
+}
+
+/// [addr, addr + len) に完全に含まれるページに mbind(2) でポリシーを設定する
+/// （他の確保と共有しうる端のページには触れない）
+fn bind_range(addr: usize, len: usize, mode: libc::c_int, nodes: &[usize]) -> io::Result<()> {
+    // SAFETY: sysconf は副作用のない問い合わせ
+    let page = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+    let start = addr.div_ceil(page) * page;
+    let end = (addr + len) & !(page - 1);
+    if end <= start {
+        return Ok(());
+    }
+    let max_node = nodes.iter().copied().max().unwrap_or(0);
+    let mut mask = vec![0 as libc::c_ulong; max_node / libc::c_ulong::BITS as usize + 1];
+    for &node in nodes {
+        mask[node / libc::c_ulong::BITS as usize] |= 1 << (node % libc::c_ulong::BITS as usize);
+    }
+    // SAFETY: 範囲は自分が確保したバッファ内のページで，mask は maxnode ビット以上ある
+    let ret = unsafe {
+        libc::syscall(
+            libc::SYS_mbind,
+            start as *mut libc::c_void,
+            end - start,
+            mode,
+            mask.as_ptr(),
+            (mask.len() * libc::c_ulong::BITS as usize) as libc::c_ulong,
+            MPOL_MF_MOVE,
+        )
+    };
+    if ret != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
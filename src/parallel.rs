@@ -0,0 +1,21 @@
+// src/parallel.rs
+//
+// `parallel` feature で証明に使うスレッド数の設定。
+// 何も設定しなければ rayon のグローバルプール（RAYON_NUM_THREADS か論理コア数）で動く。
+
+use rayon::{ThreadPoolBuildError, ThreadPoolBuilder};
+
+/// グローバルプールのスレッド数を n にする（最初の並列処理より前に 1 回だけ設定できる）
+pub fn set_num_threads(n: usize) -> Result<(), ThreadPoolBuildError> {
+    ThreadPoolBuilder::new().num_threads(n).build_global()
+}
+
+/// 現在のプールのスレッド数
+pub fn num_threads() -> usize {
+    rayon::current_num_threads()
+}
+
+/// n スレッドの専用プールの中で f を実行する（グローバルプールの設定は変えない）
+pub fn with_num_threads<R: Send>(n: usize, f: impl FnOnce() -> R + Send) -> Result<R, ThreadPoolBuildError> {
+    Ok(ThreadPoolBuilder::new().num_threads(n).build()?.install(f))
+}
@@ -0,0 +1,358 @@
+// src/pcs.rs
+//
+// 多重線形多項式のコミットメント方式（PCS）。
+// GKR の最後に残る入力層の主張 Ṽ_in(r) を，入力そのものの代わりにコミットメントの開示で確かめるために使う
+// （`GKRProver::prove_committed` / `GKRVerifier::verify_committed`）。
+//
+// 具体的な方式として，多重線形版の KZG（Papamanthou–Shi–Tamassia 2013）を BLS12-381 上で実装する。
+// f(X) - f(z) = Σ_i (X_i - z_i)·q_i(X_{i+1}, ..., X_n) と分解し，商 q_i のコミットメントを開示の証明とする。
+// 検証はペアリングの等式 e(C - v·g, h) = Π_i e(π_i, h^{τ_i - z_i}) で，証明の大きさも検証の手間も O(n)。
+// SRS（`KzgSrs`）は一度作って保存し，入力層や配線述語の MLE の変数数に合わせて `trim` で切り出す。
+//
+// 信頼できるセットアップを使わない方式として，ペアリングの無い群の上の Hyrax（Pedersen の行列コミットメント）も置く。
+// 2^n 個の値を 2^{⌊n/2⌋} × 2^{⌈n/2⌉} の行列に並べて行ごとに Pedersen でコミットし，
+// 点 (x_row, x_col) では行を eq(x_row, ·) で結合したベクトルを開示する。コミットメントと証明は O(√N)。
+// ハッシュだけに基づく方式は `basefold` にある。
+
+use ark_bls12_381::{Bls12_381, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::pairing::Pairing;
+use ark_ec::{CurveGroup, PrimeGroup, VariableBaseMSM};
+use ark_ed_on_bls12_381::EdwardsProjective;
+use ark_ff::{PrimeField, UniformRand, Zero};
+use ark_serialize::CanonicalSerialize;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use sha3::{Digest, Sha3_256};
+use std::marker::PhantomData;
+
+use crate::eq::eq_table;
+use crate::error::Error;
+use crate::ml_extension::{DenseMLE, IndexOrder, SparseMLE};
+use crate::serialization::{read_len, read_point, write_len, write_point, Endianness, PointEncoding, SerializationError};
+
+/// 多重線形多項式のコミットメント方式
+pub trait MultilinearPCS<F: PrimeField> {
+    /// コミットと開示に使う公開パラメータ
+    type ProverParam;
+    /// 検証に使う公開パラメータ
+    type VerifierParam;
+    type Commitment: Clone + std::fmt::Debug + PartialEq;
+    /// 1 点での開示の証明
+    type Proof: Clone + std::fmt::Debug + PartialEq;
+
+    fn commit(pp: &Self::ProverParam, mle: &DenseMLE<F>) -> Self::Commitment;
+
+    /// mle(point) の値と，その値が正しいことの証明
+    fn open(pp: &Self::ProverParam, mle: &DenseMLE<F>, point: &[F]) -> (F, Self::Proof);
+
+    /// `commitment` の多項式の `point` での値が `value` であることを確かめる
+    fn verify(
+        vp: &Self::VerifierParam,
+        commitment: &Self::Commitment,
+        point: &[F],
+        value: F,
+        proof: &Self::Proof,
+    ) -> Result<(), Error>;
+
+    /// transcript に吸収するためのコミットメントのバイト列
+    fn commitment_bytes(commitment: &Self::Commitment) -> Vec<u8>;
+
+    /// 疎な MLE（配線述語など）へのコミットメント。既定では密な表に直してコミットする
+    fn commit_sparse(pp: &Self::ProverParam, mle: &SparseMLE<F>) -> Self::Commitment {
+        Self::commit(pp, &mle.to_dense_multilinear_extension())
+    }
+}
+
+/// 多重線形 KZG の SRS（構造化参照文字列）
+///
+/// `trim` で必要な変数数の分だけを切り出して使う。`from_bytes` で読んだ SRS の整合性は検査しないので，
+/// 信頼できるセットアップの出力であることは利用者が保証する。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KzgSrs {
+    pub num_vars: usize,
+    /// `powers[k][b]` = g^{eq(τ_{n-k+1..n}, b)}（b ∈ {0,1}^k，先頭の変数が最上位ビット）
+    pub powers: Vec<Vec<G1Affine>>,
+    pub g: G1Affine,
+    pub h: G2Affine,
+    /// h^{τ_i}
+    pub h_tau: Vec<G2Affine>,
+}
+
+impl KzgSrs {
+    /// `num_vars` 変数以下の多項式のためのパラメータを切り出す（τ の末尾 `num_vars` 成分を使う）
+    pub fn trim(&self, num_vars: usize) -> (KzgProverParam, KzgVerifierParam) {
+        assert!(num_vars <= self.num_vars, "SRS supports at most {} variables", self.num_vars);
+        let pp = KzgProverParam { num_vars, powers: self.powers[..=num_vars].to_vec() };
+        let vp = KzgVerifierParam {
+            num_vars,
+            g: self.g,
+            h: self.h,
+            h_tau: self.h_tau[self.num_vars - num_vars..].to_vec(),
+        };
+        (pp, vp)
+    }
+
+    /// num_vars（u32 LE），g，h，h^{τ_1..τ_n}，powers[0], ..., powers[n] の順に書き出す
+    pub fn to_bytes(&self, encoding: PointEncoding) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_len(&mut out, self.num_vars, Endianness::Little);
+        write_point(&mut out, &self.g, encoding).expect("writing into a Vec cannot fail");
+        write_point(&mut out, &self.h, encoding).expect("writing into a Vec cannot fail");
+        for point in self.h_tau.iter() {
+            write_point(&mut out, point, encoding).expect("writing into a Vec cannot fail");
+        }
+        for point in self.powers.iter().flatten() {
+            write_point(&mut out, point, encoding).expect("writing into a Vec cannot fail");
+        }
+        out
+    }
+
+    /// `to_bytes` の出力を読む（各点が曲線上・部分群に属することは検査する）
+    pub fn from_bytes(bytes: &[u8], encoding: PointEncoding) -> Result<Self, SerializationError> {
+        let mut input = bytes;
+        let num_vars = read_len(&mut input, Endianness::Little)?;
+        let g = read_point(&mut input, encoding)?;
+        let h = read_point(&mut input, encoding)?;
+        let h_tau = (0..num_vars).map(|_| read_point(&mut input, encoding)).collect::<Result<_, _>>()?;
+        let powers = (0..=num_vars)
+            .map(|k| (0..1usize << k).map(|_| read_point(&mut input, encoding)).collect())
+            .collect::<Result<_, _>>()?;
+        if !input.is_empty() {
+            return Err(SerializationError::TrailingBytes(input.len()));
+        }
+        Ok(KzgSrs { num_vars, powers, g, h, h_tau })
+    }
+}
+
+/// 多重線形 KZG のコミット・開示用のパラメータ
+#[derive(Clone, Debug)]
+pub struct KzgProverParam {
+    pub num_vars: usize,
+    /// `powers[k][b]` = g^{eq(τ_{n-k+1..n}, b)}（b ∈ {0,1}^k，先頭の変数が最上位ビット）
+    pub powers: Vec<Vec<G1Affine>>,
+}
+
+/// 多重線形 KZG の検証用のパラメータ
+#[derive(Clone, Debug)]
+pub struct KzgVerifierParam {
+    pub num_vars: usize,
+    pub g: G1Affine,
+    pub h: G2Affine,
+    /// h^{τ_i}
+    pub h_tau: Vec<G2Affine>,
+}
+
+/// BLS12-381 上の多重線形 KZG
+///
+/// `num_vars` 変数で作ったパラメータで，それ以下の変数数の多項式をコミットできる
+/// （k 変数の多項式には τ の末尾 k 成分を使う）。
+pub struct MultilinearKzg;
+
+impl MultilinearKzg {
+    /// 信頼できるセットアップ。秘密の τ は `rng` から引いて捨てる
+    pub fn gen_srs<R: Rng>(num_vars: usize, rng: &mut R) -> KzgSrs {
+        let tau: Vec<Fr> = (0..num_vars).map(|_| Fr::rand(rng)).collect();
+        let (g, h) = (G1Projective::generator(), G2Projective::generator());
+        let powers = (0..=num_vars)
+            .map(|k| {
+                let scaled: Vec<G1Projective> = eq_table(&tau[num_vars - k..]).iter().map(|e| g * e).collect();
+                G1Projective::normalize_batch(&scaled)
+            })
+            .collect();
+        let h_tau: Vec<G2Projective> = tau.iter().map(|t| h * t).collect();
+        KzgSrs {
+            num_vars,
+            powers,
+            g: g.into_affine(),
+            h: h.into_affine(),
+            h_tau: G2Projective::normalize_batch(&h_tau),
+        }
+    }
+
+    /// `gen_srs` で作った SRS を全て使うパラメータ
+    pub fn setup<R: Rng>(num_vars: usize, rng: &mut R) -> (KzgProverParam, KzgVerifierParam) {
+        Self::gen_srs(num_vars, rng).trim(num_vars)
+    }
+
+    fn commit_table(pp: &KzgProverParam, table: &[Fr]) -> G1Affine {
+        let k = table.len().trailing_zeros() as usize;
+        G1Projective::msm_unchecked(&pp.powers[k], table).into_affine()
+    }
+}
+
+impl MultilinearPCS<Fr> for MultilinearKzg {
+    type ProverParam = KzgProverParam;
+    type VerifierParam = KzgVerifierParam;
+    type Commitment = G1Affine;
+    /// 商 q_1, ..., q_k のコミットメント
+    type Proof = Vec<G1Affine>;
+
+    fn commit(pp: &KzgProverParam, mle: &DenseMLE<Fr>) -> G1Affine {
+        assert!(mle.num_vars <= pp.num_vars, "polynomial has more variables than the setup");
+        Self::commit_table(pp, &mle.to_order(IndexOrder::BigEndian).evaluations)
+    }
+
+    fn open(pp: &KzgProverParam, mle: &DenseMLE<Fr>, point: &[Fr]) -> (Fr, Vec<G1Affine>) {
+        assert!(mle.num_vars <= pp.num_vars, "polynomial has more variables than the setup");
+        assert_eq!(point.len(), mle.num_vars);
+        let mut table = mle.to_order(IndexOrder::BigEndian).evaluations;
+        let mut proof = Vec::with_capacity(point.len());
+        for z in point.iter() {
+            // 先頭の変数について f = lo + X·(hi - lo) と分け，q = hi - lo をコミットして X = z を代入する
+            let half = table.len() / 2;
+            let (lo, hi) = table.split_at(half);
+            let q: Vec<Fr> = hi.iter().zip(lo.iter()).map(|(h, l)| *h - l).collect();
+            proof.push(Self::commit_table(pp, &q));
+            table = lo.iter().zip(q.iter()).map(|(l, d)| *l + *z * d).collect();
+        }
+        (table[0], proof)
+    }
+
+    fn verify(
+        vp: &KzgVerifierParam,
+        commitment: &G1Affine,
+        point: &[Fr],
+        value: Fr,
+        proof: &Vec<G1Affine>,
+    ) -> Result<(), Error> {
+        let k = point.len();
+        if k > vp.num_vars {
+            return Err(Error::LengthMismatch { what: "opening point coordinates", expected: vp.num_vars, found: k });
+        }
+        if proof.len() != k {
+            return Err(Error::LengthMismatch { what: "opening quotients", expected: k, found: proof.len() });
+        }
+        let lhs = Bls12_381::pairing(*commitment - vp.g * value, vp.h);
+        let shifts: Vec<G2Projective> =
+            vp.h_tau[vp.num_vars - k..].iter().zip(point.iter()).map(|(ht, z)| *ht - vp.h * z).collect();
+        let rhs = Bls12_381::multi_pairing(proof.iter().copied(), G2Projective::normalize_batch(&shifts));
+        if lhs != rhs {
+            return Err(Error::EvaluationMismatch("polynomial commitment opening"));
+        }
+        Ok(())
+    }
+
+    fn commitment_bytes(commitment: &G1Affine) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        commitment.serialize_compressed(&mut bytes).expect("serializing into a Vec cannot fail");
+        bytes
+    }
+
+    /// 非零の値だけで MSM をとる（配線述語のように非零要素が O(2^l) 個なら，2^{3l} の表を作らずに済む）
+    fn commit_sparse(pp: &KzgProverParam, mle: &SparseMLE<Fr>) -> G1Affine {
+        assert!(mle.num_vars <= pp.num_vars, "polynomial has more variables than the setup");
+        let (bases, scalars): (Vec<G1Affine>, Vec<Fr>) =
+            mle.big_endian_entries().map(|(index, val)| (pp.powers[mle.num_vars][index], val)).unzip();
+        G1Projective::msm_unchecked(&bases, &scalars).into_affine()
+    }
+}
+
+/// Hyrax の公開パラメータ（コミットと検証で共通）
+#[derive(Clone, Debug)]
+pub struct HyraxParams<G: CurveGroup> {
+    pub num_vars: usize,
+    /// 列ごとの Pedersen の生成元（2^{⌈num_vars/2⌉} 個）
+    pub generators: Vec<G::Affine>,
+}
+
+/// 群 `G` の上の Hyrax
+///
+/// `num_vars` 変数で作ったパラメータで，それ以下の変数数の多項式をコミットできる
+/// （k 変数の多項式は 2^{⌊k/2⌋} 行 2^{⌈k/2⌉} 列に並べ，先頭の生成元だけを使う）。
+pub struct Hyrax<G: CurveGroup>(PhantomData<G>);
+
+/// ペアリングの無い Jubjub（BLS12-381 の上に作られた Edwards 曲線）の上の Hyrax
+pub type JubjubHyrax = Hyrax<EdwardsProjective>;
+
+impl<G: CurveGroup> Hyrax<G> {
+    /// 透明なセットアップ。生成元は `label` のハッシュを種にした乱数から作るので，離散対数を誰も知らない
+    pub fn setup(num_vars: usize, label: &[u8]) -> HyraxParams<G> {
+        let mut rng = StdRng::from_seed(Sha3_256::digest(label).into());
+        let generators: Vec<G> = (0..1 << num_vars.div_ceil(2)).map(|_| G::rand(&mut rng)).collect();
+        HyraxParams { num_vars, generators: G::normalize_batch(&generators) }
+    }
+
+    /// k 変数の多項式の行の変数数と列の変数数
+    fn split(num_vars: usize) -> (usize, usize) {
+        (num_vars / 2, num_vars.div_ceil(2))
+    }
+}
+
+impl<G: CurveGroup> MultilinearPCS<G::ScalarField> for Hyrax<G> {
+    type ProverParam = HyraxParams<G>;
+    type VerifierParam = HyraxParams<G>;
+    /// 行ごとの Pedersen コミットメント
+    type Commitment = Vec<G::Affine>;
+    /// 行を eq(x_row, ·) で結合したベクトル
+    type Proof = Vec<G::ScalarField>;
+
+    fn commit(pp: &HyraxParams<G>, mle: &DenseMLE<G::ScalarField>) -> Vec<G::Affine> {
+        assert!(mle.num_vars <= pp.num_vars, "polynomial has more variables than the setup");
+        let (_, cols) = Self::split(mle.num_vars);
+        let bases = &pp.generators[..1 << cols];
+        let rows: Vec<G> = mle
+            .to_order(IndexOrder::BigEndian)
+            .evaluations
+            .chunks(1 << cols)
+            .map(|row| G::msm_unchecked(bases, row))
+            .collect();
+        G::normalize_batch(&rows)
+    }
+
+    fn open(
+        pp: &HyraxParams<G>,
+        mle: &DenseMLE<G::ScalarField>,
+        point: &[G::ScalarField],
+    ) -> (G::ScalarField, Vec<G::ScalarField>) {
+        assert!(mle.num_vars <= pp.num_vars, "polynomial has more variables than the setup");
+        assert_eq!(point.len(), mle.num_vars);
+        let (rows, cols) = Self::split(mle.num_vars);
+        let table = mle.to_order(IndexOrder::BigEndian).evaluations;
+        let mut combined = vec![G::ScalarField::zero(); 1 << cols];
+        for (row, e) in table.chunks(1 << cols).zip(eq_table(&point[..rows])) {
+            for (c, t) in combined.iter_mut().zip(row.iter()) {
+                *c += e * t;
+            }
+        }
+        let value = combined.iter().zip(eq_table(&point[rows..])).map(|(c, e)| *c * e).sum();
+        (value, combined)
+    }
+
+    fn verify(
+        vp: &HyraxParams<G>,
+        commitment: &Vec<G::Affine>,
+        point: &[G::ScalarField],
+        value: G::ScalarField,
+        proof: &Vec<G::ScalarField>,
+    ) -> Result<(), Error> {
+        let k = point.len();
+        if k > vp.num_vars {
+            return Err(Error::LengthMismatch { what: "opening point coordinates", expected: vp.num_vars, found: k });
+        }
+        let (rows, cols) = Self::split(k);
+        if commitment.len() != 1 << rows {
+            return Err(Error::LengthMismatch { what: "row commitments", expected: 1 << rows, found: commitment.len() });
+        }
+        if proof.len() != 1 << cols {
+            return Err(Error::LengthMismatch { what: "combined row", expected: 1 << cols, found: proof.len() });
+        }
+        // 行のコミットメントの結合が，開示したベクトルの Pedersen コミットメントと一致するか
+        let combined_commitment = G::msm_unchecked(commitment, &eq_table(&point[..rows]));
+        if combined_commitment != G::msm_unchecked(&vp.generators[..1 << cols], proof) {
+            return Err(Error::EvaluationMismatch("polynomial commitment opening"));
+        }
+        let at_point: G::ScalarField = proof.iter().zip(eq_table(&point[rows..])).map(|(c, e)| *c * e).sum();
+        if at_point != value {
+            return Err(Error::EvaluationMismatch("polynomial commitment opening"));
+        }
+        Ok(())
+    }
+
+    fn commitment_bytes(commitment: &Vec<G::Affine>) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for row in commitment.iter() {
+            row.serialize_compressed(&mut bytes).expect("serializing into a Vec cannot fail");
+        }
+        bytes
+    }
+}
@@ -0,0 +1,136 @@
+// src/poseidon.rs
+//
+// 素体 F 上の Poseidon 置換と，それを使うスポンジ（`TranscriptConfig::Poseidon` の中身）。
+//
+// 状態は幅 3（rate 2，capacity 1），S-box は x^α（α は gcd(α, p - 1) = 1 となる最小の奇素数），
+// 完全ラウンド 8 回と部分ラウンド 57 回。ラウンド定数は SHA3-256("gkr-poseidon" ‖ 添字) を法で還元した値，
+// MDS 行列は Cauchy 行列 1 / (x_i + y_j)（x_i = i，y_j = 幅 + j）で，どちらも回路の中で同じ手順で再現できる。
+// 参照実装（Grain LFSR で生成した定数）とは値が異なるので，他の実装の Poseidon とは互換でない。
+
+use ark_ff::{BigInteger, PrimeField};
+use sha3::{Digest, Sha3_256};
+
+/// 状態の幅
+pub const WIDTH: usize = 3;
+/// 1 回の置換で吸収する要素数
+pub const RATE: usize = 2;
+pub const FULL_ROUNDS: usize = 8;
+pub const PARTIAL_ROUNDS: usize = 57;
+
+/// 置換のパラメータ
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoseidonParams<F: PrimeField> {
+    pub alpha: u64,
+    /// ラウンドごとの加算定数（FULL_ROUNDS + PARTIAL_ROUNDS 行）
+    pub round_constants: Vec<[F; WIDTH]>,
+    pub mds: [[F; WIDTH]; WIDTH],
+}
+
+impl<F: PrimeField> PoseidonParams<F> {
+    /// 体 F のパラメータを生成する
+    pub fn new() -> Self {
+        let alpha = sbox_exponent::<F>();
+        let round_constants = (0..FULL_ROUNDS + PARTIAL_ROUNDS)
+            .map(|r| std::array::from_fn(|i| constant((r * WIDTH + i) as u64)))
+            .collect();
+        let mds = std::array::from_fn(|i| {
+            std::array::from_fn(|j| F::from((i + WIDTH + j) as u64).inverse().expect("x_i + y_j is non-zero"))
+        });
+        PoseidonParams { alpha, round_constants, mds }
+    }
+
+    /// 状態を置換する
+    pub fn permute(&self, state: &mut [F; WIDTH]) {
+        let half = FULL_ROUNDS / 2;
+        for (r, constants) in self.round_constants.iter().enumerate() {
+            for (s, c) in state.iter_mut().zip(constants) {
+                *s += c;
+            }
+            if r < half || r >= half + PARTIAL_ROUNDS {
+                for s in state.iter_mut() {
+                    *s = s.pow([self.alpha]);
+                }
+            } else {
+                state[0] = state[0].pow([self.alpha]);
+            }
+            let prev = *state;
+            for (s, row) in state.iter_mut().zip(self.mds.iter()) {
+                *s = row.iter().zip(prev.iter()).map(|(m, x)| *m * x).sum();
+            }
+        }
+    }
+}
+
+impl<F: PrimeField> Default for PoseidonParams<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// S-box の指数 α（gcd(α, p - 1) = 1 となる最小の奇素数）
+pub(crate) fn sbox_exponent<F: PrimeField>() -> u64 {
+    [3u64, 5, 7, 11, 13, 17]
+        .into_iter()
+        .find(|&a| modulus_minus_one_mod::<F>(a) != 0)
+        .expect("no small S-box exponent for this field")
+}
+
+/// (p - 1) mod a
+fn modulus_minus_one_mod<F: PrimeField>(a: u64) -> u64 {
+    let mut p_minus_one = F::MODULUS;
+    p_minus_one.sub_with_borrow(&F::BigInt::from(1u64));
+    p_minus_one.to_bits_be().into_iter().fold(0, |r, b| (2 * r + b as u64) % a)
+}
+
+/// 添字 i のラウンド定数
+fn constant<F: PrimeField>(i: u64) -> F {
+    let mut hasher = Sha3_256::new();
+    hasher.update(b"gkr-poseidon");
+    hasher.update(i.to_le_bytes());
+    F::from_le_bytes_mod_order(&hasher.finalize())
+}
+
+/// Poseidon のスポンジ（duplex）。吸収は rate の位置に足し込み，埋まるたびに置換する。
+/// 絞り出しは毎回置換してから先頭の要素を返す
+#[derive(Clone, Debug)]
+pub struct PoseidonSponge<F: PrimeField> {
+    params: PoseidonParams<F>,
+    state: [F; WIDTH],
+    absorbed: usize,
+}
+
+impl<F: PrimeField> PoseidonSponge<F> {
+    pub fn new() -> Self {
+        PoseidonSponge { params: PoseidonParams::new(), state: [F::zero(); WIDTH], absorbed: 0 }
+    }
+
+    pub fn absorb(&mut self, x: F) {
+        self.state[self.absorbed] += x;
+        self.absorbed += 1;
+        if self.absorbed == RATE {
+            self.params.permute(&mut self.state);
+            self.absorbed = 0;
+        }
+    }
+
+    /// バイト列を長さと (MODULUS_BIT_SIZE - 1) / 8 バイトずつの要素として吸収する
+    pub fn absorb_bytes(&mut self, bytes: &[u8]) {
+        self.absorb(F::from(bytes.len() as u64));
+        let chunk = (F::MODULUS_BIT_SIZE as usize - 1) / 8;
+        for c in bytes.chunks(chunk) {
+            self.absorb(F::from_le_bytes_mod_order(c));
+        }
+    }
+
+    pub fn squeeze(&mut self) -> F {
+        self.params.permute(&mut self.state);
+        self.absorbed = 0;
+        self.state[0]
+    }
+}
+
+impl<F: PrimeField> Default for PoseidonSponge<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
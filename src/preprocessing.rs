@@ -0,0 +1,156 @@
+// src/preprocessing.rs
+//
+// 固定した回路の前処理（ホログラフィック GKR）。
+// 層ごとの積の形の配線述語 f1（add, mul, relay, constant をまとめたもの）は回路だけで決まるので，
+// 一度だけ作って `MultilinearPCS` でコミットしておく。証明者は作った表を使い回し，
+// 各層のサブクレームの f1 の値をコミットメントの開示で示す。
+// 検証者は回路そのものを持たず，`VerifyingKey`（ダイジェスト，形，コミットメント）だけで検証する。
+//
+// 開示は f1 を密な表（(k + 1)·(l + 1) 変数）として行うので，証明者の手間は層の幅の k + 1 乗に比例する。
+
+use ark_ff::PrimeField;
+
+use crate::circuit::Circuit;
+use crate::circuit_prover::{product_forms, wiring_point, ClaimReduction, GKRProof, GKRProver, GKRVerifier};
+use crate::error::Error;
+use crate::ml_extension::{DenseMLE, SparseMLE};
+use crate::pcs::MultilinearPCS;
+use crate::statement::{CircuitDigest, Statement};
+use crate::transcript::Transcript;
+use crate::wiring;
+
+/// 前処理した回路の証明を初期化するラベル
+pub const PREPROCESSED_LABEL: &[u8] = b"gkr-circuit-preprocessed";
+
+/// 検証者が回路の代わりに持つ鍵
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifyingKey<C> {
+    /// 回路と体のダイジェスト
+    pub circuit_digest: CircuitDigest,
+    pub num_inputs: usize,
+    pub num_outputs: usize,
+    /// 全ての層で共通の変数数 l
+    pub num_vars: usize,
+    /// 出力側から順に，層ごとの因子数 k
+    pub fan_ins: Vec<usize>,
+    /// 出力側から順に，層ごとの f1 のコミットメント
+    pub wiring_commitments: Vec<C>,
+}
+
+/// 証明者が使う前処理済みの回路
+#[derive(Clone)]
+pub struct ProvingKey<F: PrimeField, C> {
+    pub circuit: Circuit,
+    /// 出力側から順に，層ごとの積の形の配線述語 f1
+    pub wirings: Vec<SparseMLE<F>>,
+    /// 開示に使う f1 の密な表
+    pub dense_wirings: Vec<DenseMLE<F>>,
+    pub verifying_key: VerifyingKey<C>,
+}
+
+/// 前処理した回路の証明
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PreprocessedGKRProof<F: PrimeField, O> {
+    pub proof: GKRProof<F>,
+    /// 層ごと，上の層の結合した主張の点ごとの f1 の値
+    pub wiring_evals: Vec<Vec<F>>,
+    /// `wiring_evals` の各値の開示の証明
+    pub wiring_openings: Vec<Vec<O>>,
+}
+
+impl<F: PrimeField, C> ProvingKey<F, C> {
+    /// 回路の配線述語を作ってコミットする（`pp` は (k + 1)·(l + 1) 変数まで扱えること）
+    pub fn preprocess<P: MultilinearPCS<F, Commitment = C>>(pp: &P::ProverParam, circuit: &Circuit) -> Self {
+        let wirings = product_forms::<F>(circuit);
+        let dense_wirings = wirings.iter().map(SparseMLE::to_dense_multilinear_extension).collect();
+        let verifying_key = VerifyingKey {
+            circuit_digest: CircuitDigest::new::<F>(circuit),
+            num_inputs: circuit.num_inputs,
+            num_outputs: circuit.layers[0].gates.len(),
+            num_vars: wiring::circuit_num_vars(circuit),
+            fan_ins: (0..circuit.depth()).map(|i| circuit.fan_in(i)).collect(),
+            wiring_commitments: wirings.iter().map(|f1| P::commit_sparse(pp, f1)).collect(),
+        };
+        ProvingKey { circuit: circuit.clone(), wirings, dense_wirings, verifying_key }
+    }
+
+    /// 入力に対する証明を作る。配線述語は前処理で作ったものを使い，f1 の値は開示で示す
+    pub fn prove<P: MultilinearPCS<F, Commitment = C>>(
+        &self,
+        pp: &P::ProverParam,
+        inputs: &[F],
+        reduction: ClaimReduction,
+    ) -> PreprocessedGKRProof<F, P::Proof> {
+        let values = self.circuit.evaluate(inputs);
+        let statement = self.verifying_key.statement::<F, P>(inputs, &values[0]);
+        let mut transcript = Transcript::for_statement(PREPROCESSED_LABEL, &statement);
+        let (proof, wiring_points, _) =
+            GKRProver::prove_layers(&self.circuit, &values, &self.wirings, reduction, &mut transcript);
+        let (wiring_evals, wiring_openings) = wiring_points
+            .iter()
+            .zip(self.dense_wirings.iter())
+            .map(|(points, f1)| points.iter().map(|point| P::open(pp, f1, point)).unzip())
+            .unzip();
+        PreprocessedGKRProof { proof, wiring_evals, wiring_openings }
+    }
+}
+
+impl<C> VerifyingKey<C> {
+    /// 主張：回路のダイジェスト，配線述語のコミットメント，入出力
+    fn statement<F: PrimeField, P: MultilinearPCS<F, Commitment = C>>(&self, inputs: &[F], outputs: &[F]) -> Statement<F> {
+        Statement {
+            circuit_digest: self.circuit_digest,
+            commitments: self.wiring_commitments.iter().map(P::commitment_bytes).collect(),
+            public_inputs: inputs.to_vec(),
+            public_outputs: outputs.to_vec(),
+        }
+    }
+
+    /// 回路を評価せずに，配線述語の開示で `proof.proof.outputs` が正しい出力であることを検証する
+    pub fn verify<F: PrimeField, P: MultilinearPCS<F, Commitment = C>>(
+        &self,
+        vp: &P::VerifierParam,
+        inputs: &[F],
+        proof: &PreprocessedGKRProof<F, P::Proof>,
+        reduction: ClaimReduction,
+    ) -> Result<(), Error> {
+        if inputs.len() != self.num_inputs {
+            return Err(Error::LengthMismatch { what: "inputs", expected: self.num_inputs, found: inputs.len() });
+        }
+        let depth = self.fan_ins.len();
+        if proof.wiring_evals.len() != depth || proof.wiring_openings.len() != depth {
+            let found = proof.wiring_evals.len().min(proof.wiring_openings.len());
+            return Err(Error::LengthMismatch { what: "layers of wiring openings", expected: depth, found });
+        }
+        let statement = self.statement::<F, P>(inputs, &proof.proof.outputs);
+        let mut transcript = Transcript::for_statement(PREPROCESSED_LABEL, &statement);
+        let (reduced, claim) = GKRVerifier::verify_layers(
+            self.num_vars,
+            self.num_outputs,
+            &self.fan_ins,
+            &proof.proof,
+            reduction,
+            &mut transcript,
+            |i, reduced, points, f1_claim| {
+                let (evals, openings) = (&proof.wiring_evals[i], &proof.wiring_openings[i]);
+                let num_points = reduced.points.len();
+                if evals.len() != num_points || openings.len() != num_points {
+                    let found = evals.len().min(openings.len());
+                    return Err(Error::LengthMismatch { what: "wiring openings", expected: num_points, found });
+                }
+                if reduced.combine(evals) != f1_claim {
+                    return Err(Error::EvaluationMismatch("wiring evaluation"));
+                }
+                for ((q, value), opening) in reduced.points.iter().zip(evals.iter()).zip(openings.iter()) {
+                    P::verify(vp, &self.wiring_commitments[i], &wiring_point(q, points), *value, opening)?;
+                }
+                Ok(())
+            },
+        )?;
+        // 入力層の主張は入力から直接確かめる
+        if reduced.evaluate(inputs) != claim {
+            return Err(Error::EvaluationMismatch("input layer at the final claim"));
+        }
+        Ok(())
+    }
+}
@@ -1,124 +1,475 @@
 // src/prover.rs
 
 use ark_bls12_381::Fr as ScalarField;
-use ark_ff::Zero;
+use ark_ff::PrimeField;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use crate::eq::eq_table;
+use crate::ml_extension::{DenseMLE, IndexOrder, SparseMLE};
+use crate::pcs::MultilinearPCS;
+use crate::challenge::ChallengeGenerator;
+use crate::self_check::direct_evaluation;
+use crate::statement::Statement;
+use crate::sumcheck::protocol::{self, ZkProof};
+use crate::sumcheck::MessageForm;
 use rand::Rng;
-use crate::ml_extension::{DenseMLE, SparseMLE};
-use crate::sumcheck::protocol;
-use crate::sumcheck::get_r;
+use crate::transcript::{labels, FiatShamirTranscript, Transcript};
+
+/// 非対話版で transcript を初期化するラベル
+pub const FIAT_SHAMIR_LABEL: &[u8] = b"linear-gkr";
 
 /// Linear GKR の証明メッセージ（フェーズごとに Prover から送られるメッセージ列）
-pub struct LinearGKRProof {
-    pub phase1_msgs: Vec<Vec<ScalarField>>,
-    pub phase2_msgs: Vec<Vec<ScalarField>>,
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinearGKRProof<F: PrimeField = ScalarField> {
+    /// Prover が主張する総和 ∑_{x,y} f1(g,x,y) f2(x) f3(y)
+    pub claimed_sum: F,
+    pub phase1_msgs: Vec<Vec<F>>,
+    pub phase2_msgs: Vec<Vec<F>>,
+    /// 最終点での値の主張 f1(g,u,v), f2(u), f3(v)
+    pub f1_at_guv: F,
+    pub f2_at_u: F,
+    pub f3_at_v: F,
+}
+
+/// Linear GKR のゼロ知識版の証明（各フェーズのラウンドメッセージをマスクで隠す）
+///
+/// 最終点での値 f1(g,u,v), f2(u), f3(v) はそのまま送るので，f2, f3 を隠すには
+/// 呼び出し側でこれらもコミットメントの開示に置き換えること。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ZkLinearGKRProof<F: PrimeField, C, O> {
+    pub claimed_sum: F,
+    pub phase1: ZkProof<F, C, O>,
+    pub phase2: ZkProof<F, C, O>,
+    pub f1_at_guv: F,
+    pub f2_at_u: F,
+    pub f3_at_v: F,
+}
+
+/// チャレンジ（g, u, v）に依存しない前計算の結果
+///
+/// 配線述語の並べ替えと証拠の配置だけを含むので，空いているコアで先に計算したり，
+/// 同じ回路・証拠に対する複数回の証明で使い回したりできる。
+pub struct LinearGKRPrecomputation<F: PrimeField = ScalarField> {
+    pub l: usize,
+    /// f1 の非零要素を (z, x, y, 値) に分解し，z の昇順に並べたもの
+    pub wiring: Vec<(usize, usize, usize, F)>,
+    pub f2: DenseMLE<F>,
+    pub f3: DenseMLE<F>,
+}
+
+impl<F: PrimeField> LinearGKRPrecomputation<F> {
+    /// f1(g, x, y) を 2l 変数の疎な MLE として取り出す（g の非零成分は 1 とみなす）
+    pub fn fix_g(&self, g: &[F]) -> SparseMLE<F> {
+        let l = self.l;
+        assert_eq!(g.len(), l);
+        let (start, end) = self.slice_of(g);
+        let evaluations = self.wiring[start..end]
+            .iter()
+            .map(|&(_, x, y, val)| ((y << l) | x, val))
+            .collect();
+        SparseMLE::new(2 * l, evaluations)
+    }
+
+    /// ブール点 g（非零成分は 1）に対応する配線の範囲 wiring[start..end]
+    fn slice_of(&self, g: &[F]) -> (usize, usize) {
+        let z = g.iter().fold(0, |acc, gi| (acc << 1) | usize::from(!gi.is_zero()));
+        (self.wiring.partition_point(|e| e.0 < z), self.wiring.partition_point(|e| e.0 <= z))
+    }
+
+    /// Σ_z weights[z]·f1(z, x, y) を 2l 変数の疎な MLE として取り出す
+    ///
+    /// `weights` に eq(g, ·) の表を渡せば任意の点 g での f1(g, x, y) になり，
+    /// 複数の点の eq の表の線形結合を渡せば，複数の主張をまとめた述語になる。
+    pub fn fix_weights(&self, weights: &[F]) -> SparseMLE<F> {
+        let l = self.l;
+        assert_eq!(weights.len(), 1 << l);
+        let mut evaluations = HashMap::new();
+        for &(z, x, y, val) in self.wiring.iter() {
+            if !weights[z].is_zero() {
+                *evaluations.entry((y << l) | x).or_insert_with(F::zero) += weights[z] * val;
+            }
+        }
+        SparseMLE::new(2 * l, evaluations)
+    }
 }
 
-/// Linear GKR Prover（型は固定して ScalarField を利用）
-pub struct LinearGKRProver;
+/// Phase 1, 2 の表の作り方
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ProverBackend {
+    /// Libra（Xie et al.）の bookkeeping：eq(g, ·) の表で重み付けしながら配線を 1 回ずつ走査して
+    /// Phase 1 の表 A_hg と Phase 2 の表を作る。g は任意の体の元でよく，時間は O(nnz + 2^l)
+    #[default]
+    Libra,
+    /// g をブール点とみなし（非零成分は 1），配線のうち z = g の部分だけを走査する。
+    /// 時間はその部分の非零要素数に比例するので，出力ごとに多数の証明を作る場合に向く
+    BooleanSlice,
+}
 
-impl LinearGKRProver {
-    /// f1: 3*l 変数の疎な multilinear extension
+/// `LinearGKRProver::prove_many` で証明する独立なインスタンス
+#[derive(Clone, Copy)]
+pub struct LinearGKRInstance<'a, F: PrimeField = ScalarField> {
+    pub f1: &'a SparseMLE<F>,
+    pub f2: &'a DenseMLE<F>,
+    pub f3: &'a DenseMLE<F>,
+    pub g: &'a [F],
+}
+
+/// Linear GKR Prover（任意の素体 F 上で動く）
+pub struct LinearGKRProver<F: PrimeField = ScalarField>(PhantomData<F>);
+
+impl<F: PrimeField> LinearGKRProver<F> {
+    /// f1: 3*l 変数の疎な multilinear extension（変数は z, y, x の順）
     /// f2, f3: それぞれ l 変数の密な multilinear extension
-    /// g: 固定ベクトル（長さ l）
-    pub fn prove<R: Rng>(
-        f1: &SparseMLE<ScalarField>,
-        f2: &DenseMLE<ScalarField>,
-        f3: &DenseMLE<ScalarField>,
-        g: &[ScalarField],
+    /// （どの表も `IndexOrder` はどちらでもよく，内部で `IndexOrder::BigEndian` に揃える）
+    /// g: 出力側の点（長さ l。任意の体の元でよい）
+    /// challenges: チャレンジの出どころ。検証側と共有する transcript（同じ状態から始めること），
+    /// または検証側と同じ列を返す乱数生成器
+    pub fn prove<C: ChallengeGenerator<F>>(
+        f1: &SparseMLE<F>,
+        f2: &DenseMLE<F>,
+        f3: &DenseMLE<F>,
+        g: &[F],
+        challenges: &mut C,
+    ) -> LinearGKRProof<F> {
+        let pre = Self::precompute(f1, f2, f3);
+        Self::prove_precomputed(&pre, g, challenges)
+    }
+
+    /// 非対話版：f1 と g を吸収した Fiat–Shamir transcript から全てのチャレンジを導出する
+    pub fn prove_noninteractive(
+        f1: &SparseMLE<F>,
+        f2: &DenseMLE<F>,
+        f3: &DenseMLE<F>,
+        g: &[F],
+    ) -> LinearGKRProof<F> {
+        let mut transcript = FiatShamirTranscript::for_statement(FIAT_SHAMIR_LABEL, &Statement::for_layer(f1, g));
+        Self::prove(f1, f2, f3, g, &mut transcript)
+    }
+
+    /// 独立なインスタンスをそれぞれ非対話版（`prove_noninteractive`）で証明する
+    ///
+    /// `parallel` 有効時は rayon の共有スレッドプールでインスタンスごとに並列に証明する。
+    /// 小さなインスタンスを大量に証明する場合は，1 つの証明の中の並列化よりこちらが効く。
+    pub fn prove_many(instances: &[LinearGKRInstance<F>]) -> Vec<LinearGKRProof<F>> {
+        let prove = |inst: &LinearGKRInstance<F>| Self::prove_noninteractive(inst.f1, inst.f2, inst.f3, inst.g);
+        #[cfg(feature = "parallel")]
+        {
+            instances.par_iter().map(prove).collect()
+        }
+        #[cfg(not(feature = "parallel"))]
+        instances.iter().map(prove).collect()
+    }
+
+    /// チャレンジに依存しない段階：f1 の添字を (z, x, y) に分解して並べ替え，証拠を配置する
+    pub fn precompute(
+        f1: &SparseMLE<F>,
+        f2: &DenseMLE<F>,
+        f3: &DenseMLE<F>,
+    ) -> LinearGKRPrecomputation<F> {
+        let l = f2.num_vars;
+        assert_eq!(f1.num_vars, 3 * l);
+        assert_eq!(f3.num_vars, l);
+        let mask = (1 << l) - 1;
+        // 入力の表の順序によらず，内部では先頭の変数を最上位ビットとして扱う
+        let mut wiring: Vec<_> = f1
+            .big_endian_entries()
+            .filter(|(_, val)| !val.is_zero())
+            .map(|(index, val)| (index >> (2 * l), index & mask, (index >> l) & mask, val))
+            .collect();
+        wiring.sort_unstable_by_key(|e| (e.0, e.1, e.2));
+        let (f2, f3) = (f2.to_order(IndexOrder::BigEndian), f3.to_order(IndexOrder::BigEndian));
+        LinearGKRPrecomputation { l, wiring, f2, f3 }
+    }
+
+    /// チャレンジに依存する段階：前計算を使って 2 フェーズの sum-check を実行する
+    ///
+    /// 各ラウンドのメッセージを `challenges` に渡し，チャレンジをそこから引く。
+    /// 検証側も同じ順で吸収するので，両者のチャレンジは一致する。
+    pub fn prove_precomputed<C: ChallengeGenerator<F>>(
+        pre: &LinearGKRPrecomputation<F>,
+        g: &[F],
+        challenges: &mut C,
+    ) -> LinearGKRProof<F> {
+        Self::prove_precomputed_with(pre, g, ProverBackend::default(), challenges)
+    }
+
+    /// `prove_precomputed` と同じだが，Phase 1, 2 の表の作り方を選ぶ
+    pub fn prove_precomputed_with<C: ChallengeGenerator<F>>(
+        pre: &LinearGKRPrecomputation<F>,
+        g: &[F],
+        backend: ProverBackend,
+        challenges: &mut C,
+    ) -> LinearGKRProof<F> {
+        assert_eq!(g.len(), pre.l);
+        match backend {
+            ProverBackend::Libra => Self::prove_weighted(pre, &eq_table(g), challenges).0,
+            ProverBackend::BooleanSlice => {
+                let (start, end) = pre.slice_of(g);
+                Self::prove_fixed(pre, &pre.wiring[start..end], |_| F::one(), challenges).0
+            }
+        }
+    }
+
+    /// 出力側の変数を重み付きの和 Σ_z weights[z]·f1(z, x, y) で消去して証明する
+    ///
+    /// 証明とともに，Phase 1, 2 のチャレンジ列 (u, v) を返す。
+    pub fn prove_weighted<C: ChallengeGenerator<F>>(
+        pre: &LinearGKRPrecomputation<F>,
+        weights: &[F],
+        challenges: &mut C,
+    ) -> (LinearGKRProof<F>, Vec<F>, Vec<F>) {
+        assert_eq!(weights.len(), 1 << pre.l);
+        Self::prove_fixed(pre, &pre.wiring, |z| weights[z], challenges)
+    }
+
+    /// 証拠の表を持たずに証明する（`prove` と同じ証明を返す）
+    ///
+    /// f2, f3 は添字（先頭の変数が最上位ビット）から値を返す関数で，ラウンドごとに全添字を読み直す。
+    /// メモリに置くのは eq の表と，畳み込む側の表（h_g, f1(g,u,·)）と，その回の証拠の畳み込みの O(2^l) だけで，
+    /// f1 は前計算で並べ替えずに各フェーズで 1 回ずつ走査する。証拠の読み出しは各フェーズ l + 2 回になる。
+    pub fn prove_streaming(
+        f1: &SparseMLE<F>,
+        l: usize,
+        f2: impl Fn(usize) -> F,
+        f3: impl Fn(usize) -> F,
+        g: &[F],
+        transcript: &mut Transcript,
+    ) -> LinearGKRProof<F> {
+        assert_eq!(f1.num_vars, 3 * l);
+        assert_eq!(g.len(), l);
+        let mask = (1 << l) - 1;
+        let eq_g = eq_table(g);
+
+        // ── Phase 1 ──
+        let mut h_g = vec![F::zero(); 1 << l];
+        for (index, val) in f1.big_endian_entries() {
+            let (z, x, y) = (index >> (2 * l), index & mask, (index >> l) & mask);
+            h_g[x] += eq_g[z] * val * f3(y);
+        }
+        let claimed_sum: F = h_g.iter().enumerate().map(|(x, h)| *h * f2(x)).sum();
+        transcript.append_field(labels::CLAIMED_SUM, &claimed_sum);
+        let (phase1_msgs, u, f2_at_u) =
+            stream_sumcheck(DenseMLE::from_evaluations_vec(l, h_g), &f2, 0, transcript);
+
+        // ── Phase 2 ──
+        let eq_u = eq_table(&u);
+        let mut f1_fixed_gu = vec![F::zero(); 1 << l];
+        for (index, val) in f1.big_endian_entries() {
+            let (z, x, y) = (index >> (2 * l), index & mask, (index >> l) & mask);
+            f1_fixed_gu[y] += eq_g[z] * eq_u[x] * val;
+        }
+        let scaled = f1_fixed_gu.iter().map(|e| *e * f2_at_u).collect();
+        let (phase2_msgs, v, f3_at_v) = stream_sumcheck(DenseMLE::from_evaluations_vec(l, scaled), &f3, 1, transcript);
+
+        let f1_at_guv = direct_evaluation(&f1_fixed_gu, &v);
+        transcript.append(labels::FINAL_EVALS, &[f1_at_guv, f2_at_u, f3_at_v]);
+        LinearGKRProof { claimed_sum, phase1_msgs, phase2_msgs, f1_at_guv, f2_at_u, f3_at_v }
+    }
+
+    /// `prove_precomputed` のゼロ知識版：各フェーズを `protocol::prove_zk` で行う
+    ///
+    /// マスクは `pp` でコミットするので，`pp` は ⌈log2 l⌉ + 2 変数まで扱えること。
+    pub fn prove_zk<P: MultilinearPCS<F>, R: Rng>(
+        pp: &P::ProverParam,
+        pre: &LinearGKRPrecomputation<F>,
+        g: &[F],
         rng: &mut R,
-    ) -> LinearGKRProof {
-        let l = g.len();
+        transcript: &mut Transcript,
+    ) -> ZkLinearGKRProof<F, P::Commitment, P::Proof> {
+        let l = pre.l;
+        assert_eq!(g.len(), l);
+        let weights = eq_table(g);
+        let weight = |z: usize| weights[z];
 
         // ── Phase 1 ──
-        // f1 の最初の l 変数を固定し、 h_g(x) = ∑_y f1(g, x, y) * f3(y) を計算
-        let (h_g, f1_fixed_g) = initialize_phase_one(f1, f3, g);
-        // P1(x) = h_g(x) * f2(x) の全和（sum-check の対象値）を計算
-        let claimed_sum_phase1 = compute_claimed_sum(&h_g, f2);
-        let mut prover_state1 = protocol::prover_init(l, claimed_sum_phase1);
+        let h_g = initialize_phase_one(pre, &pre.wiring, &weight);
+        let claimed_sum = protocol::prover_init(vec![h_g.clone(), pre.f2.clone()]).current_sum;
+        transcript.append_field(labels::CLAIMED_SUM, &claimed_sum);
+        transcript.append_message(labels::PHASE, &[1]);
+        let (phase1, u, state1) = protocol::prove_zk::<F, P, R>(pp, vec![h_g, pre.f2.clone()], rng, transcript);
+        let f2_at_u = state1.tables[1].evaluations[0];
+
+        // ── Phase 2 ──
+        let f1_fixed_gu = initialize_phase_two(l, &pre.wiring, &weight, &u);
+        let mut scaled = f1_fixed_gu.clone();
+        scaled.scale(f2_at_u);
+        transcript.append_message(labels::PHASE, &[2]);
+        let (phase2, v, state2) = protocol::prove_zk::<F, P, R>(pp, vec![scaled, pre.f3.clone()], rng, transcript);
+
+        let f1_at_guv = direct_evaluation(&f1_fixed_gu.evaluations, &v);
+        let f3_at_v = state2.tables[1].evaluations[0];
+        transcript.append(labels::FINAL_EVALS, &[f1_at_guv, f2_at_u, f3_at_v]);
+        ZkLinearGKRProof { claimed_sum, phase1, phase2, f1_at_guv, f2_at_u, f3_at_v }
+    }
+
+    /// 配線 `wiring` の各要素 (z, x, y, 値) を weight(z) 倍して出力側の変数を消去した
+    /// f1(x, y) = Σ_z weight(z)·f1(z, x, y) に対する 2 フェーズの sum-check
+    fn prove_fixed<C: ChallengeGenerator<F>>(
+        pre: &LinearGKRPrecomputation<F>,
+        wiring: &[(usize, usize, usize, F)],
+        weight: impl Fn(usize) -> F + Sync,
+        challenges: &mut C,
+    ) -> (LinearGKRProof<F>, Vec<F>, Vec<F>) {
+        let l = pre.l;
+        let (f2, f3) = (&pre.f2, &pre.f3);
+
+        // ── Phase 1 ──
+        // h_g(x) = ∑_y f1(g, x, y) * f3(y) を計算
+        let h_g = initialize_phase_one(pre, wiring, &weight);
+        // P1(x) = h_g(x) * f2(x) に対する sum-check
+        let mut prover_state1 = protocol::prover_init(vec![h_g, f2.clone()]);
+        let claimed_sum = prover_state1.current_sum;
+        challenges.absorb(labels::CLAIMED_SUM, &[claimed_sum]);
         let mut phase1_msgs = Vec::with_capacity(l);
         let mut u = Vec::with_capacity(l);
 
         for _ in 0..l {
-            let msg = protocol::prove_round(&mut prover_state1, rng);
-            phase1_msgs.push(msg.clone());
-            let r_i: ScalarField = get_r().unwrap();
+            let msg = protocol::prove_round(&mut prover_state1);
+            challenges.absorb(labels::PHASE1_ROUND, &msg);
+            phase1_msgs.push(msg);
+            let r_i = challenges.challenge(labels::PHASE1_CHALLENGE);
             u.push(r_i);
             protocol::apply_challenge(&mut prover_state1, r_i);
         }
+        // 全変数を束縛した後の f2 の表は f2(u) の 1 要素
+        let f2_at_u = prover_state1.tables[1].evaluations[0];
 
         // ── Phase 2 ──
-        // f1_fixed_g は f1(g, x, y) となっているので，さらに x = u を固定して f1(g, u, y) を得る
-        let f1_fixed_gu = initialize_phase_two(&f1_fixed_g, &u);
-        let f2_at_u = f2.evaluate(&u);
-        // Phase2 の対象は P2(y) = f1(g,u,y) * f3(y) * f2(u) と考える
-        let claimed_sum_phase2 = f2_at_u * compute_dense_sum(&f1_fixed_gu, f3);
-        let mut prover_state2 = protocol::prover_init(l, claimed_sum_phase2);
+        // P2(y) = f1(g,u,y) * f3(y) * f2(u) に対する sum-check
+        let f1_fixed_gu = initialize_phase_two(l, wiring, &weight, &u);
+        let mut scaled = f1_fixed_gu.clone();
+        scaled.scale(f2_at_u);
+        let mut prover_state2 = protocol::prover_init(vec![scaled, f3.clone()]);
         let mut phase2_msgs = Vec::with_capacity(l);
         let mut v = Vec::with_capacity(l);
 
         for _ in 0..l {
-            let msg = protocol::prove_round(&mut prover_state2, rng);
-            phase2_msgs.push(msg.clone());
-            let r_j: ScalarField = get_r().unwrap();
+            let msg = protocol::prove_round(&mut prover_state2);
+            challenges.absorb(labels::PHASE2_ROUND, &msg);
+            phase2_msgs.push(msg);
+            let r_j = challenges.challenge(labels::PHASE2_CHALLENGE);
             v.push(r_j);
             protocol::apply_challenge(&mut prover_state2, r_j);
         }
 
-        LinearGKRProof { phase1_msgs, phase2_msgs }
+        // 最終点での値（f2(u) で割らずに済むよう f1(g,u,v) は直接評価する）
+        let f1_at_guv = direct_evaluation(&f1_fixed_gu.evaluations, &v);
+        let f3_at_v = prover_state2.tables[1].evaluations[0];
+        challenges.absorb(labels::FINAL_EVALS, &[f1_at_guv, f2_at_u, f3_at_v]);
+
+        (LinearGKRProof { claimed_sum, phase1_msgs, phase2_msgs, f1_at_guv, f2_at_u, f3_at_v }, u, v)
     }
 }
 
-/// f1 の最初の l 変数を固定し、 h_g(x) = ∑_y f1(g,x,y)*f3(y) を計算する
-fn initialize_phase_one(
-    f1: &SparseMLE<ScalarField>,
-    f3: &DenseMLE<ScalarField>,
-    g: &[ScalarField],
-) -> (DenseMLE<ScalarField>, SparseMLE<ScalarField>) {
-    let l = g.len();
-    assert_eq!(f1.num_vars, 3 * l);
-    let f1_fixed_g = f1.fix_variables(g);
-    let size = 1 << l;
-    let mut h_evals = vec![ScalarField::zero(); size];
-    // f1_fixed_g は 2*l 変数（前半 l が x，後半 l が y）として格納されているとする
-    for (&index, &val) in f1_fixed_g.evaluations.iter() {
-        if val.is_zero() {
-            continue;
+/// 配線の各要素を `term` で (出力先, 値) に写し，長さ 2^l の表に足し込む
+///
+/// `parallel` 有効時はスレッドごとの表に足し込んでから合算する。
+fn scatter_add<F: PrimeField>(
+    l: usize,
+    wiring: &[(usize, usize, usize, F)],
+    term: impl Fn(&(usize, usize, usize, F)) -> Option<(usize, F)> + Sync,
+) -> Vec<F> {
+    #[cfg(feature = "parallel")]
+    {
+        wiring
+            .par_iter()
+            .fold(
+                || vec![F::zero(); 1 << l],
+                |mut acc, entry| {
+                    if let Some((i, x)) = term(entry) {
+                        acc[i] += x;
+                    }
+                    acc
+                },
+            )
+            .reduce(
+                || vec![F::zero(); 1 << l],
+                |mut acc, part| {
+                    acc.iter_mut().zip(part).for_each(|(a, p)| *a += p);
+                    acc
+                },
+            )
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        let mut evals = vec![F::zero(); 1 << l];
+        for entry in wiring.iter() {
+            if let Some((i, x)) = term(entry) {
+                evals[i] += x;
+            }
         }
-        let x_index = index & ((1 << l) - 1);
-        let y_index = index >> l;
-        h_evals[x_index] += val * f3.evaluations[y_index];
+        evals
     }
-    let h_g = DenseMLE::from_evaluations_vec(l, h_evals);
-    (h_g, f1_fixed_g)
 }
 
-/// Phase1 で固定した f1 の残りの変数を、u（Phase1 の乱数列）で固定して f1(g,u,y) を得る
-fn initialize_phase_two(
-    f1_fixed_g: &SparseMLE<ScalarField>,
-    u: &[ScalarField],
-) -> DenseMLE<ScalarField> {
-    let f1_fixed_gu = f1_fixed_g.fix_variables(u);
-    f1_fixed_gu.to_dense_multilinear_extension()
+/// Phase 1 の表 A_hg(x) = ∑_{z,y} weight(z)·f1(z,x,y)·f3(y) を配線の 1 回の走査で作る（O(nnz + 2^l)）
+pub(crate) fn initialize_phase_one<F: PrimeField>(
+    pre: &LinearGKRPrecomputation<F>,
+    wiring: &[(usize, usize, usize, F)],
+    weight: &(impl Fn(usize) -> F + Sync),
+) -> DenseMLE<F> {
+    let f3 = &pre.f3;
+    let h_evals = scatter_add(pre.l, wiring, |&(z, x, y, val)| {
+        let w = weight(z);
+        (!w.is_zero()).then(|| (x, w * val * f3.evaluations[y]))
+    });
+    DenseMLE::from_evaluations_vec(pre.l, h_evals)
 }
 
-/// Phase1 の claimed sum の計算：∑_x h_g(x)*f2(x)
-fn compute_claimed_sum(h_g: &DenseMLE<ScalarField>, f2: &DenseMLE<ScalarField>) -> ScalarField {
-    let l = h_g.num_vars;
-    let size = 1 << l;
-    let mut sum = ScalarField::zero();
-    for i in 0..size {
-        sum += h_g.evaluations[i] * f2.evaluations[i];
+/// Phase 1 の乱数列 u で x を固定した f1(g,u,y) = ∑_{z,x} weight(z)·eq(u,x)·f1(z,x,y) を y の表として求める
+pub(crate) fn initialize_phase_two<F: PrimeField>(
+    l: usize,
+    wiring: &[(usize, usize, usize, F)],
+    weight: &(impl Fn(usize) -> F + Sync),
+    u: &[F],
+) -> DenseMLE<F> {
+    let eq_u = eq_table(u);
+    let evals = scatter_add(l, wiring, |&(z, x, y, val)| {
+        let w = weight(z);
+        (!w.is_zero()).then(|| (y, w * val * eq_u[x]))
+    });
+    DenseMLE::from_evaluations_vec(l, evals)
+}
+
+/// 表 `table`（メモリ上で畳み込む）と証拠 `witness` の積に対する sum-check。
+/// 証拠はラウンドごとに読み直して，それまでのチャレンジで畳み込んだ表を作る
+///
+/// `phase`（0 始まり）のラベルで吸収し，メッセージ列（`MessageForm::default()`），チャレンジ列，証拠のチャレンジ点での値を返す。
+fn stream_sumcheck<F: PrimeField>(
+    mut table: DenseMLE<F>,
+    witness: &impl Fn(usize) -> F,
+    phase: usize,
+    transcript: &mut Transcript,
+) -> (Vec<Vec<F>>, Vec<F>, F) {
+    let l = table.num_vars;
+    let (round_label, challenge_label) = (labels::phase_round(phase), labels::phase_challenge(phase));
+    let mut msgs = Vec::with_capacity(l);
+    let mut challenges = Vec::with_capacity(l);
+    for _ in 0..l {
+        let tables = [table, fold_witness(l, witness, &challenges)];
+        let msg = MessageForm::default().encode(protocol::round_evaluations(&tables));
+        transcript.append(&round_label, &msg);
+        msgs.push(msg);
+        let r: F = transcript.squeeze(&challenge_label);
+        challenges.push(r);
+        let [mut t, _] = tables;
+        t.fix_first_variable_in_place(r);
+        table = t;
     }
-    sum
+    let at_point = fold_witness(l, witness, &challenges).evaluations[0];
+    (msgs, challenges, at_point)
 }
 
-/// Phase2 の dense sum：∑_y f1(g,u,y)*f3(y)
-fn compute_dense_sum(f1_fixed_gu: &DenseMLE<ScalarField>, f3: &DenseMLE<ScalarField>) -> ScalarField {
-    let l = f1_fixed_gu.num_vars;
-    let size = 1 << l;
-    let mut sum = ScalarField::zero();
-    for i in 0..size {
-        sum += f1_fixed_gu.evaluations[i] * f3.evaluations[i];
+/// 証拠の先頭 prefix.len() 変数を prefix に固定した表 Σ_p eq(prefix, p)·witness(p, b) を全添字の 1 回の走査で作る
+fn fold_witness<F: PrimeField>(l: usize, witness: &impl Fn(usize) -> F, prefix: &[F]) -> DenseMLE<F> {
+    let rest = l - prefix.len();
+    let mut evals = vec![F::zero(); 1 << rest];
+    for (p, e) in eq_table(prefix).into_iter().enumerate() {
+        for (b, v) in evals.iter_mut().enumerate() {
+            *v += e * witness((p << rest) | b);
+        }
     }
-    sum
+    DenseMLE::from_evaluations_vec(rest, evals)
 }
@@ -0,0 +1,137 @@
+// src/r1cs.rs
+//
+// arkworks の R1CS（`ConstraintSystem`）を層状の回路に変換する。
+// 制約 i ごとに残差 (A z)_i · (B z)_i − (C z)_i を出力する回路を作り，
+// 出力が全て 0 であることを GKR で示す。z = (1, instance, witness) は arkworks と同じ並び。
+//
+// 回路の入力は instance（先頭の 1 を除く），witness，係数の定数の順。
+// witness を隠したいときは `GKRProver::prove_committed` などで入力を commit する。
+
+use ark_ff::Field;
+use ark_relations::r1cs::{ConstraintMatrices, ConstraintSystemRef};
+use std::collections::HashMap;
+
+use crate::builder::{BuiltCircuit, CircuitBuilder, Wire};
+use crate::error::Error;
+
+/// R1CS から作った回路
+pub struct R1csCircuit<F: Field> {
+    pub built: BuiltCircuit<F>,
+    /// instance の変数の数（arkworks と同じく先頭の 1 を含む）
+    pub num_instance_variables: usize,
+    pub num_witness_variables: usize,
+    /// 制約の数（出力層の幅）
+    pub num_constraints: usize,
+}
+
+impl<F: Field> R1csCircuit<F> {
+    /// instance（先頭の 1 を除く）と witness から回路の入力（定数を含む）を作る
+    pub fn assign(&self, instance: &[F], witness: &[F]) -> Vec<F> {
+        assert_eq!(instance.len() + 1, self.num_instance_variables, "wrong number of instance variables");
+        assert_eq!(witness.len(), self.num_witness_variables, "wrong number of witness variables");
+        let inputs: Vec<F> = instance.iter().chain(witness).copied().collect();
+        self.built.assign(&inputs)
+    }
+
+    /// 制約ごとの残差（全て 0 なら充足している）
+    pub fn residuals(&self, instance: &[F], witness: &[F]) -> Vec<F> {
+        self.built.circuit.evaluate(&self.assign(instance, witness)).swap_remove(0)
+    }
+
+    /// 証明が主張する出力が充足を表すか（全ての制約の残差があり，全て 0）
+    pub fn accepts_outputs(&self, outputs: &[F]) -> bool {
+        outputs.len() >= self.num_constraints && outputs.iter().all(F::is_zero)
+    }
+}
+
+/// 制約行列を入力数 2 のゲートだけの回路に変換する
+pub fn compile_r1cs<F: Field>(matrices: &ConstraintMatrices<F>) -> Result<R1csCircuit<F>, Error> {
+    compile_r1cs_with(matrices, CircuitBuilder::new())
+}
+
+/// 空の `builder` に回路を組み立てる（`CircuitBuilder::with_max_fan_in` で線形結合の和の木が浅くなる）
+pub fn compile_r1cs_with<F: Field>(
+    matrices: &ConstraintMatrices<F>,
+    mut builder: CircuitBuilder<F>,
+) -> Result<R1csCircuit<F>, Error> {
+    let ConstraintMatrices { num_instance_variables, num_witness_variables, num_constraints, .. } = *matrices;
+    if num_instance_variables == 0 {
+        return Err(Error::InvalidCircuit("constraint system has no constant variable"));
+    }
+    if num_constraints == 0 {
+        return Err(Error::InvalidCircuit("constraint system has no constraints"));
+    }
+    for rows in [&matrices.a, &matrices.b, &matrices.c] {
+        if rows.len() != num_constraints {
+            return Err(Error::LengthMismatch { what: "constraint rows", expected: num_constraints, found: rows.len() });
+        }
+    }
+    let num_variables = num_instance_variables + num_witness_variables;
+    let mut constants = Constants::default();
+    let one = constants.get(&mut builder, F::one());
+    let variables: Vec<Wire> =
+        std::iter::once(one).chain((1..num_variables).map(|_| builder.input())).collect();
+
+    for i in 0..num_constraints {
+        let a = linear_combination(&mut builder, &mut constants, &variables, &matrices.a[i], F::one())?;
+        let b = linear_combination(&mut builder, &mut constants, &variables, &matrices.b[i], F::one())?;
+        let c = linear_combination(&mut builder, &mut constants, &variables, &matrices.c[i], -F::one())?;
+        let ab = builder.mul(a, b);
+        let residual = builder.add(ab, c);
+        builder.output(residual);
+    }
+    Ok(R1csCircuit { built: builder.build()?, num_instance_variables, num_witness_variables, num_constraints })
+}
+
+/// 制約系を確定して回路に変換し，割り当て済みなら回路の入力も返す
+///
+/// 行列を作らない証明モード（`construct_matrices: false`）の制約系は変換できない。
+pub fn compile_constraint_system<F: Field>(
+    cs: &ConstraintSystemRef<F>,
+) -> Result<(R1csCircuit<F>, Option<Vec<F>>), Error> {
+    cs.finalize();
+    let matrices = cs.to_matrices().ok_or(Error::InvalidCircuit("constraint system does not construct matrices"))?;
+    let circuit = compile_r1cs(&matrices)?;
+    let system = cs.borrow().ok_or(Error::InvalidCircuit("constraint system is empty"))?;
+    let assigned = system.instance_assignment.len() == circuit.num_instance_variables
+        && system.witness_assignment.len() == circuit.num_witness_variables;
+    let inputs = assigned.then(|| circuit.assign(&system.instance_assignment[1..], &system.witness_assignment));
+    Ok((circuit, inputs))
+}
+
+/// 係数として回路の入力に置く定数（同じ値は 1 度だけ作る）
+#[derive(Default)]
+struct Constants<F: Field> {
+    wires: HashMap<F, Wire>,
+}
+
+impl<F: Field> Constants<F> {
+    fn get(&mut self, builder: &mut CircuitBuilder<F>, c: F) -> Wire {
+        *self.wires.entry(c).or_insert_with(|| builder.constant(c))
+    }
+}
+
+/// scale · Σ coeff · z_j（係数が 1 の項は掛け算を省く）
+fn linear_combination<F: Field>(
+    builder: &mut CircuitBuilder<F>,
+    constants: &mut Constants<F>,
+    variables: &[Wire],
+    row: &[(F, usize)],
+    scale: F,
+) -> Result<Wire, Error> {
+    let mut terms = Vec::with_capacity(row.len());
+    for &(coeff, j) in row {
+        let z = *variables.get(j).ok_or(Error::InvalidCircuit("constraint reads an unknown variable"))?;
+        let coeff = coeff * scale;
+        terms.push(if coeff.is_one() {
+            z
+        } else {
+            let c = constants.get(builder, coeff);
+            builder.mul(c, z)
+        });
+    }
+    if terms.is_empty() {
+        return Ok(constants.get(builder, F::zero()));
+    }
+    Ok(builder.sum(&terms))
+}
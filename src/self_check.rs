@@ -0,0 +1,98 @@
+// src/self_check.rs
+//
+// 証明中の自己検査。有効にすると，prover は送る各ラウンドについて
+// g_i(0) + g_i(1) が現在の主張に一致するかを確かめ，畳み込んだ表のいくつかの要素を
+// 元の表の直接評価（eq による重み付き和）と突き合わせ，食い違えば文脈付きで panic する。
+// `self-check` feature で常に有効になるほか，`set_enabled` でスレッドごとに切り替えられる。
+
+use ark_ff::Field;
+use std::cell::Cell;
+use std::fmt::Display;
+
+use crate::eq::eq_table;
+use crate::ml_extension::DenseMLE;
+use crate::sparse_sumcheck::interpolate_at;
+
+thread_local! {
+    static ENABLED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// 自己検査が有効か
+pub fn enabled() -> bool {
+    cfg!(feature = "self-check") || ENABLED.with(Cell::get)
+}
+
+/// 呼び出したスレッドで自己検査を有効・無効にする（`self-check` feature 有効時は常に有効）
+pub fn set_enabled(on: bool) {
+    ENABLED.with(|e| e.set(on));
+}
+
+/// 表 `table` の MLE を点 `point` で直接評価する（Σ_b eq(point, b)·table[b]）
+pub fn direct_evaluation<F: Field>(table: &[F], point: &[F]) -> F {
+    assert_eq!(table.len(), 1 << point.len());
+    eq_table(point).iter().zip(table.iter()).map(|(e, t)| *e * t).sum()
+}
+
+/// 先頭の変数から束縛していく sum-check の prover に付ける検査器
+#[derive(Clone, Debug)]
+pub struct RoundChecker<F: Field> {
+    context: &'static str,
+    round: usize,
+    claim: F,
+    /// これまでのチャレンジ
+    point: Vec<F>,
+}
+
+impl<F: Field> RoundChecker<F> {
+    /// 自己検査が有効な場合だけ作る
+    pub fn new_if_enabled(context: &'static str, claim: F) -> Option<Self> {
+        enabled().then(|| RoundChecker { context, round: 0, claim, point: Vec::new() })
+    }
+
+    pub fn point(&self) -> &[F] {
+        &self.point
+    }
+
+    /// 送ろうとしているラウンド多項式の評価値 [g(0), g(1), ...] を検査する
+    pub fn check_round(&self, evals: &[F]) {
+        assert!(
+            evals.len() >= 2 && evals[0] + evals[1] == self.claim,
+            "self-check failed in {}: round {}: g(0) + g(1) = {} but the running claim is {}",
+            self.context,
+            self.round,
+            evals.iter().take(2).copied().sum::<F>(),
+            self.claim
+        );
+    }
+
+    /// チャレンジ r を受けて主張を g(r) に進める
+    pub fn advance(&mut self, evals: &[F], r: F) {
+        self.check_round(evals);
+        self.claim = interpolate_at(evals, r);
+        self.point.push(r);
+        self.round += 1;
+    }
+
+    /// 畳み込んだ表の先頭・中央・末尾の要素を，元の表の直接評価と突き合わせる
+    ///
+    /// `original` は全変数の表で，`offset` 番目以降のチャレンジがこの表の変数に対応する。
+    pub fn spot_check(&self, name: impl Display, original: &[F], offset: usize, folded: &DenseMLE<F>) {
+        let bound = &self.point[offset..];
+        let len = folded.evaluations.len();
+        for i in [0, len / 2, len - 1] {
+            let mut full = bound.to_vec();
+            full.extend((0..folded.num_vars).map(|b| F::from(((i >> (folded.num_vars - 1 - b)) & 1) as u64)));
+            let expected = direct_evaluation(original, &full);
+            assert!(
+                folded.evaluations[i] == expected,
+                "self-check failed in {}: round {}: folded table {}[{}] = {} but direct evaluation gives {}",
+                self.context,
+                self.round,
+                name,
+                i,
+                folded.evaluations[i],
+                expected
+            );
+        }
+    }
+}
@@ -0,0 +1,573 @@
+// src/serialization.rs
+
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::{BigInteger, Field, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Read, Valid, Validate, Write};
+use std::fmt;
+
+use crate::basefold::{BaseFoldProof, BaseFoldQuery};
+use crate::circuit_prover::{BatchGKRProof, CommittedGKRProof, GKRProof};
+use crate::fan_in::{FanInProof, FanInSubclaim};
+use crate::folding::FoldingProof;
+use crate::preprocessing::PreprocessedGKRProof;
+use crate::prover::{LinearGKRProof, ZkLinearGKRProof};
+use crate::streaming::{ExtendableProof, SegmentProof};
+use crate::sumcheck::protocol::{BatchProof, BatchSubclaim, Subclaim, ZkProof};
+use crate::sumcheck::MessageForm;
+use crate::verifier::LinearGKRSubclaim;
+#[cfg(feature = "zk")]
+use crate::zk_gkr::{ZkGKRProof, ZkLayerProof};
+
+/// 証明バイト列の先頭に置くマジック
+pub const PROOF_MAGIC: [u8; 4] = *b"GKRP";
+/// arkworks の正準形式（`CanonicalSerialize`）で各構造体の先頭に置くバージョン
+pub const CANONICAL_FORMAT_VERSION: u8 = 1;
+/// 現在のワイヤフォーマットのバージョン
+pub const PROOF_FORMAT_VERSION: u8 = 5;
+/// 最終点での評価値を含む最初のバージョン
+pub const FINAL_EVALUATIONS_VERSION: u8 = 3;
+/// 主張する総和を含む最初のバージョン
+pub const CLAIMED_SUM_VERSION: u8 = 4;
+/// ラウンドメッセージから g(0) を省いた（`MessageForm::OmitZero`）最初のバージョン
+pub const OMIT_ZERO_VERSION: u8 = 5;
+/// `legacy-formats` feature で読める最古のバージョン
+pub const OLDEST_SUPPORTED_VERSION: u8 = if cfg!(feature = "legacy-formats") { 1 } else { PROOF_FORMAT_VERSION };
+
+const FLAG_BIG_ENDIAN: u8 = 0b01;
+const FLAG_UNCOMPRESSED: u8 = 0b10;
+
+/// 体の元と長さフィールドのバイト順
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Endianness {
+    #[default]
+    Little,
+    /// EVM などが期待する形式
+    Big,
+}
+
+/// 群の元（PCS のコミットメントなど）の符号化
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum PointEncoding {
+    #[default]
+    Compressed,
+    Uncompressed,
+}
+
+impl From<PointEncoding> for Compress {
+    fn from(e: PointEncoding) -> Self {
+        match e {
+            PointEncoding::Compressed => Compress::Yes,
+            PointEncoding::Uncompressed => Compress::No,
+        }
+    }
+}
+
+/// ワイヤフォーマットの設定。証明ヘッダの flags バイトに記録されるため，
+/// 復号側は設定を知らなくてもよい
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct WireConfig {
+    pub endianness: Endianness,
+    pub point_encoding: PointEncoding,
+}
+
+impl WireConfig {
+    /// Solidity 検証器向けの設定（ビッグエンディアン，非圧縮点）
+    pub fn evm() -> Self {
+        WireConfig { endianness: Endianness::Big, point_encoding: PointEncoding::Uncompressed }
+    }
+
+    fn flags(&self) -> u8 {
+        let mut flags = 0;
+        if self.endianness == Endianness::Big {
+            flags |= FLAG_BIG_ENDIAN;
+        }
+        if self.point_encoding == PointEncoding::Uncompressed {
+            flags |= FLAG_UNCOMPRESSED;
+        }
+        flags
+    }
+
+    fn from_flags(flags: u8) -> Result<Self, SerializationError> {
+        if flags & !(FLAG_BIG_ENDIAN | FLAG_UNCOMPRESSED) != 0 {
+            return Err(SerializationError::UnknownFlags(flags));
+        }
+        Ok(WireConfig {
+            endianness: if flags & FLAG_BIG_ENDIAN != 0 { Endianness::Big } else { Endianness::Little },
+            point_encoding: if flags & FLAG_UNCOMPRESSED != 0 {
+                PointEncoding::Uncompressed
+            } else {
+                PointEncoding::Compressed
+            },
+        })
+    }
+}
+
+/// 復号の失敗理由
+#[derive(Debug)]
+pub enum SerializationError {
+    UnexpectedEnd,
+    BadMagic,
+    UnsupportedVersion(u8),
+    UnknownFlags(u8),
+    /// 体の元が正規形（p 未満）でない
+    NonCanonical,
+    /// 証明の後ろに余分なバイトがある
+    TrailingBytes(usize),
+    /// ヘッダに記録された体の元のバイト数が，復号しようとしている体と異なる
+    FieldWidthMismatch { expected: usize, found: usize },
+    /// 最終点での評価値を持たない旧バージョンの証明（ヘッダは読めるが検証できない）
+    MissingFinalEvaluations(u8),
+    /// 回路のバイト列に未知のゲートの tag がある
+    UnknownGate(u8),
+    /// 回路の記述の構造が不正
+    MalformedCircuit(&'static str),
+    /// 読み込んだ回路の配線が不正
+    Circuit(crate::error::Error),
+    /// JSON として読めない
+    Json(String),
+    Ark(ark_serialize::SerializationError),
+}
+
+impl fmt::Display for SerializationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SerializationError::UnexpectedEnd => write!(f, "unexpected end of input"),
+            SerializationError::BadMagic => write!(f, "bad magic bytes"),
+            SerializationError::UnsupportedVersion(v) => write!(f, "unsupported format version {}", v),
+            SerializationError::UnknownFlags(flags) => write!(f, "unknown header flags {:#04x}", flags),
+            SerializationError::NonCanonical => write!(f, "non-canonical field element"),
+            SerializationError::TrailingBytes(n) => write!(f, "{} trailing bytes", n),
+            SerializationError::FieldWidthMismatch { expected, found } => {
+                write!(f, "field element width {} does not match expected {}", found, expected)
+            }
+            SerializationError::MissingFinalEvaluations(v) => {
+                write!(f, "format version {} has no final evaluations", v)
+            }
+            SerializationError::UnknownGate(tag) => write!(f, "unknown gate tag {}", tag),
+            SerializationError::MalformedCircuit(reason) => write!(f, "malformed circuit: {}", reason),
+            SerializationError::Circuit(e) => write!(f, "{}", e),
+            SerializationError::Json(e) => write!(f, "invalid JSON: {}", e),
+            SerializationError::Ark(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for SerializationError {}
+
+impl From<crate::error::Error> for SerializationError {
+    fn from(e: crate::error::Error) -> Self {
+        SerializationError::Circuit(e)
+    }
+}
+
+impl From<ark_serialize::SerializationError> for SerializationError {
+    fn from(e: ark_serialize::SerializationError) -> Self {
+        SerializationError::Ark(e)
+    }
+}
+
+/// 体の元 1 つを表すのに必要なバイト数
+pub fn field_byte_len<F: PrimeField>() -> usize {
+    F::MODULUS_BIT_SIZE.div_ceil(8) as usize
+}
+
+/// 体の元を固定長（`field_byte_len`）で書き出す
+pub fn write_field<F: PrimeField>(out: &mut Vec<u8>, x: &F, endianness: Endianness) {
+    let mut bytes = x.into_bigint().to_bytes_le();
+    bytes.resize(field_byte_len::<F>(), 0);
+    if endianness == Endianness::Big {
+        bytes.reverse();
+    }
+    out.extend_from_slice(&bytes);
+}
+
+/// 体の元を読む。正規形でなければ拒否する
+pub fn read_field<F: PrimeField>(
+    input: &mut &[u8],
+    endianness: Endianness,
+) -> Result<F, SerializationError> {
+    let bytes = take(input, field_byte_len::<F>())?;
+    let (value, canonical) = decode_bytes::<F>(bytes, endianness);
+    if canonical {
+        Ok(value)
+    } else {
+        Err(SerializationError::NonCanonical)
+    }
+}
+
+/// バイト列を整数として読み，mod p の値と，それが正規形かどうかを返す
+pub(crate) fn decode_bytes<F: PrimeField>(bytes: &[u8], endianness: Endianness) -> (F, bool) {
+    let value = match endianness {
+        Endianness::Little => F::from_le_bytes_mod_order(bytes),
+        Endianness::Big => F::from_be_bytes_mod_order(bytes),
+    };
+    let mut le = bytes.to_vec();
+    if endianness == Endianness::Big {
+        le.reverse();
+    }
+    let mut canonical = value.into_bigint().to_bytes_le();
+    canonical.resize(le.len().max(canonical.len()), 0);
+    le.resize(canonical.len(), 0);
+    (value, le == canonical)
+}
+
+/// 長さフィールド（u32）を書き出す
+pub fn write_len(out: &mut Vec<u8>, len: usize, endianness: Endianness) {
+    let len = u32::try_from(len).expect("length does not fit in u32");
+    match endianness {
+        Endianness::Little => out.extend_from_slice(&len.to_le_bytes()),
+        Endianness::Big => out.extend_from_slice(&len.to_be_bytes()),
+    }
+}
+
+/// 長さフィールド（u32）を読む
+pub fn read_len(input: &mut &[u8], endianness: Endianness) -> Result<usize, SerializationError> {
+    let bytes: [u8; 4] = take(input, 4)?.try_into().unwrap();
+    Ok(match endianness {
+        Endianness::Little => u32::from_le_bytes(bytes),
+        Endianness::Big => u32::from_be_bytes(bytes),
+    } as usize)
+}
+
+/// 群の元を arkworks の正準形式（圧縮/非圧縮は設定に従う）で書き出す
+pub fn write_point<G: CanonicalSerialize>(
+    out: &mut Vec<u8>,
+    point: &G,
+    encoding: PointEncoding,
+) -> Result<(), SerializationError> {
+    point.serialize_with_mode(out, encoding.into())?;
+    Ok(())
+}
+
+/// 群の元を読む（曲線上・部分群に属することを検査する）
+pub fn read_point<G: CanonicalDeserialize>(
+    input: &mut &[u8],
+    encoding: PointEncoding,
+) -> Result<G, SerializationError> {
+    Ok(G::deserialize_with_mode(input, encoding.into(), Validate::Yes)?)
+}
+
+fn take<'a>(input: &mut &'a [u8], n: usize) -> Result<&'a [u8], SerializationError> {
+    if input.len() < n {
+        return Err(SerializationError::UnexpectedEnd);
+    }
+    let (head, tail) = input.split_at(n);
+    *input = tail;
+    Ok(head)
+}
+
+fn write_messages<F: PrimeField>(out: &mut Vec<u8>, msgs: &[Vec<F>], endianness: Endianness) {
+    write_len(out, msgs.len(), endianness);
+    for msg in msgs {
+        write_len(out, msg.len(), endianness);
+        for x in msg {
+            write_field(out, x, endianness);
+        }
+    }
+}
+
+fn read_messages<F: PrimeField>(
+    input: &mut &[u8],
+    endianness: Endianness,
+) -> Result<Vec<Vec<F>>, SerializationError> {
+    let count = read_len(input, endianness)?;
+    let elem_len = field_byte_len::<F>();
+    // 残りバイト数で上限を抑え，巨大な長さフィールドによる過剰確保を防ぐ
+    let mut msgs = Vec::with_capacity(count.min(input.len() / 4));
+    for _ in 0..count {
+        let len = read_len(input, endianness)?;
+        let mut msg = Vec::with_capacity(len.min(input.len() / elem_len));
+        for _ in 0..len {
+            msg.push(read_field(input, endianness)?);
+        }
+        msgs.push(msg);
+    }
+    Ok(msgs)
+}
+
+/// 証明のバイト列レイアウト（バージョン 5）
+///
+/// ```text
+/// magic   : b"GKRP"
+/// version : u8
+/// flags   : u8   (bit0: ビッグエンディアン, bit1: 非圧縮点)
+/// width   : u8   体の元 1 つのバイト数
+/// claim   : 体の元 1 つ（主張する総和）
+/// phase1  : u32 メッセージ数, 各メッセージは u32 長 + 体の元の列（g(1), ..., g(d)）
+/// phase2  : 同上
+/// final   : 体の元 3 つ f1(g,u,v), f2(u), f3(v)
+/// ```
+///
+/// 長さと体の元は flags が示すバイト順で固定長に書き出す。
+/// バージョン 1 は width バイトを持たない（体は BLS12-381 の Fr に固定）。
+/// バージョン 1, 2 は final を持たないため，ヘッダは読めても証明としては復号できない。
+/// バージョン 3 は claim を持たないので，最初のメッセージの g(0) + g(1) から復元する。
+/// バージョン 4 以前のメッセージは g(0) を含むので，読み込み時に取り除く。ただしその証明の
+/// transcript は g(0) を含むメッセージを吸収しているため，変換しても現在の検証器では受理されない。
+impl<F: PrimeField> LinearGKRProof<F> {
+    pub fn to_bytes(&self, config: &WireConfig) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&PROOF_MAGIC);
+        out.push(PROOF_FORMAT_VERSION);
+        out.push(config.flags());
+        out.push(field_byte_len::<F>() as u8);
+        write_field(&mut out, &self.claimed_sum, config.endianness);
+        write_messages(&mut out, &self.phase1_msgs, config.endianness);
+        write_messages(&mut out, &self.phase2_msgs, config.endianness);
+        for x in [&self.f1_at_guv, &self.f2_at_u, &self.f3_at_v] {
+            write_field(&mut out, x, config.endianness);
+        }
+        out
+    }
+
+    /// バージョン 1 の形式でエンコードする（最終点での評価値は書き出されない）（旧バージョンの検証器との互換性テスト用）
+    #[cfg(feature = "legacy-formats")]
+    pub fn to_bytes_v1(&self, config: &WireConfig) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&PROOF_MAGIC);
+        out.push(1);
+        out.push(config.flags());
+        write_messages(&mut out, &self.phase1_msgs, config.endianness);
+        write_messages(&mut out, &self.phase2_msgs, config.endianness);
+        out
+    }
+
+    /// バイト列から証明を復号し，ヘッダに記録された設定とともに返す
+    ///
+    /// 旧バージョンの証明は `legacy-formats` feature が有効な場合だけ読める。
+    pub fn from_bytes(bytes: &[u8]) -> Result<(Self, WireConfig), SerializationError> {
+        let version = format_version(bytes)?;
+        let mut input = bytes;
+        let config = read_header::<F>(&mut input)?;
+        if version < FINAL_EVALUATIONS_VERSION {
+            return Err(SerializationError::MissingFinalEvaluations(version));
+        }
+        let claimed_sum =
+            if version >= CLAIMED_SUM_VERSION { Some(read_field(&mut input, config.endianness)?) } else { None };
+        let mut phase1_msgs = read_messages(&mut input, config.endianness)?;
+        let mut phase2_msgs = read_messages(&mut input, config.endianness)?;
+        let f1_at_guv = read_field(&mut input, config.endianness)?;
+        let f2_at_u = read_field(&mut input, config.endianness)?;
+        let f3_at_v = read_field(&mut input, config.endianness)?;
+        if !input.is_empty() {
+            return Err(SerializationError::TrailingBytes(input.len()));
+        }
+        let claimed_sum = claimed_sum.unwrap_or_else(|| match phase1_msgs.first() {
+            // g_1(0) + g_1(1) が主張する総和（形の崩れたメッセージは検証で拒否される）
+            Some(msg) => msg.iter().take(2).copied().sum(),
+            // 変数が無ければ総和は 1 点での積そのもの
+            None => f1_at_guv * f2_at_u * f3_at_v,
+        });
+        if version < OMIT_ZERO_VERSION {
+            for msg in phase1_msgs.iter_mut().chain(phase2_msgs.iter_mut()) {
+                *msg = MessageForm::OmitZero.encode(std::mem::take(msg));
+            }
+        }
+        Ok((LinearGKRProof { claimed_sum, phase1_msgs, phase2_msgs, f1_at_guv, f2_at_u, f3_at_v }, config))
+    }
+}
+
+/// 証明バイト列のフォーマットバージョン（マジックのみ検査する）
+pub fn format_version(bytes: &[u8]) -> Result<u8, SerializationError> {
+    let mut input = bytes;
+    if take(&mut input, 4)? != PROOF_MAGIC {
+        return Err(SerializationError::BadMagic);
+    }
+    Ok(take(&mut input, 1)?[0])
+}
+
+/// 読める任意のバージョンの体 F の証明を現在のバージョンに変換する（設定はそのまま引き継ぐ）
+///
+/// 体の幅はヘッダと照合する（`FieldWidthMismatch`）。旧バージョンからの変換には `legacy-formats` feature が必要。ただし最終点での評価値を持たない
+/// バージョン 1, 2 は変換できない（`MissingFinalEvaluations`）。
+/// 既に現在のバージョンであれば検査した上で同じバイト列を返す。
+pub fn upgrade<F: PrimeField>(bytes: &[u8]) -> Result<Vec<u8>, SerializationError> {
+    let (proof, config) = LinearGKRProof::<F>::from_bytes(bytes)?;
+    Ok(proof.to_bytes(&config))
+}
+
+/// 体 F の証明としてヘッダを読み，記録された設定を返す
+///
+/// バージョン 1 は width を持たないので，F が BLS12-381 の Fr と同じ幅であることだけを確かめる。
+pub fn read_header<F: PrimeField>(input: &mut &[u8]) -> Result<WireConfig, SerializationError> {
+    if take(input, 4)? != PROOF_MAGIC {
+        return Err(SerializationError::BadMagic);
+    }
+    let version = take(input, 1)?[0];
+    if !(OLDEST_SUPPORTED_VERSION..=PROOF_FORMAT_VERSION).contains(&version) {
+        return Err(SerializationError::UnsupportedVersion(version));
+    }
+    let config = WireConfig::from_flags(take(input, 1)?[0])?;
+    let expected = field_byte_len::<F>();
+    let found = if version >= 2 { take(input, 1)?[0] as usize } else { field_byte_len::<ScalarField>() };
+    if found != expected {
+        return Err(SerializationError::FieldWidthMismatch { expected, found });
+    }
+    Ok(config)
+}
+
+/// 正準形式のバイト列レイアウト（`CanonicalSerialize`，バージョン 1）
+///
+/// 証明とサブクレームの各構造体は先頭に version : u8（`CANONICAL_FORMAT_VERSION`）を置き，
+/// 続けて宣言順にフィールドを arkworks の正準形式で書き出す。
+///
+/// ```text
+/// 体の元   : リトルエンディアン固定長（BLS12-381 の Fr は 32 バイト）
+/// Vec<T>   : u64（リトルエンディアン）の要素数 + 各要素
+/// 群の元   : Compress::Yes なら圧縮点，Compress::No なら非圧縮点
+/// Digest32 : 32 バイトそのまま
+/// usize    : u64（リトルエンディアン）
+/// ```
+///
+/// 入れ子の構造体もそれぞれの version から始まる。例えば `LinearGKRProof` は
+/// version || claimed_sum || phase1_msgs || phase2_msgs || f1_at_guv || f2_at_u || f3_at_v。
+/// 異なる version のバイト列は `InvalidData` で拒否する。
+/// `serde` feature では同じバイト列（圧縮）を 1 つのバイト列として読み書きする。
+macro_rules! versioned_canonical {
+    (impl[$($generics:tt)*] $ty:ty { $($field:ident),* $(,)? }) => {
+        impl<$($generics)*> Valid for $ty {
+            fn check(&self) -> Result<(), ark_serialize::SerializationError> {
+                $(Valid::check(&self.$field)?;)*
+                Ok(())
+            }
+        }
+
+        impl<$($generics)*> CanonicalSerialize for $ty {
+            fn serialize_with_mode<W: Write>(
+                &self,
+                mut writer: W,
+                compress: Compress,
+            ) -> Result<(), ark_serialize::SerializationError> {
+                CANONICAL_FORMAT_VERSION.serialize_with_mode(&mut writer, compress)?;
+                $(self.$field.serialize_with_mode(&mut writer, compress)?;)*
+                Ok(())
+            }
+
+            fn serialized_size(&self, compress: Compress) -> usize {
+                1 $(+ self.$field.serialized_size(compress))*
+            }
+        }
+
+        impl<$($generics)*> CanonicalDeserialize for $ty {
+            fn deserialize_with_mode<R: Read>(
+                mut reader: R,
+                compress: Compress,
+                validate: Validate,
+            ) -> Result<Self, ark_serialize::SerializationError> {
+                if u8::deserialize_with_mode(&mut reader, compress, validate)? != CANONICAL_FORMAT_VERSION {
+                    return Err(ark_serialize::SerializationError::InvalidData);
+                }
+                Ok(Self {
+                    $($field: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,)*
+                })
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<$($generics)*> serde::Serialize for $ty {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serialize_canonical(self, serializer)
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<'de, $($generics)*> serde::Deserialize<'de> for $ty {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserialize_canonical(deserializer)
+            }
+        }
+    };
+}
+
+versioned_canonical!(impl[F: PrimeField] LinearGKRProof<F> {
+    claimed_sum, phase1_msgs, phase2_msgs, f1_at_guv, f2_at_u, f3_at_v
+});
+versioned_canonical!(impl[F: PrimeField] LinearGKRSubclaim<F> { u, v, expected_value, f1_at_guv, f2_at_u, f3_at_v });
+versioned_canonical!(impl[F: Field] Subclaim<F> { point, expected_value });
+versioned_canonical!(impl[F: Field] BatchProof<F> { msgs, final_evals });
+versioned_canonical!(impl[F: Field] BatchSubclaim<F> { point, final_evals });
+versioned_canonical!(impl[F: PrimeField] FanInProof<F> { claimed_sum, phase_msgs, f1_at_point, evals });
+versioned_canonical!(impl[F: PrimeField] FanInSubclaim<F> { points, expected_value, f1_at_point, evals });
+versioned_canonical!(impl[F: PrimeField] GKRProof<F> { outputs, layer_proofs, wide_layer_proofs, line_restrictions });
+versioned_canonical!(impl[F: PrimeField] BatchGKRProof<F> { outputs, layer_proofs, wide_layer_proofs });
+versioned_canonical!(impl[F: PrimeField, O: CanonicalSerialize + CanonicalDeserialize] CommittedGKRProof<F, O> {
+    proof, input_evals, openings
+});
+versioned_canonical!(impl[F: PrimeField, O: CanonicalSerialize + CanonicalDeserialize] PreprocessedGKRProof<F, O> {
+    proof, wiring_evals, wiring_openings
+});
+versioned_canonical!(
+    impl[F: Field, C: CanonicalSerialize + CanonicalDeserialize, O: CanonicalSerialize + CanonicalDeserialize]
+    ZkProof<F, C, O> { mask_commitment, mask_sum, msgs, mask_evals, mask_openings }
+);
+versioned_canonical!(
+    impl[F: PrimeField, C: CanonicalSerialize + CanonicalDeserialize, O: CanonicalSerialize + CanonicalDeserialize]
+    ZkLinearGKRProof<F, C, O> { claimed_sum, phase1, phase2, f1_at_guv, f2_at_u, f3_at_v }
+);
+#[cfg(feature = "zk")]
+versioned_canonical!(
+    impl[F: PrimeField, C: CanonicalSerialize + CanonicalDeserialize, O: CanonicalSerialize + CanonicalDeserialize]
+    ZkLayerProof<F, C, O> { sumcheck, masked_evals, mask_evals, mask_openings }
+);
+#[cfg(feature = "zk")]
+versioned_canonical!(
+    impl[F: PrimeField, C: CanonicalSerialize + CanonicalDeserialize, O: CanonicalSerialize + CanonicalDeserialize]
+    ZkGKRProof<F, C, O> { outputs, mask_commitment, layer_proofs }
+);
+versioned_canonical!(impl[F: PrimeField] BaseFoldQuery<F> { left, right, path });
+versioned_canonical!(impl[F: PrimeField] BaseFoldProof<F> { round_msgs, roots, final_value, queries });
+versioned_canonical!(impl[F: PrimeField] FoldingProof<F> { round_msgs, running_eval, incoming_eval });
+versioned_canonical!(impl[] SegmentProof { num_steps, values, layer_proofs });
+versioned_canonical!(impl[] ExtendableProof { initial_state, segments });
+
+/// 正準形式（圧縮）のバイト列を serde の 1 つのバイト列として書き出す
+#[cfg(feature = "serde")]
+pub fn serialize_canonical<T: CanonicalSerialize, S: serde::Serializer>(
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut bytes = Vec::with_capacity(value.compressed_size());
+    value.serialize_compressed(&mut bytes).map_err(serde::ser::Error::custom)?;
+    serializer.serialize_bytes(&bytes)
+}
+
+/// `serialize_canonical` で書き出した値を読む（余分なバイトは拒否する）
+#[cfg(feature = "serde")]
+pub fn deserialize_canonical<'de, T: CanonicalDeserialize, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    struct BytesVisitor;
+
+    impl<'de> serde::de::Visitor<'de> for BytesVisitor {
+        type Value = Vec<u8>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("canonically serialized bytes")
+        }
+
+        fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Vec<u8>, E> {
+            Ok(v.to_vec())
+        }
+
+        fn visit_byte_buf<E: serde::de::Error>(self, v: Vec<u8>) -> Result<Vec<u8>, E> {
+            Ok(v)
+        }
+
+        // JSON などバイト列を持たない形式では数の配列になる
+        fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<u8>, A::Error> {
+            let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(1 << 16));
+            while let Some(b) = seq.next_element()? {
+                bytes.push(b);
+            }
+            Ok(bytes)
+        }
+    }
+
+    let bytes = deserializer.deserialize_bytes(BytesVisitor)?;
+    let mut input = &bytes[..];
+    let value = T::deserialize_compressed(&mut input).map_err(serde::de::Error::custom)?;
+    if !input.is_empty() {
+        return Err(serde::de::Error::custom(SerializationError::TrailingBytes(input.len())));
+    }
+    Ok(value)
+}
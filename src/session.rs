@@ -0,0 +1,287 @@
+// src/session.rs
+//
+// Linear GKR の対話を IO から切り離した状態機械。
+// `ProverSession::next_message` / `receive_challenge` と
+// `VerifierSession::receive_message` / `next_challenge` を交互に呼べば，
+// メッセージとチャレンジを任意の経路（チャネル，ソケット，テスト用の仲介）で運べる。
+//
+// メッセージの順は `LinearGKRProver::prove` と同じ：
+// 主張する総和，Phase 1 の l ラウンド，Phase 2 の l ラウンド，最終点での値。
+// 各ラウンドのメッセージの後に検証側のチャレンジが 1 つ入る。
+
+use ark_ff::PrimeField;
+use rand::{CryptoRng, Rng};
+
+use crate::eq::eq_table;
+use crate::error::Error;
+use crate::ml_extension::DenseMLE;
+use crate::prover::{initialize_phase_one, initialize_phase_two, LinearGKRPrecomputation, LinearGKRProof};
+use crate::self_check::direct_evaluation;
+use crate::sumcheck::protocol::{self, ProverState, VerifierState};
+use crate::transcript::{labels, Transcript};
+use crate::verifier::LinearGKRSubclaim;
+
+/// プローバから検証側へのメッセージ
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProverMessage<F: PrimeField> {
+    /// ∑_{x,y} f1(g,x,y) f2(x) f3(y) の主張
+    ClaimedSum(F),
+    /// Phase 1, 2 のラウンド多項式の 1, 2 での値（g(0) は省く）
+    Round(Vec<F>),
+    FinalEvaluations { f1_at_guv: F, f2_at_u: F, f3_at_v: F },
+}
+
+impl<F: PrimeField> ProverMessage<F> {
+    /// `LinearGKRProver::prove` と同じラベルで transcript に吸収する（Fiat–Shamir で対話を回すとき用）
+    ///
+    /// `stage` はメッセージを受け取った直後の `ProverSession::stage`（ラウンドメッセージのラベルはフェーズで変わる）。
+    pub fn append_to(&self, stage: Stage, transcript: &mut Transcript) {
+        match self {
+            ProverMessage::ClaimedSum(sum) => transcript.append_field(labels::CLAIMED_SUM, sum),
+            ProverMessage::Round(msg) => {
+                let (round, _) = stage.round_labels().expect("round messages belong to phase one or two");
+                transcript.append(round, msg)
+            }
+            ProverMessage::FinalEvaluations { f1_at_guv, f2_at_u, f3_at_v } => {
+                transcript.append(labels::FINAL_EVALS, &[*f1_at_guv, *f2_at_u, *f3_at_v])
+            }
+        }
+    }
+}
+
+/// 対話のどこまで進んだか
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stage {
+    ClaimedSum,
+    PhaseOne,
+    PhaseTwo,
+    FinalEvaluations,
+    Done,
+}
+
+impl Stage {
+    /// この段階のラウンドメッセージとチャレンジの transcript のラベル（Phase 1, 2 以外は `None`）
+    pub fn round_labels(self) -> Option<(&'static [u8], &'static [u8])> {
+        match self {
+            Stage::PhaseOne => Some((labels::PHASE1_ROUND, labels::PHASE1_CHALLENGE)),
+            Stage::PhaseTwo => Some((labels::PHASE2_ROUND, labels::PHASE2_CHALLENGE)),
+            _ => None,
+        }
+    }
+}
+
+/// Linear GKR のプローバ側の状態機械
+pub struct ProverSession<'a, F: PrimeField> {
+    pre: &'a LinearGKRPrecomputation<F>,
+    weights: Vec<F>,
+    stage: Stage,
+    state: ProverState<F>,
+    /// Phase 2 で f1(g,u,v) を直接評価するための f1(g,u,·)
+    f1_fixed_gu: Option<DenseMLE<F>>,
+    awaiting_challenge: bool,
+    proof: LinearGKRProof<F>,
+    u: Vec<F>,
+    v: Vec<F>,
+}
+
+impl<'a, F: PrimeField> ProverSession<'a, F> {
+    /// 出力側の点 g について対話を始める（表の作り方は `ProverBackend::Libra` と同じ）
+    pub fn new(pre: &'a LinearGKRPrecomputation<F>, g: &[F]) -> Self {
+        assert_eq!(g.len(), pre.l);
+        let weights = eq_table(g);
+        let h_g = initialize_phase_one(pre, &pre.wiring, &|z: usize| weights[z]);
+        let state = protocol::prover_init(vec![h_g, pre.f2.clone()]);
+        let proof = LinearGKRProof {
+            claimed_sum: state.current_sum,
+            phase1_msgs: Vec::with_capacity(pre.l),
+            phase2_msgs: Vec::with_capacity(pre.l),
+            f1_at_guv: F::zero(),
+            f2_at_u: F::zero(),
+            f3_at_v: F::zero(),
+        };
+        ProverSession {
+            pre,
+            weights,
+            stage: Stage::ClaimedSum,
+            state,
+            f1_fixed_gu: None,
+            awaiting_challenge: false,
+            proof,
+            u: Vec::new(),
+            v: Vec::new(),
+        }
+    }
+
+    pub fn stage(&self) -> Stage {
+        self.stage
+    }
+
+    /// 次に送るメッセージ。チャレンジ待ちか，対話が終わっていれば `None`
+    pub fn next_message(&mut self) -> Option<ProverMessage<F>> {
+        if self.awaiting_challenge {
+            return None;
+        }
+        match self.stage {
+            Stage::ClaimedSum => {
+                self.stage = Stage::PhaseOne;
+                self.advance();
+                Some(ProverMessage::ClaimedSum(self.proof.claimed_sum))
+            }
+            Stage::PhaseOne | Stage::PhaseTwo => {
+                let msg = protocol::prove_round(&mut self.state);
+                if self.stage == Stage::PhaseOne {
+                    self.proof.phase1_msgs.push(msg.clone());
+                } else {
+                    self.proof.phase2_msgs.push(msg.clone());
+                }
+                self.awaiting_challenge = true;
+                Some(ProverMessage::Round(msg))
+            }
+            Stage::FinalEvaluations => {
+                self.stage = Stage::Done;
+                let LinearGKRProof { f1_at_guv, f2_at_u, f3_at_v, .. } = self.proof;
+                Some(ProverMessage::FinalEvaluations { f1_at_guv, f2_at_u, f3_at_v })
+            }
+            Stage::Done => None,
+        }
+    }
+
+    /// 直前のラウンドメッセージに対する検証側のチャレンジを受け取る
+    pub fn receive_challenge(&mut self, r: F) -> Result<(), Error> {
+        if !self.awaiting_challenge {
+            return Err(Error::Transcript("prover is not waiting for a challenge"));
+        }
+        protocol::apply_challenge(&mut self.state, r);
+        if self.stage == Stage::PhaseOne { &mut self.u } else { &mut self.v }.push(r);
+        self.awaiting_challenge = false;
+        self.advance();
+        Ok(())
+    }
+
+    /// フェーズの全ラウンドが済んでいれば次の段階へ進む
+    fn advance(&mut self) {
+        let l = self.pre.l;
+        if self.stage == Stage::PhaseOne && self.u.len() == l {
+            self.proof.f2_at_u = self.state.tables[1].evaluations[0];
+            let weights = &self.weights;
+            let f1_fixed_gu = initialize_phase_two(l, &self.pre.wiring, &|z: usize| weights[z], &self.u);
+            let mut scaled = f1_fixed_gu.clone();
+            scaled.scale(self.proof.f2_at_u);
+            self.state = protocol::prover_init(vec![scaled, self.pre.f3.clone()]);
+            self.f1_fixed_gu = Some(f1_fixed_gu);
+            self.stage = Stage::PhaseTwo;
+        }
+        if self.stage == Stage::PhaseTwo && self.v.len() == l {
+            let f1_fixed_gu = self.f1_fixed_gu.as_ref().expect("phase two has started");
+            self.proof.f1_at_guv = direct_evaluation(&f1_fixed_gu.evaluations, &self.v);
+            self.proof.f3_at_v = self.state.tables[1].evaluations[0];
+            self.stage = Stage::FinalEvaluations;
+        }
+    }
+
+    /// 対話を終えていれば，送ったメッセージを `LinearGKRProof` の形で返す
+    pub fn into_proof(self) -> Option<LinearGKRProof<F>> {
+        (self.stage == Stage::Done).then_some(self.proof)
+    }
+}
+
+/// Linear GKR の検証側の状態機械
+pub struct VerifierSession<F: PrimeField> {
+    l: usize,
+    claimed_sum: F,
+    stage: Stage,
+    state: VerifierState<F>,
+    awaiting_challenge: bool,
+    u: Vec<F>,
+    subclaim: Option<LinearGKRSubclaim<F>>,
+}
+
+impl<F: PrimeField> VerifierSession<F> {
+    /// l: f2, f3 の変数数。claimed_sum: プローバが主張すべき総和
+    pub fn new(l: usize, claimed_sum: F) -> Self {
+        VerifierSession {
+            l,
+            claimed_sum,
+            stage: Stage::ClaimedSum,
+            state: protocol::verifier_init(l, 2, claimed_sum),
+            awaiting_challenge: false,
+            u: Vec::new(),
+            subclaim: None,
+        }
+    }
+
+    pub fn stage(&self) -> Stage {
+        self.stage
+    }
+
+    /// プローバのメッセージを検査する。ラウンドメッセージの後は `next_challenge` を呼ぶこと
+    pub fn receive_message(&mut self, msg: &ProverMessage<F>) -> Result<(), Error> {
+        if self.awaiting_challenge {
+            return Err(Error::Transcript("verifier must send a challenge first"));
+        }
+        match (self.stage, msg) {
+            (Stage::ClaimedSum, ProverMessage::ClaimedSum(sum)) => {
+                if *sum != self.claimed_sum {
+                    return Err(Error::EvaluationMismatch("claimed sum"));
+                }
+                self.stage = Stage::PhaseOne;
+                self.advance()?;
+            }
+            (Stage::PhaseOne | Stage::PhaseTwo, ProverMessage::Round(msg)) => {
+                protocol::verify_round(&mut self.state, msg)?;
+                self.awaiting_challenge = true;
+            }
+            (Stage::FinalEvaluations, &ProverMessage::FinalEvaluations { f1_at_guv, f2_at_u, f3_at_v }) => {
+                let expected_value = self.state.current_sum;
+                if f1_at_guv * f2_at_u * f3_at_v != expected_value {
+                    return Err(Error::EvaluationMismatch("product of the final evaluations"));
+                }
+                let v = std::mem::take(&mut self.state.challenges);
+                let u = std::mem::take(&mut self.u);
+                self.subclaim = Some(LinearGKRSubclaim { u, v, expected_value, f1_at_guv, f2_at_u, f3_at_v });
+                self.stage = Stage::Done;
+            }
+            _ => return Err(Error::MalformedProof("unexpected message for this stage")),
+        }
+        Ok(())
+    }
+
+    /// 直前のラウンドメッセージに対するチャレンジを暗号学的な rng から引いて返す。チャレンジ待ちでなければ `None`
+    pub fn next_challenge<R: Rng + CryptoRng>(&mut self, rng: &mut R) -> Option<F> {
+        if !self.awaiting_challenge {
+            return None;
+        }
+        let r = F::rand(rng);
+        self.send_challenge(r).expect("a challenge is awaited");
+        Some(r)
+    }
+
+    /// 外から決めたチャレンジ（Fiat–Shamir の transcript から引いたものなど）を使う
+    pub fn send_challenge(&mut self, r: F) -> Result<(), Error> {
+        if !self.awaiting_challenge {
+            return Err(Error::Transcript("verifier is not waiting to send a challenge"));
+        }
+        protocol::apply_challenge_verifier(&mut self.state, r);
+        self.awaiting_challenge = false;
+        self.advance()
+    }
+
+    fn advance(&mut self) -> Result<(), Error> {
+        if self.stage == Stage::PhaseOne && self.state.rounds_done == self.l {
+            let state = std::mem::replace(&mut self.state, protocol::verifier_init(0, 2, F::zero()));
+            let subclaim = protocol::finalize(state)?;
+            self.u = subclaim.point;
+            self.state = protocol::verifier_init(self.l, 2, subclaim.expected_value);
+            self.stage = Stage::PhaseTwo;
+        }
+        if self.stage == Stage::PhaseTwo && self.state.rounds_done == self.l {
+            self.stage = Stage::FinalEvaluations;
+        }
+        Ok(())
+    }
+
+    /// 対話を終えていればサブクレームを返す
+    pub fn finish(self) -> Result<LinearGKRSubclaim<F>, Error> {
+        self.subclaim.ok_or(Error::MalformedProof("interaction is not finished"))
+    }
+}
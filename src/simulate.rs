@@ -0,0 +1,141 @@
+// src/simulate.rs
+
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::{UniformRand, Zero};
+use rand::Rng;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::error::Error;
+use crate::ml_extension::{DenseMLE, SparseMLE};
+use crate::prover::{LinearGKRProof, LinearGKRProver};
+use crate::serialization::WireConfig;
+use crate::transcript::Transcript;
+use crate::verifier::{LinearGKRSubclaim, LinearGKRVerifier};
+
+/// 単層 GKR の関係（回路側）：3l 変数の配線述語 f1 と出力側の点 g
+#[derive(Clone)]
+pub struct LayerRelation {
+    pub f1: SparseMLE<ScalarField>,
+    pub g: Vec<ScalarField>,
+}
+
+/// 単層 GKR の証拠側：l 変数の f2, f3
+#[derive(Clone)]
+pub struct LayerWitness {
+    pub f2: DenseMLE<ScalarField>,
+    pub f3: DenseMLE<ScalarField>,
+}
+
+/// シミュレーションの設定
+#[derive(Clone, Copy, Debug)]
+pub struct SimulationConfig {
+    /// transcript に吸収するセッション番号（prover と verifier で共通）
+    pub seed: u64,
+    /// `Some` の場合，証明をこの設定でバイト列に往復させてから検証する
+    pub wire: Option<WireConfig>,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        SimulationConfig { seed: 0, wire: Some(WireConfig::default()) }
+    }
+}
+
+/// フェーズごとの所要時間
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PhaseTimings {
+    /// 正直な claimed sum の計算
+    pub claim: Duration,
+    pub prove: Duration,
+    /// シリアライズと復号（`wire` 未指定なら 0）
+    pub serialize: Duration,
+    pub verify: Duration,
+}
+
+/// 正直な実行の結果
+pub struct SimulationReport {
+    pub claimed_sum: ScalarField,
+    pub proof: LinearGKRProof,
+    /// シリアライズ後の証明サイズ（`wire` 未指定なら `None`）
+    pub proof_bytes: Option<usize>,
+    pub result: Result<LinearGKRSubclaim, Error>,
+    pub timings: PhaseTimings,
+}
+
+impl SimulationReport {
+    pub fn accepted(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+/// 正直な prover と verifier を走らせ，claim と所要時間をまとめて返す
+pub fn prove_and_verify(
+    relation: &LayerRelation,
+    witness: &LayerWitness,
+    config: &SimulationConfig,
+) -> SimulationReport {
+    let mut timings = PhaseTimings::default();
+    let mut transcript = Transcript::new(b"gkr-simulate");
+    transcript.append_message(b"session", &config.seed.to_le_bytes());
+    // verifier は prover と同じ状態の transcript から始める
+    let mut verifier_transcript = transcript.clone();
+
+    let start = Instant::now();
+    let claimed_sum = reference_claimed_sum(relation, witness);
+    timings.claim = start.elapsed();
+
+    let start = Instant::now();
+    let mut proof =
+        LinearGKRProver::prove(&relation.f1, &witness.f2, &witness.f3, &relation.g, &mut transcript);
+    timings.prove = start.elapsed();
+
+    let mut proof_bytes = None;
+    if let Some(wire) = &config.wire {
+        let start = Instant::now();
+        let bytes = proof.to_bytes(wire);
+        proof_bytes = Some(bytes.len());
+        proof = LinearGKRProof::from_bytes(&bytes).expect("honest proof must decode").0;
+        timings.serialize = start.elapsed();
+    }
+
+    let start = Instant::now();
+    let result =
+        LinearGKRVerifier::verify(witness.f2.num_vars, claimed_sum, &proof, &mut verifier_transcript);
+    timings.verify = start.elapsed();
+
+    SimulationReport { claimed_sum, proof, proof_bytes, result, timings }
+}
+
+/// Σ_{x,y} f1(g,x,y)·f2(x)·f3(y) を prover と同じ添字の規約で直接計算する
+pub fn reference_claimed_sum(relation: &LayerRelation, witness: &LayerWitness) -> ScalarField {
+    let l = relation.g.len();
+    let f1_fixed_g = relation.f1.fix_variables(&relation.g);
+    let mut sum = ScalarField::zero();
+    for (&index, &val) in f1_fixed_g.evaluations.iter() {
+        let x_index = index & ((1 << l) - 1);
+        let y_index = index >> l;
+        sum += val * witness.f2.evaluations[x_index] * witness.f3.evaluations[y_index];
+    }
+    sum
+}
+
+/// ランダムな単層インスタンスを生成する。f1 は `nnz` 個程度の非零要素を持ち，g はブール点
+pub fn random_instance<R: Rng>(
+    l: usize,
+    nnz: usize,
+    rng: &mut R,
+) -> (LayerRelation, LayerWitness) {
+    let mut evaluations = HashMap::new();
+    for _ in 0..nnz {
+        evaluations.insert(rng.gen_range(0..1 << (3 * l)), ScalarField::rand(rng));
+    }
+    let f1 = SparseMLE::new(3 * l, evaluations);
+    let g = (0..l).map(|_| if rng.gen::<bool>() { 1u32.into() } else { 0u32.into() }).collect();
+    let mut random_mle = || {
+        DenseMLE::from_evaluations_vec(l, (0..1 << l).map(|_| ScalarField::rand(rng)).collect())
+    };
+    let f2 = random_mle();
+    let f3 = random_mle();
+    (LayerRelation { f1, g }, LayerWitness { f2, f3 })
+}
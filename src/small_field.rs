@@ -0,0 +1,185 @@
+// src/small_field.rs
+//
+// 31 / 64 ビットの素体（BabyBear，Goldilocks）と，その上での遅延簡約。
+//
+// 1 リム（64 ビット）の Montgomery 表現 a·R（R = 2^64）同士の積は u128 に収まるので，
+// 積和 Σ a_i·b_i は Montgomery 表現の積をそのまま 192 ビット（u128 と桁あふれの回数）に足し込み，
+// 最後に 1 回だけ p で割って簡約する。和は S·R^2 の表現なので，
+// 表現 t の元（値 t·R^{-1}）に表現 1 の元（値 R^{-1}）を掛ければ S に戻る。
+//
+// sum-check の主張の総和とラウンド多項式の値（`sumcheck::protocol`）は，
+// 表の体が `Goldilocks` か `BabyBear` のときに自動でこちらの経路を使う。
+// 表の畳み込み a + r·(b - a) は出力ごとに簡約が要るので，通常の体演算のままにしている。
+
+use ark_ff::fields::{Fp64, MontBackend, MontConfig};
+use ark_ff::{BigInt, Field, PrimeField};
+use std::any::Any;
+use std::ops::Range;
+
+use crate::ml_extension::DenseMLE;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+#[derive(MontConfig)]
+#[modulus = "18446744069414584321"]
+#[generator = "7"]
+pub struct GoldilocksConfig;
+/// p = 2^64 - 2^32 + 1
+pub type Goldilocks = Fp64<MontBackend<GoldilocksConfig, 1>>;
+
+#[derive(MontConfig)]
+#[modulus = "2013265921"]
+#[generator = "31"]
+pub struct BabyBearConfig;
+/// p = 15·2^27 + 1
+pub type BabyBear = Fp64<MontBackend<BabyBearConfig, 1>>;
+
+/// 積和の途中の値（u128 の和と，その桁あふれの回数）
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WideAccumulator {
+    lo: u128,
+    hi: u64,
+}
+
+impl WideAccumulator {
+    fn add(&mut self, x: u128) {
+        let (lo, carry) = self.lo.overflowing_add(x);
+        self.lo = lo;
+        self.hi += carry as u64;
+    }
+
+    fn merge(&mut self, other: WideAccumulator) {
+        self.add(other.lo);
+        self.hi += other.hi;
+    }
+}
+
+/// 積を簡約せずに足し込める体
+pub trait DelayedReduction: PrimeField {
+    /// a·b を簡約せずに足し込む
+    fn mul_acc(acc: &mut WideAccumulator, a: &Self, b: &Self);
+
+    /// 足し込んだ積和を体の元に戻す
+    fn reduce(acc: WideAccumulator) -> Self;
+
+    /// Σ a_i·b_i（簡約は最後の 1 回だけ）
+    fn inner_product(a: &[Self], b: &[Self]) -> Self {
+        assert_eq!(a.len(), b.len());
+        let mut acc = WideAccumulator::default();
+        for (x, y) in a.iter().zip(b) {
+            Self::mul_acc(&mut acc, x, y);
+        }
+        Self::reduce(acc)
+    }
+}
+
+impl<C: MontConfig<1>> DelayedReduction for Fp64<MontBackend<C, 1>> {
+    fn mul_acc(acc: &mut WideAccumulator, a: &Self, b: &Self) {
+        acc.add(a.0 .0[0] as u128 * b.0 .0[0] as u128);
+    }
+
+    fn reduce(acc: WideAccumulator) -> Self {
+        let p = C::MODULUS.0[0] as u128;
+        // 2^128 mod p
+        let two_128 = (u128::MAX % p + 1) % p;
+        let t = (acc.lo % p + (acc.hi as u128 % p) * two_128 % p) % p;
+        Self::new_unchecked(BigInt([t as u64])) * Self::new_unchecked(BigInt([1]))
+    }
+}
+
+/// Σ_i Π_k P_k(i)。表の体が `Goldilocks` か `BabyBear` で因子が 2 つ以上のときだけ `Some`
+pub(crate) fn product_sum<F: Field>(tables: &[DenseMLE<F>]) -> Option<F> {
+    product_sum_in::<F, Goldilocks>(tables).or_else(|| product_sum_in::<F, BabyBear>(tables))
+}
+
+/// ラウンド多項式の 0, 1, ..., degree での値（`sumcheck::protocol::round_evaluations` と同じ）。条件は `product_sum` と同じ
+pub(crate) fn round_evaluations<F: Field>(tables: &[DenseMLE<F>], degree: usize) -> Option<Vec<F>> {
+    round_evaluations_in::<F, Goldilocks>(tables, degree)
+        .or_else(|| round_evaluations_in::<F, BabyBear>(tables, degree))
+}
+
+/// 添字のチャンクごとの積和の長さ
+const CHUNK: usize = 1 << 12;
+
+fn downcast_tables<F: Field, G: DelayedReduction>(tables: &[DenseMLE<F>]) -> Option<Vec<&DenseMLE<G>>> {
+    if tables.len() < 2 {
+        return None;
+    }
+    tables.iter().map(|t| (t as &dyn Any).downcast_ref::<DenseMLE<G>>()).collect()
+}
+
+fn upcast<F: Field, G: DelayedReduction>(x: G) -> F {
+    *(&x as &dyn Any).downcast_ref::<F>().expect("F and G are the same type")
+}
+
+/// 範囲ごとの積和を（`parallel` 有効時は並列に）足し合わせる
+fn sum_chunks<T: Send>(
+    len: usize,
+    init: impl Fn() -> T + Sync + Send,
+    partial: impl Fn(Range<usize>) -> T + Sync,
+    merge: impl Fn(T, T) -> T + Sync + Send,
+) -> T {
+    let range = |c: usize| c * CHUNK..((c + 1) * CHUNK).min(len);
+    #[cfg(feature = "parallel")]
+    {
+        (0..len.div_ceil(CHUNK)).into_par_iter().map(|c| partial(range(c))).reduce(init, merge)
+    }
+    #[cfg(not(feature = "parallel"))]
+    (0..len.div_ceil(CHUNK)).map(|c| partial(range(c))).fold(init(), merge)
+}
+
+fn product_sum_in<F: Field, G: DelayedReduction>(tables: &[DenseMLE<F>]) -> Option<F> {
+    let tables = downcast_tables::<F, G>(tables)?;
+    let (last, rest) = tables.split_last().expect("at least two factors");
+    let acc = sum_chunks(
+        last.evaluations.len(),
+        WideAccumulator::default,
+        |range| {
+            let mut acc = WideAccumulator::default();
+            for i in range {
+                let head: G = rest.iter().map(|t| t.evaluations[i]).product();
+                G::mul_acc(&mut acc, &head, &last.evaluations[i]);
+            }
+            acc
+        },
+        |mut a, b| {
+            a.merge(b);
+            a
+        },
+    );
+    Some(upcast(G::reduce(acc)))
+}
+
+fn round_evaluations_in<F: Field, G: DelayedReduction>(tables: &[DenseMLE<F>], degree: usize) -> Option<Vec<F>> {
+    let tables = downcast_tables::<F, G>(tables)?;
+    let d = tables.len();
+    let half = 1 << (tables[0].num_vars - 1);
+    let accs = sum_chunks(
+        half,
+        || vec![WideAccumulator::default(); degree + 1],
+        |range| {
+            let mut accs = vec![WideAccumulator::default(); degree + 1];
+            let mut current = vec![G::zero(); d];
+            let mut step = vec![G::zero(); d];
+            for b in range {
+                for (k, t) in tables.iter().enumerate() {
+                    current[k] = t.evaluations[b];
+                    step[k] = t.evaluations[b + half] - t.evaluations[b];
+                }
+                for acc in accs.iter_mut() {
+                    let head: G = current[..d - 1].iter().product();
+                    G::mul_acc(acc, &head, &current[d - 1]);
+                    for (c, s) in current.iter_mut().zip(step.iter()) {
+                        *c += s;
+                    }
+                }
+            }
+            accs
+        },
+        |mut a, b| {
+            a.iter_mut().zip(b).for_each(|(x, y)| x.merge(y));
+            a
+        },
+    );
+    Some(accs.into_iter().map(|acc| upcast(G::reduce(acc))).collect())
+}
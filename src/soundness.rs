@@ -0,0 +1,121 @@
+// src/soundness.rs
+//
+// Schwartz–Zippel による GKR 全体の健全性誤差の見積もり（union bound）。
+//
+//   出力層のランダム点 g           : num_vars / |F|
+//   各層の sum-check の各ラウンド  : d_i / |F|（d_i はラウンド多項式の次数）
+//   各層で 2 つの主張を 1 つに束ねる : 1 / |F|
+//
+// 誤差 ε を -log2 ε（ビット）で返す。ε ≥ 1 なら 0 ビット。
+// Goldilocks のような 64 ビットの体では 1 回の誤差が大きすぎるので，`SecurityConfig` で
+// 回路の証明を k 回繰り返し（`GKRProver::prove_repeated`），誤差を ε^k に下げる。
+
+use ark_ff::{BigInteger, PrimeField};
+
+use crate::circuit::Circuit;
+use crate::error::Error;
+use crate::wiring;
+
+/// 見積もり対象のプロトコルの形
+#[derive(Clone, Debug, PartialEq)]
+pub struct SoundnessParams {
+    /// log2 |F|
+    pub field_bits: f64,
+    /// 各層の変数数 l
+    pub num_vars: usize,
+    /// 1 層の sum-check の各ラウンドの次数
+    pub round_degrees: Vec<usize>,
+    pub num_layers: usize,
+}
+
+impl SoundnessParams {
+    /// Linear GKR（Phase 1, 2 の計 2l ラウンド，どれも 2 次）を体 F 上で回す場合
+    pub fn linear_gkr<F: PrimeField>(num_vars: usize, num_layers: usize) -> Self {
+        SoundnessParams {
+            field_bits: field_bits::<F>(),
+            num_vars,
+            round_degrees: vec![2; 2 * num_vars],
+            num_layers,
+        }
+    }
+
+    /// 回路全体を `GKRProver::prove_circuit` で証明する場合
+    ///
+    /// 各層は最大の因子数 k の sum-check（k フェーズ × (l + 1) ラウンド，どれも 2 次）として数える。
+    pub fn circuit<F: PrimeField>(circuit: &Circuit) -> Self {
+        let l = wiring::circuit_num_vars(circuit);
+        let k = (0..circuit.depth()).map(|i| circuit.fan_in(i)).max().unwrap_or(2);
+        SoundnessParams {
+            field_bits: field_bits::<F>(),
+            num_vars: l,
+            round_degrees: vec![2; k * (l + 1)],
+            num_layers: circuit.depth(),
+        }
+    }
+
+    /// 誤差の分子（union bound の項の和）。誤差は これ / |F|
+    pub fn error_numerator(&self) -> f64 {
+        error_numerator(self.num_vars, &self.round_degrees, self.num_layers)
+    }
+
+    /// 健全性誤差を -log2 ε で返す
+    pub fn bits(&self) -> f64 {
+        soundness_bits(self.field_bits, self.num_vars, &self.round_degrees, self.num_layers)
+    }
+}
+
+/// log2 |F|（法の上位 64 ビットから計算する）
+pub fn field_bits<F: PrimeField>() -> f64 {
+    let modulus = F::MODULUS.to_bits_be();
+    let first = modulus.iter().position(|&b| b).unwrap_or(modulus.len());
+    let significant = &modulus[first..];
+    let top = significant.iter().take(64).fold(0u64, |acc, &b| (acc << 1) | b as u64);
+    let rest = significant.len().saturating_sub(64);
+    rest as f64 + (top as f64).log2()
+}
+
+/// 体の大きさ 2^field_bits，層ごとの変数数，ラウンドの次数，層数から健全性誤差を -log2 ε で返す
+pub fn soundness_bits(field_bits: f64, num_vars: usize, round_degrees: &[usize], num_layers: usize) -> f64 {
+    (field_bits - error_numerator(num_vars, round_degrees, num_layers).log2()).max(0.0)
+}
+
+/// 1 回あたり `bits` ビットのプロトコルを並列に繰り返して `target_bits` に届く回数。
+/// `bits` が 0（1 回では何も保証しない）なら `None`
+pub fn repetitions_for(bits: f64, target_bits: f64) -> Option<usize> {
+    if bits <= 0.0 {
+        return None;
+    }
+    Some(((target_bits / bits).ceil() as usize).max(1))
+}
+
+/// 回路の証明を何回繰り返すか
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum SecurityConfig {
+    /// 1 回だけ（BLS12-381 の Fr のような大きな体向け）
+    #[default]
+    Single,
+    /// 回数を直接指定する（0 は 1 とみなす）
+    Repetitions(usize),
+    /// 健全性の見積もり（`SoundnessParams::circuit`）が指定のビット数に届く最小の回数
+    TargetBits(usize),
+}
+
+impl SecurityConfig {
+    /// 体 F 上で回路を証明するときの繰り返しの回数
+    pub fn repetitions<F: PrimeField>(&self, circuit: &Circuit) -> Result<usize, Error> {
+        match *self {
+            SecurityConfig::Single => Ok(1),
+            SecurityConfig::Repetitions(k) => Ok(k.max(1)),
+            SecurityConfig::TargetBits(target) => {
+                let bits = SoundnessParams::circuit::<F>(circuit).bits();
+                repetitions_for(bits, target as f64)
+                    .ok_or(Error::InsufficientSoundness { bits: bits as usize, target })
+            }
+        }
+    }
+}
+
+fn error_numerator(num_vars: usize, round_degrees: &[usize], num_layers: usize) -> f64 {
+    let per_layer: usize = round_degrees.iter().sum::<usize>() + 1;
+    num_vars as f64 + (num_layers * per_layer) as f64
+}
@@ -0,0 +1,180 @@
+// src/sparse_sumcheck.rs
+
+use ark_ff::Field;
+use std::collections::HashMap;
+
+use crate::error::RoundError;
+use crate::ml_extension::{fix_first_variable_batch, DenseMLE, IndexOrder, SparseMLE};
+use crate::self_check::RoundChecker;
+use crate::sumcheck::{barycentric_evaluate, MessageForm};
+
+/// Σ_{b ∈ {0,1}^n} S(b)·D(b の下位 m ビット) に対する sum-check prover
+///
+/// S は n 変数の疎な MLE（配線述語），D は末尾 m 変数だけに依存する密な MLE。
+/// 先頭 n - m 変数は S にしか現れないため，それらのラウンドは S の非零要素を
+/// 走査するだけで済み，コストは 2^n ではなく非零要素数に比例する。
+/// 残りが m 変数になった時点で S を密表に展開し，通常の積の sum-check に切り替える。
+///
+/// 変数は先頭（添字の最上位ビット）から順に束縛する。各ラウンドのメッセージは
+/// ラウンド多項式の 0, 1, ..., d での評価値の列（疎ラウンドは d = 1，密ラウンドは d = 2）で，
+/// 既定では `MessageForm::OmitZero` に従い g(0) を省いて送る。
+pub struct SparseDenseProver<F: Field> {
+    num_vars: usize,
+    dense_vars: usize,
+    sparse: HashMap<usize, F>,
+    /// 密ラウンドに入った後の [S, D]
+    tables: Vec<DenseMLE<F>>,
+    d: DenseMLE<F>,
+    form: MessageForm,
+    /// 自己検査が有効なときの検査器
+    checker: Option<RoundChecker<F>>,
+}
+
+impl<F: Field> SparseDenseProver<F> {
+    pub fn new(s: &SparseMLE<F>, d: &DenseMLE<F>) -> Self {
+        assert!(d.num_vars <= s.num_vars, "dense factor has more variables than the sparse one");
+        let mut prover = SparseDenseProver {
+            num_vars: s.num_vars,
+            dense_vars: d.num_vars,
+            sparse: s.to_order(IndexOrder::BigEndian).evaluations,
+            tables: Vec::new(),
+            d: d.to_order(IndexOrder::BigEndian),
+            form: MessageForm::default(),
+            checker: None,
+        };
+        prover.switch_to_dense_if_ready();
+        prover.checker = RoundChecker::new_if_enabled("SparseDenseProver", prover.claimed_sum());
+        prover
+    }
+
+    /// ラウンドメッセージの形式を変える
+    pub fn with_message_form(mut self, form: MessageForm) -> Self {
+        self.form = form;
+        self
+    }
+
+    /// 残りの変数数
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    /// 主張される総和 Σ S(b)·D(b)
+    pub fn claimed_sum(&self) -> F {
+        if self.tables.is_empty() {
+            let mask = (1 << self.dense_vars) - 1;
+            self.sparse.iter().map(|(&idx, &val)| val * self.d.evaluations[idx & mask]).sum()
+        } else {
+            let (s, d) = (&self.tables[0], &self.tables[1]);
+            s.evaluations.iter().zip(d.evaluations.iter()).map(|(a, b)| *a * b).sum()
+        }
+    }
+
+    /// 現ラウンドのメッセージ（設定された形式でエンコードした評価値列）を返す
+    pub fn prove_round(&self) -> Vec<F> {
+        let evals = self.round_evaluations();
+        if let Some(checker) = &self.checker {
+            checker.check_round(&evals);
+        }
+        self.form.encode(evals)
+    }
+
+    /// 現ラウンドの多項式の 0, 1, ..., d での評価値
+    pub fn round_evaluations(&self) -> Vec<F> {
+        assert!(self.num_vars > 0, "all variables are already bound");
+        if self.tables.is_empty() {
+            // 疎ラウンド: g(X) = Σ val·D(rest)·(b ? X : 1 - X) は 1 次
+            let rest_bits = self.num_vars - 1;
+            let mask = (1 << self.dense_vars) - 1;
+            let mut evals = vec![F::zero(); 2];
+            for (&idx, &val) in self.sparse.iter() {
+                let b = (idx >> rest_bits) & 1;
+                evals[b] += val * self.d.evaluations[idx & mask];
+            }
+            evals
+        } else {
+            // 密ラウンド: g(t) = Σ_i (s_lo + t·Δs)(d_lo + t·Δd) を t = 0, 1, 2 で評価
+            let (s, d) = (&self.tables[0].evaluations, &self.tables[1].evaluations);
+            let half = s.len() / 2;
+            let mut evals = vec![F::zero(); 3];
+            for i in 0..half {
+                let (s0, s1) = (s[i], s[i + half]);
+                let (d0, d1) = (d[i], d[i + half]);
+                evals[0] += s0 * d0;
+                evals[1] += s1 * d1;
+                evals[2] += (s1.double() - s0) * (d1.double() - d0);
+            }
+            evals
+        }
+    }
+
+    /// 先頭変数を r に束縛する
+    pub fn apply_challenge(&mut self, r: F) {
+        assert!(self.num_vars > 0, "all variables are already bound");
+        if let Some(mut checker) = self.checker.take() {
+            checker.advance(&self.round_evaluations(), r);
+            self.checker = Some(checker);
+        }
+        if self.tables.is_empty() {
+            let rest_bits = self.num_vars - 1;
+            let rest_mask = (1 << rest_bits) - 1;
+            let one_minus_r = F::one() - r;
+            let mut folded = HashMap::with_capacity(self.sparse.len());
+            for (&idx, &val) in self.sparse.iter() {
+                let weight = if (idx >> rest_bits) & 1 == 1 { r } else { one_minus_r };
+                *folded.entry(idx & rest_mask).or_insert_with(F::zero) += val * weight;
+            }
+            self.sparse = folded;
+            self.num_vars -= 1;
+            self.switch_to_dense_if_ready();
+        } else {
+            fix_first_variable_batch(&mut self.tables, r);
+            self.num_vars -= 1;
+            if let Some(checker) = &self.checker {
+                let offset = checker.point().len() - (self.dense_vars - self.num_vars);
+                checker.spot_check("D", &self.d.evaluations, offset, &self.tables[1]);
+            }
+        }
+    }
+
+    /// 全変数を束縛した後の S(r)·D(r)
+    pub fn final_evaluation(&self) -> F {
+        assert_eq!(self.num_vars, 0, "sum-check is not finished");
+        self.tables[0].evaluations[0] * self.tables[1].evaluations[0]
+    }
+
+    fn switch_to_dense_if_ready(&mut self) {
+        if self.tables.is_empty() && self.num_vars == self.dense_vars {
+            let s = SparseMLE::new(self.num_vars, std::mem::take(&mut self.sparse));
+            self.tables = vec![s.to_dense_multilinear_extension(), self.d.clone()];
+        }
+    }
+}
+
+/// 評価値列 [g(0), g(1), ..., g(d)] で表された 1 変数多項式を点 r で評価する（重心形のラグランジュ補間）
+pub fn interpolate_at<F: Field>(evals: &[F], r: F) -> F {
+    barycentric_evaluate(evals, r)
+}
+
+/// 1 ラウンド分の検証：g(0) + g(1) が現在の主張と一致するかを確かめ，
+/// チャレンジ r での値を次の主張として返す
+///
+/// `MessageForm::OmitZero` では g(0) を主張から復元するので，この等式は自動的に成り立つ。
+pub fn verify_round<F: Field>(
+    claim: F,
+    msg: &[F],
+    max_degree: usize,
+    r: F,
+    form: MessageForm,
+) -> Result<F, RoundError> {
+    let evals = form.decode(msg, claim);
+    if evals.len() < 2 {
+        return Err(RoundError::InvalidLength(evals.len()));
+    }
+    if evals.len() > max_degree + 1 {
+        return Err(RoundError::DegreeBoundExceeded { degree: evals.len() - 1, bound: max_degree });
+    }
+    if evals[0] + evals[1] != claim {
+        return Err(RoundError::SumMismatch);
+    }
+    Ok(interpolate_at(&evals, r))
+}
@@ -0,0 +1,162 @@
+// src/statement.rs
+//
+// 証明の対象（どの回路について，どの公開入出力を主張するか）を transcript に束縛する。
+// チャレンジを導出する前にこれを吸収しておけば，同じ証明を別の回路や
+// 別の出力の主張に使い回すことはできない（strong Fiat–Shamir）。
+// 回路のダイジェストには体の識別子も含めるので，同じ形の回路を別の体で証明した証明とも区別される。
+
+use ark_ff::{BigInteger, PrimeField};
+use sha3::{Digest, Sha3_256};
+
+use crate::circuit::{Circuit, Gate};
+use crate::ml_extension::SparseMLE;
+use crate::serialization::{write_field, Endianness};
+use crate::transcript::{Transcript, TranscriptConfig};
+
+/// 回路・配線のダイジェスト
+pub type Digest32 = [u8; 32];
+
+/// 回路の正準なダイジェスト
+///
+/// 入力数，層数，各層のゲート数と各ゲート（種類, 入力 a, 入力 b。定数ゲートは (3, 定数, 0)，
+/// `Sum`, `Product` は (4 または 5, 入力数, 入力...)）を u64 little-endian で並べてハッシュする。
+pub fn circuit_digest(circuit: &Circuit) -> Digest32 {
+    let mut hasher = Sha3_256::new();
+    hasher.update(b"gkr-circuit-v1");
+    hasher.update((circuit.num_inputs as u64).to_le_bytes());
+    hasher.update((circuit.depth() as u64).to_le_bytes());
+    for layer in circuit.layers.iter() {
+        hasher.update((layer.gates.len() as u64).to_le_bytes());
+        for gate in layer.gates.iter() {
+            let words: Vec<u64> = match gate {
+                Gate::Add(a, b) => vec![0, *a as u64, *b as u64],
+                Gate::Mul(a, b) => vec![1, *a as u64, *b as u64],
+                Gate::Input(a) => vec![2, *a as u64, *a as u64],
+                Gate::Constant(c) => vec![3, *c, 0],
+                Gate::Sum(xs) | Gate::Product(xs) => {
+                    let tag = if matches!(gate, Gate::Sum(_)) { 4 } else { 5 };
+                    [tag, xs.len() as u64].into_iter().chain(xs.iter().map(|x| *x as u64)).collect()
+                }
+            };
+            for word in words {
+                hasher.update(word.to_le_bytes());
+            }
+        }
+    }
+    hasher.finalize().into()
+}
+
+/// 単層の配線述語（疎な MLE）の正準なダイジェスト（非零要素を添字順に並べてハッシュする）
+pub fn wiring_digest<F: PrimeField>(f1: &SparseMLE<F>) -> Digest32 {
+    let mut entries: Vec<_> = f1.big_endian_entries().filter(|(_, v)| !v.is_zero()).collect();
+    entries.sort_unstable_by_key(|(i, _)| *i);
+    let mut hasher = Sha3_256::new();
+    hasher.update(b"gkr-wiring-v1");
+    hasher.update((f1.num_vars as u64).to_le_bytes());
+    hasher.update((entries.len() as u64).to_le_bytes());
+    let mut bytes = Vec::new();
+    for (i, v) in entries {
+        bytes.clear();
+        bytes.extend_from_slice(&(i as u64).to_le_bytes());
+        write_field(&mut bytes, &v, Endianness::Little);
+        hasher.update(&bytes);
+    }
+    hasher.finalize().into()
+}
+
+/// 体の識別子（法の little-endian 表現）
+pub fn field_id<F: PrimeField>() -> Vec<u8> {
+    F::MODULUS.to_bytes_le()
+}
+
+/// 回路（層，ゲートの配線）と体 F をまとめて束縛するダイジェスト
+///
+/// `circuit_digest` または `wiring_digest` に `field_id` を加えてハッシュし直したもの。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CircuitDigest(pub Digest32);
+
+impl CircuitDigest {
+    /// 体 F 上の回路のダイジェスト
+    pub fn new<F: PrimeField>(circuit: &Circuit) -> Self {
+        Self::bind_field::<F>(&circuit_digest(circuit))
+    }
+
+    /// 単層の配線述語 f1 のダイジェスト
+    pub fn for_wiring<F: PrimeField>(f1: &SparseMLE<F>) -> Self {
+        Self::bind_field::<F>(&wiring_digest(f1))
+    }
+
+    fn bind_field<F: PrimeField>(digest: &Digest32) -> Self {
+        let field = field_id::<F>();
+        let mut hasher = Sha3_256::new();
+        hasher.update(b"gkr-circuit-digest-v1");
+        hasher.update((field.len() as u64).to_le_bytes());
+        hasher.update(&field);
+        hasher.update(digest);
+        CircuitDigest(hasher.finalize().into())
+    }
+
+    pub fn as_bytes(&self) -> &Digest32 {
+        &self.0
+    }
+}
+
+/// 証明の対象となる主張
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Statement<F: PrimeField> {
+    pub circuit_digest: CircuitDigest,
+    /// 多項式コミットメント（シリアライズ済み）
+    pub commitments: Vec<Vec<u8>>,
+    pub public_inputs: Vec<F>,
+    pub public_outputs: Vec<F>,
+}
+
+impl<F: PrimeField> Statement<F> {
+    pub fn new(circuit: &Circuit, public_inputs: Vec<F>, public_outputs: Vec<F>) -> Self {
+        Statement { circuit_digest: CircuitDigest::new::<F>(circuit), commitments: Vec::new(), public_inputs, public_outputs }
+    }
+
+    /// 単層 GKR の主張：配線述語 f1 と出力側の点 g
+    pub fn for_layer(f1: &SparseMLE<F>, g: &[F]) -> Self {
+        Statement {
+            circuit_digest: CircuitDigest::for_wiring(f1),
+            commitments: Vec::new(),
+            public_inputs: g.to_vec(),
+            public_outputs: Vec::new(),
+        }
+    }
+
+    pub fn with_commitment(mut self, commitment: Vec<u8>) -> Self {
+        self.commitments.push(commitment);
+        self
+    }
+
+    /// 主張全体を transcript に吸収する
+    pub fn absorb_into(&self, transcript: &mut Transcript) {
+        transcript.append_message(b"circuit", self.circuit_digest.as_bytes());
+        transcript.append_message(b"num-commitments", &(self.commitments.len() as u64).to_le_bytes());
+        for commitment in self.commitments.iter() {
+            transcript.append_message(b"commitment", commitment);
+        }
+        transcript.append_fields(b"public-inputs", &self.public_inputs);
+        transcript.append_fields(b"public-outputs", &self.public_outputs);
+    }
+}
+
+impl Transcript {
+    /// 主張を吸収した状態の transcript を作る。チャレンジは必ずこの後に導出される
+    pub fn for_statement<F: PrimeField>(label: &[u8], statement: &Statement<F>) -> Self {
+        Self::for_statement_with_config(label, statement, TranscriptConfig::default())
+    }
+
+    /// `for_statement` と同じだが，ハッシュを `config` で選ぶ
+    pub fn for_statement_with_config<F: PrimeField>(
+        label: &[u8],
+        statement: &Statement<F>,
+        config: TranscriptConfig,
+    ) -> Self {
+        let mut transcript = Transcript::with_config(label, config);
+        statement.absorb_into(&mut transcript);
+        transcript
+    }
+}
@@ -0,0 +1,255 @@
+// src/streaming.rs
+//
+// 追記型の計算（ハッシュチェーンなど）向けの拡張可能な証明。
+// 状態 → 状態 の 1 ステップを表す回路を積み上げていき，新しいステップが届いたら
+// その分の層だけを証明してセグメントとして末尾に追加する。過去のセグメントは作り直さない。
+// セグメントの境界の状態は公開値で，検証者は隣り合うセグメントの境界が一致することを確かめる。
+
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::{One, Zero};
+use std::collections::HashMap;
+
+use crate::circuit::{Circuit, Gate, Layer};
+use crate::error::Error;
+use crate::ml_extension::{DenseMLE, SparseMLE};
+use crate::prover::{LinearGKRProof, LinearGKRProver, ProverBackend};
+use crate::statement::Statement;
+use crate::transcript::Transcript;
+use crate::verifier::LinearGKRVerifier;
+
+/// 1 ステップ分の回路。全ての層の幅が状態の幅に等しく，
+/// 各層の入力の `one_wire` 番目が常に 1 であること
+///
+/// 状態の幅は 2 のべきでなくてよい。層の値は 0 で 2 のべきに埋めて証明し，検証では実際の幅の分だけ確かめる。
+pub struct StepCircuit {
+    circuit: Circuit,
+    num_vars: usize,
+    one_wire: usize,
+    /// 層ごとの積の形の配線述語（`product_form_relation`）
+    relations: Vec<SparseMLE<ScalarField>>,
+}
+
+impl StepCircuit {
+    pub fn new(circuit: Circuit, one_wire: usize) -> Result<Self, Error> {
+        let width = circuit.num_inputs;
+        if circuit.layers.is_empty() || circuit.layers.iter().any(|layer| layer.gates.len() != width) {
+            return Err(Error::InvalidCircuit("every layer must have the state width"));
+        }
+        if one_wire >= width {
+            return Err(Error::InvalidCircuit("constant-one wire is out of range"));
+        }
+        let num_vars = width.next_power_of_two().trailing_zeros() as usize;
+        let relations =
+            circuit.layers.iter().map(|layer| product_form_relation(layer, num_vars, one_wire)).collect();
+        Ok(StepCircuit { circuit, num_vars, one_wire, relations })
+    }
+
+    /// 状態の幅
+    pub fn width(&self) -> usize {
+        self.circuit.num_inputs
+    }
+
+    pub fn circuit(&self) -> &Circuit {
+        &self.circuit
+    }
+
+    /// `num_steps` ステップ分の層の値（`values[0]` が最後の状態，最後の要素が `state`）
+    fn evaluate_steps(&self, state: &[ScalarField], num_steps: usize) -> Vec<Vec<ScalarField>> {
+        let mut values = vec![state.to_vec()];
+        for _ in 0..num_steps {
+            let mut step = self.circuit.evaluate(values.last().unwrap());
+            step.pop();
+            step.reverse();
+            values.extend(step);
+        }
+        values.reverse();
+        values
+    }
+
+    /// セグメントの上から i 番目の層の配線述語
+    fn relation(&self, i: usize) -> &SparseMLE<ScalarField> {
+        &self.relations[i % self.relations.len()]
+    }
+}
+
+/// 層を Σ_{x,y} f1(z, x, y)·V(x)·V(y) の形に書き直した配線述語（prover の添字の規約）
+///
+/// 乗算 Mul(a, b) は (a, b) に，加算 Add(a, b) は V(a)·1 + 1·V(b) として (a, one), (one, b) に，
+/// Input(a) は V(a)·1 として (a, one) に，Constant(c) は c·1·1 として (one, one) に対応させる。
+pub fn product_form_relation(layer: &Layer, num_vars: usize, one_wire: usize) -> SparseMLE<ScalarField> {
+    let l = num_vars;
+    let mut evaluations = HashMap::new();
+    let mut add = |z: usize, x: usize, y: usize, val: ScalarField| {
+        *evaluations.entry((z << (2 * l)) | (y << l) | x).or_insert_with(ScalarField::zero) += val;
+    };
+    let one = ScalarField::one();
+    for (z, gate) in layer.gates.iter().enumerate() {
+        match *gate {
+            Gate::Mul(a, b) => add(z, a, b, one),
+            Gate::Add(a, b) => {
+                add(z, a, one_wire, one);
+                add(z, one_wire, b, one);
+            }
+            Gate::Input(a) => add(z, a, one_wire, one),
+            Gate::Constant(c) => add(z, one_wire, one_wire, ScalarField::from(c)),
+            Gate::Sum(ref xs) => xs.iter().for_each(|&x| add(z, x, one_wire, one)),
+            Gate::Product(ref xs) => match xs[..] {
+                [] => add(z, one_wire, one_wire, one),
+                [a] => add(z, a, one_wire, one),
+                [a, b] => add(z, a, b, one),
+                _ => panic!("step circuits support products of at most two factors"),
+            },
+        }
+    }
+    SparseMLE::new(3 * l, evaluations)
+}
+
+fn boolean_point(z: usize, num_vars: usize) -> Vec<ScalarField> {
+    (0..num_vars)
+        .map(|i| if (z >> (num_vars - 1 - i)) & 1 == 1 { ScalarField::one() } else { ScalarField::zero() })
+        .collect()
+}
+
+/// 連続する `num_steps` ステップ分の証明
+#[derive(Clone)]
+pub struct SegmentProof {
+    pub num_steps: usize,
+    /// 各層の値（`values[0]` が終了時の状態，最後の要素が開始時の状態）
+    pub values: Vec<Vec<ScalarField>>,
+    /// 層ごと・出力ゲートごとの単層 GKR の証明
+    pub layer_proofs: Vec<Vec<LinearGKRProof>>,
+}
+
+impl SegmentProof {
+    pub fn start_state(&self) -> &[ScalarField] {
+        self.values.last().unwrap()
+    }
+
+    pub fn end_state(&self) -> &[ScalarField] {
+        &self.values[0]
+    }
+}
+
+/// セグメントを連ねた証明
+#[derive(Clone)]
+pub struct ExtendableProof {
+    pub initial_state: Vec<ScalarField>,
+    pub segments: Vec<SegmentProof>,
+}
+
+impl ExtendableProof {
+    pub fn num_steps(&self) -> usize {
+        self.segments.iter().map(|s| s.num_steps).sum()
+    }
+
+    /// 現時点の状態
+    pub fn final_state(&self) -> &[ScalarField] {
+        self.segments.last().map(|s| s.end_state()).unwrap_or(&self.initial_state)
+    }
+}
+
+/// ステップの回路と初期状態を吸収した transcript（prover と verifier で共通の出発点）
+fn initial_transcript(step: &StepCircuit, initial_state: &[ScalarField]) -> Transcript {
+    Transcript::for_statement(b"gkr-streaming", &Statement::new(&step.circuit, initial_state.to_vec(), Vec::new()))
+}
+
+/// ステップの到着に合わせて証明を伸ばしていく prover
+///
+/// transcript はセグメントをまたいで引き継ぐので，検証側は先頭から同じ順に吸収し直す。
+pub struct StreamingProver {
+    step: StepCircuit,
+    proof: ExtendableProof,
+    transcript: Transcript,
+}
+
+impl StreamingProver {
+    pub fn new(step: StepCircuit, initial_state: Vec<ScalarField>) -> Self {
+        assert_eq!(initial_state.len(), step.width(), "initial state has the wrong width");
+        StreamingProver {
+            transcript: initial_transcript(&step, &initial_state),
+            step,
+            proof: ExtendableProof { initial_state, segments: Vec::new() },
+        }
+    }
+
+    /// `num_steps` ステップを実行し，その層だけを証明して末尾に追加する
+    pub fn extend(&mut self, num_steps: usize) -> &SegmentProof {
+        assert!(num_steps > 0, "a segment needs at least one step");
+        let values = self.step.evaluate_steps(self.proof.final_state(), num_steps);
+        let l = self.step.num_vars;
+        let transcript = &mut self.transcript;
+        let layer_proofs = (0..values.len() - 1)
+            .map(|i| {
+                transcript.append_fields(b"layer_values", &values[i + 1]);
+                let v = DenseMLE::from_padded_evaluations(values[i + 1].clone());
+                let pre = LinearGKRProver::precompute(self.step.relation(i), &v, &v);
+                (0..self.step.width())
+                    .map(|z| {
+                        // 出力ごとに証明するので，z の部分の配線だけを走査する
+                        let g = boolean_point(z, l);
+                        LinearGKRProver::prove_precomputed_with(&pre, &g, ProverBackend::BooleanSlice, transcript)
+                    })
+                    .collect()
+            })
+            .collect();
+        self.proof.segments.push(SegmentProof { num_steps, values, layer_proofs });
+        self.proof.segments.last().unwrap()
+    }
+
+    pub fn proof(&self) -> &ExtendableProof {
+        &self.proof
+    }
+
+    pub fn into_proof(self) -> ExtendableProof {
+        self.proof
+    }
+}
+
+/// 拡張可能な証明を検証する
+///
+/// セグメントが初期状態から途切れずにつながっていること，定数 1 の配線が 1 であること，
+/// 各層の各ゲートの値が単層 GKR の主張として受理されることを確かめる。
+pub fn verify_extendable(step: &StepCircuit, proof: &ExtendableProof) -> Result<(), Error> {
+    let depth = step.circuit.depth();
+    let mut transcript = initial_transcript(step, &proof.initial_state);
+    let mut state: &[ScalarField] = &proof.initial_state;
+    for segment in proof.segments.iter() {
+        if segment.num_steps == 0 {
+            return Err(Error::MalformedProof("segment has no steps"));
+        }
+        if segment.values.len() != segment.num_steps * depth + 1 {
+            return Err(Error::LengthMismatch {
+                what: "segment layers",
+                expected: segment.num_steps * depth + 1,
+                found: segment.values.len(),
+            });
+        }
+        if segment.layer_proofs.len() != segment.values.len() - 1 {
+            return Err(Error::LengthMismatch {
+                what: "segment layer proofs",
+                expected: segment.values.len() - 1,
+                found: segment.layer_proofs.len(),
+            });
+        }
+        if let Some(v) = segment.values.iter().find(|v| v.len() != step.width()) {
+            return Err(Error::LengthMismatch { what: "layer values", expected: step.width(), found: v.len() });
+        }
+        if segment.start_state() != state {
+            return Err(Error::MalformedProof("segment does not continue from the previous state"));
+        }
+        for (i, proofs) in segment.layer_proofs.iter().enumerate() {
+            if segment.values[i + 1][step.one_wire] != ScalarField::one() {
+                return Err(Error::MalformedProof("constant-one wire is not one"));
+            }
+            if proofs.len() != step.width() {
+                return Err(Error::LengthMismatch { what: "gate proofs", expected: step.width(), found: proofs.len() });
+            }
+            transcript.append_fields(b"layer_values", &segment.values[i + 1]);
+            for (z, layer_proof) in proofs.iter().enumerate() {
+                LinearGKRVerifier::verify(step.num_vars, segment.values[i][z], layer_proof, &mut transcript)?;
+            }
+        }
+        state = segment.end_state();
+    }
+    Ok(())
+}
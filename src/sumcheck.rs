@@ -1,58 +1,105 @@
 // src/sumcheck.rs
 
 use ark_bls12_381::Fr as ScalarField;
-use ark_ff::Field;
+use ark_ff::{Field, PrimeField, UniformRand};
 use ark_poly::polynomial::multivariate::{SparsePolynomial, SparseTerm, Term};
 use ark_poly::polynomial::univariate::SparsePolynomial as UniSparsePolynomial;
 use ark_poly::polynomial::Polynomial;
 use ark_poly::DenseMVPolynomial;
+use rand::{CryptoRng, RngCore};
 // cfg_into_iter! は単純な iter() に置換
-use rand::Rng;
+use std::fmt;
 
-/// Sumcheck 用の多変数多項式の型
-pub type MultiPoly = SparsePolynomial<ScalarField, SparseTerm>;
-pub type UniPoly = UniSparsePolynomial<ScalarField>;
+use crate::challenge::{ChallengeGenerator, ChallengePolicy, ChallengeSampler};
+use crate::gray_code::gray_code_points;
+use crate::hypercube::{fill_point, BooleanHypercube};
+use crate::ml_extension::{fix_first_variable_batch, DenseMLE};
+use crate::self_check;
+use crate::transcript::labels;
 
-/// i を {0,1}^v 上のインデックスに変換する補助関数
-pub fn n_to_vec(i: usize, n: usize) -> Vec<ScalarField> {
-    format!("{:0>width$}", format!("{:b}", i), width = n)
-        .chars()
-        .map(|x| if x == '1' { 1.into() } else { 0.into() })
-        .collect()
+/// Sumcheck 用の多変数多項式の型（体を省略すると BLS12-381 の Fr）
+pub type MultiPoly<F = ScalarField> = SparsePolynomial<F, SparseTerm>;
+pub type UniPoly<F = ScalarField> = UniSparsePolynomial<F>;
+
+/// i を {0,1}^n 上の点に変換する補助関数（先頭の変数が最上位ビット）
+///
+/// 全点を走査するなら，点を使い回す `hypercube::BooleanHypercube` を使う。
+pub fn n_to_vec<F: Field>(i: usize, n: usize) -> Vec<F> {
+    let mut point = vec![F::zero(); n];
+    fill_point(i, &mut point);
+    point
+}
+
+/// 項を部分評価する：`values[var]` が `Some` の変数は代入し，`None` と範囲外の変数は項に残す
+///
+/// (代入した部分の積, 残った項) を返す。冪は 64 ビットの指数として二乗法で計算する。
+pub fn partial_evaluate_term<F: Field>(term: &SparseTerm, values: &[Option<F>]) -> (F, SparseTerm) {
+    let mut coeff = F::one();
+    let mut rest = Vec::new();
+    for (var, power) in term.iter() {
+        match values.get(*var).copied().flatten() {
+            Some(x) => coeff *= x.pow([*power as u64]),
+            None => rest.push((*var, *power)),
+        }
+    }
+    (coeff, SparseTerm::new(rest))
 }
 
 /// 単一の prover インスタンスの「メモリ」を模擬する構造体
 #[derive(Debug, Clone)]
-pub struct Prover {
-    pub g: MultiPoly,
-    pub r_vec: Vec<ScalarField>,
+pub struct Prover<F: Field = ScalarField> {
+    pub g: MultiPoly<F>,
+    pub r_vec: Vec<F>,
+    /// 自己検査用に保持する直前のラウンド多項式
+    previous: Option<UniPoly<F>>,
 }
 
-impl Prover {
-    pub fn new(g: &MultiPoly) -> Self {
+impl<F: Field> Prover<F> {
+    pub fn new(g: &MultiPoly<F>) -> Self {
         Prover {
             g: g.clone(),
             r_vec: vec![],
+            previous: None,
         }
     }
 
     // 多項式 g に対して、Xj を固定し xj+1 上で評価した結果（1変数多項式）を生成
-    pub fn gen_uni_polynomial(&mut self, r: Option<ScalarField>) -> UniPoly {
+    pub fn gen_uni_polynomial(&mut self, r: Option<F>) -> UniPoly<F> {
         if let Some(r_val) = r {
             self.r_vec.push(r_val);
         }
-        let v = self.g.num_vars() - self.r_vec.len();
-        (0..(2u32.pow(v as u32 - 1)))
-            .fold(UniPoly::from_coefficients_vec(vec![(0, 0u32.into())]),
-                  |sum, n| sum + self.evaluate_gj(n_to_vec(n as usize, v)))
+        let coeffs = self.gray_code_sums(&self.r_vec, true);
+        let gi = UniPoly::from_coefficients_vec(coeffs.into_iter().enumerate().filter(|(_, c)| !c.is_zero()).collect());
+        if self_check::enabled() {
+            self.check_round(&gi, r);
+        }
+        gi
+    }
+
+    /// 自己検査：g_i(0) + g_i(1) が直前の多項式の r での値（初回は直接求めた総和）に一致するか
+    fn check_round(&mut self, gi: &UniPoly<F>, r: Option<F>) {
+        let round = self.r_vec.len();
+        let claim = match (&self.previous, r) {
+            (Some(prev), Some(r)) => prev.evaluate(&r),
+            _ => BooleanHypercube::gray(self.g.num_vars()).sum(|point| self.g.evaluate(point)),
+        };
+        let sum = gi.evaluate(&F::zero()) + gi.evaluate(&F::one());
+        assert!(
+            sum == claim,
+            "self-check failed in sumcheck::Prover: round {}: g(0) + g(1) = {} but the running claim is {}",
+            round,
+            sum,
+            claim
+        );
+        self.previous = Some(gi.clone());
     }
 
     // gj を点列に対して評価し、全ての項を 1 変数多項式にまとめる
-    pub fn evaluate_gj(&self, points: Vec<ScalarField>) -> UniPoly {
+    pub fn evaluate_gj(&self, points: Vec<F>) -> UniPoly<F> {
         self.g.terms().iter().fold(
             UniPoly::from_coefficients_vec(vec![]),
             |sum, (coeff, term)| {
-                let (coeff_eval, fixed_term) = self.evaluate_term(&term, &points);
+                let (coeff_eval, fixed_term) = self.evaluate_term(term, &points);
                 let curr = match fixed_term {
                     None => UniPoly::from_coefficients_vec(vec![(0, *coeff * coeff_eval)]),
                     Some(ft) => UniPoly::from_coefficients_vec(vec![(ft.degree(), *coeff * coeff_eval)]),
@@ -63,44 +110,388 @@ impl Prover {
     }
 
     // 項 term を固定した場合の評価：(新しい係数, 固定後の項) を返す
+    //
+    // 先頭 r_vec.len() 変数は r_vec で，現在の変数 X_j は記号のまま，それ以降は point[var - j] で評価する。
+    // point が短く値の無い変数も記号のまま残るので，残る項は複数の変数を含みうる。
     pub fn evaluate_term(
         &self,
         term: &SparseTerm,
-        point: &Vec<ScalarField>,
-    ) -> (ScalarField, Option<SparseTerm>) {
-        let mut fixed_term: Option<SparseTerm> = None;
-        let coeff: ScalarField =
-            term.iter().fold(1u32.into(), |product, (var, power)| match *var {
-                j if j == self.r_vec.len() => {
-                    fixed_term = Some(SparseTerm::new(vec![(j, *power)]));
-                    product
+        point: &[F],
+    ) -> (F, Option<SparseTerm>) {
+        let values: Vec<Option<F>> = self
+            .r_vec
+            .iter()
+            .copied()
+            .map(Some)
+            .chain(std::iter::once(None))
+            .chain(point.iter().skip(1).copied().map(Some))
+            .collect();
+        let (coeff, rest) = partial_evaluate_term(term, &values);
+        (coeff, (!rest.is_constant()).then_some(rest))
+    }
+
+    // g の {0,1}^v 上での全評価和を求める（Gray 符号順の差分更新）
+    pub fn slow_sum_g(&self) -> F {
+        self.gray_code_sums(&[], false)[0]
+    }
+
+    /// 先頭の変数を `fixed` に固定し，`symbolic` なら次の 1 変数を X のまま残して，
+    /// 残りの変数を {0,1} 上で総和した結果を X の次数ごとの係数で返す
+    ///
+    /// ブール点では x^p = x なので，各項の値は「項に含まれるブール変数がすべて 1 か」で決まる。
+    /// 点を Gray 符号順に走査し，反転した変数を含む項の 0 の個数だけを更新するので，
+    /// 体の乗算は項ごとの定数部分を求める最初の一回だけで済む。
+    fn gray_code_sums(&self, fixed: &[F], symbolic: bool) -> Vec<F> {
+        let first_bool = fixed.len() + symbolic as usize;
+        let v = self.g.num_vars() - first_bool;
+        let mut consts = Vec::with_capacity(self.g.terms().len());
+        let mut degrees = Vec::with_capacity(self.g.terms().len());
+        let mut zeros = Vec::with_capacity(self.g.terms().len());
+        let mut var_terms = vec![Vec::new(); v];
+        for (t, (coeff, term)) in self.g.terms().iter().enumerate() {
+            let mut c = *coeff;
+            let mut degree = 0;
+            let mut count = 0;
+            for (var, power) in term.iter() {
+                if *var < fixed.len() {
+                    c *= fixed[*var].pow([*power as u64]);
+                } else if *var < first_bool {
+                    degree = *power;
+                } else {
+                    var_terms[*var - first_bool].push(t);
+                    count += 1;
                 }
-                j if j < self.r_vec.len() => self.r_vec[j].pow(&[*power as u64]) * product,
-                _ => point[*var - self.r_vec.len()].pow(&[*power as u64]) * product,
+            }
+            consts.push(c);
+            degrees.push(degree);
+            zeros.push(count);
+        }
+        let max_degree = degrees.iter().copied().max().unwrap_or(0);
+
+        // active[d]: 現在の点で値が 1 の項のうち，X の次数が d のものの定数部分の和
+        let mut active = vec![F::zero(); max_degree + 1];
+        for t in (0..consts.len()).filter(|&t| zeros[t] == 0) {
+            active[degrees[t]] += consts[t];
+        }
+        let mut sums = active.clone();
+        for point in gray_code_points(v).skip(1) {
+            let k = point.flipped.unwrap();
+            let to_one = point.flipped_to_one(v).unwrap();
+            for &t in var_terms[k].iter() {
+                if to_one {
+                    zeros[t] -= 1;
+                    if zeros[t] == 0 {
+                        active[degrees[t]] += consts[t];
+                    }
+                } else {
+                    if zeros[t] == 0 {
+                        active[degrees[t]] -= consts[t];
+                    }
+                    zeros[t] += 1;
+                }
+            }
+            for (s, a) in sums.iter_mut().zip(active.iter()) {
+                *s += a;
+            }
+        }
+        sums
+    }
+}
+
+/// 変数ごとに d_j + 1 点（0, 1, ..., d_j）での評価表を持つ sum-check prover
+///
+/// ある変数が 2 次以上で現れる多項式（二乗ゲートなど）でも，格子
+/// Π_j {0, ..., d_j} 上の評価表を一度作れば，各ラウンドは表の走査と
+/// ラグランジュ重みによる畳み込みだけで済み，項ごとの評価を繰り返さない。
+/// 変数は先頭から順に束縛し，表は先頭変数を最上位の桁とする混合基数で並べる。
+#[derive(Debug, Clone)]
+pub struct GridProver<F: Field> {
+    /// 残りの変数ごとの評価点の数（d_j + 1）
+    radices: Vec<usize>,
+    table: Vec<F>,
+}
+
+impl<F: Field> GridProver<F> {
+    /// `degrees[j]` は変数 j の次数の上限，`eval` は格子点での多項式の値
+    pub fn from_evaluator(degrees: &[usize], eval: impl Fn(&[F]) -> F) -> Self {
+        let radices: Vec<usize> = degrees.iter().map(|d| (*d).max(1) + 1).collect();
+        let size = radices.iter().product();
+        let mut point = vec![F::zero(); radices.len()];
+        let table = (0..size)
+            .map(|mut idx| {
+                for (coord, radix) in point.iter_mut().zip(radices.iter()).rev() {
+                    *coord = F::from((idx % radix) as u64);
+                    idx /= radix;
+                }
+                eval(&point)
+            })
+            .collect();
+        GridProver { radices, table }
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.radices.len()
+    }
+
+    /// 現ラウンドの多項式の 0, 1, ..., d_j での評価値
+    pub fn round_evaluations(&self) -> Vec<F> {
+        let radix = self.radices[0];
+        let rest = self.table.len() / radix;
+        let offsets = self.boolean_offsets();
+        (0..radix)
+            .map(|t| offsets.iter().map(|o| self.table[t * rest + o]).sum())
+            .collect()
+    }
+
+    /// 先頭変数を r に束縛する
+    pub fn apply_challenge(&mut self, r: F) {
+        let radix = self.radices.remove(0);
+        let rest = self.table.len() / radix;
+        let weights = lagrange_weights(radix, r);
+        self.table = (0..rest)
+            .map(|o| weights.iter().enumerate().map(|(t, w)| self.table[t * rest + o] * w).sum())
+            .collect();
+    }
+
+    /// 残りの全変数がブール値となる位置（表内のオフセット）を列挙する
+    fn boolean_offsets(&self) -> Vec<usize> {
+        let mut offsets = vec![0];
+        let mut stride = 1;
+        for radix in self.radices[1..].iter().rev() {
+            offsets = offsets.iter().flat_map(|o| [*o, *o + stride]).collect();
+            stride *= radix;
+        }
+        offsets
+    }
+}
+
+impl<F: Field> GridProver<F> {
+    pub fn from_poly(g: &MultiPoly<F>) -> Self {
+        GridProver::from_evaluator(&max_degrees(g), |point| g.evaluate(&point.to_vec()))
+    }
+
+    /// `Prover::gen_uni_polynomial` と同じ形で，現ラウンドの 1 変数多項式を返す
+    pub fn gen_uni_polynomial(&mut self, r: Option<F>) -> UniPoly<F> {
+        if let Some(r_val) = r {
+            self.apply_challenge(r_val);
+        }
+        uni_poly_from_evaluations(&self.round_evaluations())
+    }
+}
+
+/// 密な MLE の積 Π_k P_k(x)（例えば h_g·f2）に対する sum-check prover
+///
+/// `Prover` と同じ `gen_uni_polynomial` を持つが，多項式を項で展開せず因子ごとの
+/// bookkeeping table を半分ずつ畳み込むので，全ラウンドで O(d·2^l) の計算で済む。
+/// 変数は先頭（添字の最上位ビット）から順に束縛する。
+#[derive(Clone)]
+pub struct MLSumcheck<F: Field = ScalarField> {
+    /// 各因子の，残りの変数についての評価表
+    tables: Vec<DenseMLE<F>>,
+    /// これまでに束縛したチャレンジ
+    pub r_vec: Vec<F>,
+    claimed_sum: F,
+}
+
+impl<F: Field> MLSumcheck<F> {
+    /// `factors` は同じ変数数の MLE（少なくとも 1 つ）
+    pub fn new(factors: Vec<DenseMLE<F>>) -> Self {
+        let state = protocol::prover_init(factors);
+        MLSumcheck { tables: state.tables, r_vec: Vec::with_capacity(state.num_vars), claimed_sum: state.current_sum }
+    }
+
+    /// 残りの変数の数
+    pub fn num_vars(&self) -> usize {
+        self.tables[0].num_vars
+    }
+
+    /// ラウンド多項式の次数（因子の数）
+    pub fn degree(&self) -> usize {
+        self.tables.len()
+    }
+
+    /// ブール超立方体上の総和 Σ_x Π_k P_k(x)
+    pub fn claimed_sum(&self) -> F {
+        self.claimed_sum
+    }
+
+    /// 現ラウンドの多項式の 0, 1, ..., d での評価値
+    pub fn round_evaluations(&self) -> Vec<F> {
+        assert!(self.num_vars() > 0, "all variables are already bound");
+        protocol::round_evaluations(&self.tables)
+    }
+
+    /// 先頭変数を r に束縛する
+    pub fn apply_challenge(&mut self, r: F) {
+        assert!(self.num_vars() > 0, "all variables are already bound");
+        fix_first_variable_batch(&mut self.tables, r);
+        self.r_vec.push(r);
+    }
+
+    /// `Prover::gen_uni_polynomial` と同じ形で，現ラウンドの 1 変数多項式を返す
+    pub fn gen_uni_polynomial(&mut self, r: Option<F>) -> UniPoly<F> {
+        if let Some(r_val) = r {
+            self.apply_challenge(r_val);
+        }
+        uni_poly_from_evaluations(&self.round_evaluations())
+    }
+
+    /// 全変数を束縛した後の各因子の値 P_k(r_1, ..., r_l)
+    pub fn final_evaluations(&self) -> Vec<F> {
+        assert_eq!(self.num_vars(), 0, "some variables are still free");
+        self.tables.iter().map(|t| t.evaluations[0]).collect()
+    }
+}
+
+/// 評価値形式のラウンドメッセージの送り方
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum MessageForm {
+    /// g(1), ..., g(d) だけを送り，g(0) は検証側が直前の主張から復元する
+    #[default]
+    OmitZero,
+    /// g(0), ..., g(d) をすべて送る（デバッグ用）
+    Full,
+}
+
+impl MessageForm {
+    /// prover 側：評価値 g(0), ..., g(d) を送信用のメッセージにする
+    pub fn encode<F: Field>(&self, mut evals: Vec<F>) -> Vec<F> {
+        if *self == MessageForm::OmitZero && !evals.is_empty() {
+            evals.remove(0);
+        }
+        evals
+    }
+
+    /// 検証側：メッセージと直前の主張から g(0), ..., g(d) を復元する
+    pub fn decode<F: Field>(&self, msg: &[F], claim: F) -> Vec<F> {
+        match self {
+            MessageForm::Full => msg.to_vec(),
+            MessageForm::OmitZero => match msg.first() {
+                Some(g1) => std::iter::once(claim - g1).chain(msg.iter().copied()).collect(),
+                None => vec![],
+            },
+        }
+    }
+}
+
+/// 0, 1, ..., n-1 を補間点とするラグランジュ基底の点 r での値
+pub fn lagrange_weights<F: Field>(n: usize, r: F) -> Vec<F> {
+    (0..n)
+        .map(|i| {
+            let xi = F::from(i as u64);
+            let (num, den) = (0..n).filter(|&j| j != i).fold((F::one(), F::one()), |(num, den), j| {
+                let xj = F::from(j as u64);
+                (num * (r - xj), den * (xi - xj))
             });
-        (coeff, fixed_term)
+            num * den.inverse().expect("interpolation nodes are distinct")
+        })
+        .collect()
+}
+
+/// 0, 1, ..., n-1 を補間点とする重心重み w_i = 1 / Π_{j≠i} (i - j)
+pub fn barycentric_weights<F: Field>(n: usize) -> Vec<F> {
+    (0..n)
+        .map(|i| {
+            let den: F = (0..n).filter(|&j| j != i).map(|j| F::from(i as u64) - F::from(j as u64)).product();
+            den.inverse().expect("interpolation nodes are distinct")
+        })
+        .collect()
+}
+
+/// 評価値列 [g(0), ..., g(n-1)] で表された多項式の r での値（重心形のラグランジュ補間）
+///
+/// g(r) = l(r)·Σ_i w_i·g(i) / (r - i)，l(r) = Π_i (r - i)。逆元は分母の積で 1 回にまとめる。
+pub fn barycentric_evaluate<F: Field>(evals: &[F], r: F) -> F {
+    let n = evals.len();
+    let diffs: Vec<F> = (0..n).map(|i| r - F::from(i as u64)).collect();
+    if let Some(i) = diffs.iter().position(|d| d.is_zero()) {
+        return evals[i];
     }
+    let l: F = diffs.iter().product();
+    let weights = barycentric_weights::<F>(n);
+    let mut inverses = diffs;
+    ark_ff::batch_inversion(&mut inverses);
+    l * evals.iter().zip(weights.iter()).zip(inverses.iter()).map(|((y, w), inv)| *y * w * inv).sum::<F>()
+}
 
-    // g の {0,1}^v 上での全評価和を求める（遅い実装）
-    pub fn slow_sum_g(&self) -> ScalarField {
-        let v = self.g.num_vars();
-        let n = 2u32.pow(v as u32);
-        (0..n)
-            .map(|n| self.g.evaluate(&n_to_vec(n as usize, v)))
-            .sum()
+/// 0, 1, ..., d での評価値から係数表現の 1 変数多項式を復元する
+pub fn uni_poly_from_evaluations<F: Field>(evals: &[F]) -> UniPoly<F> {
+    let n = evals.len();
+    let mut coeffs = vec![F::zero(); n];
+    for (i, y) in evals.iter().enumerate() {
+        // Π_{j≠i} (X - j) を展開し，y_i / Π_{j≠i} (i - j) 倍して足し込む
+        let mut basis = vec![F::one()];
+        let mut den = F::one();
+        for j in (0..n).filter(|&j| j != i) {
+            let xj = F::from(j as u64);
+            let mut next = vec![F::zero(); basis.len() + 1];
+            for (k, c) in basis.iter().enumerate() {
+                next[k + 1] += c;
+                next[k] -= *c * xj;
+            }
+            basis = next;
+            den *= F::from(i as u64) - xj;
+        }
+        let scale = *y * den.inverse().unwrap();
+        for (c, b) in coeffs.iter_mut().zip(basis.iter()) {
+            *c += *b * scale;
+        }
     }
+    UniPoly::from_coefficients_vec(
+        coeffs.into_iter().enumerate().filter(|(_, c)| !c.is_zero()).collect(),
+    )
 }
 
 // 検証側の手続き
 
-pub fn get_r() -> Option<ScalarField> {
-    let mut rng = rand::thread_rng();
-    let r: ScalarField = rng.gen();
-    Some(r)
+/// sum-check の検証の失敗理由（`round` は 0 始まりのラウンド番号）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SumcheckError {
+    /// g_i(0) + g_i(1) が直前の主張と一致しない
+    WrongRoundSum { round: usize },
+    /// ラウンド多項式の次数が g の変数の次数を超えている
+    DegreeBoundExceeded { round: usize, degree: usize, bound: usize },
+    /// 最後のラウンド多項式の値が g のランダム点での値と一致しない
+    FinalEvaluationMismatch,
+    /// バッチの主張の数がインスタンスの数と異なる
+    ClaimCountMismatch { expected: usize, found: usize },
+}
+
+impl fmt::Display for SumcheckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SumcheckError::WrongRoundSum { round } => write!(f, "round {}: g(0) + g(1) does not match the claim", round),
+            SumcheckError::DegreeBoundExceeded { round, degree, bound } => {
+                write!(f, "round {}: degree {} exceeds the bound {}", round, degree, bound)
+            }
+            SumcheckError::FinalEvaluationMismatch => write!(f, "final evaluation does not match the polynomial"),
+            SumcheckError::ClaimCountMismatch { expected, found } => {
+                write!(f, "expected {} claims but found {}", expected, found)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SumcheckError {}
+
+/// ラウンド多項式 g_i の和と次数を確かめる
+fn check_round<F: Field>(round: usize, gi: &UniPoly<F>, claim: F, bound: usize) -> Result<(), SumcheckError> {
+    if gi.evaluate(&F::zero()) + gi.evaluate(&F::one()) != claim {
+        return Err(SumcheckError::WrongRoundSum { round });
+    }
+    if gi.degree() > bound {
+        return Err(SumcheckError::DegreeBoundExceeded { round, degree: gi.degree(), bound });
+    }
+    Ok(())
+}
+
+/// 検証者のチャレンジを rng から引く（再現したいときは `challenge::seeded_rng` を渡す）
+pub fn get_r<F: UniformRand, R: RngCore + CryptoRng>(rng: &mut R) -> Option<F> {
+    Some(F::rand(rng))
 }
 
 /// g の各変数に対する次数のルックアップテーブルを返す
-pub fn max_degrees(g: &MultiPoly) -> Vec<usize> {
+pub fn max_degrees<F: Field>(g: &MultiPoly<F>) -> Vec<usize> {
     let mut lookup: Vec<usize> = vec![0; g.num_vars()];
     g.terms().iter().for_each(|(_, term)| {
         term.iter().for_each(|(var, power)| {
@@ -113,97 +504,712 @@ pub fn max_degrees(g: &MultiPoly) -> Vec<usize> {
 }
 
 /// プローバの主張 c_1 を検証する（ペダンティックな例）
-pub fn verify(g: &MultiPoly, c_1: ScalarField) -> bool {
-    // 1回目のラウンド
-    let mut p = Prover::new(g);
+pub fn verify<F: PrimeField>(g: &MultiPoly<F>, c_1: F) -> Result<(), SumcheckError> {
+    verify_with_policy(g, c_1, ChallengePolicy::default())
+}
+
+/// `verify` と同じだが，チャレンジをポリシーに従って引き直す
+pub fn verify_with_policy<F: PrimeField>(
+    g: &MultiPoly<F>,
+    c_1: F,
+    policy: ChallengePolicy,
+) -> Result<(), SumcheckError> {
+    verify_with_rng(g, c_1, policy, &mut rand::thread_rng())
+}
+
+/// `verify_with_policy` と同じだが，チャレンジを与えた rng から引く
+pub fn verify_with_rng<F: PrimeField, R: RngCore + CryptoRng>(
+    g: &MultiPoly<F>,
+    c_1: F,
+    policy: ChallengePolicy,
+    rng: &mut R,
+) -> Result<(), SumcheckError> {
+    verify_with_challenges(g, c_1, policy, rng)
+}
+
+/// `verify_with_policy` と同じだが，チャレンジを `challenges` から引く
+///
+/// 各ラウンドの前に，そのラウンド多項式の 0, 1, ..., 次数の上限での値を `challenges` に渡す。
+pub fn verify_with_challenges<F: PrimeField, C: ChallengeGenerator<F>>(
+    g: &MultiPoly<F>,
+    c_1: F,
+    policy: ChallengePolicy,
+    challenges: &mut C,
+) -> Result<(), SumcheckError> {
+    let mut sampler = ChallengeSampler::new(policy);
+    let mut get_r = |gi: &UniPoly<F>, bound: usize| {
+        challenges.absorb(labels::SUMCHECK_ROUND, &round_values(gi, bound));
+        Some(sampler.sample(|_| challenges.challenge(labels::SUMCHECK_CHALLENGE)))
+    };
+
+    // 1回目のラウンド（ラウンド多項式は格子表から求める）
+    let mut p = GridProver::from_poly(g);
+    let mut r_vec = Vec::with_capacity(p.num_vars());
     let mut gi = p.gen_uni_polynomial(None);
-    let mut expected_c = gi.evaluate(&0u32.into()) + gi.evaluate(&1u32.into());
-    assert_eq!(c_1, expected_c);
-    let lookup_degree = max_degrees(&g);
-    assert!(gi.degree() <= lookup_degree[0]);
+    let lookup_degree = max_degrees(g);
+    check_round(0, &gi, c_1, lookup_degree[0])?;
 
     // 中間ラウンド
-    for j in 1..p.g.num_vars() {
-        let r = get_r();
-        expected_c = gi.evaluate(&r.unwrap());
+    for (round, degree_bound) in lookup_degree.iter().enumerate().take(p.num_vars()).skip(1) {
+        let r = get_r(&gi, lookup_degree[round - 1]);
+        r_vec.push(r.unwrap());
+        let expected_c = gi.evaluate(&r.unwrap());
         gi = p.gen_uni_polynomial(r);
-        let new_c = gi.evaluate(&0u32.into()) + gi.evaluate(&1u32.into());
-        assert_eq!(expected_c, new_c);
-        assert!(gi.degree() <= lookup_degree[j]);
+        check_round(round, &gi, expected_c, *degree_bound)?;
     }
     // 最終ラウンド
-    let r = get_r();
-    expected_c = gi.evaluate(&r.unwrap());
-    p.r_vec.push(r.unwrap());
-    let new_c = p.g.evaluate(&p.r_vec);
-    assert_eq!(expected_c, new_c);
-    true
+    let r = get_r(&gi, lookup_degree[lookup_degree.len() - 1]);
+    let expected_c = gi.evaluate(&r.unwrap());
+    r_vec.push(r.unwrap());
+    if expected_c != g.evaluate(&r_vec) {
+        return Err(SumcheckError::FinalEvaluationMismatch);
+    }
+    Ok(())
 }
 
-pub fn slow_verify(g: &MultiPoly, c_1: ScalarField) -> bool {
+/// 1 変数多項式 gi の 0, 1, ..., bound での値（transcript に渡すラウンドメッセージ）
+fn round_values<F: Field>(gi: &UniPoly<F>, bound: usize) -> Vec<F> {
+    (0..=bound).map(|t| gi.evaluate(&F::from(t as u64))).collect()
+}
+
+/// 全ての点で g を評価した和と c_1 を直接比べる
+pub fn slow_verify<F: Field>(g: &MultiPoly<F>, c_1: F) -> Result<(), SumcheckError> {
     let p = Prover::new(g);
     let manual_sum = p.slow_sum_g();
-    manual_sum == c_1
+    if manual_sum != c_1 {
+        return Err(SumcheckError::WrongRoundSum { round: 0 });
+    }
+    Ok(())
 }
 
-// ────── 以下、Linear GKR プロトコルで利用する sum-check のインタラクティブプロトコル（ダミー実装） ──────
+/// 同じ変数数の N 個のインスタンスを共通のチャレンジ列で証明する prover
+///
+/// 各ラウンドのメッセージは Σ_k w_k·g_{k,j}(X) で，証明サイズは N に依らない。
+#[derive(Debug, Clone)]
+pub struct BatchedProver<F: Field = ScalarField> {
+    pub provers: Vec<Prover<F>>,
+    pub weights: Vec<F>,
+}
+
+impl<F: Field> BatchedProver<F> {
+    pub fn new(gs: &[MultiPoly<F>], weights: &[F]) -> Self {
+        assert_eq!(gs.len(), weights.len());
+        assert!(!gs.is_empty(), "batch must contain at least one instance");
+        let num_vars = gs[0].num_vars();
+        assert!(gs.iter().all(|g| g.num_vars() == num_vars), "num_vars mismatch in batch");
+        BatchedProver { provers: gs.iter().map(Prover::new).collect(), weights: weights.to_vec() }
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.provers[0].g.num_vars()
+    }
+
+    /// 全インスタンスに同じチャレンジ r を適用し，重み付きの和を返す
+    pub fn gen_uni_polynomial(&mut self, r: Option<F>) -> UniPoly<F> {
+        self.provers.iter_mut().zip(self.weights.iter()).fold(
+            UniPoly::from_coefficients_vec(vec![]),
+            |sum, (p, w)| sum + &p.gen_uni_polynomial(r) * *w,
+        )
+    }
+
+    /// 最終点での Σ_k w_k·g_k(r)
+    pub fn evaluate_at(&self, point: &[F]) -> F {
+        self.provers.iter().zip(self.weights.iter()).map(|(p, w)| p.g.evaluate(&point.to_vec()) * w).sum()
+    }
+}
+
+/// 各インスタンスの変数ごとの次数の最大値
+pub fn batched_max_degrees<F: Field>(gs: &[MultiPoly<F>]) -> Vec<usize> {
+    gs.iter().map(max_degrees).fold(vec![], |acc, d| {
+        if acc.is_empty() {
+            d
+        } else {
+            acc.iter().zip(d.iter()).map(|(a, b)| *a.max(b)).collect()
+        }
+    })
+}
+
+/// N 個の主張 (g_k, c_k) を共通のチャレンジ列でまとめて検証する
+///
+/// 検証者はランダムな重み w_k を選び，Σ_k w_k·c_k を 1 つの sum-check で検証する。
+pub fn verify_batched<F: Field>(gs: &[MultiPoly<F>], claims: &[F]) -> Result<(), SumcheckError> {
+    verify_batched_with_rng(gs, claims, &mut rand::thread_rng())
+}
+
+/// `verify_batched` と同じだが，重みとチャレンジを与えた rng から引く
+pub fn verify_batched_with_rng<F: Field, R: RngCore + CryptoRng>(
+    gs: &[MultiPoly<F>],
+    claims: &[F],
+    rng: &mut R,
+) -> Result<(), SumcheckError> {
+    if claims.len() != gs.len() {
+        return Err(SumcheckError::ClaimCountMismatch { expected: gs.len(), found: claims.len() });
+    }
+    let weights: Vec<F> = gs.iter().map(|_| get_r(rng).unwrap()).collect();
+    let mut p = BatchedProver::new(gs, &weights);
+    let combined_claim: F = claims.iter().zip(weights.iter()).map(|(c, w)| *c * w).sum();
+    let lookup_degree = batched_max_degrees(gs);
+
+    // 1回目のラウンド
+    let mut gi = p.gen_uni_polynomial(None);
+    check_round(0, &gi, combined_claim, lookup_degree[0])?;
+
+    // 中間ラウンド
+    let mut r_vec = Vec::with_capacity(p.num_vars());
+    for (round, degree_bound) in lookup_degree.iter().enumerate().take(p.num_vars()).skip(1) {
+        let r = get_r(rng).unwrap();
+        r_vec.push(r);
+        let expected_c = gi.evaluate(&r);
+        gi = p.gen_uni_polynomial(Some(r));
+        check_round(round, &gi, expected_c, *degree_bound)?;
+    }
+    // 最終ラウンド
+    let r = get_r(rng).unwrap();
+    r_vec.push(r);
+    if gi.evaluate(&r) != p.evaluate_at(&r_vec) {
+        return Err(SumcheckError::FinalEvaluationMismatch);
+    }
+    Ok(())
+}
 
+// ────── 以下、Linear GKR プロトコルで利用する sum-check のインタラクティブプロトコル ──────
+
+/// 密な MLE の積 Π_k P_k(x) に対する sum-check（bookkeeping table による O(2^l) の実装）
+///
+/// 変数は先頭（添字の最上位ビット）から順に束縛する。各ラウンドのメッセージは
+/// ラウンド多項式 g_i の 0, 1, ..., d での評価値（d は因子の数）を `MessageForm::default()`
+/// （g_i(0) を省く `OmitZero`）でエンコードしたもの。
 pub mod protocol {
-    use ark_ff::Field;
+    use ark_ff::{Field, PrimeField};
+
+    use crate::error::{Error, RoundError};
+    use crate::ml_extension::{fix_first_variable_batch, DenseMLE, IndexOrder};
+    use crate::pcs::MultilinearPCS;
+    use crate::sumcheck::{barycentric_evaluate, MessageForm};
+    use crate::small_field;
+    use crate::transcript::{labels, Transcript};
     use rand::Rng;
+    #[cfg(feature = "parallel")]
+    use rayon::prelude::*;
+    use std::ops::Range;
 
-    /// Sum-check プローバ側の状態（簡易な例）
+    /// ラウンド多項式の計算で 1 スレッドが受け持つ添字の数
+    #[cfg(feature = "parallel")]
+    const ROUND_CHUNK: usize = 1 << 12;
+
+    /// Sum-check プローバ側の状態：各因子の，残りの変数についての評価表
     pub struct ProverState<F: Field> {
         pub num_vars: usize,
         pub current_sum: F,
+        pub tables: Vec<DenseMLE<F>>,
+        /// ラウンドメッセージの送り方
+        pub form: MessageForm,
+        /// 直前の `prove_round` で求めた g_i(0), ..., g_i(d)（`apply_challenge` で g_i(r) の補間に使う）
+        pub last_evals: Option<Vec<F>>,
     }
 
-    /// Sum-check 検証側の状態（簡易な例）
+    /// Sum-check 検証側の状態
     pub struct VerifierState<F: Field> {
         pub num_vars: usize,
+        /// ラウンド多項式の次数の上限
+        pub max_degree: usize,
+        /// 現ラウンドで g_i(0) + g_i(1) が一致すべき値
         pub current_sum: F,
+        /// 直前に受け取ったメッセージ（g_i(0), ..., g_i(d) に復元したもの）
+        pub last_msg: Option<Vec<F>>,
+        /// ラウンドメッセージの送り方（プローバと揃える）
+        pub form: MessageForm,
+        /// これまでに送ったチャレンジ (r_1, ..., r_i)
+        pub challenges: Vec<F>,
+        pub rounds_done: usize,
+    }
+
+    /// プローバ側の状態初期化。`tables` は同じ変数数の因子（少なくとも 1 つ）
+    ///
+    /// メッセージは `MessageForm::default()`（g_i(0) を省く）で送る。
+    pub fn prover_init<F: Field>(tables: Vec<DenseMLE<F>>) -> ProverState<F> {
+        prover_init_with_form(tables, MessageForm::default())
+    }
+
+    /// `prover_init` と同じだが，ラウンドメッセージを `form` で送る
+    ///
+    /// 因子の表は先頭の変数を最上位ビットとする順序（`IndexOrder::BigEndian`）に揃えてから持つ。
+    pub fn prover_init_with_form<F: Field>(tables: Vec<DenseMLE<F>>, form: MessageForm) -> ProverState<F> {
+        assert!(!tables.is_empty(), "sum-check needs at least one factor");
+        let num_vars = tables[0].num_vars;
+        assert!(tables.iter().all(|t| t.num_vars == num_vars), "num_vars mismatch between factors");
+        let tables: Vec<DenseMLE<F>> = tables
+            .into_iter()
+            .map(|t| if t.order == IndexOrder::BigEndian { t } else { t.to_order(IndexOrder::BigEndian) })
+            .collect();
+        let current_sum = small_field::product_sum(&tables).unwrap_or_else(|| {
+            let product_at = |i: usize| tables.iter().map(|t| t.evaluations[i]).product::<F>();
+            #[cfg(feature = "parallel")]
+            let sum = (0..1usize << num_vars).into_par_iter().map(product_at).sum();
+            #[cfg(not(feature = "parallel"))]
+            let sum = (0..1usize << num_vars).map(product_at).sum();
+            sum
+        });
+        ProverState { num_vars, current_sum, tables, form, last_evals: None }
+    }
+
+    /// 現ラウンドのメッセージを生成する（`Full` なら [g_i(0), ..., g_i(d)]，`OmitZero` なら g_i(0) を除く）
+    ///
+    /// g_i(t) = Σ_b Π_k ((1 - t)·P_k(0, b) + t·P_k(1, b)) を，表の下半分と上半分から t ごとに計算する。
+    /// 求めた値は `apply_challenge` のために状態に残す。
+    pub fn prove_round<F: Field>(state: &mut ProverState<F>) -> Vec<F> {
+        let evals = current_round_evaluations(state);
+        state.form.encode(evals)
+    }
+
+    /// 現ラウンドの g_i(0), ..., g_i(d) を求め，状態に残して返す
+    fn current_round_evaluations<F: Field>(state: &mut ProverState<F>) -> Vec<F> {
+        assert!(state.num_vars > 0, "all variables are already bound");
+        let evals = round_evaluations(&state.tables);
+        state.last_evals = Some(evals.clone());
+        evals
     }
 
-    /// プローバ側の状態初期化
-    pub fn prover_init<F: Field>(num_vars: usize, claimed_sum: F) -> ProverState<F> {
-        ProverState { num_vars, current_sum: claimed_sum }
+    /// 同じ変数数（1 以上）の表の積について，先頭変数のラウンド多項式の 0, 1, ..., d での値を求める
+    pub fn round_evaluations<F: Field>(tables: &[DenseMLE<F>]) -> Vec<F> {
+        round_evaluations_up_to(tables, tables.len())
     }
 
-    /// 現ラウンドの証明メッセージ（例：1次多項式の係数）を生成する（ダミー実装）
-    pub fn prove_round<F: Field, R: Rng>(_state: &mut ProverState<F>, _rng: &mut R) -> Vec<F> {
-        vec![F::zero(), F::one()]
+    /// `round_evaluations` と同じだが，0, 1, ..., degree（因子の数以上）での値を求める
+    fn round_evaluations_up_to<F: Field>(tables: &[DenseMLE<F>], degree: usize) -> Vec<F> {
+        if let Some(evals) = small_field::round_evaluations(tables, degree) {
+            return evals;
+        }
+        let d = tables.len();
+        let half = 1 << (tables[0].num_vars - 1);
+        // 添字 b の範囲ごとの部分和
+        let partial = |range: Range<usize>| {
+            let mut evals = vec![F::zero(); degree + 1];
+            let mut current = vec![F::zero(); d];
+            let mut step = vec![F::zero(); d];
+            for b in range {
+                for (k, t) in tables.iter().enumerate() {
+                    current[k] = t.evaluations[b];
+                    step[k] = t.evaluations[b + half] - t.evaluations[b];
+                }
+                for e in evals.iter_mut() {
+                    *e += current.iter().product::<F>();
+                    for (c, s) in current.iter_mut().zip(step.iter()) {
+                        *c += s;
+                    }
+                }
+            }
+            evals
+        };
+        #[cfg(feature = "parallel")]
+        {
+            (0..half.div_ceil(ROUND_CHUNK))
+                .into_par_iter()
+                .map(|c| partial(c * ROUND_CHUNK..((c + 1) * ROUND_CHUNK).min(half)))
+                .reduce(
+                    || vec![F::zero(); degree + 1],
+                    |mut acc, part| {
+                        acc.iter_mut().zip(part).for_each(|(a, p)| *a += p);
+                        acc
+                    },
+                )
+        }
+        #[cfg(not(feature = "parallel"))]
+        partial(0..half)
+    }
+
+    /// プローバ側の状態を検証側のランダムチャレンジで更新（全ての表の先頭変数を r に固定）
+    ///
+    /// 次の主張 g_i(r) は `prove_round` で残した値から補間する（表を読み直さない）。
+    pub fn apply_challenge<F: Field>(state: &mut ProverState<F>, r: F) {
+        assert!(state.num_vars > 0, "all variables are already bound");
+        let evals = state.last_evals.take().expect("prove_round must be called before applying a challenge");
+        fix_first_variable_batch(&mut state.tables, r);
+        state.num_vars -= 1;
+        state.current_sum = barycentric_evaluate(&evals, r);
     }
 
-    /// プローバ側の状態を検証側のランダムチャレンジで更新（ダミー実装）
-    pub fn apply_challenge<F: Field>(_state: &mut ProverState<F>, _r: F) {
-        // 状態更新（ダミー実装）
+    /// Verifier 用のチャレンジ適用関数：次の主張を g_i(r) にする
+    pub fn apply_challenge_verifier<F: Field>(state: &mut VerifierState<F>, r: F) {
+        let msg = state.last_msg.take().expect("verify_round must be called before applying a challenge");
+        state.current_sum = barycentric_evaluate(&msg, r);
+        state.challenges.push(r);
+        state.rounds_done += 1;
     }
 
-    /// Verifier 用のチャレンジ適用関数（状態更新はダミー）
-    pub fn apply_challenge_verifier<F: Field>(_state: &mut VerifierState<F>, _r: F) {
-        // 状態更新（ダミー実装）
+    impl<F: Field> ProverState<F> {
+        /// ラウンド多項式の次数（因子の数）。検証側の `max_degree` に渡す
+        pub fn degree(&self) -> usize {
+            self.tables.len()
+        }
+    }
+
+    /// 検証側の状態初期化（claimed_sum をセットする）。メッセージは `MessageForm::default()` で受け取る
+    pub fn verifier_init<F: Field>(num_vars: usize, max_degree: usize, claimed_sum: F) -> VerifierState<F> {
+        verifier_init_with_form(num_vars, max_degree, claimed_sum, MessageForm::default())
     }
 
-    /// 検証側の状態初期化（claimed_sum をセットする）
-    pub fn verifier_init<F: Field>(num_vars: usize, claimed_sum: F) -> VerifierState<F> {
-        VerifierState { num_vars, current_sum: claimed_sum }
+    /// `verifier_init` と同じだが，メッセージを `form` で受け取る
+    pub fn verifier_init_with_form<F: Field>(
+        num_vars: usize,
+        max_degree: usize,
+        claimed_sum: F,
+        form: MessageForm,
+    ) -> VerifierState<F> {
+        VerifierState {
+            num_vars,
+            max_degree,
+            current_sum: claimed_sum,
+            last_msg: None,
+            form,
+            challenges: Vec::with_capacity(num_vars),
+            rounds_done: 0,
+        }
     }
 
-    /// 各ラウンドでプローバから送られたメッセージの検証（ダミー実装）
-    pub fn verify_round<F: Field>(_state: &mut VerifierState<F>, _msg: &Vec<F>) -> Result<(), &'static str> {
+    /// 各ラウンドでプローバから送られたメッセージの検証：次数の上限と c_i = g_i(0) + g_i(1)
+    ///
+    /// メッセージを g_i(0), ..., g_i(d) に復元してから検査するので，長さ d + 1 が次数 d を表す。
+    /// `OmitZero` では g_i(0) を c_i - g_i(1) として復元するため，和の等式は自動的に成り立つ。
+    pub fn verify_round<F: Field>(state: &mut VerifierState<F>, msg: &[F]) -> Result<(), Error> {
+        let round = state.rounds_done;
+        if round >= state.num_vars {
+            return Err(Error::Round { round, kind: RoundError::TooManyRounds });
+        }
+        let evals = state.form.decode(msg, state.current_sum);
+        if evals.len() < 2 {
+            return Err(Error::Round { round, kind: RoundError::InvalidLength(evals.len()) });
+        }
+        if evals.len() > state.max_degree + 1 {
+            let kind = RoundError::DegreeBoundExceeded { degree: evals.len() - 1, bound: state.max_degree };
+            return Err(Error::Round { round, kind });
+        }
+        if evals[0] + evals[1] != state.current_sum {
+            return Err(Error::Round { round, kind: RoundError::SumMismatch });
+        }
+        state.last_msg = Some(evals);
         Ok(())
     }
 
     /// Sum-check の最終検証を行い、サブクレーム（ランダム点と期待値）を生成
+    #[derive(Clone, Debug, PartialEq, Eq)]
     pub struct Subclaim<F: Field> {
+        /// 検証側のチャレンジ (r_1, ..., r_l)
         pub point: Vec<F>,
+        /// 最後のラウンド多項式の r_l での値（P(r_1, ..., r_l) と一致すべき値）
         pub expected_value: F,
     }
-    pub fn finalize<F: Field>(state: VerifierState<F>, claimed_sum: F) -> Result<Subclaim<F>, &'static str> {
-        if state.current_sum == claimed_sum {
-            Ok(Subclaim { point: vec![F::one(); state.num_vars], expected_value: claimed_sum })
+    pub fn finalize<F: Field>(state: VerifierState<F>) -> Result<Subclaim<F>, Error> {
+        if state.rounds_done == state.num_vars {
+            Ok(Subclaim { point: state.challenges, expected_value: state.current_sum })
         } else {
-            Err("Final sum-check failed")
+            Err(Error::LengthMismatch { what: "sum-check rounds", expected: state.num_vars, found: state.rounds_done })
+        }
+    }
+
+    /// 複数の主張をまとめた sum-check の証明
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct BatchProof<F: Field> {
+        /// 結合した多項式 Σ_k ρ_k·Π_j P_{k,j} の各ラウンドの 1, ..., d での値（`MessageForm::default()`）
+        pub msgs: Vec<Vec<F>>,
+        /// 主張ごとの，各因子の最終点での値 P_{k,j}(r)
+        pub final_evals: Vec<Vec<F>>,
+    }
+
+    /// 結合した sum-check の検証結果：最終点と，主張ごとの因子の値（呼び出し側がオラクルで確かめる）
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct BatchSubclaim<F: Field> {
+        pub point: Vec<F>,
+        pub final_evals: Vec<Vec<F>>,
+    }
+
+    /// k 個の主張 Σ_x Π_j P_{k,j}(x)（変数の数は共通）を 1 つの sum-check で証明する
+    ///
+    /// 各主張の総和を transcript に吸収してから重み ρ_k を引き，Σ_k ρ_k·Π_j P_{k,j} について
+    /// sum-check を行う。証明サイズと検証のラウンド数は k ではなく変数の数 l に比例する。
+    /// 主張ごとの総和と証明を返す。
+    pub fn batch_prove<F: PrimeField>(claims: Vec<Vec<DenseMLE<F>>>, transcript: &mut Transcript) -> (Vec<F>, BatchProof<F>) {
+        assert!(!claims.is_empty(), "batch must contain at least one claim");
+        let mut states: Vec<ProverState<F>> = claims.into_iter().map(prover_init).collect();
+        let num_vars = states[0].num_vars;
+        assert!(states.iter().all(|s| s.num_vars == num_vars), "num_vars mismatch between claims");
+        let sums: Vec<F> = states.iter().map(|s| s.current_sum).collect();
+        transcript.append_fields(b"claimed_sums", &sums);
+        let coeffs: Vec<F> = sums.iter().map(|_| transcript.challenge_field(b"batch_coeff")).collect();
+        let degree = states.iter().map(|s| s.tables.len()).max().unwrap();
+
+        let mut msgs = Vec::with_capacity(num_vars);
+        for _ in 0..num_vars {
+            let mut evals = vec![F::zero(); degree + 1];
+            for (state, c) in states.iter().zip(coeffs.iter()) {
+                for (m, e) in evals.iter_mut().zip(round_evaluations_up_to(&state.tables, degree)) {
+                    *m += e * c;
+                }
+            }
+            let msg = MessageForm::default().encode(evals);
+            transcript.append(labels::SUMCHECK_ROUND, &msg);
+            msgs.push(msg);
+            let r: F = transcript.squeeze(labels::SUMCHECK_CHALLENGE);
+            for state in states.iter_mut() {
+                fix_first_variable_batch(&mut state.tables, r);
+                state.num_vars -= 1;
+            }
+        }
+        let final_evals: Vec<Vec<F>> =
+            states.iter().map(|s| s.tables.iter().map(|t| t.evaluations[0]).collect()).collect();
+        for evals in final_evals.iter() {
+            transcript.append(labels::SUMCHECK_FINAL_EVALS, evals);
+        }
+        (sums, BatchProof { msgs, final_evals })
+    }
+
+    /// `batch_prove` の証明を検証する。`degree` は各主張の因子の数の最大値
+    pub fn batch_verify<F: PrimeField>(
+        num_vars: usize,
+        degree: usize,
+        claimed_sums: &[F],
+        proof: &BatchProof<F>,
+        transcript: &mut Transcript,
+    ) -> Result<BatchSubclaim<F>, Error> {
+        if proof.final_evals.len() != claimed_sums.len() {
+            return Err(Error::LengthMismatch {
+                what: "batched claims",
+                expected: claimed_sums.len(),
+                found: proof.final_evals.len(),
+            });
+        }
+        if proof.msgs.len() != num_vars {
+            return Err(Error::LengthMismatch { what: "round messages", expected: num_vars, found: proof.msgs.len() });
+        }
+        transcript.append_fields(b"claimed_sums", claimed_sums);
+        let coeffs: Vec<F> = claimed_sums.iter().map(|_| transcript.challenge_field(b"batch_coeff")).collect();
+        let combined = claimed_sums.iter().zip(coeffs.iter()).map(|(s, c)| *s * c).sum();
+
+        let mut state = verifier_init(num_vars, degree, combined);
+        for msg in proof.msgs.iter() {
+            verify_round(&mut state, msg)?;
+            transcript.append(labels::SUMCHECK_ROUND, msg);
+            let r: F = transcript.squeeze(labels::SUMCHECK_CHALLENGE);
+            apply_challenge_verifier(&mut state, r);
+        }
+        let subclaim = finalize(state)?;
+        let at_point: F = proof
+            .final_evals
+            .iter()
+            .zip(coeffs.iter())
+            .map(|(evals, c)| evals.iter().product::<F>() * c)
+            .sum();
+        if at_point != subclaim.expected_value {
+            return Err(Error::EvaluationMismatch("combination of the final evaluations"));
+        }
+        for evals in proof.final_evals.iter() {
+            transcript.append(labels::SUMCHECK_FINAL_EVALS, evals);
+        }
+        Ok(BatchSubclaim { point: subclaim.point, final_evals: proof.final_evals.clone() })
+    }
+
+    /// マスク多項式 g(x) = Σ_i g_i(x_i)（g_i は d 次の 1 変数多項式）で隠した sum-check の証明（Libra）
+    ///
+    /// g の係数は (i, j) を添字とする表 C（i が上位 ⌈log2 n⌉ ビット，j が下位 ⌈log2 (d + 1)⌉ ビット）としてコミットする。
+    /// g_i(r) = Π_s (1 + r^{2^s})·C(i, p(r))（p(r)_s = r^{2^s} / (1 + r^{2^s})）なので，g(r) は C の n 点の開示で求まる。
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct ZkProof<F: Field, C, O> {
+        pub mask_commitment: C,
+        /// Σ_{b ∈ {0,1}^n} g(b)
+        pub mask_sum: F,
+        /// f + ρ·g のラウンドメッセージ（1, ..., d での値，`MessageForm::default()`）
+        pub msgs: Vec<Vec<F>>,
+        /// C(i, p(r_i))
+        pub mask_evals: Vec<F>,
+        pub mask_openings: Vec<O>,
+    }
+
+    /// g_i(r_i) を C の開示から求めるための点 (i, p(r_i)) と倍率 Π_s (1 + r_i^{2^s})
+    ///
+    /// 1 + r_i^{2^s} = 0 となるチャレンジ（確率は無視できる）では `None`。
+    fn mask_point<F: Field>(i: usize, row_vars: usize, col_vars: usize, r: F) -> Option<(Vec<F>, F)> {
+        let (col, scale) = power_point(r, col_vars)?;
+        let point = (0..row_vars).rev().map(|t| F::from(((i >> t) & 1) as u64)).chain(col).collect();
+        Some((point, scale))
+    }
+
+    /// 係数の表 c（2^k 個，添字 j = r^j の係数）の MLE を評価すると Σ_j c_j·r^j / scale になる点と scale
+    ///
+    /// 点は p_s = r^{2^s} / (1 + r^{2^s})（s = k - 1, ..., 0 の順），scale = Π_s (1 + r^{2^s})。
+    /// 1 + r^{2^s} = 0 のときは `None`。
+    pub fn power_point<F: Field>(r: F, num_vars: usize) -> Option<(Vec<F>, F)> {
+        let mut scale = F::one();
+        let mut power = r;
+        let mut point = Vec::with_capacity(num_vars);
+        for _ in 0..num_vars {
+            let denom = F::one() + power;
+            point.push(power * denom.inverse()?);
+            scale *= denom;
+            power.square_in_place();
+        }
+        point.reverse();
+        Some((point, scale))
+    }
+
+    /// マスクの係数の表の変数数（行，列）
+    fn mask_shape(num_vars: usize, degree: usize) -> (usize, usize) {
+        let bits = |n: usize| n.max(1).next_power_of_two().trailing_zeros() as usize;
+        (bits(num_vars), bits(degree + 1))
+    }
+
+    /// 1 変数多項式（係数表現）の r での値
+    fn horner<F: Field>(coeffs: &[F], r: F) -> F {
+        coeffs.iter().rev().fold(F::zero(), |acc, c| acc * r + c)
+    }
+
+    /// 2^m / 2（m = 0 のときは使わない）
+    fn half_power<F: Field>(m: usize) -> F {
+        F::from(2u64).pow([m as u64]) * F::from(2u64).inverse().unwrap()
+    }
+
+    /// Σ_x Π_j P_j(x) のゼロ知識版の sum-check
+    ///
+    /// ランダムなマスク g をコミットし，その総和 G を送ってから ρ を引いて，f + ρ·g について
+    /// sum-check を行う。主張する総和は呼び出し側が transcript に吸収しておく。
+    /// 最後の点 r では g(r) を開示し，検証側は f(r) の主張を得る（f(r) そのものはオラクルで確かめる）。
+    /// 完全なゼロ知識には開示の証明も何も漏らさない（hiding な）PCS が要る。
+    /// 証明，チャレンジ列 r，全ての変数を束縛した状態を返す。
+    #[allow(clippy::type_complexity)]
+    pub fn prove_zk<F: PrimeField, P: MultilinearPCS<F>, R: Rng>(
+        pp: &P::ProverParam,
+        tables: Vec<DenseMLE<F>>,
+        rng: &mut R,
+        transcript: &mut Transcript,
+    ) -> (ZkProof<F, P::Commitment, P::Proof>, Vec<F>, ProverState<F>) {
+        let mut state = prover_init(tables);
+        let (n, d) = (state.num_vars, state.degree());
+        let (proof, point) = prove_zk_with::<F, P, R>(
+            pp,
+            n,
+            d,
+            |_, r| {
+                if let Some(r) = r {
+                    apply_challenge(&mut state, r);
+                }
+                current_round_evaluations(&mut state)
+            },
+            rng,
+            transcript,
+        );
+        if let Some(&r) = point.last() {
+            apply_challenge(&mut state, r);
+        }
+        (proof, point, state)
+    }
+
+    /// `prove_zk` の一般形：k ラウンド目のマスクなしのメッセージ（0, 1, ..., `degree` での値）を
+    /// `round(k, 前のラウンドのチャレンジ)` で作る。積の形でない和（途中で次数の上がる項を持つものなど）にも使える。
+    /// 最後のチャレンジは返す点の末尾にだけ現れる。
+    pub fn prove_zk_with<F: PrimeField, P: MultilinearPCS<F>, R: Rng>(
+        pp: &P::ProverParam,
+        num_vars: usize,
+        degree: usize,
+        mut round: impl FnMut(usize, Option<F>) -> Vec<F>,
+        rng: &mut R,
+        transcript: &mut Transcript,
+    ) -> (ZkProof<F, P::Commitment, P::Proof>, Vec<F>) {
+        let (n, d) = (num_vars, degree);
+        let coeffs: Vec<Vec<F>> = (0..n).map(|_| (0..=d).map(|_| F::rand(rng)).collect()).collect();
+        let (row_vars, col_vars) = mask_shape(n, d);
+        let mut table = vec![F::zero(); 1 << (row_vars + col_vars)];
+        for (i, c) in coeffs.iter().enumerate() {
+            table[i << col_vars..][..=d].copy_from_slice(c);
+        }
+        let mask = DenseMLE::from_evaluations_vec(row_vars + col_vars, table);
+        let mask_commitment = P::commit(pp, &mask);
+        // g_i(0) + g_i(1)
+        let boolean_sums: Vec<F> = coeffs.iter().map(|c| c[0] + c.iter().sum::<F>()).collect();
+        let mask_sum = if n == 0 { F::zero() } else { half_power::<F>(n) * boolean_sums.iter().sum::<F>() };
+        transcript.append_message(b"mask_commitment", &P::commitment_bytes(&mask_commitment));
+        transcript.append_field(b"mask_sum", &mask_sum);
+        let rho: F = transcript.challenge_field(b"mask_coeff");
+
+        let mut msgs = Vec::with_capacity(n);
+        let mut point: Vec<F> = Vec::with_capacity(n);
+        // Σ_{i<k} g_i(r_i) と Σ_{i>k} (g_i(0) + g_i(1))
+        let mut fixed = F::zero();
+        let mut rest: F = boolean_sums.iter().sum();
+        for (k, c) in coeffs.iter().enumerate() {
+            rest -= boolean_sums[k];
+            let m = n - k - 1;
+            let rest_part = if m == 0 { F::zero() } else { half_power::<F>(m) * rest };
+            let scale = F::from(2u64).pow([m as u64]);
+            let evals = round(k, point.last().copied());
+            assert_eq!(evals.len(), d + 1, "round message does not match the degree");
+            let masked: Vec<F> = evals
+                .into_iter()
+                .enumerate()
+                .map(|(t, e)| e + rho * (scale * (fixed + horner(c, F::from(t as u64))) + rest_part))
+                .collect();
+            let msg = MessageForm::default().encode(masked);
+            transcript.append(labels::SUMCHECK_ROUND, &msg);
+            msgs.push(msg);
+            let r: F = transcript.squeeze(labels::SUMCHECK_CHALLENGE);
+            fixed += horner(c, r);
+            point.push(r);
+        }
+
+        let (mask_evals, mask_openings) = point
+            .iter()
+            .enumerate()
+            .map(|(i, r)| {
+                let (p, _) = mask_point(i, row_vars, col_vars, *r).expect("challenge makes the mask unopenable");
+                P::open(pp, &mask, &p)
+            })
+            .unzip();
+        let proof = ZkProof { mask_commitment, mask_sum, msgs, mask_evals, mask_openings };
+        transcript.append_fields(b"mask_evals", &proof.mask_evals);
+        (proof, point)
+    }
+
+    /// `prove_zk` の証明を検証し，f(r) についてのサブクレームを返す（g(r) の分は開示で確かめて取り除く）
+    pub fn verify_zk<F: PrimeField, P: MultilinearPCS<F>>(
+        vp: &P::VerifierParam,
+        num_vars: usize,
+        max_degree: usize,
+        claimed_sum: F,
+        proof: &ZkProof<F, P::Commitment, P::Proof>,
+        transcript: &mut Transcript,
+    ) -> Result<Subclaim<F>, Error> {
+        let n = num_vars;
+        if proof.msgs.len() != n {
+            return Err(Error::LengthMismatch { what: "round messages", expected: n, found: proof.msgs.len() });
+        }
+        if proof.mask_evals.len() != n {
+            return Err(Error::LengthMismatch { what: "mask evaluations", expected: n, found: proof.mask_evals.len() });
+        }
+        if proof.mask_openings.len() != n {
+            return Err(Error::LengthMismatch { what: "mask openings", expected: n, found: proof.mask_openings.len() });
+        }
+        transcript.append_message(b"mask_commitment", &P::commitment_bytes(&proof.mask_commitment));
+        transcript.append_field(b"mask_sum", &proof.mask_sum);
+        let rho: F = transcript.challenge_field(b"mask_coeff");
+
+        let mut state = verifier_init(n, max_degree, claimed_sum + rho * proof.mask_sum);
+        for msg in proof.msgs.iter() {
+            verify_round(&mut state, msg)?;
+            transcript.append(labels::SUMCHECK_ROUND, msg);
+            let r: F = transcript.squeeze(labels::SUMCHECK_CHALLENGE);
+            apply_challenge_verifier(&mut state, r);
+        }
+        let subclaim = finalize(state)?;
+
+        let (row_vars, col_vars) = mask_shape(n, max_degree);
+        let mut mask_at_point = F::zero();
+        for (i, ((r, value), opening)) in
+            subclaim.point.iter().zip(proof.mask_evals.iter()).zip(proof.mask_openings.iter()).enumerate()
+        {
+            let (p, scale) = mask_point(i, row_vars, col_vars, *r)
+                .ok_or(Error::Transcript("challenge makes the mask unopenable"))?;
+            P::verify(vp, &proof.mask_commitment, &p, *value, opening)?;
+            mask_at_point += scale * value;
         }
+        transcript.append_fields(b"mask_evals", &proof.mask_evals);
+        Ok(Subclaim { point: subclaim.point, expected_value: subclaim.expected_value - rho * mask_at_point })
     }
 }
@@ -0,0 +1,278 @@
+// src/transcript.rs
+//
+// `TranscriptConfig::Keccak256` の吸収の符号化（Solidity の検証器が同じチャレンジを導出できるように固定する）。
+// 状態は 32 バイトの s（初期値は 0）で，吸収のたびに s ← keccak256(s ‖ tag ‖ 本体) と置き換える。
+// 長さはすべて 8 バイトのビッグエンディアン（be64）。
+//
+//   初期化          : append_message("protocol", プロトコル名)
+//   バイト列         : tag 0x00 ‖ be64(|label|) ‖ label ‖ be64(|m|) ‖ m
+//   体の元の列       : tag 0x01 ‖ be64(|label|) ‖ label ‖ be64(n) ‖ x_1 ‖ ... ‖ x_n
+//                     （各元は正準形のビッグエンディアンを 32 バイトに左詰めで 0 埋めしたもの）
+//   チャレンジの候補 : h_b = keccak256(s ‖ 0x02 ‖ be64(|label|) ‖ label ‖ be64(counter) ‖ b)（b = 0, 1，状態は変えない）
+//                     r = (h_0 · 2^256 + h_1) mod p。引いた r は体の元の列（長さ 1）として同じラベルで吸収する
+//   32 バイトのチャレンジ : h_0 をそのまま返し，バイト列として同じラベルで吸収する
+
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::{BigInteger, PrimeField};
+use sha3::{Digest, Keccak256, Sha3_256};
+use std::any::Any;
+
+use crate::challenge::ChallengeSampler;
+use crate::poseidon::PoseidonSponge;
+use crate::serialization::{write_field, Endianness};
+
+/// 非対話版（Fiat–Shamir）の prover/verifier が使う transcript
+pub type FiatShamirTranscript = Transcript;
+
+/// プロトコルのメッセージごとのラベル
+///
+/// フェーズ，層，最終点での値などメッセージの種類ごとに別のラベルで吸収するので，
+/// 他の証明系と同じ transcript を共有しても，あるメッセージを別の種類のメッセージとして読み替えられない。
+pub mod labels {
+    /// 主張する総和
+    pub const CLAIMED_SUM: &[u8] = b"claimed_sum";
+    /// Linear GKR の Phase 1（x の変数）のラウンドメッセージとチャレンジ
+    pub const PHASE1_ROUND: &[u8] = b"phase1_round";
+    pub const PHASE1_CHALLENGE: &[u8] = b"phase1_challenge";
+    /// Linear GKR の Phase 2（y の変数）のラウンドメッセージとチャレンジ
+    pub const PHASE2_ROUND: &[u8] = b"phase2_round";
+    pub const PHASE2_CHALLENGE: &[u8] = b"phase2_challenge";
+    /// Linear GKR の最終点での値 f1(g,u,v), f2(u), f3(v)
+    pub const FINAL_EVALS: &[u8] = b"final_evals";
+    /// ゼロ知識版でフェーズの区切りに吸収するフェーズ番号
+    pub const PHASE: &[u8] = b"phase";
+    /// k フェーズの sum-check（`fan_in`）の最終点での値
+    pub const FAN_IN_FINAL_EVALS: &[u8] = b"fan_in_final_evals";
+    /// 汎用の sum-check（`sumcheck`, `virtual_poly`）のラウンドメッセージ，チャレンジ，最終点での値
+    pub const SUMCHECK_ROUND: &[u8] = b"sumcheck_round";
+    pub const SUMCHECK_CHALLENGE: &[u8] = b"sumcheck_challenge";
+    pub const SUMCHECK_FINAL_EVALS: &[u8] = b"sumcheck_final_evals";
+    /// 回路の証明で，各層の還元の前に吸収する層の添字（出力層が 0）
+    pub const LAYER: &[u8] = b"layer";
+
+    /// j 番目（0 始まり）のフェーズのラウンドメッセージのラベル（j = 0, 1 は `PHASE1_ROUND`, `PHASE2_ROUND`）
+    pub fn phase_round(j: usize) -> Vec<u8> {
+        format!("phase{}_round", j + 1).into_bytes()
+    }
+
+    /// j 番目（0 始まり）のフェーズのチャレンジのラベル
+    pub fn phase_challenge(j: usize) -> Vec<u8> {
+        format!("phase{}_challenge", j + 1).into_bytes()
+    }
+}
+
+/// transcript のハッシュの選択
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum TranscriptConfig {
+    /// SHA3-256（既定）
+    #[default]
+    Sha3,
+    /// BLS12-381 の Fr 上の Poseidon スポンジ（`poseidon`）
+    ///
+    /// Fr の元は分解せずにそのまま吸収・導出するので，証明の検証を Fr 上の SNARK 回路の中で
+    /// 行うときにビット分解が要らない。他の体の元とバイト列は，Fr の元に詰めて吸収する。
+    Poseidon,
+    /// Keccak-256 のハッシュ連鎖（EVM 上の検証器向け）。符号化はファイル先頭のコメントのとおり
+    Keccak256,
+}
+
+#[derive(Clone)]
+enum State {
+    Sha3(Sha3_256),
+    Poseidon(PoseidonSponge<ScalarField>),
+    Keccak256([u8; 32]),
+}
+
+/// Fiat–Shamir 変換用のハッシュ transcript
+///
+/// 吸収したメッセージ列（ラベルと長さ付き）をハッシュの状態に積み上げ，
+/// チャレンジはその状態から導出する。導出したチャレンジも吸収するので，
+/// 同じラベルで続けて引いても異なる値になる。ハッシュは `TranscriptConfig` で選ぶ。
+#[derive(Clone)]
+pub struct Transcript {
+    state: State,
+}
+
+impl Transcript {
+    /// プロトコル名 `label` で初期化する（SHA3-256）
+    pub fn new(label: &[u8]) -> Self {
+        Self::with_config(label, TranscriptConfig::default())
+    }
+
+    /// プロトコル名 `label` で，`config` のハッシュを使って初期化する
+    pub fn with_config(label: &[u8], config: TranscriptConfig) -> Self {
+        let state = match config {
+            TranscriptConfig::Sha3 => State::Sha3(Sha3_256::new()),
+            TranscriptConfig::Poseidon => State::Poseidon(PoseidonSponge::new()),
+            TranscriptConfig::Keccak256 => State::Keccak256([0; 32]),
+        };
+        let mut transcript = Transcript { state };
+        transcript.append_message(b"protocol", label);
+        transcript
+    }
+
+    pub fn config(&self) -> TranscriptConfig {
+        match self.state {
+            State::Sha3(_) => TranscriptConfig::Sha3,
+            State::Poseidon(_) => TranscriptConfig::Poseidon,
+            State::Keccak256(_) => TranscriptConfig::Keccak256,
+        }
+    }
+
+    /// ラベル付きのバイト列を吸収する
+    pub fn append_message(&mut self, label: &[u8], message: &[u8]) {
+        match &mut self.state {
+            State::Sha3(state) => {
+                state.update((label.len() as u64).to_le_bytes());
+                state.update(label);
+                state.update((message.len() as u64).to_le_bytes());
+                state.update(message);
+            }
+            State::Poseidon(sponge) => {
+                sponge.absorb(ScalarField::from(0u64));
+                sponge.absorb_bytes(label);
+                sponge.absorb_bytes(message);
+            }
+            State::Keccak256(state) => *state = keccak_chain(state, 0x00, label, message.len() as u64, message),
+        }
+    }
+
+    /// 体の元を正準な little-endian 表現で吸収する
+    pub fn append_field<F: PrimeField>(&mut self, label: &[u8], x: &F) {
+        self.append_fields(label, std::slice::from_ref(x));
+    }
+
+    /// 体の元の列を吸収する（長さも含めて吸収される）
+    ///
+    /// Poseidon で F が Fr のときは，各元をそのまま 1 要素として吸収する。
+    pub fn append_fields<F: PrimeField>(&mut self, label: &[u8], xs: &[F]) {
+        if let State::Poseidon(sponge) = &mut self.state {
+            if let Some(xs) = as_scalars(xs) {
+                sponge.absorb(ScalarField::from(1u64));
+                sponge.absorb_bytes(label);
+                sponge.absorb(ScalarField::from(xs.len() as u64));
+                xs.iter().for_each(|x| sponge.absorb(*x));
+                return;
+            }
+        }
+        if let State::Keccak256(state) = &mut self.state {
+            let mut bytes = Vec::with_capacity(32 * xs.len());
+            for x in xs {
+                let mut be = Vec::new();
+                write_field(&mut be, x, Endianness::Big);
+                bytes.resize(bytes.len() + 32usize.saturating_sub(be.len()), 0);
+                bytes.extend_from_slice(&be);
+            }
+            *state = keccak_chain(state, 0x01, label, xs.len() as u64, &bytes);
+            return;
+        }
+        let mut bytes = Vec::new();
+        for x in xs {
+            write_field(&mut bytes, x, Endianness::Little);
+        }
+        self.append_message(label, &bytes);
+    }
+
+    /// 現在の状態から 32 バイトを導出する（状態は変えない）
+    fn derive(&self, label: &[u8], counter: u64, block: u8) -> [u8; 32] {
+        match &self.state {
+            State::Sha3(state) => {
+                let mut hasher = state.clone();
+                hasher.update(b"challenge");
+                hasher.update((label.len() as u64).to_le_bytes());
+                hasher.update(label);
+                hasher.update(counter.to_le_bytes());
+                hasher.update([block]);
+                hasher.finalize().into()
+            }
+            State::Keccak256(state) => keccak_chain(state, 0x02, label, counter, &[block]),
+            State::Poseidon(sponge) => {
+                let mut out = [0u8; 32];
+                let bytes = derive_scalar(sponge, label, counter, block).into_bigint().to_bytes_le();
+                out[..bytes.len()].copy_from_slice(&bytes);
+                out
+            }
+        }
+    }
+
+    /// 試行カウンタ `counter` に対するチャレンジの候補（状態は変えない）
+    ///
+    /// 偏りを抑えるため 64 バイトを導出して法で還元する。Poseidon で F が Fr のときは絞り出した元をそのまま返す。
+    pub fn challenge_candidate<F: PrimeField>(&self, label: &[u8], counter: u64) -> F {
+        if let State::Poseidon(sponge) = &self.state {
+            let r = derive_scalar(sponge, label, counter, 0);
+            if let Some(r) = (&r as &dyn Any).downcast_ref::<F>() {
+                return *r;
+            }
+        }
+        let mut bytes = self.derive(label, counter, 0).to_vec();
+        bytes.extend_from_slice(&self.derive(label, counter, 1));
+        if let State::Keccak256(_) = self.state {
+            return F::from_be_bytes_mod_order(&bytes);
+        }
+        F::from_le_bytes_mod_order(&bytes)
+    }
+
+    /// チャレンジを導出し，それを吸収する
+    pub fn challenge_field<F: PrimeField>(&mut self, label: &[u8]) -> F {
+        let r = self.challenge_candidate(label, 0);
+        self.append_field(label, &r);
+        r
+    }
+
+    /// `sampler` のポリシーで拒否されなくなるまでカウンタを進めてチャレンジを導出し，それを吸収する
+    pub fn challenge_with<F: PrimeField>(&mut self, label: &[u8], sampler: &mut ChallengeSampler<F>) -> F {
+        let r = sampler.sample(|counter| self.challenge_candidate(label, counter));
+        self.append_field(label, &r);
+        r
+    }
+
+    /// プロトコルのメッセージ（体の元の列）をラベル付きで吸収する。ラベルは `labels` のものを使う
+    pub fn append<F: PrimeField>(&mut self, label: &[u8], msg: &[F]) {
+        self.append_fields(label, msg);
+    }
+
+    /// ラベル付きでチャレンジを 1 つ絞り出す（`challenge_field` と同じ）
+    pub fn squeeze<F: PrimeField>(&mut self, label: &[u8]) -> F {
+        self.challenge_field(label)
+    }
+
+    /// 回路の i 番目の層の還元を始める（層の添字を吸収する）
+    pub fn begin_layer(&mut self, i: usize) {
+        self.append_message(labels::LAYER, &(i as u64).to_le_bytes());
+    }
+
+    /// 32 バイトのチャレンジを導出し，それを吸収する
+    pub fn challenge_bytes(&mut self, label: &[u8]) -> [u8; 32] {
+        let bytes = self.derive(label, 0, 0);
+        self.append_message(label, &bytes);
+        bytes
+    }
+}
+
+/// keccak256(state ‖ tag ‖ be64(|label|) ‖ label ‖ be64(n) ‖ body)
+fn keccak_chain(state: &[u8; 32], tag: u8, label: &[u8], n: u64, body: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(state);
+    hasher.update([tag]);
+    hasher.update((label.len() as u64).to_be_bytes());
+    hasher.update(label);
+    hasher.update(n.to_be_bytes());
+    hasher.update(body);
+    hasher.finalize().into()
+}
+
+/// スポンジの複製にラベル，カウンタ，ブロック番号を吸収して 1 要素を絞り出す
+fn derive_scalar(sponge: &PoseidonSponge<ScalarField>, label: &[u8], counter: u64, block: u8) -> ScalarField {
+    let mut sponge = sponge.clone();
+    sponge.absorb(ScalarField::from(2u64));
+    sponge.absorb_bytes(label);
+    sponge.absorb(ScalarField::from(counter));
+    sponge.absorb(ScalarField::from(block as u64));
+    sponge.squeeze()
+}
+
+/// F が Fr のとき，元の列を Fr の列として返す
+fn as_scalars<F: PrimeField>(xs: &[F]) -> Option<Vec<ScalarField>> {
+    xs.iter().map(|x| (x as &dyn Any).downcast_ref::<ScalarField>().copied()).collect()
+}
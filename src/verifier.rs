@@ -1,62 +1,284 @@
 // src/verifier.rs
 
 use ark_bls12_381::Fr as ScalarField;
-use rand::Rng;
-use crate::sumcheck::protocol;
-use crate::prover::LinearGKRProof;
+use ark_ff::PrimeField;
+use std::marker::PhantomData;
+use crate::challenge::ChallengeGenerator;
+use crate::error::{Error, RoundError};
+use crate::sumcheck::{barycentric_evaluate, protocol, MessageForm};
+use crate::ml_extension::{DenseMLE, SparseMLE};
+use crate::pcs::MultilinearPCS;
+use crate::prover::{LinearGKRProof, ZkLinearGKRProof, FIAT_SHAMIR_LABEL};
+use crate::statement::Statement;
+use crate::transcript::{labels, FiatShamirTranscript, Transcript};
+use rand::{CryptoRng, RngCore};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 /// Linear GKR のサブクレーム。これを次層への入力または最終検証に利用する。
-pub struct LinearGKRSubclaim {
-    pub u: Vec<ScalarField>,
-    pub v: Vec<ScalarField>,
-    pub expected_value: ScalarField,
+///
+/// `f1_at_guv`, `f2_at_u`, `f3_at_v` は Prover の主張で，積が `expected_value` に一致することは確認済み。
+/// 各値が実際の f1, f2, f3 の評価に等しいかはオラクル（またはコミットメントの開示）で確かめる。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinearGKRSubclaim<F: PrimeField = ScalarField> {
+    pub u: Vec<F>,
+    pub v: Vec<F>,
+    pub expected_value: F,
+    pub f1_at_guv: F,
+    pub f2_at_u: F,
+    pub f3_at_v: F,
 }
 
-/// Linear GKR Verifier
-pub struct LinearGKRVerifier;
+impl<F: PrimeField> LinearGKRSubclaim<F> {
+    /// 平文の f1, f2, f3 を (g, u, v), u, v で評価して最終検査を行う
+    ///
+    /// f1 の変数は z, y, x の順なので，点 (g, v, u) で評価する。
+    pub fn verify_against(&self, f1: &SparseMLE<F>, f2: &DenseMLE<F>, f3: &DenseMLE<F>, g: &[F]) -> Result<(), Error> {
+        self.verify_with_oracles(
+            g,
+            |g, u, v| f1.evaluate(&[g, v, u].concat()),
+            |u| f2.evaluate(u),
+            |v| f3.evaluate(v),
+        )
+    }
+
+    /// `verify_against` のオラクル版。各クロージャは f1(g, u, v), f2(u), f3(v) を返す
+    /// （コミットメントの開示を検証してから値を返すなど，MLE が平文で手元にない場合に使う）
+    pub fn verify_with_oracles(
+        &self,
+        g: &[F],
+        f1: impl FnOnce(&[F], &[F], &[F]) -> F,
+        f2: impl FnOnce(&[F]) -> F,
+        f3: impl FnOnce(&[F]) -> F,
+    ) -> Result<(), Error> {
+        if f1(g, &self.u, &self.v) != self.f1_at_guv {
+            return Err(Error::EvaluationMismatch("f1 at (g, u, v)"));
+        }
+        if f2(&self.u) != self.f2_at_u {
+            return Err(Error::EvaluationMismatch("f2 at u"));
+        }
+        if f3(&self.v) != self.f3_at_v {
+            return Err(Error::EvaluationMismatch("f3 at v"));
+        }
+        if self.f1_at_guv * self.f2_at_u * self.f3_at_v != self.expected_value {
+            return Err(Error::EvaluationMismatch("product of the final evaluations"));
+        }
+        Ok(())
+    }
+}
+
+/// 一括検証する非対話版の証明の公開部分（f1, g と主張する総和）
+#[derive(Clone, Copy)]
+pub struct LinearGKRClaim<'a, F: PrimeField = ScalarField> {
+    pub f1: &'a SparseMLE<F>,
+    pub g: &'a [F],
+    pub claimed_sum: F,
+}
 
-impl LinearGKRVerifier {
+/// Linear GKR Verifier（任意の素体 F 上で動く）
+pub struct LinearGKRVerifier<F: PrimeField = ScalarField>(PhantomData<F>);
+
+impl<F: PrimeField> LinearGKRVerifier<F> {
     /// f2_num_vars: f2（および f3）の変数数（l）
-    /// claimed_sum: Phase1 で Prover が主張した総和
+    /// claimed_sum: Phase1 で Prover が主張した総和（証明に埋め込まれた値と一致しなければ拒否する）
     /// proof: Prover からの Linear GKR 証明
-    pub fn verify<R: Rng>(
+    /// challenges: チャレンジの出どころ。Fiat–Shamir なら Prover と共有する transcript（Prover と同じ状態から始めること），
+    /// 対話版なら乱数生成器
+    pub fn verify<C: ChallengeGenerator<F>>(
         f2_num_vars: usize,
-        claimed_sum: ScalarField,
-        proof: &LinearGKRProof,
-        _rng: &mut R,
-    ) -> Result<LinearGKRSubclaim, &'static str> {
+        claimed_sum: F,
+        proof: &LinearGKRProof<F>,
+        challenges: &mut C,
+    ) -> Result<LinearGKRSubclaim<F>, Error> {
         let l = f2_num_vars;
-        if proof.phase1_msgs.len() != l || proof.phase2_msgs.len() != l {
-            return Err("Invalid proof length");
+        if proof.claimed_sum != claimed_sum {
+            return Err(Error::EvaluationMismatch("claimed sum"));
+        }
+        for msgs in [&proof.phase1_msgs, &proof.phase2_msgs] {
+            if msgs.len() != l {
+                return Err(Error::LengthMismatch { what: "round messages", expected: l, found: msgs.len() });
+            }
         }
 
         // ── Phase 1 の検証 ──
-        let mut verifier_state1 = protocol::verifier_init(l, claimed_sum);
-        let mut u = Vec::with_capacity(l);
+        // 各フェーズのラウンド多項式は 2 つの MLE の積なので 2 次
+        let mut verifier_state1 = protocol::verifier_init(l, 2, claimed_sum);
+        challenges.absorb(labels::CLAIMED_SUM, &[claimed_sum]);
         for msg in proof.phase1_msgs.iter() {
             protocol::verify_round(&mut verifier_state1, msg)?;
-            // ダミーの乱数生成（実際は Fiat–Shamir などで生成）
-            let r_i: ScalarField = crate::sumcheck::get_r().unwrap();
-            u.push(r_i);
+            // Prover と同じ順でメッセージを吸収してからチャレンジを引く
+            challenges.absorb(labels::PHASE1_ROUND, msg);
+            let r_i = challenges.challenge(labels::PHASE1_CHALLENGE);
             protocol::apply_challenge_verifier(&mut verifier_state1, r_i);
         }
-        let subclaim1 = protocol::finalize(verifier_state1, claimed_sum)?;
+        let subclaim1 = protocol::finalize(verifier_state1)?;
         let u_point = subclaim1.point;
         let expected_phase1_val = subclaim1.expected_value;
 
         // ── Phase 2 の検証 ──
-        let mut verifier_state2 = protocol::verifier_init(l, expected_phase1_val);
-        let mut v = Vec::with_capacity(l);
+        let mut verifier_state2 = protocol::verifier_init(l, 2, expected_phase1_val);
         for msg in proof.phase2_msgs.iter() {
             protocol::verify_round(&mut verifier_state2, msg)?;
-            let r_j: ScalarField = crate::sumcheck::get_r().unwrap();
-            v.push(r_j);
+            challenges.absorb(labels::PHASE2_ROUND, msg);
+            let r_j = challenges.challenge(labels::PHASE2_CHALLENGE);
             protocol::apply_challenge_verifier(&mut verifier_state2, r_j);
         }
-        let subclaim2 = protocol::finalize(verifier_state2, expected_phase1_val)?;
+        let subclaim2 = protocol::finalize(verifier_state2)?;
         let v_point = subclaim2.point;
         let expected_phase2_val = subclaim2.expected_value;
 
-        Ok(LinearGKRSubclaim { u: u_point, v: v_point, expected_value: expected_phase2_val })
+        // 最終ラウンドの値と Prover の主張する各評価値の積が一致するか
+        let (f1_at_guv, f2_at_u, f3_at_v) = (proof.f1_at_guv, proof.f2_at_u, proof.f3_at_v);
+        if f1_at_guv * f2_at_u * f3_at_v != expected_phase2_val {
+            return Err(Error::EvaluationMismatch("product of the final evaluations"));
+        }
+        challenges.absorb(labels::FINAL_EVALS, &[f1_at_guv, f2_at_u, f3_at_v]);
+
+        Ok(LinearGKRSubclaim {
+            u: u_point,
+            v: v_point,
+            expected_value: expected_phase2_val,
+            f1_at_guv,
+            f2_at_u,
+            f3_at_v,
+        })
+    }
+
+    /// `LinearGKRProver::prove_zk` の証明を検証する。マスクの開示は `vp` で確かめる
+    pub fn verify_zk<P: MultilinearPCS<F>>(
+        vp: &P::VerifierParam,
+        f2_num_vars: usize,
+        claimed_sum: F,
+        proof: &ZkLinearGKRProof<F, P::Commitment, P::Proof>,
+        transcript: &mut Transcript,
+    ) -> Result<LinearGKRSubclaim<F>, Error> {
+        let l = f2_num_vars;
+        if proof.claimed_sum != claimed_sum {
+            return Err(Error::EvaluationMismatch("claimed sum"));
+        }
+        transcript.append_field(labels::CLAIMED_SUM, &claimed_sum);
+        transcript.append_message(labels::PHASE, &[1]);
+        let subclaim1 = protocol::verify_zk::<F, P>(vp, l, 2, claimed_sum, &proof.phase1, transcript)?;
+        transcript.append_message(labels::PHASE, &[2]);
+        let subclaim2 = protocol::verify_zk::<F, P>(vp, l, 2, subclaim1.expected_value, &proof.phase2, transcript)?;
+
+        let (f1_at_guv, f2_at_u, f3_at_v) = (proof.f1_at_guv, proof.f2_at_u, proof.f3_at_v);
+        if f1_at_guv * f2_at_u * f3_at_v != subclaim2.expected_value {
+            return Err(Error::EvaluationMismatch("product of the final evaluations"));
+        }
+        transcript.append(labels::FINAL_EVALS, &[f1_at_guv, f2_at_u, f3_at_v]);
+
+        Ok(LinearGKRSubclaim {
+            u: subclaim1.point,
+            v: subclaim2.point,
+            expected_value: subclaim2.expected_value,
+            f1_at_guv,
+            f2_at_u,
+            f3_at_v,
+        })
+    }
+
+    /// 非対話版：`LinearGKRProver::prove_noninteractive` と同じく f1 と g を吸収した transcript で検証する
+    pub fn verify_noninteractive(
+        f1: &SparseMLE<F>,
+        g: &[F],
+        claimed_sum: F,
+        proof: &LinearGKRProof<F>,
+    ) -> Result<LinearGKRSubclaim<F>, Error> {
+        let mut transcript = FiatShamirTranscript::for_statement(FIAT_SHAMIR_LABEL, &Statement::for_layer(f1, g));
+        Self::verify(g.len(), claimed_sum, proof, &mut transcript)
+    }
+
+    /// `LinearGKRProver::prove_many` の証明をまとめて検証する
+    ///
+    /// 各証明の transcript をたどって，ラウンドごとの g_i(0) + g_i(1) - c_i と最後の積の差を集め，
+    /// それらのランダムな線形結合が 0 になるかを 1 回だけ確かめる。
+    /// メッセージの数や長さの検査は個別に行い，線形結合が 0 でなければ個別の検証で失敗した証明の理由を返す。
+    pub fn verify_many(
+        claims: &[LinearGKRClaim<F>],
+        proofs: &[LinearGKRProof<F>],
+    ) -> Result<Vec<LinearGKRSubclaim<F>>, Error> {
+        Self::verify_many_with_rng(claims, proofs, &mut rand::thread_rng())
+    }
+
+    /// `verify_many` と同じだが，線形結合の係数を与えた rng から引く
+    pub fn verify_many_with_rng<R: RngCore + CryptoRng>(
+        claims: &[LinearGKRClaim<F>],
+        proofs: &[LinearGKRProof<F>],
+        rng: &mut R,
+    ) -> Result<Vec<LinearGKRSubclaim<F>>, Error> {
+        if proofs.len() != claims.len() {
+            return Err(Error::LengthMismatch { what: "proofs", expected: claims.len(), found: proofs.len() });
+        }
+        let replay = |(claim, proof): (&LinearGKRClaim<F>, &LinearGKRProof<F>)| {
+            let mut transcript =
+                FiatShamirTranscript::for_statement(FIAT_SHAMIR_LABEL, &Statement::for_layer(claim.f1, claim.g));
+            replay_unchecked(claim.g.len(), claim.claimed_sum, proof, &mut transcript)
+        };
+        #[cfg(feature = "parallel")]
+        let replayed: Vec<_> = claims.par_iter().zip(proofs.par_iter()).map(replay).collect::<Result<_, _>>()?;
+        #[cfg(not(feature = "parallel"))]
+        let replayed: Vec<_> = claims.iter().zip(proofs.iter()).map(replay).collect::<Result<_, _>>()?;
+
+        let rho = F::rand(rng);
+        let mut power = F::one();
+        let mut combined = F::zero();
+        for residual in replayed.iter().flat_map(|(residuals, _)| residuals) {
+            combined += power * residual;
+            power *= rho;
+        }
+        if combined.is_zero() {
+            return Ok(replayed.into_iter().map(|(_, subclaim)| subclaim).collect());
+        }
+        for (claim, proof) in claims.iter().zip(proofs) {
+            Self::verify_noninteractive(claim.f1, claim.g, claim.claimed_sum, proof)?;
+        }
+        Err(Error::EvaluationMismatch("batched linear combination"))
+    }
+}
+
+/// `LinearGKRVerifier::verify` と同じ順で transcript をたどるが，和と積の等式は確かめずに差として返す
+fn replay_unchecked<F: PrimeField>(
+    l: usize,
+    claimed_sum: F,
+    proof: &LinearGKRProof<F>,
+    transcript: &mut Transcript,
+) -> Result<(Vec<F>, LinearGKRSubclaim<F>), Error> {
+    if proof.claimed_sum != claimed_sum {
+        return Err(Error::EvaluationMismatch("claimed sum"));
+    }
+    let mut residuals = Vec::with_capacity(2 * l + 1);
+    let mut current = claimed_sum;
+    transcript.append_field(labels::CLAIMED_SUM, &claimed_sum);
+    let mut points = Vec::with_capacity(2);
+    for (phase, msgs) in [&proof.phase1_msgs, &proof.phase2_msgs].into_iter().enumerate() {
+        let (round_label, challenge_label) = (labels::phase_round(phase), labels::phase_challenge(phase));
+        if msgs.len() != l {
+            return Err(Error::LengthMismatch { what: "round messages", expected: l, found: msgs.len() });
+        }
+        let mut point = Vec::with_capacity(l);
+        for (round, msg) in msgs.iter().enumerate() {
+            // 各フェーズのラウンド多項式は 2 次
+            let evals = MessageForm::default().decode(msg, current);
+            if evals.len() < 2 {
+                return Err(Error::Round { round, kind: RoundError::InvalidLength(evals.len()) });
+            }
+            if evals.len() > 3 {
+                let kind = RoundError::DegreeBoundExceeded { degree: evals.len() - 1, bound: 2 };
+                return Err(Error::Round { round, kind });
+            }
+            residuals.push(evals[0] + evals[1] - current);
+            transcript.append(&round_label, msg);
+            let r: F = transcript.squeeze(&challenge_label);
+            current = barycentric_evaluate(&evals, r);
+            point.push(r);
+        }
+        points.push(point);
     }
+    let (f1_at_guv, f2_at_u, f3_at_v) = (proof.f1_at_guv, proof.f2_at_u, proof.f3_at_v);
+    residuals.push(f1_at_guv * f2_at_u * f3_at_v - current);
+    transcript.append(labels::FINAL_EVALS, &[f1_at_guv, f2_at_u, f3_at_v]);
+    let v = points.pop().expect("two phases");
+    let u = points.pop().expect("two phases");
+    Ok((residuals, LinearGKRSubclaim { u, v, expected_value: current, f1_at_guv, f2_at_u, f3_at_v }))
 }
@@ -0,0 +1,157 @@
+// src/virtual_poly.rs
+
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::{Field, PrimeField};
+
+use crate::error::Error;
+use crate::ml_extension::{fix_first_variable_batch, DenseMLE, IndexOrder};
+use crate::sumcheck::protocol::{self, Subclaim};
+use crate::sumcheck::MessageForm;
+use crate::transcript::{labels, Transcript};
+
+/// 積の和 Σ_i c_i·Π_j P_{i,j}(x) で表した多項式（各 P は同じ変数数の密な MLE）
+///
+/// GKR の層 add_i·(V + V) + mult_i·(V·V) のように同じ MLE が複数の積に現れるので，
+/// MLE は `mles` に一度ずつ持ち，積はその添字の列で表す。疎な多変数多項式に展開せずに
+/// bookkeeping table のまま sum-check を回せる。変数は先頭（添字の最上位ビット）から束縛する。
+#[derive(Clone)]
+pub struct VirtualPolynomial<F: Field = ScalarField> {
+    pub num_vars: usize,
+    /// 積の因子として現れる MLE（先頭の変数を最上位ビットとする表）
+    pub mles: Vec<DenseMLE<F>>,
+    /// (係数 c_i, `mles` への添字の列)
+    pub products: Vec<(F, Vec<usize>)>,
+}
+
+impl<F: Field> VirtualPolynomial<F> {
+    /// 恒等的に 0 の num_vars 変数の多項式
+    pub fn new(num_vars: usize) -> Self {
+        VirtualPolynomial { num_vars, mles: Vec::new(), products: Vec::new() }
+    }
+
+    /// MLE を因子の候補として登録し，その添字を返す（同じ表が既にあれば登録済みの添字）
+    pub fn add_mle(&mut self, mle: DenseMLE<F>) -> usize {
+        assert_eq!(mle.num_vars, self.num_vars, "num_vars mismatch");
+        let mle = mle.to_order(IndexOrder::BigEndian);
+        if let Some(i) = self.mles.iter().position(|m| m.evaluations == mle.evaluations) {
+            return i;
+        }
+        self.mles.push(mle);
+        self.mles.len() - 1
+    }
+
+    /// 項 coeff·Π_j factors_j を加える
+    pub fn add_product(&mut self, coeff: F, factors: impl IntoIterator<Item = DenseMLE<F>>) {
+        let indices = factors.into_iter().map(|mle| self.add_mle(mle)).collect();
+        self.products.push((coeff, indices));
+    }
+
+    /// 登録済みの MLE の添字で項 coeff·Π_j mles[indices_j] を加える
+    pub fn add_product_indices(&mut self, coeff: F, indices: Vec<usize>) {
+        assert!(indices.iter().all(|&i| i < self.mles.len()), "unknown MLE index");
+        self.products.push((coeff, indices));
+    }
+
+    /// 各変数についての次数（最も多くの因子を持つ項の因子数）
+    pub fn degree(&self) -> usize {
+        self.products.iter().map(|(_, indices)| indices.len()).max().unwrap_or(0)
+    }
+
+    /// 任意の点での値
+    pub fn evaluate(&self, point: &[F]) -> F {
+        assert_eq!(point.len(), self.num_vars, "point has the wrong number of coordinates");
+        let values: Vec<F> = self.mles.iter().map(|m| m.evaluate(point)).collect();
+        self.products.iter().map(|(c, indices)| indices.iter().map(|&i| values[i]).product::<F>() * c).sum()
+    }
+
+    /// ブール超立方体上の総和
+    pub fn sum(&self) -> F {
+        (0..1usize << self.num_vars)
+            .map(|b| {
+                self.products
+                    .iter()
+                    .map(|(c, indices)| indices.iter().map(|&i| self.mles[i].evaluations[b]).product::<F>() * c)
+                    .sum::<F>()
+            })
+            .sum()
+    }
+
+    /// 先頭変数のラウンド多項式の 0, 1, ..., d での値（d = `degree()`，ただし 1 以上）
+    pub fn round_evaluations(&self) -> Vec<F> {
+        assert!(self.num_vars > 0, "all variables are already bound");
+        let d = self.degree().max(1);
+        let half = 1 << (self.num_vars - 1);
+        let mut evals = vec![F::zero(); d + 1];
+        // values[k][t] = P_k(t, b)
+        let mut values = vec![vec![F::zero(); d + 1]; self.mles.len()];
+        for b in 0..half {
+            for (mle, vals) in self.mles.iter().zip(values.iter_mut()) {
+                let (lo, hi) = (mle.evaluations[b], mle.evaluations[b + half]);
+                let step = hi - lo;
+                vals[0] = lo;
+                for t in 1..=d {
+                    vals[t] = vals[t - 1] + step;
+                }
+            }
+            for (c, indices) in self.products.iter() {
+                for (t, e) in evals.iter_mut().enumerate() {
+                    *e += indices.iter().map(|&i| values[i][t]).product::<F>() * c;
+                }
+            }
+        }
+        evals
+    }
+
+    /// 全ての MLE の先頭変数を r に固定する
+    pub fn fix_first_variable(&mut self, r: F) {
+        assert!(self.num_vars > 0, "all variables are already bound");
+        fix_first_variable_batch(&mut self.mles, r);
+        self.num_vars -= 1;
+    }
+}
+
+impl<F: PrimeField> VirtualPolynomial<F> {
+    /// 総和についての sum-check を行い，(総和, 各ラウンドのメッセージ, チャレンジの列) を返す
+    ///
+    /// メッセージは `MessageForm::default()` でエンコードし，transcript には総和と，
+    /// 汎用の sum-check のラベル（`labels::SUMCHECK_ROUND`）でメッセージを吸収する。
+    pub fn prove(mut self, transcript: &mut Transcript) -> (F, Vec<Vec<F>>, Vec<F>) {
+        let claimed_sum = self.sum();
+        transcript.append_field(labels::CLAIMED_SUM, &claimed_sum);
+        let mut msgs = Vec::with_capacity(self.num_vars);
+        let mut point = Vec::with_capacity(self.num_vars);
+        while self.num_vars > 0 {
+            let msg = MessageForm::default().encode(self.round_evaluations());
+            transcript.append(labels::SUMCHECK_ROUND, &msg);
+            msgs.push(msg);
+            let r: F = transcript.squeeze(labels::SUMCHECK_CHALLENGE);
+            point.push(r);
+            self.fix_first_variable(r);
+        }
+        (claimed_sum, msgs, point)
+    }
+
+    /// `prove` の出力を検証し，最終点と，そこで多項式が取るべき値を返す
+    ///
+    /// 返り値の期待値は呼び出し側が `evaluate` かオラクルで確かめる。
+    pub fn verify(
+        num_vars: usize,
+        degree: usize,
+        claimed_sum: F,
+        msgs: &[Vec<F>],
+        transcript: &mut Transcript,
+    ) -> Result<Subclaim<F>, Error> {
+        if msgs.len() != num_vars {
+            return Err(Error::LengthMismatch { what: "round messages", expected: num_vars, found: msgs.len() });
+        }
+        let mut state = protocol::verifier_init(num_vars, degree.max(1), claimed_sum);
+        transcript.append_field(labels::CLAIMED_SUM, &claimed_sum);
+        for msg in msgs {
+            protocol::verify_round(&mut state, msg)?;
+            transcript.append(labels::SUMCHECK_ROUND, msg);
+            let r: F = transcript.squeeze(labels::SUMCHECK_CHALLENGE);
+            protocol::apply_challenge_verifier(&mut state, r);
+        }
+        protocol::finalize(state)
+    }
+}
@@ -0,0 +1,228 @@
+// src/wiring.rs
+
+use ark_ff::Field;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::circuit::{Circuit, Gate, Layer};
+use crate::ml_extension::SparseMLE;
+
+/// 層の配線述語 pred(z, x, y)：出力ゲート z が入力 x, y を読むとき 1
+///
+/// 点の各成分は，添字の最上位ビットから順に並べる。
+pub trait WiringPredicate<F: Field> {
+    /// 出力側（z）の変数数
+    fn num_vars_out(&self) -> usize;
+    /// 入力側（x, y それぞれ）の変数数
+    fn num_vars_in(&self) -> usize;
+    /// 多重線形拡張の点 (z, x, y) での値
+    fn evaluate(&self, z: &[F], x: &[F], y: &[F]) -> F;
+    /// 述語が 1 となるブール点 (z, x, y)
+    fn entries(&self) -> Vec<(usize, usize, usize)>;
+
+    /// (z, x, y) を上位から並べた添字を持つ疎な MLE
+    fn to_sparse_mle(&self) -> SparseMLE<F> {
+        let n_in = self.num_vars_in();
+        let evaluations: HashMap<usize, F> = self
+            .entries()
+            .into_iter()
+            .map(|(z, x, y)| ((z << (2 * n_in)) | (x << n_in) | y, F::one()))
+            .collect();
+        SparseMLE::new(self.num_vars_out() + 2 * n_in, evaluations)
+    }
+}
+
+/// Π_i (a_i b_i c_i + (1 - a_i)(1 - b_i)(1 - c_i))：3 点のビットが全て一致するときの多重線形な指示関数
+///
+/// eq(a, b)·eq(a, c) は a について 2 次になるため，多重線形拡張としては使えない。
+fn eq3<F: Field>(a: &[F], b: &[F], c: &[F]) -> F {
+    assert!(a.len() == b.len() && a.len() == c.len());
+    let one = F::one();
+    a.iter()
+        .zip(b.iter())
+        .zip(c.iter())
+        .map(|((a, b), c)| *a * b * c + (one - a) * (one - b) * (one - c))
+        .product()
+}
+
+/// 二分木の 1 層：ゲート z が 2z と 2z+1 を読む
+///
+/// x = (z, 0), y = (z, 1) なので pred = eq3(z, x', y') (1 - x_last) y_last で，O(k) で評価できる。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BinaryTreeLayer {
+    pub num_vars_out: usize,
+}
+
+impl<F: Field> WiringPredicate<F> for BinaryTreeLayer {
+    fn num_vars_out(&self) -> usize {
+        self.num_vars_out
+    }
+
+    fn num_vars_in(&self) -> usize {
+        self.num_vars_out + 1
+    }
+
+    fn evaluate(&self, z: &[F], x: &[F], y: &[F]) -> F {
+        let k = self.num_vars_out;
+        eq3(z, &x[..k], &y[..k]) * (F::one() - x[k]) * y[k]
+    }
+
+    fn entries(&self) -> Vec<(usize, usize, usize)> {
+        (0..1 << self.num_vars_out).map(|z| (z, 2 * z, 2 * z + 1)).collect()
+    }
+}
+
+/// 内積回路の乗算層：入力 a || b（長さ 2^(k+1)）に対し，ゲート z が a_z と b_z を読む
+///
+/// x = (0, z), y = (1, z) なので pred = (1 - x_0) y_0 eq3(z, x', y')。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InnerProductLayer {
+    pub num_vars_out: usize,
+}
+
+impl<F: Field> WiringPredicate<F> for InnerProductLayer {
+    fn num_vars_out(&self) -> usize {
+        self.num_vars_out
+    }
+
+    fn num_vars_in(&self) -> usize {
+        self.num_vars_out + 1
+    }
+
+    fn evaluate(&self, z: &[F], x: &[F], y: &[F]) -> F {
+        (F::one() - x[0]) * y[0] * eq3(z, &x[1..], &y[1..])
+    }
+
+    fn entries(&self) -> Vec<(usize, usize, usize)> {
+        let n = 1 << self.num_vars_out;
+        (0..n).map(|z| (z, z, n + z)).collect()
+    }
+}
+
+/// 木の種類
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TreeOp {
+    Add,
+    Mul,
+}
+
+fn tree_layer(num_vars_out: usize, op: TreeOp) -> Layer {
+    let gates = (0..1 << num_vars_out)
+        .map(|z| match op {
+            TreeOp::Add => Gate::Add(2 * z, 2 * z + 1),
+            TreeOp::Mul => Gate::Mul(2 * z, 2 * z + 1),
+        })
+        .collect();
+    Layer { gates }
+}
+
+/// 2^k 個の入力の総和・総積を求める二分木回路
+pub fn binary_tree_circuit(k: usize, op: TreeOp) -> Circuit {
+    Circuit { num_inputs: 1 << k, layers: (0..k).map(|i| tree_layer(i, op)).collect() }
+}
+
+/// `binary_tree_circuit(k, _)` の各層の述語（層 i は `BinaryTreeLayer { num_vars_out: i }`）
+pub fn binary_tree_predicates<F: Field>(k: usize) -> Vec<Box<dyn WiringPredicate<F>>> {
+    (0..k).map(|i| Box::new(BinaryTreeLayer { num_vars_out: i }) as Box<dyn WiringPredicate<F>>).collect()
+}
+
+/// 長さ 2^k のベクトル a, b の内積回路（入力は a || b）
+///
+/// 層 0..k は加算の二分木，層 k は a_z · b_z を計算する乗算層。
+pub fn inner_product_circuit(k: usize) -> Circuit {
+    let n = 1 << k;
+    let mut layers: Vec<Layer> = (0..k).map(|i| tree_layer(i, TreeOp::Add)).collect();
+    layers.push(Layer { gates: (0..n).map(|z| Gate::Mul(z, n + z)).collect() });
+    Circuit { num_inputs: 2 * n, layers }
+}
+
+/// `inner_product_circuit(k)` の各層の述語
+pub fn inner_product_predicates<F: Field>(k: usize) -> Vec<Box<dyn WiringPredicate<F>>> {
+    let mut predicates = binary_tree_predicates(k);
+    predicates.push(Box::new(InnerProductLayer { num_vars_out: k }));
+    predicates
+}
+
+/// 1 層分の配線述語：加算・乗算 add(z, x, y), mul(z, x, y)（3l 変数），中継 relay(z, x)（2l 変数），定数 constant(z)（l 変数），
+/// 因子が 3 つ以上の積 mul_k(z, x_1, ..., x_k)（(k + 1)·l 変数）
+///
+/// add, mul の添字は `LinearGKRProver` の規約 `z << 2l | y << l | x` に従うので，そのまま `f1` として渡せる。
+/// relay の添字は `z << l | x`，mul_k の添字は `z << kl | x_k << (k-1)l | ... | x_1`（`fan_in` モジュールの規約）。
+/// 層の値 V について
+/// V_out(z) = Σ_{x,y} add(z,x,y)·(V(x) + V(y)) + mul(z,x,y)·V(x)·V(y) + Σ_x relay(z,x)·V(x) + constant(z)
+///          + Σ_k Σ_{x_1..x_k} mul_k(z, x_1, ..., x_k)·V(x_1)···V(x_k) が成り立つ。
+#[derive(Clone)]
+pub struct LayerWiring<F: Field> {
+    pub num_vars: usize,
+    pub add: SparseMLE<F>,
+    pub mul: SparseMLE<F>,
+    pub relay: SparseMLE<F>,
+    pub constant: SparseMLE<F>,
+    /// 因子の数 k（3 以上）ごとの mul_k
+    pub wide_mul: BTreeMap<usize, SparseMLE<F>>,
+}
+
+impl<F: Field> LayerWiring<F> {
+    /// 積の形での最大の因子数（2 未満なら 2）
+    pub fn fan_in(&self) -> usize {
+        self.wide_mul.keys().copied().fold(2, usize::max)
+    }
+}
+
+/// 層の配線述語を作る。出力側・入力側とも幅が 2^l 以下であること
+///
+/// `Input(a)` は relay(z, a) = 1，`Constant(c)` は constant(z) = c で表す。
+/// `Sum` は読む値ごとの relay に，`Product` は因子の数に応じて constant, relay, mul, mul_k に振り分ける。
+pub fn layer_wiring<F: Field>(layer: &Layer, num_vars: usize) -> LayerWiring<F> {
+    let l = num_vars;
+    assert!(layer.gates.len() <= 1 << l, "layer is wider than 2^l");
+    let mut add = HashMap::new();
+    let mut mul = HashMap::new();
+    let mut relay = HashMap::new();
+    let mut constant = HashMap::new();
+    let mut wide_mul: BTreeMap<usize, HashMap<usize, F>> = BTreeMap::new();
+    let put = |table: &mut HashMap<usize, F>, index: usize, weight: F| {
+        *table.entry(index).or_insert_with(F::zero) += weight;
+    };
+    for (z, gate) in layer.gates.iter().enumerate() {
+        let reads = gate.reads();
+        assert!(reads.iter().all(|&x| x < 1 << l), "gate reads a wire beyond 2^l");
+        match gate {
+            Gate::Add(x, y) => put(&mut add, (z << (2 * l)) | (y << l) | x, F::one()),
+            Gate::Constant(c) => put(&mut constant, z, F::from(*c)),
+            Gate::Input(_) | Gate::Sum(_) => {
+                for x in reads {
+                    put(&mut relay, (z << l) | x, F::one());
+                }
+            }
+            Gate::Mul(..) | Gate::Product(_) => match reads.len() {
+                0 => put(&mut constant, z, F::one()),
+                1 => put(&mut relay, (z << l) | reads[0], F::one()),
+                2 => put(&mut mul, (z << (2 * l)) | (reads[1] << l) | reads[0], F::one()),
+                k => {
+                    let index = reads.iter().rev().fold(z, |acc, x| (acc << l) | x);
+                    put(wide_mul.entry(k).or_default(), index, F::one());
+                }
+            },
+        }
+    }
+    LayerWiring {
+        num_vars: l,
+        add: SparseMLE::new(3 * l, add),
+        mul: SparseMLE::new(3 * l, mul),
+        relay: SparseMLE::new(2 * l, relay),
+        constant: SparseMLE::new(l, constant),
+        wide_mul: wide_mul.into_iter().map(|(k, table)| (k, SparseMLE::new((k + 1) * l, table))).collect(),
+    }
+}
+
+/// 回路の全ての層（と入力）の幅を収める変数数 l
+pub fn circuit_num_vars(circuit: &Circuit) -> usize {
+    let width = circuit.layers.iter().map(|layer| layer.gates.len()).fold(circuit.num_inputs, usize::max);
+    width.next_power_of_two().trailing_zeros() as usize
+}
+
+/// 回路の各層の配線述語（全ての層で共通の l = `circuit_num_vars(circuit)` を使う）
+pub fn circuit_wiring<F: Field>(circuit: &Circuit) -> Vec<LayerWiring<F>> {
+    let l = circuit_num_vars(circuit);
+    circuit.layers.iter().map(|layer| layer_wiring(layer, l)).collect()
+}
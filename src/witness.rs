@@ -0,0 +1,313 @@
+// src/witness.rs
+
+use ark_ff::PrimeField;
+use serde_json::value::RawValue;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use crate::ml_extension::DenseMLE;
+use crate::serialization::{decode_bytes, Endianness};
+pub use crate::serialization::field_byte_len;
+
+/// 外部ファイル中の値を体の元へ写すときの規則
+///
+/// - 整数: 10 進表記（負数は p - |x| に写す）。p 以上の値は mod p で還元する（JSON の数値も桁数によらない）
+/// - 真偽値: `true` / `false` をそれぞれ 1 / 0 に写す
+/// - バイト列: `0x` で始まる 16 進文字列。`byte_order` に従って整数として解釈する（空のバイト列は拒否する）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EncodingRules {
+    pub byte_order: ByteOrder,
+    /// バイト列を何バイトずつ 1 つの元に詰めるか（`None` ならバイト列全体で 1 元）
+    pub bytes_per_element: Option<usize>,
+}
+
+impl Default for EncodingRules {
+    fn default() -> Self {
+        EncodingRules { byte_order: ByteOrder::BigEndian, bytes_per_element: None }
+    }
+}
+
+/// バイト列を整数として読むときのバイト順
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ByteOrder {
+    LittleEndian,
+    BigEndian,
+}
+
+impl From<ByteOrder> for Endianness {
+    fn from(order: ByteOrder) -> Self {
+        match order {
+            ByteOrder::LittleEndian => Endianness::Little,
+            ByteOrder::BigEndian => Endianness::Big,
+        }
+    }
+}
+
+/// 対応するファイル形式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WitnessFormat {
+    /// 値の配列，または `{"inputs": [...]}`（入れ子の配列は平坦化する）
+    Json,
+    /// カンマ区切り（改行も区切りとみなす）。`#` 以降はコメント
+    Csv { has_header: bool },
+    /// 固定長（`bytes_per_element`，既定は体のバイト長）の元を連結したもの
+    Binary,
+}
+
+impl WitnessFormat {
+    /// 拡張子から形式を推定する
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        match path.as_ref().extension()?.to_str()? {
+            "json" => Some(WitnessFormat::Json),
+            "csv" => Some(WitnessFormat::Csv { has_header: false }),
+            "bin" => Some(WitnessFormat::Binary),
+            _ => None,
+        }
+    }
+}
+
+/// 入力の読み込みに失敗した理由
+#[derive(Debug)]
+pub enum WitnessError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    UnknownFormat,
+    /// 入力の `index` 番目の値（JSON の入れ子は平坦化した順，CSV はセルの順）が解釈できない
+    InvalidValue { index: usize, value: String },
+    /// バイト列が体に収まらない，または体の元として正規形でない
+    ByteStringTooLong { index: usize, len: usize },
+    NonCanonical { index: usize },
+    /// バイナリ長が元の長さの倍数でない
+    TruncatedBinary { len: usize, element_len: usize },
+}
+
+impl fmt::Display for WitnessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WitnessError::Io(e) => write!(f, "io error: {}", e),
+            WitnessError::Json(e) => write!(f, "json error: {}", e),
+            WitnessError::UnknownFormat => write!(f, "unknown witness file format"),
+            WitnessError::InvalidValue { index, value } => {
+                write!(f, "invalid value at {}: {:?}", index, value)
+            }
+            WitnessError::ByteStringTooLong { index, len } => {
+                write!(f, "byte string at {} does not fit in the field ({} bytes)", index, len)
+            }
+            WitnessError::NonCanonical { index } => {
+                write!(f, "element at {} is not a canonical field encoding", index)
+            }
+            WitnessError::TruncatedBinary { len, element_len } => {
+                write!(f, "binary length {} is not a multiple of {}", len, element_len)
+            }
+        }
+    }
+}
+
+impl std::error::Error for WitnessError {}
+
+impl From<std::io::Error> for WitnessError {
+    fn from(e: std::io::Error) -> Self {
+        WitnessError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for WitnessError {
+    fn from(e: serde_json::Error) -> Self {
+        WitnessError::Json(e)
+    }
+}
+
+/// 入力層への割り当て（入力ゲート順の値列）
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InputAssignment<F: PrimeField> {
+    pub values: Vec<F>,
+}
+
+impl<F: PrimeField> InputAssignment<F> {
+    /// 長さを 2 の冪に 0 埋めした際の変数数
+    pub fn num_vars(&self) -> usize {
+        self.values.len().max(1).next_power_of_two().trailing_zeros() as usize
+    }
+
+    /// 0 埋めして入力層の multilinear extension を作る
+    pub fn to_mle(&self) -> DenseMLE<F> {
+        DenseMLE::from_padded_evaluations(self.values.clone())
+    }
+}
+
+/// ファイルを読み込む。形式は拡張子から推定する
+pub fn load_witness<F: PrimeField, P: AsRef<Path>>(
+    path: P,
+    rules: &EncodingRules,
+) -> Result<InputAssignment<F>, WitnessError> {
+    let format = WitnessFormat::from_path(&path).ok_or(WitnessError::UnknownFormat)?;
+    load_witness_with_format(path, format, rules)
+}
+
+/// 形式を明示してファイルを読み込む
+pub fn load_witness_with_format<F: PrimeField, P: AsRef<Path>>(
+    path: P,
+    format: WitnessFormat,
+    rules: &EncodingRules,
+) -> Result<InputAssignment<F>, WitnessError> {
+    match format {
+        WitnessFormat::Json => parse_json(&std::fs::read_to_string(path)?, rules),
+        WitnessFormat::Csv { has_header } => {
+            parse_csv(&std::fs::read_to_string(path)?, has_header, rules)
+        }
+        WitnessFormat::Binary => parse_binary(&std::fs::read(path)?, rules),
+    }
+}
+
+/// JSON 文字列から割り当てを読む
+///
+/// 数値は `RawValue` のまま字句として読むので，64 ビットを超える整数も桁を失わずに mod p で還元できる。
+pub fn parse_json<F: PrimeField>(
+    input: &str,
+    rules: &EncodingRules,
+) -> Result<InputAssignment<F>, WitnessError> {
+    let root: &RawValue = serde_json::from_str(input)?;
+    let root = if root.get().starts_with('{') {
+        let mut map: HashMap<String, &RawValue> = serde_json::from_str(root.get())?;
+        map.remove("inputs").ok_or(WitnessError::InvalidValue {
+            index: 0,
+            value: "missing \"inputs\"".to_string(),
+        })?
+    } else {
+        root
+    };
+    let mut values = Vec::new();
+    push_json_value(root, rules, &mut 0, &mut values)?;
+    Ok(InputAssignment { values })
+}
+
+/// `position` は入力の値（配列でないもの）の通し番号。バイト列は複数の元になるので `out` の長さとは別に数える
+fn push_json_value<F: PrimeField>(
+    value: &RawValue,
+    rules: &EncodingRules,
+    position: &mut usize,
+    out: &mut Vec<F>,
+) -> Result<(), WitnessError> {
+    let index = *position;
+    let text = value.get();
+    match text.as_bytes().first() {
+        Some(b'[') => {
+            let items: Vec<&RawValue> = serde_json::from_str(text)?;
+            for item in items {
+                push_json_value(item, rules, position, out)?;
+            }
+            return Ok(());
+        }
+        // 数値は 10 進表記の字句をそのまま整数として読む（小数や指数表記は拒否される）
+        Some(b'-' | b'0'..=b'9') => out.push(parse_integer(text, index)?),
+        _ => match serde_json::from_str::<Value>(text)? {
+            Value::Bool(b) => out.push(if b { F::one() } else { F::zero() }),
+            Value::String(s) => encode_scalar(&s, index, rules, out)?,
+            other => {
+                return Err(WitnessError::InvalidValue { index, value: other.to_string() });
+            }
+        },
+    }
+    *position += 1;
+    Ok(())
+}
+
+/// CSV 文字列から割り当てを読む（行優先で平坦化する）
+pub fn parse_csv<F: PrimeField>(
+    input: &str,
+    has_header: bool,
+    rules: &EncodingRules,
+) -> Result<InputAssignment<F>, WitnessError> {
+    let mut values = Vec::new();
+    let lines = input
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .skip(if has_header { 1 } else { 0 });
+    let cells = lines.flat_map(|line| line.split(',').map(str::trim).filter(|c| !c.is_empty()));
+    for (index, cell) in cells.enumerate() {
+        encode_scalar(cell, index, rules, &mut values)?;
+    }
+    Ok(InputAssignment { values })
+}
+
+/// バイナリから割り当てを読む
+///
+/// 各元は `bytes_per_element`（既定は体のバイト長）バイトで `byte_order` に従い格納される。
+/// 正規形（p 未満）でない元は拒否する。
+pub fn parse_binary<F: PrimeField>(
+    input: &[u8],
+    rules: &EncodingRules,
+) -> Result<InputAssignment<F>, WitnessError> {
+    let element_len = rules.bytes_per_element.unwrap_or_else(field_byte_len::<F>);
+    if element_len == 0 || !input.len().is_multiple_of(element_len) {
+        return Err(WitnessError::TruncatedBinary { len: input.len(), element_len });
+    }
+    let values = input
+        .chunks(element_len)
+        .enumerate()
+        .map(|(index, chunk)| {
+            let (value, canonical) = decode_bytes::<F>(chunk, rules.byte_order.into());
+            if canonical {
+                Ok(value)
+            } else {
+                Err(WitnessError::NonCanonical { index })
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(InputAssignment { values })
+}
+
+/// 文字列 1 つを規則に従って元（バイト列の場合は複数になりうる）に変換する
+fn encode_scalar<F: PrimeField>(
+    s: &str,
+    index: usize,
+    rules: &EncodingRules,
+    out: &mut Vec<F>,
+) -> Result<(), WitnessError> {
+    match s {
+        "true" => out.push(F::one()),
+        "false" => out.push(F::zero()),
+        _ => {
+            if let Some(hex) = s.strip_prefix("0x") {
+                let bytes = decode_hex(hex)
+                    .filter(|bytes| !bytes.is_empty())
+                    .ok_or_else(|| WitnessError::InvalidValue { index, value: s.to_string() })?;
+                out.extend(encode_byte_string::<F>(&bytes, index, rules)?);
+            } else {
+                out.push(parse_integer(s, index)?);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn parse_integer<F: PrimeField>(s: &str, index: usize) -> Result<F, WitnessError> {
+    F::from_str(s).map_err(|_| WitnessError::InvalidValue { index, value: s.to_string() })
+}
+
+/// バイト列を元の列に詰める。1 元あたりのバイト数は体の容量（MODULUS_BIT_SIZE - 1 ビット）以下でなければならない
+fn encode_byte_string<F: PrimeField>(
+    bytes: &[u8],
+    index: usize,
+    rules: &EncodingRules,
+) -> Result<Vec<F>, WitnessError> {
+    let capacity = (F::MODULUS_BIT_SIZE as usize - 1) / 8;
+    let chunk_len = rules.bytes_per_element.unwrap_or(bytes.len()).max(1);
+    if chunk_len > capacity {
+        return Err(WitnessError::ByteStringTooLong { index, len: chunk_len });
+    }
+    Ok(bytes.chunks(chunk_len).map(|chunk| decode_bytes::<F>(chunk, rules.byte_order.into()).0).collect())
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
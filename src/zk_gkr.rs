@@ -0,0 +1,379 @@
+// src/zk_gkr.rs
+//
+// 中間層の値を隠すゼロ知識 GKR（`zk` フィーチャ）。Libra（Xie et al.）の方式に従う。
+//
+// 中間層 i の W を，超立方体上では変わらない Ẇ_i(z) = W_i(z) + Z(z)·Σ_{w∈{0,1}} S_i(z_0, w) に置き換える
+// （Z(z) = Π_j z_j·(1 - z_j)，S_i は z_0, w についてそれぞれ 2 次のランダムな多項式）。
+// 上の層の sum-check が明かす Ẇ_i(u), Ẇ_i(v) は S_i で隠れる。S_i の項は層 i の sum-check に
+// 1 変数 w を足した G(w, x, y) = F(x, y) / 2 + T(w) / 2^{2n} の和として取り込むので，
+// 検証者は S_i を w のランダムな 1 点でしか見ない。ラウンドメッセージは `protocol::prove_zk_with` のマスクで隠す。
+//
+// 全ての S_i の係数は 1 つの MLE（添字 (i - 1, p, q)，p, q は z_0, w の次数）としてコミットし，主張に含める。
+// 出力と入力は公開のままで，入力層の Ẇ は W そのもの。
+// 因子数 2 の層と `ClaimReduction::RandomCombination` の還元に限る。
+// 完全なゼロ知識には開示の証明も何も漏らさない（hiding な）PCS が要る。
+
+use ark_ff::{Field, PrimeField};
+use rand::Rng;
+
+use crate::circuit::Circuit;
+use crate::circuit_prover::{
+    extended_values, output_point, product_forms, wiring_point, CombinedClaim, GKRProver, GKRVerifier,
+};
+use crate::eq::eq_table;
+use crate::error::Error;
+use crate::ml_extension::DenseMLE;
+use crate::pcs::MultilinearPCS;
+use crate::prover::LinearGKRProver;
+use crate::statement::Statement;
+use crate::sumcheck::protocol::{self, power_point, ZkProof};
+use crate::transcript::Transcript;
+use crate::wiring;
+
+/// ゼロ知識版の回路の証明を初期化するラベル
+pub const ZK_LABEL: &[u8] = b"gkr-circuit-zk";
+
+/// S_i の z_0, w それぞれについての次数
+const LAYER_MASK_DEGREE: usize = 2;
+
+/// S_i の係数の表の 1 層分の変数数（z_0 と w に 2 ビットずつ）
+const LAYER_MASK_VARS: usize = 4;
+
+/// 1 層分のゼロ知識の証明
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ZkLayerProof<F: PrimeField, C, O> {
+    /// G(w, x, y) の sum-check（2n + 1 変数）
+    pub sumcheck: ZkProof<F, C, O>,
+    /// 下の層の Ẇ(u), Ẇ(v)
+    pub masked_evals: Vec<F>,
+    /// 上の層の主張の点 u_j ごとの S_i(u_j0, w*) を係数の表の MLE の値に直したもの
+    pub mask_evals: Vec<F>,
+    /// `mask_evals` の各値の開示の証明
+    pub mask_openings: Vec<O>,
+}
+
+/// 中間層の値を隠した回路の証明（`GKRProver::prove_zk`）
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ZkGKRProof<F: PrimeField, C, O> {
+    pub outputs: Vec<F>,
+    /// 全ての中間層の S_i の係数へのコミットメント
+    pub mask_commitment: C,
+    /// 出力側から順に，層ごとの証明
+    pub layer_proofs: Vec<ZkLayerProof<F, C, O>>,
+}
+
+/// ⌈log2 n⌉（n = 0 のときも 0）
+fn bits(n: usize) -> usize {
+    n.max(1).next_power_of_two().trailing_zeros() as usize
+}
+
+/// 層の sum-check のラウンド多項式の次数の上限（最後のラウンドで Z の t·(1 - t) が掛かる）
+fn round_degree(n: usize) -> usize {
+    // n = 1 のときは S_i の z_0 もそのラウンドの変数になる
+    if n == 1 {
+        3 + LAYER_MASK_DEGREE
+    } else {
+        3
+    }
+}
+
+/// 中間層の数（S_i の数）に対する係数の表の先頭の変数数
+fn mask_row_vars(circuit: &Circuit) -> usize {
+    bits(circuit.depth().saturating_sub(1))
+}
+
+/// `prove_zk` のコミットメントに必要な変数数（S_i の表と層の sum-check のマスクの大きい方）
+pub fn zk_commitment_vars(circuit: &Circuit) -> usize {
+    let n = wiring::circuit_num_vars(circuit) + 1;
+    let sumcheck_vars = bits(2 * n + 1) + bits(round_degree(n) + 1);
+    sumcheck_vars.max(mask_row_vars(circuit) + LAYER_MASK_VARS)
+}
+
+/// Z(u) = Π_j u_j·(1 - u_j)
+fn vanishing<F: Field>(u: &[F]) -> F {
+    u.iter().map(|r| *r * (F::one() - r)).product()
+}
+
+/// 1 変数多項式（係数表現）の r での値
+fn horner<F: Field>(coeffs: &[F], r: F) -> F {
+    coeffs.iter().rev().fold(F::zero(), |acc, c| acc * r + c)
+}
+
+/// 先頭の変数を r に固定する
+fn fold<F: Field>(table: &mut Vec<F>, r: F) {
+    let half = table.len() / 2;
+    for i in 0..half {
+        let (lo, hi) = (table[i], table[half + i]);
+        table[i] = lo + r * (hi - lo);
+    }
+    table.truncate(half);
+}
+
+/// S_i(a, w) を開示する点（層の行，a と w の冪の点）と倍率
+fn layer_mask_point<F: Field>(layer: usize, row_vars: usize, a: F, w: F) -> Result<(Vec<F>, F), Error> {
+    let unopenable = Error::Transcript("challenge makes the layer mask unopenable");
+    let (pa, sa) = power_point(a, LAYER_MASK_VARS / 2).ok_or(unopenable.clone())?;
+    let (pw, sw) = power_point(w, LAYER_MASK_VARS / 2).ok_or(unopenable)?;
+    let row = (0..row_vars).rev().map(|t| F::from(((layer >> t) & 1) as u64));
+    Ok((row.chain(pa).chain(pw).collect(), sa * sw))
+}
+
+/// 中間層 i（1 始まり）の S_i の係数 s[p][q]
+type LayerMask<F> = [[F; LAYER_MASK_DEGREE + 1]; LAYER_MASK_DEGREE + 1];
+
+/// σ(a) = Σ_{w∈{0,1}} S(a, w) の a についての係数
+fn boolean_sum<F: Field>(mask: &LayerMask<F>) -> Vec<F> {
+    mask.iter().map(|row| row[0] + row.iter().sum::<F>()).collect()
+}
+
+/// T(w) = Σ_j e_j·S(a_j, w) の w についての係数
+fn mask_terms<F: Field>(mask: &LayerMask<F>, terms: &[(F, F)]) -> Vec<F> {
+    (0..=LAYER_MASK_DEGREE)
+        .map(|q| terms.iter().map(|(a, e)| *e * horner(&mask.iter().map(|row| row[q]).collect::<Vec<_>>(), *a)).sum())
+        .collect()
+}
+
+/// 層 i の sum-check の後の W 側の 2 点の主張を，次の層の V 側の結合した主張と
+/// S_{i+1} の項 (u_j0, c_j·Z(u_j))，定数 1 の配線の分 Σ_j c_j·u_j0·eq(u_j', 0) に分ける
+fn split_claim<F: PrimeField>(next: &CombinedClaim<F>) -> (CombinedClaim<F>, Vec<(F, F)>, F) {
+    let mut constant = F::zero();
+    let mut terms = Vec::with_capacity(next.points.len());
+    let mut coeffs = Vec::with_capacity(next.points.len());
+    for (p, c) in next.points.iter().zip(next.coeffs.iter()) {
+        let (u0, rest) = (p[0], &p[1..]);
+        constant += *c * u0 * rest.iter().map(|r| F::one() - r).product::<F>();
+        terms.push((u0, *c * vanishing(p)));
+        coeffs.push(*c * (F::one() - u0));
+    }
+    let points = next.points.iter().map(|p| p[1..].to_vec()).collect();
+    (CombinedClaim { points, coeffs }, terms, constant)
+}
+
+/// 因子数 2 以外の層を持つ回路は扱わない
+fn check_fan_ins(circuit: &Circuit) -> Result<(), Error> {
+    if (0..circuit.depth()).any(|i| circuit.fan_in(i) != 2) {
+        return Err(Error::MalformedProof("zero-knowledge mode needs every layer to have fan-in 2"));
+    }
+    Ok(())
+}
+
+impl<F: PrimeField> GKRProver<F> {
+    /// 中間層の値を隠して証明する（入力と出力は公開）
+    ///
+    /// `pp` は `zk_commitment_vars(circuit)` 変数まで扱えること。
+    pub fn prove_zk<P: MultilinearPCS<F>, R: Rng>(
+        pp: &P::ProverParam,
+        circuit: &Circuit,
+        inputs: &[F],
+        rng: &mut R,
+    ) -> ZkGKRProof<F, P::Commitment, P::Proof> {
+        check_fan_ins(circuit).expect("unsupported circuit");
+        let values = circuit.evaluate(inputs);
+        let depth = circuit.depth();
+        let l = wiring::circuit_num_vars(circuit);
+        let n = l + 1;
+        let degree = round_degree(n);
+
+        // 中間層 1..depth の S_i をまとめてコミットする
+        let masks: Vec<LayerMask<F>> = (1..depth)
+            .map(|_| std::array::from_fn(|_| std::array::from_fn(|_| F::rand(rng))))
+            .collect();
+        let row_vars = mask_row_vars(circuit);
+        let mut table = vec![F::zero(); 1 << (row_vars + LAYER_MASK_VARS)];
+        for (i, mask) in masks.iter().enumerate() {
+            for (p, row) in mask.iter().enumerate() {
+                for (q, s) in row.iter().enumerate() {
+                    table[(i << LAYER_MASK_VARS) | (p << (LAYER_MASK_VARS / 2)) | q] = *s;
+                }
+            }
+        }
+        let mask_mle = DenseMLE::from_evaluations_vec(row_vars + LAYER_MASK_VARS, table);
+        let mask_commitment = P::commit(pp, &mask_mle);
+        let statement = Statement::new(circuit, inputs.to_vec(), values[0].clone())
+            .with_commitment(P::commitment_bytes(&mask_commitment));
+        let mut transcript = Transcript::for_statement(ZK_LABEL, &statement);
+
+        let f1s = product_forms::<F>(circuit);
+        let half = F::from(2u64).inverse().unwrap();
+        let full_inverse = half.pow([(2 * n) as u64]);
+        let mut reduced = CombinedClaim::at_point(output_point(&mut transcript, &values[0], l));
+        let mut terms: Vec<(F, F)> = Vec::new();
+        let mut layer_proofs = Vec::with_capacity(depth);
+        for (i, f1) in f1s.iter().enumerate() {
+            transcript.begin_layer(i);
+            let w = extended_values(&values[i + 1], l);
+            // 下の層が中間層なら σ_{i+1}，入力層なら 0
+            let sigma = if i + 1 < depth { boolean_sum(&masks[i]) } else { vec![F::zero()] };
+            let t_coeffs = if i == 0 { vec![F::zero()] } else { mask_terms(&masks[i - 1], &terms) };
+            let weights = reduced.weights();
+            let pre = LinearGKRProver::precompute(f1, &w, &w);
+            let mut h = vec![F::zero(); 1 << n];
+            for &(z, x, y, val) in pre.wiring.iter() {
+                h[x] += weights[z] * val * w.evaluations[y];
+            }
+            let layer_sum: F = h.iter().zip(w.evaluations.iter()).map(|(a, b)| *a * b).sum();
+
+            // 現在のフェーズの表 (a, b)，そのフェーズで固定した点，w*，u，Ẇ(u)
+            let (mut a, mut b) = (h, w.evaluations.clone());
+            let mut phase_point: Vec<F> = Vec::new();
+            let mut w_star = F::zero();
+            let mut u: Vec<F> = Vec::new();
+            let round = |k: usize, r: Option<F>| {
+                if k == 1 {
+                    w_star = r.unwrap();
+                } else if let Some(r) = r {
+                    fold(&mut a, r);
+                    fold(&mut b, r);
+                    phase_point.push(r);
+                }
+                if k == 0 {
+                    let sum_part = half * layer_sum;
+                    return (0..=degree).map(|t| sum_part + horner(&t_coeffs, F::from(t as u64))).collect();
+                }
+                if k == n + 1 {
+                    // Phase 2：f1(g, u, y)·Ẇ(u) と W(y)
+                    u = std::mem::take(&mut phase_point);
+                    let w_at_u = b[0] + vanishing(&u) * horner(&sigma, u[0]);
+                    let eq_u = eq_table(&u);
+                    a = vec![F::zero(); 1 << n];
+                    for &(z, x, y, val) in pre.wiring.iter() {
+                        a[y] += weights[z] * val * eq_u[x] * w_at_u;
+                    }
+                    b = w.evaluations.clone();
+                }
+                // このラウンドの後に残る超立方体の変数の数
+                let rest = 2 * n - k;
+                let mask_part = F::from(2u64).pow([rest as u64]) * full_inverse * horner(&t_coeffs, w_star);
+                let size = a.len() / 2;
+                (0..=degree)
+                    .map(|t| {
+                        let t = F::from(t as u64);
+                        let product = if size > 1 {
+                            (0..size).map(|j| (a[j] + t * (a[size + j] - a[j])) * (b[j] + t * (b[size + j] - b[j]))).sum()
+                        } else {
+                            // 最後のラウンドだけ Ẇ と W が異なる
+                            let first = phase_point.first().copied().unwrap_or(t);
+                            let z = vanishing(&phase_point) * t * (F::one() - t);
+                            (a[0] + t * (a[1] - a[0])) * (b[0] + t * (b[1] - b[0]) + z * horner(&sigma, first))
+                        };
+                        half * product + mask_part
+                    })
+                    .collect()
+            };
+            let (sumcheck, point) =
+                protocol::prove_zk_with::<F, P, R>(pp, 2 * n + 1, degree, round, rng, &mut transcript);
+
+            let w_star = point[0];
+            let (u, v) = (point[1..=n].to_vec(), point[n + 1..].to_vec());
+            let masked_evals: Vec<F> =
+                [&u, &v].iter().map(|p| w.evaluate(p) + vanishing(p) * horner(&sigma, p[0])).collect();
+            let (mask_evals, mask_openings) = terms
+                .iter()
+                .map(|(a, _)| {
+                    let (point, _) = layer_mask_point(i - 1, row_vars, *a, w_star).expect("unopenable layer mask");
+                    P::open(pp, &mask_mle, &point)
+                })
+                .unzip();
+            let proof = ZkLayerProof { sumcheck, masked_evals, mask_evals, mask_openings };
+            transcript.append_fields(b"masked_evals", &proof.masked_evals);
+            transcript.append_fields(b"layer_mask_evals", &proof.mask_evals);
+            layer_proofs.push(proof);
+            (reduced, terms, _) = split_claim(&CombinedClaim::reduce_many(&mut transcript, vec![u, v]));
+        }
+        ZkGKRProof { outputs: values[0].clone(), mask_commitment, layer_proofs }
+    }
+}
+
+impl<F: PrimeField> GKRVerifier<F> {
+    /// `GKRProver::prove_zk` の証明を，入力と回路から検証する
+    ///
+    /// 各層の G の sum-check の最後の値を，配線，Ẇ(u), Ẇ(v)，S_i の開示から確かめ，
+    /// 入力層の Ẇ(u), Ẇ(v) は入力から直接確かめる。
+    pub fn verify_zk<P: MultilinearPCS<F>>(
+        vp: &P::VerifierParam,
+        circuit: &Circuit,
+        inputs: &[F],
+        proof: &ZkGKRProof<F, P::Commitment, P::Proof>,
+    ) -> Result<(), Error> {
+        circuit.validate()?;
+        check_fan_ins(circuit)?;
+        if inputs.len() != circuit.num_inputs {
+            return Err(Error::LengthMismatch { what: "inputs", expected: circuit.num_inputs, found: inputs.len() });
+        }
+        let num_outputs = circuit.layers[0].gates.len();
+        if proof.outputs.len() != num_outputs {
+            return Err(Error::LengthMismatch { what: "outputs", expected: num_outputs, found: proof.outputs.len() });
+        }
+        let depth = circuit.depth();
+        if proof.layer_proofs.len() != depth {
+            return Err(Error::LengthMismatch { what: "layer proofs", expected: depth, found: proof.layer_proofs.len() });
+        }
+        let l = wiring::circuit_num_vars(circuit);
+        let n = l + 1;
+        let row_vars = mask_row_vars(circuit);
+        let statement = Statement::new(circuit, inputs.to_vec(), proof.outputs.clone())
+            .with_commitment(P::commitment_bytes(&proof.mask_commitment));
+        let mut transcript = Transcript::for_statement(ZK_LABEL, &statement);
+
+        let half = F::from(2u64).inverse().unwrap();
+        let full_inverse = half.pow([(2 * n) as u64]);
+        let mut reduced = CombinedClaim::at_point(output_point(&mut transcript, &proof.outputs, l));
+        let mut claim = reduced.evaluate(&proof.outputs);
+        let mut terms: Vec<(F, F)> = Vec::new();
+        for (i, (f1, layer_proof)) in product_forms::<F>(circuit).iter().zip(proof.layer_proofs.iter()).enumerate() {
+            transcript.begin_layer(i);
+            if layer_proof.masked_evals.len() != 2 {
+                return Err(Error::LengthMismatch {
+                    what: "masked evaluations",
+                    expected: 2,
+                    found: layer_proof.masked_evals.len(),
+                });
+            }
+            if layer_proof.mask_evals.len() != terms.len() || layer_proof.mask_openings.len() != terms.len() {
+                let found = layer_proof.mask_evals.len().min(layer_proof.mask_openings.len());
+                return Err(Error::LengthMismatch { what: "layer mask openings", expected: terms.len(), found });
+            }
+            let subclaim =
+                protocol::verify_zk::<F, P>(vp, 2 * n + 1, round_degree(n), claim, &layer_proof.sumcheck, &mut transcript)?;
+            let w_star = subclaim.point[0];
+            let (u, v) = (subclaim.point[1..=n].to_vec(), subclaim.point[n + 1..].to_vec());
+
+            let mut mask_at_w = F::zero();
+            for ((a, e), (value, opening)) in
+                terms.iter().zip(layer_proof.mask_evals.iter().zip(layer_proof.mask_openings.iter()))
+            {
+                let (point, scale) = layer_mask_point(i - 1, row_vars, *a, w_star)?;
+                P::verify(vp, &proof.mask_commitment, &point, *value, opening)?;
+                mask_at_w += *e * scale * value;
+            }
+            let points = [u.clone(), v.clone()];
+            let f1_at_point: F = reduced
+                .points
+                .iter()
+                .zip(reduced.coeffs.iter())
+                .map(|(q, c)| *c * f1.evaluate(&wiring_point(q, &points)))
+                .sum();
+            let (w_at_u, w_at_v) = (layer_proof.masked_evals[0], layer_proof.masked_evals[1]);
+            if half * f1_at_point * w_at_u * w_at_v + full_inverse * mask_at_w != subclaim.expected_value {
+                return Err(Error::EvaluationMismatch("masked layer evaluation"));
+            }
+            transcript.append_fields(b"masked_evals", &layer_proof.masked_evals);
+            transcript.append_fields(b"layer_mask_evals", &layer_proof.mask_evals);
+
+            let next = CombinedClaim::reduce_many(&mut transcript, vec![u, v]);
+            if i + 1 == depth {
+                // 入力層は隠さないので，Ẇ は入力の W そのもの
+                let w = extended_values(inputs, l);
+                if next.points.iter().zip(layer_proof.masked_evals.iter()).any(|(p, e)| w.evaluate(p) != *e) {
+                    return Err(Error::EvaluationMismatch("input layer at the final claim"));
+                }
+            } else {
+                let constant;
+                let value = next.combine(&layer_proof.masked_evals);
+                (reduced, terms, constant) = split_claim(&next);
+                claim = value - constant;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::{One, UniformRand};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use gkr::accumulator::Accumulator;
+use gkr::folding;
+use gkr::ml_extension::{DenseMLE, IndexOrder};
+use gkr::sumcheck::protocol::Subclaim;
+use gkr::transcript::Transcript;
+
+fn random_claim(mle: &DenseMLE<ScalarField>, rng: &mut StdRng) -> (Vec<ScalarField>, ScalarField) {
+	let point: Vec<ScalarField> = (0..mle.num_vars).map(|_| ScalarField::rand(rng)).collect();
+	let value = folding::evaluate_mle(mle, &point);
+	(point, value)
+}
+
+#[test]
+fn true_claims_pass_one_batched_check() {
+	let mut rng = StdRng::seed_from_u64(0);
+	let (w, v) = (DenseMLE::rand(4, &mut rng), DenseMLE::rand(2, &mut rng));
+	let mut acc = Accumulator::new(Transcript::new(b"app"));
+	for _ in 0..5 {
+		let (p, x) = random_claim(&w, &mut rng);
+		acc.add("w", p, x);
+	}
+	// 別のサブプロトコルの accumulator を取り込む
+	let mut sub = Accumulator::new(Transcript::new(b"sub"));
+	let (point, expected_value) = random_claim(&v, &mut rng);
+	sub.add_subclaim("v", &Subclaim { point, expected_value });
+	acc.merge(sub);
+	assert_eq!(acc.len(), 6);
+
+	let oracle = |k: &&str| match *k {
+		"w" => Some(&w),
+		"v" => Some(&v),
+		_ => None,
+	};
+	assert_eq!(acc.finalize(oracle), Ok(()));
+}
+
+#[test]
+fn little_endian_oracles_are_read_in_the_claim_order() {
+	let mut rng = StdRng::seed_from_u64(3);
+	let w = DenseMLE::rand(3, &mut rng);
+	let little = w.to_order(IndexOrder::LittleEndian);
+	let mut acc = Accumulator::new(Transcript::new(b"app"));
+	for _ in 0..3 {
+		let (p, x) = random_claim(&w, &mut rng);
+		acc.add("w", p, x);
+	}
+	assert_eq!(acc.finalize(|_| Some(&little)), Ok(()));
+}
+
+#[test]
+fn a_single_false_claim_fails_the_batch() {
+	let mut rng = StdRng::seed_from_u64(1);
+	let w = DenseMLE::rand(3, &mut rng);
+	let mut acc = Accumulator::new(Transcript::new(b"app"));
+	for i in 0..4 {
+		let (p, mut x) = random_claim(&w, &mut rng);
+		if i == 2 {
+			x += ScalarField::one();
+		}
+		acc.add("w", p, x);
+	}
+	assert!(acc.finalize(|_| Some(&w)).is_err());
+}
+
+#[test]
+fn unknown_oracles_and_shape_mismatches_fail() {
+	let mut rng = StdRng::seed_from_u64(2);
+	let w = DenseMLE::rand(3, &mut rng);
+	let mut acc = Accumulator::new(Transcript::new(b"app"));
+	let (p, x) = random_claim(&w, &mut rng);
+	acc.add("w", p, x);
+	assert!(acc.finalize(|_| None).is_err());
+
+	let mut acc = Accumulator::new(Transcript::new(b"app"));
+	acc.add("w", vec![ScalarField::one(); 2], ScalarField::one());
+	assert!(acc.finalize(|_| Some(&w)).is_err());
+	assert!(Accumulator::<ScalarField>::new(Transcript::new(b"app")).finalize(|_| None).is_ok());
+}
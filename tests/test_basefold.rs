@@ -0,0 +1,133 @@
+use ark_bls12_381::Fr as ScalarField;
+use ark_ed_on_bls12_381::Fr as JubjubScalar;
+use ark_ff::{One, PrimeField};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rstest::rstest;
+use gkr::basefold::{BaseFold, DEFAULT_NUM_QUERIES};
+use gkr::circuit::{Circuit, Gate, Layer};
+use gkr::circuit_prover::{ClaimReduction, GKRProver, GKRVerifier};
+use gkr::error::Error;
+use gkr::ml_extension::{DenseMLE, IndexOrder};
+use gkr::pcs::MultilinearPCS;
+
+fn random_point<F: PrimeField>(num_vars: usize, rng: &mut StdRng) -> Vec<F> {
+	(0..num_vars).map(|_| F::rand(rng)).collect()
+}
+
+fn check_opening<F: PrimeField>(num_vars: usize, seed: u64) {
+	let mut rng = StdRng::seed_from_u64(seed);
+	let params = BaseFold::<F>::setup(5, b"basefold_test");
+	let mle: DenseMLE<F> = DenseMLE::rand(num_vars, &mut rng);
+	let point = random_point(num_vars, &mut rng);
+	let commitment = BaseFold::commit(&params, &mle);
+	let (value, proof) = BaseFold::open(&params, &mle, &point);
+	assert_eq!(value, mle.evaluate(&point));
+	assert_eq!(proof.queries.len(), DEFAULT_NUM_QUERIES);
+	assert!(BaseFold::verify(&params, &commitment, &point, value, &proof).is_ok());
+}
+
+#[rstest]
+#[case(0)]
+#[case(1)]
+#[case(4)]
+#[case(5)]
+fn openings_are_accepted_over_any_field(#[case] num_vars: usize) {
+	check_opening::<ScalarField>(num_vars, num_vars as u64);
+	// Jubjub のスカラー体は 2-adicity が 1 で FFT には向かないが，折り畳み可能な符号は使える
+	check_opening::<JubjubScalar>(num_vars, num_vars as u64);
+}
+
+#[rstest]
+fn encoding_is_linear_and_starts_from_repetition() {
+	let mut rng = StdRng::seed_from_u64(1);
+	let params = BaseFold::<ScalarField>::setup(3, b"basefold_test");
+	let mle: DenseMLE<ScalarField> = DenseMLE::rand(3, &mut rng);
+	let codeword = BaseFold::encode(&params, &mle.evaluations);
+	assert_eq!(codeword.len(), 1 << (3 + params.log_blowup));
+	// 符号は線形
+	let doubled: Vec<ScalarField> = mle.evaluations.iter().map(|x| *x + x).collect();
+	let expected: Vec<ScalarField> = codeword.iter().map(|x| *x + x).collect();
+	assert_eq!(BaseFold::encode(&params, &doubled), expected);
+	// 0 変数の表は繰り返し符号
+	let seven = ScalarField::from(7u64);
+	assert_eq!(BaseFold::encode(&params, &[seven]), vec![seven; 1 << params.log_blowup]);
+}
+
+#[rstest]
+fn index_order_does_not_change_the_commitment() {
+	let mut rng = StdRng::seed_from_u64(2);
+	let params = BaseFold::<ScalarField>::setup(4, b"basefold_test");
+	let mle: DenseMLE<ScalarField> = DenseMLE::rand(4, &mut rng);
+	assert_eq!(BaseFold::commit(&params, &mle), BaseFold::commit(&params, &mle.to_order(IndexOrder::LittleEndian)));
+}
+
+#[rstest]
+#[case::value(0)]
+#[case::round_message(1)]
+#[case::final_value(2)]
+#[case::queried_leaf(3)]
+#[case::folded_root(4)]
+#[case::commitment(5)]
+fn tampered_openings_are_rejected(#[case] target: usize) {
+	let mut rng = StdRng::seed_from_u64(3);
+	let params = BaseFold::<ScalarField>::setup(4, b"basefold_test");
+	let mle: DenseMLE<ScalarField> = DenseMLE::rand(4, &mut rng);
+	let point = random_point(4, &mut rng);
+	let mut commitment = BaseFold::commit(&params, &mle);
+	let (mut value, mut proof) = BaseFold::open(&params, &mle, &point);
+	match target {
+		0 => value += ScalarField::one(),
+		1 => proof.round_msgs[1][1] += ScalarField::one(),
+		2 => proof.final_value += ScalarField::one(),
+		3 => proof.queries[5][2].left += ScalarField::one(),
+		4 => proof.roots[0][0] ^= 1,
+		_ => commitment = BaseFold::commit(&params, &DenseMLE::rand(4, &mut rng)),
+	}
+	assert!(BaseFold::verify(&params, &commitment, &point, value, &proof).is_err());
+}
+
+#[rstest]
+fn query_count_must_match_the_parameters() {
+	let mut rng = StdRng::seed_from_u64(4);
+	let params = BaseFold::<ScalarField>::setup_with(3, 1, 8, b"basefold_test");
+	let mle: DenseMLE<ScalarField> = DenseMLE::rand(3, &mut rng);
+	let point = random_point(3, &mut rng);
+	let commitment = BaseFold::commit(&params, &mle);
+	let (value, mut proof) = BaseFold::open(&params, &mle, &point);
+	assert!(BaseFold::verify(&params, &commitment, &point, value, &proof).is_ok());
+	proof.queries.pop();
+	assert_eq!(
+		BaseFold::verify(&params, &commitment, &point, value, &proof),
+		Err(Error::LengthMismatch { what: "queries", expected: 8, found: 7 })
+	);
+}
+
+#[rstest]
+#[case(ClaimReduction::RandomCombination)]
+#[case(ClaimReduction::LineRestriction)]
+fn circuits_are_proved_with_hash_based_commitments(#[case] reduction: ClaimReduction) {
+	let mut rng = StdRng::seed_from_u64(5);
+	let circuit = Circuit::new(
+		3,
+		vec![
+			Layer { gates: vec![Gate::Mul(0, 1)] },
+			Layer { gates: vec![Gate::Add(0, 1), Gate::Input(2)] },
+			Layer { gates: vec![Gate::Mul(0, 1), Gate::Add(1, 2), Gate::Input(2)] },
+		],
+	)
+	.unwrap();
+	let params = BaseFold::<JubjubScalar>::setup(2, b"basefold_test");
+	let inputs: Vec<JubjubScalar> = random_point(3, &mut rng);
+	let (commitment, proof) = GKRProver::prove_committed::<BaseFold<JubjubScalar>>(&params, &circuit, &inputs, reduction);
+	assert_eq!(proof.proof.outputs, circuit.evaluate(&inputs)[0]);
+	assert!(GKRVerifier::verify_committed::<BaseFold<JubjubScalar>>(&params, &circuit, &commitment, &proof, reduction)
+		.is_ok());
+
+	let mut tampered = proof.clone();
+	tampered.input_evals[0] += JubjubScalar::one();
+	assert!(
+		GKRVerifier::verify_committed::<BaseFold<JubjubScalar>>(&params, &circuit, &commitment, &tampered, reduction)
+			.is_err()
+	);
+}
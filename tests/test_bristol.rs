@@ -0,0 +1,97 @@
+use ark_bls12_381::Fr as ScalarField;
+use rstest::rstest;
+use gkr::bristol::{parse_bristol, parse_bristol_with, BristolCircuit, BristolError, BristolMode};
+use gkr::builder::CircuitBuilder;
+use gkr::circuit_prover::{GKRProver, GKRVerifier};
+
+/// 1 ビットの全加算器：入力 a, b, cin，出力 (sum, cout)
+const FULL_ADDER: &str = "5 8
+3 1 1 1
+1 2
+
+2 1 0 1 3 XOR
+2 1 0 1 4 AND
+2 1 3 2 5 AND
+2 1 3 2 6 XOR
+2 1 4 5 7 XOR
+";
+
+/// 残りのゲート：出力は (a·b, ¬a, ¬b, 1 ⊕ 1)
+const OTHER_GATES: &str = "6 10
+1 2
+1 4
+1 1 0 2 INV
+1 1 1 3 EQ
+1 1 1 4 EQW
+4 2 0 2 1 3 6 7 MAND
+1 1 4 8 INV
+2 1 3 3 9 XOR
+";
+
+fn bits(x: usize, n: usize) -> Vec<bool> {
+	(0..n).map(|i| (x >> i) & 1 == 1).collect()
+}
+
+#[rstest]
+fn full_adder_adds_every_input() {
+	let circuit: BristolCircuit<ScalarField> = parse_bristol(FULL_ADDER, BristolMode::Arithmetic).unwrap();
+	assert_eq!(circuit.input_sizes, vec![1, 1, 1]);
+	assert_eq!(circuit.output_sizes, vec![2]);
+	for x in 0..8 {
+		let input = bits(x, 3);
+		let total = input.iter().filter(|&&b| b).count();
+		assert_eq!(circuit.evaluate_bits(&input), bits(total, 2));
+	}
+}
+
+#[rstest]
+fn every_gate_kind_is_supported() {
+	let circuit: BristolCircuit<ScalarField> = parse_bristol(OTHER_GATES, BristolMode::Arithmetic).unwrap();
+	for x in 0..4 {
+		let [a, b] = bits(x, 2)[..] else { unreachable!() };
+		assert_eq!(circuit.evaluate_bits(&[a, b]), vec![a && b, !a, !b, false]);
+	}
+}
+
+#[rstest]
+#[case(2)]
+#[case(3)]
+fn imported_circuits_are_provable(#[case] max_fan_in: usize) {
+	let circuit: BristolCircuit<ScalarField> =
+		parse_bristol_with(FULL_ADDER, BristolMode::Arithmetic, CircuitBuilder::with_max_fan_in(max_fan_in)).unwrap();
+	let inputs = circuit.assign_bits(&[true, false, true]);
+	let proof = GKRProver::prove_circuit(&circuit.built.circuit, &inputs);
+	assert!(GKRVerifier::verify_circuit(&circuit.built.circuit, &inputs, &proof).is_ok());
+}
+
+#[rstest]
+fn wider_gates_make_xor_shallower() {
+	let narrow: BristolCircuit<ScalarField> = parse_bristol(FULL_ADDER, BristolMode::Arithmetic).unwrap();
+	let wide: BristolCircuit<ScalarField> =
+		parse_bristol_with(FULL_ADDER, BristolMode::Arithmetic, CircuitBuilder::with_max_fan_in(3)).unwrap();
+	assert!(wide.built.circuit.depth() < narrow.built.circuit.depth());
+}
+
+#[rstest]
+fn binary_mode_needs_characteristic_two() {
+	let result = parse_bristol::<ScalarField>(FULL_ADDER, BristolMode::Binary);
+	assert!(matches!(result, Err(BristolError::UnsupportedField)));
+}
+
+#[rstest]
+#[case::missing_header("")]
+#[case::bad_counts("5\n3 1 1 1\n1 2\n")]
+#[case::bad_sizes("1 3\n2 1\n1 1\n2 1 0 1 2 AND\n")]
+#[case::gate_count("2 3\n2 1 1\n1 1\n2 1 0 1 2 AND\n")]
+#[case::arity("1 3\n2 1 1\n1 1\n2 1 0 1 AND\n")]
+#[case::unknown_gate("1 3\n2 1 1\n1 1\n2 1 0 1 2 NAND\n")]
+#[case::wrong_arity("1 3\n2 1 1\n1 1\n1 1 0 2 AND\n")]
+#[case::undefined("1 4\n2 1 1\n1 1\n2 1 0 2 3 AND\n")]
+#[case::out_of_range("1 3\n2 1 1\n1 1\n2 1 0 9 2 AND\n")]
+#[case::defined_twice("2 3\n2 1 1\n1 1\n2 1 0 1 2 AND\n2 1 0 1 2 XOR\n")]
+#[case::overwrites_input("1 3\n2 1 1\n1 1\n2 1 0 1 1 AND\n")]
+#[case::output_undefined("1 4\n2 1 1\n1 1\n2 1 0 1 2 AND\n")]
+#[case::bad_constant("1 2\n1 1\n1 1\n1 1 2 1 EQ\n")]
+fn malformed_circuits_are_rejected(#[case] input: &str) {
+	assert!(parse_bristol::<ScalarField>(input, BristolMode::Arithmetic).is_err());
+}
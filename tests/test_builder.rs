@@ -0,0 +1,121 @@
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::UniformRand;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rstest::rstest;
+use gkr::builder::CircuitBuilder;
+use gkr::circuit_prover::{GKRProver, GKRVerifier};
+use gkr::error::Error;
+
+#[rstest]
+fn built_circuits_compute_their_expressions() {
+	let mut rng = StdRng::seed_from_u64(0);
+	let mut b = CircuitBuilder::<ScalarField>::new();
+	let (x, y, z) = (b.input(), b.input(), b.input());
+	let three = b.constant(3u32.into());
+	// ((x + y)·z + 3, x·x, y)
+	let s = b.add(x, y);
+	let p = b.mul(s, z);
+	let out = b.add(p, three);
+	let sq = b.mul(x, x);
+	b.output(out);
+	b.output(sq);
+	b.output(y);
+	let built = b.build().unwrap();
+
+	assert_eq!(built.circuit.depth(), 3);
+	assert_eq!(built.wiring.len(), 3);
+	// 幅は 2 のべきに切り上げない
+	assert_eq!(built.layer_widths, built.circuit.layers.iter().map(|layer| layer.gates.len()).collect::<Vec<_>>());
+	assert_eq!(built.layer_widths[0], 3);
+	assert_eq!(built.circuit.num_inputs, 4);
+
+	let inputs: Vec<ScalarField> = (0..3).map(|_| ScalarField::rand(&mut rng)).collect();
+	let (xv, yv, zv) = (inputs[0], inputs[1], inputs[2]);
+	assert_eq!(built.evaluate(&inputs), vec![(xv + yv) * zv + ScalarField::from(3u32), xv * xv, yv]);
+
+	let assigned = built.assign(&inputs);
+	let proof = GKRProver::prove_circuit(&built.circuit, &assigned);
+	assert_eq!(proof.outputs[..3], built.evaluate(&inputs)[..]);
+	assert!(GKRVerifier::verify_circuit(&built.circuit, &assigned, &proof).is_ok());
+}
+
+#[rstest]
+fn builders_without_outputs_are_rejected() {
+	let mut b = CircuitBuilder::<ScalarField>::new();
+	b.input();
+	assert_eq!(b.build().err(), Some(Error::InvalidCircuit("builder has no outputs")));
+}
+
+#[rstest]
+fn passing_an_input_through_needs_one_layer() {
+	let mut b = CircuitBuilder::<ScalarField>::new();
+	let x = b.input();
+	b.output(x);
+	let built = b.build().unwrap();
+	assert_eq!(built.circuit.depth(), 1);
+	assert_eq!(built.evaluate(&[7u32.into()]), vec![ScalarField::from(7u32)]);
+}
+
+#[rstest]
+fn small_constants_become_constant_gates() {
+	use gkr::circuit::Gate;
+	let mut rng = StdRng::seed_from_u64(3);
+	let mut b = CircuitBuilder::<ScalarField>::new();
+	let (x, y) = (b.input(), b.input());
+	let seven = b.small_constant(7);
+	// (x·y + 7, 7)
+	let p = b.mul(x, y);
+	let out = b.add(p, seven);
+	b.output(out);
+	b.output(seven);
+	let built = b.build().unwrap();
+
+	// 定数は入力に並ばず，最初の層の定数ゲートとして中継される
+	assert!(built.constants.is_empty());
+	assert_eq!(built.circuit.num_inputs, 2);
+	assert!(built.circuit.layers.last().unwrap().gates.contains(&Gate::Constant(7)));
+	assert!(built.wiring.last().unwrap().constant.evaluations.values().any(|c| *c == ScalarField::from(7u32)));
+	assert!(built.wiring[0].relay.evaluations.len() == 1);
+
+	let inputs: Vec<ScalarField> = (0..2).map(|_| ScalarField::rand(&mut rng)).collect();
+	let seven = ScalarField::from(7u32);
+	assert_eq!(built.evaluate(&inputs), vec![inputs[0] * inputs[1] + seven, seven]);
+
+	let assigned = built.assign(&inputs);
+	let proof = GKRProver::prove_circuit(&built.circuit, &assigned);
+	assert!(GKRVerifier::verify_circuit(&built.circuit, &assigned, &proof).is_ok());
+	// 定数を変えた回路の主張としては通らない
+	let mut other = built.circuit.clone();
+	for gate in other.layers.last_mut().unwrap().gates.iter_mut() {
+		if *gate == Gate::Constant(7) {
+			*gate = Gate::Constant(8);
+		}
+	}
+	assert!(GKRVerifier::verify_circuit(&other, &assigned, &proof).is_err());
+}
+
+#[rstest]
+#[case(2, 4)]
+#[case(4, 2)]
+#[case(16, 1)]
+fn wide_fan_in_gives_shallower_circuits(#[case] max_fan_in: usize, #[case] depth: usize) {
+	let mut rng = StdRng::seed_from_u64(max_fan_in as u64);
+	let mut b = CircuitBuilder::<ScalarField>::with_max_fan_in(max_fan_in);
+	let xs: Vec<_> = (0..16).map(|_| b.input()).collect();
+	let total = b.sum(&xs);
+	let prod = b.product(&xs[..9]);
+	b.output(total);
+	b.output(prod);
+	let built = b.build().unwrap();
+	// 9 因子の積の木の深さ
+	assert_eq!(built.circuit.depth(), depth);
+	assert!((0..built.circuit.depth()).all(|i| built.circuit.fan_in(i) <= max_fan_in));
+
+	let inputs: Vec<ScalarField> = (0..16).map(|_| ScalarField::rand(&mut rng)).collect();
+	let expected = vec![inputs.iter().sum::<ScalarField>(), inputs[..9].iter().product::<ScalarField>()];
+	assert_eq!(built.evaluate(&inputs), expected);
+	let assigned = built.assign(&inputs);
+	let proof = GKRProver::prove_circuit(&built.circuit, &assigned);
+	assert!(GKRVerifier::verify_circuit(&built.circuit, &assigned, &proof).is_ok());
+}
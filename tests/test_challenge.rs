@@ -0,0 +1,98 @@
+use ark_bls12_381::Fr as ScalarField;
+use rstest::rstest;
+use gkr::challenge::{seeded_rng, ChallengeGenerator, ChallengePolicy, ChallengeSampler};
+use gkr::prover::LinearGKRProver;
+use gkr::simulate;
+use gkr::sumcheck::get_r;
+use gkr::transcript::labels;
+use gkr::verifier::LinearGKRVerifier;
+
+fn scripted(values: &[u64]) -> impl FnMut(u64) -> ScalarField + '_ {
+	move |counter| ScalarField::from(values[counter as usize])
+}
+
+#[rstest]
+fn strict_policy_resamples_degenerate_and_repeated() {
+	let values = [0, 1, 5, 5, 0, 7, 5];
+	let mut sampler = ChallengeSampler::<ScalarField>::new(ChallengePolicy::strict());
+	let mut draw = scripted(&values);
+	assert_eq!(sampler.sample(&mut draw), ScalarField::from(5u64));
+	assert_eq!(sampler.sample(&mut draw), ScalarField::from(7u64));
+	assert_eq!(sampler.attempts(), 6);
+	// 新しいフェーズでは 5 を再び使える
+	sampler.begin_phase();
+	assert_eq!(sampler.sample(&mut draw), ScalarField::from(5u64));
+}
+
+#[rstest]
+fn default_policy_accepts_everything() {
+	let values = [0, 0, 1];
+	let mut sampler = ChallengeSampler::<ScalarField>::default();
+	let mut draw = scripted(&values);
+	assert_eq!(sampler.sample(&mut draw), ScalarField::from(0u64));
+	assert_eq!(sampler.sample(&mut draw), ScalarField::from(0u64));
+	assert_eq!(sampler.attempts(), 2);
+}
+
+#[rstest]
+fn partial_policies() {
+	let only_degenerate = ChallengePolicy { reject_degenerate: true, reject_repeated: false };
+	let mut sampler = ChallengeSampler::<ScalarField>::new(only_degenerate);
+	assert!(!sampler.accepts(&ScalarField::from(1u64)));
+	let mut draw = scripted(&[3, 3]);
+	sampler.sample(&mut draw);
+	assert!(sampler.accepts(&ScalarField::from(3u64)));
+}
+
+#[rstest]
+#[case(0)]
+#[case(42)]
+fn seeded_challenges_are_reproducible(#[case] seed: u64) {
+	let draw = |seed: u64| {
+		let mut rng = seeded_rng(seed);
+		(0..4).map(|_| get_r::<ScalarField, _>(&mut rng).unwrap()).collect::<Vec<_>>()
+	};
+	assert_eq!(draw(seed), draw(seed));
+	assert_ne!(draw(seed), draw(seed + 1));
+}
+
+/// 吸収したラベルを記録し，チャレンジは 2, 3, 4, ... を順に返す
+#[derive(Default)]
+struct Recording {
+	labels: Vec<Vec<u8>>,
+	next: u64,
+}
+
+impl ChallengeGenerator<ScalarField> for Recording {
+	fn absorb(&mut self, label: &[u8], _msg: &[ScalarField]) {
+		self.labels.push(label.to_vec());
+	}
+
+	fn challenge(&mut self, _label: &[u8]) -> ScalarField {
+		self.next += 1;
+		ScalarField::from(self.next + 1)
+	}
+}
+
+#[rstest]
+fn linear_gkr_accepts_any_challenge_generator() {
+	let (relation, witness) = simulate::random_instance(3, 20, &mut seeded_rng(1));
+	let claimed_sum = simulate::reference_claimed_sum(&relation, &witness);
+	let pre = LinearGKRProver::precompute(&relation.f1, &witness.f2, &witness.f3);
+
+	// 対話版：同じシードの rng を両側で使う
+	let proof = LinearGKRProver::prove_precomputed(&pre, &relation.g, &mut seeded_rng(7));
+	assert!(LinearGKRVerifier::verify(3, claimed_sum, &proof, &mut seeded_rng(7)).is_ok());
+	assert!(LinearGKRVerifier::verify(3, claimed_sum, &proof, &mut seeded_rng(8)).is_err());
+
+	// 独自の導出
+	let mut recording = Recording::default();
+	let proof = LinearGKRProver::prove_precomputed(&pre, &relation.g, &mut Recording::default());
+	assert!(LinearGKRVerifier::verify(3, claimed_sum, &proof, &mut recording).is_ok());
+	assert_eq!(recording.next, 6);
+	let mut expected = vec![labels::CLAIMED_SUM.to_vec()];
+	expected.extend(std::iter::repeat_n(labels::PHASE1_ROUND.to_vec(), 3));
+	expected.extend(std::iter::repeat_n(labels::PHASE2_ROUND.to_vec(), 3));
+	expected.push(labels::FINAL_EVALS.to_vec());
+	assert_eq!(recording.labels, expected);
+}
@@ -0,0 +1,47 @@
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::UniformRand;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rstest::rstest;
+use gkr::chunked_mle::ChunkedMLE;
+use gkr::ml_extension::{DenseMLE, IndexOrder};
+
+#[rstest]
+#[case(10, 3)]
+#[case(10, 10)]
+#[case(6, 8)]
+#[case(0, 2)]
+fn folding_pages_matches_the_dense_table(#[case] num_vars: usize, #[case] page_vars: usize) {
+	let mut rng = StdRng::seed_from_u64(num_vars as u64);
+	let dense = DenseMLE::<ScalarField>::rand(num_vars, &mut rng);
+	let mut chunked = ChunkedMLE::from_dense(&dense, page_vars);
+	assert_eq!(chunked.to_dense().evaluations, dense.evaluations);
+	assert_eq!(chunked.get((1 << num_vars) - 1), dense.evaluations[(1 << num_vars) - 1]);
+
+	let point: Vec<ScalarField> = (0..num_vars).map(|_| ScalarField::rand(&mut rng)).collect();
+	assert_eq!(chunked.evaluate(&point), dense.evaluate(&point));
+
+	let mut folded = dense.clone();
+	for (i, r) in point.iter().enumerate() {
+		let pages = chunked.num_pages();
+		chunked.fix_variable_in_place(*r);
+		folded.fix_first_variable_in_place(*r);
+		assert_eq!(chunked.num_vars(), num_vars - i - 1);
+		assert_eq!(chunked.iter().copied().collect::<Vec<_>>(), folded.evaluations);
+		// ページが複数あるうちは，上半分のページを手放す
+		assert_eq!(chunked.num_pages(), (pages / 2).max(1));
+	}
+}
+
+#[rstest]
+fn pages_are_built_from_a_closure_or_any_index_order() {
+	let chunked = ChunkedMLE::from_fn(5, 2, |i| ScalarField::from(i as u64));
+	assert_eq!(chunked.num_pages(), 8);
+	assert_eq!(chunked.page_vars(), 2);
+	assert_eq!(chunked.to_dense().evaluations, DenseMLE::from_fn(5, |i| ScalarField::from(i as u64)).evaluations);
+
+	let mut rng = StdRng::seed_from_u64(3);
+	let dense = DenseMLE::<ScalarField>::rand(4, &mut rng);
+	let little = ChunkedMLE::from_dense(&dense.to_order(IndexOrder::LittleEndian), 2);
+	assert_eq!(little.to_dense().evaluations, dense.evaluations);
+}
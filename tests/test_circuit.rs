@@ -0,0 +1,47 @@
+use ark_bls12_381::Fr as ScalarField;
+use rstest::rstest;
+use gkr::circuit::{Circuit, Gate, Layer};
+
+fn layer(gates: Vec<Gate>) -> Layer {
+	Layer { gates }
+}
+
+#[rstest]
+fn evaluate_produces_every_layer() {
+	// 出力 = (a + b) · c，c は Input ゲートで運ぶ
+	let circuit = Circuit::new(
+		3,
+		vec![layer(vec![Gate::Mul(0, 1)]), layer(vec![Gate::Add(0, 1), Gate::Input(2)])],
+	)
+	.unwrap();
+	let inputs: Vec<ScalarField> = vec![2u32.into(), 3u32.into(), 7u32.into()];
+	let values = circuit.evaluate(&inputs);
+	assert_eq!(values.len(), circuit.depth() + 1);
+	assert_eq!(values[0], vec![ScalarField::from(35u32)]);
+	assert_eq!(values[1], vec![ScalarField::from(5u32), ScalarField::from(7u32)]);
+	assert_eq!(values[2], inputs);
+	assert_eq!(circuit.input_width(0), 2);
+	assert_eq!(circuit.input_width(1), 3);
+}
+
+#[rstest]
+#[case(0, vec![layer(vec![Gate::Add(0, 0)])])]
+#[case(2, vec![])]
+#[case(2, vec![layer(vec![])])]
+#[case(2, vec![layer(vec![Gate::Mul(0, 2)])])]
+#[case(2, vec![layer(vec![Gate::Input(0)]), layer(vec![Gate::Add(0, 1), Gate::Input(2)])])]
+#[case(2, vec![layer(vec![Gate::Input(1)]), layer(vec![Gate::Add(0, 1)])])]
+#[case(2, vec![layer(vec![Gate::Sum(vec![0, 1, 2])])])]
+#[case(2, vec![layer(vec![Gate::Product(vec![1, 1, 5])])])]
+fn invalid_wiring_is_rejected(#[case] num_inputs: usize, #[case] layers: Vec<Layer>) {
+	assert!(Circuit::new(num_inputs, layers).is_err());
+}
+
+#[rstest]
+fn wide_gates_evaluate_every_input() {
+	let circuit = Circuit::new(4, vec![layer(vec![Gate::Sum(vec![0, 1, 2, 3]), Gate::Product(vec![1, 2, 3]), Gate::Product(vec![])])]).unwrap();
+	let inputs: Vec<ScalarField> = vec![2u32.into(), 3u32.into(), 5u32.into(), 7u32.into()];
+	assert_eq!(circuit.evaluate(&inputs)[0], vec![ScalarField::from(17u32), ScalarField::from(105u32), ScalarField::from(1u32)]);
+	assert_eq!(circuit.fan_in(0), 3);
+	assert_eq!(Gate::Sum(vec![0, 1, 2]).fan_in(), 1);
+}
@@ -0,0 +1,101 @@
+use ark_bls12_381::Fr as ScalarField;
+use rstest::rstest;
+use gkr::circuit::{Circuit, Gate, Layer};
+use gkr::circuit_format::{CIRCUIT_FORMAT_VERSION, CIRCUIT_MAGIC};
+use gkr::examples_circuits::standard_suite;
+use gkr::serialization::SerializationError;
+
+fn every_gate_kind() -> Circuit {
+	Circuit::new(
+		4,
+		vec![
+			Layer { gates: vec![Gate::Mul(0, 1), Gate::Sum(vec![1, 2, 3]), Gate::Product(vec![])] },
+			Layer {
+				gates: vec![
+					Gate::Add(0, 1),
+					Gate::Input(3),
+					Gate::Constant(u64::MAX),
+					Gate::Product(vec![0, 1, 2, 3]),
+				],
+			},
+		],
+	)
+	.unwrap()
+}
+
+fn circuits() -> Vec<Circuit> {
+	let mut circuits: Vec<Circuit> = standard_suite::<ScalarField>().into_iter().map(|example| example.circuit).collect();
+	circuits.push(every_gate_kind());
+	circuits
+}
+
+#[rstest]
+fn binary_and_json_round_trip() {
+	for circuit in circuits() {
+		let bytes = circuit.serialize();
+		assert_eq!(bytes[..4], CIRCUIT_MAGIC);
+		assert_eq!(bytes[4], CIRCUIT_FORMAT_VERSION);
+		assert_eq!(Circuit::deserialize(&bytes).unwrap(), circuit);
+		let json = circuit.to_json();
+		assert_eq!(Circuit::from_json(&json).unwrap(), circuit);
+		assert!(bytes.len() < json.len());
+	}
+}
+
+#[rstest]
+fn binary_layout_is_stable() {
+	let circuit = Circuit::new(2, vec![Layer { gates: vec![Gate::Mul(0, 1), Gate::Constant(300)] }]).unwrap();
+	let mut expected = b"GKRC".to_vec();
+	expected.extend([1, 2, 1, 2, 1, 0, 1, 3, 0xac, 0x02]);
+	assert_eq!(circuit.serialize(), expected);
+}
+
+#[rstest]
+fn json_is_readable() {
+	let json = r#"{"num_inputs": 3, "layers": [[{"mul": [0, 1]}], [{"add": [0, 1]}, {"input": 2}]]}"#;
+	let circuit = Circuit::from_json(json).unwrap();
+	let values = circuit.evaluate(&[ScalarField::from(2u32), ScalarField::from(3u32), ScalarField::from(7u32)]);
+	assert_eq!(values[0], vec![ScalarField::from(35u32)]);
+}
+
+#[rstest]
+fn malformed_bytes_are_rejected() {
+	let bytes = every_gate_kind().serialize();
+	for len in 0..bytes.len() {
+		assert!(Circuit::deserialize(&bytes[..len]).is_err());
+	}
+	let mut bad_magic = bytes.clone();
+	bad_magic[0] ^= 1;
+	assert!(matches!(Circuit::deserialize(&bad_magic), Err(SerializationError::BadMagic)));
+	let mut bad_version = bytes.clone();
+	bad_version[4] = CIRCUIT_FORMAT_VERSION + 1;
+	assert!(matches!(Circuit::deserialize(&bad_version), Err(SerializationError::UnsupportedVersion(_))));
+	let mut trailing = bytes.clone();
+	trailing.push(0);
+	assert!(matches!(Circuit::deserialize(&trailing), Err(SerializationError::TrailingBytes(1))));
+	// 最初のゲートの tag
+	let mut unknown = bytes.clone();
+	unknown[8] = 9;
+	assert!(matches!(Circuit::deserialize(&unknown), Err(SerializationError::UnknownGate(9))));
+	// Mul(0, 1) を Mul(0, 7) にすると下の層の外を読む
+	let mut out_of_range = bytes;
+	out_of_range[10] = 7;
+	assert!(matches!(Circuit::deserialize(&out_of_range), Err(SerializationError::Circuit(_))));
+	// 巨大な層数でも確保せずに拒否する
+	let mut huge = b"GKRC".to_vec();
+	huge.extend([1, 1, 0xff, 0xff, 0xff, 0xff, 0x0f]);
+	assert!(matches!(Circuit::deserialize(&huge), Err(SerializationError::UnexpectedEnd)));
+}
+
+#[rstest]
+#[case("not json")]
+#[case(r#"{"layers": [[{"input": 0}]]}"#)]
+#[case(r#"{"num_inputs": 1, "layers": [{"input": 0}]}"#)]
+#[case(r#"{"num_inputs": 1, "layers": [[{"xor": [0, 0]}]]}"#)]
+#[case(r#"{"num_inputs": 1, "layers": [[{"add": [0]}]]}"#)]
+#[case(r#"{"num_inputs": 1, "layers": [[{"add": [0, 0], "mul": [0, 0]}]]}"#)]
+#[case(r#"{"num_inputs": 1, "layers": [[{"constant": -1}]]}"#)]
+#[case(r#"{"num_inputs": 1, "layers": [[{"input": 1}]]}"#)]
+fn malformed_json_is_rejected(#[case] json: &str) {
+	assert!(Circuit::from_json(json).is_err());
+}
@@ -0,0 +1,324 @@
+use ark_bls12_381::Fr as ScalarField;
+use ark_ed_on_bls12_381::Fr as JubjubScalar;
+use ark_ff::{One, UniformRand, Zero};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rstest::rstest;
+use gkr::circuit::{Circuit, Gate, Layer};
+use gkr::circuit_prover::{ClaimReduction, CombinedClaim, GKRProver, GKRVerifier};
+use gkr::error::Error;
+use gkr::pcs::{JubjubHyrax, MultilinearKzg};
+use gkr::soundness::SecurityConfig;
+use gkr::transcript::Transcript;
+use gkr::examples_circuits;
+
+fn layer(gates: Vec<Gate>) -> Layer {
+	Layer { gates }
+}
+
+/// 幅が 2 のべきでなく，Input ゲートを含む回路
+fn uneven_circuit() -> Circuit {
+	Circuit::new(
+		3,
+		vec![
+			layer(vec![Gate::Mul(0, 1)]),
+			layer(vec![Gate::Add(0, 1), Gate::Input(2)]),
+			layer(vec![Gate::Mul(0, 1), Gate::Add(1, 2), Gate::Input(2)]),
+		],
+	)
+	.unwrap()
+}
+
+#[rstest]
+#[case(ClaimReduction::RandomCombination)]
+#[case(ClaimReduction::LineRestriction)]
+fn honest_proofs_are_accepted(#[case] reduction: ClaimReduction) {
+	let mut rng = StdRng::seed_from_u64(0);
+	for example in examples_circuits::standard_suite::<ScalarField>() {
+		let inputs = example.random_inputs(&mut rng);
+		let proof = GKRProver::prove_circuit_with(&example.circuit, &inputs, reduction);
+		assert_eq!(proof.outputs, example.expected_output(&inputs), "{}", example.name);
+		assert!(
+			GKRVerifier::verify_circuit_with(&example.circuit, &inputs, &proof, reduction).is_ok(),
+			"{}",
+			example.name
+		);
+	}
+	let circuit = uneven_circuit();
+	let inputs: Vec<ScalarField> = (0..3).map(|_| ScalarField::rand(&mut rng)).collect();
+	let proof = GKRProver::prove_circuit_with(&circuit, &inputs, reduction);
+	assert!(GKRVerifier::verify_circuit_with(&circuit, &inputs, &proof, reduction).is_ok());
+}
+
+#[rstest]
+fn line_restrictions_are_checked() {
+	let mut rng = StdRng::seed_from_u64(2);
+	let circuit = uneven_circuit();
+	let inputs: Vec<ScalarField> = (0..3).map(|_| ScalarField::rand(&mut rng)).collect();
+	let proof = GKRProver::prove_circuit_with(&circuit, &inputs, ClaimReduction::LineRestriction);
+	assert_eq!(proof.line_restrictions.len(), circuit.depth());
+	// 証明と異なる還元方法では受理しない
+	assert!(GKRVerifier::verify_circuit(&circuit, &inputs, &proof).is_err());
+	for i in 0..circuit.depth() {
+		for j in 0..proof.line_restrictions[i].len() {
+			let mut tampered = proof.clone();
+			tampered.line_restrictions[i][j] += ScalarField::one();
+			assert!(GKRVerifier::verify_circuit_with(&circuit, &inputs, &tampered, ClaimReduction::LineRestriction).is_err());
+		}
+	}
+}
+
+#[rstest]
+#[case::outputs(0)]
+#[case::layer_message(1)]
+#[case::final_evaluation(2)]
+#[case::inputs(3)]
+fn tampering_is_rejected(#[case] target: usize) {
+	let mut rng = StdRng::seed_from_u64(1);
+	let circuit = uneven_circuit();
+	let mut inputs: Vec<ScalarField> = (0..3).map(|_| ScalarField::rand(&mut rng)).collect();
+	let mut proof = GKRProver::prove_circuit(&circuit, &inputs);
+	match target {
+		0 => proof.outputs[0] += ScalarField::one(),
+		1 => proof.layer_proofs[1].phase1_msgs[0][0] += ScalarField::one(),
+		2 => proof.layer_proofs[2].f2_at_u += ScalarField::one(),
+		_ => inputs[2] += ScalarField::one(),
+	}
+	assert!(GKRVerifier::verify_circuit(&circuit, &inputs, &proof).is_err());
+}
+
+#[rstest]
+#[case(1, 2)]
+#[case(3, 5)]
+#[case(3, 8)]
+fn combined_claim_matches_its_weights(#[case] l: usize, #[case] len: usize) {
+	let mut rng = StdRng::seed_from_u64(l as u64);
+	let values: Vec<ScalarField> = (0..len).map(|_| ScalarField::rand(&mut rng)).collect();
+	let u: Vec<ScalarField> = (0..l).map(|_| ScalarField::rand(&mut rng)).collect();
+	let v: Vec<ScalarField> = (0..l).map(|_| ScalarField::rand(&mut rng)).collect();
+	let claim = CombinedClaim::reduce(&mut Transcript::new(b"test_circuit_prover"), u, v);
+	// 重みは定数 1 の配線を含む W 側の表なので，V の部分だけが和に効く
+	let weights = claim.weights();
+	assert_eq!(weights.len(), 2 << l);
+	let sum: ScalarField = values.iter().zip(weights.iter()).map(|(a, b)| *a * b).sum();
+	assert_eq!(sum, claim.evaluate(&values));
+	assert!(weights[1 << l..].iter().all(|w| w.is_zero()));
+}
+
+/// 因子 3, 4 の積と多入力の和を含む回路
+fn wide_circuit() -> Circuit {
+	Circuit::new(
+		5,
+		vec![
+			layer(vec![Gate::Product(vec![0, 1, 2]), Gate::Add(2, 3)]),
+			layer(vec![Gate::Sum(vec![0, 1, 2, 3]), Gate::Product(vec![0, 1, 2, 3]), Gate::Mul(3, 4), Gate::Constant(9)]),
+		],
+	)
+	.unwrap()
+}
+
+#[rstest]
+#[case(ClaimReduction::RandomCombination)]
+#[case(ClaimReduction::LineRestriction)]
+fn wide_layers_use_the_fan_in_sumcheck(#[case] reduction: ClaimReduction) {
+	let mut rng = StdRng::seed_from_u64(5);
+	let circuit = wide_circuit();
+	let inputs: Vec<ScalarField> = (0..5).map(|_| ScalarField::rand(&mut rng)).collect();
+	let proof = GKRProver::prove_circuit_with(&circuit, &inputs, reduction);
+	assert_eq!(proof.outputs, circuit.evaluate(&inputs)[0]);
+	assert!(proof.layer_proofs.is_empty());
+	assert_eq!(proof.wide_layer_proofs.iter().map(|p| p.fan_in()).collect::<Vec<_>>(), vec![3, 4]);
+	assert!(GKRVerifier::verify_circuit_with(&circuit, &inputs, &proof, reduction).is_ok());
+
+	let mut tampered = proof.clone();
+	tampered.wide_layer_proofs[1].phase_msgs[3][0][1] += ScalarField::one();
+	assert!(GKRVerifier::verify_circuit_with(&circuit, &inputs, &tampered, reduction).is_err());
+	let mut tampered = proof.clone();
+	tampered.outputs[0] += ScalarField::one();
+	assert!(GKRVerifier::verify_circuit_with(&circuit, &inputs, &tampered, reduction).is_err());
+}
+
+#[rstest]
+fn combined_claims_of_many_points_match_their_weights() {
+	let mut rng = StdRng::seed_from_u64(6);
+	let values: Vec<ScalarField> = (0..6).map(|_| ScalarField::rand(&mut rng)).collect();
+	let points: Vec<Vec<ScalarField>> = (0..4).map(|_| (0..3).map(|_| ScalarField::rand(&mut rng)).collect()).collect();
+	let claim = CombinedClaim::reduce_many(&mut Transcript::new(b"test_circuit_prover"), points);
+	assert_eq!(claim.coeffs.len(), 4);
+	let sum: ScalarField = values.iter().zip(claim.weights().iter()).map(|(a, b)| *a * b).sum();
+	assert_eq!(sum, claim.evaluate(&values));
+}
+
+#[rstest]
+fn layers_of_a_thousand_gates_need_no_padding() {
+	let mut rng = StdRng::seed_from_u64(7);
+	let n = 1000;
+	let circuit = Circuit::new(
+		n,
+		vec![
+			layer(vec![Gate::Sum((0..n).collect()), Gate::Mul(0, n - 1), Gate::Input(3)]),
+			layer((0..n).map(|i| Gate::Mul(i, (i + 1) % n)).collect()),
+		],
+	)
+	.unwrap();
+	let inputs: Vec<ScalarField> = (0..n).map(|_| ScalarField::rand(&mut rng)).collect();
+	let proof = GKRProver::prove_circuit(&circuit, &inputs);
+	assert_eq!(proof.outputs.len(), 3);
+	assert!(GKRVerifier::verify_circuit(&circuit, &inputs, &proof).is_ok());
+
+	// 埋めた位置を出力や入力として主張しても受理しない
+	let mut padded = proof.clone();
+	padded.outputs.push(ScalarField::zero());
+	assert!(GKRVerifier::verify_circuit(&circuit, &inputs, &padded).is_err());
+	let mut longer = inputs.clone();
+	longer.resize(1024, ScalarField::zero());
+	assert!(GKRVerifier::verify_circuit(&circuit, &longer, &proof).is_err());
+}
+
+#[rstest]
+#[case::single(1)]
+#[case::padded_copies(3)]
+#[case::power_of_two(4)]
+fn batches_of_one_circuit_are_proved_together(#[case] num_copies: usize) {
+	let mut rng = StdRng::seed_from_u64(num_copies as u64);
+	for circuit in [uneven_circuit(), wide_circuit()] {
+		let inputs: Vec<Vec<ScalarField>> =
+			(0..num_copies).map(|_| (0..circuit.num_inputs).map(|_| ScalarField::rand(&mut rng)).collect()).collect();
+		let proof = GKRProver::prove_batch(&circuit, &inputs);
+		for (outputs, x) in proof.outputs.iter().zip(inputs.iter()) {
+			assert_eq!(*outputs, circuit.evaluate(x)[0]);
+		}
+		assert!(GKRVerifier::verify_batch(&circuit, &inputs, &proof).is_ok());
+
+		// どれか 1 つのコピーの出力や入力を変えれば受理しない
+		let mut tampered = proof.clone();
+		tampered.outputs[num_copies - 1][0] += ScalarField::one();
+		assert!(GKRVerifier::verify_batch(&circuit, &inputs, &tampered).is_err());
+		let mut other = inputs.clone();
+		other[0][1] += ScalarField::one();
+		assert!(GKRVerifier::verify_batch(&circuit, &other, &proof).is_err());
+	}
+}
+
+#[rstest]
+fn batches_must_match_their_instances() {
+	let mut rng = StdRng::seed_from_u64(9);
+	let circuit = uneven_circuit();
+	let inputs: Vec<Vec<ScalarField>> = (0..2).map(|_| (0..3).map(|_| ScalarField::rand(&mut rng)).collect()).collect();
+	let proof = GKRProver::prove_batch(&circuit, &inputs);
+	assert_eq!(
+		GKRVerifier::verify_batch(&circuit, &inputs[..1], &proof).err(),
+		Some(Error::LengthMismatch { what: "batch outputs", expected: 1, found: 2 })
+	);
+	assert!(GKRVerifier::verify_batch(&circuit, &[], &proof).is_err());
+	assert!(GKRVerifier::verify_batch(&circuit, &inputs[..1], &GKRProver::prove_batch(&circuit, &inputs[..1])).is_ok());
+}
+
+#[rstest]
+#[case(SecurityConfig::Single, 1)]
+#[case(SecurityConfig::Repetitions(3), 3)]
+#[case(SecurityConfig::TargetBits(128), 1)]
+#[case(SecurityConfig::TargetBits(400), 2)]
+fn repeated_proofs_use_the_configured_count(#[case] config: SecurityConfig, #[case] count: usize) {
+	let mut rng = StdRng::seed_from_u64(count as u64);
+	for circuit in [uneven_circuit(), wide_circuit()] {
+		let inputs: Vec<ScalarField> = (0..circuit.num_inputs).map(|_| ScalarField::rand(&mut rng)).collect();
+		let proof = GKRProver::prove_repeated(&circuit, &inputs, &config).unwrap();
+		assert_eq!(proof.repetitions.len(), count);
+		assert_eq!(proof.outputs(), &circuit.evaluate(&inputs)[0][..]);
+		assert!(GKRVerifier::verify_repeated(&circuit, &inputs, &proof, &config).is_ok());
+		// 各回は別々のチャレンジで還元する
+		if count > 1 {
+			assert_ne!(proof.repetitions[0], proof.repetitions[1]);
+		}
+	}
+}
+
+#[rstest]
+fn repeated_proofs_are_checked_in_every_run() {
+	let mut rng = StdRng::seed_from_u64(5);
+	let circuit = uneven_circuit();
+	let inputs: Vec<ScalarField> = (0..3).map(|_| ScalarField::rand(&mut rng)).collect();
+	let config = SecurityConfig::Repetitions(3);
+	let proof = GKRProver::prove_repeated(&circuit, &inputs, &config).unwrap();
+
+	// 回数は検証側の設定で決まる
+	assert_eq!(
+		GKRVerifier::verify_repeated(&circuit, &inputs, &proof, &SecurityConfig::Repetitions(2)).err(),
+		Some(Error::LengthMismatch { what: "repetitions", expected: 2, found: 3 })
+	);
+	let mut tampered = proof.clone();
+	tampered.repetitions[2].outputs[0] += ScalarField::one();
+	assert_eq!(
+		GKRVerifier::verify_repeated(&circuit, &inputs, &tampered, &config).err(),
+		Some(Error::MalformedProof("repetitions claim different outputs"))
+	);
+	let mut tampered = proof.clone();
+	tampered.repetitions[1].layer_proofs[0].claimed_sum += ScalarField::one();
+	assert!(GKRVerifier::verify_repeated(&circuit, &inputs, &tampered, &config).is_err());
+	// 1 回目だけを差し替えても後の回の transcript が合わない
+	let mut swapped = proof.clone();
+	swapped.repetitions.swap(0, 1);
+	assert!(GKRVerifier::verify_repeated(&circuit, &inputs, &swapped, &config).is_err());
+}
+
+#[rstest]
+#[case(ClaimReduction::RandomCombination)]
+#[case(ClaimReduction::LineRestriction)]
+fn committed_inputs_are_verified_by_openings(#[case] reduction: ClaimReduction) {
+	let mut rng = StdRng::seed_from_u64(11);
+	let (pp, vp) = MultilinearKzg::setup(4, &mut rng);
+	for circuit in [uneven_circuit(), wide_circuit()] {
+		let inputs: Vec<ScalarField> = (0..circuit.num_inputs).map(|_| ScalarField::rand(&mut rng)).collect();
+		let (commitment, proof) = GKRProver::prove_committed::<MultilinearKzg>(&pp, &circuit, &inputs, reduction);
+		assert_eq!(proof.proof.outputs, circuit.evaluate(&inputs)[0]);
+		assert!(GKRVerifier::verify_committed::<MultilinearKzg>(&vp, &circuit, &commitment, &proof, reduction).is_ok());
+
+		// 別の入力へのコミットメントでは受理しない
+		let mut other = inputs.clone();
+		other[0] += ScalarField::one();
+		let (other_commitment, _) = GKRProver::prove_committed::<MultilinearKzg>(&pp, &circuit, &other, reduction);
+		assert!(GKRVerifier::verify_committed::<MultilinearKzg>(&vp, &circuit, &other_commitment, &proof, reduction)
+			.is_err());
+	}
+}
+
+#[rstest]
+#[case::output(0)]
+#[case::input_evaluation(1)]
+#[case::missing_opening(2)]
+fn tampered_committed_proofs_are_rejected(#[case] target: usize) {
+	let mut rng = StdRng::seed_from_u64(12);
+	let (pp, vp) = MultilinearKzg::setup(2, &mut rng);
+	let circuit = uneven_circuit();
+	let inputs: Vec<ScalarField> = (0..3).map(|_| ScalarField::rand(&mut rng)).collect();
+	let reduction = ClaimReduction::RandomCombination;
+	let (commitment, mut proof) = GKRProver::prove_committed::<MultilinearKzg>(&pp, &circuit, &inputs, reduction);
+	match target {
+		0 => proof.proof.outputs[0] += ScalarField::one(),
+		1 => proof.input_evals[1] += ScalarField::one(),
+		_ => {
+			proof.openings.pop();
+		}
+	}
+	assert!(GKRVerifier::verify_committed::<MultilinearKzg>(&vp, &circuit, &commitment, &proof, reduction).is_err());
+}
+
+#[rstest]
+#[case(ClaimReduction::RandomCombination)]
+#[case(ClaimReduction::LineRestriction)]
+fn inputs_committed_with_hyrax_need_no_trusted_setup(#[case] reduction: ClaimReduction) {
+	let mut rng = StdRng::seed_from_u64(13);
+	let params = JubjubHyrax::setup(4, b"circuit_prover_test");
+	for circuit in [uneven_circuit(), wide_circuit()] {
+		let inputs: Vec<JubjubScalar> = (0..circuit.num_inputs).map(|_| JubjubScalar::rand(&mut rng)).collect();
+		let (commitment, proof) = GKRProver::prove_committed::<JubjubHyrax>(&params, &circuit, &inputs, reduction);
+		assert_eq!(proof.proof.outputs, circuit.evaluate(&inputs)[0]);
+		assert!(GKRVerifier::verify_committed::<JubjubHyrax>(&params, &circuit, &commitment, &proof, reduction).is_ok());
+
+		let mut tampered = proof.clone();
+		tampered.openings[0][0] += JubjubScalar::one();
+		assert!(GKRVerifier::verify_committed::<JubjubHyrax>(&params, &circuit, &commitment, &tampered, reduction)
+			.is_err());
+	}
+}
@@ -0,0 +1,97 @@
+#![cfg(feature = "cli")]
+
+use rstest::rstest;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use gkr::cli::{run, CliError};
+use gkr::wiring::{binary_tree_circuit, TreeOp};
+
+/// テストごとの作業ディレクトリに 2^3 入力の総積回路と入力 1..=8 を置く
+fn workspace(name: &str) -> PathBuf {
+	let dir = std::env::temp_dir().join(format!("gkr-cli-{}-{}", name, std::process::id()));
+	std::fs::create_dir_all(&dir).unwrap();
+	let circuit = binary_tree_circuit(3, TreeOp::Mul);
+	std::fs::write(dir.join("c.bin"), circuit.serialize()).unwrap();
+	std::fs::write(dir.join("c.json"), circuit.to_json()).unwrap();
+	std::fs::write(dir.join("w.json"), "[1, 2, 3, 4, 5, 6, 7, 8]").unwrap();
+	dir
+}
+
+fn path(dir: &Path, file: &str) -> String {
+	dir.join(file).to_str().unwrap().to_string()
+}
+
+fn args(list: &[&str]) -> Vec<String> {
+	list.iter().map(|s| s.to_string()).collect()
+}
+
+#[rstest]
+#[case("c.bin")]
+#[case("c.json")]
+fn prove_then_verify(#[case] circuit: &str) {
+	let dir = workspace(&circuit.replace('.', "-"));
+	let (c, w, p) = (path(&dir, circuit), path(&dir, "w.json"), path(&dir, "proof.bin"));
+	let proved = run(&args(&["prove", "--circuit", &c, "--witness", &w, "--out", &p])).unwrap();
+	assert_eq!(proved, "[\"40320\"]");
+	// オプションの順は問わない
+	let verified = run(&args(&["verify", "--proof", &p, "--witness", &w, "--circuit", &c])).unwrap();
+	assert_eq!(verified, proved);
+}
+
+#[rstest]
+fn verify_rejects_a_different_witness_or_a_tampered_proof() {
+	let dir = workspace("reject");
+	let (c, w, p) = (path(&dir, "c.bin"), path(&dir, "w.json"), path(&dir, "proof.bin"));
+	run(&args(&["prove", "--circuit", &c, "--witness", &w, "--out", &p])).unwrap();
+
+	let other = path(&dir, "other.json");
+	std::fs::write(&other, "[1, 2, 3, 4, 5, 6, 7, 9]").unwrap();
+	let err = run(&args(&["verify", "--circuit", &c, "--witness", &other, "--proof", &p])).unwrap_err();
+	assert!(matches!(err, CliError::Rejected(_)));
+	assert_eq!(err.exit_code(), 1);
+
+	let mut bytes = std::fs::read(&p).unwrap();
+	bytes.truncate(bytes.len() - 1);
+	let tampered = path(&dir, "tampered.bin");
+	std::fs::write(&tampered, bytes).unwrap();
+	let err = run(&args(&["verify", "--circuit", &c, "--witness", &w, "--proof", &tampered])).unwrap_err();
+	assert!(matches!(err, CliError::Serialization(_)));
+}
+
+#[rstest]
+fn witness_must_match_the_circuit() {
+	let dir = workspace("count");
+	let short = path(&dir, "short.json");
+	std::fs::write(&short, "[1, 2, 3]").unwrap();
+	let err = run(&args(&["prove", "--circuit", &path(&dir, "c.bin"), "--witness", &short, "--out", &path(&dir, "p.bin")]))
+		.unwrap_err();
+	assert!(matches!(err, CliError::InputCount { expected: 8, found: 3 }));
+}
+
+#[rstest]
+#[case(&[])]
+#[case(&["frobnicate"])]
+#[case(&["prove", "--circuit", "c.bin", "--witness", "w.json"])]
+#[case(&["prove", "--circuit", "c.bin", "--witness", "w.json", "--out"])]
+#[case(&["prove", "--circuit", "c.bin", "--circuit", "c.bin", "--witness", "w.json", "--out", "p.bin"])]
+#[case(&["verify", "--circuit", "c.bin", "--witness", "w.json", "--out", "p.bin"])]
+fn usage_errors_exit_with_code_two(#[case] list: &[&str]) {
+	let err = run(&args(list)).unwrap_err();
+	assert!(matches!(err, CliError::Usage(_)));
+	assert_eq!(err.exit_code(), 2);
+}
+
+#[rstest]
+fn binary_proves_and_verifies() {
+	let dir = workspace("bin");
+	let (c, w, p) = (path(&dir, "c.bin"), path(&dir, "w.json"), path(&dir, "proof.bin"));
+	let gkr = env!("CARGO_BIN_EXE_gkr");
+	let prove = Command::new(gkr).args(["prove", "--circuit", &c, "--witness", &w, "--out", &p]).output().unwrap();
+	assert!(prove.status.success());
+	assert_eq!(String::from_utf8(prove.stdout).unwrap().trim(), "[\"40320\"]");
+	let verify = Command::new(gkr).args(["verify", "--circuit", &c, "--witness", &w, "--proof", &p]).output().unwrap();
+	assert!(verify.status.success());
+	let usage = Command::new(gkr).arg("prove").output().unwrap();
+	assert_eq!(usage.status.code(), Some(2));
+	assert!(String::from_utf8(usage.stderr).unwrap().contains("usage:"));
+}
@@ -0,0 +1,116 @@
+use ark_bls12_381::Fr as ScalarField;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rstest::rstest;
+use gkr::assert_rejects;
+use gkr::corrupt::{self, Phase};
+use gkr::error::{Error, RoundError};
+use gkr::prover::{LinearGKRProof, LinearGKRProver};
+use gkr::serialization::WireConfig;
+use gkr::simulate;
+use gkr::transcript::Transcript;
+use gkr::verifier::LinearGKRVerifier;
+
+const L: usize = 2;
+
+/// prover と verifier で共通の出発点となる transcript
+fn transcript() -> Transcript {
+	Transcript::new(b"test_corrupt")
+}
+
+fn honest_bytes() -> (Vec<u8>, ScalarField) {
+	let mut rng = StdRng::seed_from_u64(7);
+	let (relation, witness) = simulate::random_instance(L, 10, &mut rng);
+	let proof = LinearGKRProver::prove(&relation.f1, &witness.f2, &witness.f3, &relation.g, &mut transcript());
+	(proof.to_bytes(&WireConfig::default()), simulate::reference_claimed_sum(&relation, &witness))
+}
+
+#[rstest]
+fn helpers_change_the_decoded_proof() {
+	let (bytes, _) = honest_bytes();
+	let (proof, _) = LinearGKRProof::<ScalarField>::from_bytes(&bytes).unwrap();
+
+	let n = corrupt::num_elements(&bytes);
+	let (flipped, _) = LinearGKRProof::<ScalarField>::from_bytes(&corrupt::flip_element(&bytes, n - 1)).unwrap();
+	assert_eq!(flipped.phase1_msgs, proof.phase1_msgs);
+	assert_ne!(flipped.phase2_msgs, proof.phase2_msgs);
+
+	let (swapped, _) = LinearGKRProof::<ScalarField>::from_bytes(&corrupt::swap_phases(&bytes)).unwrap();
+	assert_eq!(swapped.phase1_msgs, proof.phase2_msgs);
+
+	let (short, _) = LinearGKRProof::<ScalarField>::from_bytes(&corrupt::truncate_message(&bytes, Phase::Two, 0)).unwrap();
+	assert_eq!(short.phase2_msgs[0].len() + 1, proof.phase2_msgs[0].len());
+}
+
+#[rstest]
+#[case(Phase::One)]
+#[case(Phase::Two)]
+fn truncated_phases_are_rejected(#[case] phase: Phase) {
+	let (bytes, claimed_sum) = honest_bytes();
+	let bad = corrupt::truncate_messages(&bytes, phase, L - 1);
+	assert_rejects!(bad, |proof| LinearGKRVerifier::verify(L, claimed_sum, proof, &mut transcript()));
+}
+
+#[rstest]
+fn raw_byte_corruption_is_rejected() {
+	let (bytes, claimed_sum) = honest_bytes();
+	// マジックとバージョンの改ざんは復号の段階で拒否される
+	for offset in 0..5 {
+		let bad = corrupt::flip_byte(&bytes, offset);
+		assert_rejects!(bad, |proof| LinearGKRVerifier::verify(L, claimed_sum, proof, &mut transcript()));
+	}
+}
+
+#[rstest]
+fn honest_proof_is_accepted() {
+	let (bytes, claimed_sum) = honest_bytes();
+	let (proof, _) = LinearGKRProof::<ScalarField>::from_bytes(&bytes).unwrap();
+	assert!(LinearGKRVerifier::verify(L, claimed_sum, &proof, &mut transcript()).is_ok());
+}
+
+#[rstest]
+fn tampered_messages_are_rejected() {
+	let (bytes, claimed_sum) = honest_bytes();
+	// ラウンドの整合性検査と，最終点での評価値との照合で拒否される
+	for index in 0..corrupt::num_elements(&bytes) {
+		let bad = corrupt::flip_element(&bytes, index);
+		assert_rejects!(bad, |proof| LinearGKRVerifier::verify(L, claimed_sum, proof, &mut transcript()));
+	}
+	let (proof, _) = LinearGKRProof::<ScalarField>::from_bytes(&bytes).unwrap();
+	let mut bad = proof.clone();
+	bad.f3_at_v += ScalarField::from(1u32);
+	assert!(LinearGKRVerifier::verify(L, claimed_sum, &bad, &mut transcript()).is_err());
+	assert_rejects!(corrupt::swap_phases(&bytes), |proof| LinearGKRVerifier::verify(L, claimed_sum, proof, &mut transcript()));
+}
+
+#[rstest]
+fn rejections_report_their_cause() {
+	let (bytes, claimed_sum) = honest_bytes();
+	let (proof, _) = LinearGKRProof::<ScalarField>::from_bytes(&bytes).unwrap();
+	let verify = |proof: &LinearGKRProof| LinearGKRVerifier::verify(L, claimed_sum, proof, &mut transcript());
+
+	let mut bad = proof.clone();
+	bad.phase1_msgs.pop();
+	assert_eq!(verify(&bad).err(), Some(Error::LengthMismatch { what: "round messages", expected: L, found: L - 1 }));
+
+	// g(0) は直前の主張から復元するので，値の改ざんは最終点での照合で見つかる
+	let mut bad = proof.clone();
+	bad.phase2_msgs[1][0] += ScalarField::from(1u32);
+	assert_eq!(verify(&bad).err(), Some(Error::EvaluationMismatch("product of the final evaluations")));
+
+	let mut bad = proof.clone();
+	bad.phase1_msgs[0].clear();
+	assert_eq!(verify(&bad).err(), Some(Error::Round { round: 0, kind: RoundError::InvalidLength(0) }));
+
+	// 2 つの MLE の積なので 3 次の多項式は送れない
+	let mut bad = proof.clone();
+	bad.phase2_msgs[0].push(ScalarField::from(1u32));
+	assert_eq!(
+		verify(&bad).err(),
+		Some(Error::Round { round: 0, kind: RoundError::DegreeBoundExceeded { degree: 3, bound: 2 } })
+	);
+
+	let mut bad = proof;
+	bad.f1_at_guv += ScalarField::from(1u32);
+	assert!(matches!(verify(&bad), Err(Error::EvaluationMismatch(_))));
+}
@@ -0,0 +1,69 @@
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::UniformRand;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rstest::rstest;
+use gkr::eq;
+use gkr::sumcheck::n_to_vec;
+
+fn random_point(n: usize, rng: &mut StdRng) -> Vec<ScalarField> {
+	(0..n).map(|_| ScalarField::rand(rng)).collect()
+}
+
+#[rstest]
+#[case(0)]
+#[case(1)]
+#[case(5)]
+fn table_matches_pointwise_evaluation(#[case] n: usize) {
+	let mut rng = StdRng::seed_from_u64(1);
+	let r = random_point(n, &mut rng);
+	let table = eq::eq_table(&r);
+	assert_eq!(table.len(), 1 << n);
+	for (b, e) in table.iter().enumerate() {
+		assert_eq!(*e, eq::eq_eval(&r, &n_to_vec(b, n)));
+	}
+	// Σ_b eq(r, b) = 1
+	assert_eq!(table.iter().sum::<ScalarField>(), ScalarField::from(1u32));
+}
+
+#[rstest]
+fn extension_appends_the_lowest_variable() {
+	let mut rng = StdRng::seed_from_u64(2);
+	let r = random_point(4, &mut rng);
+	let mut table = eq::eq_table(&r[..3]);
+	eq::extend_eq_table(&mut table, r[3]);
+	assert_eq!(table, eq::eq_table(&r));
+}
+
+#[rstest]
+fn eq_is_symmetric_and_selects_boolean_points() {
+	let mut rng = StdRng::seed_from_u64(3);
+	let (a, b) = (random_point(3, &mut rng), random_point(3, &mut rng));
+	assert_eq!(eq::eq_eval(&a, &b), eq::eq_eval(&b, &a));
+	let (p, q) = (n_to_vec::<ScalarField>(5, 3), n_to_vec::<ScalarField>(6, 3));
+	assert_eq!(eq::eq_eval(&p, &p), ScalarField::from(1u32));
+	assert_eq!(eq::eq_eval(&p, &q), ScalarField::from(0u32));
+}
+
+#[rstest]
+#[case(0, 1)]
+#[case(3, 0)]
+#[case(3, 5)]
+#[case(3, 8)]
+fn prefix_sums_match_the_table(#[case] n: usize, #[case] count: usize) {
+	let mut rng = StdRng::seed_from_u64(count as u64);
+	let r: Vec<ScalarField> = (0..n).map(|_| ScalarField::rand(&mut rng)).collect();
+	let table = eq::eq_table(&r);
+	assert_eq!(eq::eq_prefix_sum(&r, count), table[..count].iter().sum::<ScalarField>());
+}
+
+#[rstest]
+fn many_point_indicator_sums_products_of_eq() {
+	let mut rng = StdRng::seed_from_u64(11);
+	let points: Vec<Vec<ScalarField>> = (0..3).map(|_| (0..4).map(|_| ScalarField::rand(&mut rng)).collect()).collect();
+	let tables: Vec<Vec<ScalarField>> = points.iter().map(|p| eq::eq_table(p)).collect();
+	let expected: ScalarField = (0..16).map(|b| tables.iter().map(|t| t[b]).product::<ScalarField>()).sum();
+	let refs: Vec<&[ScalarField]> = points.iter().map(Vec::as_slice).collect();
+	assert_eq!(eq::eq_eval_many(&refs), expected);
+	assert_eq!(eq::eq_eval_many(&refs[..2]), eq::eq_eval(&points[0], &points[1]));
+}
@@ -0,0 +1,77 @@
+use ark_bls12_381::Fr as ScalarField;
+use ark_serialize::CanonicalSerialize;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rstest::rstest;
+use std::time::Duration;
+use gkr::circuit_prover::GKRProver;
+use gkr::estimate::{self, CostModel, EstimateConfig, RelationShape};
+use gkr::examples_circuits::{self, ExampleCircuit};
+use gkr::prover::{LinearGKRProver, ProverBackend};
+use gkr::serialization::WireConfig;
+use gkr::simulate;
+use gkr::transcript::Transcript;
+
+#[rstest]
+#[case(1)]
+#[case(3)]
+fn proof_size_matches_encoding(#[case] l: usize) {
+	let mut rng = StdRng::seed_from_u64(1);
+	let (relation, witness) = simulate::random_instance(l, 8, &mut rng);
+	let proof = LinearGKRProver::prove(&relation.f1, &witness.f2, &witness.f3, &relation.g, &mut Transcript::new(b"test"));
+	// 各ラウンドは g(0) を省いた 2 つの値
+	assert!(proof.phase1_msgs.iter().chain(proof.phase2_msgs.iter()).all(|msg| msg.len() == 2));
+	assert_eq!(estimate::proof_bytes(l), proof.to_bytes(&WireConfig::default()).len());
+}
+
+#[rstest]
+fn estimates_grow_with_size() {
+	let model = CostModel::default();
+	let small = estimate::prover_cost(&RelationShape { num_vars: 10, wiring_nnz: 1 << 10 }, &model);
+	let large = estimate::prover_cost(&RelationShape { num_vars: 28, wiring_nnz: 1 << 28 }, &model);
+	assert!(small.time < large.time);
+	assert!(small.peak_memory < large.peak_memory);
+	assert!(small.fits(Duration::from_secs(1), 1 << 30));
+	assert!(!large.fits(Duration::from_secs(1), 1 << 30));
+}
+
+#[rstest]
+fn boolean_slices_scan_less_of_the_wiring() {
+	let model = CostModel::default();
+	let shape = RelationShape { num_vars: 16, wiring_nnz: 1 << 20 };
+	let libra = estimate::prover_cost_with(&shape, &model, ProverBackend::Libra);
+	let slice = estimate::prover_cost_with(&shape, &model, ProverBackend::BooleanSlice);
+	assert_eq!(estimate::prover_cost(&shape, &model).time, libra.time);
+	assert!(slice.time < libra.time);
+	assert!(slice.peak_memory < libra.peak_memory);
+	assert_eq!(slice.proof_bytes, libra.proof_bytes);
+}
+
+#[rstest]
+#[case(examples_circuits::mul_tree(3))]
+#[case(examples_circuits::hash_chain(4))]
+fn circuit_proof_size_matches_encoding(#[case] example: ExampleCircuit<ScalarField>) {
+	let mut rng = StdRng::seed_from_u64(3);
+	let inputs = example.random_inputs(&mut rng);
+	let proof = GKRProver::prove_circuit(&example.circuit, &inputs);
+	let cost = estimate::circuit_cost(&example.circuit, &EstimateConfig::default());
+	assert_eq!(cost.proof_bytes, proof.compressed_size());
+	assert!(cost.time > Duration::ZERO);
+}
+
+#[rstest]
+fn circuit_estimates_grow_with_depth() {
+	let config = EstimateConfig::default();
+	let shallow = estimate::circuit_cost(&examples_circuits::mul_tree::<ScalarField>(2).circuit, &config);
+	let deep = estimate::circuit_cost(&examples_circuits::mul_tree::<ScalarField>(6).circuit, &config);
+	assert!(shallow.time < deep.time);
+	assert!(shallow.peak_memory < deep.peak_memory);
+	assert!(shallow.proof_bytes < deep.proof_bytes);
+}
+
+#[rstest]
+fn calibration_produces_nonzero_costs() {
+	let mut rng = StdRng::seed_from_u64(2);
+	let model = CostModel::calibrate(1 << 12, &mut rng);
+	assert!(model.field_mul + model.field_add + model.hash_op > Duration::ZERO);
+}
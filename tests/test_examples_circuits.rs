@@ -0,0 +1,72 @@
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::{One, Zero};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rstest::rstest;
+use std::collections::HashMap;
+use gkr::circuit::Gate;
+use gkr::examples_circuits::{self, ExampleCircuit};
+use gkr::ml_extension::{DenseMLE, SparseMLE};
+use gkr::simulate::{self, LayerRelation, LayerWitness, SimulationConfig};
+
+#[rstest]
+#[case(examples_circuits::mul_tree(3), 3)]
+#[case(examples_circuits::inner_product(2), 3)]
+#[case(examples_circuits::fibonacci(5), 5)]
+#[case(examples_circuits::hash_chain(2), 8)]
+fn witness_matches_reference(#[case] example: ExampleCircuit<ScalarField>, #[case] depth: usize) {
+	let mut rng = StdRng::seed_from_u64(depth as u64);
+	assert_eq!(example.circuit.depth(), depth);
+	for _ in 0..3 {
+		let inputs = example.random_inputs(&mut rng);
+		let witness = example.witness(&inputs);
+		assert_eq!(witness.len(), depth + 1);
+		assert_eq!(witness[depth], inputs);
+		assert_eq!(witness[0], example.expected_output(&inputs), "{}", example.name);
+	}
+}
+
+#[test]
+fn standard_suite_has_power_of_two_layers() {
+	for example in examples_circuits::standard_suite::<ScalarField>() {
+		let circuit = &example.circuit;
+		assert!(circuit.num_inputs.is_power_of_two(), "{}", example.name);
+		let mut below = circuit.num_inputs;
+		for layer in circuit.layers.iter().rev() {
+			assert!(layer.gates.len().is_power_of_two(), "{}", example.name);
+			assert!(layer.gates.iter().all(|g| g.inputs().0 < below && g.inputs().1 < below));
+			below = layer.gates.len();
+		}
+	}
+}
+
+// 幅 4 で一定の回路について，各層の乗算ゲート部分
+//   V_i(g) = Σ_{x,y} mul_i(g, x, y) V_{i+1}(x) V_{i+1}(y)
+// を単層 GKR で証明・検証する（ブールの g に限る）
+#[rstest]
+#[case(examples_circuits::fibonacci(4))]
+#[case(examples_circuits::hash_chain(1))]
+fn mul_gates_of_each_layer_are_provable(#[case] example: ExampleCircuit<ScalarField>) {
+	let mut rng = StdRng::seed_from_u64(7);
+	let l = 2;
+	let witness = example.witness(&example.random_inputs(&mut rng));
+	for (i, layer) in example.circuit.layers.iter().enumerate() {
+		let below = DenseMLE::from_evaluations_vec(l, witness[i + 1].clone());
+		let mut evaluations = HashMap::new();
+		for (z, gate) in layer.gates.iter().enumerate() {
+			if let Gate::Mul(x, y) = *gate {
+				evaluations.insert((z << (2 * l)) | (y << l) | x, ScalarField::one());
+			}
+		}
+		let f1 = SparseMLE::new(3 * l, evaluations);
+		for (z, gate) in layer.gates.iter().enumerate() {
+			let g = (0..l).map(|b| if (z >> (l - 1 - b)) & 1 == 1 { ScalarField::one() } else { ScalarField::zero() }).collect();
+			let relation = LayerRelation { f1: f1.clone(), g };
+			let layer_witness = LayerWitness { f2: below.clone(), f3: below.clone() };
+			let report = simulate::prove_and_verify(&relation, &layer_witness, &SimulationConfig::default());
+			assert!(report.accepted(), "{} layer {}: {:?}", example.name, i, report.result.err());
+			let expected = if matches!(gate, Gate::Mul(..)) { witness[i][z] } else { ScalarField::zero() };
+			assert_eq!(report.claimed_sum, expected, "{} layer {} gate {}", example.name, i, z);
+		}
+	}
+}
@@ -0,0 +1,95 @@
+use ark_bls12_381::Fr as ScalarField;
+use rstest::rstest;
+use gkr::builder::{BuiltCircuit, CircuitBuilder};
+use gkr::circuit_prover::{GKRProver, GKRVerifier};
+use gkr::error::Error;
+use gkr::expr::{compile, compile_with, Expr};
+
+type E = Expr<ScalarField>;
+
+fn x(i: usize) -> E {
+	Expr::var(i)
+}
+
+fn c(v: u64) -> E {
+	Expr::constant(v)
+}
+
+fn values(n: u64) -> Vec<ScalarField> {
+	(0..n).map(|i| ScalarField::from(3 + 2 * i)).collect()
+}
+
+/// 中継を除いて計算しているゲートの数
+fn computed_gates(built: &BuiltCircuit<ScalarField>) -> usize {
+	built.layer_widths.iter().sum::<usize>() - built.num_relays
+}
+
+#[rstest]
+fn cubic_formula_is_proved() {
+	let formula = x(0).pow(3) + x(0) + c(5);
+	let built = compile(std::slice::from_ref(&formula), 1).unwrap();
+	let vars = vec![ScalarField::from(3u32)];
+	assert_eq!(built.evaluate(&vars), vec![ScalarField::from(35u32)]);
+	assert_eq!(formula.evaluate(&vars), ScalarField::from(35u32));
+
+	let inputs = built.assign(&vars);
+	let proof = GKRProver::prove_circuit(&built.circuit, &inputs);
+	assert!(GKRVerifier::verify_circuit(&built.circuit, &inputs, &proof).is_ok());
+}
+
+#[rstest]
+#[case(x(0) - x(1) * x(2), 3)]
+#[case(-(x(0) + c(7)).pow(5) * x(1), 2)]
+#[case(x(0).pow(0) + x(1).pow(1) + x(2).pow(13), 3)]
+#[case((x(0) + x(1)) * (x(0) + x(1)) - (x(0) + x(1)), 2)]
+#[case(x(1), 2)]
+fn compiled_circuits_match_the_formula(#[case] formula: E, #[case] num_vars: usize) {
+	let built = compile(std::slice::from_ref(&formula), num_vars).unwrap();
+	let vars = values(num_vars as u64);
+	assert_eq!(built.evaluate(&vars), vec![formula.evaluate(&vars)]);
+}
+
+#[rstest]
+fn common_subexpressions_are_computed_once() {
+	// s = x·y + 1 を 2 回使う：x·y, s, s², s² + s の 4 ゲート
+	let s = x(0) * x(1) + c(1);
+	let formula = s.clone().pow(2) + s.clone();
+	let built = compile(&[formula, s.clone()], 2).unwrap();
+	assert_eq!(computed_gates(&built), 4);
+
+	// 別々の冪でも平方は使い回す
+	let built = compile(&[x(0).pow(4), x(0).pow(2)], 1).unwrap();
+	assert_eq!(computed_gates(&built), 2);
+}
+
+#[rstest]
+fn chains_are_balanced_by_depth() {
+	let chain = (1..8).fold(x(0), |acc, i| acc + x(i));
+	let built = compile(&[chain], 8).unwrap();
+	assert_eq!(built.circuit.depth(), 3);
+
+	// 深い項は最後に足す
+	let deep = x(0).pow(8);
+	let built = compile(&[deep + x(1) + x(2) + x(3) + x(4)], 5).unwrap();
+	assert_eq!(built.circuit.depth(), 4);
+
+	let product = (1..8).fold(x(0), |acc, i| acc * x(i));
+	let built = compile_with(&[product], 8, CircuitBuilder::with_max_fan_in(4)).unwrap();
+	assert_eq!(built.circuit.depth(), 2);
+}
+
+#[rstest]
+fn constants_are_folded() {
+	let formula = (c(2) + c(3)) * x(0) + -c(4);
+	let built = compile(std::slice::from_ref(&formula), 1).unwrap();
+	assert_eq!(built.constants.len(), 2);
+	assert!(built.constants.contains(&ScalarField::from(5u32)));
+	assert!(built.constants.contains(&-ScalarField::from(4u32)));
+	let vars = values(1);
+	assert_eq!(built.evaluate(&vars), vec![formula.evaluate(&vars)]);
+}
+
+#[rstest]
+fn unknown_variables_are_rejected() {
+	assert_eq!(compile(&[x(0) + x(2)], 2).err(), Some(Error::InvalidCircuit("expression reads an unknown variable")));
+}
@@ -0,0 +1,94 @@
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::{One, UniformRand};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rstest::rstest;
+use std::collections::HashMap;
+use gkr::error::Error;
+use gkr::fan_in::{FanInProver, FanInVerifier};
+use gkr::ml_extension::{DenseMLE, SparseMLE};
+use gkr::prover::LinearGKRProver;
+use gkr::transcript::Transcript;
+
+fn random_instance(k: usize, l: usize, nnz: usize, rng: &mut StdRng) -> (SparseMLE<ScalarField>, Vec<DenseMLE<ScalarField>>, Vec<ScalarField>) {
+	let evaluations: HashMap<usize, ScalarField> =
+		(0..nnz).map(|_| (rng.gen_range(0..1 << ((k + 1) * l)), ScalarField::rand(rng))).collect();
+	let f1 = SparseMLE::new((k + 1) * l, evaluations);
+	let fs = (0..k)
+		.map(|_| DenseMLE::from_evaluations_vec(l, (0..1 << l).map(|_| ScalarField::rand(rng)).collect()))
+		.collect();
+	let g = (0..l).map(|_| ScalarField::rand(rng)).collect();
+	(f1, fs, g)
+}
+
+/// Σ_{x_1..x_k} f1(g, x_1, ..., x_k)·Π f_j(x_j) を定義どおりに求める
+fn brute_force_sum(f1: &SparseMLE<ScalarField>, fs: &[DenseMLE<ScalarField>], g: &[ScalarField]) -> ScalarField {
+	let (k, l) = (fs.len(), g.len());
+	let mask = (1 << l) - 1;
+	let eq_g = gkr::eq::eq_table(g);
+	f1.evaluations
+		.iter()
+		.map(|(index, val)| {
+			let product: ScalarField = (0..k).map(|j| fs[j].evaluations[(index >> (j * l)) & mask]).product();
+			*val * eq_g[index >> (k * l)] * product
+		})
+		.sum()
+}
+
+#[rstest]
+fn two_phases_match_linear_gkr() {
+	let mut rng = StdRng::seed_from_u64(0);
+	let (f1, fs, g) = random_instance(2, 3, 40, &mut rng);
+	let linear = LinearGKRProver::prove(&f1, &fs[0], &fs[1], &g, &mut Transcript::new(b"fan_in_test"));
+	let proof = FanInProver::prove(&f1, &[&fs[0], &fs[1]], &g, &mut Transcript::new(b"fan_in_test"));
+	assert_eq!(proof.claimed_sum, linear.claimed_sum);
+	assert_eq!(proof.phase_msgs, vec![linear.phase1_msgs, linear.phase2_msgs]);
+	assert_eq!(proof.f1_at_point, linear.f1_at_guv);
+	assert_eq!(proof.evals, vec![linear.f2_at_u, linear.f3_at_v]);
+}
+
+#[rstest]
+#[case(1, 3)]
+#[case(3, 2)]
+#[case(4, 3)]
+fn honest_proofs_are_accepted(#[case] k: usize, #[case] l: usize) {
+	let mut rng = StdRng::seed_from_u64((k * 10 + l) as u64);
+	let (f1, fs, g) = random_instance(k, l, 60, &mut rng);
+	let refs: Vec<&DenseMLE<ScalarField>> = fs.iter().collect();
+	let proof = FanInProver::prove(&f1, &refs, &g, &mut Transcript::new(b"fan_in_test"));
+	assert_eq!(proof.fan_in(), k);
+	assert_eq!(proof.claimed_sum, brute_force_sum(&f1, &fs, &g));
+	let subclaim = FanInVerifier::verify(l, k, proof.claimed_sum, &proof, &mut Transcript::new(b"fan_in_test")).unwrap();
+	assert_eq!(subclaim.points.len(), k);
+	assert!(subclaim.verify_against(&f1, &refs, &g).is_ok());
+}
+
+#[rstest]
+#[case::message(0)]
+#[case::final_evaluation(1)]
+#[case::claimed_sum(2)]
+fn tampering_is_rejected(#[case] target: usize) {
+	let mut rng = StdRng::seed_from_u64(7);
+	let (f1, fs, g) = random_instance(3, 2, 30, &mut rng);
+	let refs: Vec<&DenseMLE<ScalarField>> = fs.iter().collect();
+	let mut proof = FanInProver::prove(&f1, &refs, &g, &mut Transcript::new(b"fan_in_test"));
+	let claimed_sum = proof.claimed_sum;
+	match target {
+		0 => proof.phase_msgs[2][1][0] += ScalarField::one(),
+		1 => proof.evals[1] += ScalarField::one(),
+		_ => proof.claimed_sum += ScalarField::one(),
+	}
+	assert!(FanInVerifier::verify(2, 3, claimed_sum, &proof, &mut Transcript::new(b"fan_in_test")).is_err());
+}
+
+#[rstest]
+fn wrong_fan_in_is_rejected() {
+	let mut rng = StdRng::seed_from_u64(8);
+	let (f1, fs, g) = random_instance(3, 2, 30, &mut rng);
+	let refs: Vec<&DenseMLE<ScalarField>> = fs.iter().collect();
+	let proof = FanInProver::prove(&f1, &refs, &g, &mut Transcript::new(b"fan_in_test"));
+	assert_eq!(
+		FanInVerifier::verify(2, 2, proof.claimed_sum, &proof, &mut Transcript::new(b"fan_in_test")).err(),
+		Some(Error::LengthMismatch { what: "phases", expected: 2, found: 3 })
+	);
+}
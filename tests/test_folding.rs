@@ -0,0 +1,96 @@
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::{One, UniformRand};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rstest::rstest;
+use gkr::folding::{self, EvaluationClaim, FoldingProver, FoldingVerifier, PlainCommitment};
+use gkr::ml_extension::{DenseMLE, IndexOrder};
+use gkr::transcript::Transcript;
+
+type Claim = EvaluationClaim<ScalarField, PlainCommitment<ScalarField>>;
+
+fn random_step(n: usize, rng: &mut StdRng) -> (Claim, DenseMLE<ScalarField>) {
+	let mle = DenseMLE::from_evaluations_vec(n, (0..1 << n).map(|_| ScalarField::rand(rng)).collect());
+	let point: Vec<ScalarField> = (0..n).map(|_| ScalarField::rand(rng)).collect();
+	let value = folding::evaluate_mle(&mle, &point);
+	(EvaluationClaim { commitment: PlainCommitment::commit(&mle), point, value }, mle)
+}
+
+#[rstest]
+#[case(1, 2)]
+#[case(3, 5)]
+#[case(5, 8)]
+fn folded_claims_are_decided_once(#[case] n: usize, #[case] steps: usize) {
+	let mut rng = StdRng::seed_from_u64(n as u64);
+	let mut prover = FoldingProver::new(Transcript::new(b"ivc"));
+	let mut verifier = FoldingVerifier::new(Transcript::new(b"ivc"));
+	for _ in 0..steps {
+		let (claim, mle) = random_step(n, &mut rng);
+		let proof = prover.push(claim.clone(), mle);
+		verifier.push(claim, proof.as_ref()).unwrap();
+		assert_eq!(verifier.running(), prover.running().map(|(c, _)| c));
+	}
+	let (_, witness) = prover.running().unwrap();
+	assert!(verifier.decide(witness));
+
+	// 証拠を差し替えると decide で拒否される
+	let mut bad = witness.clone();
+	bad.evaluations[0] += ScalarField::one();
+	assert!(!verifier.decide(&bad));
+}
+
+// self-check 有効時は偽の主張を折り畳もうとした時点で prover が panic する
+#[cfg(not(feature = "self-check"))]
+#[test]
+fn false_claims_are_rejected_by_fold_verify() {
+	let mut rng = StdRng::seed_from_u64(9);
+	let n = 4;
+	let (running, running_mle) = random_step(n, &mut rng);
+	let (mut incoming, incoming_mle) = random_step(n, &mut rng);
+	incoming.value += ScalarField::one();
+
+	let (proof, _, _) =
+		folding::fold_prove(&mut Transcript::new(b"ivc"), &running, &running_mle, &incoming, &incoming_mle);
+	assert!(folding::fold_verify(&mut Transcript::new(b"ivc"), &running, &incoming, &proof).is_err());
+}
+
+#[test]
+fn tampered_folding_proofs_are_rejected() {
+	let mut rng = StdRng::seed_from_u64(10);
+	let n = 3;
+	let (running, running_mle) = random_step(n, &mut rng);
+	let (incoming, incoming_mle) = random_step(n, &mut rng);
+	let (proof, folded, _) =
+		folding::fold_prove(&mut Transcript::new(b"ivc"), &running, &running_mle, &incoming, &incoming_mle);
+	assert_eq!(folding::fold_verify(&mut Transcript::new(b"ivc"), &running, &incoming, &proof), Ok(folded));
+
+	let mut bad = proof.clone();
+	bad.incoming_eval += ScalarField::one();
+	assert!(folding::fold_verify(&mut Transcript::new(b"ivc"), &running, &incoming, &bad).is_err());
+
+	let mut bad = proof.clone();
+	bad.round_msgs[1][0] += ScalarField::one();
+	assert!(folding::fold_verify(&mut Transcript::new(b"ivc"), &running, &incoming, &bad).is_err());
+
+	let mut bad = proof;
+	bad.round_msgs.pop();
+	assert!(folding::fold_verify(&mut Transcript::new(b"ivc"), &running, &incoming, &bad).is_err());
+}
+
+#[test]
+fn little_endian_witnesses_fold_like_big_endian_ones() {
+	let mut rng = StdRng::seed_from_u64(11);
+	let n = 3;
+	let (running, running_mle) = random_step(n, &mut rng);
+	let (incoming, incoming_mle) = random_step(n, &mut rng);
+	let (proof, folded, folded_mle) =
+		folding::fold_prove(&mut Transcript::new(b"ivc"), &running, &running_mle, &incoming, &incoming_mle);
+	let (little_running, little_incoming) =
+		(running_mle.to_order(IndexOrder::LittleEndian), incoming_mle.to_order(IndexOrder::LittleEndian));
+	let (little_proof, little_folded, little_mle) =
+		folding::fold_prove(&mut Transcript::new(b"ivc"), &running, &little_running, &incoming, &little_incoming);
+	assert_eq!((little_proof, little_folded), (proof.clone(), folded.clone()));
+	assert_eq!(little_mle.evaluations, folded_mle.evaluations);
+	assert_eq!(folding::fold_verify(&mut Transcript::new(b"ivc"), &running, &incoming, &proof), Ok(folded.clone()));
+	assert!(folding::decide(&folded, &folded_mle.to_order(IndexOrder::LittleEndian)));
+}
@@ -8,8 +8,10 @@ use rstest::rstest;
 use std::collections::HashMap;
 
 // 各モジュールは src 内の実装（lib.rs 経由で公開）を利用する
+use gkr::error::Error;
 use gkr::ml_extension::{DenseMLE, SparseMLE};
-use gkr::prover::LinearGKRProver;
+use gkr::prover::{LinearGKRProver, ProverBackend};
+use gkr::transcript::Transcript;
 use gkr::verifier::LinearGKRVerifier;
 
 lazy_static! {
@@ -20,7 +22,7 @@ lazy_static! {
         for i in 0..(1 << 3) {
             evals.insert(i, 1u32.into());
         }
-        SparseMLE { num_vars: 3, evaluations: evals }
+        SparseMLE::new(3, evals)
     };
 
     // f2: 1 変数の密な multilinear extension、評価：f2(0)=2, f2(1)=3
@@ -39,11 +41,11 @@ lazy_static! {
 
 #[rstest]
 fn linear_gkr_test() {
-    // 乱数生成器を用意（実際の実装では Fiat–Shamir 等の変換も可能）
-    let mut rng = rand::thread_rng();
+    // Prover と Verifier は同じ状態の transcript からチャレンジを引く
+    let transcript = Transcript::new(b"linear_gkr_test");
 
     // Prover 側：Linear GKR プロトコルの証明を生成
-    let proof = LinearGKRProver::prove(&F1, &F2, &F3, &G, &mut rng);
+    let proof = LinearGKRProver::prove(&F1, &F2, &F3, &G, &mut transcript.clone());
 
     // 上記の各定義から，Phase1 での claimed sum は以下のように計算できる:
     // f1 は定数 1 で，g = [1] により f1(g,x,y) は {0,1}^2 上の定数 1 となる．
@@ -51,9 +53,312 @@ fn linear_gkr_test() {
     // f2 は [2, 3] なので，
     // claimed_sum = 9 * f2(0) + 9 * f2(1) = 9*2 + 9*3 = 18 + 27 = 45.
     let claimed_sum_phase1: ScalarField = 45u32.into();
+    assert_eq!(proof.claimed_sum, claimed_sum_phase1);
 
     // Verifier 側：Prover から受け取った証明を検証する
-    let subclaim = LinearGKRVerifier::verify(1, claimed_sum_phase1, &proof, &mut rng);
+    let subclaim = LinearGKRVerifier::verify(1, claimed_sum_phase1, &proof, &mut transcript.clone());
     assert!(subclaim.is_ok(), "Linear GKR proof verification failed");
+
+    // 証明に埋め込まれた総和と異なる主張では検証しない
+    let wrong = LinearGKRVerifier::verify(1, claimed_sum_phase1 + ScalarField::from(1u32), &proof, &mut transcript.clone());
+    assert_eq!(wrong.err(), Some(Error::EvaluationMismatch("claimed sum")));
+}
+
+#[rstest]
+fn precomputation_matches_direct_fixing() {
+    use rand::SeedableRng;
+    let mut rng = rand::rngs::StdRng::seed_from_u64(5);
+    let (relation, witness) = gkr::simulate::random_instance(3, 40, &mut rng);
+    let pre = LinearGKRProver::precompute(&relation.f1, &witness.f2, &witness.f3);
+    assert_eq!(pre.wiring.len(), relation.f1.evaluations.len());
+
+    // 前計算は g に依存しないので，複数の g に対して使い回せる
+    for g_index in 0..(1 << 3) {
+        let g: Vec<ScalarField> = (0..3).map(|i| ((g_index >> (2 - i)) & 1).into()).collect();
+        let direct = relation.f1.fix_variables(&g);
+        assert_eq!(pre.fix_g(&g).evaluations, direct.evaluations);
+        let proof = LinearGKRProver::prove_precomputed(&pre, &g, &mut Transcript::new(b"test"));
+        assert_eq!(proof.phase1_msgs.len(), 3);
+        // ブール点ではどちらの表の作り方でも同じ証明になる
+        let sliced = LinearGKRProver::prove_precomputed_with(&pre, &g, ProverBackend::BooleanSlice, &mut Transcript::new(b"test"));
+        assert_eq!(proof, sliced);
+    }
+}
+
+#[rstest]
+#[case(1)]
+#[case(4)]
+fn libra_backend_accepts_any_output_point(#[case] l: usize) {
+    use ark_ff::UniformRand;
+    use rand::SeedableRng;
+    let mut rng = rand::rngs::StdRng::seed_from_u64(6);
+    let (relation, witness) = gkr::simulate::random_instance(l, 30, &mut rng);
+    let g: Vec<ScalarField> = (0..l).map(|_| ScalarField::rand(&mut rng)).collect();
+    let proof = LinearGKRProver::prove(&relation.f1, &witness.f2, &witness.f3, &g, &mut Transcript::new(b"test"));
+
+    // Σ_{x,y} f1(g,x,y)·f2(x)·f3(y) を f1 の MLE から直接求める
+    let f1_at_g = relation.f1.fix_variables(&g);
+    let expected: ScalarField = f1_at_g
+        .evaluations
+        .iter()
+        .map(|(&index, &val)| val * witness.f2.evaluations[index & ((1 << l) - 1)] * witness.f3.evaluations[index >> l])
+        .sum();
+    assert_eq!(proof.claimed_sum, expected);
+    let subclaim = LinearGKRVerifier::verify(l, expected, &proof, &mut Transcript::new(b"test")).unwrap();
+    assert!(subclaim.verify_against(&relation.f1, &witness.f2, &witness.f3, &g).is_ok());
+}
+
+#[rstest]
+#[case(1)]
+#[case(3)]
+fn subclaim_matches_oracle_at_challenges(#[case] l: usize) {
+    use gkr::self_check::direct_evaluation;
+    use rand::SeedableRng;
+    let mut rng = rand::rngs::StdRng::seed_from_u64(11);
+    let (relation, witness) = gkr::simulate::random_instance(l, 20, &mut rng);
+    let claimed_sum = gkr::simulate::reference_claimed_sum(&relation, &witness);
+    let proof = LinearGKRProver::prove(&relation.f1, &witness.f2, &witness.f3, &relation.g, &mut Transcript::new(b"test"));
+    let subclaim = LinearGKRVerifier::verify(l, claimed_sum, &proof, &mut Transcript::new(b"test")).unwrap();
+    assert_eq!(subclaim.u.len(), l);
+    // 別の状態の transcript から引いたチャレンジでは整合しない
+    assert!(LinearGKRVerifier::verify(l, claimed_sum, &proof, &mut Transcript::new(b"other")).is_err());
+    assert_eq!(subclaim.v.len(), l);
+
+    // f1 の添字は (z << 2l) | (y << l) | x なので，点は g || v || u の順に並べる
+    let point: Vec<ScalarField> =
+        relation.g.iter().chain(subclaim.v.iter()).chain(subclaim.u.iter()).copied().collect();
+    let f1_at_guv = relation.f1.evaluate(&point);
+    let expected = f1_at_guv
+        * direct_evaluation(&witness.f2.evaluations, &subclaim.u)
+        * direct_evaluation(&witness.f3.evaluations, &subclaim.v);
+    assert_eq!(subclaim.expected_value, expected);
+    assert_eq!(subclaim.f1_at_guv, f1_at_guv);
+    assert_eq!(subclaim.f2_at_u, direct_evaluation(&witness.f2.evaluations, &subclaim.u));
+    assert_eq!(subclaim.f3_at_v, direct_evaluation(&witness.f3.evaluations, &subclaim.v));
+
+    assert!(subclaim.verify_against(&relation.f1, &witness.f2, &witness.f3, &relation.g).is_ok());
+    // 証拠が違えば最終検査で拒否される
+    let mut other_f2 = witness.f2.clone();
+    other_f2.evaluations[0] += ScalarField::from(1u32);
+    assert_eq!(
+        subclaim.verify_against(&relation.f1, &other_f2, &witness.f3, &relation.g),
+        Err(Error::EvaluationMismatch("f2 at u"))
+    );
+    // オラクル版：評価値だけを返すクロージャで同じ検査を行う
+    let oracles = |f1_at_guv: ScalarField| {
+        subclaim.verify_with_oracles(&relation.g, |_, _, _| f1_at_guv, |u| witness.f2.evaluate(u), |v| witness.f3.evaluate(v))
+    };
+    assert!(oracles(f1_at_guv).is_ok());
+    assert_eq!(oracles(f1_at_guv + ScalarField::from(1u32)), Err(Error::EvaluationMismatch("f1 at (g, u, v)")));
+}
+
+#[rstest]
+fn noninteractive_proofs_bind_the_statement() {
+    use rand::SeedableRng;
+    let mut rng = rand::rngs::StdRng::seed_from_u64(21);
+    let (relation, witness) = gkr::simulate::random_instance(3, 30, &mut rng);
+    let claimed_sum = gkr::simulate::reference_claimed_sum(&relation, &witness);
+    let proof = LinearGKRProver::prove_noninteractive(&relation.f1, &witness.f2, &witness.f3, &relation.g);
+    assert!(LinearGKRVerifier::verify_noninteractive(&relation.f1, &relation.g, claimed_sum, &proof).is_ok());
+    // 決定的：同じ主張からは同じ証明ができる
+    assert_eq!(proof, LinearGKRProver::prove_noninteractive(&relation.f1, &witness.f2, &witness.f3, &relation.g));
+
+    // 別の g や別の配線述語に対しては受理されない
+    let mut other_g = relation.g.clone();
+    other_g[0] = ScalarField::from(1u32) - other_g[0];
+    assert!(LinearGKRVerifier::verify_noninteractive(&relation.f1, &other_g, claimed_sum, &proof).is_err());
+    let mut other_f1 = relation.f1.clone();
+    other_f1.evaluations.insert(0, ScalarField::from(99u32));
+    assert!(LinearGKRVerifier::verify_noninteractive(&other_f1, &relation.g, claimed_sum, &proof).is_err());
+}
+
+// 体を入れ替えても同じコードで証明・検証できる（BLS12-381 の Fr と，幅の異なる Fq）
+fn prove_and_verify_over<F: ark_ff::PrimeField>(l: usize) {
+    use gkr::prover::LinearGKRProof;
+    use gkr::serialization::WireConfig;
+    use rand::{Rng, SeedableRng};
+    let mut rng = rand::rngs::StdRng::seed_from_u64(31);
+    let z0 = rng.gen_range(0..1 << l);
+    let mut evaluations = HashMap::new();
+    for _ in 0..4 * l {
+        let (x, y) = (rng.gen_range(0..1 << l), rng.gen_range(0..1 << l));
+        evaluations.insert((z0 << (2 * l)) | (y << l) | x, F::rand(&mut rng));
+    }
+    let f1 = SparseMLE::new(3 * l, evaluations);
+    let f2 = DenseMLE::from_evaluations_vec(l, (0..1 << l).map(|_| F::rand(&mut rng)).collect());
+    let f3 = DenseMLE::from_evaluations_vec(l, (0..1 << l).map(|_| F::rand(&mut rng)).collect());
+    let g: Vec<F> = (0..l).map(|i| F::from(((z0 >> (l - 1 - i)) & 1) as u64)).collect();
+    let mask = (1 << l) - 1;
+    let claimed_sum: F =
+        f1.evaluations.iter().map(|(index, val)| *val * f2.evaluations[index & mask] * f3.evaluations[(index >> l) & mask]).sum();
+
+    let proof = LinearGKRProver::prove_noninteractive(&f1, &f2, &f3, &g);
+    let bytes = proof.to_bytes(&WireConfig::default());
+    let (decoded, _) = LinearGKRProof::<F>::from_bytes(&bytes).unwrap();
+    assert_eq!(decoded, proof);
+    assert!(LinearGKRVerifier::verify_noninteractive(&f1, &g, claimed_sum, &decoded).is_ok());
+    assert!(LinearGKRVerifier::verify_noninteractive(&f1, &g, claimed_sum + F::one(), &decoded).is_err());
+}
+
+#[rstest]
+#[case(1)]
+#[case(3)]
+fn linear_gkr_is_generic_over_the_field(#[case] l: usize) {
+    prove_and_verify_over::<ScalarField>(l);
+    prove_and_verify_over::<ark_bls12_381::Fq>(l);
 }
 
+#[rstest]
+#[case(2)]
+#[case(3)]
+fn prover_accepts_either_index_order(#[case] l: usize) {
+    use gkr::ml_extension::IndexOrder;
+    use rand::SeedableRng;
+    let mut rng = rand::rngs::StdRng::seed_from_u64(l as u64);
+    let (relation, witness) = gkr::simulate::random_instance(l, 20, &mut rng);
+    let prove = |f1: &SparseMLE<ScalarField>, f2: &DenseMLE<ScalarField>, f3: &DenseMLE<ScalarField>| {
+        LinearGKRProver::prove_noninteractive(f1, f2, f3, &relation.g)
+    };
+    let big = prove(&relation.f1, &witness.f2, &witness.f3);
+    let little = prove(
+        &relation.f1.to_order(IndexOrder::LittleEndian),
+        &witness.f2.to_order(IndexOrder::LittleEndian),
+        &witness.f3.to_order(IndexOrder::LittleEndian),
+    );
+    assert_eq!(big, little);
+    let claimed_sum = gkr::simulate::reference_claimed_sum(&relation, &witness);
+    let little_f1 = relation.f1.to_order(IndexOrder::LittleEndian);
+    assert!(LinearGKRVerifier::verify_noninteractive(&little_f1, &relation.g, claimed_sum, &little).is_ok());
+}
+
+#[rstest]
+#[case(1)]
+#[case(3)]
+fn zk_proofs_give_the_same_kind_of_subclaim(#[case] l: usize) {
+    use gkr::basefold::BaseFold;
+    use rand::SeedableRng;
+    let mut rng = rand::rngs::StdRng::seed_from_u64(16);
+    let (relation, witness) = gkr::simulate::random_instance(l, 20, &mut rng);
+    let claimed_sum = gkr::simulate::reference_claimed_sum(&relation, &witness);
+    let pre = LinearGKRProver::precompute(&relation.f1, &witness.f2, &witness.f3);
+    let params = BaseFold::<ScalarField>::setup(4, b"zk_linear_gkr");
+    let proof = LinearGKRProver::prove_zk::<BaseFold<ScalarField>, _>(
+        &params,
+        &pre,
+        &relation.g,
+        &mut rng,
+        &mut Transcript::new(b"test"),
+    );
+    assert_eq!(proof.claimed_sum, claimed_sum);
+    let subclaim =
+        LinearGKRVerifier::verify_zk::<BaseFold<ScalarField>>(&params, l, claimed_sum, &proof, &mut Transcript::new(b"test"))
+            .unwrap();
+    assert!(subclaim.verify_against(&relation.f1, &witness.f2, &witness.f3, &relation.g).is_ok());
+
+    // 同じ証人でもマスクが変わればメッセージも変わる
+    let other = LinearGKRProver::prove_zk::<BaseFold<ScalarField>, _>(
+        &params,
+        &pre,
+        &relation.g,
+        &mut rng,
+        &mut Transcript::new(b"test"),
+    );
+    assert_ne!(proof.phase1.msgs, other.phase1.msgs);
+
+    let mut tampered = proof.clone();
+    tampered.f3_at_v += ScalarField::from(1u32);
+    assert_eq!(
+        LinearGKRVerifier::verify_zk::<BaseFold<ScalarField>>(&params, l, claimed_sum, &tampered, &mut Transcript::new(b"test"))
+            .err(),
+        Some(Error::EvaluationMismatch("product of the final evaluations"))
+    );
+    assert!(LinearGKRVerifier::verify_zk::<BaseFold<ScalarField>>(
+        &params,
+        l,
+        claimed_sum,
+        &proof,
+        &mut Transcript::new(b"other")
+    )
+    .is_err());
+}
+
+#[rstest]
+#[case(1)]
+#[case(4)]
+fn streaming_prover_matches_the_in_memory_prover(#[case] l: usize) {
+    use ark_ff::UniformRand;
+    use gkr::ml_extension::IndexOrder;
+    use rand::SeedableRng;
+    use std::cell::Cell;
+    let mut rng = rand::rngs::StdRng::seed_from_u64(9);
+    let (relation, witness) = gkr::simulate::random_instance(l, 30, &mut rng);
+    let g: Vec<ScalarField> = (0..l).map(|_| ScalarField::rand(&mut rng)).collect();
+    let expected = LinearGKRProver::prove(&relation.f1, &witness.f2, &witness.f3, &g, &mut Transcript::new(b"test"));
+
+    let (f2, f3) = (witness.f2.to_order(IndexOrder::BigEndian), witness.f3.to_order(IndexOrder::BigEndian));
+    let reads = Cell::new(0usize);
+    let proof = LinearGKRProver::prove_streaming(
+        &relation.f1,
+        l,
+        |x| {
+            reads.set(reads.get() + 1);
+            f2.evaluations[x]
+        },
+        |y| f3.evaluations[y],
+        &g,
+        &mut Transcript::new(b"test"),
+    );
+    assert_eq!(proof, expected);
+    // f2 は Phase 1 の各ラウンドと総和・最終値で読み直す
+    assert_eq!(reads.get(), (l + 2) << l);
+    let subclaim = LinearGKRVerifier::verify(l, proof.claimed_sum, &proof, &mut Transcript::new(b"test")).unwrap();
+    assert!(subclaim.verify_against(&relation.f1, &witness.f2, &witness.f3, &g).is_ok());
+}
+
+#[rstest]
+fn many_instances_are_proved_and_verified_together() {
+    use ark_ff::UniformRand;
+    use gkr::prover::LinearGKRInstance;
+    use gkr::verifier::LinearGKRClaim;
+    use rand::SeedableRng;
+    let mut rng = rand::rngs::StdRng::seed_from_u64(10);
+    let setups: Vec<_> = (1..=5)
+        .map(|l| {
+            let (relation, witness) = gkr::simulate::random_instance(l % 3 + 1, 12, &mut rng);
+            let g: Vec<ScalarField> = (0..l % 3 + 1).map(|_| ScalarField::rand(&mut rng)).collect();
+            (relation, witness, g)
+        })
+        .collect();
+    let instances: Vec<_> = setups
+        .iter()
+        .map(|(r, w, g)| LinearGKRInstance { f1: &r.f1, f2: &w.f2, f3: &w.f3, g })
+        .collect();
+    let proofs = LinearGKRProver::prove_many(&instances);
+    for (inst, proof) in instances.iter().zip(&proofs) {
+        assert_eq!(*proof, LinearGKRProver::prove_noninteractive(inst.f1, inst.f2, inst.f3, inst.g));
+    }
+
+    let claims: Vec<_> = instances
+        .iter()
+        .zip(&proofs)
+        .map(|(inst, proof)| LinearGKRClaim { f1: inst.f1, g: inst.g, claimed_sum: proof.claimed_sum })
+        .collect();
+    let subclaims = LinearGKRVerifier::verify_many(&claims, &proofs).unwrap();
+    for ((inst, proof), subclaim) in instances.iter().zip(&proofs).zip(&subclaims) {
+        let single = LinearGKRVerifier::verify_noninteractive(inst.f1, inst.g, proof.claimed_sum, proof).unwrap();
+        assert_eq!(*subclaim, single);
+        assert!(subclaim.verify_against(inst.f1, inst.f2, inst.f3, inst.g).is_ok());
+    }
+
+    // 1 つでも壊れていれば，個別の検証と同じ理由で拒否する
+    let mut tampered = proofs.clone();
+    tampered[3].phase2_msgs[0][1] += ScalarField::from(1u64);
+    let err = LinearGKRVerifier::verify_many(&claims, &tampered).unwrap_err();
+    assert_eq!(err, Error::EvaluationMismatch("product of the final evaluations"));
+    tampered = proofs.clone();
+    tampered[1].f3_at_v += ScalarField::from(1u64);
+    let err = LinearGKRVerifier::verify_many(&claims, &tampered).unwrap_err();
+    assert_eq!(err, Error::EvaluationMismatch("product of the final evaluations"));
+    let err = LinearGKRVerifier::verify_many(&claims, &proofs[..4]).unwrap_err();
+    assert_eq!(err, Error::LengthMismatch { what: "proofs", expected: 5, found: 4 });
+}
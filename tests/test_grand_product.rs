@@ -0,0 +1,74 @@
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::One;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rstest::rstest;
+use gkr::error::Error;
+use gkr::gadgets::grand_product::{prove_product, prove_product_with, verify_product, verify_product_with};
+use gkr::ml_extension::{DenseMLE, IndexOrder};
+use gkr::transcript::Transcript;
+
+#[rstest]
+#[case(1)]
+#[case(3)]
+#[case(6)]
+fn honest_products_are_accepted(#[case] n: usize) {
+	let mut rng = StdRng::seed_from_u64(n as u64);
+	let values = DenseMLE::<ScalarField>::rand(n, &mut rng);
+	let product: ScalarField = values.evaluations.iter().product();
+	let proof = prove_product(&values);
+	assert_eq!(proof.product(), product);
+	let claim = verify_product(n, product, &proof).unwrap();
+	assert_eq!(claim.point.len(), n);
+	assert!(claim.verify_against(&values).is_ok());
+}
+
+#[rstest]
+fn index_order_does_not_change_the_claim() {
+	let mut rng = StdRng::seed_from_u64(1);
+	let values = DenseMLE::<ScalarField>::rand(4, &mut rng);
+	let little = values.to_order(IndexOrder::LittleEndian);
+	let proof = prove_product(&little);
+	let claim = verify_product(4, proof.product(), &proof).unwrap();
+	assert!(claim.verify_against(&values).is_ok());
+	assert!(claim.verify_against(&little).is_ok());
+}
+
+#[rstest]
+fn wrong_products_and_inputs_are_rejected() {
+	let mut rng = StdRng::seed_from_u64(2);
+	let values = DenseMLE::<ScalarField>::rand(3, &mut rng);
+	let proof = prove_product(&values);
+	let wrong = proof.product() + ScalarField::one();
+	assert_eq!(verify_product(3, wrong, &proof), Err(Error::EvaluationMismatch("claimed product")));
+
+	// 総積だけ書き換えた証明は層の還元で落ちる
+	let mut forged = proof.clone();
+	forged.proof.outputs[0] = wrong;
+	assert!(verify_product(3, wrong, &forged).is_err());
+
+	// 別の値で主張を確かめると落ちる
+	let claim = verify_product(3, proof.product(), &proof).unwrap();
+	let other = DenseMLE::<ScalarField>::rand(3, &mut rng);
+	assert_eq!(claim.verify_against(&other), Err(Error::EvaluationMismatch("input values at the final claim")));
+}
+
+#[rstest]
+fn products_compose_with_an_outer_transcript() {
+	let mut rng = StdRng::seed_from_u64(3);
+	let values = DenseMLE::<ScalarField>::rand(3, &mut rng);
+	let mut transcript = Transcript::new(b"outer");
+	transcript.append_message(b"commitment", b"values");
+	let proof = prove_product_with(&values, &mut transcript);
+
+	let mut transcript = Transcript::new(b"outer");
+	transcript.append_message(b"commitment", b"values");
+	let claim = verify_product_with(3, proof.product(), &proof, &mut transcript).unwrap();
+	assert!(claim.verify_against(&values).is_ok());
+
+	let mut transcript = Transcript::new(b"outer");
+	transcript.append_message(b"commitment", b"other");
+	if let Ok(claim) = verify_product_with(3, proof.product(), &proof, &mut transcript) {
+		assert!(claim.verify_against(&values).is_err());
+	}
+}
@@ -0,0 +1,33 @@
+use rstest::rstest;
+use std::collections::HashSet;
+use gkr::gray_code::{self, GrayPoint};
+
+#[rstest]
+#[case(0)]
+#[case(1)]
+#[case(4)]
+#[case(7)]
+fn gray_iteration_visits_every_point_once(#[case] n: usize) {
+	let points: Vec<GrayPoint> = gray_code::gray_code_points(n).collect();
+	assert_eq!(points.len(), 1 << n);
+	assert_eq!(gray_code::gray_code_points(n).len(), 1 << n);
+	let visited: HashSet<usize> = points.iter().map(|p| p.index).collect();
+	assert_eq!(visited.len(), 1 << n);
+	assert_eq!(points[0], GrayPoint { index: 0, flipped: None });
+
+	for w in points.windows(2) {
+		let diff = w[0].index ^ w[1].index;
+		assert_eq!(diff.count_ones(), 1);
+		// flipped は先頭の変数を 0 とする位置
+		let v = w[1].flipped.unwrap();
+		assert_eq!(diff, 1 << (n - 1 - v));
+		assert_eq!(w[1].flipped_to_one(n), Some(w[1].index & diff != 0));
+	}
+}
+
+#[test]
+fn gray_rank_inverts_gray_code() {
+	for i in 0..1024 {
+		assert_eq!(gray_code::gray_rank(gray_code::gray_code(i)), i);
+	}
+}
@@ -0,0 +1,49 @@
+use ark_bls12_381::Fr as ScalarField;
+use rstest::rstest;
+use gkr::hypercube::BooleanHypercube;
+use gkr::sumcheck::n_to_vec;
+
+#[rstest]
+#[case(0)]
+#[case(1)]
+#[case(4)]
+fn lexicographic_points_match_their_indices(#[case] n: usize) {
+	let mut cube = BooleanHypercube::<ScalarField>::new(n);
+	let mut count = 0;
+	while let Some((index, point)) = cube.next_point() {
+		assert_eq!(index, count);
+		assert_eq!(*point, n_to_vec::<ScalarField>(index, n));
+		count += 1;
+	}
+	assert_eq!(count, 1 << n);
+	assert!(cube.next_point().is_none());
+}
+
+#[rstest]
+#[case(1)]
+#[case(5)]
+fn gray_points_flip_one_coordinate(#[case] n: usize) {
+	let mut cube = BooleanHypercube::<ScalarField>::gray(n);
+	let mut seen = vec![false; 1 << n];
+	let mut previous: Option<Vec<ScalarField>> = None;
+	while let Some((index, point)) = cube.next_point() {
+		assert_eq!(*point, n_to_vec::<ScalarField>(index, n));
+		if let Some(prev) = previous {
+			assert_eq!(prev.iter().zip(point.iter()).filter(|(a, b)| a != b).count(), 1);
+		}
+		assert!(!seen[index]);
+		seen[index] = true;
+		previous = Some(point.clone());
+	}
+	assert!(seen.iter().all(|&s| s));
+}
+
+#[rstest]
+fn sums_agree_in_both_orders() {
+	let f = |p: &Vec<ScalarField>| p.iter().enumerate().map(|(i, x)| *x * ScalarField::from(i as u64 + 1)).sum::<ScalarField>() + p[0] * p[2];
+	let lex = BooleanHypercube::new(3).sum(f);
+	let gray = BooleanHypercube::gray(3).sum(f);
+	// Σ_i (i + 1)·4 + 2 = 4·(1 + 2 + 3) + 2
+	assert_eq!(lex, ScalarField::from(26u32));
+	assert_eq!(gray, lex);
+}
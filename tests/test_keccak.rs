@@ -0,0 +1,75 @@
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::One;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rstest::rstest;
+use gkr::error::Error;
+use gkr::gadgets::keccak::{self, keccak_f, keccak_p, state_bits, state_from_bits};
+use gkr::small_field::Goldilocks;
+
+fn random_states(count: usize, seed: u64) -> Vec<[u64; 25]> {
+	let mut rng = StdRng::seed_from_u64(seed);
+	(0..count).map(|_| std::array::from_fn(|_| rng.gen())).collect()
+}
+
+#[rstest]
+fn native_permutation_matches_the_known_vector() {
+	// Keccak-f[1600] の全て 0 の状態に対する既知の値（KeccakF-1600-IntermediateValues）
+	let mut state = [0u64; 25];
+	keccak_f(&mut state);
+	assert_eq!(state[0], 0xf1258f7940e1dde7);
+	assert_eq!(state[1], 0x84d5ccf933c0478a);
+}
+
+#[rstest]
+#[case(1)]
+#[case(2)]
+fn circuit_matches_the_native_permutation(#[case] rounds: usize) {
+	let built = keccak::circuit::<ScalarField>(rounds).unwrap();
+	let mut state = random_states(1, rounds as u64)[0];
+	let outputs = built.evaluate(&state_bits(&state));
+	keccak_p(&mut state, rounds);
+	assert_eq!(state_from_bits(&outputs), Ok(state));
+}
+
+#[rstest]
+fn boolean_circuit_runs_over_a_small_field() {
+	let built = keccak::circuit::<Goldilocks>(1).unwrap();
+	let mut state = random_states(1, 3)[0];
+	let outputs = built.evaluate(&state_bits(&state));
+	keccak_p(&mut state, 1);
+	assert_eq!(state_from_bits(&outputs), Ok(state));
+}
+
+#[rstest]
+fn batched_permutations_are_proved_together() {
+	let built = keccak::circuit::<ScalarField>(1).unwrap();
+	let states = random_states(3, 4);
+	let proof = keccak::prove_batch(&built, &states);
+	let expected: Vec<[u64; 25]> = states
+		.iter()
+		.map(|s| {
+			let mut s = *s;
+			keccak_p(&mut s, 1);
+			s
+		})
+		.collect();
+	assert_eq!(keccak::verify_batch(&built, &states, &proof), Ok(expected));
+
+	let mut forged = proof;
+	forged.outputs[1][0] += ScalarField::one();
+	assert!(keccak::verify_batch(&built, &states, &forged).is_err());
+}
+
+#[rstest]
+fn invalid_rounds_and_states_are_rejected() {
+	assert!(matches!(keccak::circuit::<ScalarField>(0), Err(Error::InvalidCircuit(_))));
+	assert!(matches!(keccak::circuit::<ScalarField>(25), Err(Error::InvalidCircuit(_))));
+	let mut bits = state_bits::<ScalarField>(&[0; 25]);
+	bits[7] = ScalarField::from(2u32);
+	assert_eq!(state_from_bits(&bits), Err(Error::MalformedProof("state value is not a bit")));
+	assert_eq!(
+		state_from_bits(&bits[1..]),
+		Err(Error::LengthMismatch { what: "state bits", expected: 1600, found: 1599 })
+	);
+}
@@ -0,0 +1,53 @@
+use ark_bls12_381::Fr as ScalarField;
+use rstest::rstest;
+use gkr::circuit_prover::{GKRProver, GKRVerifier};
+use gkr::error::Error;
+use gkr::layering::{DagGate, GateGraph};
+
+/// 位相順に並んでいない DAG：out = (a·b)·(a + c) + 5，ほかに a·b を 1 層目から直接出力する
+fn unordered_graph() -> GateGraph<ScalarField> {
+	GateGraph {
+		gates: vec![
+			DagGate::Add(1, 6),  // 0: (a·b)·(a + c) + 5
+			DagGate::Mul(2, 3),  // 1: (a·b)·(a + c)
+			DagGate::Mul(4, 5),  // 2: a·b
+			DagGate::Add(4, 7),  // 3: a + c
+			DagGate::Input,      // 4: a
+			DagGate::Input,      // 5: b
+			DagGate::Constant(5u32.into()),
+			DagGate::Input,      // 7: c
+		],
+		outputs: vec![0, 2],
+	}
+}
+
+#[rstest]
+fn dags_are_layered_in_topological_order() {
+	let graph = unordered_graph();
+	let layering = graph.layer().unwrap();
+	let order = &layering.topological_order;
+	let position = |i: usize| order.iter().position(|&j| j == i).unwrap();
+	assert!(position(4) < position(2) && position(2) < position(1) && position(1) < position(0));
+
+	assert_eq!(layering.depth(), 3);
+	// 出力層：out と a·b の中継，次の層：(a·b)·(a + c) と定数の中継と a·b の中継
+	assert_eq!(layering.widths(), &[2, 3, 3]);
+	assert!(layering.num_relays() > 0);
+
+	let built = &layering.built;
+	let (a, b, c) = (ScalarField::from(2u32), ScalarField::from(3u32), ScalarField::from(4u32));
+	assert_eq!(built.evaluate(&[a, b, c]), vec![a * b * (a + c) + ScalarField::from(5u32), a * b]);
+
+	let inputs = built.assign(&[a, b, c]);
+	let proof = GKRProver::prove_circuit(&built.circuit, &inputs);
+	assert!(GKRVerifier::verify_circuit(&built.circuit, &inputs, &proof).is_ok());
+}
+
+#[rstest]
+#[case::cycle(vec![DagGate::Add(1, 2), DagGate::Mul(0, 2), DagGate::Input], "gate graph has a cycle")]
+#[case::self_loop(vec![DagGate::Add(0, 1), DagGate::Input], "gate graph has a cycle")]
+#[case::unknown(vec![DagGate::Add(1, 5), DagGate::Input], "gate reads an unknown gate")]
+fn malformed_graphs_are_rejected(#[case] gates: Vec<DagGate<ScalarField>>, #[case] reason: &'static str) {
+	let graph = GateGraph { gates, outputs: vec![0] };
+	assert_eq!(graph.layer().err().map(|e| e.to_string()), Some(Error::InvalidCircuit(reason).to_string()));
+}
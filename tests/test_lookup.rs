@@ -0,0 +1,125 @@
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::{Field, One, Zero};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rstest::rstest;
+use gkr::error::Error;
+use gkr::gadgets::lookup::{
+	fractional_sum_circuit, prove_fractional_sum, prove_lookup, prove_lookup_with, verify_lookup, verify_lookup_with,
+	LookupProof, LOOKUP_LABEL,
+};
+use gkr::ml_extension::DenseMLE;
+use gkr::transcript::Transcript;
+
+fn field(values: &[u64]) -> Vec<ScalarField> {
+	values.iter().map(|&v| ScalarField::from(v)).collect()
+}
+
+/// 表 0..size から一様に引いた 2^n 個の witness
+fn random_witness(n: usize, size: u64, rng: &mut StdRng) -> DenseMLE<ScalarField> {
+	DenseMLE::from_evaluations_vec(n, (0..1 << n).map(|_| ScalarField::from(rng.gen_range(0..size))).collect())
+}
+
+/// 既定の入口と同じく witness の値を吸収した transcript
+fn lookup_transcript(witness: &DenseMLE<ScalarField>) -> Transcript {
+	let mut transcript = Transcript::new(LOOKUP_LABEL);
+	transcript.append_fields(b"lookup_witness", &witness.evaluations);
+	transcript
+}
+
+#[rstest]
+fn fractional_sum_circuit_adds_fractions() {
+	let (p, q) = (field(&[1, 2, 3, 4]), field(&[5, 6, 7, 8]));
+	let outputs = &fractional_sum_circuit(2).evaluate(&[p.clone(), q.clone()].concat())[0];
+	let expected: ScalarField = p.iter().zip(q.iter()).map(|(p, q)| *p / q).sum();
+	assert_eq!(outputs[0] / outputs[1], expected);
+}
+
+#[rstest]
+#[case(1, 4)]
+#[case(4, 16)]
+#[case(5, 3)]
+fn values_in_the_table_are_accepted(#[case] n: usize, #[case] size: u64) {
+	let mut rng = StdRng::seed_from_u64(n as u64);
+	let table: Vec<ScalarField> = (0..size).map(ScalarField::from).collect();
+	let witness = random_witness(n, size, &mut rng);
+	let proof = prove_lookup(&witness, &table).unwrap();
+	assert!(verify_lookup(&witness, &table, &proof).is_ok());
+}
+
+#[rstest]
+fn values_outside_the_table_are_rejected() {
+	let table = field(&[1, 2, 3]);
+	let witness = DenseMLE::from_evaluations_vec(2, field(&[1, 3, 0, 2]));
+	assert_eq!(prove_lookup(&witness, &table), Err(Error::NotInTable { index: 2 }));
+	assert_eq!(prove_lookup(&witness, &[]), Err(Error::InvalidCircuit("lookup table is empty")));
+}
+
+#[rstest]
+fn forged_proofs_are_rejected() {
+	let mut rng = StdRng::seed_from_u64(7);
+	let table: Vec<ScalarField> = (0..8).map(ScalarField::from).collect();
+	let witness = random_witness(3, 8, &mut rng);
+	let proof = prove_lookup(&witness, &table).unwrap();
+
+	let mut forged = proof.clone();
+	forged.multiplicities[0] += ScalarField::one();
+	assert!(verify_lookup(&witness, &table, &forged).is_err());
+
+	let mut forged = proof.clone();
+	forged.witness_proof.outputs[0] += ScalarField::one();
+	assert!(verify_lookup(&witness, &table, &forged).is_err());
+
+	// 別の witness で主張を確かめると落ちる
+	let claim = verify_lookup_with(3, &table, &proof, &mut lookup_transcript(&witness)).unwrap();
+	let other = DenseMLE::from_fn(3, |i| ScalarField::from(i as u64));
+	assert_eq!(claim.verify_against(&other), Err(Error::EvaluationMismatch("witness at the final claim")));
+	assert!(verify_lookup(&other, &table, &proof).is_err());
+}
+
+#[rstest]
+fn padding_does_not_admit_zero() {
+	// 表の長さ 3 を 4 に埋めても 0 は引けない
+	let table = field(&[1, 2, 3]);
+	let witness = DenseMLE::from_evaluations_vec(1, vec![ScalarField::zero(), ScalarField::one()]);
+	assert_eq!(prove_lookup(&witness, &table), Err(Error::NotInTable { index: 0 }));
+}
+
+#[rstest]
+fn lookups_compose_with_an_outer_transcript() {
+	let mut rng = StdRng::seed_from_u64(9);
+	let table: Vec<ScalarField> = (0..4).map(ScalarField::from).collect();
+	let witness = random_witness(3, 4, &mut rng);
+	let mut transcript = Transcript::new(b"outer");
+	let proof = prove_lookup_with(&witness, &table, &mut transcript).unwrap();
+	let claim = verify_lookup_with(3, &table, &proof, &mut Transcript::new(b"outer")).unwrap();
+	assert!(claim.verify_against(&witness).is_ok());
+	assert!(verify_lookup(&witness, &table, &proof).is_err());
+}
+
+#[rstest]
+fn witness_chosen_after_the_challenge_is_rejected() {
+	// 表 {0, 1} と回数 m = (2, 0) だけを吸収した transcript から x を引く
+	let table = field(&[0, 1]);
+	let multiplicities = field(&[2, 0]);
+	let mut transcript = Transcript::new(LOOKUP_LABEL);
+	transcript.append_message(b"lookup_witness_len", &2u64.to_le_bytes());
+	transcript.append_fields(b"lookup_table", &table);
+	transcript.append_fields(b"lookup_multiplicities", &multiplicities);
+	let x: ScalarField = transcript.challenge_field(b"lookup_challenge");
+
+	// x を見てから 1/(x + 5) + 1/(x + w_1) = 2/x となる w_1 を選ぶ（5 は表にない）
+	let w_0 = ScalarField::from(5u64);
+	let w_1 = (ScalarField::from(2u64) / x - (x + w_0).inverse().unwrap()).inverse().unwrap() - x;
+	let witness = DenseMLE::from_evaluations_vec(1, vec![w_0, w_1]);
+	let witness_proof = prove_fractional_sum(&[ScalarField::one(); 2], &[w_0, w_1], x, &mut transcript);
+	let table_proof = prove_fractional_sum(&multiplicities, &table, x, &mut transcript);
+	let proof = LookupProof { multiplicities, witness_proof, table_proof };
+
+	// witness に縛られない transcript では通ってしまう
+	let claim = verify_lookup_with(1, &table, &proof, &mut Transcript::new(LOOKUP_LABEL)).unwrap();
+	assert!(claim.verify_against(&witness).is_ok());
+	// 既定の入口は witness を吸収してから x を引くので落ちる
+	assert!(verify_lookup(&witness, &table, &proof).is_err());
+	assert!(verify_lookup_with(1, &table, &proof, &mut lookup_transcript(&witness)).is_err());
+}
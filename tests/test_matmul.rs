@@ -0,0 +1,63 @@
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::One;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rstest::rstest;
+use gkr::error::Error;
+use gkr::gadgets::matmul::{matrix_mle, multiply, prove_matmul, verify_matmul, MatMulShape, MATMUL_LABEL};
+use gkr::ml_extension::DenseMLE;
+use gkr::transcript::Transcript;
+
+fn random_matrices(shape: MatMulShape, seed: u64) -> (DenseMLE<ScalarField>, DenseMLE<ScalarField>) {
+	let mut rng = StdRng::seed_from_u64(seed);
+	let a = DenseMLE::rand(shape.row_vars + shape.inner_vars, &mut rng);
+	let b = DenseMLE::rand(shape.inner_vars + shape.col_vars, &mut rng);
+	(a, b)
+}
+
+fn matrix(rows: &[[u64; 2]]) -> DenseMLE<ScalarField> {
+	let rows: Vec<Vec<ScalarField>> = rows.iter().map(|r| r.iter().map(|&v| ScalarField::from(v)).collect()).collect();
+	matrix_mle(&rows)
+}
+
+#[rstest]
+fn multiply_matches_the_schoolbook_product() {
+	let c = multiply(&matrix(&[[1, 2], [3, 4]]), &matrix(&[[5, 6], [7, 8]]), MatMulShape::square(1));
+	assert_eq!(c.evaluations, matrix(&[[19, 22], [43, 50]]).evaluations);
+}
+
+#[rstest]
+#[case(MatMulShape::square(1))]
+#[case(MatMulShape::square(4))]
+#[case(MatMulShape { row_vars: 2, inner_vars: 3, col_vars: 0 })]
+#[case(MatMulShape { row_vars: 1, inner_vars: 0, col_vars: 2 })]
+fn honest_products_are_accepted(#[case] shape: MatMulShape) {
+	let (a, b) = random_matrices(shape, 1);
+	let c = multiply(&a, &b, shape);
+	let proof = prove_matmul(&a, &b, shape, &mut Transcript::new(MATMUL_LABEL));
+	assert_eq!(proof.msgs.len(), shape.inner_vars);
+	let claims = verify_matmul(shape, &proof, &mut Transcript::new(MATMUL_LABEL)).unwrap();
+	assert!(claims.verify_against(&a, &b, &c).is_ok());
+}
+
+#[rstest]
+fn wrong_products_are_rejected() {
+	let shape = MatMulShape::square(3);
+	let (a, b) = random_matrices(shape, 2);
+	let mut c = multiply(&a, &b, shape);
+	c.evaluations[5] += ScalarField::one();
+	let proof = prove_matmul(&a, &b, shape, &mut Transcript::new(MATMUL_LABEL));
+	let claims = verify_matmul(shape, &proof, &mut Transcript::new(MATMUL_LABEL)).unwrap();
+	assert_eq!(claims.verify_against(&a, &b, &c), Err(Error::EvaluationMismatch("C at (r_1, r_2)")));
+
+	let mut forged = proof.clone();
+	forged.claimed_value += ScalarField::one();
+	assert!(verify_matmul(shape, &forged, &mut Transcript::new(MATMUL_LABEL)).is_err());
+
+	let mut forged = proof;
+	forged.a_value += ScalarField::one();
+	assert_eq!(
+		verify_matmul(shape, &forged, &mut Transcript::new(MATMUL_LABEL)),
+		Err(Error::EvaluationMismatch("product of the final evaluations"))
+	);
+}
@@ -0,0 +1,305 @@
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::{One, UniformRand, Zero};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rstest::rstest;
+use gkr::ml_extension::{self, DenseMLE, IndexOrder, SparseMLE};
+use std::collections::HashMap;
+use gkr::self_check::direct_evaluation;
+
+fn random_mle(num_vars: usize, rng: &mut StdRng) -> DenseMLE<ScalarField> {
+	DenseMLE::rand(num_vars, rng)
+}
+
+#[rstest]
+#[case(1)]
+#[case(4)]
+#[case(14)]
+fn batch_fold_matches_direct_formula(#[case] num_vars: usize) {
+	let mut rng = StdRng::seed_from_u64(num_vars as u64);
+	let mles: Vec<_> = (0..3).map(|_| random_mle(num_vars, &mut rng)).collect();
+	let r = ScalarField::rand(&mut rng);
+
+	let mut folded = mles.clone();
+	ml_extension::fix_first_variable_batch(&mut folded, r);
+
+	let half = 1 << (num_vars - 1);
+	for (before, after) in mles.iter().zip(folded.iter()) {
+		assert_eq!(after.num_vars, num_vars - 1);
+		for i in 0..half {
+			let expected = (ScalarField::one() - r) * before.evaluations[i] + r * before.evaluations[i + half];
+			assert_eq!(after.evaluations[i], expected);
+		}
+	}
+
+	let mut single = mles[0].clone();
+	single.fix_first_variable_in_place(r);
+	assert_eq!(single.evaluations, folded[0].evaluations);
+}
+
+#[rstest]
+#[should_panic(expected = "num_vars mismatch")]
+fn batch_fold_rejects_mismatched_sizes() {
+	let mut rng = StdRng::seed_from_u64(0);
+	let mut mles = vec![random_mle(2, &mut rng), random_mle(3, &mut rng)];
+	ml_extension::fix_first_variable_batch(&mut mles, ScalarField::one());
+}
+
+#[rstest]
+#[case(0)]
+#[case(1)]
+#[case(5)]
+fn evaluate_interpolates_at_arbitrary_points(#[case] num_vars: usize) {
+	let mut rng = StdRng::seed_from_u64(num_vars as u64);
+	let mle = random_mle(num_vars, &mut rng);
+	let point: Vec<ScalarField> = (0..num_vars).map(|_| ScalarField::rand(&mut rng)).collect();
+	assert_eq!(mle.evaluate(&point), direct_evaluation(&mle.evaluations, &point));
+	// ブール点では表の値そのもの（先頭の座標が添字の最上位ビット）
+	for index in 0..1 << num_vars {
+		let boolean: Vec<ScalarField> = (0..num_vars)
+			.map(|i| if (index >> (num_vars - 1 - i)) & 1 == 1 { ScalarField::one() } else { ScalarField::zero() })
+			.collect();
+		assert_eq!(mle.evaluate(&boolean), mle.evaluations[index]);
+	}
+}
+
+#[rstest]
+#[case(4, 0, 0)]
+#[case(4, 1, 2)]
+#[case(6, 3, 3)]
+#[case(6, 0, 4)]
+fn fix_variables_matches_full_evaluation(#[case] num_vars: usize, #[case] num_prefix: usize, #[case] num_suffix: usize) {
+	let mut rng = StdRng::seed_from_u64((num_vars * 10 + num_prefix) as u64);
+	let mle = random_mle(num_vars, &mut rng);
+	let point: Vec<ScalarField> = (0..num_vars).map(|_| ScalarField::rand(&mut rng)).collect();
+	let (prefix, rest) = point.split_at(num_prefix);
+	let (middle, suffix) = rest.split_at(rest.len() - num_suffix);
+
+	let fixed = mle.fix_variables(prefix).fix_last_variables(suffix);
+	assert_eq!(fixed.num_vars, middle.len());
+	assert_eq!(fixed.evaluate(middle), mle.evaluate(&point));
+
+	// その場版は同じ表になる
+	let mut in_place = mle.clone();
+	in_place.fix_last_variables_in_place(suffix);
+	in_place.fix_variables_in_place(prefix);
+	assert_eq!(in_place.evaluations, fixed.evaluations);
+}
+
+fn random_sparse_mle(num_vars: usize, nnz: usize, rng: &mut StdRng) -> SparseMLE<ScalarField> {
+	let evaluations: HashMap<usize, ScalarField> =
+		(0..nnz).map(|i| ((i * 7919) % (1 << num_vars), ScalarField::rand(rng))).collect();
+	SparseMLE::new(num_vars, evaluations)
+}
+
+#[rstest]
+#[case(3, 1)]
+#[case(6, 2)]
+#[case(6, 6)]
+fn sparse_fix_variables_matches_dense(#[case] num_vars: usize, #[case] num_fixed: usize) {
+	let mut rng = StdRng::seed_from_u64((num_vars * 10 + num_fixed) as u64);
+	let sparse = random_sparse_mle(num_vars, 20, &mut rng);
+	let dense = sparse.to_dense_multilinear_extension();
+
+	let fixed: Vec<ScalarField> = (0..num_fixed).map(|_| ScalarField::rand(&mut rng)).collect();
+	let restricted = sparse.fix_variables(&fixed);
+	assert_eq!(restricted.num_vars, num_vars - num_fixed);
+	assert_eq!(restricted.to_dense_multilinear_extension().evaluations, dense.fix_variables(&fixed).evaluations);
+
+	// ブール点で固定すると先頭のビットが一致する要素だけが残る
+	let boolean: Vec<ScalarField> = (0..num_fixed).map(|i| ScalarField::from((i % 2) as u64)).collect();
+	let restricted = sparse.fix_variables(&boolean);
+	assert_eq!(restricted.to_dense_multilinear_extension().evaluations, dense.fix_variables(&boolean).evaluations);
+	assert!(restricted.evaluations.len() <= sparse.evaluations.len());
+}
+
+#[rstest]
+#[case(0, 1)]
+#[case(5, 12)]
+#[case(12, 40)]
+fn sparse_evaluate_matches_dense(#[case] num_vars: usize, #[case] nnz: usize) {
+	let mut rng = StdRng::seed_from_u64(num_vars as u64);
+	let sparse = random_sparse_mle(num_vars, nnz, &mut rng);
+	let point: Vec<ScalarField> = (0..num_vars).map(|_| ScalarField::rand(&mut rng)).collect();
+	assert_eq!(sparse.evaluate(&point), sparse.to_dense_multilinear_extension().evaluate(&point));
+}
+
+#[rstest]
+#[case(0)]
+#[case(3)]
+#[case(7)]
+fn index_order_conversion_preserves_the_polynomial(#[case] num_vars: usize) {
+	let mut rng = StdRng::seed_from_u64(num_vars as u64);
+	let dense = random_mle(num_vars, &mut rng);
+	let sparse = random_sparse_mle(num_vars, 10, &mut rng);
+	let point: Vec<ScalarField> = (0..num_vars).map(|_| ScalarField::rand(&mut rng)).collect();
+
+	let little = dense.to_order(IndexOrder::LittleEndian);
+	assert_eq!(little.order, IndexOrder::LittleEndian);
+	assert_eq!(little.evaluate(&point), dense.evaluate(&point));
+	assert_eq!(little.to_order(IndexOrder::BigEndian).evaluations, dense.evaluations);
+
+	let sparse_little = sparse.to_order(IndexOrder::LittleEndian);
+	assert_eq!(sparse_little.evaluate(&point), sparse.evaluate(&point));
+	assert_eq!(sparse_little.to_dense_multilinear_extension().evaluations, sparse.to_dense_multilinear_extension().to_order(IndexOrder::LittleEndian).evaluations);
+
+	// 部分評価も順序によらず同じ多項式になる
+	let (prefix, rest) = point.split_at(num_vars / 2);
+	assert_eq!(little.fix_variables(prefix).evaluate(rest), dense.evaluate(&point));
+	assert_eq!(little.fix_last_variables(rest).evaluate(prefix), dense.evaluate(&point));
+	assert_eq!(sparse_little.fix_variables(prefix).evaluate(rest), sparse.evaluate(&point));
+}
+
+#[rstest]
+#[case(0b0001, 4, 0b1000)]
+#[case(0b0110, 4, 0b0110)]
+#[case(0b101, 3, 0b101)]
+#[case(0b011, 3, 0b110)]
+fn reverse_bits_flips_the_low_bits(#[case] index: usize, #[case] num_vars: usize, #[case] expected: usize) {
+	assert_eq!(ml_extension::reverse_bits(index, num_vars), expected);
+	assert_eq!(IndexOrder::LittleEndian.convert_index(index, num_vars, IndexOrder::BigEndian), expected);
+}
+
+#[rstest]
+#[case(0, 0)]
+#[case(1, 0)]
+#[case(3, 2)]
+#[case(1000, 10)]
+fn padded_tables_round_up_to_a_power_of_two(#[case] len: usize, #[case] num_vars: usize) {
+	let mut rng = StdRng::seed_from_u64(len as u64);
+	let values: Vec<ScalarField> = (0..len).map(|_| ScalarField::rand(&mut rng)).collect();
+	let mle = DenseMLE::from_padded_evaluations(values.clone());
+	assert_eq!(mle.num_vars, num_vars);
+	assert_eq!(mle.evaluations[..len], values[..]);
+	assert!(mle.evaluations[len..].iter().all(|v| v.is_zero()));
+}
+
+#[rstest]
+#[case(0)]
+#[case(3)]
+#[case(10)]
+fn element_wise_operations_match_their_evaluations(#[case] num_vars: usize) {
+	let mut rng = StdRng::seed_from_u64(num_vars as u64 + 100);
+	let (p, q) = (random_mle(num_vars, &mut rng), random_mle(num_vars, &mut rng));
+	let point: Vec<ScalarField> = (0..num_vars).map(|_| ScalarField::rand(&mut rng)).collect();
+	let (a, b) = (p.evaluate(&point), q.evaluate(&point));
+	assert_eq!((&p + &q).evaluate(&point), a + b);
+	assert_eq!((&p - &q).evaluate(&point), a - b);
+	let mut acc = p.clone();
+	acc += &q;
+	acc -= &p;
+	assert_eq!(acc.evaluations, q.evaluations);
+
+	let c = ScalarField::rand(&mut rng);
+	let combined = DenseMLE::linear_combination(&[(c, &p), (ScalarField::from(3u64), &q), (-c, &q)]);
+	assert_eq!(combined.evaluate(&point), c * a + ScalarField::from(3u64) * b - c * b);
+
+	// 積の表はブール点でだけ P·Q に一致する
+	let product = p.hadamard(&q);
+	for i in 0..1 << num_vars {
+		assert_eq!(product.evaluations[i], p.evaluations[i] * q.evaluations[i]);
+	}
+}
+
+#[rstest]
+fn element_wise_operations_align_index_orders() {
+	let mut rng = StdRng::seed_from_u64(7);
+	let p = random_mle(4, &mut rng);
+	let q = random_mle(4, &mut rng);
+	let q_little = q.to_order(IndexOrder::LittleEndian);
+	let point: Vec<ScalarField> = (0..4).map(|_| ScalarField::rand(&mut rng)).collect();
+	let sum = &p + &q_little;
+	assert_eq!(sum.order, IndexOrder::BigEndian);
+	assert_eq!(sum.evaluate(&point), p.evaluate(&point) + q.evaluate(&point));
+	assert_eq!(p.hadamard(&q_little).evaluations, p.hadamard(&q).evaluations);
+}
+
+#[rstest]
+#[should_panic(expected = "num_vars mismatch")]
+fn element_wise_operations_check_num_vars() {
+	let mut rng = StdRng::seed_from_u64(8);
+	let _ = &random_mle(3, &mut rng) + &random_mle(4, &mut rng);
+}
+
+#[rstest]
+#[case(0)]
+#[case(5)]
+fn from_fn_fills_the_hypercube_in_index_order(#[case] num_vars: usize) {
+	let mle = DenseMLE::from_fn(num_vars, |i| ScalarField::from(i as u64 * 3 + 1));
+	assert_eq!(mle.evaluations.len(), 1 << num_vars);
+	assert_eq!(mle.order, IndexOrder::BigEndian);
+	for i in 0..1 << num_vars {
+		let point: Vec<ScalarField> = gkr::sumcheck::n_to_vec(i, num_vars);
+		assert_eq!(mle.evaluate(&point), ScalarField::from(i as u64 * 3 + 1));
+	}
+}
+
+#[rstest]
+#[case(4, 0)]
+#[case(4, 5)]
+#[case(4, 16)]
+#[case(20, 100)]
+fn random_mles_are_reproducible_and_have_the_requested_sparsity(#[case] num_vars: usize, #[case] nnz: usize) {
+	let sparse = SparseMLE::<ScalarField>::rand(num_vars, nnz, &mut StdRng::seed_from_u64(1));
+	assert_eq!(sparse.num_vars, num_vars);
+	assert_eq!(sparse.evaluations.len(), nnz);
+	assert!(sparse.evaluations.keys().all(|&i| i < 1 << num_vars));
+	let again = SparseMLE::<ScalarField>::rand(num_vars, nnz, &mut StdRng::seed_from_u64(1));
+	assert_eq!(sparse.evaluations, again.evaluations);
+
+	let small = num_vars.min(10);
+	let dense = DenseMLE::<ScalarField>::rand(small, &mut StdRng::seed_from_u64(2));
+	assert_eq!(dense.evaluations, DenseMLE::<ScalarField>::rand(small, &mut StdRng::seed_from_u64(2)).evaluations);
+	assert_ne!(dense.evaluations, DenseMLE::<ScalarField>::rand(small, &mut StdRng::seed_from_u64(3)).evaluations);
+}
+
+#[rstest]
+#[should_panic(expected = "more non-zero entries")]
+fn sparse_mles_cannot_exceed_the_hypercube() {
+	SparseMLE::<ScalarField>::rand(2, 5, &mut StdRng::seed_from_u64(0));
+}
+
+#[rstest]
+fn sparse_mles_from_entries_merge_duplicates() {
+	let one = ScalarField::one();
+	let mle = SparseMLE::from_entries(3, vec![(5, one), (2, one + one), (5, one), (7, one), (7, -one)]);
+	assert_eq!(mle.nnz(), 2);
+	let entries: Vec<(usize, ScalarField)> = mle.sorted_entries().collect();
+	assert_eq!(entries, vec![(2, one + one), (5, one + one)]);
+	assert_eq!(SparseMLE::<ScalarField>::from_entries(2, vec![]).nnz(), 0);
+}
+
+#[rstest]
+#[should_panic(expected = "outside the hypercube")]
+fn sparse_entries_must_lie_on_the_hypercube() {
+	SparseMLE::from_entries(2, vec![(4, ScalarField::one())]);
+}
+
+#[rstest]
+fn sparse_addition_aligns_orders_and_drops_cancelled_entries() {
+	let mut rng = StdRng::seed_from_u64(11);
+	let a = SparseMLE::<ScalarField>::rand(5, 8, &mut rng);
+	let b = SparseMLE::<ScalarField>::rand(5, 8, &mut rng).to_order(IndexOrder::LittleEndian);
+	let point: Vec<ScalarField> = (0..5).map(|_| ScalarField::rand(&mut rng)).collect();
+	let sum = &a + &b;
+	assert_eq!(sum.order, IndexOrder::BigEndian);
+	assert_eq!(sum.evaluate(&point), a.evaluate(&point) + b.evaluate(&point));
+
+	let negated = SparseMLE::from_entries(5, a.sorted_entries().map(|(i, v)| (i, -v)).collect());
+	let mut cancelled = a.clone();
+	cancelled += &negated;
+	assert_eq!(cancelled.nnz(), 0);
+}
+
+#[rstest]
+#[case(0.25, true)]
+#[case(0.1, false)]
+fn dense_tables_become_sparse_below_the_threshold(#[case] max_density: f64, #[case] converted: bool) {
+	// 16 点のうち 3 点だけが非零
+	let dense = DenseMLE::from_fn(4, |i| if i % 5 == 0 && i > 0 { ScalarField::from(i as u64) } else { ScalarField::zero() });
+	let sparse = SparseMLE::from_dense_if_sparse(&dense, max_density);
+	assert_eq!(sparse.is_some(), converted);
+	let sparse = SparseMLE::from(&dense);
+	assert_eq!(sparse.nnz(), 3);
+	assert_eq!(sparse.to_dense_multilinear_extension().evaluations, dense.evaluations);
+}
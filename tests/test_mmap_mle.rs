@@ -0,0 +1,68 @@
+#![cfg(feature = "mmap")]
+
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::UniformRand;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rstest::rstest;
+use std::path::PathBuf;
+use gkr::ml_extension::{DenseMLE, IndexOrder};
+use gkr::mmap_mle::MmapMLE;
+use gkr::serialization::field_byte_len;
+
+fn table_path(name: &str) -> PathBuf {
+	std::env::temp_dir().join(format!("gkr-mmap-{}-{}.bin", name, std::process::id()))
+}
+
+#[rstest]
+#[case(8, 1)]
+#[case(8, 5)]
+#[case(8, 1 << 16)]
+#[case(0, 4)]
+fn folding_the_file_matches_the_dense_table(#[case] num_vars: usize, #[case] working_set: usize) {
+	let mut rng = StdRng::seed_from_u64(num_vars as u64);
+	let dense = DenseMLE::<ScalarField>::rand(num_vars, &mut rng);
+	let path = table_path(&format!("fold-{}-{}", num_vars, working_set));
+	let mut table = MmapMLE::from_dense(&path, &dense).unwrap().with_working_set(working_set);
+	assert_eq!(table.to_dense().unwrap().evaluations, dense.evaluations);
+
+	let point: Vec<ScalarField> = (0..num_vars).map(|_| ScalarField::rand(&mut rng)).collect();
+	assert_eq!(table.evaluate(&point).unwrap(), dense.evaluate(&point));
+
+	let mut folded = dense.clone();
+	for (i, r) in point.iter().enumerate() {
+		table.fix_variable_in_place(*r).unwrap();
+		folded.fix_first_variable_in_place(*r);
+		assert_eq!(table.num_vars(), num_vars - i - 1);
+		assert_eq!(table.to_dense().unwrap().evaluations, folded.evaluations);
+		let bytes = std::fs::metadata(&path).unwrap().len() as usize;
+		assert_eq!(bytes, (1 << table.num_vars()) * field_byte_len::<ScalarField>());
+	}
+	assert_eq!(table.get(0).unwrap(), dense.evaluate(&point));
+	std::fs::remove_file(&path).unwrap();
+}
+
+#[rstest]
+fn little_endian_tables_are_written_in_big_endian_order() {
+	let values: Vec<ScalarField> = (0..8u64).map(ScalarField::from).collect();
+	let dense = DenseMLE::from_evaluations_vec(3, values).to_order(IndexOrder::LittleEndian);
+	let path = table_path("order");
+	let table = MmapMLE::from_dense(&path, &dense).unwrap();
+	assert_eq!(table.to_dense().unwrap().evaluations, dense.to_order(IndexOrder::BigEndian).evaluations);
+	std::fs::remove_file(&path).unwrap();
+}
+
+#[rstest]
+fn reopening_keeps_the_values_and_checks_the_length() {
+	let path = table_path("open");
+	let table = MmapMLE::create(&path, 4, |i| ScalarField::from(i as u64 * 3)).unwrap();
+	table.flush().unwrap();
+	drop(table);
+
+	let reopened = MmapMLE::<ScalarField>::open(&path, 4).unwrap();
+	assert_eq!(reopened.get(5).unwrap(), ScalarField::from(15u64));
+	drop(reopened);
+	let err = MmapMLE::<ScalarField>::open(&path, 5).unwrap_err();
+	assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+	std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,121 @@
+#![cfg(feature = "net")]
+
+use ark_bls12_381::Fr as ScalarField;
+use ark_std::UniformRand;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rstest::rstest;
+use std::collections::HashMap;
+use std::future::Future;
+use tokio::net::{TcpListener, TcpStream};
+use gkr::error::Error;
+use gkr::ml_extension::{DenseMLE, SparseMLE};
+use gkr::net::{decode_message, encode_message, run_prover, run_verifier, NetError};
+use gkr::prover::{LinearGKRPrecomputation, LinearGKRProver};
+use gkr::session::{ProverMessage, ProverSession, VerifierSession};
+use gkr::transcript::Transcript;
+
+struct Layer {
+	f1: SparseMLE<ScalarField>,
+	f2: DenseMLE<ScalarField>,
+	f3: DenseMLE<ScalarField>,
+	g: Vec<ScalarField>,
+	pre: LinearGKRPrecomputation<ScalarField>,
+	claimed_sum: ScalarField,
+}
+
+fn random_layer(l: usize, seed: u64) -> Layer {
+	let mut rng = StdRng::seed_from_u64(seed);
+	let entries: HashMap<usize, ScalarField> =
+		(0..4 << l).map(|_| (rng.gen_range(0..1 << (3 * l)), ScalarField::rand(&mut rng))).collect();
+	let dense = |rng: &mut StdRng| DenseMLE::from_evaluations_vec(l, (0..1 << l).map(|_| ScalarField::rand(rng)).collect());
+	let (f1, f2, f3) = (SparseMLE::new(3 * l, entries), dense(&mut rng), dense(&mut rng));
+	let g: Vec<ScalarField> = (0..l).map(|_| ScalarField::rand(&mut rng)).collect();
+	let pre = LinearGKRProver::precompute(&f1, &f2, &f3);
+	let claimed_sum = LinearGKRProver::prove(&f1, &f2, &f3, &g, &mut Transcript::new(b"sum")).claimed_sum;
+	Layer { f1, f2, f3, g, pre, claimed_sum }
+}
+
+fn block_on<T>(future: impl Future<Output = T>) -> T {
+	tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(future)
+}
+
+#[rstest]
+fn prover_and_verifier_interact_over_a_duplex_stream() {
+	let layer = random_layer(3, 1);
+	let (mut prover_end, verifier_end) = tokio::io::duplex(64);
+	let (proof, subclaim) = block_on(async {
+		let prover = run_prover(&mut prover_end, ProverSession::new(&layer.pre, &layer.g));
+		let verifier = async move {
+			let mut stream = verifier_end;
+			let mut rng = StdRng::seed_from_u64(2);
+			run_verifier(&mut stream, VerifierSession::new(3, layer.claimed_sum), &mut rng).await
+		};
+		tokio::join!(prover, verifier)
+	});
+	let subclaim = subclaim.unwrap();
+	assert_eq!(proof.unwrap().phase2_msgs.len(), 3);
+	assert!(subclaim.verify_against(&layer.f1, &layer.f2, &layer.f3, &layer.g).is_ok());
+}
+
+#[rstest]
+fn prover_and_verifier_interact_over_tcp() {
+	let layer = random_layer(2, 3);
+	let subclaim = block_on(async {
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let addr = listener.local_addr().unwrap();
+		let verifier = async {
+			let (mut stream, _) = listener.accept().await.unwrap();
+			let mut rng = StdRng::seed_from_u64(4);
+			run_verifier(&mut stream, VerifierSession::new(2, layer.claimed_sum), &mut rng).await
+		};
+		let prover = async {
+			let mut stream = TcpStream::connect(addr).await.unwrap();
+			run_prover(&mut stream, ProverSession::new(&layer.pre, &layer.g)).await
+		};
+		let (subclaim, proof) = tokio::join!(verifier, prover);
+		proof.unwrap();
+		subclaim
+	});
+	assert!(subclaim.unwrap().verify_against(&layer.f1, &layer.f2, &layer.f3, &layer.g).is_ok());
+}
+
+#[rstest]
+fn rejection_ends_the_interaction_on_both_sides() {
+	let layer = random_layer(2, 5);
+	let (mut prover_end, verifier_end) = tokio::io::duplex(64);
+	let (proof, subclaim) = block_on(async {
+		let prover = run_prover(&mut prover_end, ProverSession::new(&layer.pre, &layer.g));
+		let verifier = async move {
+			let mut stream = verifier_end;
+			let mut rng = StdRng::seed_from_u64(6);
+			let wrong_sum = layer.claimed_sum + ScalarField::from(1u32);
+			run_verifier(&mut stream, VerifierSession::new(2, wrong_sum), &mut rng).await
+		};
+		tokio::join!(prover, verifier)
+	});
+	assert!(matches!(subclaim, Err(NetError::Protocol(Error::EvaluationMismatch("claimed sum")))));
+	assert!(matches!(proof, Err(NetError::Io(_))));
+}
+
+#[rstest]
+fn messages_round_trip_through_frames() {
+	let x = ScalarField::from(7u32);
+	let messages = vec![
+		ProverMessage::ClaimedSum(x),
+		ProverMessage::Round(vec![x, -x, x * x]),
+		ProverMessage::FinalEvaluations { f1_at_guv: x, f2_at_u: -x, f3_at_v: x + x },
+	];
+	for msg in messages {
+		let frame = encode_message(&msg);
+		assert_eq!(decode_message::<ScalarField>(&frame).unwrap(), msg);
+		assert!(decode_message::<ScalarField>(&frame[..frame.len() - 1]).is_err());
+		let mut trailing = frame.clone();
+		trailing.push(0);
+		assert!(decode_message::<ScalarField>(&trailing).is_err());
+	}
+	assert!(matches!(decode_message::<ScalarField>(&[9]), Err(NetError::UnknownMessage(9))));
+	assert!(decode_message::<ScalarField>(&[]).is_err());
+	// 巨大な個数を主張するラウンドは確保する前に拒否する
+	assert!(decode_message::<ScalarField>(&[1, 0xff, 0xff, 0xff, 0xff]).is_err());
+}
@@ -0,0 +1,92 @@
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::One;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rstest::rstest;
+use gkr::error::Error;
+use gkr::gadgets::nn::{
+	from_signed, prove_inference, to_signed, verify_inference, FixedPoint, Network, INFERENCE_LABEL,
+};
+use gkr::ml_extension::DenseMLE;
+use gkr::transcript::Transcript;
+
+const QUANT: FixedPoint = FixedPoint { bits: 8, frac_bits: 3 };
+
+/// [-1, 1] の値を量子化した 2^num_vars 個の値
+fn random_values(num_vars: usize, scale: FixedPoint, rng: &mut StdRng) -> DenseMLE<ScalarField> {
+	let values = (0..1 << num_vars).map(|_| scale.quantize(rng.gen_range(-1.0..1.0))).collect();
+	DenseMLE::from_evaluations_vec(num_vars, values)
+}
+
+/// 4 → 4 → 2 の 2 層の全結合ネットワーク（バッチ 2）
+fn network(rng: &mut StdRng) -> Network<ScalarField> {
+	let bias_scale = FixedPoint { bits: 16, frac_bits: 2 * QUANT.frac_bits };
+	Network::new(QUANT, 2, 1)
+		.dense(random_values(4, QUANT, rng), random_values(2, bias_scale, rng))
+		.rescale()
+		.relu()
+		.dense(random_values(3, QUANT, rng), random_values(1, bias_scale, rng))
+		.rescale()
+}
+
+#[rstest]
+fn signed_values_round_trip() {
+	for x in [-5i64, 0, 7, -(1 << 40)] {
+		assert_eq!(to_signed(from_signed::<ScalarField>(x)), x);
+	}
+	assert_eq!(QUANT.dequantize(QUANT.quantize::<ScalarField>(-0.75)), -0.75);
+}
+
+#[rstest]
+fn forward_pass_applies_relu_and_rescale() {
+	let mut rng = StdRng::seed_from_u64(0);
+	let net = network(&mut rng);
+	let trace = net.forward(&random_values(3, QUANT, &mut rng));
+	assert_eq!(trace.len(), 6);
+	assert!(trace[3].evaluations.iter().all(|v| to_signed(*v) >= 0));
+	for (x, y) in trace[1].evaluations.iter().zip(trace[2].evaluations.iter()) {
+		assert_eq!(to_signed(*y), to_signed(*x).div_euclid(1 << QUANT.frac_bits));
+	}
+}
+
+#[rstest]
+fn honest_inference_is_accepted() {
+	let mut rng = StdRng::seed_from_u64(1);
+	let net = network(&mut rng);
+	let input = random_values(3, QUANT, &mut rng);
+	let (trace, proof) = prove_inference(&net, &input, &mut Transcript::new(INFERENCE_LABEL)).unwrap();
+	let claims = verify_inference(&net, &proof, &mut Transcript::new(INFERENCE_LABEL)).unwrap();
+	assert!(claims.verify_against(&trace).is_ok());
+}
+
+#[rstest]
+fn tampered_traces_and_proofs_are_rejected() {
+	let mut rng = StdRng::seed_from_u64(2);
+	let net = network(&mut rng);
+	let input = random_values(3, QUANT, &mut rng);
+	let (trace, proof) = prove_inference(&net, &input, &mut Transcript::new(INFERENCE_LABEL)).unwrap();
+	let claims = verify_inference(&net, &proof, &mut Transcript::new(INFERENCE_LABEL)).unwrap();
+	for i in 1..trace.len() {
+		let mut forged = trace.clone();
+		forged[i].evaluations[0] += ScalarField::one();
+		assert_eq!(claims.verify_against(&forged), Err(Error::EvaluationMismatch("activation at the final claim")));
+	}
+
+	let mut swapped = proof.clone();
+	swapped.layer_proofs.swap(0, 1);
+	assert_eq!(
+		verify_inference(&net, &swapped, &mut Transcript::new(INFERENCE_LABEL)),
+		Err(Error::MalformedProof("layer proof does not match the layer"))
+	);
+}
+
+#[rstest]
+fn values_outside_the_relu_range_are_rejected() {
+	// 再スケールを省くと全結合層の出力が 8 ビットを超える
+	let weights = DenseMLE::from_evaluations_vec(2, vec![from_signed(100); 4]);
+	let bias = DenseMLE::from_evaluations_vec(1, vec![ScalarField::one(); 2]);
+	let net = Network::new(QUANT, 1, 0).dense(weights, bias).relu();
+	let input = DenseMLE::from_evaluations_vec(1, vec![from_signed(100); 2]);
+	let result = prove_inference(&net, &input, &mut Transcript::new(INFERENCE_LABEL));
+	assert!(matches!(result, Err(Error::NotInTable { .. })));
+}
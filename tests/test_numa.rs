@@ -0,0 +1,29 @@
+#![cfg(all(feature = "numa", target_os = "linux"))]
+
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::Zero;
+use rstest::rstest;
+use gkr::numa::{self, NumaPolicy};
+
+#[rstest]
+fn cpu_list_parsing() {
+	assert_eq!(numa::parse_cpu_list("0-3,8,10-11"), Some(vec![0, 1, 2, 3, 8, 10, 11]));
+	assert_eq!(numa::parse_cpu_list("x"), None);
+	assert!(!numa::online_nodes().is_empty());
+}
+
+#[rstest]
+#[case(NumaPolicy::Default)]
+#[case(NumaPolicy::Interleave)]
+#[case(NumaPolicy::NodeLocal)]
+fn tables_are_zeroed_under_every_policy(#[case] policy: NumaPolicy) {
+	let mle = numa::zeroed_mle::<ScalarField>(12, policy);
+	assert_eq!(mle.evaluations.len(), 1 << 12);
+	assert!(mle.evaluations.iter().all(|e| e.is_zero()));
+}
+
+#[rstest]
+fn pinned_pool_runs_jobs() {
+	let pool = numa::pinned_thread_pool(2).unwrap();
+	assert_eq!(pool.install(rayon::current_num_threads), 2);
+}
@@ -0,0 +1,33 @@
+#![cfg(feature = "parallel")]
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rstest::rstest;
+use gkr::parallel;
+use gkr::prover::LinearGKRProver;
+use gkr::simulate::{self, random_instance};
+use gkr::transcript::Transcript;
+use gkr::verifier::LinearGKRVerifier;
+
+#[rstest]
+#[case(1)]
+#[case(4)]
+fn thread_pool_size_is_configurable(#[case] n: usize) {
+	assert_eq!(parallel::with_num_threads(n, parallel::num_threads).unwrap(), n);
+}
+
+// 表がスレッドあたりの担当範囲より大きくなる変数数で，スレッド数によらず同じ証明になる
+#[rstest]
+fn proofs_do_not_depend_on_the_thread_count() {
+	let mut rng = StdRng::seed_from_u64(21);
+	let (relation, witness) = random_instance(14, 2000, &mut rng);
+	let prove = || LinearGKRProver::prove(&relation.f1, &witness.f2, &witness.f3, &relation.g, &mut Transcript::new(b"parallel_test"));
+	let single = parallel::with_num_threads(1, prove).unwrap();
+	let multi = parallel::with_num_threads(4, prove).unwrap();
+	assert_eq!(single, multi);
+
+	let claimed_sum = simulate::reference_claimed_sum(&relation, &witness);
+	assert_eq!(single.claimed_sum, claimed_sum);
+	let subclaim = LinearGKRVerifier::verify(14, claimed_sum, &multi, &mut Transcript::new(b"parallel_test")).unwrap();
+	assert!(subclaim.verify_against(&relation.f1, &witness.f2, &witness.f3, &relation.g).is_ok());
+}
@@ -0,0 +1,195 @@
+use ark_bls12_381::Fr as ScalarField;
+use ark_ed_on_bls12_381::Fr as JubjubScalar;
+use ark_ff::{One, PrimeField};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rstest::rstest;
+use gkr::error::Error;
+use gkr::ml_extension::{DenseMLE, IndexOrder, SparseMLE};
+use gkr::pcs::{JubjubHyrax, KzgSrs, MultilinearKzg, MultilinearPCS};
+use gkr::serialization::{PointEncoding, SerializationError};
+use gkr::wiring;
+use gkr::circuit::{Circuit, Gate, Layer};
+use std::collections::HashMap;
+
+fn random_point<F: PrimeField>(num_vars: usize, rng: &mut StdRng) -> Vec<F> {
+	(0..num_vars).map(|_| F::rand(rng)).collect()
+}
+
+#[rstest]
+#[case(0)]
+#[case(1)]
+#[case(3)]
+#[case(5)]
+fn openings_are_accepted(#[case] num_vars: usize) {
+	let mut rng = StdRng::seed_from_u64(num_vars as u64);
+	let (pp, vp) = MultilinearKzg::setup(5, &mut rng);
+	let mle: DenseMLE<ScalarField> = DenseMLE::rand(num_vars, &mut rng);
+	let point = random_point(num_vars, &mut rng);
+	let commitment = MultilinearKzg::commit(&pp, &mle);
+	let (value, proof) = MultilinearKzg::open(&pp, &mle, &point);
+	assert_eq!(value, mle.evaluate(&point));
+	assert_eq!(proof.len(), num_vars);
+	assert!(MultilinearKzg::verify(&vp, &commitment, &point, value, &proof).is_ok());
+}
+
+#[rstest]
+fn index_order_does_not_change_the_commitment() {
+	let mut rng = StdRng::seed_from_u64(1);
+	let (pp, _) = MultilinearKzg::setup(3, &mut rng);
+	let mle: DenseMLE<ScalarField> = DenseMLE::rand(3, &mut rng);
+	assert_eq!(
+		MultilinearKzg::commit(&pp, &mle),
+		MultilinearKzg::commit(&pp, &mle.to_order(IndexOrder::LittleEndian))
+	);
+}
+
+#[rstest]
+#[case::value(0)]
+#[case::point(1)]
+#[case::quotient(2)]
+#[case::commitment(3)]
+fn wrong_openings_are_rejected(#[case] target: usize) {
+	let mut rng = StdRng::seed_from_u64(2);
+	let (pp, vp) = MultilinearKzg::setup(4, &mut rng);
+	let mle: DenseMLE<ScalarField> = DenseMLE::rand(4, &mut rng);
+	let mut point = random_point(4, &mut rng);
+	let mut commitment = MultilinearKzg::commit(&pp, &mle);
+	let (mut value, mut proof) = MultilinearKzg::open(&pp, &mle, &point);
+	match target {
+		0 => value += ScalarField::one(),
+		1 => point[2] += ScalarField::one(),
+		2 => proof.swap(0, 1),
+		_ => commitment = MultilinearKzg::commit(&pp, &DenseMLE::<ScalarField>::rand(4, &mut rng)),
+	}
+	assert_eq!(
+		MultilinearKzg::verify(&vp, &commitment, &point, value, &proof),
+		Err(Error::EvaluationMismatch("polynomial commitment opening"))
+	);
+}
+
+#[rstest]
+fn truncated_proofs_are_rejected() {
+	let mut rng = StdRng::seed_from_u64(3);
+	let (pp, vp) = MultilinearKzg::setup(3, &mut rng);
+	let mle: DenseMLE<ScalarField> = DenseMLE::rand(3, &mut rng);
+	let point = random_point(3, &mut rng);
+	let commitment = MultilinearKzg::commit(&pp, &mle);
+	let (value, mut proof) = MultilinearKzg::open(&pp, &mle, &point);
+	proof.pop();
+	assert_eq!(
+		MultilinearKzg::verify(&vp, &commitment, &point, value, &proof),
+		Err(Error::LengthMismatch { what: "opening quotients", expected: 3, found: 2 })
+	);
+}
+
+#[rstest]
+#[case(0)]
+#[case(1)]
+#[case(4)]
+#[case(5)]
+fn hyrax_openings_are_accepted(#[case] num_vars: usize) {
+	let mut rng = StdRng::seed_from_u64(num_vars as u64);
+	let params = JubjubHyrax::setup(5, b"hyrax_test");
+	let mle: DenseMLE<JubjubScalar> = DenseMLE::rand(num_vars, &mut rng);
+	let point = random_point(num_vars, &mut rng);
+	let commitment = JubjubHyrax::commit(&params, &mle);
+	// 2^{⌊k/2⌋} 行のコミットメントと 2^{⌈k/2⌉} 個の値の証明
+	assert_eq!(commitment.len(), 1 << (num_vars / 2));
+	let (value, proof) = JubjubHyrax::open(&params, &mle, &point);
+	assert_eq!(proof.len(), 1 << num_vars.div_ceil(2));
+	assert_eq!(value, mle.evaluate(&point));
+	assert!(JubjubHyrax::verify(&params, &commitment, &point, value, &proof).is_ok());
+}
+
+#[rstest]
+fn hyrax_setup_is_deterministic() {
+	let mut rng = StdRng::seed_from_u64(4);
+	let mle: DenseMLE<JubjubScalar> = DenseMLE::rand(4, &mut rng);
+	let commitment = JubjubHyrax::commit(&JubjubHyrax::setup(4, b"hyrax_test"), &mle);
+	assert_eq!(commitment, JubjubHyrax::commit(&JubjubHyrax::setup(4, b"hyrax_test"), &mle));
+	assert_ne!(commitment, JubjubHyrax::commit(&JubjubHyrax::setup(4, b"other_label"), &mle));
+}
+
+#[rstest]
+#[case::value(0)]
+#[case::point(1)]
+#[case::combined_row(2)]
+#[case::commitment(3)]
+fn wrong_hyrax_openings_are_rejected(#[case] target: usize) {
+	let mut rng = StdRng::seed_from_u64(5);
+	let params = JubjubHyrax::setup(4, b"hyrax_test");
+	let mle: DenseMLE<JubjubScalar> = DenseMLE::rand(4, &mut rng);
+	let mut point = random_point(4, &mut rng);
+	let mut commitment = JubjubHyrax::commit(&params, &mle);
+	let (mut value, mut proof) = JubjubHyrax::open(&params, &mle, &point);
+	match target {
+		0 => value += JubjubScalar::one(),
+		1 => point[0] += JubjubScalar::one(),
+		2 => proof[3] += JubjubScalar::one(),
+		_ => commitment.swap(0, 1),
+	}
+	assert_eq!(
+		JubjubHyrax::verify(&params, &commitment, &point, value, &proof),
+		Err(Error::EvaluationMismatch("polynomial commitment opening"))
+	);
+}
+
+#[rstest]
+#[case(0)]
+#[case(2)]
+#[case(4)]
+fn trimmed_srs_commits_like_the_full_one(#[case] num_vars: usize) {
+	let mut rng = StdRng::seed_from_u64(6);
+	let srs = MultilinearKzg::gen_srs(4, &mut rng);
+	let (full_pp, _) = srs.trim(4);
+	let (pp, vp) = srs.trim(num_vars);
+	assert_eq!(vp.h_tau.len(), num_vars);
+	let mle: DenseMLE<ScalarField> = DenseMLE::rand(num_vars, &mut rng);
+	let point = random_point(num_vars, &mut rng);
+	let commitment = MultilinearKzg::commit(&pp, &mle);
+	assert_eq!(commitment, MultilinearKzg::commit(&full_pp, &mle));
+	let (value, proof) = MultilinearKzg::open(&pp, &mle, &point);
+	assert!(MultilinearKzg::verify(&vp, &commitment, &point, value, &proof).is_ok());
+}
+
+#[rstest]
+#[case(PointEncoding::Compressed)]
+#[case(PointEncoding::Uncompressed)]
+fn srs_round_trips_through_bytes(#[case] encoding: PointEncoding) {
+	let mut rng = StdRng::seed_from_u64(7);
+	let srs = MultilinearKzg::gen_srs(3, &mut rng);
+	let bytes = srs.to_bytes(encoding);
+	assert_eq!(KzgSrs::from_bytes(&bytes, encoding).unwrap(), srs);
+
+	let mut longer = bytes.clone();
+	longer.push(0);
+	assert!(matches!(KzgSrs::from_bytes(&longer, encoding), Err(SerializationError::TrailingBytes(1))));
+	assert!(KzgSrs::from_bytes(&bytes[..bytes.len() - 1], encoding).is_err());
+}
+
+#[rstest]
+fn wiring_predicates_are_committed_sparsely() {
+	let mut rng = StdRng::seed_from_u64(8);
+	let circuit = Circuit::new(4, vec![Layer { gates: vec![Gate::Mul(0, 1), Gate::Mul(2, 3), Gate::Add(1, 2)] }]).unwrap();
+	let layer = wiring::circuit_wiring::<ScalarField>(&circuit).remove(0);
+	let n = layer.mul.num_vars;
+	let (pp, vp) = MultilinearKzg::setup(n, &mut rng);
+	let commitment = MultilinearKzg::commit_sparse(&pp, &layer.mul);
+	assert_eq!(commitment, MultilinearKzg::commit(&pp, &layer.mul.to_dense_multilinear_extension()));
+	let point = random_point(n, &mut rng);
+	let (value, proof) = MultilinearKzg::open(&pp, &layer.mul.to_dense_multilinear_extension(), &point);
+	assert_eq!(value, layer.mul.evaluate(&point));
+	assert!(MultilinearKzg::verify(&vp, &commitment, &point, value, &proof).is_ok());
+}
+
+#[rstest]
+fn hyrax_commits_sparse_tables_like_dense_ones() {
+	let params = JubjubHyrax::setup(5, b"hyrax_test");
+	let evaluations: HashMap<usize, JubjubScalar> = (0..6).map(|i| (i * 5, JubjubScalar::from(i as u64 + 1))).collect();
+	let mle = SparseMLE::new(5, evaluations);
+	assert_eq!(
+		JubjubHyrax::commit_sparse(&params, &mle),
+		JubjubHyrax::commit(&params, &mle.to_dense_multilinear_extension())
+	);
+}
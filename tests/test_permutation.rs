@@ -0,0 +1,109 @@
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::{Field, One};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rstest::rstest;
+use gkr::error::Error;
+use gkr::gadgets::permutation::{
+	prove_multiset_equality, prove_permutation, verify_multiset_equality, verify_permutation, PERMUTATION_LABEL,
+};
+use gkr::ml_extension::DenseMLE;
+use gkr::transcript::Transcript;
+
+/// 列 a と，a を同じ置換 σ で並べ替えた列
+fn shuffled(n: usize, rng: &mut StdRng) -> (DenseMLE<ScalarField>, DenseMLE<ScalarField>, Vec<usize>) {
+	let a = DenseMLE::rand(n, rng);
+	let mut sigma: Vec<usize> = (0..1 << n).collect();
+	sigma.shuffle(rng);
+	let b = DenseMLE::from_fn(n, |i| a.evaluations[sigma[i]]);
+	(a, b, sigma)
+}
+
+/// 既定の入口と同じく両辺の列を吸収した transcript
+fn permutation_transcript(a: &DenseMLE<ScalarField>, b: &DenseMLE<ScalarField>) -> Transcript {
+	let mut transcript = Transcript::new(PERMUTATION_LABEL);
+	transcript.append_fields(b"permutation_lhs", &a.evaluations);
+	transcript.append_fields(b"permutation_rhs", &b.evaluations);
+	transcript
+}
+
+#[rstest]
+#[case(1)]
+#[case(4)]
+fn permuted_columns_are_accepted(#[case] n: usize) {
+	let mut rng = StdRng::seed_from_u64(n as u64);
+	let (a, b, _) = shuffled(n, &mut rng);
+	let proof = prove_permutation(&a, &b);
+	assert!(verify_permutation(&a, &b, &proof).is_ok());
+	let claims = verify_multiset_equality(n, 1, &proof, &mut permutation_transcript(&a, &b)).unwrap();
+	assert!(claims.verify_against(&[a], &[b]).is_ok());
+}
+
+#[rstest]
+fn different_multisets_are_rejected() {
+	let mut rng = StdRng::seed_from_u64(1);
+	let (a, mut b, _) = shuffled(3, &mut rng);
+	b.evaluations[0] += ScalarField::one();
+	let proof = prove_permutation(&a, &b);
+	assert_eq!(verify_permutation(&a, &b, &proof), Err(Error::EvaluationMismatch("products of both sides")));
+
+	// 片側の総積を書き換えても，その側の還元か列の主張で落ちる
+	let mut forged = proof.clone();
+	forged.rhs.proof.outputs[0] = proof.lhs.product();
+	assert!(verify_permutation(&a, &b, &forged).is_err());
+}
+
+#[rstest]
+fn columns_chosen_after_the_challenge_are_rejected() {
+	// 列を吸収しない transcript から γ を引く（1 列なので β は無い）
+	let mut transcript = Transcript::new(PERMUTATION_LABEL);
+	transcript.append_message(b"permutation_num_vars", &1u64.to_le_bytes());
+	transcript.append_message(b"permutation_num_columns", &1u64.to_le_bytes());
+	let gamma: ScalarField = transcript.challenge_field(b"permutation_gamma");
+
+	// γ を見てから (γ - a_0)(γ - a_1) = (γ - 5)(γ - b_1) となる b_1 を選ぶ（b は a の置換でない）
+	let a = DenseMLE::from_evaluations_vec(1, vec![ScalarField::from(1u64), ScalarField::from(2u64)]);
+	let b_0 = ScalarField::from(5u64);
+	let b_1 = gamma - (gamma - a.evaluations[0]) * (gamma - a.evaluations[1]) * (gamma - b_0).inverse().unwrap();
+	let b = DenseMLE::from_evaluations_vec(1, vec![b_0, b_1]);
+	let proof = prove_multiset_equality(&[a.clone()], &[b.clone()], &mut Transcript::new(PERMUTATION_LABEL));
+
+	// 列に縛られない transcript では通ってしまう
+	let claims = verify_multiset_equality(1, 1, &proof, &mut Transcript::new(PERMUTATION_LABEL)).unwrap();
+	assert!(claims.verify_against(&[a.clone()], &[b.clone()]).is_ok());
+	// 既定の入口は列を吸収してから γ を引くので落ちる
+	assert!(verify_permutation(&a, &b, &proof).is_err());
+	assert!(verify_permutation(&a, &b, &prove_permutation(&a, &b)).is_err());
+}
+
+#[rstest]
+fn tuples_are_compared_position_by_position() {
+	let mut rng = StdRng::seed_from_u64(2);
+	let (values, permuted, sigma) = shuffled(3, &mut rng);
+	let ids = DenseMLE::from_fn(3, |i| ScalarField::from(i as u64));
+	let permuted_ids = DenseMLE::from_fn(3, |i| ScalarField::from(sigma[i] as u64));
+	let lhs = vec![values.clone(), ids.clone()];
+	let rhs = vec![permuted.clone(), permuted_ids];
+
+	let proof = prove_multiset_equality(&lhs, &rhs, &mut Transcript::new(b"copy"));
+	let claims = verify_multiset_equality(3, 2, &proof, &mut Transcript::new(b"copy")).unwrap();
+	assert_eq!(claims.lhs.coeffs.len(), 2);
+	assert!(claims.verify_against(&lhs, &rhs).is_ok());
+
+	// 値の多重集合は等しくても，組の対応が崩れていれば落ちる
+	let rhs = vec![permuted, ids];
+	let proof = prove_multiset_equality(&lhs, &rhs, &mut Transcript::new(b"copy"));
+	assert!(verify_multiset_equality(3, 2, &proof, &mut Transcript::new(b"copy")).is_err());
+}
+
+#[rstest]
+fn claims_check_the_number_of_columns() {
+	let mut rng = StdRng::seed_from_u64(3);
+	let (a, b, _) = shuffled(2, &mut rng);
+	let claims = verify_multiset_equality(2, 1, &prove_permutation(&a, &b), &mut permutation_transcript(&a, &b)).unwrap();
+	assert_eq!(
+		claims.lhs.verify_against(&[a.clone(), a]),
+		Err(Error::LengthMismatch { what: "columns", expected: 1, found: 2 })
+	);
+}
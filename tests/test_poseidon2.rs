@@ -0,0 +1,81 @@
+use ark_bls12_381::Fr as ScalarField;
+use ark_std::UniformRand;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rstest::rstest;
+use gkr::builder::CircuitBuilder;
+use gkr::circuit_prover::{GKRProver, GKRVerifier};
+use gkr::error::Error;
+use gkr::gadgets::poseidon2::{self, permute_wires, Poseidon2Params};
+
+fn random_state(width: usize, seed: u64) -> Vec<ScalarField> {
+	let mut rng = StdRng::seed_from_u64(seed);
+	(0..width).map(|_| ScalarField::rand(&mut rng)).collect()
+}
+
+#[rstest]
+#[case(2)]
+#[case(3)]
+#[case(4)]
+#[case(8)]
+fn circuit_matches_the_native_permutation(#[case] width: usize) {
+	let params = Poseidon2Params::<ScalarField>::generated(width, 4, 3).unwrap();
+	let built = poseidon2::circuit(&params).unwrap();
+	let mut state = random_state(width, width as u64);
+	let outputs = built.evaluate(&state);
+	params.permute(&mut state);
+	assert_eq!(outputs, state);
+}
+
+#[rstest]
+fn full_permutation_is_a_bijection_on_samples() {
+	let params = Poseidon2Params::<ScalarField>::with_default_rounds(3).unwrap();
+	assert_eq!((params.external_constants.len(), params.internal_constants.len()), (8, 56));
+	let (mut a, mut b) = (random_state(3, 1), random_state(3, 2));
+	params.permute(&mut a);
+	params.permute(&mut b);
+	assert_ne!(a, b);
+}
+
+#[rstest]
+fn gkr_proves_the_permutation() {
+	let params = Poseidon2Params::<ScalarField>::generated(3, 2, 2).unwrap();
+	let built = poseidon2::circuit(&params).unwrap();
+	let inputs = built.assign(&random_state(3, 3));
+	let proof = GKRProver::prove_circuit(&built.circuit, &inputs);
+	assert_eq!(proof.outputs, built.evaluate(&inputs[..3]));
+	assert!(GKRVerifier::verify_circuit(&built.circuit, &inputs, &proof).is_ok());
+
+	let mut forged = proof;
+	forged.outputs[0] += ScalarField::from(1u32);
+	assert!(GKRVerifier::verify_circuit(&built.circuit, &inputs, &forged).is_err());
+}
+
+#[rstest]
+fn permutations_chain_in_one_circuit() {
+	// 2 回の置換を繋げた回路（Merkle パスの 1 段ずつに相当）
+	let params = Poseidon2Params::<ScalarField>::generated(2, 2, 1).unwrap();
+	let mut builder = CircuitBuilder::new();
+	let state: Vec<_> = (0..2).map(|_| builder.input()).collect();
+	let once = permute_wires(&mut builder, &params, &state);
+	for w in permute_wires(&mut builder, &params, &once) {
+		builder.output(w);
+	}
+	let built = builder.build().unwrap();
+	let mut expected = random_state(2, 4);
+	let outputs = built.evaluate(&expected);
+	params.permute(&mut expected);
+	params.permute(&mut expected);
+	assert_eq!(outputs, expected);
+}
+
+#[rstest]
+fn invalid_parameters_are_rejected() {
+	assert!(matches!(Poseidon2Params::<ScalarField>::generated(5, 8, 56), Err(Error::InvalidCircuit(_))));
+	assert!(matches!(Poseidon2Params::<ScalarField>::generated(3, 3, 56), Err(Error::InvalidCircuit(_))));
+	let diagonal = vec![ScalarField::from(2u32); 2];
+	assert_eq!(
+		Poseidon2Params::new(3, Vec::new(), Vec::new(), diagonal),
+		Err(Error::LengthMismatch { what: "internal diagonal", expected: 3, found: 2 })
+	);
+}
@@ -0,0 +1,124 @@
+#[macro_use]
+extern crate lazy_static;
+
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::{One, UniformRand};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rstest::rstest;
+use gkr::basefold::BaseFold;
+use gkr::circuit::{Circuit, Gate, Layer};
+use gkr::circuit_prover::{ClaimReduction, GKRProver};
+use gkr::error::Error;
+use gkr::pcs::{KzgProverParam, KzgVerifierParam, MultilinearKzg};
+use gkr::preprocessing::ProvingKey;
+use gkr::statement::CircuitDigest;
+
+fn layer(gates: Vec<Gate>) -> Layer {
+	Layer { gates }
+}
+
+/// 幅が 2 のべきでなく，Input ゲートと定数ゲートを含む回路（l = 2）
+fn small_circuit() -> Circuit {
+	Circuit::new(
+		3,
+		vec![
+			layer(vec![Gate::Mul(0, 1)]),
+			layer(vec![Gate::Add(0, 1), Gate::Input(2)]),
+			layer(vec![Gate::Mul(0, 1), Gate::Add(1, 2), Gate::Constant(5)]),
+		],
+	)
+	.unwrap()
+}
+
+/// 因子が 3 つの積を含む回路（l = 2，k = 3）
+fn wide_circuit() -> Circuit {
+	Circuit::new(3, vec![layer(vec![Gate::Add(0, 1)]), layer(vec![Gate::Product(vec![0, 1, 2]), Gate::Mul(1, 2)])])
+		.unwrap()
+}
+
+lazy_static! {
+	/// small_circuit の配線述語は (2 + 1)·(2 + 1) = 9 変数
+	static ref KZG_PARAMS: (KzgProverParam, KzgVerifierParam) = MultilinearKzg::setup(9, &mut StdRng::seed_from_u64(0));
+}
+
+fn random_inputs(n: usize, rng: &mut StdRng) -> Vec<ScalarField> {
+	(0..n).map(|_| ScalarField::rand(rng)).collect()
+}
+
+#[rstest]
+#[case(ClaimReduction::RandomCombination)]
+#[case(ClaimReduction::LineRestriction)]
+fn wiring_is_checked_through_openings(#[case] reduction: ClaimReduction) {
+	let mut rng = StdRng::seed_from_u64(1);
+	let (pp, vp) = &*KZG_PARAMS;
+	let circuit = small_circuit();
+	let pk = ProvingKey::preprocess::<MultilinearKzg>(pp, &circuit);
+	let vk = &pk.verifying_key;
+	assert_eq!(vk.circuit_digest, CircuitDigest::new::<ScalarField>(&circuit));
+	assert_eq!(vk.fan_ins, vec![2, 2, 2]);
+	// 同じ鍵で何度でも証明できる
+	for _ in 0..2 {
+		let inputs = random_inputs(3, &mut rng);
+		let proof = pk.prove::<MultilinearKzg>(pp, &inputs, reduction);
+		assert_eq!(proof.proof.outputs, circuit.evaluate(&inputs)[0]);
+		assert_eq!(proof.proof.layer_proofs.len(), GKRProver::prove_circuit(&circuit, &inputs).layer_proofs.len());
+		// 出力層の主張は 1 点，それより下は 2 点（直線への制限なら 1 点）の結合
+		let points: Vec<usize> = proof.wiring_evals.iter().map(Vec::len).collect();
+		let combined = if reduction == ClaimReduction::LineRestriction { 1 } else { 2 };
+		assert_eq!(points, vec![1, combined, combined]);
+		assert!(vk.verify::<ScalarField, MultilinearKzg>(vp, &inputs, &proof, reduction).is_ok());
+	}
+}
+
+#[rstest]
+#[case::wiring_evaluation(0)]
+#[case::wiring_opening(1)]
+fn tampering_is_rejected(#[case] target: usize) {
+	let mut rng = StdRng::seed_from_u64(3);
+	let (pp, vp) = &*KZG_PARAMS;
+	let pk = ProvingKey::preprocess::<MultilinearKzg>(pp, &small_circuit());
+	let inputs = random_inputs(3, &mut rng);
+	let reduction = ClaimReduction::RandomCombination;
+	let mut proof = pk.prove::<MultilinearKzg>(pp, &inputs, reduction);
+	match target {
+		0 => proof.wiring_evals[1][0] += ScalarField::one(),
+		_ => proof.wiring_openings[2][1].swap(0, 1),
+	}
+	assert!(pk.verifying_key.verify::<ScalarField, MultilinearKzg>(vp, &inputs, &proof, reduction).is_err());
+}
+
+#[rstest]
+fn keys_of_another_circuit_are_rejected() {
+	let mut rng = StdRng::seed_from_u64(4);
+	let (pp, vp) = &*KZG_PARAMS;
+	let circuit = small_circuit();
+	let mut other = circuit.clone();
+	other.layers[2].gates[0] = Gate::Add(0, 1);
+	let pk = ProvingKey::preprocess::<MultilinearKzg>(pp, &circuit);
+	let other_vk = ProvingKey::preprocess::<MultilinearKzg>(pp, &other).verifying_key;
+	let inputs = random_inputs(3, &mut rng);
+	let proof = pk.prove::<MultilinearKzg>(pp, &inputs, ClaimReduction::RandomCombination);
+	assert!(other_vk.verify::<ScalarField, MultilinearKzg>(vp, &inputs, &proof, ClaimReduction::RandomCombination).is_err());
+
+	let mut truncated = proof.clone();
+	truncated.wiring_openings.pop();
+	assert_eq!(
+		pk.verifying_key.verify::<ScalarField, MultilinearKzg>(vp, &inputs, &truncated, ClaimReduction::RandomCombination),
+		Err(Error::LengthMismatch { what: "layers of wiring openings", expected: 3, found: 2 })
+	);
+}
+
+#[rstest]
+fn wide_layers_open_their_wiring_with_a_hash_based_commitment() {
+	let mut rng = StdRng::seed_from_u64(5);
+	let params = BaseFold::<ScalarField>::setup(12, b"preprocessing_test");
+	let circuit = wide_circuit();
+	let pk = ProvingKey::preprocess::<BaseFold<ScalarField>>(&params, &circuit);
+	assert_eq!(pk.verifying_key.fan_ins, vec![2, 3]);
+	let inputs = random_inputs(3, &mut rng);
+	let proof = pk.prove::<BaseFold<ScalarField>>(&params, &inputs, ClaimReduction::RandomCombination);
+	assert_eq!(proof.proof.wide_layer_proofs.len(), 1);
+	let vk = &pk.verifying_key;
+	assert!(vk.verify::<ScalarField, BaseFold<ScalarField>>(&params, &inputs, &proof, ClaimReduction::RandomCombination).is_ok());
+}
@@ -0,0 +1,95 @@
+#![cfg(feature = "r1cs")]
+
+use ark_bls12_381::Fr as ScalarField;
+use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef, LinearCombination, SynthesisMode, Variable};
+use rstest::rstest;
+use gkr::builder::CircuitBuilder;
+use gkr::circuit_prover::{GKRProver, GKRVerifier};
+use gkr::r1cs::{compile_constraint_system, compile_r1cs, compile_r1cs_with};
+
+/// x³ + x + 5 = y（y は instance，x は witness）
+fn cubic(x: u64) -> ConstraintSystemRef<ScalarField> {
+	let cs = ConstraintSystem::<ScalarField>::new_ref();
+	let x = ScalarField::from(x);
+	let x2 = x * x;
+	let x3 = x2 * x;
+	let y_var = cs.new_input_variable(|| Ok(x3 + x + ScalarField::from(5u32))).unwrap();
+	let x_var = cs.new_witness_variable(|| Ok(x)).unwrap();
+	let x2_var = cs.new_witness_variable(|| Ok(x2)).unwrap();
+	let x3_var = cs.new_witness_variable(|| Ok(x3)).unwrap();
+	let lc = |v: Variable| LinearCombination::from(v);
+	cs.enforce_constraint(lc(x_var), lc(x_var), lc(x2_var)).unwrap();
+	cs.enforce_constraint(lc(x2_var), lc(x_var), lc(x3_var)).unwrap();
+	cs.enforce_constraint(
+		lc(x3_var) + x_var + (ScalarField::from(5u32), Variable::One),
+		lc(Variable::One),
+		lc(y_var),
+	)
+	.unwrap();
+	cs
+}
+
+#[rstest]
+fn satisfied_systems_prove_zero_residuals() {
+	let cs = cubic(3);
+	assert!(cs.is_satisfied().unwrap());
+	let (circuit, inputs) = compile_constraint_system(&cs).unwrap();
+	assert_eq!(circuit.num_instance_variables, 2);
+	assert_eq!(circuit.num_witness_variables, 3);
+	assert_eq!(circuit.num_constraints, 3);
+	let inputs = inputs.unwrap();
+	let proof = GKRProver::prove_circuit(&circuit.built.circuit, &inputs);
+	assert!(GKRVerifier::verify_circuit(&circuit.built.circuit, &inputs, &proof).is_ok());
+	assert!(circuit.accepts_outputs(&proof.outputs));
+}
+
+#[rstest]
+fn residuals_match_the_constraints() {
+	let cs = cubic(3);
+	cs.finalize();
+	let matrices = cs.to_matrices().unwrap();
+	let circuit = compile_r1cs(&matrices).unwrap();
+	let nine = ScalarField::from(9u32);
+	let (x, x2, x3) = (ScalarField::from(3u32), nine, ScalarField::from(27u32));
+	let y = ScalarField::from(35u32);
+	assert_eq!(circuit.residuals(&[y], &[x, x2, x3]), vec![ScalarField::from(0u32); 3]);
+	// x² を 10 と偽ると 1 つ目と 2 つ目の制約が崩れる
+	let residuals = circuit.residuals(&[y], &[x, x2 + ScalarField::from(1u32), x3]);
+	assert_eq!(residuals[0], -ScalarField::from(1u32));
+	assert_eq!(residuals[1], ScalarField::from(3u32));
+	assert_eq!(residuals[2], ScalarField::from(0u32));
+	assert!(!circuit.accepts_outputs(&residuals));
+}
+
+#[rstest]
+fn unsatisfied_witnesses_are_exposed_by_the_outputs() {
+	let cs = cubic(3);
+	let (circuit, _) = compile_constraint_system(&cs).unwrap();
+	let wrong = circuit.assign(&[ScalarField::from(36u32)], &[3u32.into(), 9u32.into(), 27u32.into()]);
+	let proof = GKRProver::prove_circuit(&circuit.built.circuit, &wrong);
+	assert!(GKRVerifier::verify_circuit(&circuit.built.circuit, &wrong, &proof).is_ok());
+	assert!(!circuit.accepts_outputs(&proof.outputs));
+}
+
+#[rstest]
+fn wider_gates_give_a_shallower_circuit() {
+	let cs = cubic(2);
+	cs.finalize();
+	let matrices = cs.to_matrices().unwrap();
+	let narrow = compile_r1cs(&matrices).unwrap();
+	let wide = compile_r1cs_with(&matrices, CircuitBuilder::with_max_fan_in(3)).unwrap();
+	assert!(wide.built.circuit.depth() < narrow.built.circuit.depth());
+	let (_, inputs) = compile_constraint_system(&cs).unwrap();
+	let inputs = inputs.unwrap();
+	let proof = GKRProver::prove_circuit(&wide.built.circuit, &inputs);
+	assert!(GKRVerifier::verify_circuit(&wide.built.circuit, &inputs, &proof).is_ok());
+	assert!(wide.accepts_outputs(&proof.outputs));
+}
+
+#[rstest]
+fn systems_without_matrices_are_rejected() {
+	let cs = ConstraintSystem::<ScalarField>::new_ref();
+	cs.set_mode(SynthesisMode::Prove { construct_matrices: false });
+	assert!(compile_constraint_system(&cs).is_err());
+	assert!(compile_constraint_system(&ConstraintSystem::<ScalarField>::new_ref()).is_err());
+}
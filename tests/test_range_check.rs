@@ -0,0 +1,84 @@
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::One;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rstest::rstest;
+use gkr::error::Error;
+use gkr::gadgets::range_check::{
+	decompose, verify_range_check, verify_range_check_with, RangeCheckMethod, RangeProof, RANGE_CHECK_LABEL,
+};
+use gkr::gadgets::range_check;
+use gkr::ml_extension::DenseMLE;
+use gkr::transcript::Transcript;
+
+fn values_in_range(num_vars: usize, bits: usize, seed: u64) -> DenseMLE<ScalarField> {
+	let mut rng = StdRng::seed_from_u64(seed);
+	let values = (0..1 << num_vars).map(|_| ScalarField::from(rng.gen_range(0..1u64 << bits))).collect();
+	DenseMLE::from_evaluations_vec(num_vars, values)
+}
+
+/// 既定の入口と同じく値を吸収した transcript
+fn range_transcript(values: &DenseMLE<ScalarField>) -> Transcript {
+	let mut transcript = Transcript::new(RANGE_CHECK_LABEL);
+	transcript.append_fields(b"range_values", &values.evaluations);
+	transcript
+}
+
+#[rstest]
+fn method_is_selected_by_size() {
+	assert_eq!(RangeCheckMethod::select(10, 8), RangeCheckMethod::Lookup);
+	assert_eq!(RangeCheckMethod::select(4, 32), RangeCheckMethod::BitDecomposition);
+	assert_eq!(RangeCheckMethod::select(16, 24), RangeCheckMethod::BitDecomposition);
+	assert_eq!(RangeCheckMethod::select(0, 1), RangeCheckMethod::BitDecomposition);
+}
+
+#[rstest]
+#[case(6, 4)]
+#[case(3, 20)]
+#[case(2, 64)]
+#[case(0, 8)]
+fn values_in_range_are_accepted(#[case] num_vars: usize, #[case] bits: usize) {
+	let values = values_in_range(num_vars, bits.min(63), num_vars as u64);
+	let proof = range_check(&values, bits).unwrap();
+	let lookup = RangeCheckMethod::select(num_vars, bits) == RangeCheckMethod::Lookup;
+	assert_eq!(matches!(proof, RangeProof::Lookup(_)), lookup);
+	assert!(verify_range_check(&values, bits, &proof).is_ok());
+}
+
+#[rstest]
+#[case(6, 4)]
+#[case(3, 20)]
+fn values_out_of_range_are_rejected(#[case] num_vars: usize, #[case] bits: usize) {
+	let mut values = values_in_range(num_vars, bits, 1);
+	values.evaluations[5] = ScalarField::from(1u64 << bits);
+	assert_eq!(range_check(&values, bits), Err(Error::OutOfRange { index: 5, bits }));
+	assert!(matches!(range_check(&values, 0), Err(Error::InvalidCircuit(_))));
+}
+
+#[rstest]
+fn forged_proofs_and_claims_are_rejected() {
+	let (num_vars, bits) = (3, 20);
+	let values = values_in_range(num_vars, bits, 2);
+	let proof = range_check(&values, bits).unwrap();
+	let RangeProof::BitDecomposition(inner) = &proof else { panic!("expected a bit decomposition") };
+
+	let mut forged = inner.clone();
+	forged.bit_values[0] += ScalarField::one();
+	assert_eq!(
+		verify_range_check(&values, bits, &RangeProof::BitDecomposition(forged)),
+		Err(Error::EvaluationMismatch("booleanity at the final point"))
+	);
+	assert_eq!(
+		verify_range_check(&values, 4, &proof),
+		Err(Error::MalformedProof("range proof does not match the selected method"))
+	);
+
+	let claim = verify_range_check_with(num_vars, bits, &proof, &mut range_transcript(&values)).unwrap();
+	let mut other = values.clone();
+	other.evaluations[0] += ScalarField::one();
+	assert_eq!(
+		claim.verify_against(&other, &decompose(&values, bits).unwrap()),
+		Err(Error::EvaluationMismatch("values at the final claim"))
+	);
+	assert!(verify_range_check(&other, bits, &proof).is_err());
+}
@@ -0,0 +1,75 @@
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::{One, UniformRand};
+use ark_poly::polynomial::multivariate::{SparsePolynomial, SparseTerm, Term};
+use ark_poly::DenseMVPolynomial;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use gkr::folding::{self, EvaluationClaim, PlainCommitment};
+use gkr::ml_extension::{DenseMLE, SparseMLE};
+use gkr::self_check;
+use gkr::sparse_sumcheck::SparseDenseProver;
+use gkr::sumcheck;
+use gkr::transcript::Transcript;
+
+fn claim_of(mle: &DenseMLE<ScalarField>, rng: &mut StdRng) -> EvaluationClaim<ScalarField, PlainCommitment<ScalarField>> {
+	let point: Vec<ScalarField> = (0..mle.num_vars).map(|_| ScalarField::rand(rng)).collect();
+	let value = folding::evaluate_mle(mle, &point);
+	EvaluationClaim { commitment: PlainCommitment::commit(mle), point, value }
+}
+
+#[test]
+fn direct_evaluation_matches_folding() {
+	let mut rng = StdRng::seed_from_u64(0);
+	let mle = DenseMLE::rand(5, &mut rng);
+	let point: Vec<ScalarField> = (0..5).map(|_| ScalarField::rand(&mut rng)).collect();
+	assert_eq!(self_check::direct_evaluation(&mle.evaluations, &point), folding::evaluate_mle(&mle, &point));
+}
+
+#[test]
+fn honest_provers_pass_self_check() {
+	self_check::set_enabled(true);
+	let mut rng = StdRng::seed_from_u64(1);
+
+	let mut evaluations = HashMap::new();
+	for _ in 0..12 {
+		evaluations.insert(rng.gen_range(0..1 << 7), ScalarField::rand(&mut rng));
+	}
+	let s = SparseMLE::new(7, evaluations);
+	let mut prover = SparseDenseProver::new(&s, &DenseMLE::rand(3, &mut rng));
+	for _ in 0..7 {
+		prover.prove_round();
+		prover.apply_challenge(ScalarField::rand(&mut rng));
+	}
+
+	let g: sumcheck::MultiPoly = SparsePolynomial::from_coefficients_vec(
+		3,
+		vec![
+			(2u32.into(), SparseTerm::new(vec![(0, 3)])),
+			(1u32.into(), SparseTerm::new(vec![(0, 1), (2, 1)])),
+		],
+	);
+	let sum = sumcheck::Prover::new(&g).slow_sum_g();
+	assert!(sumcheck::verify(&g, sum).is_ok());
+
+	let (w1, w2) = (DenseMLE::rand(4, &mut rng), DenseMLE::rand(4, &mut rng));
+	let (c1, c2) = (claim_of(&w1, &mut rng), claim_of(&w2, &mut rng));
+	folding::fold_prove(&mut Transcript::new(b"t"), &c1, &w1, &c2, &w2);
+	self_check::set_enabled(false);
+}
+
+#[test]
+#[should_panic(expected = "self-check failed in fold_prove: round 0")]
+fn false_claims_panic_with_context() {
+	self_check::set_enabled(true);
+	let mut rng = StdRng::seed_from_u64(2);
+	let (w1, w2) = (DenseMLE::rand(3, &mut rng), DenseMLE::rand(3, &mut rng));
+	let (c1, mut c2) = (claim_of(&w1, &mut rng), claim_of(&w2, &mut rng));
+	c2.value += ScalarField::one();
+	folding::fold_prove(&mut Transcript::new(b"t"), &c1, &w1, &c2, &w2);
+}
+
+#[test]
+fn self_check_is_off_by_default() {
+	assert_eq!(self_check::enabled(), cfg!(feature = "self-check"));
+}
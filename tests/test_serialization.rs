@@ -0,0 +1,318 @@
+use ark_bls12_381::{Fr as ScalarField, G1Affine};
+use rstest::rstest;
+use gkr::prover::LinearGKRProof;
+use gkr::serialization::{self, Endianness, PointEncoding, SerializationError, WireConfig};
+use gkr::small_field::Goldilocks;
+
+fn sample_proof() -> LinearGKRProof {
+	LinearGKRProof {
+		claimed_sum: 3u32.into(),
+		phase1_msgs: vec![vec![1u32.into(), 2u32.into()], vec![3u32.into()]],
+		phase2_msgs: vec![vec![-ScalarField::from(4u32)]],
+		f1_at_guv: 5u32.into(),
+		f2_at_u: 6u32.into(),
+		f3_at_v: -ScalarField::from(7u32),
+	}
+}
+
+#[rstest]
+#[case(WireConfig::default())]
+#[case(WireConfig::evm())]
+#[case(WireConfig { endianness: Endianness::Big, point_encoding: PointEncoding::Compressed })]
+fn proof_round_trip(#[case] config: WireConfig) {
+	let proof = sample_proof();
+	let bytes = proof.to_bytes(&config);
+	let (decoded, decoded_config) = LinearGKRProof::<ScalarField>::from_bytes(&bytes).unwrap();
+	assert_eq!(decoded.phase1_msgs, proof.phase1_msgs);
+	assert_eq!(decoded.phase2_msgs, proof.phase2_msgs);
+	assert_eq!(decoded, proof);
+	assert_eq!(decoded_config, config);
+}
+
+#[rstest]
+fn field_byte_order() {
+	let x: ScalarField = 0x0102u32.into();
+	let mut le = Vec::new();
+	serialization::write_field(&mut le, &x, Endianness::Little);
+	let mut be = Vec::new();
+	serialization::write_field(&mut be, &x, Endianness::Big);
+	assert_eq!(le.len(), 32);
+	assert_eq!(&le[..2], &[0x02, 0x01]);
+	assert_eq!(&be[30..], &[0x01, 0x02]);
+	let mut input = &be[..];
+	assert_eq!(serialization::read_field::<ScalarField>(&mut input, Endianness::Big).unwrap(), x);
+}
+
+#[rstest]
+fn point_encoding_sizes() {
+	let p = G1Affine::default();
+	let mut compressed = Vec::new();
+	serialization::write_point(&mut compressed, &p, PointEncoding::Compressed).unwrap();
+	let mut uncompressed = Vec::new();
+	serialization::write_point(&mut uncompressed, &p, PointEncoding::Uncompressed).unwrap();
+	assert_eq!(compressed.len(), 48);
+	assert_eq!(uncompressed.len(), 96);
+	let q: G1Affine = serialization::read_point(&mut &uncompressed[..], PointEncoding::Uncompressed).unwrap();
+	assert_eq!(p, q);
+}
+
+#[rstest]
+fn malformed_bytes_are_rejected() {
+	let bytes = sample_proof().to_bytes(&WireConfig::default());
+	assert!(matches!(LinearGKRProof::<ScalarField>::from_bytes(&bytes[..bytes.len() - 1]), Err(SerializationError::UnexpectedEnd)));
+	let mut bad = bytes.clone();
+	bad[0] = b'X';
+	assert!(matches!(LinearGKRProof::<ScalarField>::from_bytes(&bad), Err(SerializationError::BadMagic)));
+	let mut bad = bytes.clone();
+	bad.push(0);
+	assert!(matches!(LinearGKRProof::<ScalarField>::from_bytes(&bad), Err(SerializationError::TrailingBytes(1))));
+	// 最初のメッセージの体の元を p 以上に書き換える
+	let mut bad = bytes;
+	let offset = 4 + 1 + 1 + 1 + 32 + 4 + 4;
+	bad[offset..offset + 32].copy_from_slice(&[0xff; 32]);
+	assert!(matches!(LinearGKRProof::<ScalarField>::from_bytes(&bad), Err(SerializationError::NonCanonical)));
+}
+
+// バージョン 1 のバイト列を手で組み立てる（アーカイブ済みの証明の代わり）
+fn sample_proof_v1(config: WireConfig) -> Vec<u8> {
+	let proof = sample_proof();
+	let flags = match config.endianness {
+		Endianness::Little => 0,
+		Endianness::Big => 1,
+	} | if config.point_encoding == PointEncoding::Uncompressed { 2 } else { 0 };
+	let mut out = vec![b'G', b'K', b'R', b'P', 1, flags];
+	for msgs in [&proof.phase1_msgs, &proof.phase2_msgs] {
+		serialization::write_len(&mut out, msgs.len(), config.endianness);
+		for msg in msgs.iter() {
+			serialization::write_len(&mut out, msg.len(), config.endianness);
+			for x in msg {
+				serialization::write_field(&mut out, x, config.endianness);
+			}
+		}
+	}
+	out
+}
+
+#[rstest]
+#[case(WireConfig::default())]
+#[case(WireConfig::evm())]
+fn legacy_proofs_follow_feature(#[case] config: WireConfig) {
+	let old = sample_proof_v1(config);
+	assert_eq!(serialization::format_version(&old).unwrap(), 1);
+	if cfg!(feature = "legacy-formats") {
+		// ヘッダは読めるが，最終点での評価値を持たないので証明としては復号できない
+		let mut input = old.as_slice();
+		assert_eq!(serialization::read_header::<ScalarField>(&mut input).unwrap(), config);
+		assert!(matches!(LinearGKRProof::<ScalarField>::from_bytes(&old), Err(SerializationError::MissingFinalEvaluations(1))));
+		assert!(matches!(serialization::upgrade::<ScalarField>(&old), Err(SerializationError::MissingFinalEvaluations(1))));
+	} else {
+		assert!(matches!(LinearGKRProof::<ScalarField>::from_bytes(&old), Err(SerializationError::UnsupportedVersion(1))));
+		assert!(matches!(serialization::upgrade::<ScalarField>(&old), Err(SerializationError::UnsupportedVersion(1))));
+	}
+}
+
+#[cfg(feature = "legacy-formats")]
+#[rstest]
+fn legacy_writer_matches_fixture() {
+	for config in [WireConfig::default(), WireConfig::evm()] {
+		assert_eq!(sample_proof().to_bytes_v1(&config), sample_proof_v1(config));
+	}
+}
+
+// バージョン 3 のバイト列（主張する総和を持たない）を現在の形式から作る
+fn sample_proof_v3(config: WireConfig) -> Vec<u8> {
+	let mut bytes = sample_proof().to_bytes(&config);
+	bytes[4] = 3;
+	bytes.drain(7..7 + 32);
+	bytes
+}
+
+// バージョン 4 以前のメッセージは g(0) を含むので，読み込むと先頭の元が落ちる
+fn without_zero(mut proof: LinearGKRProof) -> LinearGKRProof {
+	for msg in proof.phase1_msgs.iter_mut().chain(proof.phase2_msgs.iter_mut()) {
+		msg.remove(0);
+	}
+	proof
+}
+
+#[rstest]
+#[case(WireConfig::default())]
+#[case(WireConfig::evm())]
+fn version_three_recovers_the_claimed_sum(#[case] config: WireConfig) {
+	let old = sample_proof_v3(config);
+	if cfg!(feature = "legacy-formats") {
+		// 最初のメッセージの g(0) + g(1) = 1 + 2 が主張する総和になる
+		let (decoded, _) = LinearGKRProof::<ScalarField>::from_bytes(&old).unwrap();
+		assert_eq!(decoded, without_zero(sample_proof()));
+		assert_eq!(serialization::upgrade::<ScalarField>(&old).unwrap(), without_zero(sample_proof()).to_bytes(&config));
+	} else {
+		assert!(matches!(LinearGKRProof::<ScalarField>::from_bytes(&old), Err(SerializationError::UnsupportedVersion(3))));
+	}
+}
+
+#[rstest]
+#[case(WireConfig::default())]
+#[case(WireConfig::evm())]
+fn version_four_messages_drop_the_zero_evaluation(#[case] config: WireConfig) {
+	let mut old = sample_proof().to_bytes(&config);
+	old[4] = 4;
+	if cfg!(feature = "legacy-formats") {
+		let (decoded, _) = LinearGKRProof::<ScalarField>::from_bytes(&old).unwrap();
+		assert_eq!(decoded.claimed_sum, sample_proof().claimed_sum);
+		assert_eq!(decoded, without_zero(sample_proof()));
+		assert_eq!(serialization::upgrade::<ScalarField>(&old).unwrap(), without_zero(sample_proof()).to_bytes(&config));
+	} else {
+		assert!(matches!(LinearGKRProof::<ScalarField>::from_bytes(&old), Err(SerializationError::UnsupportedVersion(4))));
+	}
+}
+
+#[rstest]
+fn current_proofs_upgrade_to_themselves() {
+	let bytes = sample_proof().to_bytes(&WireConfig::evm());
+	assert_eq!(serialization::upgrade::<ScalarField>(&bytes).unwrap(), bytes);
+	let mut future = bytes.clone();
+	future[4] = serialization::PROOF_FORMAT_VERSION + 1;
+	assert!(matches!(serialization::upgrade::<ScalarField>(&future), Err(SerializationError::UnsupportedVersion(_))));
+}
+
+#[rstest]
+fn field_width_is_checked() {
+	let mut bytes = sample_proof().to_bytes(&WireConfig::default());
+	bytes[6] = 8;
+	assert!(matches!(
+		LinearGKRProof::<ScalarField>::from_bytes(&bytes),
+		Err(SerializationError::FieldWidthMismatch { expected: 32, found: 8 })
+	));
+}
+
+#[rstest]
+fn upgrade_reads_proofs_over_other_fields() {
+	let proof = LinearGKRProof::<Goldilocks> {
+		claimed_sum: Goldilocks::from(3u64),
+		phase1_msgs: vec![vec![Goldilocks::from(1u64), Goldilocks::from(2u64)]],
+		phase2_msgs: vec![vec![Goldilocks::from(4u64), Goldilocks::from(5u64)]],
+		f1_at_guv: Goldilocks::from(6u64),
+		f2_at_u: Goldilocks::from(7u64),
+		f3_at_v: Goldilocks::from(8u64),
+	};
+	let bytes = proof.to_bytes(&WireConfig::evm());
+	assert_eq!(serialization::upgrade::<Goldilocks>(&bytes).unwrap(), bytes);
+	assert!(matches!(
+		serialization::upgrade::<ScalarField>(&bytes),
+		Err(SerializationError::FieldWidthMismatch { expected: 32, found: 8 })
+	));
+}
+
+/// 正準形式で書き出して読み戻し，元の値と長さが一致することを確かめる
+fn canonical_round_trip<T>(value: &T, compress: ark_serialize::Compress) -> Vec<u8>
+where
+	T: ark_serialize::CanonicalSerialize + ark_serialize::CanonicalDeserialize + PartialEq + std::fmt::Debug,
+{
+	let mut bytes = Vec::new();
+	value.serialize_with_mode(&mut bytes, compress).unwrap();
+	assert_eq!(bytes.len(), value.serialized_size(compress));
+	assert_eq!(bytes[0], serialization::CANONICAL_FORMAT_VERSION);
+	let decoded = T::deserialize_with_mode(&bytes[..], compress, ark_serialize::Validate::Yes).unwrap();
+	assert_eq!(&decoded, value);
+	bytes
+}
+
+#[rstest]
+fn canonical_layout_is_stable() {
+	use ark_serialize::{CanonicalDeserialize, Compress};
+	let proof = sample_proof();
+	let bytes = canonical_round_trip(&proof, Compress::Yes);
+	// version || claimed_sum || phase1（u64 の個数，各メッセージは u64 の長さ + 元）|| phase2 || 最終点での値 3 つ
+	assert_eq!(bytes.len(), 1 + 32 + (8 + 8 + 2 * 32 + 8 + 32) + (8 + 8 + 32) + 3 * 32);
+	assert_eq!(&bytes[1..3], &[3, 0]);
+	assert_eq!(&bytes[33..41], &2u64.to_le_bytes());
+
+	let mut future = bytes.clone();
+	future[0] = serialization::CANONICAL_FORMAT_VERSION + 1;
+	assert!(matches!(
+		LinearGKRProof::<ScalarField>::deserialize_compressed(&future[..]),
+		Err(ark_serialize::SerializationError::InvalidData)
+	));
+	assert!(LinearGKRProof::<ScalarField>::deserialize_compressed(&bytes[..bytes.len() - 1]).is_err());
+}
+
+#[rstest]
+#[case(ark_serialize::Compress::Yes)]
+#[case(ark_serialize::Compress::No)]
+fn circuit_proofs_and_subclaims_round_trip(#[case] compress: ark_serialize::Compress) {
+	use ark_serialize::CanonicalSerialize;
+	use gkr::circuit::{Circuit, Gate, Layer};
+	use gkr::circuit_prover::{ClaimReduction, GKRProver};
+	use gkr::pcs::MultilinearKzg;
+	use gkr::transcript::Transcript;
+	use gkr::verifier::LinearGKRVerifier;
+	use rand::SeedableRng;
+	let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+	// 因子数 3 の層を含む回路
+	let circuit = Circuit::new(
+		3,
+		vec![
+			Layer { gates: vec![Gate::Product(vec![0, 1, 2]), Gate::Add(0, 2)] },
+			Layer { gates: vec![Gate::Mul(0, 1), Gate::Add(1, 2), Gate::Input(2)] },
+		],
+	)
+	.unwrap();
+	let inputs: Vec<ScalarField> = (1..=3u32).map(ScalarField::from).collect();
+	let proof = GKRProver::prove_circuit_with(&circuit, &inputs, ClaimReduction::LineRestriction);
+	assert_eq!(proof.wide_layer_proofs.len(), 1);
+	canonical_round_trip(&proof, compress);
+	canonical_round_trip(&GKRProver::prove_batch(&circuit, &[inputs.clone(), inputs.clone()]), compress);
+
+	let (pp, _) = MultilinearKzg::setup(2, &mut rng);
+	let (_, committed) =
+		GKRProver::prove_committed::<MultilinearKzg>(&pp, &circuit, &inputs, ClaimReduction::RandomCombination);
+	let bytes = canonical_round_trip(&committed, compress);
+	// 開示の証明の群の元は圧縮の有無で大きさが変わる
+	let points = committed.openings.iter().map(Vec::len).sum::<usize>();
+	let extra = if compress == ark_serialize::Compress::No { points * 48 } else { 0 };
+	assert_eq!(bytes.len(), committed.serialized_size(ark_serialize::Compress::Yes) + extra);
+
+	let (relation, witness) = gkr::simulate::random_instance(2, 8, &mut rng);
+	let claimed = gkr::simulate::reference_claimed_sum(&relation, &witness);
+	let linear = gkr::prover::LinearGKRProver::prove(&relation.f1, &witness.f2, &witness.f3, &relation.g, &mut Transcript::new(b"test"));
+	let subclaim = LinearGKRVerifier::verify(2, claimed, &linear, &mut Transcript::new(b"test")).unwrap();
+	canonical_round_trip(&subclaim, compress);
+}
+
+#[rstest]
+fn pcs_and_zero_knowledge_proofs_round_trip() {
+	use ark_serialize::Compress;
+	use gkr::basefold::BaseFold;
+	use gkr::pcs::MultilinearPCS;
+	use gkr::ml_extension::DenseMLE;
+	use gkr::sumcheck::protocol;
+	use gkr::transcript::Transcript;
+	use rand::SeedableRng;
+	let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+	let params = BaseFold::<ScalarField>::setup(4, b"serialization");
+	let tables: Vec<DenseMLE<ScalarField>> =
+		(0..2).map(|_| DenseMLE::from_evaluations_vec(3, (0..8u32).map(|i| ScalarField::from(i * 7 + 1)).collect())).collect();
+	let (zk, _, _) = protocol::prove_zk::<_, BaseFold<ScalarField>, _>(&params, tables, &mut rng, &mut Transcript::new(b"test"));
+	canonical_round_trip(&zk, Compress::Yes);
+	canonical_round_trip(&zk.mask_openings[0], Compress::Yes);
+
+	let mle = DenseMLE::from_evaluations_vec(4, (0..16u32).map(ScalarField::from).collect());
+	let (_, opening) = BaseFold::open(&params, &mle, &[ScalarField::from(3u32); 4]);
+	canonical_round_trip(&opening, Compress::No);
+}
+
+#[cfg(feature = "serde")]
+#[rstest]
+fn serde_uses_the_canonical_bytes() {
+	use ark_serialize::CanonicalSerialize;
+	let proof = sample_proof();
+	let json = serde_json::to_string(&proof).unwrap();
+	let decoded: LinearGKRProof<ScalarField> = serde_json::from_str(&json).unwrap();
+	assert_eq!(decoded, proof);
+	let mut bytes = Vec::new();
+	proof.serialize_compressed(&mut bytes).unwrap();
+	assert_eq!(json, serde_json::to_string(&bytes).unwrap());
+	// 余分なバイトは拒否する
+	bytes.push(0);
+	assert!(serde_json::from_str::<LinearGKRProof<ScalarField>>(&serde_json::to_string(&bytes).unwrap()).is_err());
+}
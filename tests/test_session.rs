@@ -0,0 +1,153 @@
+use ark_bls12_381::Fr as ScalarField;
+use ark_std::UniformRand;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rstest::rstest;
+use std::sync::mpsc;
+use std::thread;
+use gkr::error::Error;
+use gkr::ml_extension::{DenseMLE, SparseMLE};
+use gkr::prover::{LinearGKRPrecomputation, LinearGKRProver};
+use gkr::session::{ProverMessage, ProverSession, Stage, VerifierSession};
+use gkr::transcript::Transcript;
+
+struct Layer {
+	f1: SparseMLE<ScalarField>,
+	f2: DenseMLE<ScalarField>,
+	f3: DenseMLE<ScalarField>,
+	g: Vec<ScalarField>,
+}
+
+fn random_layer(l: usize, seed: u64) -> Layer {
+	let mut rng = StdRng::seed_from_u64(seed);
+	let f1 = SparseMLE::rand(3 * l, 4 << l, &mut rng);
+	let (f2, f3) = (DenseMLE::rand(l, &mut rng), DenseMLE::rand(l, &mut rng));
+	let g = (0..l).map(|_| ScalarField::rand(&mut rng)).collect();
+	Layer { f1, f2, f3, g }
+}
+
+fn precompute(layer: &Layer) -> LinearGKRPrecomputation<ScalarField> {
+	LinearGKRProver::precompute(&layer.f1, &layer.f2, &layer.f3)
+}
+
+fn claimed_sum(layer: &Layer) -> ScalarField {
+	LinearGKRProver::prove(&layer.f1, &layer.f2, &layer.f3, &layer.g, &mut Transcript::new(b"sum")).claimed_sum
+}
+
+#[rstest]
+#[case(1)]
+#[case(3)]
+fn in_process_interaction_gives_a_valid_subclaim(#[case] l: usize) {
+	let layer = random_layer(l, l as u64);
+	let pre = precompute(&layer);
+	let mut rng = StdRng::seed_from_u64(7);
+	let mut prover = ProverSession::new(&pre, &layer.g);
+	let mut verifier = VerifierSession::new(l, claimed_sum(&layer));
+	let mut rounds = 0;
+	while let Some(msg) = prover.next_message() {
+		verifier.receive_message(&msg).unwrap();
+		if let Some(r) = verifier.next_challenge(&mut rng) {
+			prover.receive_challenge(r).unwrap();
+			rounds += 1;
+		}
+	}
+	assert_eq!(rounds, 2 * l);
+	assert_eq!(prover.stage(), Stage::Done);
+	let subclaim = verifier.finish().unwrap();
+	assert!(subclaim.verify_against(&layer.f1, &layer.f2, &layer.f3, &layer.g).is_ok());
+	assert_eq!(prover.into_proof().unwrap().phase1_msgs.len(), l);
+}
+
+#[rstest]
+fn fiat_shamir_over_sessions_matches_prove() {
+	let layer = random_layer(3, 11);
+	let pre = precompute(&layer);
+	let mut transcript = Transcript::new(b"session");
+	let mut prover = ProverSession::new(&pre, &layer.g);
+	while let Some(msg) = prover.next_message() {
+		let stage = prover.stage();
+		msg.append_to(stage, &mut transcript);
+		if matches!(msg, ProverMessage::Round(_)) {
+			let (_, challenge) = stage.round_labels().unwrap();
+			prover.receive_challenge(transcript.squeeze(challenge)).unwrap();
+		}
+	}
+	let expected = LinearGKRProver::prove_precomputed(&pre, &layer.g, &mut Transcript::new(b"session"));
+	assert_eq!(prover.into_proof().unwrap(), expected);
+}
+
+#[rstest]
+fn sessions_run_over_channels() {
+	let layer = random_layer(2, 5);
+	let sum = claimed_sum(&layer);
+	let (to_verifier, from_prover) = mpsc::channel::<ProverMessage<ScalarField>>();
+	let (to_prover, from_verifier) = mpsc::channel::<ScalarField>();
+	let verifier = thread::spawn(move || {
+		let mut rng = StdRng::seed_from_u64(3);
+		let mut session = VerifierSession::new(2, sum);
+		for msg in from_prover {
+			session.receive_message(&msg)?;
+			if let Some(r) = session.next_challenge(&mut rng) {
+				to_prover.send(r).unwrap();
+			}
+		}
+		session.finish()
+	});
+	let pre = precompute(&layer);
+	let mut prover = ProverSession::new(&pre, &layer.g);
+	while let Some(msg) = prover.next_message() {
+		let is_round = matches!(msg, ProverMessage::Round(_));
+		to_verifier.send(msg).unwrap();
+		if is_round {
+			prover.receive_challenge(from_verifier.recv().unwrap()).unwrap();
+		}
+	}
+	drop(to_verifier);
+	let subclaim = verifier.join().unwrap().unwrap();
+	assert!(subclaim.verify_against(&layer.f1, &layer.f2, &layer.f3, &layer.g).is_ok());
+}
+
+#[rstest]
+fn messages_out_of_turn_are_rejected() {
+	let layer = random_layer(2, 9);
+	let pre = precompute(&layer);
+	let mut prover = ProverSession::new(&pre, &layer.g);
+	let mut verifier = VerifierSession::new(2, claimed_sum(&layer));
+	assert!(prover.receive_challenge(ScalarField::from(1u32)).is_err());
+	assert!(verifier.send_challenge(ScalarField::from(1u32)).is_err());
+	assert!(verifier.receive_message(&ProverMessage::Round(vec![ScalarField::from(1u32); 3])).is_err());
+
+	let claimed = prover.next_message().unwrap();
+	verifier.receive_message(&claimed).unwrap();
+	let round = prover.next_message().unwrap();
+	// チャレンジを受け取るまで次のメッセージは出ない
+	assert_eq!(prover.next_message(), None);
+	verifier.receive_message(&round).unwrap();
+	assert!(verifier.receive_message(&round).is_err());
+	assert!(verifier.finish().is_err());
+}
+
+#[rstest]
+fn tampered_messages_are_rejected() {
+	let layer = random_layer(2, 13);
+	let pre = precompute(&layer);
+	let mut rng = StdRng::seed_from_u64(1);
+
+	let mut verifier = VerifierSession::new(2, claimed_sum(&layer) + ScalarField::from(1u32));
+	let msg = ProverSession::new(&pre, &layer.g).next_message().unwrap();
+	assert_eq!(verifier.receive_message(&msg), Err(Error::EvaluationMismatch("claimed sum")));
+
+	let mut prover = ProverSession::new(&pre, &layer.g);
+	let mut verifier = VerifierSession::new(2, claimed_sum(&layer));
+	let mut result = Ok(());
+	while let Some(mut msg) = prover.next_message() {
+		if let ProverMessage::FinalEvaluations { f3_at_v, .. } = &mut msg {
+			*f3_at_v += ScalarField::from(1u32);
+		}
+		result = verifier.receive_message(&msg);
+		if let Some(r) = verifier.next_challenge(&mut rng) {
+			prover.receive_challenge(r).unwrap();
+		}
+	}
+	assert_eq!(result, Err(Error::EvaluationMismatch("product of the final evaluations")));
+}
@@ -0,0 +1,59 @@
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::One;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rstest::rstest;
+use gkr::circuit_prover::{GKRProver, GKRVerifier};
+use gkr::error::Error;
+use gkr::gadgets::sha256::{self, compress, IV};
+use gkr::small_field::Goldilocks;
+
+/// "abc" を詰めた 1 ブロック
+const ABC: [u32; 16] = [0x61626380, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x18];
+const ABC_DIGEST: [u32; 8] =
+	[0xba7816bf, 0x8f01cfea, 0x414140de, 0x5dae2223, 0xb00361a3, 0x96177a9c, 0xb410ff61, 0xf20015ad];
+
+#[rstest]
+fn native_compression_matches_the_known_digest() {
+	let mut state = IV;
+	compress(&mut state, &ABC);
+	assert_eq!(state, ABC_DIGEST);
+}
+
+#[rstest]
+#[case(0)]
+#[case(1)]
+fn circuit_matches_the_native_compression(#[case] seed: u64) {
+	let mut rng = StdRng::seed_from_u64(seed);
+	let mut state: [u32; 8] = std::array::from_fn(|_| rng.gen());
+	let block: [u32; 16] = std::array::from_fn(|_| rng.gen());
+	let gadget = sha256::circuit::<ScalarField>().unwrap();
+	let outputs = gadget.built.evaluate(&gadget.witness(&state, &block));
+	compress(&mut state, &block);
+	assert_eq!(gadget.digest(&outputs), Ok(state));
+}
+
+#[rstest]
+fn tampered_witnesses_violate_the_constraints() {
+	let gadget = sha256::circuit::<ScalarField>().unwrap();
+	let witness = gadget.witness(&IV, &ABC);
+	// 入力の状態とブロック（768 ビット）の後ろが補助の値
+	for i in [768, 768 + 33, witness.len() - 1] {
+		let mut forged = witness.clone();
+		forged[i] = ScalarField::one() - forged[i];
+		let outputs = gadget.built.evaluate(&forged);
+		assert_eq!(gadget.digest(&outputs), Err(Error::EvaluationMismatch("SHA-256 constraint")));
+	}
+	let mut forged = witness;
+	forged[800] += ScalarField::one();
+	assert!(gadget.digest(&gadget.built.evaluate(&forged)).is_err());
+}
+
+#[rstest]
+fn gkr_proves_the_compression() {
+	let gadget = sha256::circuit::<Goldilocks>().unwrap();
+	let inputs = gadget.built.assign(&gadget.witness(&IV, &ABC));
+	let proof = GKRProver::prove_circuit(&gadget.built.circuit, &inputs);
+	assert!(GKRVerifier::verify_circuit(&gadget.built.circuit, &inputs, &proof).is_ok());
+	assert_eq!(gadget.digest(&proof.outputs), Ok(ABC_DIGEST));
+}
@@ -0,0 +1,24 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rstest::rstest;
+use gkr::serialization::WireConfig;
+use gkr::simulate::{self, SimulationConfig};
+
+// ランダムな単層インスタンスで正直な実行が受理されることを確認する
+#[rstest]
+#[case(1, 4)]
+#[case(2, 16)]
+#[case(3, 40)]
+fn honest_runs_are_accepted(#[case] l: usize, #[case] nnz: usize) {
+	for seed in 0..4u64 {
+		let mut rng = StdRng::seed_from_u64(seed);
+		let (relation, witness) = simulate::random_instance(l, nnz, &mut rng);
+		for wire in [None, Some(WireConfig::default()), Some(WireConfig::evm())] {
+			let config = SimulationConfig { seed, wire };
+			let report = simulate::prove_and_verify(&relation, &witness, &config);
+			assert!(report.accepted(), "l = {}, seed = {}: {:?}", l, seed, report.result.err());
+			assert_eq!(report.proof.phase1_msgs.len(), l);
+			assert_eq!(report.proof_bytes.is_some(), wire.is_some());
+		}
+	}
+}
@@ -0,0 +1,97 @@
+use ark_ff::{Field, PrimeField};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rstest::rstest;
+use std::collections::HashMap;
+use gkr::circuit_prover::{GKRProver, GKRVerifier};
+use gkr::ml_extension::{DenseMLE, SparseMLE};
+use gkr::prover::LinearGKRProver;
+use gkr::small_field::{BabyBear, DelayedReduction, Goldilocks};
+use gkr::soundness::SecurityConfig;
+use gkr::sumcheck::protocol;
+use gkr::transcript::Transcript;
+use gkr::verifier::LinearGKRVerifier;
+use gkr::wiring::{binary_tree_circuit, TreeOp};
+
+fn naive_inner_product<F: Field>(a: &[F], b: &[F]) -> F {
+	a.iter().zip(b).map(|(x, y)| *x * y).sum()
+}
+
+fn check_inner_product<F: DelayedReduction>(len: usize) {
+	let mut rng = StdRng::seed_from_u64(len as u64);
+	let a: Vec<F> = (0..len).map(|_| F::rand(&mut rng)).collect();
+	let b: Vec<F> = (0..len).map(|_| F::rand(&mut rng)).collect();
+	assert_eq!(F::inner_product(&a, &b), naive_inner_product(&a, &b));
+	// 最大の元同士の積を並べて u128 の桁あふれを何度も起こす
+	let max = vec![-F::one(); len];
+	assert_eq!(F::inner_product(&max, &max), F::from(len as u64));
+}
+
+#[rstest]
+#[case(0)]
+#[case(1)]
+#[case(1000)]
+#[case(100_000)]
+fn delayed_reduction_matches_field_arithmetic(#[case] len: usize) {
+	check_inner_product::<Goldilocks>(len);
+	check_inner_product::<BabyBear>(len);
+}
+
+fn random_table<F: Field>(l: usize, rng: &mut StdRng) -> DenseMLE<F> {
+	DenseMLE::from_evaluations_vec(l, (0..1 << l).map(|_| F::rand(rng)).collect())
+}
+
+fn check_sumcheck_tables<F: PrimeField>(factors: usize) {
+	let mut rng = StdRng::seed_from_u64(factors as u64);
+	let tables: Vec<DenseMLE<F>> = (0..factors).map(|_| random_table(13, &mut rng)).collect();
+	let expected: F = (0..1 << 13).map(|i| tables.iter().map(|t| t.evaluations[i]).product::<F>()).sum();
+	let state = protocol::prover_init(tables.clone());
+	assert_eq!(state.current_sum, expected);
+	// g(t) = Σ_b Π_k ((1 - t)·P_k(0, b) + t·P_k(1, b))
+	let half = 1 << 12;
+	for (t, value) in protocol::round_evaluations(&tables).into_iter().enumerate() {
+		let t = F::from(t as u64);
+		let direct: F = (0..half)
+			.map(|b| tables.iter().map(|p| p.evaluations[b] + t * (p.evaluations[b + half] - p.evaluations[b])).product::<F>())
+			.sum();
+		assert_eq!(value, direct);
+	}
+}
+
+#[rstest]
+#[case(1)]
+#[case(2)]
+#[case(3)]
+fn sumcheck_tables_over_small_fields(#[case] factors: usize) {
+	check_sumcheck_tables::<Goldilocks>(factors);
+	check_sumcheck_tables::<BabyBear>(factors);
+}
+
+fn check_linear_gkr<F: PrimeField>() {
+	let l = 4;
+	let mut rng = StdRng::seed_from_u64(3);
+	let entries: HashMap<usize, F> = (0..64).map(|_| (rng.gen_range(0..1 << (3 * l)), F::rand(&mut rng))).collect();
+	let f1 = SparseMLE::new(3 * l, entries);
+	let (f2, f3) = (random_table::<F>(l, &mut rng), random_table::<F>(l, &mut rng));
+	let g: Vec<F> = (0..l).map(|_| F::rand(&mut rng)).collect();
+	let proof = LinearGKRProver::prove(&f1, &f2, &f3, &g, &mut Transcript::new(b"small"));
+	let subclaim = LinearGKRVerifier::verify(l, proof.claimed_sum, &proof, &mut Transcript::new(b"small")).unwrap();
+	assert!(subclaim.verify_against(&f1, &f2, &f3, &g).is_ok());
+}
+
+#[rstest]
+fn linear_gkr_runs_over_small_fields() {
+	check_linear_gkr::<Goldilocks>();
+	check_linear_gkr::<BabyBear>();
+}
+
+#[rstest]
+fn small_field_circuits_are_repeated_to_reach_the_target() {
+	let circuit = binary_tree_circuit(3, TreeOp::Mul);
+	let inputs: Vec<Goldilocks> = (1..=8u64).map(Goldilocks::from).collect();
+	let config = SecurityConfig::TargetBits(100);
+	assert_eq!(config.repetitions::<Goldilocks>(&circuit), Ok(2));
+	let proof = GKRProver::prove_repeated(&circuit, &inputs, &config).unwrap();
+	assert_eq!(proof.outputs(), &[Goldilocks::from(40320u64)]);
+	assert!(GKRVerifier::verify_repeated(&circuit, &inputs, &proof, &config).is_ok());
+}
@@ -0,0 +1,58 @@
+use ark_bls12_381::Fr as ScalarField;
+use rstest::rstest;
+use gkr::soundness::{field_bits, repetitions_for, soundness_bits, SecurityConfig, SoundnessParams};
+use gkr::wiring::{binary_tree_circuit, TreeOp};
+
+#[rstest]
+fn bls12_381_scalar_field_has_about_255_bits() {
+	let bits = field_bits::<ScalarField>();
+	assert!((254.85..254.86).contains(&bits), "{}", bits);
+}
+
+#[rstest]
+#[case(64.0, 1, &[2, 2], 1, 64.0 - (1.0f64 + 5.0).log2())]
+#[case(64.0, 20, &[2; 40], 30, 64.0 - (20.0f64 + 30.0 * 81.0).log2())]
+#[case(128.0, 3, &[3, 3, 3], 2, 128.0 - (3.0f64 + 2.0 * 10.0).log2())]
+fn union_bound_over_layers_and_rounds(
+	#[case] field: f64,
+	#[case] num_vars: usize,
+	#[case] degrees: &[usize],
+	#[case] layers: usize,
+	#[case] expected: f64,
+) {
+	assert!((soundness_bits(field, num_vars, degrees, layers) - expected).abs() < 1e-9);
+}
+
+#[rstest]
+fn tiny_fields_give_no_soundness() {
+	assert_eq!(soundness_bits(4.0, 10, &[2; 20], 10), 0.0);
+	assert_eq!(repetitions_for(0.0, 100.0), None);
+}
+
+#[rstest]
+fn linear_gkr_over_bls12_381_is_far_above_128_bits() {
+	let params = SoundnessParams::linear_gkr::<ScalarField>(20, 100);
+	assert_eq!(params.round_degrees.len(), 40);
+	assert_eq!(params.error_numerator(), 20.0 + 100.0 * 81.0);
+	assert!(params.bits() > 240.0);
+	assert_eq!(repetitions_for(params.bits(), 128.0), Some(1));
+}
+
+#[rstest]
+#[case(40.0, 128.0, 4)]
+#[case(64.0, 128.0, 2)]
+#[case(50.0, 100.0, 2)]
+fn repetitions_reach_the_target(#[case] bits: f64, #[case] target: f64, #[case] expected: usize) {
+	assert_eq!(repetitions_for(bits, target), Some(expected));
+}
+
+#[rstest]
+fn circuits_count_every_layer_at_their_widest_fan_in() {
+	let circuit = binary_tree_circuit(3, TreeOp::Mul);
+	let params = SoundnessParams::circuit::<ScalarField>(&circuit);
+	assert_eq!((params.num_vars, params.num_layers), (3, 3));
+	assert_eq!(params.round_degrees, vec![2; 2 * 4]);
+	assert_eq!(SecurityConfig::TargetBits(128).repetitions::<ScalarField>(&circuit), Ok(1));
+	assert_eq!(SecurityConfig::TargetBits(1000).repetitions::<ScalarField>(&circuit), Ok(5));
+	assert_eq!(SecurityConfig::Repetitions(0).repetitions::<ScalarField>(&circuit), Ok(1));
+}
@@ -0,0 +1,73 @@
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::{One, UniformRand};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rstest::rstest;
+use std::collections::HashMap;
+use gkr::ml_extension::{DenseMLE, SparseMLE};
+use gkr::sparse_sumcheck::{self, SparseDenseProver};
+use gkr::sumcheck::MessageForm;
+
+fn random_instance(n: usize, m: usize, nnz: usize, rng: &mut StdRng) -> (SparseMLE<ScalarField>, DenseMLE<ScalarField>) {
+	let mut evaluations = HashMap::new();
+	for _ in 0..nnz {
+		evaluations.insert(rng.gen_range(0..1 << n), ScalarField::rand(rng));
+	}
+	let d = DenseMLE::from_evaluations_vec(m, (0..1 << m).map(|_| ScalarField::rand(rng)).collect());
+	(SparseMLE::new(n, evaluations), d)
+}
+
+#[rstest]
+#[case(6, 2, 10, MessageForm::OmitZero)]
+#[case(6, 6, 20, MessageForm::OmitZero)]
+#[case(9, 3, 30, MessageForm::OmitZero)]
+#[case(3, 0, 4, MessageForm::OmitZero)]
+#[case(6, 2, 10, MessageForm::Full)]
+fn sparse_dense_sumcheck_is_complete(#[case] n: usize, #[case] m: usize, #[case] nnz: usize, #[case] form: MessageForm) {
+	let mut rng = StdRng::seed_from_u64((n * 100 + m) as u64);
+	let (s, d) = random_instance(n, m, nnz, &mut rng);
+
+	let mask = (1 << m) - 1;
+	let brute: ScalarField = s.evaluations.iter().map(|(&i, &v)| v * d.evaluations[i & mask]).sum();
+	let mut prover = SparseDenseProver::new(&s, &d).with_message_form(form);
+	assert_eq!(prover.claimed_sum(), brute);
+
+	let mut claim = brute;
+	let mut point = vec![];
+	for round in 0..n {
+		let msg = prover.prove_round();
+		// S にしか現れない変数のラウンドは 1 次。OmitZero では 1 要素少ない
+		let omitted = if form == MessageForm::OmitZero { 1 } else { 0 };
+		assert_eq!(msg.len() + omitted, if round < n - m { 2 } else { 3 });
+		let r = ScalarField::rand(&mut rng);
+		claim = sparse_sumcheck::verify_round(claim, &msg, 2, r, form).unwrap();
+		prover.apply_challenge(r);
+		point.push(r);
+	}
+	assert_eq!(prover.final_evaluation(), claim);
+
+	// S(r)·D(r の末尾 m 成分) を密表の畳み込みで独立に計算して比較する
+	let mut s_dense = s.to_dense_multilinear_extension();
+	let mut d_dense = d.clone();
+	for (i, r) in point.iter().enumerate() {
+		s_dense.fix_first_variable_in_place(*r);
+		if i >= n - m {
+			d_dense.fix_first_variable_in_place(*r);
+		}
+	}
+	assert_eq!(claim, s_dense.evaluations[0] * d_dense.evaluations[0]);
+}
+
+#[rstest]
+fn wrong_round_message_is_rejected() {
+	let mut rng = StdRng::seed_from_u64(3);
+	let (s, d) = random_instance(4, 2, 6, &mut rng);
+	let prover = SparseDenseProver::new(&s, &d).with_message_form(MessageForm::Full);
+	let r = ScalarField::one();
+	let mut msg = prover.prove_round();
+	msg[0] += ScalarField::one();
+	assert!(sparse_sumcheck::verify_round(prover.claimed_sum(), &msg, 2, r, MessageForm::Full).is_err());
+	assert!(sparse_sumcheck::verify_round(prover.claimed_sum(), &[r; 4], 2, r, MessageForm::Full).is_err());
+	assert!(sparse_sumcheck::verify_round(prover.claimed_sum(), &[r; 3], 2, r, MessageForm::OmitZero).is_err());
+	assert!(sparse_sumcheck::verify_round(prover.claimed_sum(), &[], 2, r, MessageForm::OmitZero).is_err());
+}
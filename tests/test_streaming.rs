@@ -0,0 +1,98 @@
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::One;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rstest::rstest;
+use gkr::examples_circuits::{self, ExampleCircuit};
+use gkr::serialization::WireConfig;
+use gkr::streaming::{self, StepCircuit, StreamingProver};
+
+fn step_of(example: ExampleCircuit<ScalarField>) -> StepCircuit {
+	// fibonacci と hash_chain はどの層も 2 番目の配線が定数 1
+	StepCircuit::new(example.circuit, 2).unwrap()
+}
+
+#[rstest]
+#[case(examples_circuits::hash_chain(1), examples_circuits::hash_chain(6))]
+#[case(examples_circuits::fibonacci(1), examples_circuits::fibonacci(6))]
+fn extension_proves_only_new_steps(
+	#[case] step: ExampleCircuit<ScalarField>,
+	#[case] whole: ExampleCircuit<ScalarField>,
+) {
+	let mut rng = StdRng::seed_from_u64(0);
+	let initial = whole.random_inputs(&mut rng);
+	let mut prover = StreamingProver::new(step_of(step), initial.clone());
+
+	prover.extend(2);
+	let first: Vec<Vec<u8>> = prover.proof().segments[0]
+		.layer_proofs
+		.iter()
+		.flatten()
+		.map(|p| p.to_bytes(&WireConfig::default()))
+		.collect();
+	prover.extend(1);
+	prover.extend(3);
+
+	let proof = prover.proof();
+	assert_eq!(proof.segments.len(), 3);
+	assert_eq!(proof.num_steps(), 6);
+	assert_eq!(proof.final_state(), whole.expected_output(&initial).as_slice());
+	// 既存のセグメントは作り直されない
+	let first_after: Vec<Vec<u8>> = proof.segments[0]
+		.layer_proofs
+		.iter()
+		.flatten()
+		.map(|p| p.to_bytes(&WireConfig::default()))
+		.collect();
+	assert_eq!(first, first_after);
+}
+
+#[test]
+fn extendable_proofs_verify_and_reject_broken_chains() {
+	let step = step_of(examples_circuits::hash_chain(1));
+	let initial = vec![ScalarField::from(3u64), ScalarField::one(), ScalarField::one(), ScalarField::one()];
+	let mut prover = StreamingProver::new(step_of(examples_circuits::hash_chain(1)), initial.clone());
+	assert!(streaming::verify_extendable(&step, prover.proof()).is_ok());
+	prover.extend(2);
+	prover.extend(2);
+	let proof = prover.into_proof();
+	assert!(streaming::verify_extendable(&step, &proof).is_ok());
+
+	// 2 つ目のセグメントの開始状態を差し替える
+	let mut broken = proof.clone();
+	let last = broken.segments[1].values.len() - 1;
+	broken.segments[1].values[last][0] += ScalarField::one();
+	assert!(streaming::verify_extendable(&step, &broken).is_err());
+
+	// 初期状態を差し替える
+	let mut broken = proof.clone();
+	broken.initial_state[0] += ScalarField::one();
+	assert!(streaming::verify_extendable(&step, &broken).is_err());
+
+	// 層を 1 つ落とす
+	let mut broken = proof;
+	broken.segments[0].layer_proofs.pop();
+	assert!(streaming::verify_extendable(&step, &broken).is_err());
+}
+
+#[test]
+fn step_circuit_shape_is_validated() {
+	assert!(StepCircuit::new(examples_circuits::mul_tree::<ScalarField>(2).circuit, 0).is_err());
+	assert!(StepCircuit::new(examples_circuits::fibonacci::<ScalarField>(1).circuit, 4).is_err());
+}
+
+#[rstest]
+fn step_circuits_of_odd_width_are_proved() {
+	use gkr::circuit::{Circuit, Gate, Layer};
+	// 状態 (a, b, 1) → (a + b, a·b, 1)
+	let circuit = Circuit::new(3, vec![Layer { gates: vec![Gate::Add(0, 1), Gate::Mul(0, 1), Gate::Input(2)] }]).unwrap();
+	let step = StepCircuit::new(circuit.clone(), 2).unwrap();
+	let initial = vec![ScalarField::from(2u64), ScalarField::from(3u64), ScalarField::one()];
+	let mut prover = StreamingProver::new(StepCircuit::new(circuit, 2).unwrap(), initial);
+	prover.extend(3);
+	let proof = prover.into_proof();
+	// (2, 3) → (5, 6) → (11, 30) → (41, 330)
+	assert_eq!(proof.final_state(), &[ScalarField::from(41u64), ScalarField::from(330u64), ScalarField::one()][..]);
+	assert!(proof.segments[0].layer_proofs.iter().all(|proofs| proofs.len() == 3));
+	assert!(streaming::verify_extendable(&step, &proof).is_ok());
+}
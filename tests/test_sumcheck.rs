@@ -5,6 +5,8 @@ use ark_bls12_381::Fr as ScalarField;
 use ark_poly::polynomial::multivariate::{SparsePolynomial, SparseTerm, Term};
 use ark_poly::DenseMVPolynomial;
 use rstest::rstest;
+use gkr::challenge::seeded_rng;
+use gkr::ml_extension::{DenseMLE, IndexOrder};
 use gkr::sumcheck;
 
 lazy_static! {
@@ -35,5 +37,479 @@ lazy_static! {
 #[case(&G_0, &G_0_SUM)]
 #[case(&G_1, &G_1_SUM)]
 fn sumcheck_test(#[case] p: &sumcheck::MultiPoly, #[case] c: &ScalarField) {
-	assert!(sumcheck::verify(&p, *c));
+	assert!(sumcheck::verify(p, *c).is_ok());
+}
+
+#[rstest]
+fn batched_sumcheck_test() {
+	// G_0 と同じ変数数の別インスタンス
+	let g_2: sumcheck::MultiPoly = SparsePolynomial::from_coefficients_vec(
+		3,
+		vec![
+			(5u32.into(), SparseTerm::new(vec![(0, 1), (1, 1)])),
+			(3u32.into(), SparseTerm::new(vec![(2, 2)])),
+		],
+	);
+	let g_2_sum = sumcheck::Prover::new(&g_2).slow_sum_g();
+	let gs = vec![G_0.clone(), g_2];
+	assert!(sumcheck::verify_batched(&gs, &[*G_0_SUM, g_2_sum]).is_ok());
+	// 片方の主張を改ざんすると拒否される
+	assert!(sumcheck::verify_batched(&gs, &[*G_0_SUM, g_2_sum + ScalarField::from(1u32)]).is_err());
+	assert_eq!(
+		sumcheck::verify_batched(&gs, &[*G_0_SUM]),
+		Err(sumcheck::SumcheckError::ClaimCountMismatch { expected: 2, found: 1 })
+	);
+}
+
+#[rstest]
+#[case(&G_0, &G_0_SUM)]
+#[case(&G_1, &G_1_SUM)]
+fn wrong_claims_are_rejected_without_panicking(#[case] p: &sumcheck::MultiPoly, #[case] c: &ScalarField) {
+	let wrong = *c + ScalarField::from(1u32);
+	assert_eq!(sumcheck::verify(p, wrong), Err(sumcheck::SumcheckError::WrongRoundSum { round: 0 }));
+	assert_eq!(sumcheck::slow_verify(p, wrong), Err(sumcheck::SumcheckError::WrongRoundSum { round: 0 }));
+}
+
+#[rstest]
+#[case(&G_0)]
+#[case(&G_1)]
+fn grid_prover_matches_term_prover(#[case] p: &sumcheck::MultiPoly) {
+	let mut slow = sumcheck::Prover::new(p);
+	let mut grid = sumcheck::GridProver::from_poly(p);
+	assert_eq!(slow.gen_uni_polynomial(None), grid.gen_uni_polynomial(None));
+	for j in 1..p.num_vars() {
+		let r = Some(ScalarField::from((j * 7 + 3) as u64));
+		assert_eq!(slow.gen_uni_polynomial(r), grid.gen_uni_polynomial(r));
+	}
+}
+
+#[rstest]
+fn high_degree_sumcheck_test() {
+	// g = 3(x_1)^2(x_2)^3 + (x_2)^4(x_3) + 7
+	let g: sumcheck::MultiPoly = SparsePolynomial::from_coefficients_vec(
+		3,
+		vec![
+			(3u32.into(), SparseTerm::new(vec![(0, 2), (1, 3)])),
+			(1u32.into(), SparseTerm::new(vec![(1, 4), (2, 1)])),
+			(7u32.into(), SparseTerm::new(vec![])),
+		],
+	);
+	let sum = sumcheck::Prover::new(&g).slow_sum_g();
+	assert!(sumcheck::verify(&g, sum).is_ok());
+}
+
+#[rstest]
+#[case(&G_0, &G_0_SUM)]
+#[case(&G_1, &G_1_SUM)]
+fn sumcheck_with_strict_challenges_test(#[case] p: &sumcheck::MultiPoly, #[case] c: &ScalarField) {
+	assert!(sumcheck::verify_with_policy(p, *c, gkr::challenge::ChallengePolicy::strict()).is_ok());
+}
+
+#[rstest]
+#[case(&G_0, &G_0_SUM)]
+#[case(&G_1, &G_1_SUM)]
+fn sumcheck_with_transcript_challenges_test(#[case] p: &sumcheck::MultiPoly, #[case] c: &ScalarField) {
+	use gkr::transcript::Transcript;
+	let policy = gkr::challenge::ChallengePolicy::default();
+	let (mut a, mut b) = (Transcript::new(b"sumcheck"), Transcript::new(b"sumcheck"));
+	assert!(sumcheck::verify_with_challenges(p, *c, policy, &mut a).is_ok());
+	assert!(sumcheck::verify_with_challenges(p, *c, policy, &mut b).is_ok());
+	// ラウンド多項式も吸収するので，検証後の状態は同じ主張なら一致する
+	assert_eq!(a.challenge_field::<ScalarField>(b"r"), b.challenge_field::<ScalarField>(b"r"));
+	assert!(sumcheck::verify_with_challenges(p, *c + ScalarField::from(1u32), policy, &mut a).is_err());
+}
+
+#[rstest]
+#[case(&G_0, &G_0_SUM)]
+#[case(&G_1, &G_1_SUM)]
+fn sumcheck_with_seeded_challenges_test(#[case] p: &sumcheck::MultiPoly, #[case] c: &ScalarField) {
+	let policy = gkr::challenge::ChallengePolicy::strict();
+	let (mut a, mut b) = (seeded_rng(9), seeded_rng(9));
+	assert!(sumcheck::verify_with_rng(p, *c, policy, &mut a).is_ok());
+	assert!(sumcheck::verify_with_rng(p, *c, policy, &mut b).is_ok());
+	// 同じシードなら同じチャレンジ列を消費する
+	assert_eq!(sumcheck::get_r::<ScalarField, _>(&mut a), sumcheck::get_r(&mut b));
+	let wrong = *c + ScalarField::from(1u32);
+	assert_eq!(
+		sumcheck::verify_with_rng(p, wrong, policy, &mut seeded_rng(9)),
+		Err(sumcheck::SumcheckError::WrongRoundSum { round: 0 })
+	);
+}
+
+#[rstest]
+fn batched_sumcheck_with_seeded_challenges_test() {
+	let gs = vec![G_0.clone(), G_0.clone()];
+	let claims = [*G_0_SUM, *G_0_SUM];
+	assert!(sumcheck::verify_batched_with_rng(&gs, &claims, &mut seeded_rng(3)).is_ok());
+	assert!(sumcheck::verify_batched_with_rng(&gs, &[*G_0_SUM, *G_0_SUM + ScalarField::from(1u32)], &mut seeded_rng(3)).is_err());
+}
+
+// Gray 符号による差分更新が，点ごとに評価し直す素朴な計算と一致することを確認する
+#[rstest]
+#[case(&G_0)]
+#[case(&G_1)]
+fn gray_code_sums_match_naive(#[case] g: &sumcheck::MultiPoly) {
+	use ark_poly::polynomial::Polynomial;
+	let v = g.num_vars();
+	let naive: ScalarField = (0..1 << v).map(|i| g.evaluate(&sumcheck::n_to_vec(i, v))).sum();
+	let mut p = sumcheck::Prover::new(g);
+	assert_eq!(p.slow_sum_g(), naive);
+
+	let mut r = None;
+	for j in 0..v {
+		let gi = p.gen_uni_polynomial(r);
+		let rest = v - j;
+		let expected = (0..1 << (rest - 1)).fold(sumcheck::UniPoly::from_coefficients_vec(vec![]), |sum, n| {
+			sum + p.evaluate_gj(sumcheck::n_to_vec(n, rest))
+		});
+		assert_eq!(gi, expected);
+		r = Some(ScalarField::from(j as u64 + 3));
+	}
+}
+
+// 複数変数・大きな指数を含む項の部分評価を，SparsePolynomial::evaluate と網羅的に突き合わせる
+#[rstest]
+#[case(vec![(0, 1)])]
+#[case(vec![(0, 3), (2, 1)])]
+#[case(vec![(1, 70), (3, 129)])]
+#[case(vec![(0, 2), (1, 1), (2, 5), (3, 1)])]
+#[case(vec![])]
+fn partial_term_evaluation_is_exact(#[case] powers: Vec<(usize, usize)>) {
+	use ark_ff::Field;
+	use ark_poly::polynomial::Polynomial;
+	let term = SparseTerm::new(powers);
+	let g: sumcheck::MultiPoly = SparsePolynomial::from_coefficients_vec(4, vec![(ScalarField::from(7u32), term.clone())]);
+	let point: Vec<ScalarField> = (0..4).map(|i| ScalarField::from(3u64 + 5 * i as u64)).collect();
+	let expected = g.evaluate(&point);
+
+	// 全ての代入パターン（どの変数を残すか）を試す
+	for mask in 0..16usize {
+		let values: Vec<Option<ScalarField>> =
+			(0..4).map(|i| if (mask >> i) & 1 == 1 { Some(point[i]) } else { None }).collect();
+		let (coeff, rest) = sumcheck::partial_evaluate_term(&term, &values);
+		for (var, _) in rest.iter() {
+			assert!(values[*var].is_none());
+		}
+		// 残った変数にも代入すれば全体の値に一致する
+		let (rest_coeff, empty) = sumcheck::partial_evaluate_term(&rest, &point.iter().copied().map(Some).collect::<Vec<_>>());
+		assert!(empty.is_constant());
+		assert_eq!(ScalarField::from(7u32) * coeff * rest_coeff, expected, "mask = {:04b}", mask);
+	}
+
+	// evaluate_term: 先頭を r_vec，X_0 を記号のまま，残りを point で評価
+	let p = sumcheck::Prover::new(&g);
+	let (coeff, fixed) = p.evaluate_term(&term, &point);
+	let x0 = fixed.map(|t| t.iter().map(|(_, e)| *e).sum::<usize>()).unwrap_or(0);
+	let with_x0 = coeff * point[0].pow([x0 as u64]);
+	assert_eq!(ScalarField::from(7u32) * with_x0, expected);
+}
+
+#[rstest]
+#[case(1)]
+#[case(2)]
+#[case(3)]
+fn protocol_accepts_honest_product_sums(#[case] num_factors: usize) {
+	use ark_ff::UniformRand;
+	use gkr::sumcheck::{protocol, MessageForm};
+	use rand::SeedableRng;
+	let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+	let tables: Vec<DenseMLE<ScalarField>> = (0..num_factors)
+		.map(|_| DenseMLE::from_evaluations_vec(3, (0..8).map(|_| ScalarField::rand(&mut rng)).collect()))
+		.collect();
+	let mut prover = protocol::prover_init(tables.clone());
+	let claimed = prover.current_sum;
+
+	assert_eq!(prover.degree(), num_factors);
+	let mut verifier = protocol::verifier_init(3, prover.degree(), claimed);
+	let mut challenges = Vec::new();
+	for _ in 0..3 {
+		let msg = protocol::prove_round(&mut prover);
+		// 既定の形式では g(0) を省くので d 個の値
+		assert_eq!(msg.len(), num_factors);
+		protocol::verify_round(&mut verifier, &msg).unwrap();
+		let r = ScalarField::rand(&mut rng);
+		challenges.push(r);
+		protocol::apply_challenge(&mut prover, r);
+		protocol::apply_challenge_verifier(&mut verifier, r);
+	}
+	let subclaim = protocol::finalize(verifier).unwrap();
+	assert_eq!(subclaim.point, challenges);
+	let at_point: ScalarField = prover.tables.iter().map(|t| t.evaluations[0]).product();
+	assert_eq!(subclaim.expected_value, at_point);
+
+	// 誤った総和は，g(0) を送る形式なら最初のラウンドで拒否される
+	let wrong = claimed + ScalarField::from(1u32);
+	let mut verifier = protocol::verifier_init_with_form(3, num_factors, wrong, MessageForm::Full);
+	let full = protocol::prove_round(&mut protocol::prover_init_with_form(tables.clone(), MessageForm::Full));
+	assert!(protocol::verify_round(&mut verifier, &full).is_err());
+	let msg = protocol::prove_round(&mut protocol::prover_init(tables));
+
+	// 因子の数を超える次数のメッセージは拒否される
+	let mut verifier = protocol::verifier_init(3, num_factors, claimed);
+	let mut long = msg;
+	long.push(ScalarField::from(0u32));
+	assert_eq!(
+		protocol::verify_round(&mut verifier, &long).err(),
+		Some(gkr::error::Error::Round {
+			round: 0,
+			kind: gkr::error::RoundError::DegreeBoundExceeded { degree: num_factors + 1, bound: num_factors },
+		})
+	);
+}
+
+#[rstest]
+#[case(1, IndexOrder::BigEndian)]
+#[case(2, IndexOrder::BigEndian)]
+#[case(3, IndexOrder::LittleEndian)]
+fn ml_sumcheck_matches_grid_prover(#[case] num_factors: usize, #[case] order: IndexOrder) {
+	use ark_ff::UniformRand;
+	use ark_poly::Polynomial;
+	use rand::SeedableRng;
+	let mut rng = rand::rngs::StdRng::seed_from_u64(11);
+	let factors: Vec<DenseMLE<ScalarField>> = (0..num_factors)
+		.map(|_| DenseMLE::from_evaluations_with_order(3, (0..8).map(|_| ScalarField::rand(&mut rng)).collect(), order))
+		.collect();
+	let product = |point: &[ScalarField]| factors.iter().map(|f| f.evaluate(point)).product::<ScalarField>();
+	let mut ml = sumcheck::MLSumcheck::new(factors.clone());
+	let mut grid = sumcheck::GridProver::from_evaluator(&[num_factors; 3], product);
+	assert_eq!(ml.degree(), num_factors);
+	assert_eq!(ml.claimed_sum(), (0..8).map(|i| product(&sumcheck::n_to_vec(i, 3))).sum::<ScalarField>());
+
+	let mut gi = ml.gen_uni_polynomial(None);
+	assert_eq!(gi, grid.gen_uni_polynomial(None));
+	assert_eq!(gi.evaluate(&0u32.into()) + gi.evaluate(&1u32.into()), ml.claimed_sum());
+	for _ in 1..3 {
+		let r = Some(ScalarField::rand(&mut rng));
+		gi = ml.gen_uni_polynomial(r);
+		assert_eq!(gi, grid.gen_uni_polynomial(r));
+	}
+	let r = ScalarField::rand(&mut rng);
+	ml.apply_challenge(r);
+	assert_eq!(ml.num_vars(), 0);
+	let finals = ml.final_evaluations();
+	assert_eq!(finals.iter().product::<ScalarField>(), gi.evaluate(&r));
+	assert_eq!(finals.iter().product::<ScalarField>(), product(&ml.r_vec));
+}
+
+#[rstest]
+fn batched_product_claims_share_one_sumcheck() {
+	use ark_ff::UniformRand;
+	use gkr::error::Error;
+	use gkr::sumcheck::protocol;
+	use gkr::transcript::Transcript;
+	use rand::SeedableRng;
+	let mut rng = rand::rngs::StdRng::seed_from_u64(12);
+	// 因子の数が 1, 2, 3 の主張
+	let claims: Vec<Vec<DenseMLE<ScalarField>>> = (1..=3)
+		.map(|k| (0..k).map(|_| DenseMLE::from_evaluations_vec(3, (0..8).map(|_| ScalarField::rand(&mut rng)).collect())).collect())
+		.collect();
+	let transcript = Transcript::new(b"batched_product_claims");
+	let (sums, proof) = protocol::batch_prove(claims.clone(), &mut transcript.clone());
+	for (sum, claim) in sums.iter().zip(claims.iter()) {
+		assert_eq!(*sum, protocol::prover_init(claim.clone()).current_sum);
+	}
+	assert_eq!(proof.msgs.len(), 3);
+	assert!(proof.msgs.iter().all(|msg| msg.len() == 3));
+
+	let subclaim = protocol::batch_verify(3, 3, &sums, &proof, &mut transcript.clone()).unwrap();
+	for (evals, claim) in subclaim.final_evals.iter().zip(claims.iter()) {
+		let expected: Vec<ScalarField> = claim.iter().map(|f| f.evaluate(&subclaim.point)).collect();
+		assert_eq!(*evals, expected);
+	}
+
+	let mut wrong_sums = sums.clone();
+	wrong_sums[1] += ScalarField::from(1u32);
+	assert!(protocol::batch_verify(3, 3, &wrong_sums, &proof, &mut transcript.clone()).is_err());
+	let mut tampered = proof.clone();
+	tampered.final_evals[2][0] += ScalarField::from(1u32);
+	assert_eq!(
+		protocol::batch_verify(3, 3, &sums, &tampered, &mut transcript.clone()).err(),
+		Some(Error::EvaluationMismatch("combination of the final evaluations"))
+	);
+	assert!(matches!(
+		protocol::batch_verify(3, 3, &sums[..2], &proof, &mut transcript.clone()),
+		Err(Error::LengthMismatch { what: "batched claims", expected: 2, found: 3 })
+	));
+}
+
+#[rstest]
+#[case(2)]
+#[case(4)]
+fn barycentric_evaluation_matches_lagrange(#[case] n: usize) {
+	use ark_ff::UniformRand;
+	use rand::SeedableRng;
+	let mut rng = rand::rngs::StdRng::seed_from_u64(13);
+	let evals: Vec<ScalarField> = (0..n).map(|_| ScalarField::rand(&mut rng)).collect();
+	let r = ScalarField::rand(&mut rng);
+	let lagrange: ScalarField = sumcheck::lagrange_weights(n, r).iter().zip(evals.iter()).map(|(w, y)| *w * y).sum();
+	assert_eq!(sumcheck::barycentric_evaluate(&evals, r), lagrange);
+	// 補間点ではその点の値をそのまま返す
+	for (i, y) in evals.iter().enumerate() {
+		assert_eq!(sumcheck::barycentric_evaluate(&evals, ScalarField::from(i as u64)), *y);
+	}
+}
+
+#[rstest]
+#[case(2)]
+#[case(3)]
+fn compressed_messages_give_the_same_subclaim(#[case] num_factors: usize) {
+	use ark_ff::UniformRand;
+	use gkr::sumcheck::{protocol, MessageForm};
+	use rand::SeedableRng;
+	let mut rng = rand::rngs::StdRng::seed_from_u64(14);
+	let tables: Vec<DenseMLE<ScalarField>> = (0..num_factors)
+		.map(|_| DenseMLE::from_evaluations_vec(3, (0..8).map(|_| ScalarField::rand(&mut rng)).collect()))
+		.collect();
+	let challenges: Vec<ScalarField> = (0..3).map(|_| ScalarField::rand(&mut rng)).collect();
+	let run = |form: MessageForm| {
+		let mut prover = protocol::prover_init_with_form(tables.clone(), form);
+		let mut verifier = protocol::verifier_init_with_form(3, num_factors, prover.current_sum, form);
+		let mut sizes = Vec::new();
+		for r in challenges.iter() {
+			let msg = protocol::prove_round(&mut prover);
+			sizes.push(msg.len());
+			protocol::verify_round(&mut verifier, &msg).unwrap();
+			protocol::apply_challenge(&mut prover, *r);
+			protocol::apply_challenge_verifier(&mut verifier, *r);
+		}
+		let subclaim = protocol::finalize(verifier).unwrap();
+		(subclaim.point, subclaim.expected_value, sizes)
+	};
+	let (full_point, full_value, full_sizes) = run(MessageForm::Full);
+	let (point, value, sizes) = run(MessageForm::OmitZero);
+	assert_eq!((point, value), (full_point, full_value));
+	assert!(full_sizes.iter().all(|&n| n == num_factors + 1));
+	assert!(sizes.iter().all(|&n| n == num_factors));
+
+	// 圧縮形式でも次数の上限は復元した多項式について検査する
+	let mut prover = protocol::prover_init_with_form(tables, MessageForm::OmitZero);
+	let mut verifier = protocol::verifier_init_with_form(3, num_factors - 1, prover.current_sum, MessageForm::OmitZero);
+	assert!(protocol::verify_round(&mut verifier, &protocol::prove_round(&mut prover)).is_err());
+}
+
+#[rstest]
+fn prover_claim_follows_the_last_round_message() {
+	use gkr::sumcheck::protocol;
+	use rand::SeedableRng;
+	let mut rng = rand::rngs::StdRng::seed_from_u64(16);
+	let tables: Vec<DenseMLE<ScalarField>> = (0..2).map(|_| DenseMLE::rand(3, &mut rng)).collect();
+	let mut prover = protocol::prover_init(tables);
+	for i in 0..3u32 {
+		protocol::prove_round(&mut prover);
+		protocol::apply_challenge(&mut prover, ScalarField::from(i + 7));
+		// g_i(r) は束縛後の表の総和に一致する
+		let sum: ScalarField = (0..1 << prover.num_vars)
+			.map(|b| prover.tables.iter().map(|t| t.evaluations[b]).product::<ScalarField>())
+			.sum();
+		assert_eq!(prover.current_sum, sum);
+		assert!(prover.last_evals.is_none());
+	}
+}
+
+#[rstest]
+#[should_panic(expected = "prove_round must be called")]
+fn challenge_before_the_round_message_panics() {
+	use gkr::sumcheck::protocol;
+	use rand::SeedableRng;
+	let mut rng = rand::rngs::StdRng::seed_from_u64(17);
+	let mut prover = protocol::prover_init(vec![DenseMLE::<ScalarField>::rand(2, &mut rng)]);
+	protocol::apply_challenge(&mut prover, ScalarField::from(3u32));
+}
+
+#[rstest]
+#[case(1)]
+#[case(3)]
+fn little_endian_factors_give_the_same_messages(#[case] num_factors: usize) {
+	use gkr::sumcheck::protocol;
+	use rand::SeedableRng;
+	let mut rng = rand::rngs::StdRng::seed_from_u64(15);
+	let tables: Vec<DenseMLE<ScalarField>> = (0..num_factors).map(|_| DenseMLE::rand(3, &mut rng)).collect();
+	let little: Vec<DenseMLE<ScalarField>> = tables.iter().map(|t| t.to_order(IndexOrder::LittleEndian)).collect();
+	let mut big = protocol::prover_init(tables.clone());
+	let mut prover = protocol::prover_init(little);
+	assert_eq!(prover.current_sum, big.current_sum);
+	let mut verifier = protocol::verifier_init(3, num_factors, prover.current_sum);
+	for i in 0..3u32 {
+		let msg = protocol::prove_round(&mut prover);
+		assert_eq!(msg, protocol::prove_round(&mut big));
+		protocol::verify_round(&mut verifier, &msg).unwrap();
+		let r = ScalarField::from(i + 5);
+		protocol::apply_challenge(&mut prover, r);
+		protocol::apply_challenge(&mut big, r);
+		protocol::apply_challenge_verifier(&mut verifier, r);
+	}
+	let subclaim = protocol::finalize(verifier).unwrap();
+	let at_point: ScalarField = tables.iter().map(|t| t.evaluate(&subclaim.point)).product();
+	assert_eq!(subclaim.expected_value, at_point);
+}
+
+#[rstest]
+fn sumcheck_runs_over_other_fields() {
+	use ark_bls12_381::Fq;
+	// g = 2(x_1)^3 + (x_1)(x_3) + (x_2)(x_3) を Fq 上で
+	let g: sumcheck::MultiPoly<Fq> = SparsePolynomial::from_coefficients_vec(
+		3,
+		vec![
+			(2u32.into(), SparseTerm::new(vec![(0, 3)])),
+			(1u32.into(), SparseTerm::new(vec![(0, 1), (2, 1)])),
+			(1u32.into(), SparseTerm::new(vec![(1, 1), (2, 1)])),
+		],
+	);
+	let sum = sumcheck::Prover::new(&g).slow_sum_g();
+	assert_eq!(sum, Fq::from(12u32));
+	assert!(sumcheck::verify(&g, sum).is_ok());
+	assert!(sumcheck::slow_verify(&g, sum).is_ok());
+}
+
+#[rstest]
+#[case(1, 1)]
+#[case(3, 2)]
+#[case(5, 3)]
+fn zk_sumcheck_hides_round_messages_behind_a_mask(#[case] num_vars: usize, #[case] num_factors: usize) {
+	use ark_ff::UniformRand;
+	use gkr::basefold::BaseFold;
+	use gkr::error::Error;
+	use gkr::sumcheck::protocol;
+	use gkr::transcript::Transcript;
+	use rand::SeedableRng;
+	let mut rng = rand::rngs::StdRng::seed_from_u64(15);
+	let tables: Vec<DenseMLE<ScalarField>> = (0..num_factors)
+		.map(|_| {
+			DenseMLE::from_evaluations_vec(num_vars, (0..1 << num_vars).map(|_| ScalarField::rand(&mut rng)).collect())
+		})
+		.collect();
+	let claimed = protocol::prover_init(tables.clone()).current_sum;
+	let params = BaseFold::<ScalarField>::setup(5, b"zk_sumcheck");
+	let transcript = Transcript::new(b"zk_sumcheck");
+	let (proof, point, state) =
+		protocol::prove_zk::<_, BaseFold<ScalarField>, _>(&params, tables.clone(), &mut rng, &mut transcript.clone());
+	assert_eq!(proof.msgs.len(), num_vars);
+	assert_eq!(state.num_vars, 0);
+
+	let subclaim =
+		protocol::verify_zk::<_, BaseFold<ScalarField>>(&params, num_vars, num_factors, claimed, &proof, &mut transcript.clone())
+			.unwrap();
+	assert_eq!(subclaim.point, point);
+	let at_point: ScalarField = tables.iter().map(|t| t.evaluate(&point)).product();
+	assert_eq!(subclaim.expected_value, at_point);
+
+	// 最初のメッセージはマスクなしのものと異なる
+	assert_ne!(proof.msgs[0], protocol::prove_round(&mut protocol::prover_init(tables.clone())));
+
+	let verify = |claimed: ScalarField, proof: &protocol::ZkProof<_, _, _>| {
+		protocol::verify_zk::<_, BaseFold<ScalarField>>(&params, num_vars, num_factors, claimed, proof, &mut transcript.clone())
+	};
+	assert!(verify(claimed + ScalarField::from(1u32), &proof).is_err());
+	let mut tampered = proof.clone();
+	tampered.mask_sum += ScalarField::from(1u32);
+	assert!(verify(claimed, &tampered).is_err());
+	let mut tampered = proof.clone();
+	tampered.mask_evals[0] += ScalarField::from(1u32);
+	assert!(verify(claimed, &tampered).is_err());
+	let mut tampered = proof.clone();
+	tampered.mask_openings.pop();
+	assert!(matches!(
+		verify(claimed, &tampered),
+		Err(Error::LengthMismatch { what: "mask openings", .. })
+	));
 }
@@ -0,0 +1,235 @@
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::{One, Zero};
+use rstest::rstest;
+use std::collections::HashMap;
+use gkr::challenge::{ChallengePolicy, ChallengeSampler};
+use gkr::circuit::Gate;
+use gkr::examples_circuits;
+use gkr::ml_extension::SparseMLE;
+use gkr::statement::{self, CircuitDigest, Statement};
+use gkr::poseidon::{PoseidonParams, PoseidonSponge};
+use gkr::small_field::Goldilocks;
+use gkr::transcript::{labels, Transcript, TranscriptConfig};
+
+fn first_challenge(statement: &Statement<ScalarField>) -> ScalarField {
+	Transcript::for_statement(b"gkr", statement).challenge_field(b"r")
+}
+
+#[test]
+fn transcript_is_deterministic() {
+	let mut a = Transcript::new(b"gkr");
+	let mut b = Transcript::new(b"gkr");
+	a.append_field(b"x", &ScalarField::from(5u64));
+	b.append_field(b"x", &ScalarField::from(5u64));
+	let (ra, rb): (ScalarField, ScalarField) = (a.challenge_field(b"r"), b.challenge_field(b"r"));
+	assert_eq!(ra, rb);
+	// 導出したチャレンジは吸収されるので，続けて引くと別の値になる
+	assert_ne!(a.challenge_field::<ScalarField>(b"r"), ra);
+
+	let mut c = Transcript::new(b"gkr");
+	c.append_field(b"x", &ScalarField::from(6u64));
+	assert_ne!(c.challenge_field::<ScalarField>(b"r"), ra);
+}
+
+#[test]
+fn statement_binds_circuit_and_public_io() {
+	let circuit = examples_circuits::fibonacci::<ScalarField>(3).circuit;
+	let inputs = vec![ScalarField::one(), ScalarField::one(), ScalarField::one(), ScalarField::one()];
+	let outputs = circuit.evaluate(&inputs)[0].clone();
+	let base = Statement::new(&circuit, inputs.clone(), outputs.clone());
+	let r = first_challenge(&base);
+	assert_eq!(r, first_challenge(&base.clone()));
+
+	// 回路を 1 ゲート変える
+	let mut other = circuit.clone();
+	other.layers[0].gates[0] = Gate::Add(1, 2);
+	assert_ne!(CircuitDigest::new::<ScalarField>(&other), base.circuit_digest);
+	assert_ne!(first_challenge(&Statement::new(&other, inputs.clone(), outputs.clone())), r);
+
+	// 主張する出力を変える
+	let mut wrong_outputs = outputs.clone();
+	wrong_outputs[0] += ScalarField::one();
+	assert_ne!(first_challenge(&Statement::new(&circuit, inputs.clone(), wrong_outputs)), r);
+
+	// 入力と出力の境界をずらしても同じにはならない
+	let mut shifted_inputs = inputs.clone();
+	shifted_inputs.push(outputs[0]);
+	let shifted = Statement::new(&circuit, shifted_inputs, outputs[1..].to_vec());
+	assert_ne!(first_challenge(&shifted), r);
+
+	// コミットメントも束縛される
+	assert_ne!(first_challenge(&base.clone().with_commitment(vec![1, 2, 3])), r);
+}
+
+#[test]
+fn circuit_digest_binds_the_field() {
+	let circuit = examples_circuits::fibonacci::<ScalarField>(3).circuit;
+	let over_fr = CircuitDigest::new::<ScalarField>(&circuit);
+	assert_eq!(over_fr, CircuitDigest::new::<ScalarField>(&circuit));
+	assert_ne!(over_fr, CircuitDigest::new::<Goldilocks>(&circuit));
+	assert_ne!(over_fr.as_bytes(), &statement::circuit_digest(&circuit));
+	assert_eq!(Statement::<ScalarField>::new(&circuit, Vec::new(), Vec::new()).circuit_digest, over_fr);
+}
+
+#[test]
+fn message_labels_separate_phases_and_layers() {
+	let msg = [ScalarField::from(3u64), ScalarField::from(4u64), ScalarField::from(5u64)];
+	let after = |round: &[u8], challenge: &[u8]| {
+		let mut t = Transcript::new(b"gkr");
+		t.append(round, &msg);
+		t.squeeze::<ScalarField>(challenge)
+	};
+	let phase1 = after(labels::PHASE1_ROUND, labels::PHASE1_CHALLENGE);
+	assert_ne!(phase1, after(labels::PHASE2_ROUND, labels::PHASE1_CHALLENGE));
+	assert_ne!(phase1, after(labels::PHASE1_ROUND, labels::PHASE2_CHALLENGE));
+	assert_eq!(phase1, after(&labels::phase_round(0), &labels::phase_challenge(0)));
+	assert_eq!(labels::phase_round(1), labels::PHASE2_ROUND);
+
+	// `append` と `squeeze` は `append_fields` と `challenge_field` と同じ
+	let mut t = Transcript::new(b"gkr");
+	t.append_fields(labels::PHASE1_ROUND, &msg);
+	assert_eq!(t.challenge_field::<ScalarField>(labels::PHASE1_CHALLENGE), phase1);
+
+	let layer = |i: usize| {
+		let mut t = Transcript::new(b"gkr");
+		t.begin_layer(i);
+		t.squeeze::<ScalarField>(labels::PHASE1_CHALLENGE)
+	};
+	assert_ne!(layer(0), layer(1));
+}
+
+#[test]
+fn wiring_digest_ignores_zero_entries() {
+	let mut evaluations = HashMap::new();
+	for i in [3usize, 17, 40, 9] {
+		evaluations.insert(i, ScalarField::from(i as u64));
+	}
+	let f1 = SparseMLE::new(6, evaluations.clone());
+	evaluations.insert(5, ScalarField::zero());
+	let padded = SparseMLE::new(6, evaluations);
+	assert_eq!(statement::wiring_digest(&f1), statement::wiring_digest(&padded));
+	let wider = SparseMLE::new(7, f1.evaluations.clone());
+	assert_ne!(statement::wiring_digest(&f1), statement::wiring_digest(&wider));
+}
+
+#[rstest]
+#[case(ChallengePolicy::default())]
+#[case(ChallengePolicy::strict())]
+fn challenge_with_policy_is_deterministic(#[case] policy: ChallengePolicy) {
+	let mut a = Transcript::new(b"gkr");
+	let mut b = Transcript::new(b"gkr");
+	let mut sa = ChallengeSampler::new(policy);
+	let mut sb = ChallengeSampler::new(policy);
+	for _ in 0..8 {
+		let ra: ScalarField = a.challenge_with(b"r", &mut sa);
+		assert_eq!(ra, b.challenge_with(b"r", &mut sb));
+	}
+}
+
+#[rstest]
+#[case(TranscriptConfig::Sha3)]
+#[case(TranscriptConfig::Poseidon)]
+#[case(TranscriptConfig::Keccak256)]
+fn every_backend_is_deterministic_and_binds_its_inputs(#[case] config: TranscriptConfig) {
+	let run = |x: u64, bytes: &[u8]| {
+		let mut t = Transcript::with_config(b"gkr", config);
+		assert_eq!(t.config(), config);
+		t.append_field(b"x", &ScalarField::from(x));
+		t.append_message(b"m", bytes);
+		let r: ScalarField = t.challenge_field(b"r");
+		let small: Goldilocks = t.challenge_field(b"r");
+		(r, small, t.challenge_bytes(b"b"))
+	};
+	let base = run(5, b"abc");
+	assert_eq!(run(5, b"abc"), base);
+	assert_ne!(run(6, b"abc").0, base.0);
+	assert_ne!(run(5, b"abd").0, base.0);
+	assert_ne!(run(5, b"abc\0").0, base.0);
+	assert_ne!(Transcript::with_config(b"gkr", config).challenge_field::<ScalarField>(b"r"), base.0);
+}
+
+#[rstest]
+fn poseidon_and_sha3_transcripts_differ() {
+	let mut sha3 = Transcript::new(b"gkr");
+	let mut poseidon = Transcript::with_config(b"gkr", TranscriptConfig::Poseidon);
+	assert_eq!(sha3.config(), TranscriptConfig::Sha3);
+	assert_ne!(sha3.challenge_field::<ScalarField>(b"r"), poseidon.challenge_field::<ScalarField>(b"r"));
+	// 体の元と，その正準なバイト列は別物として吸収される
+	let mut as_field = Transcript::with_config(b"gkr", TranscriptConfig::Poseidon);
+	let mut as_bytes = as_field.clone();
+	as_field.append_field(b"x", &ScalarField::from(7u64));
+	let mut bytes = vec![7u8];
+	bytes.resize(32, 0);
+	as_bytes.append_message(b"x", &bytes);
+	assert_ne!(as_field.challenge_field::<ScalarField>(b"r"), as_bytes.challenge_field::<ScalarField>(b"r"));
+}
+
+#[rstest]
+fn poseidon_parameters_pick_an_invertible_sbox() {
+	assert_eq!(PoseidonParams::<ScalarField>::new().alpha, 5);
+	// Goldilocks の p - 1 は 3 と 5 で割り切れる
+	assert_eq!(PoseidonParams::<Goldilocks>::new().alpha, 7);
+	let mut sponge = PoseidonSponge::<Goldilocks>::new();
+	sponge.absorb(Goldilocks::from(1u64));
+	let a = sponge.clone().squeeze();
+	sponge.absorb(Goldilocks::from(2u64));
+	assert_ne!(sponge.squeeze(), a);
+}
+
+#[rstest]
+#[case(TranscriptConfig::Poseidon)]
+#[case(TranscriptConfig::Keccak256)]
+fn linear_gkr_runs_over_other_transcripts(#[case] config: TranscriptConfig) {
+	use gkr::prover::LinearGKRProver;
+	use gkr::verifier::LinearGKRVerifier;
+	use rand::SeedableRng;
+	let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+	let (relation, witness) = gkr::simulate::random_instance(3, 20, &mut rng);
+	let g = vec![ScalarField::from(2u64), ScalarField::from(3u64), ScalarField::from(4u64)];
+	let new = || Transcript::with_config(b"gkr", config);
+	let proof = LinearGKRProver::prove(&relation.f1, &witness.f2, &witness.f3, &g, &mut new());
+	let subclaim = LinearGKRVerifier::verify(3, proof.claimed_sum, &proof, &mut new()).unwrap();
+	assert!(subclaim.verify_against(&relation.f1, &witness.f2, &witness.f3, &g).is_ok());
+	// SHA3 の transcript ではチャレンジが変わるので通らない
+	let sha3 = LinearGKRVerifier::verify(3, proof.claimed_sum, &proof, &mut Transcript::new(b"gkr"));
+	assert!(sha3.is_err());
+}
+
+#[rstest]
+fn keccak_challenges_follow_the_documented_encoding() {
+	use ark_ff::{BigInteger, PrimeField};
+	use sha3::{Digest, Keccak256};
+	let chain = |state: &[u8; 32], tag: u8, label: &[u8], n: u64, body: &[u8]| -> [u8; 32] {
+		let mut h = Keccak256::new();
+		h.update(state);
+		h.update([tag]);
+		h.update((label.len() as u64).to_be_bytes());
+		h.update(label);
+		h.update(n.to_be_bytes());
+		h.update(body);
+		h.finalize().into()
+	};
+	let x = ScalarField::from(0x1234u64);
+	let mut t = Transcript::with_config(b"gkr", TranscriptConfig::Keccak256);
+	t.append_message(b"m", b"hello");
+	t.append_fields(b"xs", &[x, -x]);
+	let r: ScalarField = t.challenge_field(b"r");
+
+	// Solidity 側と同じ手順で導出し直す
+	let mut s = chain(&[0; 32], 0x00, b"protocol", 3, b"gkr");
+	s = chain(&s, 0x00, b"m", 5, b"hello");
+	let mut body = Vec::new();
+	for e in [x, -x] {
+		let be = e.into_bigint().to_bytes_be();
+		body.extend(std::iter::repeat_n(0u8, 32 - be.len()));
+		body.extend(be);
+	}
+	s = chain(&s, 0x01, b"xs", 2, &body);
+	let h0 = chain(&s, 0x02, b"r", 0, &[0]);
+	let h1 = chain(&s, 0x02, b"r", 0, &[1]);
+	assert_eq!(r, ScalarField::from_be_bytes_mod_order(&[h0, h1].concat()));
+
+	// 引いた r は同じラベルの長さ 1 の列として吸収される
+	s = chain(&s, 0x01, b"r", 1, &r.into_bigint().to_bytes_be());
+	assert_eq!(t.challenge_bytes(b"b"), chain(&s, 0x02, b"b", 0, &[0]));
+}
@@ -0,0 +1,99 @@
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::{One, PrimeField};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rstest::rstest;
+use gkr::circuit_prover::{GKRProver, GKRVerifier};
+use gkr::error::Error;
+use gkr::gadgets::uint::{UintBuilder, UintCircuit};
+use gkr::small_field::{BabyBear, Goldilocks};
+
+/// x, y の 64 ビットと 32 ビットの演算を並べた回路，回路の入力，期待する出力
+fn program<F: PrimeField>(x: u64, y: u64) -> (UintCircuit<F>, Vec<F>, Vec<u64>) {
+	let mut b = UintBuilder::<F>::new().unwrap();
+	let (x64, y64) = (b.input_u64(x), b.input_u64(y));
+	let (x32, y32) = (b.input_u32(x as u32), b.input_u32(y as u32));
+
+	let (sum, carry) = b.overflowing_add(&x64, &y64);
+	let (diff, borrow) = b.overflowing_sub(&x64, &y64);
+	let product = b.wrapping_mul(&x64, &y64);
+	let (low, high) = b.widening_mul(&x64, &y64);
+	let (lt, le, eq) = (b.lt(&x64, &y64), b.le(&x64, &y64), b.is_equal(&x64, &y64));
+	let (shl, shr, rotl) = (b.shl(&x64, 13), b.shr(&x64, 7), b.rotate_left(&x64, 21));
+	let (sum32, product32, rotr32) = (b.wrapping_add(&x32, &y32), b.wrapping_mul(&x32, &y32), b.rotate_right(&x32, 5));
+	for v in [&sum, &diff, &product, &low, &high, &shl, &shr, &rotl, &sum32, &product32, &rotr32] {
+		b.output(v);
+	}
+	for bit in [carry, borrow, lt, le, eq] {
+		b.output_bit(bit);
+	}
+
+	let (s, c) = x.overflowing_add(y);
+	let (d, br) = x.overflowing_sub(y);
+	let p = x as u128 * y as u128;
+	let (x32, y32) = (x as u32, y as u32);
+	let expected = vec![
+		s,
+		d,
+		x.wrapping_mul(y),
+		p as u64,
+		(p >> 64) as u64,
+		x << 13,
+		x >> 7,
+		x.rotate_left(21),
+		x32.wrapping_add(y32) as u64,
+		x32.wrapping_mul(y32) as u64,
+		x32.rotate_right(5) as u64,
+		c as u64,
+		br as u64,
+		(x < y) as u64,
+		(x <= y) as u64,
+		(x == y) as u64,
+	];
+	assert_eq!(sum.value(), s);
+	assert_eq!(high.value(), (p >> 64) as u64);
+	let (circuit, witness) = b.build().unwrap();
+	(circuit, witness, expected)
+}
+
+#[rstest]
+#[case(0x0123_4567_89ab_cdef, 0xfedc_ba98_7654_3210)]
+#[case(u64::MAX, 1)]
+#[case(42, 42)]
+#[case(7, u64::MAX)]
+fn circuit_matches_native_integer_arithmetic(#[case] x: u64, #[case] y: u64) {
+	let (circuit, witness, expected) = program::<ScalarField>(x, y);
+	let outputs = circuit.built.evaluate(&witness);
+	assert_eq!(circuit.check_outputs(&outputs), Ok(expected));
+}
+
+#[rstest]
+fn limb_equations_do_not_wrap_in_a_64_bit_field() {
+	let mut rng = StdRng::seed_from_u64(0);
+	let (x, y) = (rng.gen(), rng.gen());
+	let (circuit, witness, expected) = program::<Goldilocks>(x, y);
+	let outputs = circuit.built.evaluate(&witness);
+	assert_eq!(circuit.check_outputs(&outputs), Ok(expected));
+	assert!(matches!(UintBuilder::<BabyBear>::new(), Err(Error::InvalidCircuit(_))));
+}
+
+#[rstest]
+fn tampered_witnesses_violate_the_constraints() {
+	let (circuit, witness, _) = program::<ScalarField>(3, 5);
+	// 入力（64 + 64 + 32 + 32 ビット）の後ろの補助の値を書き換える
+	for i in [192, 200, witness.len() - 1] {
+		let mut forged = witness.clone();
+		forged[i] = ScalarField::one() - forged[i];
+		let outputs = circuit.built.evaluate(&forged);
+		assert_eq!(circuit.check_outputs(&outputs), Err(Error::EvaluationMismatch("integer constraint")));
+	}
+}
+
+#[rstest]
+fn gkr_proves_the_integer_circuit() {
+	let (circuit, witness, expected) = program::<ScalarField>(1 << 40, 12345);
+	let inputs = circuit.built.assign(&witness);
+	let proof = GKRProver::prove_circuit(&circuit.built.circuit, &inputs);
+	assert!(GKRVerifier::verify_circuit(&circuit.built.circuit, &inputs, &proof).is_ok());
+	assert_eq!(circuit.check_outputs(&proof.outputs), Ok(expected));
+}
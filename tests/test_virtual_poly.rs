@@ -0,0 +1,79 @@
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::UniformRand;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rstest::rstest;
+use gkr::ml_extension::DenseMLE;
+use gkr::sumcheck::{n_to_vec, MLSumcheck};
+use gkr::transcript::Transcript;
+use gkr::virtual_poly::VirtualPolynomial;
+
+/// add·(V1 + V2) + mult·(V1·V2) の形の多項式
+fn layer_like(num_vars: usize, rng: &mut StdRng) -> VirtualPolynomial<ScalarField> {
+	let (add, mult, v1, v2) = (DenseMLE::rand(num_vars, rng), DenseMLE::rand(num_vars, rng), DenseMLE::rand(num_vars, rng), DenseMLE::rand(num_vars, rng));
+	let mut poly = VirtualPolynomial::new(num_vars);
+	poly.add_product(1u32.into(), [add.clone(), v1.clone()]);
+	poly.add_product(1u32.into(), [add, v2.clone()]);
+	poly.add_product(1u32.into(), [mult, v1, v2]);
+	poly
+}
+
+#[rstest]
+fn shared_factors_are_stored_once() {
+	let mut rng = StdRng::seed_from_u64(1);
+	let poly = layer_like(3, &mut rng);
+	assert_eq!(poly.mles.len(), 4);
+	assert_eq!(poly.products.len(), 3);
+	assert_eq!(poly.degree(), 3);
+}
+
+#[rstest]
+#[case(1)]
+#[case(3)]
+fn sum_and_evaluate_match_the_products(#[case] num_vars: usize) {
+	let mut rng = StdRng::seed_from_u64(2);
+	let poly = layer_like(num_vars, &mut rng);
+	let brute: ScalarField = (0..1 << num_vars).map(|b| poly.evaluate(&n_to_vec(b, num_vars))).sum();
+	assert_eq!(poly.sum(), brute);
+
+	let point: Vec<ScalarField> = (0..num_vars).map(|_| ScalarField::rand(&mut rng)).collect();
+	// 登録順は add, V1, V2, mult
+	let [add, v1, v2, mult] = [0, 1, 2, 3].map(|i| poly.mles[i].evaluate(&point));
+	assert_eq!(poly.evaluate(&point), add * (v1 + v2) + mult * v1 * v2);
+}
+
+#[rstest]
+fn single_product_matches_ml_sumcheck() {
+	let mut rng = StdRng::seed_from_u64(3);
+	let (a, b) = (DenseMLE::rand(3, &mut rng), DenseMLE::rand(3, &mut rng));
+	let mut poly = VirtualPolynomial::new(3);
+	poly.add_product(1u32.into(), [a.clone(), b.clone()]);
+	let mut ml = MLSumcheck::new(vec![a, b]);
+	assert_eq!(poly.sum(), ml.claimed_sum());
+	for _ in 0..3 {
+		assert_eq!(poly.round_evaluations(), ml.round_evaluations());
+		let r = ScalarField::rand(&mut rng);
+		poly.fix_first_variable(r);
+		ml.apply_challenge(r);
+	}
+}
+
+#[rstest]
+#[case(1)]
+#[case(4)]
+fn sumcheck_over_a_layer(#[case] num_vars: usize) {
+	let mut rng = StdRng::seed_from_u64(4);
+	let poly = layer_like(num_vars, &mut rng);
+	let transcript = Transcript::new(b"virtual_poly_test");
+	let (claimed_sum, msgs, point) = poly.clone().prove(&mut transcript.clone());
+	assert_eq!(claimed_sum, poly.sum());
+
+	let subclaim = VirtualPolynomial::verify(num_vars, poly.degree(), claimed_sum, &msgs, &mut transcript.clone()).unwrap();
+	assert_eq!(subclaim.point, point);
+	assert_eq!(subclaim.expected_value, poly.evaluate(&point));
+
+	// g(0) は主張から復元するので，誤った総和はラウンドでは通っても最終点の値が合わない
+	let wrong = claimed_sum + ScalarField::from(1u32);
+	let subclaim = VirtualPolynomial::verify(num_vars, poly.degree(), wrong, &msgs, &mut transcript.clone()).unwrap();
+	assert_ne!(subclaim.expected_value, poly.evaluate(&subclaim.point));
+}
@@ -0,0 +1,185 @@
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::{One, UniformRand, Zero};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rstest::rstest;
+use gkr::circuit::{Circuit, Gate};
+use gkr::wiring::{self, BinaryTreeLayer, InnerProductLayer, TreeOp, WiringPredicate};
+
+/// 疎な MLE を密表に展開し，先頭変数から順に畳み込んで評価する
+fn evaluate_by_folding(pred: &dyn WiringPredicate<ScalarField>, point: &[ScalarField]) -> ScalarField {
+	let mut dense = pred.to_sparse_mle().to_dense_multilinear_extension();
+	for r in point {
+		dense.fix_first_variable_in_place(*r);
+	}
+	dense.evaluations[0]
+}
+
+fn check_against_circuit(circuit: &Circuit, predicates: &[Box<dyn WiringPredicate<ScalarField>>]) {
+	assert_eq!(circuit.depth(), predicates.len());
+	for (layer, pred) in circuit.layers.iter().zip(predicates.iter()) {
+		let gates: Vec<(usize, usize, usize)> = layer
+			.gates
+			.iter()
+			.enumerate()
+			.map(|(z, gate)| {
+				let (x, y) = gate.inputs();
+				(z, x, y)
+			})
+			.collect();
+		assert_eq!(pred.entries(), gates);
+		assert_eq!(layer.gates.len(), 1 << pred.num_vars_out());
+	}
+}
+
+#[rstest]
+#[case(0)]
+#[case(1)]
+#[case(3)]
+fn closed_form_matches_sparse_mle(#[case] k: usize) {
+	let mut rng = StdRng::seed_from_u64(k as u64);
+	let predicates: Vec<Box<dyn WiringPredicate<ScalarField>>> =
+		vec![Box::new(BinaryTreeLayer { num_vars_out: k }), Box::new(InnerProductLayer { num_vars_out: k })];
+	for pred in predicates.iter() {
+		let n_in = pred.num_vars_in();
+		for _ in 0..4 {
+			let z: Vec<ScalarField> = (0..k).map(|_| ScalarField::rand(&mut rng)).collect();
+			let x: Vec<ScalarField> = (0..n_in).map(|_| ScalarField::rand(&mut rng)).collect();
+			let y: Vec<ScalarField> = (0..n_in).map(|_| ScalarField::rand(&mut rng)).collect();
+			let point: Vec<ScalarField> = z.iter().chain(x.iter()).chain(y.iter()).copied().collect();
+			assert_eq!(pred.evaluate(&z, &x, &y), evaluate_by_folding(pred.as_ref(), &point));
+		}
+	}
+}
+
+#[test]
+fn closed_form_is_boolean_indicator() {
+	let pred = BinaryTreeLayer { num_vars_out: 2 };
+	let bits = |v: usize, n: usize| -> Vec<ScalarField> {
+		(0..n).map(|i| if (v >> (n - 1 - i)) & 1 == 1 { ScalarField::one() } else { ScalarField::zero() }).collect()
+	};
+	for z in 0..4 {
+		for x in 0..8 {
+			for y in 0..8 {
+				let expected = x == 2 * z && y == 2 * z + 1;
+				let value = WiringPredicate::<ScalarField>::evaluate(&pred, &bits(z, 2), &bits(x, 3), &bits(y, 3));
+				assert_eq!(value, if expected { ScalarField::one() } else { ScalarField::zero() });
+			}
+		}
+	}
+}
+
+#[rstest]
+#[case(1)]
+#[case(4)]
+fn tree_circuits_match_predicates(#[case] k: usize) {
+	let mut rng = StdRng::seed_from_u64(10 + k as u64);
+	let inputs: Vec<ScalarField> = (0..1 << k).map(|_| ScalarField::rand(&mut rng)).collect();
+
+	let mul = wiring::binary_tree_circuit(k, TreeOp::Mul);
+	check_against_circuit(&mul, &wiring::binary_tree_predicates(k));
+	assert_eq!(mul.evaluate(&inputs)[0], vec![inputs.iter().product::<ScalarField>()]);
+
+	let add = wiring::binary_tree_circuit(k, TreeOp::Add);
+	assert!(add.layers.iter().all(|l| l.gates.iter().all(|g| matches!(g, Gate::Add(..)))));
+	assert_eq!(add.evaluate(&inputs)[0], vec![inputs.iter().sum::<ScalarField>()]);
+}
+
+#[rstest]
+#[case(0)]
+#[case(3)]
+fn inner_product_circuit_matches_predicates(#[case] k: usize) {
+	let mut rng = StdRng::seed_from_u64(20 + k as u64);
+	let a: Vec<ScalarField> = (0..1 << k).map(|_| ScalarField::rand(&mut rng)).collect();
+	let b: Vec<ScalarField> = (0..1 << k).map(|_| ScalarField::rand(&mut rng)).collect();
+
+	let circuit = wiring::inner_product_circuit(k);
+	check_against_circuit(&circuit, &wiring::inner_product_predicates(k));
+	let inputs: Vec<ScalarField> = a.iter().chain(b.iter()).copied().collect();
+	let expected: ScalarField = a.iter().zip(b.iter()).map(|(x, y)| *x * y).sum();
+	assert_eq!(circuit.evaluate(&inputs)[0], vec![expected]);
+}
+
+#[rstest]
+fn layer_wiring_reproduces_every_layer() {
+	use gkr::circuit::Layer;
+	use gkr::examples_circuits;
+	let mut circuits: Vec<Circuit> =
+		examples_circuits::standard_suite::<ScalarField>().into_iter().map(|example| example.circuit).collect();
+	// 幅の揃っていない回路（入力 3 つ，Input ゲートと定数ゲートを含む）
+	circuits.push(
+		Circuit::new(
+			3,
+			vec![
+				Layer { gates: vec![Gate::Mul(0, 1), Gate::Add(1, 2)] },
+				Layer { gates: vec![Gate::Add(0, 1), Gate::Input(2), Gate::Constant(5)] },
+			],
+		)
+		.unwrap(),
+	);
+	// 多入力の和と積を含む回路
+	circuits.push(
+		Circuit::new(
+			4,
+			vec![
+				Layer { gates: vec![Gate::Product(vec![0, 1, 2]), Gate::Sum(vec![1, 2, 3])] },
+				Layer { gates: vec![Gate::Product(vec![0, 1, 2, 3]), Gate::Sum(vec![0, 0, 3]), Gate::Product(vec![2]), Gate::Mul(1, 3)] },
+			],
+		)
+		.unwrap(),
+	);
+	let mut rng = StdRng::seed_from_u64(4);
+	for circuit in circuits.iter() {
+		let inputs: Vec<ScalarField> = (0..circuit.num_inputs).map(|_| ScalarField::rand(&mut rng)).collect();
+		let values = circuit.evaluate(&inputs);
+		let wiring = wiring::circuit_wiring::<ScalarField>(circuit);
+		let l = wiring::circuit_num_vars(circuit);
+		assert!(values.iter().all(|v| v.len() <= 1 << l));
+		for (i, layer) in wiring.iter().enumerate() {
+			assert_eq!(layer.add.num_vars, 3 * l);
+			let below = &values[i + 1];
+			let v = |w: usize| below.get(w).copied().unwrap_or_else(ScalarField::zero);
+			let mut out = vec![ScalarField::zero(); 1 << l];
+			for (index, val) in layer.add.evaluations.iter() {
+				let (z, y, x) = (index >> (2 * l), (index >> l) & ((1 << l) - 1), index & ((1 << l) - 1));
+				out[z] += *val * (v(x) + v(y));
+			}
+			for (index, val) in layer.mul.evaluations.iter() {
+				let (z, y, x) = (index >> (2 * l), (index >> l) & ((1 << l) - 1), index & ((1 << l) - 1));
+				out[z] += *val * v(x) * v(y);
+			}
+			for (index, val) in layer.relay.evaluations.iter() {
+				out[index >> l] += *val * v(index & ((1 << l) - 1));
+			}
+			for (z, val) in layer.constant.evaluations.iter() {
+				out[*z] += *val;
+			}
+			for (k, table) in layer.wide_mul.iter() {
+				for (index, val) in table.evaluations.iter() {
+					let product: ScalarField = (0..*k).map(|j| v((index >> (j * l)) & ((1 << l) - 1))).product();
+					out[index >> (k * l)] += *val * product;
+				}
+			}
+			out.truncate(values[i].len());
+			assert_eq!(out, values[i]);
+		}
+	}
+}
+
+#[rstest]
+fn mul_wiring_feeds_the_layer_prover() {
+	use gkr::ml_extension::DenseMLE;
+	use gkr::prover::LinearGKRProver;
+	use gkr::verifier::LinearGKRVerifier;
+	let circuit = wiring::binary_tree_circuit(3, TreeOp::Mul);
+	let mut rng = StdRng::seed_from_u64(9);
+	let inputs: Vec<ScalarField> = (0..8).map(|_| ScalarField::rand(&mut rng)).collect();
+	let values = circuit.evaluate(&inputs);
+	let l = wiring::circuit_num_vars(&circuit);
+	let layer = wiring::layer_wiring::<ScalarField>(&circuit.layers[2], l);
+	let v = DenseMLE::from_evaluations_vec(l, values[3].clone());
+	// 出力ゲート z = 1 の値を主張として証明する
+	let g = vec![ScalarField::zero(), ScalarField::zero(), ScalarField::one()];
+	let proof = LinearGKRProver::prove_noninteractive(&layer.mul, &v, &v, &g);
+	assert!(LinearGKRVerifier::verify_noninteractive(&layer.mul, &g, values[2][1], &proof).is_ok());
+}
@@ -0,0 +1,98 @@
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::Field;
+use rstest::rstest;
+use gkr::witness::{self, ByteOrder, EncodingRules, WitnessError, WitnessFormat};
+
+fn fr(n: i64) -> ScalarField {
+	if n < 0 {
+		-ScalarField::from((-n) as u64)
+	} else {
+		ScalarField::from(n as u64)
+	}
+}
+
+#[rstest]
+fn json_mixed_values() {
+	let rules = EncodingRules::default();
+	let input = r#"{"inputs": [1, -2, true, false, "7", [3, 4], "0x0102"]}"#;
+	let w = witness::parse_json::<ScalarField>(input, &rules).unwrap();
+	assert_eq!(w.values, vec![fr(1), fr(-2), fr(1), fr(0), fr(7), fr(3), fr(4), fr(0x0102)]);
+	// 8 要素なので 3 変数，0 埋めは不要
+	let mle = w.to_mle();
+	assert_eq!(mle.num_vars, 3);
+	assert_eq!(mle.evaluations, w.values);
+}
+
+#[rstest]
+fn csv_with_header_and_padding() {
+	let rules = EncodingRules::default();
+	let input = "a,b,c\n1,2,3 # comment\n\n4,5\n";
+	let w = witness::parse_csv::<ScalarField>(input, true, &rules).unwrap();
+	assert_eq!(w.values, vec![fr(1), fr(2), fr(3), fr(4), fr(5)]);
+	let mle = w.to_mle();
+	assert_eq!(mle.num_vars, 3);
+	assert_eq!(mle.evaluations[5..], [fr(0), fr(0), fr(0)]);
+}
+
+#[rstest]
+#[case(ByteOrder::LittleEndian, 0x0201)]
+#[case(ByteOrder::BigEndian, 0x0102)]
+fn byte_strings_are_chunked(#[case] byte_order: ByteOrder, #[case] first: i64) {
+	let rules = EncodingRules { byte_order, bytes_per_element: Some(2) };
+	let w = witness::parse_csv::<ScalarField>("0x01020304", false, &rules).unwrap();
+	assert_eq!(w.values.len(), 2);
+	assert_eq!(w.values[0], fr(first));
+}
+
+#[rstest]
+fn binary_round_trip_and_rejects_non_canonical() {
+	let rules = EncodingRules { byte_order: ByteOrder::LittleEndian, bytes_per_element: None };
+	let len = witness::field_byte_len::<ScalarField>();
+	let mut bytes = vec![0u8; 2 * len];
+	bytes[0] = 5;
+	bytes[len] = 9;
+	let w = witness::parse_binary::<ScalarField>(&bytes, &rules).unwrap();
+	assert_eq!(w.values, vec![fr(5), fr(9)]);
+
+	assert!(witness::parse_binary::<ScalarField>(&bytes[1..], &rules).is_err());
+	let all_ones = vec![0xffu8; len];
+	assert!(witness::parse_binary::<ScalarField>(&all_ones, &rules).is_err());
+}
+
+#[rstest]
+fn invalid_values_are_rejected() {
+	let rules = EncodingRules::default();
+	assert!(witness::parse_csv::<ScalarField>("1,abc", false, &rules).is_err());
+	assert!(witness::parse_json::<ScalarField>("[null]", &rules).is_err());
+	assert!(witness::parse_csv::<ScalarField>("0x123", false, &rules).is_err());
+	assert_eq!(WitnessFormat::from_path("w.csv"), Some(WitnessFormat::Csv { has_header: false }));
+	assert_eq!(WitnessFormat::from_path("w.txt"), None);
+}
+
+#[rstest]
+fn json_integers_beyond_u64_are_reduced() {
+	let rules = EncodingRules::default();
+	// 2^70 と p + 5（p は BLS12-381 のスカラー体の位数）
+	let input = "[1180591620717411303424, 52435875175126190479447740508185965837690552500527637822603658699938581184518]";
+	let w = witness::parse_json::<ScalarField>(input, &rules).unwrap();
+	assert_eq!(w.values, vec![ScalarField::from(2u64).pow([70]), fr(5)]);
+	assert!(witness::parse_json::<ScalarField>("[1.5]", &rules).is_err());
+}
+
+#[rstest]
+fn errors_report_the_input_position() {
+	// バイト列が 2 元になっても，4 番目の入力値（添字 3）として報告する
+	let rules = EncodingRules { byte_order: ByteOrder::BigEndian, bytes_per_element: Some(2) };
+	let err = witness::parse_json::<ScalarField>(r#"[["0x01020304", 5], [true, null]]"#, &rules).unwrap_err();
+	assert!(matches!(err, WitnessError::InvalidValue { index: 3, .. }));
+	let err = witness::parse_csv::<ScalarField>("0x01020304,abc", false, &rules).unwrap_err();
+	assert!(matches!(err, WitnessError::InvalidValue { index: 1, .. }));
+}
+
+#[rstest]
+fn empty_byte_strings_are_rejected() {
+	let rules = EncodingRules::default();
+	let err = witness::parse_json::<ScalarField>(r#"[1, "0x"]"#, &rules).unwrap_err();
+	assert!(matches!(err, WitnessError::InvalidValue { index: 1, .. }));
+	assert!(witness::parse_csv::<ScalarField>("0x", false, &rules).is_err());
+}
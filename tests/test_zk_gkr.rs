@@ -0,0 +1,127 @@
+#![cfg(feature = "zk")]
+
+use ark_bls12_381::Fr as ScalarField;
+use ark_ff::{One, UniformRand};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rstest::rstest;
+use gkr::basefold::BaseFold;
+use gkr::circuit::{Circuit, Gate, Layer};
+use gkr::circuit_prover::{GKRProver, GKRVerifier};
+use gkr::error::Error;
+use gkr::examples_circuits;
+use gkr::zk_gkr::zk_commitment_vars;
+
+type Pcs = BaseFold<ScalarField>;
+
+/// マスクの開示が多いので，テストでは問い合わせを減らした BaseFold を使う
+fn params(circuit: &Circuit) -> gkr::basefold::BaseFoldParams<ScalarField> {
+	Pcs::setup_with(zk_commitment_vars(circuit), 1, 4, b"zk_gkr_test")
+}
+
+fn layer(gates: Vec<Gate>) -> Layer {
+	Layer { gates }
+}
+
+/// 幅が 2 のべきでなく，Input ゲートを含む回路
+fn uneven_circuit() -> Circuit {
+	Circuit::new(
+		3,
+		vec![
+			layer(vec![Gate::Mul(0, 1)]),
+			layer(vec![Gate::Add(0, 1), Gate::Input(2)]),
+			layer(vec![Gate::Mul(0, 1), Gate::Add(1, 2), Gate::Input(2)]),
+		],
+	)
+	.unwrap()
+}
+
+/// 各層のゲートが 1 つ（l = 0）の回路
+fn narrow_circuit() -> Circuit {
+	Circuit::new(1, vec![layer(vec![Gate::Mul(0, 0)]), layer(vec![Gate::Add(0, 0)]), layer(vec![Gate::Input(0)])]).unwrap()
+}
+
+fn random_inputs(circuit: &Circuit, rng: &mut StdRng) -> Vec<ScalarField> {
+	(0..circuit.num_inputs).map(|_| ScalarField::rand(rng)).collect()
+}
+
+#[rstest]
+fn honest_zk_proofs_are_accepted() {
+	let mut rng = StdRng::seed_from_u64(0);
+	let mut circuits: Vec<Circuit> = examples_circuits::standard_suite::<ScalarField>()
+		.into_iter()
+		.map(|example| example.circuit)
+		.filter(|c| (0..c.depth()).all(|i| c.fan_in(i) == 2))
+		.take(2)
+		.collect();
+	circuits.extend([uneven_circuit(), narrow_circuit()]);
+	for circuit in circuits.iter() {
+		let params = params(circuit);
+		let inputs = random_inputs(circuit, &mut rng);
+		let proof = GKRProver::prove_zk::<Pcs, _>(&params, circuit, &inputs, &mut rng);
+		assert_eq!(proof.outputs, circuit.evaluate(&inputs)[0]);
+		assert_eq!(proof.layer_proofs.len(), circuit.depth());
+		assert!(GKRVerifier::verify_zk::<Pcs>(&params, circuit, &inputs, &proof).is_ok());
+	}
+}
+
+#[rstest]
+fn fresh_masks_change_every_revealed_value() {
+	let mut rng = StdRng::seed_from_u64(1);
+	let circuit = uneven_circuit();
+	let params = params(&circuit);
+	let inputs = random_inputs(&circuit, &mut rng);
+	let first = GKRProver::prove_zk::<Pcs, _>(&params, &circuit, &inputs, &mut rng);
+	let second = GKRProver::prove_zk::<Pcs, _>(&params, &circuit, &inputs, &mut rng);
+	assert_eq!(first.outputs, second.outputs);
+	for (a, b) in first.layer_proofs.iter().zip(second.layer_proofs.iter()) {
+		assert_ne!(a.sumcheck.msgs[0], b.sumcheck.msgs[0]);
+		assert_ne!(a.masked_evals, b.masked_evals);
+	}
+	// 中間層の値は S_i で隠れ，入力層の分だけが W そのもの
+	assert_eq!(first.layer_proofs[0].mask_evals.len(), 0);
+	assert_eq!(first.layer_proofs[1].mask_evals.len(), 2);
+}
+
+#[rstest]
+fn tampered_zk_proofs_are_rejected() {
+	let mut rng = StdRng::seed_from_u64(2);
+	let circuit = uneven_circuit();
+	let params = params(&circuit);
+	let inputs = random_inputs(&circuit, &mut rng);
+	let proof = GKRProver::prove_zk::<Pcs, _>(&params, &circuit, &inputs, &mut rng);
+	let verify = |inputs: &[ScalarField], proof| GKRVerifier::verify_zk::<Pcs>(&params, &circuit, inputs, proof);
+	assert!(verify(&inputs, &proof).is_ok());
+
+	let mut wrong_inputs = inputs.clone();
+	wrong_inputs[2] += ScalarField::one();
+	assert!(verify(&wrong_inputs, &proof).is_err());
+	let mut tampered = proof.clone();
+	tampered.outputs[0] += ScalarField::one();
+	assert!(verify(&inputs, &tampered).is_err());
+	let mut tampered = proof.clone();
+	tampered.layer_proofs[0].masked_evals[1] += ScalarField::one();
+	assert_eq!(verify(&inputs, &tampered).err(), Some(Error::EvaluationMismatch("masked layer evaluation")));
+	let mut tampered = proof.clone();
+	tampered.layer_proofs[1].mask_evals[0] += ScalarField::one();
+	assert!(verify(&inputs, &tampered).is_err());
+	let mut tampered = proof.clone();
+	tampered.layer_proofs[2].masked_evals[0] += ScalarField::one();
+	assert!(verify(&inputs, &tampered).is_err());
+	let mut tampered = proof.clone();
+	tampered.layer_proofs.pop();
+	assert!(matches!(verify(&inputs, &tampered), Err(Error::LengthMismatch { what: "layer proofs", .. })));
+}
+
+#[rstest]
+fn wide_layers_are_not_supported() {
+	let circuit = Circuit::new(3, vec![layer(vec![Gate::Product(vec![0, 1, 2])])]).unwrap();
+	let mut rng = StdRng::seed_from_u64(3);
+	let params = params(&circuit);
+	let inputs = random_inputs(&circuit, &mut rng);
+	let proof = GKRProver::prove_zk::<Pcs, _>(&params, &uneven_circuit(), &inputs, &mut rng);
+	assert!(matches!(
+		GKRVerifier::verify_zk::<Pcs>(&params, &circuit, &inputs, &proof),
+		Err(Error::MalformedProof(_))
+	));
+}